@@ -0,0 +1,93 @@
+//! Gridding benchmarks for the station interpolation methods
+//!
+//! The server is a binary crate, so the interpolation module is compiled in
+//! directly together with the error type it depends on.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+#[allow(dead_code)]
+#[path = "../src/error.rs"]
+mod error;
+
+#[allow(dead_code)]
+#[path = "../src/data_fusion/spatial_interpolation.rs"]
+mod spatial_interpolation;
+
+use spatial_interpolation::{
+    DriftOrder, ElevationSource, GridDefinition, SpatialInterpolationEngine, SpatialInterpolationMethod, StationObservation,
+};
+
+struct Terrain;
+
+impl ElevationSource for Terrain {
+    fn elevation_m(&self, latitude: f64, longitude: f64) -> Option<f64> {
+        Some(300.0 + 250.0 * (latitude * 7.0).sin() * (longitude * 5.0).cos())
+    }
+}
+
+/// A deterministic pseudo-random network over a 2° x 2° region
+fn network(count: usize) -> Vec<StationObservation> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+    (0..count)
+        .map(|k| {
+            let latitude = 51.0 + 2.0 * next();
+            let longitude = 4.0 + 2.0 * next();
+            let elevation = Terrain.elevation_m(latitude, longitude).unwrap_or(0.0);
+            StationObservation {
+                station_id: format!("B{:04}", k),
+                latitude,
+                longitude,
+                elevation_m: Some(elevation),
+                value: 14.0 - 0.0065 * elevation + (latitude * 3.0).sin() + 0.5 * (longitude * 4.0).cos() + 0.2 * next(),
+            }
+        })
+        .collect()
+}
+
+fn methods() -> Vec<(&'static str, SpatialInterpolationMethod)> {
+    vec![
+        ("idw", SpatialInterpolationMethod::InverseDistance { power: 2.0, max_neighbours: Some(12) }),
+        ("ordinary_kriging", SpatialInterpolationMethod::OrdinaryKriging { variogram: None }),
+        ("universal_kriging", SpatialInterpolationMethod::UniversalKriging { drift: DriftOrder::Linear, variogram: None }),
+        ("elevation_kriging", SpatialInterpolationMethod::ElevationDetrendedKriging { variogram: None }),
+        ("thin_plate_spline", SpatialInterpolationMethod::ThinPlateSpline { smoothing: 0.1 }),
+    ]
+}
+
+fn bench_gridding(c: &mut Criterion) {
+    let grid = GridDefinition::lat_lon(53.0, 51.0, 6.0, 4.0, 0.02).expect("valid grid");
+    let mut group = c.benchmark_group("grid_100x100");
+    group.sample_size(10);
+    for stations in [50, 200] {
+        let observations = network(stations);
+        for (name, method) in methods() {
+            let engine = SpatialInterpolationEngine::new(method);
+            group.bench_with_input(BenchmarkId::new(name, stations), &observations, |b, observations| {
+                b.iter(|| engine.interpolate(black_box(observations), &grid, Some(&Terrain)).expect("interpolation succeeds"))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_cross_validation(c: &mut Criterion) {
+    let observations = network(100);
+    let mut group = c.benchmark_group("leave_one_out_100");
+    group.sample_size(10);
+    for (name, method) in methods() {
+        let engine = SpatialInterpolationEngine::new(method);
+        group.bench_function(name, |b| {
+            b.iter(|| engine.cross_validate(black_box(&observations), Some(&Terrain)).expect("cross-validation succeeds"))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_gridding, bench_cross_validation);
+criterion_main!(benches);
@@ -42,6 +42,25 @@ pub struct Config {
     pub smtp_port: Option<u16>,
     pub smtp_username: Option<String>,
     pub smtp_password: Option<String>,
+
+    // Continuous satellite reconstruction
+    pub reconstruction_satellites: Vec<String>,
+    pub reconstruction_interval_seconds: u64,
+    pub reconstruction_horizon_hours: f64,
+    // Acquired strips, JSON Lines, replayed at startup and appended to
+    pub strip_archive_path: Option<String>,
+
+    // Versioned sensor calibrations, JSON Lines
+    pub calibration_registry_path: Option<String>,
+
+    // Sensor data fusion
+    pub fusion_byzantine_fault_threshold: f64,
+
+    // CelesTrak-style TLE file for optical overpass forecasts
+    pub tle_catalog_path: Option<String>,
+
+    // Folder of DEM tiles (.asc, .hgt, .tif) for terrain-aware evidence processing
+    pub dem_directory: Option<String>,
 }
 
 impl Config {
@@ -118,6 +137,36 @@ impl Config {
                 .and_then(|p| p.parse().ok()),
             smtp_username: env::var("SMTP_USERNAME").ok(),
             smtp_password: env::var("SMTP_PASSWORD").ok(),
+
+            // Continuous satellite reconstruction
+            reconstruction_satellites: env::var("RECONSTRUCTION_SATELLITES")
+                .unwrap_or_default()
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.trim().to_string())
+                .collect(),
+            // One cycle per 15-minute forecast frame
+            reconstruction_interval_seconds: env::var("RECONSTRUCTION_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "900".to_string())
+                .parse()
+                .context("Invalid RECONSTRUCTION_INTERVAL_SECONDS")?,
+            reconstruction_horizon_hours: env::var("RECONSTRUCTION_HORIZON_HOURS")
+                .unwrap_or_else(|_| "6".to_string())
+                .parse()
+                .context("Invalid RECONSTRUCTION_HORIZON_HOURS")?,
+            strip_archive_path: env::var("STRIP_ARCHIVE_PATH").ok(),
+
+            calibration_registry_path: env::var("CALIBRATION_REGISTRY_PATH").ok(),
+
+            // Sensor data fusion
+            fusion_byzantine_fault_threshold: env::var("FUSION_BYZANTINE_FAULT_THRESHOLD")
+                .unwrap_or_else(|_| "0.3".to_string())
+                .parse()
+                .context("Invalid FUSION_BYZANTINE_FAULT_THRESHOLD")?,
+
+            tle_catalog_path: env::var("TLE_CATALOG_PATH").ok(),
+
+            dem_directory: env::var("DEM_DIRECTORY").ok(),
         };
 
         // Validate configuration
@@ -162,6 +211,18 @@ impl Config {
             anyhow::bail!("WORKER_THREADS must be greater than 0");
         }
 
+        if !(0.0..0.5).contains(&self.fusion_byzantine_fault_threshold) {
+            anyhow::bail!("FUSION_BYZANTINE_FAULT_THRESHOLD must be in [0, 0.5)");
+        }
+
+        if self.reconstruction_interval_seconds == 0 {
+            anyhow::bail!("RECONSTRUCTION_INTERVAL_SECONDS must be greater than 0");
+        }
+
+        if !(self.reconstruction_horizon_hours > 0.0 && self.reconstruction_horizon_hours <= 72.0) {
+            anyhow::bail!("RECONSTRUCTION_HORIZON_HOURS must be in (0, 72]");
+        }
+
         // Validate SMTP configuration (if provided)
         if let (Some(_), Some(_), Some(_), Some(_)) = (
             &self.smtp_host,
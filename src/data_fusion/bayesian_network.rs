@@ -8,7 +8,8 @@ use crate::error::AppError;
 use super::{
     SensorType, TimestampedMeasurement, FusionResult, 
     fuzzy_evidence::{FuzzyEvidence, FuzzyReliability},
-    fusion_algorithms::{AlgorithmType, EvidenceAnalysis, ComputationalConstraints}
+    fusion_algorithms::AlgorithmType,
+    meta_learning::{EvidenceAnalysis, ComputationalConstraints, MetaLearningAlgorithmSelector},
 };
 
 /// Fuzzy Bayesian Evidence Network for agricultural weather intelligence
@@ -17,7 +18,7 @@ pub struct FuzzyBayesianNetwork {
     nodes: HashMap<String, NetworkNode>,
     edges: Vec<NetworkEdge>,
     inference_engine: InferenceEngine,
-    algorithm_selector: Arc<RwLock<super::fusion_algorithms::MetaLearningAlgorithmSelector>>,
+    algorithm_selector: Arc<RwLock<MetaLearningAlgorithmSelector>>,
     agricultural_semantics: AgriculturalSemantics,
 }
 
@@ -329,7 +330,7 @@ impl FuzzyBayesianNetwork {
         };
 
         let algorithm_selector = Arc::new(RwLock::new(
-            super::fusion_algorithms::MetaLearningAlgorithmSelector::new_with_agricultural_domain_knowledge().await?
+            MetaLearningAlgorithmSelector::new_with_agricultural_domain_knowledge().await?
         ));
 
         Ok(Self {
@@ -378,6 +379,24 @@ impl FuzzyBayesianNetwork {
             agricultural_insights,
         })
     }
+
+    /// Replace the linguistic rules of every node that a configured rule concludes on;
+    /// returns the number of nodes updated
+    pub fn install_rule_base(&mut self, rule_base: &super::fuzzy_rule_base::FuzzyRuleBase) -> usize {
+        let mut rules_by_node: HashMap<String, Vec<FuzzyRule>> = HashMap::new();
+        for rule in rule_base.fuzzy_rules() {
+            rules_by_node.entry(rule.consequent.variable_name.clone()).or_default().push(rule);
+        }
+
+        let mut updated = 0;
+        for (node_id, rules) in rules_by_node {
+            if let Some(node) = self.nodes.get_mut(&node_id) {
+                node.fuzzy_cpt.linguistic_rules = rules;
+                updated += 1;
+            }
+        }
+        updated
+    }
     
     async fn propagate_fuzzy_beliefs_with_agricultural_semantics(&mut self, fusion_result: FusionResult) -> Result<(), AppError> {
         // Get topological order for belief propagation
@@ -613,8 +632,8 @@ impl FuzzyBayesianNetwork {
         Ok(evidence) // Placeholder
     }
 
-    async fn analyze_evidence_ensemble_comprehensively(&self, _evidence: &[FuzzyEvidence]) -> Result<EvidenceAnalysis, AppError> {
-        Ok(EvidenceAnalysis::default()) // Placeholder
+    async fn analyze_evidence_ensemble_comprehensively(&self, evidence: &[FuzzyEvidence]) -> Result<EvidenceAnalysis, AppError> {
+        Ok(EvidenceAnalysis::from_evidence(evidence))
     }
 
     fn get_current_network_state(&self) -> NetworkState {
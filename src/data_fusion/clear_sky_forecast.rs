@@ -0,0 +1,487 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Serialize;
+
+use crate::error::AppError;
+use crate::data_ingestion::{BoundingBox, DataSource, DataSourceCategory};
+use crate::data_fusion::orbit_propagation::{
+    bounding_box_center, greenwich_mean_sidereal_time, imager_half_field_of_view_deg, julian_date, Geodetic,
+    PassSearch, SatellitePass, Sgp4Propagator, TwoLineElements
+};
+use crate::data_fusion::phantom_satellites::{ConfidenceDecayModel, DecayFunction};
+use crate::data_fusion::{InformationTheoreticSelector, SensorType};
+
+/// Optical catalogue products and the TLE names of the satellites that acquire them
+const OPTICAL_FAMILIES: &[(&str, &[&str])] = &[
+    ("Sentinel-2", &["SENTINEL-2"]),
+    ("Landsat", &["LANDSAT 8", "LANDSAT 9"]),
+    ("MODIS Terra", &["TERRA"]),
+    ("MODIS Aqua", &["AQUA"]),
+];
+
+/// An optical product from the data source catalogue and the satellites behind it
+#[derive(Debug, Clone)]
+pub struct OpticalPlatform {
+    pub source_name: String,
+    pub half_field_of_view_deg: f64,
+    propagators: Vec<Sgp4Propagator>,
+}
+
+impl OpticalPlatform {
+    pub fn new(source_name: impl Into<String>, half_field_of_view_deg: f64, tles: &[TwoLineElements]) -> Result<Self, AppError> {
+        let source_name = source_name.into();
+        if tles.is_empty() {
+            return Err(AppError::validation(format!("Optical platform {} has no TLEs", source_name)));
+        }
+        Ok(Self {
+            source_name,
+            half_field_of_view_deg,
+            propagators: tles.iter().cloned().map(Sgp4Propagator::new).collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Platforms for the Sentinel-2, Landsat and MODIS imaging sources in the catalogue
+    /// whose satellites appear in `tles`; other sources are skipped
+    pub fn from_catalog(sources: &[DataSource], tles: &[TwoLineElements]) -> Result<Vec<Self>, AppError> {
+        let mut platforms = Vec::new();
+        for source in sources.iter().filter(|s| s.category == DataSourceCategory::SatelliteImaging) {
+            let Some((_, satellite_names)) = OPTICAL_FAMILIES.iter().find(|(family, _)| source.name.contains(family)) else {
+                continue;
+            };
+            let Some(half_field_of_view_deg) = imager_half_field_of_view_deg(&source.name) else {
+                continue;
+            };
+            let matching: Vec<TwoLineElements> = tles
+                .iter()
+                .filter(|tle| {
+                    let name = tle.name.as_deref().unwrap_or("").to_ascii_uppercase();
+                    satellite_names.iter().any(|prefix| name.starts_with(prefix))
+                })
+                .cloned()
+                .collect();
+            if !matching.is_empty() {
+                platforms.push(Self::new(source.name.clone(), half_field_of_view_deg, &matching)?);
+            }
+        }
+        Ok(platforms)
+    }
+}
+
+/// Cloud fraction forecast over one field
+#[derive(Debug, Clone)]
+pub struct CloudCoverForecast {
+    pub issued_at: DateTime<Utc>,
+    /// Valid time and forecast cloud fraction (0-1), in time order
+    pub samples: Vec<(DateTime<Utc>, f64)>,
+}
+
+impl CloudCoverForecast {
+    /// Linearly interpolated cloud fraction; `None` outside the forecast range
+    pub fn cloud_fraction_at(&self, time: DateTime<Utc>) -> Option<f64> {
+        let after = self.samples.iter().position(|(valid, _)| *valid >= time)?;
+        let (t1, c1) = self.samples[after];
+        if after == 0 {
+            return (t1 == time).then_some(c1);
+        }
+        let (t0, c0) = self.samples[after - 1];
+        let span = (t1 - t0).num_seconds() as f64;
+        let fraction = if span > 0.0 { (time - t0).num_seconds() as f64 / span } else { 1.0 };
+        Some(c0 + fraction * (c1 - c0))
+    }
+}
+
+/// A field the forecaster tracks
+#[derive(Debug, Clone)]
+pub struct FieldRegistration {
+    pub field_id: String,
+    pub bounds: BoundingBox,
+    /// Long-run probability that the field is cloud-free at overpass time
+    pub clear_sky_climatology: f64,
+    pub last_clear_observation: Option<DateTime<Utc>>,
+    pub confidence_model: ConfidenceDecayModel,
+}
+
+impl FieldRegistration {
+    /// Registration with a 50% clear-sky climatology and a 0.1/day exponential confidence decay
+    pub fn new(field_id: impl Into<String>, bounds: BoundingBox) -> Self {
+        Self {
+            field_id: field_id.into(),
+            bounds,
+            clear_sky_climatology: 0.5,
+            last_clear_observation: None,
+            confidence_model: ConfidenceDecayModel {
+                initial_confidence: 0.95,
+                decay_rate: 0.1,
+                minimum_confidence: 0.1,
+                decay_function: DecayFunction::Exponential { lambda: 0.1 / 86400.0 },
+                environmental_factors: HashMap::new(),
+                agricultural_factors: HashMap::new(),
+            },
+        }
+    }
+}
+
+/// One imaging pass and the chance its scene is usable
+#[derive(Debug, Clone, Serialize)]
+pub struct ClearObservationCandidate {
+    pub source_name: String,
+    pub pass: SatellitePass,
+    pub solar_elevation_deg: f64,
+    pub clear_probability: f64,
+}
+
+/// Passes close enough in time to see the same clouds
+#[derive(Debug, Clone, Serialize)]
+pub struct ObservationOpportunity {
+    pub time: DateTime<Utc>,
+    pub candidates: Vec<ClearObservationCandidate>,
+    pub clear_probability: f64,
+    /// Probability that this is the first usable image
+    pub probability_first_clear: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NextClearObservation {
+    pub field_id: String,
+    pub issued_at: DateTime<Utc>,
+    /// Expected date of the next usable image, given that one arrives within the horizon
+    pub expected_date: Option<DateTime<Utc>>,
+    /// Earliest date by which a usable image is at least 50% likely
+    pub median_date: Option<DateTime<Utc>>,
+    pub probability_within_horizon: f64,
+    pub opportunities: Vec<ObservationOpportunity>,
+    pub last_clear_observation: Option<DateTime<Utc>>,
+    /// `ConfidenceDecayModel` confidence of the last usable image now
+    pub last_observation_confidence: Option<f64>,
+}
+
+/// Forecasts when each registered field will next get a cloud-free optical image
+///
+/// Overpasses come from SGP4 pass prediction for each catalogue platform, kept
+/// when the field is inside the imager swath and in daylight. Each pass is
+/// usable with probability one minus the forecast cloud fraction, relaxed
+/// towards the field's clear-sky climatology as forecast lead time grows and
+/// replaced by it beyond the forecast. Passes within `shared_cloud_window` of
+/// each other are treated as seeing the same clouds; otherwise passes are
+/// assumed independent.
+#[derive(Debug, Clone)]
+pub struct ClearObservationForecaster {
+    pub platforms: Vec<OpticalPlatform>,
+    pub horizon: ChronoDuration,
+    /// E-folding lead time of cloud forecast skill, days
+    pub forecast_skill_days: f64,
+    pub shared_cloud_window: ChronoDuration,
+    /// Optical scenes need the sun at least this high
+    pub min_solar_elevation_deg: f64,
+    fields: HashMap<String, FieldRegistration>,
+    cloud_forecasts: HashMap<String, CloudCoverForecast>,
+}
+
+impl ClearObservationForecaster {
+    pub fn new(platforms: Vec<OpticalPlatform>) -> Self {
+        Self {
+            platforms,
+            horizon: ChronoDuration::days(16),
+            forecast_skill_days: 3.0,
+            shared_cloud_window: ChronoDuration::hours(1),
+            min_solar_elevation_deg: 10.0,
+            fields: HashMap::new(),
+            cloud_forecasts: HashMap::new(),
+        }
+    }
+
+    pub fn register_field(&mut self, field: FieldRegistration) -> Result<(), AppError> {
+        if field.bounds.north < field.bounds.south {
+            return Err(AppError::validation(format!("Field {} bounds are inverted", field.field_id)));
+        }
+        if !(0.0..=1.0).contains(&field.clear_sky_climatology) {
+            return Err(AppError::validation(format!(
+                "Field {} clear-sky climatology {} is not a probability",
+                field.field_id, field.clear_sky_climatology
+            )));
+        }
+        self.cloud_forecasts.remove(&field.field_id);
+        self.fields.insert(field.field_id.clone(), field);
+        Ok(())
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = &FieldRegistration> {
+        self.fields.values()
+    }
+
+    pub fn update_cloud_forecast(&mut self, field_id: &str, mut forecast: CloudCoverForecast) -> Result<(), AppError> {
+        if !self.fields.contains_key(field_id) {
+            return Err(AppError::not_found(format!("Field {} is not registered", field_id)));
+        }
+        if forecast.samples.iter().any(|(_, cloud)| !(0.0..=1.0).contains(cloud)) {
+            return Err(AppError::validation("Cloud fractions must lie in [0, 1]"));
+        }
+        forecast.samples.sort_by_key(|(valid, _)| *valid);
+        self.cloud_forecasts.insert(field_id.to_string(), forecast);
+        Ok(())
+    }
+
+    pub fn record_clear_observation(&mut self, field_id: &str, time: DateTime<Utc>) -> Result<(), AppError> {
+        let field = self
+            .fields
+            .get_mut(field_id)
+            .ok_or_else(|| AppError::not_found(format!("Field {} is not registered", field_id)))?;
+        if field.last_clear_observation.is_none_or(|last| time > last) {
+            field.last_clear_observation = Some(time);
+        }
+        Ok(())
+    }
+
+    pub fn forecast_all(&self, now: DateTime<Utc>) -> Result<Vec<NextClearObservation>, AppError> {
+        let mut ids: Vec<&String> = self.fields.keys().collect();
+        ids.sort();
+        ids.into_iter().map(|id| self.forecast_field(id, now)).collect()
+    }
+
+    pub fn forecast_field(&self, field_id: &str, now: DateTime<Utc>) -> Result<NextClearObservation, AppError> {
+        let field = self
+            .fields
+            .get(field_id)
+            .ok_or_else(|| AppError::not_found(format!("Field {} is not registered", field_id)))?;
+        let forecast = self.cloud_forecasts.get(field_id);
+        let centre = bounding_box_center(&field.bounds);
+
+        let mut candidates = Vec::new();
+        for platform in &self.platforms {
+            for propagator in &platform.propagators {
+                let passes = propagator.predict_passes_over_region(&field.bounds, now, now + self.horizon, &PassSearch::default())?;
+                for pass in passes.into_iter().filter(|p| p.within_swath(platform.half_field_of_view_deg)) {
+                    let solar_elevation_deg = solar_elevation_deg(&centre, pass.culmination);
+                    if solar_elevation_deg < self.min_solar_elevation_deg {
+                        continue;
+                    }
+                    candidates.push(ClearObservationCandidate {
+                        source_name: platform.source_name.clone(),
+                        clear_probability: self.clear_probability(field, forecast, pass.culmination),
+                        solar_elevation_deg,
+                        pass,
+                    });
+                }
+            }
+        }
+        candidates.sort_by_key(|c| c.pass.culmination);
+
+        let mut opportunities: Vec<ObservationOpportunity> = Vec::new();
+        for candidate in candidates {
+            match opportunities.last_mut() {
+                Some(last) if candidate.pass.culmination - last.time <= self.shared_cloud_window => {
+                    last.clear_probability = last.clear_probability.max(candidate.clear_probability);
+                    last.candidates.push(candidate);
+                }
+                _ => opportunities.push(ObservationOpportunity {
+                    time: candidate.pass.culmination,
+                    clear_probability: candidate.clear_probability,
+                    candidates: vec![candidate],
+                    probability_first_clear: 0.0,
+                }),
+            }
+        }
+
+        let mut still_waiting = 1.0;
+        let mut weighted_seconds = 0.0;
+        let mut median_date = None;
+        for opportunity in &mut opportunities {
+            opportunity.probability_first_clear = still_waiting * opportunity.clear_probability;
+            weighted_seconds += opportunity.probability_first_clear * (opportunity.time - now).num_seconds() as f64;
+            still_waiting *= 1.0 - opportunity.clear_probability;
+            if median_date.is_none() && still_waiting <= 0.5 {
+                median_date = Some(opportunity.time);
+            }
+        }
+        let probability_within_horizon = 1.0 - still_waiting;
+        let expected_date = (probability_within_horizon > 0.0)
+            .then(|| now + ChronoDuration::seconds((weighted_seconds / probability_within_horizon).round() as i64));
+
+        let last_observation_confidence = field.last_clear_observation.map(|last| {
+            field.confidence_model.compute_confidence((now - last).num_seconds().max(0) as f64)
+        });
+
+        Ok(NextClearObservation {
+            field_id: field.field_id.clone(),
+            issued_at: now,
+            expected_date,
+            median_date,
+            probability_within_horizon,
+            opportunities,
+            last_clear_observation: field.last_clear_observation,
+            last_observation_confidence,
+        })
+    }
+
+    /// Discount tasked optical imagery of a field by the chance a usable scene arrives within the horizon
+    pub fn apply_to_tasking(
+        &self,
+        field_id: &str,
+        now: DateTime<Utc>,
+        selector: &mut InformationTheoreticSelector,
+    ) -> Result<NextClearObservation, AppError> {
+        let forecast = self.forecast_field(field_id, now)?;
+        selector.set_delivery_probability(SensorType::SatelliteImagery, forecast.probability_within_horizon);
+        Ok(forecast)
+    }
+
+    fn clear_probability(&self, field: &FieldRegistration, forecast: Option<&CloudCoverForecast>, time: DateTime<Utc>) -> f64 {
+        let climatology = field.clear_sky_climatology;
+        let Some((forecast, cloud)) = forecast.and_then(|f| f.cloud_fraction_at(time).map(|c| (f, c))) else {
+            return climatology;
+        };
+        let lead_days = (time - forecast.issued_at).num_seconds().max(0) as f64 / 86400.0;
+        let skill = (-lead_days / self.forecast_skill_days).exp();
+        skill * (1.0 - cloud) + (1.0 - skill) * climatology
+    }
+}
+
+/// Solar elevation from the low-precision almanac formulae (about 0.01° accuracy)
+pub fn solar_elevation_deg(site: &Geodetic, time: DateTime<Utc>) -> f64 {
+    solar_position_deg(site, time).0
+}
+
+/// Solar elevation and azimuth (clockwise from north) from the same almanac formulae
+pub fn solar_position_deg(site: &Geodetic, time: DateTime<Utc>) -> (f64, f64) {
+    let jd = julian_date(time);
+    let n = jd - 2_451_545.0;
+    let mean_longitude = (280.460 + 0.9856474 * n).to_radians();
+    let mean_anomaly = (357.528 + 0.9856003 * n).to_radians();
+    let ecliptic_longitude = mean_longitude
+        + (1.915_f64.to_radians()) * mean_anomaly.sin()
+        + (0.020_f64.to_radians()) * (2.0 * mean_anomaly).sin();
+    let obliquity = (23.439 - 0.0000004 * n).to_radians();
+    let right_ascension = (obliquity.cos() * ecliptic_longitude.sin()).atan2(ecliptic_longitude.cos());
+    let declination = (obliquity.sin() * ecliptic_longitude.sin()).asin();
+    let hour_angle = greenwich_mean_sidereal_time(jd) + site.longitude_deg.to_radians() - right_ascension;
+    let latitude = site.latitude_deg.to_radians();
+    let elevation = (latitude.sin() * declination.sin() + latitude.cos() * declination.cos() * hour_angle.cos())
+        .clamp(-1.0, 1.0)
+        .asin();
+    let azimuth = (-hour_angle.sin()).atan2(declination.tan() * latitude.cos() - latitude.sin() * hour_angle.cos());
+    (elevation.to_degrees(), azimuth.to_degrees().rem_euclid(360.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_fusion::BeliefState;
+    use crate::data_ingestion::{
+        CoverageScope, DataFormat, GeographicalCoverage, IngestionStatus, TemporalCoverage, UpdateFrequency,
+    };
+
+    const SUN_SYNCHRONOUS: &str = "MODIS TERRA\n\
+                                   1 28057U 03049A   06177.78615833  .00000060  00000-0  35940-4 0  1836\n\
+                                   2 28057  98.4283 247.6961 0000884  88.1964 271.9322 14.35478080140550";
+
+    fn forecaster() -> (ClearObservationForecaster, DateTime<Utc>) {
+        let tle = TwoLineElements::parse(SUN_SYNCHRONOUS).unwrap();
+        let now = tle.epoch;
+        let platform = OpticalPlatform::new("MODIS Terra Daily Global 1km", 55.0, &[tle]).unwrap();
+        let mut forecaster = ClearObservationForecaster::new(vec![platform]);
+        forecaster.horizon = ChronoDuration::days(8);
+        for id in ["clear", "overcast"] {
+            let bounds = BoundingBox { north: 41.02, south: 41.0, east: -93.98, west: -94.0 };
+            forecaster.register_field(FieldRegistration::new(id, bounds)).unwrap();
+        }
+        (forecaster, now)
+    }
+
+    fn catalog_source(name: &str, category: DataSourceCategory) -> DataSource {
+        DataSource {
+            id: uuid::Uuid::new_v4(),
+            name: name.to_string(),
+            category,
+            provider: "Test Provider".to_string(),
+            description: String::new(),
+            api_endpoint: None,
+            auth_required: false,
+            auth_method: None,
+            data_format: DataFormat::GeoTIFF,
+            update_frequency: UpdateFrequency::Daily,
+            geographical_coverage: GeographicalCoverage { scope: CoverageScope::Global, bounds: None, resolution: None },
+            temporal_coverage: TemporalCoverage { start_date: None, end_date: None, temporal_resolution: None },
+            parameters: vec![],
+            quality_indicators: vec![],
+            associated_publications: vec![],
+            last_ingestion: None,
+            status: IngestionStatus::Active,
+            priority: 5,
+        }
+    }
+
+    #[test]
+    fn test_catalog_optical_sources_pair_with_named_tles() {
+        let lines: Vec<&str> = SUN_SYNCHRONOUS.lines().collect();
+        let tle = |name: &str| TwoLineElements::from_lines(Some(name.to_string()), lines[1], lines[2]).unwrap();
+        let tles = [tle("TERRA"), tle("SENTINEL-2A"), tle("SENTINEL-2B"), tle("NOAA 20")];
+        let sources = [
+            catalog_source("MODIS Terra Daily Global 1km", DataSourceCategory::SatelliteImaging),
+            catalog_source("Sentinel-2 MSI Level-2A Surface Reflectance", DataSourceCategory::SatelliteImaging),
+            // No Landsat TLE, no optical family for SkySat, and SAR is not optical
+            catalog_source("Landsat 8-9 OLI Surface Reflectance", DataSourceCategory::SatelliteImaging),
+            catalog_source("Planet SkySat Daily Imagery", DataSourceCategory::SatelliteImaging),
+            catalog_source("Sentinel-1 SAR Ground Range Detected", DataSourceCategory::SatelliteRadar),
+        ];
+
+        let platforms = OpticalPlatform::from_catalog(&sources, &tles).unwrap();
+        let summary: Vec<(&str, f64, usize)> = platforms
+            .iter()
+            .map(|p| (p.source_name.as_str(), p.half_field_of_view_deg, p.propagators.len()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("MODIS Terra Daily Global 1km", 55.0, 1), ("Sentinel-2 MSI Level-2A Surface Reflectance", 10.3, 2)]
+        );
+    }
+
+    #[test]
+    fn test_cloud_forecast_discounts_tasked_imagery() {
+        let (mut forecaster, now) = forecaster();
+        forecaster.horizon = ChronoDuration::days(2);
+        let samples = |cloud: f64| (0..=4).map(|h| (now + ChronoDuration::hours(h * 12), cloud)).collect();
+        forecaster.update_cloud_forecast("clear", CloudCoverForecast { issued_at: now, samples: samples(0.0) }).unwrap();
+        forecaster.update_cloud_forecast("overcast", CloudCoverForecast { issued_at: now, samples: samples(1.0) }).unwrap();
+
+        let prior = BeliefState::agricultural_prior();
+        let plan_for = |field_id: &str| {
+            let mut selector = InformationTheoreticSelector::new(1000.0).with_available_sensors(vec![SensorType::SatelliteImagery]);
+            let forecast = forecaster.apply_to_tasking(field_id, now, &mut selector).unwrap();
+            selector.plan(&prior).unwrap();
+            (forecast.probability_within_horizon, selector.information_gains()[&SensorType::SatelliteImagery])
+        };
+        let (clear_probability, clear_gain) = plan_for("clear");
+        let (overcast_probability, overcast_gain) = plan_for("overcast");
+
+        assert!(overcast_probability < clear_probability && clear_probability > 0.0);
+        assert!((overcast_gain / clear_gain - overcast_probability / clear_probability).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_overcast_forecast_delays_the_next_clear_image() {
+        let (mut forecaster, now) = forecaster();
+        let samples = |cloud: f64| (0..=12).map(|h| (now + ChronoDuration::hours(h * 12), cloud)).collect();
+        forecaster.update_cloud_forecast("clear", CloudCoverForecast { issued_at: now, samples: samples(0.0) }).unwrap();
+        forecaster.update_cloud_forecast("overcast", CloudCoverForecast { issued_at: now, samples: samples(1.0) }).unwrap();
+        forecaster.record_clear_observation("overcast", now - ChronoDuration::days(5)).unwrap();
+
+        let clear = forecaster.forecast_field("clear", now).unwrap();
+        let overcast = forecaster.forecast_field("overcast", now).unwrap();
+
+        assert!(!clear.opportunities.is_empty());
+        for opportunity in &clear.opportunities {
+            for candidate in &opportunity.candidates {
+                assert!(candidate.solar_elevation_deg >= forecaster.min_solar_elevation_deg);
+                assert!(candidate.pass.within_swath(55.0));
+            }
+        }
+        let total: f64 = overcast.opportunities.iter().map(|o| o.probability_first_clear).sum();
+        assert!((total - overcast.probability_within_horizon).abs() < 1e-9);
+        assert!(clear.opportunities[0].clear_probability > overcast.opportunities[0].clear_probability);
+        assert!(overcast.expected_date.unwrap() > clear.expected_date.unwrap());
+
+        assert!(clear.last_observation_confidence.is_none());
+        let confidence = overcast.last_observation_confidence.unwrap();
+        assert!(confidence < 0.95 && confidence >= 0.1);
+    }
+}
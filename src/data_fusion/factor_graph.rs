@@ -0,0 +1,826 @@
+use std::collections::HashMap;
+use nalgebra::{DMatrix, DVector};
+
+use super::fusion_algorithms::{Factor, Variable};
+
+/// Component layout of the environmental state variables used by the
+/// sliding-window smoother. Every factor in this module indexes into it.
+pub mod state_index {
+    /// Near-surface air temperature at the reference elevation (°C)
+    pub const TEMPERATURE: usize = 0;
+    /// Station-level pressure reduced to the reference elevation (hPa)
+    pub const PRESSURE: usize = 1;
+    /// Volumetric root-zone soil moisture (m³/m³)
+    pub const SOIL_MOISTURE: usize = 2;
+    /// Eastward wind component (m/s)
+    pub const WIND_U: usize = 3;
+    /// Northward wind component (m/s)
+    pub const WIND_V: usize = 4;
+    /// Canopy NDVI
+    pub const NDVI: usize = 5;
+}
+
+/// Dimension of an environmental state variable
+pub const ENVIRONMENTAL_STATE_DIM: usize = 6;
+
+/// Robust kernel applied to the whitened residual norm of a factor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RobustLoss {
+    /// Plain least squares
+    Quadratic,
+    /// Quadratic near zero, linear beyond `delta` standard deviations
+    Huber { delta: f64 },
+    /// Logarithmic growth beyond `scale`, strongly suppresses gross outliers
+    Cauchy { scale: f64 },
+}
+
+impl RobustLoss {
+    /// Loss ρ(s²) for a squared whitened residual
+    pub fn cost(&self, squared_norm: f64) -> f64 {
+        match *self {
+            RobustLoss::Quadratic => 0.5 * squared_norm,
+            RobustLoss::Huber { delta } => {
+                let s = squared_norm.sqrt();
+                if s <= delta { 0.5 * squared_norm } else { delta * (s - 0.5 * delta) }
+            },
+            RobustLoss::Cauchy { scale } => 0.5 * scale * scale * (1.0 + squared_norm / (scale * scale)).ln(),
+        }
+    }
+
+    /// IRLS weight ρ'(s²)/s applied to the factor's information matrix
+    pub fn weight(&self, squared_norm: f64) -> f64 {
+        match *self {
+            RobustLoss::Quadratic => 1.0,
+            RobustLoss::Huber { delta } => {
+                let s = squared_norm.sqrt();
+                if s <= delta { 1.0 } else { delta / s }
+            },
+            RobustLoss::Cauchy { scale } => 1.0 / (1.0 + squared_norm / (scale * scale)),
+        }
+    }
+}
+
+/// Linearised contribution of one factor, ready to be accumulated
+pub struct LinearizedFactor {
+    pub residual: DVector<f64>,
+    pub jacobian: DMatrix<f64>,
+    /// Information matrix after the robust weight has been applied
+    pub weighted_information: DMatrix<f64>,
+    pub robust_cost: f64,
+    pub robust_weight: f64,
+}
+
+/// Evaluate a factor at the current variable values
+pub fn linearize_factor(factor: &dyn Factor, variables: &HashMap<String, Variable>) -> LinearizedFactor {
+    let residual = factor.error(variables);
+    let jacobian = factor.jacobian(variables);
+    let information = factor.information_matrix();
+    let squared_norm = (residual.transpose() * &information * &residual)[(0, 0)].max(0.0);
+    let loss = factor.robust_loss();
+    let robust_weight = loss.weight(squared_norm);
+
+    LinearizedFactor {
+        weighted_information: information * robust_weight,
+        robust_cost: loss.cost(squared_norm),
+        robust_weight,
+        residual,
+        jacobian,
+    }
+}
+
+/// Gauss-Newton normal equations stored as dense blocks per variable pair
+///
+/// Only blocks between variables that share a factor are allocated, so the
+/// memory and the matrix-vector product scale with the number of factors
+/// rather than with the square of the window length. The system is solved
+/// with block-Jacobi preconditioned conjugate gradients.
+pub struct BlockSparseSystem {
+    offsets: Vec<usize>,
+    dims: Vec<usize>,
+    rows: Vec<HashMap<usize, DMatrix<f64>>>,
+    gradient: DVector<f64>,
+}
+
+impl BlockSparseSystem {
+    pub fn new(dims: Vec<usize>) -> Self {
+        let mut offsets = Vec::with_capacity(dims.len());
+        let mut total = 0;
+        for dim in &dims {
+            offsets.push(total);
+            total += dim;
+        }
+
+        Self {
+            offsets,
+            rows: vec![HashMap::new(); dims.len()],
+            dims,
+            gradient: DVector::zeros(total),
+        }
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.gradient.len()
+    }
+
+    pub fn gradient(&self) -> &DVector<f64> {
+        &self.gradient
+    }
+
+    pub fn offset(&self, variable: usize) -> usize {
+        self.offsets[variable]
+    }
+
+    /// Accumulate JᵀΩJ and JᵀΩr for a factor touching `indices` (in Jacobian column order)
+    pub fn add_factor(&mut self, indices: &[usize], linearized: &LinearizedFactor) {
+        let weighted_jacobian_t = linearized.jacobian.transpose() * &linearized.weighted_information;
+        let mut column_starts = Vec::with_capacity(indices.len());
+        let mut column = 0;
+        for &index in indices {
+            column_starts.push(column);
+            column += self.dims[index];
+        }
+
+        for (a, &row_var) in indices.iter().enumerate() {
+            let rows_a = weighted_jacobian_t.rows(column_starts[a], self.dims[row_var]);
+            let g = &rows_a * &linearized.residual;
+            let mut target = self.gradient.rows_mut(self.offsets[row_var], self.dims[row_var]);
+            target += g;
+
+            for (b, &col_var) in indices.iter().enumerate() {
+                let block = &rows_a * linearized.jacobian.columns(column_starts[b], self.dims[col_var]);
+                self.rows[row_var]
+                    .entry(col_var)
+                    .and_modify(|existing| *existing += &block)
+                    .or_insert(block);
+            }
+        }
+    }
+
+    /// Dense copy of H, for small local systems such as marginalisation
+    pub fn to_dense(&self) -> DMatrix<f64> {
+        let n = self.dimension();
+        let mut dense = DMatrix::zeros(n, n);
+        for (row_var, row) in self.rows.iter().enumerate() {
+            for (&col_var, block) in row {
+                dense
+                    .view_mut((self.offsets[row_var], self.offsets[col_var]), (self.dims[row_var], self.dims[col_var]))
+                    .copy_from(block);
+            }
+        }
+        dense
+    }
+
+    /// y = (H + λ·diag(H)) x
+    fn multiply(&self, x: &DVector<f64>, damping: f64) -> DVector<f64> {
+        let mut y = DVector::zeros(x.len());
+        for (row_var, row) in self.rows.iter().enumerate() {
+            let offset = self.offsets[row_var];
+            let dim = self.dims[row_var];
+            let mut accumulator = DVector::zeros(dim);
+            for (&col_var, block) in row {
+                accumulator += block * x.rows(self.offsets[col_var], self.dims[col_var]);
+                if col_var == row_var {
+                    for i in 0..dim {
+                        accumulator[i] += damping * block[(i, i)] * x[offset + i];
+                    }
+                }
+            }
+            y.rows_mut(offset, dim).copy_from(&accumulator);
+        }
+        y
+    }
+
+    fn preconditioner(&self, damping: f64) -> Vec<DMatrix<f64>> {
+        (0..self.dims.len())
+            .map(|var| {
+                let dim = self.dims[var];
+                let mut block = self.rows[var].get(&var).cloned().unwrap_or_else(|| DMatrix::zeros(dim, dim));
+                for i in 0..dim {
+                    block[(i, i)] = block[(i, i)] * (1.0 + damping) + 1e-12;
+                }
+                block.clone().try_inverse().unwrap_or_else(|| DMatrix::identity(dim, dim))
+            })
+            .collect()
+    }
+
+    fn apply_preconditioner(&self, inverses: &[DMatrix<f64>], r: &DVector<f64>) -> DVector<f64> {
+        let mut z = DVector::zeros(r.len());
+        for (var, inverse) in inverses.iter().enumerate() {
+            let offset = self.offsets[var];
+            let dim = self.dims[var];
+            z.rows_mut(offset, dim).copy_from(&(inverse * r.rows(offset, dim)));
+        }
+        z
+    }
+
+    /// Solve (H + λ·diag(H)) x = rhs with preconditioned conjugate gradients
+    pub fn solve(&self, rhs: &DVector<f64>, damping: f64, tolerance: f64, max_iterations: usize) -> Option<DVector<f64>> {
+        let inverses = self.preconditioner(damping);
+        let mut x = DVector::zeros(rhs.len());
+        let mut r = rhs.clone();
+        let rhs_norm = rhs.norm();
+        if rhs_norm == 0.0 {
+            return Some(x);
+        }
+
+        let mut z = self.apply_preconditioner(&inverses, &r);
+        let mut p = z.clone();
+        let mut rz = r.dot(&z);
+
+        for _ in 0..max_iterations {
+            let hp = self.multiply(&p, damping);
+            let curvature = p.dot(&hp);
+            if curvature <= 0.0 || !curvature.is_finite() {
+                return None;
+            }
+            let alpha = rz / curvature;
+            x.axpy(alpha, &p, 1.0);
+            r.axpy(-alpha, &hp, 1.0);
+            if r.norm() <= tolerance * rhs_norm {
+                return Some(x);
+            }
+
+            z = self.apply_preconditioner(&inverses, &r);
+            let rz_next = r.dot(&z);
+            p = &z + (rz_next / rz) * &p;
+            rz = rz_next;
+        }
+
+        x.iter().all(|v| v.is_finite()).then_some(x)
+    }
+}
+
+/// Linear prior left behind when variables are marginalised out of the window
+///
+/// Holds the Schur complement H' = H_rr - H_rm H_mm⁻¹ H_mr and the matching
+/// gradient b', expressed as a Gaussian on the remaining variables around the
+/// linearisation point x₀: r = (x - x₀) + H'⁺b', Ω = H'.
+pub struct MarginalizationPriorFactor {
+    variables: Vec<String>,
+    dims: Vec<usize>,
+    linearization_point: DVector<f64>,
+    offset: DVector<f64>,
+    information: DMatrix<f64>,
+}
+
+impl MarginalizationPriorFactor {
+    pub fn new(
+        variables: Vec<String>,
+        dims: Vec<usize>,
+        linearization_point: DVector<f64>,
+        information: DMatrix<f64>,
+        gradient: DVector<f64>,
+    ) -> Self {
+        let offset = information
+            .clone()
+            .pseudo_inverse(1e-9)
+            .map(|inverse| inverse * gradient)
+            .unwrap_or_else(|_| DVector::zeros(linearization_point.len()));
+
+        Self { variables, dims, linearization_point, offset, information }
+    }
+
+    fn stacked_values(&self, variables: &HashMap<String, Variable>) -> DVector<f64> {
+        let mut stacked = DVector::zeros(self.linearization_point.len());
+        let mut start = 0;
+        for (id, dim) in self.variables.iter().zip(&self.dims) {
+            match variables.get(id) {
+                Some(variable) => stacked.rows_mut(start, *dim).copy_from(&variable.value),
+                None => stacked.rows_mut(start, *dim).copy_from(&self.linearization_point.rows(start, *dim)),
+            }
+            start += dim;
+        }
+        stacked
+    }
+}
+
+impl Factor for MarginalizationPriorFactor {
+    fn error(&self, variables: &HashMap<String, Variable>) -> DVector<f64> {
+        self.stacked_values(variables) - &self.linearization_point + &self.offset
+    }
+
+    fn jacobian(&self, _variables: &HashMap<String, Variable>) -> DMatrix<f64> {
+        DMatrix::identity(self.linearization_point.len(), self.linearization_point.len())
+    }
+
+    fn information_matrix(&self) -> DMatrix<f64> {
+        self.information.clone()
+    }
+
+    fn get_connected_variables(&self) -> Vec<String> {
+        self.variables.clone()
+    }
+}
+
+fn component_jacobian(component: usize, derivative: f64) -> DMatrix<f64> {
+    let mut jacobian = DMatrix::zeros(1, ENVIRONMENTAL_STATE_DIM);
+    jacobian[(0, component)] = derivative;
+    jacobian
+}
+
+fn component_value(variables: &HashMap<String, Variable>, id: &str, component: usize) -> Option<f64> {
+    variables.get(id).and_then(|variable| variable.value.get(component).copied())
+}
+
+fn scalar_information(sigma: f64) -> DMatrix<f64> {
+    DMatrix::from_element(1, 1, 1.0 / (sigma * sigma).max(1e-12))
+}
+
+/// Random-walk process model between consecutive environmental states
+///
+/// Ties state k to state k+1 with a per-component diffusion rate so the
+/// smoother interpolates between sparse, multi-rate observations.
+pub struct EnvironmentalProcessFactor {
+    from: String,
+    to: String,
+    information: DMatrix<f64>,
+}
+
+impl EnvironmentalProcessFactor {
+    /// `diffusion` holds per-component variance growth per second
+    pub fn new(from: String, to: String, dt_seconds: f64, diffusion: &[f64; ENVIRONMENTAL_STATE_DIM]) -> Self {
+        let information = DMatrix::from_diagonal(&DVector::from_iterator(
+            ENVIRONMENTAL_STATE_DIM,
+            diffusion.iter().map(|q| 1.0 / (q * dt_seconds.abs()).max(1e-12)),
+        ));
+        Self { from, to, information }
+    }
+}
+
+impl Factor for EnvironmentalProcessFactor {
+    fn error(&self, variables: &HashMap<String, Variable>) -> DVector<f64> {
+        match (variables.get(&self.from), variables.get(&self.to)) {
+            (Some(from), Some(to)) => &to.value - &from.value,
+            _ => DVector::zeros(ENVIRONMENTAL_STATE_DIM),
+        }
+    }
+
+    fn jacobian(&self, _variables: &HashMap<String, Variable>) -> DMatrix<f64> {
+        let mut jacobian = DMatrix::zeros(ENVIRONMENTAL_STATE_DIM, 2 * ENVIRONMENTAL_STATE_DIM);
+        for i in 0..ENVIRONMENTAL_STATE_DIM {
+            jacobian[(i, i)] = -1.0;
+            jacobian[(i, ENVIRONMENTAL_STATE_DIM + i)] = 1.0;
+        }
+        jacobian
+    }
+
+    fn information_matrix(&self) -> DMatrix<f64> {
+        self.information.clone()
+    }
+
+    fn get_connected_variables(&self) -> Vec<String> {
+        vec![self.from.clone(), self.to.clone()]
+    }
+}
+
+/// Weather station air temperature, corrected from station to reference elevation
+pub struct StationTemperatureFactor {
+    variable: String,
+    temperature_c: f64,
+    sigma: f64,
+    /// Station elevation minus reference elevation (m)
+    elevation_offset_m: f64,
+    lapse_rate_c_per_m: f64,
+    loss: RobustLoss,
+}
+
+impl StationTemperatureFactor {
+    pub fn new(variable: String, temperature_c: f64, sigma: f64, elevation_offset_m: f64) -> Self {
+        Self {
+            variable,
+            temperature_c,
+            sigma,
+            elevation_offset_m,
+            lapse_rate_c_per_m: 0.0065,
+            loss: RobustLoss::Huber { delta: 2.0 },
+        }
+    }
+
+    pub fn with_loss(mut self, loss: RobustLoss) -> Self {
+        self.loss = loss;
+        self
+    }
+}
+
+impl Factor for StationTemperatureFactor {
+    fn error(&self, variables: &HashMap<String, Variable>) -> DVector<f64> {
+        let predicted = component_value(variables, &self.variable, state_index::TEMPERATURE)
+            .map(|t| t - self.lapse_rate_c_per_m * self.elevation_offset_m)
+            .unwrap_or(self.temperature_c);
+        DVector::from_element(1, self.temperature_c - predicted)
+    }
+
+    fn jacobian(&self, _variables: &HashMap<String, Variable>) -> DMatrix<f64> {
+        component_jacobian(state_index::TEMPERATURE, -1.0)
+    }
+
+    fn information_matrix(&self) -> DMatrix<f64> {
+        scalar_information(self.sigma)
+    }
+
+    fn get_connected_variables(&self) -> Vec<String> {
+        vec![self.variable.clone()]
+    }
+
+    fn robust_loss(&self) -> RobustLoss {
+        self.loss
+    }
+}
+
+/// Station barometer reading, related to the reference-level pressure via the
+/// hypsometric equation using the state's temperature
+pub struct StationPressureFactor {
+    variable: String,
+    pressure_hpa: f64,
+    sigma: f64,
+    elevation_offset_m: f64,
+    loss: RobustLoss,
+}
+
+/// g / R_d (K/m)
+const HYPSOMETRIC_CONSTANT: f64 = 9.80665 / 287.05;
+
+impl StationPressureFactor {
+    pub fn new(variable: String, pressure_hpa: f64, sigma: f64, elevation_offset_m: f64) -> Self {
+        Self { variable, pressure_hpa, sigma, elevation_offset_m, loss: RobustLoss::Huber { delta: 2.0 } }
+    }
+
+    pub fn with_loss(mut self, loss: RobustLoss) -> Self {
+        self.loss = loss;
+        self
+    }
+
+    fn reduction(&self, temperature_c: f64) -> (f64, f64) {
+        let temperature_k = (temperature_c + 273.15).max(150.0);
+        let factor = (-HYPSOMETRIC_CONSTANT * self.elevation_offset_m / temperature_k).exp();
+        let d_factor_d_t = factor * HYPSOMETRIC_CONSTANT * self.elevation_offset_m / (temperature_k * temperature_k);
+        (factor, d_factor_d_t)
+    }
+}
+
+impl Factor for StationPressureFactor {
+    fn error(&self, variables: &HashMap<String, Variable>) -> DVector<f64> {
+        let residual = match (
+            component_value(variables, &self.variable, state_index::PRESSURE),
+            component_value(variables, &self.variable, state_index::TEMPERATURE),
+        ) {
+            (Some(pressure), Some(temperature)) => self.pressure_hpa - pressure * self.reduction(temperature).0,
+            _ => 0.0,
+        };
+        DVector::from_element(1, residual)
+    }
+
+    fn jacobian(&self, variables: &HashMap<String, Variable>) -> DMatrix<f64> {
+        let pressure = component_value(variables, &self.variable, state_index::PRESSURE).unwrap_or(self.pressure_hpa);
+        let temperature = component_value(variables, &self.variable, state_index::TEMPERATURE).unwrap_or(15.0);
+        let (factor, d_factor_d_t) = self.reduction(temperature);
+
+        let mut jacobian = DMatrix::zeros(1, ENVIRONMENTAL_STATE_DIM);
+        jacobian[(0, state_index::PRESSURE)] = -factor;
+        jacobian[(0, state_index::TEMPERATURE)] = -pressure * d_factor_d_t;
+        jacobian
+    }
+
+    fn information_matrix(&self) -> DMatrix<f64> {
+        scalar_information(self.sigma)
+    }
+
+    fn get_connected_variables(&self) -> Vec<String> {
+        vec![self.variable.clone()]
+    }
+
+    fn robust_loss(&self) -> RobustLoss {
+        self.loss
+    }
+}
+
+/// Soil moisture probe with a linear calibration θ_probe = gain·θ + offset
+pub struct SoilMoistureFactor {
+    variable: String,
+    volumetric_water_content: f64,
+    sigma: f64,
+    calibration_gain: f64,
+    calibration_offset: f64,
+    loss: RobustLoss,
+}
+
+impl SoilMoistureFactor {
+    pub fn new(variable: String, volumetric_water_content: f64, sigma: f64) -> Self {
+        Self {
+            variable,
+            volumetric_water_content,
+            sigma,
+            calibration_gain: 1.0,
+            calibration_offset: 0.0,
+            // Cheap capacitance probes fail badly when they lose soil contact
+            loss: RobustLoss::Cauchy { scale: 1.5 },
+        }
+    }
+
+    pub fn with_calibration(mut self, gain: f64, offset: f64) -> Self {
+        self.calibration_gain = gain;
+        self.calibration_offset = offset;
+        self
+    }
+
+    pub fn with_loss(mut self, loss: RobustLoss) -> Self {
+        self.loss = loss;
+        self
+    }
+}
+
+impl Factor for SoilMoistureFactor {
+    fn error(&self, variables: &HashMap<String, Variable>) -> DVector<f64> {
+        let residual = component_value(variables, &self.variable, state_index::SOIL_MOISTURE)
+            .map(|theta| self.volumetric_water_content - (self.calibration_gain * theta + self.calibration_offset))
+            .unwrap_or(0.0);
+        DVector::from_element(1, residual)
+    }
+
+    fn jacobian(&self, _variables: &HashMap<String, Variable>) -> DMatrix<f64> {
+        component_jacobian(state_index::SOIL_MOISTURE, -self.calibration_gain)
+    }
+
+    fn information_matrix(&self) -> DMatrix<f64> {
+        scalar_information(self.sigma)
+    }
+
+    fn get_connected_variables(&self) -> Vec<String> {
+        vec![self.variable.clone()]
+    }
+
+    fn robust_loss(&self) -> RobustLoss {
+        self.loss
+    }
+}
+
+/// Anemometer speed and meteorological direction, observed as wind components
+///
+/// Speed/direction noise is propagated into a rotated (u, v) covariance so the
+/// factor stays linear and well behaved in calm conditions.
+pub struct WindFactor {
+    variable: String,
+    observed: DVector<f64>,
+    information: DMatrix<f64>,
+    loss: RobustLoss,
+}
+
+impl WindFactor {
+    pub fn new(variable: String, speed_ms: f64, direction_deg: f64, speed_sigma: f64, direction_sigma_deg: f64) -> Self {
+        // Meteorological convention: direction the wind blows from, clockwise from north
+        let theta = direction_deg.to_radians();
+        let (sin, cos) = theta.sin_cos();
+        let observed = DVector::from_vec(vec![-speed_ms * sin, -speed_ms * cos]);
+
+        // Along-wind variance from speed, cross-wind from direction
+        let along = speed_sigma.powi(2).max(1e-6);
+        let across = (speed_ms.max(0.5) * direction_sigma_deg.to_radians()).powi(2).max(1e-6);
+        let rotation = DMatrix::from_row_slice(2, 2, &[-sin, -cos, -cos, sin]);
+        let covariance = &rotation * DMatrix::from_diagonal(&DVector::from_vec(vec![along, across])) * rotation.transpose();
+        let information = covariance.try_inverse().unwrap_or_else(|| DMatrix::identity(2, 2));
+
+        Self { variable, observed, information, loss: RobustLoss::Huber { delta: 2.5 } }
+    }
+
+    pub fn with_loss(mut self, loss: RobustLoss) -> Self {
+        self.loss = loss;
+        self
+    }
+}
+
+impl Factor for WindFactor {
+    fn error(&self, variables: &HashMap<String, Variable>) -> DVector<f64> {
+        match (
+            component_value(variables, &self.variable, state_index::WIND_U),
+            component_value(variables, &self.variable, state_index::WIND_V),
+        ) {
+            (Some(u), Some(v)) => DVector::from_vec(vec![self.observed[0] - u, self.observed[1] - v]),
+            _ => DVector::zeros(2),
+        }
+    }
+
+    fn jacobian(&self, _variables: &HashMap<String, Variable>) -> DMatrix<f64> {
+        let mut jacobian = DMatrix::zeros(2, ENVIRONMENTAL_STATE_DIM);
+        jacobian[(0, state_index::WIND_U)] = -1.0;
+        jacobian[(1, state_index::WIND_V)] = -1.0;
+        jacobian
+    }
+
+    fn information_matrix(&self) -> DMatrix<f64> {
+        self.information.clone()
+    }
+
+    fn get_connected_variables(&self) -> Vec<String> {
+        vec![self.variable.clone()]
+    }
+
+    fn robust_loss(&self) -> RobustLoss {
+        self.loss
+    }
+}
+
+/// Satellite-derived products that constrain the environmental state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SatelliteProduct {
+    /// Land surface temperature (°C); related to air temperature with a bias
+    LandSurfaceTemperature { surface_air_bias_c: f64 },
+    /// Surface soil moisture retrieval (m³/m³), scaled to the root zone
+    SurfaceSoilMoisture { root_zone_ratio: f64 },
+    /// Normalised difference vegetation index
+    Ndvi,
+}
+
+/// Pixel value from a satellite product, down-weighted by footprint coverage
+pub struct SatelliteObservationFactor {
+    variable: String,
+    product: SatelliteProduct,
+    value: f64,
+    sigma: f64,
+    loss: RobustLoss,
+}
+
+impl SatelliteObservationFactor {
+    /// `footprint_fraction` is the share of the pixel that covers the field
+    pub fn new(variable: String, product: SatelliteProduct, value: f64, sigma: f64, footprint_fraction: f64) -> Self {
+        Self {
+            variable,
+            product,
+            value,
+            sigma: sigma / footprint_fraction.clamp(0.05, 1.0).sqrt(),
+            // Residual cloud contamination produces gross outliers
+            loss: RobustLoss::Cauchy { scale: 2.0 },
+        }
+    }
+
+    pub fn with_loss(mut self, loss: RobustLoss) -> Self {
+        self.loss = loss;
+        self
+    }
+
+    fn model(&self) -> (usize, f64, f64) {
+        match self.product {
+            SatelliteProduct::LandSurfaceTemperature { surface_air_bias_c } => (state_index::TEMPERATURE, 1.0, surface_air_bias_c),
+            SatelliteProduct::SurfaceSoilMoisture { root_zone_ratio } => (state_index::SOIL_MOISTURE, root_zone_ratio, 0.0),
+            SatelliteProduct::Ndvi => (state_index::NDVI, 1.0, 0.0),
+        }
+    }
+}
+
+impl Factor for SatelliteObservationFactor {
+    fn error(&self, variables: &HashMap<String, Variable>) -> DVector<f64> {
+        let (component, gain, bias) = self.model();
+        let residual = component_value(variables, &self.variable, component)
+            .map(|x| self.value - (gain * x + bias))
+            .unwrap_or(0.0);
+        DVector::from_element(1, residual)
+    }
+
+    fn jacobian(&self, _variables: &HashMap<String, Variable>) -> DMatrix<f64> {
+        let (component, gain, _) = self.model();
+        component_jacobian(component, -gain)
+    }
+
+    fn information_matrix(&self) -> DMatrix<f64> {
+        scalar_information(self.sigma)
+    }
+
+    fn get_connected_variables(&self) -> Vec<String> {
+        vec![self.variable.clone()]
+    }
+
+    fn robust_loss(&self) -> RobustLoss {
+        self.loss
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_fusion::fusion_algorithms::{MultiRateSensorFusion, SlidingWindowEstimate};
+
+    /// Direct observation of the whole state, used to anchor test windows
+    struct StatePriorFactor {
+        variable: String,
+        observed: DVector<f64>,
+        information: DMatrix<f64>,
+    }
+
+    impl StatePriorFactor {
+        fn new(variable: &str, observed: DVector<f64>, sigma: f64) -> Self {
+            let dim = observed.len();
+            Self {
+                variable: variable.to_string(),
+                observed,
+                information: DMatrix::identity(dim, dim) / (sigma * sigma),
+            }
+        }
+    }
+
+    impl Factor for StatePriorFactor {
+        fn error(&self, variables: &HashMap<String, Variable>) -> DVector<f64> {
+            &self.observed - &variables[&self.variable].value
+        }
+
+        fn jacobian(&self, _variables: &HashMap<String, Variable>) -> DMatrix<f64> {
+            -DMatrix::identity(self.observed.len(), self.observed.len())
+        }
+
+        fn information_matrix(&self) -> DMatrix<f64> {
+            self.information.clone()
+        }
+
+        fn get_connected_variables(&self) -> Vec<String> {
+            vec![self.variable.clone()]
+        }
+    }
+
+    fn linearized(rows: usize, cols: usize, seed: usize) -> LinearizedFactor {
+        LinearizedFactor {
+            residual: DVector::from_fn(rows, |i, _| ((3 * i + seed) as f64).cos()),
+            jacobian: DMatrix::from_fn(rows, cols, |i, j| ((7 * i + 3 * j + seed) as f64).sin()),
+            weighted_information: DMatrix::from_diagonal(&DVector::from_fn(rows, |i, _| 1.0 + 0.5 * i as f64)),
+            robust_cost: 0.0,
+            robust_weight: 1.0,
+        }
+    }
+
+    fn state(temperature: f64, pressure: f64, soil_moisture: f64) -> DVector<f64> {
+        DVector::from_vec(vec![temperature, pressure, soil_moisture, 1.5, -0.5, 0.45])
+    }
+
+    /// Hourly chain of states, each observed directly, with random-walk process factors
+    fn hourly_chain(window: usize) -> MultiRateSensorFusion {
+        let diffusion = [1e-3, 1e-2, 1e-7, 1e-3, 1e-3, 1e-8];
+        let mut fusion = MultiRateSensorFusion::new().with_window(window);
+        for k in 0..6 {
+            let id = format!("x{}", k);
+            fusion.add_variable(id.clone(), 3600.0 * k as f64, DVector::zeros(ENVIRONMENTAL_STATE_DIM));
+            let observed = state(18.0 + k as f64, 1010.0 - 0.5 * k as f64, 0.30 - 0.01 * k as f64);
+            fusion.add_factor(Box::new(StatePriorFactor::new(&id, observed, 0.5 + 0.1 * k as f64)));
+            if k > 0 {
+                fusion.add_factor(Box::new(EnvironmentalProcessFactor::new(format!("x{}", k - 1), id, 3600.0, &diffusion)));
+            }
+        }
+        fusion
+    }
+
+    #[test]
+    fn test_pcg_matches_dense_cholesky_solve() {
+        let mut system = BlockSparseSystem::new(vec![2, 3, 2]);
+        system.add_factor(&[0], &linearized(2, 2, 1));
+        system.add_factor(&[1], &linearized(3, 3, 2));
+        system.add_factor(&[2], &linearized(2, 2, 3));
+        system.add_factor(&[0, 1], &linearized(3, 5, 4));
+        system.add_factor(&[1, 2], &linearized(4, 5, 5));
+        system.add_factor(&[2, 0], &linearized(2, 4, 6));
+
+        let rhs = -system.gradient();
+        for damping in [0.0, 1e-2, 10.0] {
+            let dense = system.to_dense();
+            let damped = &dense + DMatrix::from_diagonal(&dense.diagonal()) * damping;
+            let expected = damped.cholesky().expect("test system is positive definite").solve(&rhs);
+            let solution = system.solve(&rhs, damping, 1e-12, 100).expect("PCG converges");
+            assert!((&solution - &expected).norm() < 1e-8 * expected.norm().max(1.0), "damping {}", damping);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_marginalised_window_matches_full_batch_solve() {
+        let full = hourly_chain(10).optimize_sliding_window().await.unwrap();
+        let windowed = hourly_chain(3).optimize_sliding_window().await.unwrap();
+
+        assert_eq!(full.marginalized_variables, 0);
+        assert_eq!(windowed.marginalized_variables, 3);
+        assert_eq!(windowed.variables.len(), 3);
+        for k in 3..6 {
+            let id = format!("x{}", k);
+            let difference = (&windowed.variables[&id] - &full.variables[&id]).abs().max();
+            assert!(difference < 1e-4, "{}: {}", id, difference);
+        }
+        let (full_cov, windowed_cov) = (full.latest_covariance.unwrap(), windowed.latest_covariance.unwrap());
+        assert!((full_cov - windowed_cov).abs().max() < 1e-6);
+    }
+
+    async fn single_station_estimate(loss: RobustLoss) -> SlidingWindowEstimate {
+        let mut fusion = MultiRateSensorFusion::new();
+        fusion.add_variable("x0", 0.0, DVector::zeros(ENVIRONMENTAL_STATE_DIM));
+        fusion.add_factor(Box::new(StatePriorFactor::new("x0", DVector::zeros(ENVIRONMENTAL_STATE_DIM), 100.0)));
+        for reading in [20.0, 20.2, 19.8, 20.1, 35.0] {
+            fusion.add_factor(Box::new(StationTemperatureFactor::new("x0".to_string(), reading, 0.5, 0.0).with_loss(loss)));
+        }
+        fusion.optimize_sliding_window().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_robust_loss_downweights_outlying_station() {
+        let least_squares = single_station_estimate(RobustLoss::Quadratic).await;
+        let huber = single_station_estimate(RobustLoss::Huber { delta: 2.0 }).await;
+        let temperature = |estimate: &SlidingWindowEstimate| estimate.variables["x0"][state_index::TEMPERATURE];
+
+        assert!(temperature(&least_squares) > 22.5);
+        assert_eq!(least_squares.downweighted_factors, 0);
+        assert!((temperature(&huber) - 20.27).abs() < 0.1, "{}", temperature(&huber));
+        assert_eq!(huber.downweighted_factors, 1);
+        assert!(huber.converged);
+    }
+}
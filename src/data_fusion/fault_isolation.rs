@@ -0,0 +1,887 @@
+use std::collections::{HashMap, VecDeque};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Serialize;
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+use crate::error::AppError;
+use super::fuzzy_evidence::haversine_distance_m;
+use super::{
+    FusedState, FusionConfig, MeasurementValue, PressureUnit, SensorMeasurementBundle, SensorType,
+    TemperatureScale, TimestampedMeasurement, UncertaintyEstimates,
+};
+
+/// Tuning for sensor fault detection and isolation
+#[derive(Debug, Clone)]
+pub struct FaultIsolationConfig {
+    /// False-alarm rate of the single-sample innovation test (χ², 1 dof)
+    pub innovation_significance: f64,
+    /// Number of normalised innovations summed for the drift/bias test
+    pub innovation_window: usize,
+    /// False-alarm rate of the windowed test (χ², `innovation_window` dof)
+    pub window_significance: f64,
+    /// Consecutive innovation or consensus failures before quarantine
+    pub consecutive_failures_to_quarantine: usize,
+    /// Fused estimates older than this are not used as a reference
+    pub max_reference_age: ChronoDuration,
+    /// Sensors closer than this observe the same conditions
+    pub consensus_radius_m: f64,
+    /// Fewer neighbours than this and no consensus test is made
+    pub min_consensus_neighbours: usize,
+    /// Fraction trimmed from each end of the neighbour values; the share of
+    /// neighbours that may be faulty without moving the consensus
+    pub trim_fraction: f64,
+    /// Robust z-score beyond which a reading disagrees with its neighbours
+    pub consensus_threshold: f64,
+    /// Identical readings in a row, while the reference moves, that mark a stuck sensor
+    pub stuck_samples: usize,
+    /// Movement from the first reported position that marks a relocated sensor
+    pub relocation_tolerance_m: f64,
+    /// Time spent quarantined before a sensor is retested on probation
+    pub probation_period: ChronoDuration,
+    /// Clean tests on probation before a sensor is trusted again
+    pub probation_clean_tests: usize,
+}
+
+impl Default for FaultIsolationConfig {
+    fn default() -> Self {
+        Self {
+            innovation_significance: 0.001,
+            innovation_window: 12,
+            window_significance: 0.001,
+            consecutive_failures_to_quarantine: 3,
+            max_reference_age: ChronoDuration::minutes(30),
+            consensus_radius_m: 5_000.0,
+            min_consensus_neighbours: 3,
+            trim_fraction: 0.3,
+            consensus_threshold: 4.0,
+            stuck_samples: 12,
+            relocation_tolerance_m: 50.0,
+            probation_period: ChronoDuration::hours(6),
+            probation_clean_tests: 6,
+        }
+    }
+}
+
+impl FaultIsolationConfig {
+    /// Defaults with the trimming fraction taken from the engine's Byzantine fault threshold
+    pub fn from_fusion_config(config: &FusionConfig) -> Self {
+        Self {
+            trim_fraction: config.byzantine_fault_threshold.clamp(0.0, 0.45),
+            ..Self::default()
+        }
+    }
+}
+
+/// Why a sensor failed a test
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QuarantineReason {
+    /// Single reading inconsistent with the fused estimate
+    InnovationOutlier { quantity: String, nis: f64, threshold: f64 },
+    /// Sum of recent normalised innovations too large: bias or drift
+    PersistentBias { quantity: String, windowed_nis: f64, threshold: f64, samples: usize },
+    /// Reading far from the robust consensus of nearby sensors
+    ConsensusDeviation { quantity: String, value: f64, median: f64, trimmed_mean: f64, robust_z: f64, neighbours: usize },
+    /// Same value repeated while the estimate it should follow changed
+    StuckReading { quantity: String, value: f64, samples: usize },
+    /// Reported position moved away from where the sensor was installed
+    Relocated { distance_m: f64 },
+    /// Quarantined by an operator
+    Operator { note: String },
+}
+
+impl QuarantineReason {
+    /// Evidence that is already aggregated over time and quarantines on first sight
+    fn is_conclusive(&self) -> bool {
+        !matches!(self, QuarantineReason::InnovationOutlier { .. } | QuarantineReason::ConsensusDeviation { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum SensorStatus {
+    Healthy,
+    /// Failing tests but not yet quarantined
+    Suspect { consecutive_failures: usize },
+    /// Excluded from fusion
+    Quarantined { since: DateTime<Utc> },
+    /// Still excluded, being retested before it is trusted again
+    Probation { since: DateTime<Utc>, clean_tests: usize },
+}
+
+impl SensorStatus {
+    /// Whether measurements from the sensor are withheld from fusion
+    pub fn is_excluded(&self) -> bool {
+        matches!(self, SensorStatus::Quarantined { .. } | SensorStatus::Probation { .. })
+    }
+}
+
+/// One quarantine episode, from first exclusion until the sensor passes probation
+#[derive(Debug, Clone, Serialize)]
+pub struct QuarantineRecord {
+    pub started: DateTime<Utc>,
+    pub ended: Option<DateTime<Utc>>,
+    pub reasons: Vec<QuarantineReason>,
+    pub failed_probations: usize,
+    pub released_by_operator: bool,
+}
+
+/// Recent history of one quantity reported by a sensor
+#[derive(Debug, Clone, Default)]
+struct QuantityTrack {
+    nis: VecDeque<f64>,
+    values: VecDeque<f64>,
+    centres: VecDeque<f64>,
+}
+
+impl QuantityTrack {
+    fn clear(&mut self) {
+        self.nis.clear();
+        self.values.clear();
+        self.centres.clear();
+    }
+}
+
+fn push_bounded(window: &mut VecDeque<f64>, value: f64, capacity: usize) {
+    if window.len() == capacity {
+        window.pop_front();
+    }
+    window.push_back(value);
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SensorHealth {
+    pub sensor_id: String,
+    pub sensor_type: SensorType,
+    pub status: SensorStatus,
+    /// First reported position, or the position confirmed when an operator released the sensor
+    pub registered_location: (f64, f64, f64),
+    pub last_location: (f64, f64, f64),
+    pub last_seen: DateTime<Utc>,
+    /// Tests failed by the most recent measurement
+    pub failed_tests: Vec<QuarantineReason>,
+    pub tests_run: u64,
+    pub tests_failed: u64,
+    pub history: Vec<QuarantineRecord>,
+    #[serde(skip)]
+    tracks: HashMap<&'static str, QuantityTrack>,
+}
+
+impl SensorHealth {
+    fn new(sensor_id: String, sensor_type: SensorType, location: (f64, f64, f64), time: DateTime<Utc>) -> Self {
+        Self {
+            sensor_id,
+            sensor_type,
+            status: SensorStatus::Healthy,
+            registered_location: location,
+            last_location: location,
+            last_seen: time,
+            failed_tests: Vec::new(),
+            tests_run: 0,
+            tests_failed: 0,
+            history: Vec::new(),
+            tracks: HashMap::new(),
+        }
+    }
+
+    fn quarantine(&mut self, time: DateTime<Utc>, reasons: Vec<QuarantineReason>) {
+        let failed_probation = matches!(self.status, SensorStatus::Probation { .. });
+        self.status = SensorStatus::Quarantined { since: time };
+        match self.history.last_mut().filter(|record| record.ended.is_none()) {
+            // Still within an episode, e.g. after failing probation
+            Some(record) => {
+                record.failed_probations += usize::from(failed_probation);
+                record.reasons.extend(reasons);
+            },
+            None => self.history.push(QuarantineRecord {
+                started: time,
+                ended: None,
+                reasons,
+                failed_probations: 0,
+                released_by_operator: false,
+            }),
+        }
+    }
+
+    fn start_probation(&mut self, time: DateTime<Utc>) {
+        self.status = SensorStatus::Probation { since: time, clean_tests: 0 };
+        // Windows filled while faulty would condemn the sensor again straight away
+        self.tracks.values_mut().for_each(QuantityTrack::clear);
+    }
+
+    /// Move a quarantined sensor to probation once it has served its time
+    fn advance_clock(&mut self, time: DateTime<Utc>, config: &FaultIsolationConfig) {
+        if let SensorStatus::Quarantined { since } = self.status {
+            if time - since >= config.probation_period {
+                self.start_probation(time);
+            }
+        }
+    }
+
+    fn apply_outcome(&mut self, failures: Vec<QuarantineReason>, tested: bool, time: DateTime<Utc>, config: &FaultIsolationConfig) {
+        if tested {
+            self.tests_run += 1;
+            self.tests_failed += u64::from(!failures.is_empty());
+        }
+        self.failed_tests = failures.clone();
+
+        match self.status.clone() {
+            SensorStatus::Healthy | SensorStatus::Suspect { .. } if failures.is_empty() => {
+                if tested {
+                    self.status = SensorStatus::Healthy;
+                }
+            },
+            SensorStatus::Healthy | SensorStatus::Suspect { .. } => {
+                let consecutive_failures = match self.status {
+                    SensorStatus::Suspect { consecutive_failures } => consecutive_failures + 1,
+                    _ => 1,
+                };
+                if consecutive_failures >= config.consecutive_failures_to_quarantine
+                    || failures.iter().any(QuarantineReason::is_conclusive)
+                {
+                    self.quarantine(time, failures);
+                } else {
+                    self.status = SensorStatus::Suspect { consecutive_failures };
+                }
+            },
+            SensorStatus::Quarantined { .. } => {},
+            SensorStatus::Probation { .. } if !failures.is_empty() => self.quarantine(time, failures),
+            SensorStatus::Probation { since, clean_tests } if tested => {
+                let clean_tests = clean_tests + 1;
+                if clean_tests >= config.probation_clean_tests {
+                    self.status = SensorStatus::Healthy;
+                    if let Some(record) = self.history.last_mut() {
+                        record.ended = Some(time);
+                    }
+                } else {
+                    self.status = SensorStatus::Probation { since, clean_tests };
+                }
+            },
+            SensorStatus::Probation { .. } => {},
+        }
+    }
+
+    pub fn summary(&self) -> SensorHealthSummary {
+        SensorHealthSummary {
+            sensor_id: self.sensor_id.clone(),
+            sensor_type: self.sensor_type,
+            status: self.status.clone(),
+            failed_tests: self.failed_tests.clone(),
+            current_reasons: match (&self.status, self.history.last()) {
+                (status, Some(record)) if status.is_excluded() => record.reasons.clone(),
+                _ => Vec::new(),
+            },
+            quarantine_count: self.history.len(),
+            last_seen: self.last_seen,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SensorHealthSummary {
+    pub sensor_id: String,
+    pub sensor_type: SensorType,
+    pub status: SensorStatus,
+    pub failed_tests: Vec<QuarantineReason>,
+    /// Reasons for the current quarantine, if excluded
+    pub current_reasons: Vec<QuarantineReason>,
+    pub quarantine_count: usize,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Sensor health at the end of a fusion cycle
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SensorHealthReport {
+    pub generated_at: DateTime<Utc>,
+    pub sensors: Vec<SensorHealthSummary>,
+    /// Measurements withheld from fusion in this cycle
+    pub excluded_measurements: usize,
+}
+
+impl SensorHealthReport {
+    pub fn excluded(&self) -> impl Iterator<Item = &SensorHealthSummary> {
+        self.sensors.iter().filter(|sensor| sensor.status.is_excluded())
+    }
+}
+
+/// Fused estimate of a quantity, in canonical units
+#[derive(Debug, Clone, Copy)]
+struct ReferenceEstimate {
+    mean: f64,
+    variance: f64,
+}
+
+/// A reading reduced to a monitored quantity in canonical units
+#[derive(Debug, Clone)]
+struct Observation {
+    sensor_type: SensorType,
+    index: usize,
+    sensor_id: String,
+    quantity: Option<&'static str>,
+    value: f64,
+    sigma: f64,
+    location: (f64, f64, f64),
+    time: DateTime<Utc>,
+}
+
+/// Quantity name, value and unit scale in canonical units
+/// (°C, %, hPa, m/s, m³/m³, mm/h, W/m²)
+fn canonical_quantity(value: &MeasurementValue) -> Option<(&'static str, f64, f64)> {
+    match value {
+        MeasurementValue::Temperature { value, scale, .. } => Some(match scale {
+            TemperatureScale::Celsius => ("temperature", *value, 1.0),
+            TemperatureScale::Fahrenheit => ("temperature", (value - 32.0) * 5.0 / 9.0, 5.0 / 9.0),
+            TemperatureScale::Kelvin => ("temperature", value - 273.15, 1.0),
+        }),
+        MeasurementValue::Humidity(value) => Some(("humidity", *value, 1.0)),
+        MeasurementValue::Pressure { value, unit } => {
+            let to_hpa = match unit {
+                PressureUnit::Pascal => 0.01,
+                PressureUnit::Hectopascal | PressureUnit::Millibar => 1.0,
+                PressureUnit::InchesHg => 33.8639,
+                PressureUnit::MillimetersHg => 1.33322,
+            };
+            Some(("pressure", value * to_hpa, to_hpa))
+        },
+        MeasurementValue::WindVector { speed, .. } => Some(("wind_speed", *speed, 1.0)),
+        MeasurementValue::SoilMoisture { value, .. } => Some(("soil_moisture", *value, 1.0)),
+        MeasurementValue::Precipitation { rate, .. } => Some(("precipitation_rate", *rate, 1.0)),
+        MeasurementValue::SolarRadiation { global, .. } => Some(("solar_radiation", *global, 1.0)),
+        _ => None,
+    }
+}
+
+fn measurement_time(measurement: &TimestampedMeasurement) -> DateTime<Utc> {
+    let seconds = measurement.timestamp.floor();
+    let nanos = ((measurement.timestamp - seconds) * 1e9) as u32;
+    DateTime::<Utc>::from_timestamp(seconds as i64, nanos).unwrap_or_default()
+}
+
+/// Sensors that are expected to move
+fn is_mobile(sensor_type: SensorType) -> bool {
+    matches!(
+        sensor_type,
+        SensorType::GPS | SensorType::SatelliteImagery | SensorType::DroneMultispectral | SensorType::AutonomousWeatherBuoy
+    )
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let n = sorted.len();
+    if n % 2 == 1 { sorted[n / 2] } else { 0.5 * (sorted[n / 2 - 1] + sorted[n / 2]) }
+}
+
+/// Median, trimmed mean and MAD-based standard deviation of `values`
+fn robust_consensus(values: &mut [f64], trim_fraction: f64) -> (f64, f64, f64) {
+    values.sort_by(f64::total_cmp);
+    let centre = median(values);
+    let trim = ((values.len() as f64) * trim_fraction).floor() as usize;
+    let kept = &values[trim..values.len() - trim];
+    let trimmed_mean = kept.iter().sum::<f64>() / kept.len() as f64;
+    let mut deviations: Vec<f64> = values.iter().map(|v| (v - centre).abs()).collect();
+    deviations.sort_by(f64::total_cmp);
+    (centre, trimmed_mean, 1.4826 * median(&deviations))
+}
+
+/// Residual tracking, consensus voting and quarantine for individual sensors
+///
+/// Each measurement is tested against the last fused estimate with a χ²
+/// innovation test (single sample and windowed, to catch slow drift) and
+/// against the median/trimmed-mean of nearby sensors reporting the same
+/// quantity. Sensors that fail repeatedly, drift, stick or move are
+/// quarantined, withheld from fusion and retested on probation after
+/// `probation_period`. A relocated sensor keeps failing probation until an
+/// operator confirms its new position with [`SensorFaultMonitor::release`].
+#[derive(Debug, Clone)]
+pub struct SensorFaultMonitor {
+    config: FaultIsolationConfig,
+    sensors: HashMap<String, SensorHealth>,
+    reference: HashMap<&'static str, ReferenceEstimate>,
+    reference_time: Option<DateTime<Utc>>,
+    innovation_threshold: f64,
+    window_threshold: f64,
+}
+
+impl SensorFaultMonitor {
+    pub fn new(config: FaultIsolationConfig) -> Self {
+        let chi_square_quantile = |dof: usize, significance: f64| {
+            ChiSquared::new(dof.max(1) as f64)
+                .map(|distribution| distribution.inverse_cdf(1.0 - significance.clamp(1e-12, 0.5)))
+                .unwrap_or(f64::INFINITY)
+        };
+        Self {
+            innovation_threshold: chi_square_quantile(1, config.innovation_significance),
+            window_threshold: chi_square_quantile(config.innovation_window, config.window_significance),
+            config,
+            sensors: HashMap::new(),
+            reference: HashMap::new(),
+            reference_time: None,
+        }
+    }
+
+    pub fn config(&self) -> &FaultIsolationConfig {
+        &self.config
+    }
+
+    /// Use a fused state as the reference for the innovation tests
+    ///
+    /// Only quantities the fusion reported an uncertainty (standard deviation) for are used.
+    pub fn set_fused_reference(&mut self, state: &FusedState, uncertainty: &UncertaintyEstimates) {
+        let weather = &state.weather_state;
+        let candidates = [
+            ("temperature", weather.temperature, uncertainty.weather_uncertainty.get("temperature")),
+            ("humidity", weather.humidity, uncertainty.weather_uncertainty.get("humidity")),
+            ("pressure", weather.pressure, uncertainty.weather_uncertainty.get("pressure")),
+            ("wind_speed", weather.wind_speed, uncertainty.weather_uncertainty.get("wind_speed")),
+            ("precipitation_rate", weather.precipitation_rate, uncertainty.weather_uncertainty.get("precipitation_rate")),
+            ("solar_radiation", weather.solar_radiation, uncertainty.weather_uncertainty.get("solar_radiation")),
+            (
+                "soil_moisture",
+                state.agricultural_conditions.soil_moisture,
+                uncertainty.agricultural_uncertainty.get("soil_moisture"),
+            ),
+        ];
+
+        self.reference = candidates
+            .into_iter()
+            .filter_map(|(quantity, mean, sigma)| {
+                let sigma = *sigma?;
+                (mean.is_finite() && sigma.is_finite() && sigma >= 0.0)
+                    .then_some((quantity, ReferenceEstimate { mean, variance: sigma * sigma }))
+            })
+            .collect();
+        self.reference_time = Some(state.timestamp);
+    }
+
+    /// Test every measurement in the bundle and withhold those from excluded sensors
+    pub fn screen(&mut self, mut bundle: SensorMeasurementBundle) -> (SensorMeasurementBundle, SensorHealthReport) {
+        let mut observations: Vec<Observation> = bundle
+            .measurements
+            .iter()
+            .flat_map(|(sensor_type, measurements)| {
+                measurements.iter().enumerate().map(move |(index, measurement)| {
+                    let canonical = canonical_quantity(&measurement.value);
+                    Observation {
+                        sensor_type: *sensor_type,
+                        index,
+                        sensor_id: measurement.sensor_metadata.sensor_id.clone(),
+                        quantity: canonical.map(|(quantity, _, _)| quantity),
+                        value: canonical.map_or(f64::NAN, |(_, value, _)| value),
+                        sigma: canonical.map_or(f64::NAN, |(_, _, scale)| measurement.uncertainty.abs() * scale),
+                        location: measurement.sensor_metadata.location,
+                        time: measurement_time(measurement),
+                    }
+                })
+            })
+            .collect();
+        observations.sort_by_key(|observation| observation.time);
+
+        // Neighbour consensus uses each trusted sensor's latest reading in the bundle
+        let mut latest: HashMap<(&'static str, &str), &Observation> = HashMap::new();
+        for observation in &observations {
+            let trusted = self.sensors.get(&observation.sensor_id).is_none_or(|health| !health.status.is_excluded());
+            if let (Some(quantity), true) = (observation.quantity, trusted && observation.value.is_finite()) {
+                latest.insert((quantity, observation.sensor_id.as_str()), observation);
+            }
+        }
+        let latest: Vec<Observation> = latest.into_values().cloned().collect();
+
+        for observation in &observations {
+            self.evaluate(observation, &latest);
+        }
+
+        let mut excluded_measurements = 0;
+        for (sensor_type, measurements) in bundle.measurements.iter_mut() {
+            let before = measurements.len();
+            measurements.retain(|measurement| {
+                self.sensors
+                    .get(&measurement.sensor_metadata.sensor_id)
+                    .is_none_or(|health| !health.status.is_excluded())
+            });
+            excluded_measurements += before - measurements.len();
+            if before != measurements.len() {
+                tracing::debug!("Withheld {} {:?} measurements from quarantined sensors", before - measurements.len(), sensor_type);
+            }
+        }
+        bundle.measurements.retain(|_, measurements| !measurements.is_empty());
+
+        let report = SensorHealthReport {
+            excluded_measurements,
+            ..self.report(bundle.temporal_window.1)
+        };
+        (bundle, report)
+    }
+
+    fn evaluate(&mut self, observation: &Observation, latest: &[Observation]) {
+        let config = &self.config;
+        let health = self.sensors.entry(observation.sensor_id.clone()).or_insert_with(|| {
+            SensorHealth::new(observation.sensor_id.clone(), observation.sensor_type, observation.location, observation.time)
+        });
+        health.advance_clock(observation.time, config);
+        health.last_seen = health.last_seen.max(observation.time);
+        health.last_location = observation.location;
+
+        let mut failures = Vec::new();
+        let mut tested = false;
+
+        if !is_mobile(observation.sensor_type) {
+            let registered = health.registered_location;
+            let distance_m = haversine_distance_m((registered.0, registered.1), (observation.location.0, observation.location.1));
+            if distance_m > config.relocation_tolerance_m {
+                failures.push(QuarantineReason::Relocated { distance_m });
+            }
+        }
+
+        if let (Some(quantity), true) = (observation.quantity, observation.value.is_finite()) {
+            let track = health.tracks.entry(quantity).or_default();
+            let mut centre = None;
+
+            // Innovation against the fused state
+            let reference = self
+                .reference
+                .get(quantity)
+                .filter(|_| self.reference_time.is_some_and(|t| (observation.time - t).abs() <= config.max_reference_age));
+            if let Some(reference) = reference {
+                tested = true;
+                let innovation_variance = (reference.variance + observation.sigma * observation.sigma).max(f64::EPSILON);
+                let nis = (observation.value - reference.mean).powi(2) / innovation_variance;
+                if nis > self.innovation_threshold {
+                    failures.push(QuarantineReason::InnovationOutlier {
+                        quantity: quantity.to_string(),
+                        nis,
+                        threshold: self.innovation_threshold,
+                    });
+                }
+                push_bounded(&mut track.nis, nis, config.innovation_window);
+                let windowed_nis: f64 = track.nis.iter().sum();
+                if track.nis.len() == config.innovation_window && windowed_nis > self.window_threshold {
+                    failures.push(QuarantineReason::PersistentBias {
+                        quantity: quantity.to_string(),
+                        windowed_nis,
+                        threshold: self.window_threshold,
+                        samples: track.nis.len(),
+                    });
+                    track.nis.clear();
+                }
+                centre = Some(reference.mean);
+            }
+
+            // Consensus among nearby sensors
+            let mut neighbour_values: Vec<f64> = Vec::new();
+            let mut neighbour_variance = 0.0;
+            for neighbour in latest {
+                let nearby = haversine_distance_m(
+                    (neighbour.location.0, neighbour.location.1),
+                    (observation.location.0, observation.location.1),
+                ) <= config.consensus_radius_m;
+                if neighbour.quantity == Some(quantity) && neighbour.sensor_id != observation.sensor_id && nearby {
+                    neighbour_values.push(neighbour.value);
+                    neighbour_variance += neighbour.sigma * neighbour.sigma;
+                }
+            }
+            if neighbour_values.len() >= config.min_consensus_neighbours.max(1) {
+                tested = true;
+                let neighbours = neighbour_values.len();
+                neighbour_variance /= neighbours as f64;
+                let (median, trimmed_mean, spread) = robust_consensus(&mut neighbour_values, config.trim_fraction);
+                let scale = (spread * spread + neighbour_variance + observation.sigma * observation.sigma).sqrt().max(f64::EPSILON);
+                let robust_z = (observation.value - trimmed_mean).abs() / scale;
+                if robust_z > config.consensus_threshold {
+                    failures.push(QuarantineReason::ConsensusDeviation {
+                        quantity: quantity.to_string(),
+                        value: observation.value,
+                        median,
+                        trimmed_mean,
+                        robust_z,
+                        neighbours,
+                    });
+                }
+                centre = centre.or(Some(trimmed_mean));
+            }
+
+            // A silent failure often repeats the last good value
+            if let Some(centre) = centre {
+                push_bounded(&mut track.values, observation.value, config.stuck_samples);
+                push_bounded(&mut track.centres, centre, config.stuck_samples);
+                let range = |window: &VecDeque<f64>| {
+                    window.iter().copied().fold(f64::NEG_INFINITY, f64::max) - window.iter().copied().fold(f64::INFINITY, f64::min)
+                };
+                let frozen = range(&track.values) <= 1e-9 * observation.value.abs().max(1.0);
+                if track.values.len() == config.stuck_samples
+                    && frozen
+                    && range(&track.centres) > 3.0 * observation.sigma.max(1e-6)
+                {
+                    failures.push(QuarantineReason::StuckReading {
+                        quantity: quantity.to_string(),
+                        value: observation.value,
+                        samples: track.values.len(),
+                    });
+                    track.values.clear();
+                    track.centres.clear();
+                }
+            }
+        }
+
+        let was_excluded = health.status.is_excluded();
+        health.apply_outcome(failures, tested, observation.time, config);
+        if !was_excluded && health.status.is_excluded() {
+            tracing::warn!("Quarantined {:?} sensor {}: {:?}", health.sensor_type, health.sensor_id, health.failed_tests);
+        }
+    }
+
+    pub fn report(&self, generated_at: DateTime<Utc>) -> SensorHealthReport {
+        let mut sensors: Vec<SensorHealthSummary> = self.sensors.values().map(SensorHealth::summary).collect();
+        sensors.sort_by(|a, b| a.sensor_id.cmp(&b.sensor_id));
+        SensorHealthReport { generated_at, sensors, excluded_measurements: 0 }
+    }
+
+    pub fn sensor(&self, sensor_id: &str) -> Option<&SensorHealth> {
+        self.sensors.get(sensor_id)
+    }
+
+    /// Exclude a sensor on an operator's say-so, e.g. after it was knocked over
+    pub fn quarantine(&mut self, sensor_id: &str, note: String, time: DateTime<Utc>) -> Result<&SensorHealth, AppError> {
+        let health = self
+            .sensors
+            .get_mut(sensor_id)
+            .ok_or_else(|| AppError::not_found(format!("Sensor {} has not reported", sensor_id)))?;
+        health.quarantine(time, vec![QuarantineReason::Operator { note }]);
+        Ok(health)
+    }
+
+    /// Start probation now for a quarantined sensor, accepting its current position as its installed one
+    pub fn release(&mut self, sensor_id: &str, time: DateTime<Utc>) -> Result<&SensorHealth, AppError> {
+        let health = self
+            .sensors
+            .get_mut(sensor_id)
+            .ok_or_else(|| AppError::not_found(format!("Sensor {} has not reported", sensor_id)))?;
+        if !matches!(health.status, SensorStatus::Quarantined { .. }) {
+            return Err(AppError::validation(format!("Sensor {} is not quarantined", sensor_id)));
+        }
+        health.registered_location = health.last_location;
+        if let Some(record) = health.history.last_mut() {
+            record.released_by_operator = true;
+        }
+        health.start_probation(time);
+        Ok(health)
+    }
+}
+
+impl Default for SensorFaultMonitor {
+    fn default() -> Self {
+        Self::new(FaultIsolationConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{
+        AgriculturalContext, ClimateZone, EnvironmentalData, GeoBounds, GeographicRegion, GrowthStage,
+        IrrigationSystem, PowerStatus, QualityFlags, SensorMetadata,
+    };
+
+    fn station(id: &str, location: (f64, f64)) -> SensorMetadata {
+        SensorMetadata {
+            sensor_id: id.to_string(),
+            manufacturer: String::new(),
+            model: String::new(),
+            serial_number: String::new(),
+            calibration_date: DateTime::<Utc>::default(),
+            last_maintenance: DateTime::<Utc>::default(),
+            firmware_version: String::new(),
+            location: (location.0, location.1, 1100.0),
+            installation_date: DateTime::<Utc>::default(),
+            communication_latency: None,
+            power_status: PowerStatus::GridPowered,
+            communication_quality: 1.0,
+            calibration_version: None,
+        }
+    }
+
+    fn temperature(metadata: &SensorMetadata, timestamp: f64, value: f64) -> TimestampedMeasurement {
+        TimestampedMeasurement {
+            timestamp,
+            value: MeasurementValue::Temperature { value, scale: TemperatureScale::Celsius, sensor_id: metadata.sensor_id.clone() },
+            uncertainty: 0.3,
+            temporal_uncertainty: None,
+            environmental_data: EnvironmentalData {
+                temperature: value,
+                humidity: 50.0,
+                pressure: 900.0,
+                altitude: 1100.0,
+                magnetic_field: None,
+                solar_activity: None,
+                electromagnetic_interference: None,
+                vibration_level: None,
+            },
+            sensor_metadata: metadata.clone(),
+            quality_flags: QualityFlags {
+                is_valid: true,
+                is_calibrated: true,
+                drift_detected: false,
+                outlier_detected: false,
+                communication_error: false,
+                sensor_malfunction: false,
+                environmental_impact: false,
+                data_completeness: 1.0,
+            },
+        }
+    }
+
+    fn bundle(measurements: Vec<TimestampedMeasurement>) -> SensorMeasurementBundle {
+        let end = measurements.iter().map(measurement_time).max().unwrap_or_default();
+        SensorMeasurementBundle {
+            measurements: HashMap::from([(SensorType::WeatherStation, measurements)]),
+            quality_metrics: HashMap::new(),
+            temporal_window: (end, end),
+            bundle_id: uuid::Uuid::nil(),
+            geographic_region: GeographicRegion {
+                name: "Buhera".to_string(),
+                bounds: GeoBounds { north: -19.0, south: -20.0, east: 32.0, west: 31.0 },
+                elevation_range: (900.0, 1300.0),
+                climate_zone: ClimateZone::Semiarid,
+                soil_types: Vec::new(),
+                typical_crops: Vec::new(),
+            },
+            agricultural_context: AgriculturalContext {
+                crop_type: "maize".to_string(),
+                growth_stage: GrowthStage::Vegetative,
+                planting_date: None,
+                expected_harvest_date: None,
+                irrigation_system: IrrigationSystem::None,
+                field_management_practices: Vec::new(),
+                historical_yield_data: None,
+            },
+        }
+    }
+
+    fn fused(timestamp: f64, temperature: f64) -> (FusedState, UncertaintyEstimates) {
+        let mut state = FusedState { timestamp: DateTime::<Utc>::from_timestamp(timestamp as i64, 0).unwrap(), ..Default::default() };
+        state.weather_state.temperature = temperature;
+        let uncertainty = UncertaintyEstimates {
+            weather_uncertainty: HashMap::from([("temperature".to_string(), 0.2)]),
+            ..Default::default()
+        };
+        (state, uncertainty)
+    }
+
+    fn network() -> Vec<SensorMetadata> {
+        (0..5).map(|i| station(&format!("ws-{}", i), (-19.5 + 0.002 * i as f64, 31.6))).collect()
+    }
+
+    #[test]
+    fn test_robust_consensus_ignores_minority() {
+        let mut values = vec![20.0, 20.2, 19.9, 20.1, 45.0];
+        let (median, trimmed_mean, spread) = robust_consensus(&mut values, 0.2);
+        assert_eq!(median, 20.1);
+        assert!((trimmed_mean - 20.1).abs() < 1e-9);
+        assert!(spread < 0.5);
+    }
+
+    #[test]
+    fn test_consensus_outlier_is_quarantined_and_withheld() {
+        let sensors = network();
+        let mut monitor = SensorFaultMonitor::default();
+
+        let mut last_report = SensorHealthReport::default();
+        for step in 0..3 {
+            let t = 1_700_000_000.0 + 600.0 * step as f64;
+            let readings = sensors
+                .iter()
+                .enumerate()
+                .map(|(i, s)| temperature(s, t, if i == 4 { 35.0 } else { 24.0 + 0.1 * i as f64 }))
+                .collect();
+            let (screened, report) = monitor.screen(bundle(readings));
+            last_report = report;
+            let kept = &screened.measurements[&SensorType::WeatherStation];
+            if step < 2 {
+                assert_eq!(kept.len(), 5);
+            } else {
+                assert_eq!(kept.len(), 4);
+            }
+        }
+
+        assert_eq!(last_report.excluded_measurements, 1);
+        let excluded: Vec<_> = last_report.excluded().collect();
+        assert_eq!(excluded.len(), 1);
+        assert_eq!(excluded[0].sensor_id, "ws-4");
+        assert!(matches!(excluded[0].current_reasons[0], QuarantineReason::ConsensusDeviation { .. }));
+        assert_eq!(monitor.sensor("ws-0").unwrap().status, SensorStatus::Healthy);
+    }
+
+    #[test]
+    fn test_windowed_innovation_catches_slow_drift() {
+        let sensor = station("drifting", (-19.5, 31.6));
+        let mut monitor = SensorFaultMonitor::default();
+        let mut quarantined_at = None;
+
+        for step in 0..40 {
+            let t = 1_700_000_000.0 + 300.0 * step as f64;
+            let (state, uncertainty) = fused(t, 22.0);
+            monitor.set_fused_reference(&state, &uncertainty);
+            // 0.05 °C per step: each reading alone stays within the single-sample test for a while
+            let (_, report) = monitor.screen(bundle(vec![temperature(&sensor, t, 22.0 + 0.05 * step as f64)]));
+            if report.excluded().next().is_some() {
+                quarantined_at = Some(step);
+                break;
+            }
+        }
+
+        let health = monitor.sensor("drifting").unwrap();
+        assert!(quarantined_at.is_some());
+        assert!(health.history[0].reasons.iter().any(|r| matches!(r, QuarantineReason::PersistentBias { .. })));
+    }
+
+    #[test]
+    fn test_stuck_and_relocated_sensors() {
+        let mut monitor = SensorFaultMonitor::default();
+        let stuck = station("stuck", (-19.5, 31.6));
+        for step in 0..12 {
+            let t = 1_700_000_000.0 + 300.0 * step as f64;
+            let (state, uncertainty) = fused(t, 18.0 + 0.2 * step as f64);
+            monitor.set_fused_reference(&state, &uncertainty);
+            monitor.screen(bundle(vec![temperature(&stuck, t, 19.0)]));
+        }
+        let health = monitor.sensor("stuck").unwrap();
+        assert!(health.status.is_excluded());
+        assert!(health.history[0].reasons.iter().any(|r| matches!(r, QuarantineReason::StuckReading { .. })));
+
+        let mut monitor = SensorFaultMonitor::default();
+        let mut moved = station("moved", (-19.5, 31.6));
+        monitor.screen(bundle(vec![temperature(&moved, 1_700_000_000.0, 20.0)]));
+        moved.location.0 += 0.01;
+        monitor.screen(bundle(vec![temperature(&moved, 1_700_000_600.0, 20.0)]));
+        let health = monitor.sensor("moved").unwrap();
+        assert!(matches!(health.history[0].reasons[0], QuarantineReason::Relocated { distance_m } if distance_m > 1_000.0));
+    }
+
+    #[test]
+    fn test_probation_reinstates_recovered_sensor() {
+        let config = FaultIsolationConfig { probation_clean_tests: 3, ..Default::default() };
+        let mut monitor = SensorFaultMonitor::new(config);
+        let sensor = station("ws", (-19.5, 31.6));
+        let start = 1_700_000_000.0;
+
+        monitor.screen(bundle(vec![temperature(&sensor, start, 20.0)]));
+        monitor.quarantine("ws", "knocked over by cattle".to_string(), DateTime::<Utc>::from_timestamp(start as i64, 0).unwrap()).unwrap();
+        assert!(monitor.release("unknown", Utc::now()).is_err());
+
+        // Still quarantined inside the probation period
+        let t = start + 3_600.0;
+        let (state, uncertainty) = fused(t, 20.0);
+        monitor.set_fused_reference(&state, &uncertainty);
+        let (screened, _) = monitor.screen(bundle(vec![temperature(&sensor, t, 20.0)]));
+        assert!(screened.measurements.is_empty());
+
+        // After six hours it is retested and reinstated after three clean tests
+        for step in 0..4 {
+            let t = start + 7.0 * 3_600.0 + 300.0 * step as f64;
+            let (state, uncertainty) = fused(t, 20.0);
+            monitor.set_fused_reference(&state, &uncertainty);
+            monitor.screen(bundle(vec![temperature(&sensor, t, 20.1)]));
+        }
+        let health = monitor.sensor("ws").unwrap();
+        assert_eq!(health.status, SensorStatus::Healthy);
+        assert!(health.history[0].ended.is_some());
+        assert!(matches!(health.history[0].reasons[0], QuarantineReason::Operator { .. }));
+    }
+}
@@ -1,7 +1,10 @@
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use chrono::{DateTime, Utc};
 use nalgebra::{DMatrix, DVector, SMatrix, SVector};
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+use serde::{Deserialize, Serialize};
 use crate::error::AppError;
 
 #[derive(Debug, Clone)]
@@ -50,122 +53,556 @@ pub struct SyntheticApertureProcessor {
     pub motion_compensation: MotionCompensator,
 }
 
-use super::{SensorType, TimestampedMeasurement, SensorMeasurementBundle};
+use super::{
+    AgriculturalConditions, AgriculturalInsights, ConfidenceMetrics, FusedState, FusionResult,
+    MeasurementValue, OptimizationTrace, SensorMeasurementBundle, SensorType, TimestampedMeasurement,
+    UncertaintyEstimates,
+};
+use super::factor_graph::{linearize_factor, BlockSparseSystem, MarginalizationPriorFactor, RobustLoss};
 
 // ACTUAL ALGORITHMS FROM USER'S fusion.md FILE
 
 pub trait Factor: Send + Sync {
+    /// Residual r(x) = z - h(x) at the current variable values
     fn error(&self, variables: &HashMap<String, Variable>) -> DVector<f64>;
+
+    /// ∂r/∂x, with columns ordered as the concatenation of `get_connected_variables`
     fn jacobian(&self, variables: &HashMap<String, Variable>) -> DMatrix<f64>;
+
     fn information_matrix(&self) -> DMatrix<f64>;
+
+    /// Ids of the graph variables this factor constrains
+    fn get_connected_variables(&self) -> Vec<String>;
+
+    /// Robust kernel applied to the whitened residual
+    fn robust_loss(&self) -> RobustLoss {
+        RobustLoss::Quadratic
+    }
 }
 
 // Mutual Information Maximization for sensor selection - FROM USER'S CODE
+
+/// Components of the Gaussian belief that sensor tasking plans over, in order
+pub const TASKING_STATE: [&str; 10] = [
+    "air_temperature_c",
+    "relative_humidity_pct",
+    "precipitation_mm_h",
+    "wind_speed_m_s",
+    "solar_radiation_w_m2",
+    "soil_moisture_m3_m3",
+    "leaf_area_index",
+    "evapotranspiration_mm_day",
+    "position_error_m",
+    "clock_offset_ns",
+];
+
+const AIR_TEMPERATURE: usize = 0;
+const HUMIDITY: usize = 1;
+const PRECIPITATION: usize = 2;
+const WIND_SPEED: usize = 3;
+const SOLAR_RADIATION: usize = 4;
+const SOIL_MOISTURE: usize = 5;
+const LEAF_AREA_INDEX: usize = 6;
+const EVAPOTRANSPIRATION: usize = 7;
+const POSITION_ERROR: usize = 8;
+const CLOCK_OFFSET: usize = 9;
+
+/// How one sensor channel responds to its state component
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObservationOperator {
+    /// Reads the component directly
+    Direct,
+    /// NDVI through a saturating Beer–Lambert response to leaf area index
+    CanopyNdvi { ndvi_soil: f64, ndvi_max: f64, extinction: f64 },
+    /// Radar reflectivity in dBZ from rain rate, `Z = a·Rᵇ`
+    Reflectivity { a: f64, b: f64 },
+    /// Cosmic-ray neutron count rate from volumetric soil moisture (Desilets et al., 2010)
+    NeutronCount { n0: f64 },
+}
+
+impl ObservationOperator {
+    /// Predicted reading and its derivative with respect to the state component
+    fn value_and_slope(&self, x: f64) -> (f64, f64) {
+        match *self {
+            ObservationOperator::Direct => (x, 1.0),
+            ObservationOperator::CanopyNdvi { ndvi_soil, ndvi_max, extinction } => {
+                let decay = (-extinction * x.max(0.0)).exp();
+                (ndvi_max - (ndvi_max - ndvi_soil) * decay, (ndvi_max - ndvi_soil) * extinction * decay)
+            },
+            ObservationOperator::Reflectivity { a, b } => {
+                // Linearise above drizzle so dry conditions keep a finite slope
+                let rate = x.max(0.1);
+                (10.0 * (a * rate.powf(b)).log10(), 10.0 * b / (rate * std::f64::consts::LN_10))
+            },
+            ObservationOperator::NeutronCount { n0 } => {
+                let theta = x.max(0.02);
+                (n0 * (0.0808 / (theta + 0.115) + 0.372), -n0 * 0.0808 / (theta + 0.115).powi(2))
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObservationChannel {
+    /// Index into `TASKING_STATE`
+    pub state_index: usize,
+    pub operator: ObservationOperator,
+    pub noise_std: f64,
+}
+
+impl ObservationChannel {
+    fn direct(state_index: usize, noise_std: f64) -> Self {
+        Self { state_index, operator: ObservationOperator::Direct, noise_std }
+    }
+
+    fn ndvi(noise_std: f64) -> Self {
+        Self {
+            state_index: LEAF_AREA_INDEX,
+            operator: ObservationOperator::CanopyNdvi { ndvi_soil: 0.15, ndvi_max: 0.9, extinction: 0.7 },
+            noise_std,
+        }
+    }
+}
+
+/// Observation model of one sensor acquisition, with independent channel noise
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorObservationModel {
+    pub channels: Vec<ObservationChannel>,
+}
+
+impl SensorObservationModel {
+    /// Typical accuracy of one acquisition of each sensor type
+    pub fn for_sensor(sensor_type: SensorType) -> Self {
+        use ObservationChannel as C;
+        let channels = match sensor_type {
+            SensorType::GPS => vec![C::direct(POSITION_ERROR, 3.0), C::direct(CLOCK_OFFSET, 20.0)],
+            SensorType::AtomicClock => vec![C::direct(CLOCK_OFFSET, 1.0)],
+            SensorType::WeatherStation => vec![
+                C::direct(AIR_TEMPERATURE, 0.3),
+                C::direct(HUMIDITY, 3.0),
+                C::direct(PRECIPITATION, 0.3),
+                C::direct(WIND_SPEED, 0.5),
+                C::direct(SOLAR_RADIATION, 25.0),
+            ],
+            SensorType::SoilSensor => vec![C::direct(SOIL_MOISTURE, 0.03)],
+            SensorType::SatelliteImagery => vec![C::ndvi(0.05), C::direct(SOLAR_RADIATION, 60.0)],
+            SensorType::SatelliteRadar => vec![C::direct(SOIL_MOISTURE, 0.05)],
+            SensorType::RadarPrecipitation => vec![C {
+                state_index: PRECIPITATION,
+                operator: ObservationOperator::Reflectivity { a: 200.0, b: 1.6 },
+                noise_std: 2.0,
+            }],
+            // Flash rate says little more than whether convective rain is falling
+            SensorType::LightningDetector => vec![C::direct(PRECIPITATION, 5.0)],
+            SensorType::WindProfiler => vec![C::direct(WIND_SPEED, 0.3)],
+            SensorType::AutonomousWeatherBuoy => {
+                vec![C::direct(AIR_TEMPERATURE, 0.5), C::direct(HUMIDITY, 5.0), C::direct(WIND_SPEED, 0.7)]
+            },
+            SensorType::AgriculturalIoT => vec![C::direct(SOIL_MOISTURE, 0.05), C::direct(AIR_TEMPERATURE, 0.8)],
+            SensorType::DroneMultispectral => vec![C::ndvi(0.02)],
+            SensorType::GroundTruthStation => vec![
+                C::direct(AIR_TEMPERATURE, 0.1),
+                C::direct(HUMIDITY, 1.5),
+                C::direct(PRECIPITATION, 0.1),
+                C::direct(SOIL_MOISTURE, 0.01),
+            ],
+            SensorType::CosmicRayNeutron => vec![C {
+                state_index: SOIL_MOISTURE,
+                operator: ObservationOperator::NeutronCount { n0: 1500.0 },
+                noise_std: 15.0,
+            }],
+            SensorType::EddyCovarianceTower => vec![C::direct(EVAPOTRANSPIRATION, 0.3), C::direct(WIND_SPEED, 0.3)],
+            SensorType::Phenocam => vec![C::ndvi(0.08)],
+            SensorType::Lysimeter => vec![C::direct(EVAPOTRANSPIRATION, 0.1), C::direct(SOIL_MOISTURE, 0.02)],
+        };
+        Self { channels }
+    }
+
+    /// Predicted reading `h(μ)` and Jacobian `H = ∂h/∂x` at the belief mean
+    pub fn linearize(&self, mean: &DVector<f64>) -> (DVector<f64>, DMatrix<f64>) {
+        let mut predicted = DVector::zeros(self.channels.len());
+        let mut jacobian = DMatrix::zeros(self.channels.len(), mean.len());
+        for (row, channel) in self.channels.iter().enumerate() {
+            let (value, slope) = channel.operator.value_and_slope(mean[channel.state_index]);
+            predicted[row] = value;
+            jacobian[(row, channel.state_index)] = slope;
+        }
+        (predicted, jacobian)
+    }
+
+    pub fn noise_covariance(&self) -> DMatrix<f64> {
+        DMatrix::from_diagonal(&DVector::from_iterator(
+            self.channels.len(),
+            self.channels.iter().map(|c| c.noise_std.powi(2)),
+        ))
+    }
+
+    fn max_state_index(&self) -> Option<usize> {
+        self.channels.iter().map(|c| c.state_index).max()
+    }
+}
+
+/// Nominal price of one acquisition, in the unit of the selector's budget
+///
+/// Sensors already streaming cost little; tasked imagery, flights and field
+/// campaigns dominate the weekly budget.
+fn default_acquisition_cost(sensor_type: SensorType) -> f64 {
+    match sensor_type {
+        SensorType::GPS | SensorType::AtomicClock | SensorType::WeatherStation | SensorType::LightningDetector => 1.0,
+        SensorType::AgriculturalIoT | SensorType::Phenocam => 2.0,
+        SensorType::RadarPrecipitation => 5.0,
+        SensorType::AutonomousWeatherBuoy => 10.0,
+        SensorType::WindProfiler | SensorType::SatelliteRadar => 20.0,
+        SensorType::SoilSensor => 25.0,
+        SensorType::EddyCovarianceTower => 30.0,
+        SensorType::Lysimeter => 35.0,
+        SensorType::GroundTruthStation => 40.0,
+        SensorType::CosmicRayNeutron => 60.0,
+        SensorType::DroneMultispectral => 150.0,
+        SensorType::SatelliteImagery => 300.0,
+    }
+}
+
+/// `ln det` of a symmetric positive definite matrix
+fn log_determinant(matrix: &DMatrix<f64>) -> Result<f64, AppError> {
+    let cholesky = matrix
+        .clone()
+        .cholesky()
+        .ok_or_else(|| AppError::validation("Covariance matrix is not positive definite"))?;
+    Ok(2.0 * cholesky.l().diagonal().iter().map(|d| d.ln()).sum::<f64>())
+}
+
+/// Gaussian belief after conditioning on a linearised observation
+struct ConditionedBelief {
+    gain: DMatrix<f64>,
+    covariance: DMatrix<f64>,
+    /// `I(x; z) = ½ ln det(S) − ½ ln det(R)` in nats
+    information: f64,
+}
+
+fn condition(covariance: &DMatrix<f64>, jacobian: &DMatrix<f64>, noise: &DMatrix<f64>) -> Result<ConditionedBelief, AppError> {
+    let innovation = jacobian * covariance * jacobian.transpose() + noise;
+    let information = 0.5 * (log_determinant(&innovation)? - log_determinant(noise)?);
+    let cholesky = innovation
+        .cholesky()
+        .ok_or_else(|| AppError::validation("Innovation covariance is not positive definite"))?;
+    let gain = cholesky.solve(&(jacobian * covariance)).transpose();
+
+    // Joseph form keeps the posterior symmetric positive definite
+    let n = covariance.nrows();
+    let reduction = DMatrix::identity(n, n) - &gain * jacobian;
+    let posterior = &reduction * covariance * reduction.transpose() + &gain * noise * gain.transpose();
+    Ok(ConditionedBelief { gain, covariance: (&posterior + posterior.transpose()) * 0.5, information })
+}
+
+/// Chooses which acquisitions to buy by expected information gain per unit cost
+///
+/// The belief is Gaussian over `TASKING_STATE`. Each sensor is linearised at the
+/// belief mean, so the information it would add is the closed-form mutual
+/// information `½ ln det(HPHᵀ + R) − ½ ln det R`, and conditioning on a set of
+/// sensors needs no simulated readings. Because that gain is submodular,
+/// greedy selection by marginal gain per cost, compared with greedy by raw gain,
+/// is within a constant factor of the best affordable set. Acquisitions that may
+/// come back unusable, such as optical imagery under cloud, count at their
+/// delivery probability times that gain.
 pub struct InformationTheoreticSelector {
     available_sensors: Vec<SensorType>,
     information_gains: HashMap<SensorType, f64>,
     costs: HashMap<SensorType, f64>,
+    delivery_probabilities: HashMap<SensorType, f64>,
+    models: HashMap<SensorType, SensorObservationModel>,
     budget: f64,
 }
 
 impl InformationTheoreticSelector {
     pub fn new(budget: f64) -> Self {
+        let available_sensors = vec![
+            SensorType::GPS,
+            SensorType::AtomicClock,
+            SensorType::WeatherStation,
+            SensorType::SoilSensor,
+            SensorType::SatelliteImagery,
+            SensorType::SatelliteRadar,
+            SensorType::RadarPrecipitation,
+            SensorType::LightningDetector,
+            SensorType::WindProfiler,
+            SensorType::AutonomousWeatherBuoy,
+            SensorType::AgriculturalIoT,
+            SensorType::DroneMultispectral,
+            SensorType::GroundTruthStation,
+            SensorType::CosmicRayNeutron,
+            SensorType::EddyCovarianceTower,
+            SensorType::Phenocam,
+            SensorType::Lysimeter,
+        ];
         Self {
-            available_sensors: vec![SensorType::GPS, SensorType::AtomicClock, SensorType::WeatherStation],
+            costs: available_sensors.iter().map(|&s| (s, default_acquisition_cost(s))).collect(),
+            delivery_probabilities: HashMap::new(),
+            models: available_sensors.iter().map(|&s| (s, SensorObservationModel::for_sensor(s))).collect(),
+            available_sensors,
             information_gains: HashMap::new(),
-            costs: HashMap::new(),
             budget,
         }
     }
-    
+
+    /// Restrict tasking to the sensors deployed on this farm
+    pub fn with_available_sensors(mut self, sensors: Vec<SensorType>) -> Self {
+        self.available_sensors = sensors;
+        self
+    }
+
+    pub fn set_cost(&mut self, sensor_type: SensorType, cost: f64) {
+        self.costs.insert(sensor_type, cost);
+    }
+
+    pub fn set_observation_model(&mut self, sensor_type: SensorType, model: SensorObservationModel) {
+        self.models.insert(sensor_type, model);
+    }
+
+    pub fn set_budget(&mut self, budget: f64) {
+        self.budget = budget;
+    }
+
+    /// Chance that a tasked acquisition yields a usable reading, e.g. a cloud-free scene; 1 unless set
+    pub fn set_delivery_probability(&mut self, sensor_type: SensorType, probability: f64) {
+        self.delivery_probabilities.insert(sensor_type, probability.clamp(0.0, 1.0));
+    }
+
+    /// Expected mutual information of each sensor alone with the last planned belief, in nats
+    pub fn information_gains(&self) -> &HashMap<SensorType, f64> {
+        &self.information_gains
+    }
+
     pub async fn select_optimal_sensors(&mut self, current_belief: &BeliefState) -> Result<Vec<SensorType>, AppError> {
-        // Compute information gain for each sensor
-        for sensor_type in &self.available_sensors {
-            let predicted_measurement = self.predict_measurement(*sensor_type, current_belief);
-            let mut information_gain = 0.0;
-            
-            // Monte Carlo estimation of expected information gain
-            for _ in 0..100 {
-                let simulated_measurement = self.simulate_measurement(*sensor_type, &predicted_measurement);
-                let posterior_if_measurement = self.update_belief(current_belief, &simulated_measurement, *sensor_type);
-                let kl_divergence = self.compute_kl_divergence(&posterior_if_measurement, current_belief);
-                information_gain += kl_divergence / 100.0;
+        Ok(self.plan(current_belief)?.selected.into_iter().map(|choice| choice.sensor_type).collect())
+    }
+
+    /// Affordable set of acquisitions that maximises expected information gain
+    pub fn plan(&mut self, current_belief: &BeliefState) -> Result<TaskingPlan, AppError> {
+        let dimension = current_belief.mean.len();
+        if current_belief.covariance.shape() != (dimension, dimension) {
+            return Err(AppError::validation("Belief covariance does not match the mean"));
+        }
+
+        let mut candidates = Vec::new();
+        for &sensor_type in &self.available_sensors {
+            let model = self.model(sensor_type)?;
+            if model.max_state_index().is_none_or(|index| index >= dimension) {
+                return Err(AppError::validation(format!(
+                    "{:?} observes state components outside the {}-dimensional belief",
+                    sensor_type, dimension
+                )));
             }
-            
-            self.information_gains.insert(*sensor_type, information_gain);
+            let cost = self.cost(sensor_type);
+            if !(cost.is_finite() && cost >= 0.0) {
+                return Err(AppError::validation(format!("{:?} has invalid cost {}", sensor_type, cost)));
+            }
+            let (_, jacobian) = model.linearize(&current_belief.mean);
+            candidates.push(TaskingCandidate {
+                sensor_type,
+                cost,
+                delivery_probability: self.delivery_probabilities.get(&sensor_type).copied().unwrap_or(1.0),
+                jacobian,
+                noise: model.noise_covariance(),
+            });
         }
-        
-        // Select sensors that maximize information gain per cost
-        let selected_sensors = self.optimize_sensor_subset();
-        
-        Ok(selected_sensors)
-    }
-    
-    fn predict_measurement(&self, _sensor_type: SensorType, _belief: &BeliefState) -> PredictedMeasurement {
-        PredictedMeasurement {
-            expected_value: 0.0,
-            uncertainty: 1.0,
+
+        self.information_gains.clear();
+        for candidate in &candidates {
+            let alone = condition(&current_belief.covariance, &candidate.jacobian, &candidate.noise)?;
+            self.information_gains.insert(candidate.sensor_type, candidate.delivery_probability * alone.information);
         }
+
+        let per_cost = self.greedy(&candidates, &current_belief.covariance, true)?;
+        let by_gain = self.greedy(&candidates, &current_belief.covariance, false)?;
+        let (selected, covariance) = if by_gain.0.iter().map(|c| c.marginal_gain_nats).sum::<f64>()
+            > per_cost.0.iter().map(|c| c.marginal_gain_nats).sum::<f64>()
+        {
+            by_gain
+        } else {
+            per_cost
+        };
+
+        Ok(TaskingPlan {
+            total_cost: selected.iter().map(|c| c.cost).sum(),
+            information_gain_nats: selected.iter().map(|c| c.marginal_gain_nats).sum(),
+            selected,
+            expected_posterior: BeliefState { mean: current_belief.mean.clone(), covariance },
+        })
     }
-    
-    fn simulate_measurement(&self, _sensor_type: SensorType, predicted: &PredictedMeasurement) -> SimulatedMeasurement {
-        SimulatedMeasurement {
-            value: predicted.expected_value + rand::random::<f64>() * predicted.uncertainty,
+
+    /// Add the best remaining affordable acquisition until nothing fits or nothing helps
+    fn greedy(
+        &self,
+        candidates: &[TaskingCandidate],
+        prior_covariance: &DMatrix<f64>,
+        per_unit_cost: bool,
+    ) -> Result<(Vec<TaskingChoice>, DMatrix<f64>), AppError> {
+        let mut covariance = prior_covariance.clone();
+        let mut remaining_budget = self.budget;
+        let mut chosen = vec![false; candidates.len()];
+        let mut selected = Vec::new();
+
+        loop {
+            let mut best: Option<(usize, f64, f64, ConditionedBelief)> = None;
+            for (i, candidate) in candidates.iter().enumerate() {
+                if chosen[i] || candidate.cost > remaining_budget {
+                    continue;
+                }
+                let conditioned = condition(&covariance, &candidate.jacobian, &candidate.noise)?;
+                let expected_gain = candidate.delivery_probability * conditioned.information;
+                let score = if per_unit_cost {
+                    expected_gain / candidate.cost.max(f64::EPSILON)
+                } else {
+                    expected_gain
+                };
+                if best.as_ref().is_none_or(|(_, best_score, _, _)| score > *best_score) {
+                    best = Some((i, score, expected_gain, conditioned));
+                }
+            }
+            let Some((i, _, expected_gain, conditioned)) = best else { break };
+            if expected_gain <= 1e-9 {
+                break;
+            }
+            chosen[i] = true;
+            remaining_budget -= candidates[i].cost;
+            covariance = conditioned.covariance;
+            selected.push(TaskingChoice {
+                sensor_type: candidates[i].sensor_type,
+                cost: candidates[i].cost,
+                marginal_gain_nats: expected_gain,
+            });
         }
+        Ok((selected, covariance))
     }
-    
-    fn update_belief(&self, _current: &BeliefState, _measurement: &SimulatedMeasurement, _sensor_type: SensorType) -> BeliefState {
-        BeliefState {
-            mean: DVector::zeros(6),
-            covariance: DMatrix::identity(6, 6),
-        }
+
+    fn model(&self, sensor_type: SensorType) -> Result<&SensorObservationModel, AppError> {
+        self.models
+            .get(&sensor_type)
+            .ok_or_else(|| AppError::validation(format!("No observation model for {:?}", sensor_type)))
     }
-    
-    fn compute_kl_divergence(&self, _p: &BeliefState, _q: &BeliefState) -> f64 {
-        1.0
+
+    fn cost(&self, sensor_type: SensorType) -> f64 {
+        self.costs.get(&sensor_type).copied().unwrap_or_else(|| default_acquisition_cost(sensor_type))
     }
-    
-    fn optimize_sensor_subset(&self) -> Vec<SensorType> {
-        let mut selected = Vec::new();
-        let mut remaining_budget = self.budget;
-        
-        let mut sensor_ratios: Vec<(SensorType, f64)> = self.available_sensors.iter()
-            .map(|&s| {
-                let gain = self.information_gains.get(&s).unwrap_or(&0.0);
-                let cost = self.costs.get(&s).unwrap_or(&1.0);
-                (s, gain / cost)
-            })
-            .collect();
-        
-        sensor_ratios.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        
-        for (sensor_type, _ratio) in sensor_ratios {
-            let cost = self.costs.get(&sensor_type).unwrap_or(&1.0);
-            if remaining_budget >= *cost {
-                selected.push(sensor_type);
-                remaining_budget -= cost;
-            }
+
+    /// Reading the sensor is expected to return and its innovation covariance `HPHᵀ + R`
+    pub fn predict_measurement(&self, sensor_type: SensorType, belief: &BeliefState) -> Result<PredictedMeasurement, AppError> {
+        let model = self.model(sensor_type)?;
+        let (expected_value, jacobian) = model.linearize(&belief.mean);
+        Ok(PredictedMeasurement {
+            expected_value,
+            covariance: &jacobian * &belief.covariance * jacobian.transpose() + model.noise_covariance(),
+        })
+    }
+
+    pub fn simulate_measurement(&self, predicted: &PredictedMeasurement, rng: &mut StdRng) -> Result<SimulatedMeasurement, AppError> {
+        let cholesky = predicted
+            .covariance
+            .clone()
+            .cholesky()
+            .ok_or_else(|| AppError::validation("Predicted covariance is not positive definite"))?;
+        let z = DVector::from_fn(predicted.expected_value.len(), |_, _| StandardNormal.sample(rng));
+        Ok(SimulatedMeasurement { value: &predicted.expected_value + cholesky.l() * z })
+    }
+
+    /// Extended Kalman update of the belief with one acquisition
+    pub fn update_belief(
+        &self,
+        current: &BeliefState,
+        measurement: &SimulatedMeasurement,
+        sensor_type: SensorType,
+    ) -> Result<BeliefState, AppError> {
+        let model = self.model(sensor_type)?;
+        if measurement.value.len() != model.channels.len() {
+            return Err(AppError::validation(format!(
+                "{:?} reads {} channels, got {}",
+                sensor_type,
+                model.channels.len(),
+                measurement.value.len()
+            )));
         }
-        
-        selected
+        let (predicted, jacobian) = model.linearize(&current.mean);
+        let conditioned = condition(&current.covariance, &jacobian, &model.noise_covariance())?;
+        Ok(BeliefState {
+            mean: &current.mean + &conditioned.gain * (&measurement.value - predicted),
+            covariance: conditioned.covariance,
+        })
+    }
+
+    /// `KL(p ‖ q)` between Gaussian beliefs, in nats
+    pub fn compute_kl_divergence(p: &BeliefState, q: &BeliefState) -> Result<f64, AppError> {
+        let k = p.mean.len();
+        let q_cholesky = q
+            .covariance
+            .clone()
+            .cholesky()
+            .ok_or_else(|| AppError::validation("Covariance matrix is not positive definite"))?;
+        let difference = &q.mean - &p.mean;
+        let trace = q_cholesky.solve(&p.covariance).trace();
+        let mahalanobis = difference.dot(&q_cholesky.solve(&difference));
+        Ok(0.5 * (trace + mahalanobis - k as f64 + log_determinant(&q.covariance)? - log_determinant(&p.covariance)?))
     }
 }
 
-#[derive(Debug)]
+struct TaskingCandidate {
+    sensor_type: SensorType,
+    cost: f64,
+    delivery_probability: f64,
+    jacobian: DMatrix<f64>,
+    noise: DMatrix<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskingChoice {
+    pub sensor_type: SensorType,
+    pub cost: f64,
+    /// Expected information added given the acquisitions chosen before it
+    pub marginal_gain_nats: f64,
+}
+
+/// Acquisitions to buy, in the order greedy selection added them
+#[derive(Debug, Clone)]
+pub struct TaskingPlan {
+    pub selected: Vec<TaskingChoice>,
+    pub total_cost: f64,
+    pub information_gain_nats: f64,
+    /// Belief covariance once every selected reading is in and usable; the mean is unchanged in expectation
+    pub expected_posterior: BeliefState,
+}
+
+#[derive(Debug, Clone)]
 pub struct BeliefState {
     pub mean: DVector<f64>,
     pub covariance: DMatrix<f64>,
 }
 
-#[derive(Debug)]
+impl BeliefState {
+    /// Wide prior over `TASKING_STATE` for a growing-season field with no recent readings
+    pub fn agricultural_prior() -> Self {
+        let mean = [22.0, 60.0, 0.5, 3.0, 500.0, 0.25, 2.0, 4.0, 0.0, 0.0];
+        let std = [3.0, 15.0, 2.0, 2.0, 200.0, 0.08, 1.0, 1.5, 5.0, 100.0];
+        Self {
+            mean: DVector::from_column_slice(&mean),
+            covariance: DMatrix::from_diagonal(&DVector::from_iterator(std.len(), std.iter().map(|s| s * s))),
+        }
+    }
+
+    /// Standard deviation of each component
+    pub fn marginal_std(&self) -> DVector<f64> {
+        self.covariance.diagonal().map(|v| v.max(0.0).sqrt())
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct PredictedMeasurement {
-    pub expected_value: f64,
-    pub uncertainty: f64,
+    pub expected_value: DVector<f64>,
+    pub covariance: DMatrix<f64>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SimulatedMeasurement {
-    pub value: f64,
+    pub value: DVector<f64>,
 }
 
 // Manifold-Constrained Fusion - FROM USER'S CODE
@@ -346,11 +783,40 @@ pub struct Variable {
     pub timestamp: f64,
 }
 
+/// Levenberg-Marquardt damping beyond which the sliding-window solve gives up
+const MAX_LM_DAMPING: f64 = 1e12;
+
+/// Sliding-window factor graph smoother for multi-rate sensors
+///
+/// State variables are added per epoch and observed by pluggable `Factor`s.
+/// Once the window holds more than `optimization_window` variables the oldest
+/// are marginalised into a `MarginalizationPriorFactor`, so the information
+/// they carried is kept without growing the problem. Each solve runs
+/// Levenberg-Marquardt with IRLS robust weights on a block-sparse system.
 pub struct MultiRateSensorFusion {
     graph: FactorGraph,
     optimization_window: usize,
     convergence_threshold: f64,
     max_iterations: usize,
+    /// Relative residual tolerance of the conjugate gradient linear solve
+    linear_solver_tolerance: f64,
+}
+
+/// Result of one sliding-window solve
+#[derive(Debug, Clone)]
+pub struct SlidingWindowEstimate {
+    /// Smoothed value of every variable still inside the window
+    pub variables: HashMap<String, DVector<f64>>,
+    /// Id of the most recent variable in the window
+    pub latest_variable: Option<String>,
+    /// Marginal covariance of the most recent variable
+    pub latest_covariance: Option<DMatrix<f64>>,
+    pub final_cost: f64,
+    pub iterations: usize,
+    pub converged: bool,
+    /// Factors whose robust weight fell below 0.5, i.e. suspected faulty readings
+    pub downweighted_factors: usize,
+    pub marginalized_variables: usize,
 }
 
 impl MultiRateSensorFusion {
@@ -363,259 +829,1061 @@ impl MultiRateSensorFusion {
             optimization_window: 100,
             convergence_threshold: 1e-6,
             max_iterations: 50,
+            linear_solver_tolerance: 1e-8,
         }
     }
-    
+
+    pub fn with_window(mut self, optimization_window: usize) -> Self {
+        self.optimization_window = optimization_window.max(1);
+        self
+    }
+
+    /// Add (or re-seed) a state variable
+    pub fn add_variable(&mut self, id: impl Into<String>, timestamp: f64, initial_value: DVector<f64>) {
+        let id = id.into();
+        self.graph.variables.insert(id.clone(), Variable { id, value: initial_value, timestamp });
+    }
+
+    /// Add any factor over variables already in the graph
+    pub fn add_factor(&mut self, factor: Box<dyn Factor + Send + Sync>) {
+        self.graph.factors.push(factor);
+    }
+
     pub fn add_gps_factor(&mut self, timestamp: f64, measurement: GPSMeasurement) {
         let factor = GPSFactor::new(timestamp, measurement);
         self.graph.factors.push(Box::new(factor));
     }
-    
+
     pub fn add_atomic_clock_factor(&mut self, timestamp: f64, time_ref: f64) {
         let factor = AtomicClockFactor::new(timestamp, time_ref);
         self.graph.factors.push(Box::new(factor));
     }
-    
-    pub async fn optimize_sliding_window(&mut self) -> Result<StateEstimate, AppError> {
-        // ACTUAL Levenberg-Marquardt implementation from user's code
-        let mut current_estimate = DVector::zeros(self.graph.variables.len() * 3); // pos, vel, bias
+
+    pub async fn optimize_sliding_window(&mut self) -> Result<SlidingWindowEstimate, AppError> {
+        let marginalized_variables = self.marginalize_old_variables()?;
+
+        let order = self.variable_order();
+        if order.is_empty() {
+            return Err(AppError::OptimizationFailed("Sliding window contains no variables".to_string()));
+        }
+        let index: HashMap<String, usize> = order.iter().enumerate().map(|(i, id)| (id.clone(), i)).collect();
+
         let mut lambda = 1e-3; // LM damping parameter
-        let mut cost = f64::INFINITY;
-        
+        let mut cost = self.compute_cost(&self.graph.variables);
+        let mut converged = false;
+        let mut iterations = 0;
+
         for iteration in 0..self.max_iterations {
-            // Compute residuals for all factors
-            let mut residuals = DVector::zeros(self.graph.factors.len() * 3);
-            let mut jacobian = DMatrix::zeros(self.graph.factors.len() * 3, current_estimate.len());
-            
-            let mut residual_idx = 0;
-            for factor in &self.graph.factors {
-                let factor_residual = factor.error(&self.graph.variables);
-                let factor_jacobian = factor.jacobian(&self.graph.variables);
-                
-                // Fill residuals
-                for i in 0..factor_residual.len() {
-                    residuals[residual_idx + i] = factor_residual[i];
-                }
-                
-                // Fill Jacobian
-                for i in 0..factor_jacobian.nrows() {
-                    for j in 0..factor_jacobian.ncols() {
-                        jacobian[(residual_idx + i, j)] = factor_jacobian[(i, j)];
-                    }
-                }
-                
-                residual_idx += factor_residual.len();
-            }
-            
-            // Compute Hessian approximation: H = J^T * J
-            let hessian = &jacobian.transpose() * &jacobian;
-            let gradient = &jacobian.transpose() * &residuals;
-            
-            // Add LM damping: H_damped = H + λI
-            let damped_hessian = &hessian + &DMatrix::identity(hessian.nrows(), hessian.ncols()) * lambda;
-            
-            // Solve normal equations: (H + λI)δ = -g
-            let decomp = damped_hessian.lu();
-            let delta = match decomp.solve(&(-gradient)) {
-                Some(d) => d,
+            iterations = iteration + 1;
+            let system = self.build_system(&order, &index, &self.graph.variables);
+
+            // Solve (H + λ·diag(H))δ = -g
+            let delta = match system.solve(&-system.gradient(), lambda, self.linear_solver_tolerance, 10 * system.dimension()) {
+                Some(delta) => delta,
                 None => {
+                    // Indefinite or non-finite system: escalate damping, give up once it dominates
                     lambda *= 10.0;
+                    if lambda > MAX_LM_DAMPING {
+                        break;
+                    }
                     continue;
                 }
             };
-            
-            // Try update
-            let new_estimate = &current_estimate + &delta;
-            let new_cost = self.compute_cost(&new_estimate)?;
-            
+
+            let candidate = Self::apply_update(&self.graph.variables, &order, &system, &delta);
+            let new_cost = self.compute_cost(&candidate);
+
             if new_cost < cost {
                 // Accept update
-                current_estimate = new_estimate;
+                self.graph.variables = candidate;
+                let improvement = cost - new_cost;
                 cost = new_cost;
-                lambda /= 3.0;
-                
-                // Check convergence
-                if delta.norm() < self.convergence_threshold {
-                    return Ok(StateEstimate {
-                        position: [current_estimate[0], current_estimate[1], current_estimate[2]],
-                        velocity: [current_estimate[3], current_estimate[4], current_estimate[5]],
-                        clock_bias: current_estimate[6],
-                        uncertainty: hessian.try_inverse().unwrap_or(DMatrix::identity(hessian.nrows(), hessian.ncols())),
-                        timestamp: chrono::Utc::now(),
-                    });
+                lambda = (lambda / 3.0).max(1e-12);
+
+                if delta.norm() < self.convergence_threshold || improvement < self.convergence_threshold * cost.max(1.0) {
+                    converged = true;
+                    break;
                 }
             } else {
                 // Reject update, increase damping
                 lambda *= 10.0;
+                if lambda > MAX_LM_DAMPING {
+                    break;
+                }
             }
         }
-        
-        Err(AppError::OptimizationFailed("Failed to converge".to_string()))
+
+        let latest_variable = order.last().cloned();
+        let latest_covariance = latest_variable
+            .as_ref()
+            .and_then(|id| self.marginal_covariance(&order, &index, id));
+        let downweighted_factors = self
+            .graph
+            .factors
+            .iter()
+            .filter(|factor| linearize_factor(factor.as_ref(), &self.graph.variables).robust_weight < 0.5)
+            .count();
+
+        Ok(SlidingWindowEstimate {
+            variables: self.graph.variables.iter().map(|(id, v)| (id.clone(), v.value.clone())).collect(),
+            latest_variable,
+            latest_covariance,
+            final_cost: cost,
+            iterations,
+            converged,
+            downweighted_factors,
+            marginalized_variables,
+        })
     }
-    
-    fn compute_cost(&self, estimate: &DVector<f64>) -> Result<f64, AppError> {
-        let mut total_cost = 0.0;
-        
+
+    /// Variable ids ordered by timestamp (ties broken by id for determinism)
+    fn variable_order(&self) -> Vec<String> {
+        let mut order: Vec<&Variable> = self.graph.variables.values().collect();
+        order.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp).then_with(|| a.id.cmp(&b.id)));
+        order.into_iter().map(|v| v.id.clone()).collect()
+    }
+
+    fn build_system(
+        &self,
+        order: &[String],
+        index: &HashMap<String, usize>,
+        variables: &HashMap<String, Variable>,
+    ) -> BlockSparseSystem {
+        let dims = order.iter().map(|id| variables[id].value.len()).collect();
+        let mut system = BlockSparseSystem::new(dims);
+
         for factor in &self.graph.factors {
-            let residual = factor.error(&self.graph.variables);
-            let info_matrix = factor.information_matrix();
-            let cost = residual.transpose() * info_matrix * residual;
-            total_cost += cost[(0, 0)];
+            let connected: Option<Vec<usize>> = factor
+                .get_connected_variables()
+                .iter()
+                .map(|id| index.get(id).copied())
+                .collect();
+            if let Some(indices) = connected {
+                let linearized = linearize_factor(factor.as_ref(), variables);
+                system.add_factor(&indices, &linearized);
+            }
         }
-        
-        Ok(total_cost)
+
+        system
+    }
+
+    fn apply_update(
+        variables: &HashMap<String, Variable>,
+        order: &[String],
+        system: &BlockSparseSystem,
+        delta: &DVector<f64>,
+    ) -> HashMap<String, Variable> {
+        let mut updated = variables.clone();
+        for (i, id) in order.iter().enumerate() {
+            if let Some(variable) = updated.get_mut(id) {
+                let dim = variable.value.len();
+                variable.value += delta.rows(system.offset(i), dim);
+            }
+        }
+        updated
+    }
+
+    /// Marginal covariance of one variable: the matching block of H⁻¹
+    fn marginal_covariance(&self, order: &[String], index: &HashMap<String, usize>, id: &str) -> Option<DMatrix<f64>> {
+        let system = self.build_system(order, index, &self.graph.variables);
+        let variable = *index.get(id)?;
+        let offset = system.offset(variable);
+        let dim = self.graph.variables.get(id)?.value.len();
+
+        let mut covariance = DMatrix::zeros(dim, dim);
+        for column in 0..dim {
+            let mut unit = DVector::zeros(system.dimension());
+            unit[offset + column] = 1.0;
+            let solution = system.solve(&unit, 0.0, self.linear_solver_tolerance, 10 * system.dimension())?;
+            covariance.set_column(column, &solution.rows(offset, dim));
+        }
+        Some(covariance)
     }
-}
 
-// Particle Flow Multi-Sensor Fusion - ACTUAL IMPLEMENTATION FROM USER'S CODE
+    /// Remove the oldest variables beyond the window via the Schur complement
+    fn marginalize_old_variables(&mut self) -> Result<usize, AppError> {
+        let order = self.variable_order();
+        if order.len() <= self.optimization_window {
+            return Ok(0);
+        }
+
+        let marginalized: Vec<String> = order[..order.len() - self.optimization_window].to_vec();
+        let marginalized_set: std::collections::HashSet<&String> = marginalized.iter().collect();
+
+        // A factor that reaches a variable outside the graph cannot be folded into
+        // the prior; refuse rather than silently losing its information
+        if let Some(missing) = self
+            .graph
+            .factors
+            .iter()
+            .filter(|factor| factor.get_connected_variables().iter().any(|id| marginalized_set.contains(id)))
+            .flat_map(|factor| factor.get_connected_variables())
+            .find(|id| !self.graph.variables.contains_key(id))
+        {
+            return Err(AppError::OptimizationFailed(format!(
+                "Cannot marginalise the oldest window variables: a factor on them references unknown variable '{}'",
+                missing
+            )));
+        }
+
+        // Factors touching a marginalised variable, and the Markov blanket they reach
+        let (touching, remaining): (Vec<_>, Vec<_>) = std::mem::take(&mut self.graph.factors)
+            .into_iter()
+            .partition(|factor| factor.get_connected_variables().iter().any(|id| marginalized_set.contains(id)));
+        self.graph.factors = remaining;
+
+        let mut blanket: Vec<String> = Vec::new();
+        for factor in &touching {
+            for id in factor.get_connected_variables() {
+                if !marginalized_set.contains(&id) && !blanket.contains(&id) {
+                    blanket.push(id);
+                }
+            }
+        }
+
+        if !blanket.is_empty() {
+            // Dense local system over [marginalised, blanket]
+            let local_order: Vec<String> = marginalized.iter().cloned().chain(blanket.iter().cloned()).collect();
+            let local_index: HashMap<String, usize> =
+                local_order.iter().enumerate().map(|(i, id)| (id.clone(), i)).collect();
+            let dims: Vec<usize> = local_order.iter().map(|id| self.graph.variables[id].value.len()).collect();
+            let mut local = BlockSparseSystem::new(dims.clone());
+            for factor in &touching {
+                let indices: Vec<usize> = factor.get_connected_variables().iter().map(|id| local_index[id]).collect();
+                local.add_factor(&indices, &linearize_factor(factor.as_ref(), &self.graph.variables));
+            }
+
+            let m: usize = dims[..marginalized.len()].iter().sum();
+            let n = local.dimension();
+            let hessian = local.to_dense();
+            let gradient = local.gradient().clone();
+
+            let h_mm = hessian.view((0, 0), (m, m)).into_owned();
+            let h_rm = hessian.view((m, 0), (n - m, m)).into_owned();
+            let h_rr = hessian.view((m, m), (n - m, n - m)).into_owned();
+            let h_mm_inv = h_mm
+                .pseudo_inverse(1e-9)
+                .map_err(|e| AppError::OptimizationFailed(format!("Marginalisation failed: {}", e)))?;
+
+            let schur_information = &h_rr - &h_rm * &h_mm_inv * h_rm.transpose();
+            let schur_gradient = gradient.rows(m, n - m) - &h_rm * &h_mm_inv * gradient.rows(0, m);
+            // Symmetrise to suppress round-off before it is reused as an information matrix
+            let schur_information = 0.5 * (&schur_information + schur_information.transpose());
+
+            let linearization_point = DVector::from_iterator(
+                n - m,
+                blanket.iter().flat_map(|id| self.graph.variables[id].value.iter().copied().collect::<Vec<_>>()),
+            );
+            self.graph.factors.push(Box::new(MarginalizationPriorFactor::new(
+                blanket.clone(),
+                dims[marginalized.len()..].to_vec(),
+                linearization_point,
+                schur_information,
+                schur_gradient,
+            )));
+        }
+
+        for id in &marginalized {
+            self.graph.variables.remove(id);
+        }
+
+        Ok(marginalized.len())
+    }
+
+    fn compute_cost(&self, variables: &HashMap<String, Variable>) -> f64 {
+        self.graph
+            .factors
+            .iter()
+            .map(|factor| linearize_factor(factor.as_ref(), variables).robust_cost)
+            .sum()
+    }
+}
+
+// Particle Flow Multi-Sensor Fusion - PF-PF with exact Daum–Huang flow
+/// Particle flow particle filter (PF-PF) with the exact Daum–Huang flow
+///
+/// Particles are migrated from the prior to the posterior along the flow
+/// dx/dλ = A(λ)x + b(λ) instead of being reweighted directly. The flow is
+/// re-linearised at the ensemble mean every pseudo-time step (EDH). The flow
+/// is an invertible affine map, so particles are reweighted by the ratio of
+/// the Gaussian predictive density N(x̄, P) the flow was built from before and
+/// after migration (the Jacobian determinant is shared and cancels). Linear
+/// observations leave the weights uniform; linearisation error in strongly
+/// nonlinear ones shows up as weight spread, monitored through the effective
+/// sample size and systematically resampled below `resampling_threshold`.
+#[derive(Clone)]
 pub struct ParticleFlowFilter {
     particles: Vec<Particle>,
     flow_integrator: ODEIntegrator,
     num_particles: usize,
+    model: Arc<dyn ParticleFlowModel>,
+    /// Resample when ESS falls below this fraction of the particle count
+    resampling_threshold: f64,
+    diagnostics: FlowDiagnostics,
+    rng: StdRng,
 }
 
 #[derive(Debug, Clone)]
 pub struct Particle {
-    pub state: DVector<f64>, // position + velocity
+    pub state: DVector<f64>,
     pub weight: f64,
 }
 
+/// Resampling and weight diagnostics of the last update
+#[derive(Debug, Clone, Default)]
+pub struct FlowDiagnostics {
+    /// ESS after the weight update, before any resampling
+    pub effective_sample_size: f64,
+    pub resampled: bool,
+    pub resample_count: usize,
+    pub update_count: usize,
+}
+
+/// Process and observation model tracked by the `ParticleFlowFilter`
+pub trait ParticleFlowModel: Send + Sync {
+    fn state_dimension(&self) -> usize;
+
+    /// Names of the state components, in order
+    fn state_labels(&self) -> Vec<String>;
+
+    /// Deterministic one-step transition x_k = f(x_{k-1}, u_k)
+    fn propagate(&self, state: &DVector<f64>, forcing: &HashMap<String, f64>) -> DVector<f64>;
+
+    /// Additive Gaussian process noise covariance Q for one step
+    fn process_noise(&self) -> DMatrix<f64>;
+
+    /// Predicted observation h(x)
+    fn observe(&self, state: &DVector<f64>, observation: &FlowObservation) -> DVector<f64>;
+
+    /// ∂h/∂x, central differences unless the model knows better
+    fn observation_jacobian(&self, state: &DVector<f64>, observation: &FlowObservation) -> DMatrix<f64> {
+        numerical_jacobian(|x| self.observe(x, observation), state)
+    }
+
+    /// Project a state back onto its physically valid range
+    fn constrain(&self, _state: &mut DVector<f64>) {}
+
+    /// Convert a fusion measurement into an observation this model understands
+    fn observation_from_measurement(
+        &self,
+        _sensor_type: SensorType,
+        _measurement: &TimestampedMeasurement,
+    ) -> Option<FlowObservation> {
+        None
+    }
+
+    /// Map the state mean onto the fused agricultural conditions
+    fn agricultural_conditions(&self, _mean: &DVector<f64>) -> AgriculturalConditions {
+        AgriculturalConditions::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowObservationKind {
+    /// In-situ volumetric soil moisture probe (m³/m³)
+    SoilMoistureProbe,
+    /// NDVI from satellite, drone or phenocam
+    Ndvi,
+}
+
+#[derive(Debug, Clone)]
+pub struct FlowObservation {
+    pub kind: FlowObservationKind,
+    pub value: DVector<f64>,
+    pub noise_covariance: DMatrix<f64>,
+}
+
+impl FlowObservation {
+    pub fn scalar(kind: FlowObservationKind, value: f64, sigma: f64) -> Self {
+        Self {
+            kind,
+            value: DVector::from_element(1, value),
+            noise_covariance: DMatrix::from_element(1, 1, sigma * sigma),
+        }
+    }
+}
+
+/// Central-difference Jacobian of a vector function
+pub fn numerical_jacobian<F>(f: F, x: &DVector<f64>) -> DMatrix<f64>
+where
+    F: Fn(&DVector<f64>) -> DVector<f64>,
+{
+    let f0 = f(x);
+    let mut jacobian = DMatrix::zeros(f0.len(), x.len());
+    for j in 0..x.len() {
+        let h = 1e-6 * x[j].abs().max(1.0);
+        let mut forward = x.clone();
+        let mut backward = x.clone();
+        forward[j] += h;
+        backward[j] -= h;
+        jacobian.set_column(j, &((f(&forward) - f(&backward)) / (2.0 * h)));
+    }
+    jacobian
+}
+
+/// Pseudo-time integrator for the flow over λ ∈ [0, 1]
+#[derive(Debug, Clone)]
 pub struct ODEIntegrator {
-    step_size: f64,
+    /// Step sizes ε_j, summing to one
+    steps: Vec<f64>,
 }
 
 impl ODEIntegrator {
+    /// Uniform steps of the given size
     pub fn new(step_size: f64) -> Self {
-        Self { step_size }
+        let count = (1.0 / step_size.clamp(1e-4, 1.0)).round().max(1.0) as usize;
+        Self { steps: vec![1.0 / count as f64; count] }
     }
-    
+
+    /// Exponentially growing steps ε_j = ε_1 q^{j-1}; small early steps where
+    /// the flow is stiffest, as recommended for the Daum–Huang filters
+    pub fn exponential(num_steps: usize, ratio: f64) -> Self {
+        let n = num_steps.max(1);
+        let first = if (ratio - 1.0).abs() < 1e-12 {
+            1.0 / n as f64
+        } else {
+            (1.0 - ratio) / (1.0 - ratio.powi(n as i32))
+        };
+        Self { steps: (0..n).map(|j| first * ratio.powi(j as i32)).collect() }
+    }
+
+    /// Pseudo-time step sizes, in order
+    pub fn steps(&self) -> &[f64] {
+        &self.steps
+    }
+
+    /// Euler integration of a single state through a fixed flow field
     pub fn integrate(&self, initial_state: &DVector<f64>, flow_field: &FlowField, start_lambda: f64, end_lambda: f64) -> DVector<f64> {
         let mut current_state = initial_state.clone();
         let mut lambda = start_lambda;
-        
-        while lambda < end_lambda {
-            let flow_vector = flow_field.compute_flow(&current_state, lambda);
-            current_state += self.step_size * flow_vector;
-            lambda += self.step_size;
+        let span = end_lambda - start_lambda;
+
+        for step in &self.steps {
+            let epsilon = step * span;
+            current_state += epsilon * flow_field.compute_flow(&current_state, lambda + 0.5 * epsilon);
+            lambda += epsilon;
         }
-        
+
         current_state
     }
 }
 
+/// Exact Daum–Huang flow for a Gaussian prior and a locally linear measurement
+///
+/// A(λ) = -½ P Hᵀ (λ H P Hᵀ + R)⁻¹ H
+/// b(λ) = (I + 2λA)[(I + λA) P Hᵀ R⁻¹ (z - e) + A x̄]
+/// where e = h(η̄) - H η̄ is the offset of the linearisation at η̄.
+#[derive(Debug, Clone)]
 pub struct FlowField {
-    pub gradient_log_likelihood: DVector<f64>,
-    pub ensemble_average: DVector<f64>,
+    pub prior_mean: DVector<f64>,
+    pub prior_covariance: DMatrix<f64>,
+    pub observation_jacobian: DMatrix<f64>,
+    pub observation_noise: DMatrix<f64>,
+    /// z - e
+    pub linearised_observation: DVector<f64>,
 }
 
 impl FlowField {
-    pub fn compute_flow(&self, _state: &DVector<f64>, _lambda: f64) -> DVector<f64> {
-        &self.gradient_log_likelihood - &self.ensemble_average
+    /// Flow coefficients A(λ), b(λ)
+    pub fn coefficients(&self, lambda: f64) -> Option<(DMatrix<f64>, DVector<f64>)> {
+        let p = &self.prior_covariance;
+        let h = &self.observation_jacobian;
+        let n = p.nrows();
+        let identity = DMatrix::<f64>::identity(n, n);
+
+        let innovation_covariance = lambda * h * p * h.transpose() + &self.observation_noise;
+        let a = -0.5 * p * h.transpose() * innovation_covariance.try_inverse()? * h;
+        let r_inverse = self.observation_noise.clone().try_inverse()?;
+        let b = (&identity + 2.0 * lambda * &a)
+            * ((&identity + lambda * &a) * p * h.transpose() * r_inverse * &self.linearised_observation
+                + &a * &self.prior_mean);
+
+        Some((a, b))
+    }
+
+    pub fn compute_flow(&self, state: &DVector<f64>, lambda: f64) -> DVector<f64> {
+        match self.coefficients(lambda) {
+            Some((a, b)) => a * state + b,
+            None => DVector::zeros(state.len()),
+        }
     }
 }
 
 impl ParticleFlowFilter {
-    pub fn new(num_particles: usize) -> Self {
-        let mut particles = Vec::new();
-        for _ in 0..num_particles {
-            particles.push(Particle {
-                state: DVector::zeros(6), // position + velocity
-                weight: 1.0 / num_particles as f64,
-            });
+    pub fn new(
+        model: Arc<dyn ParticleFlowModel>,
+        num_particles: usize,
+        initial_mean: &DVector<f64>,
+        initial_covariance: &DMatrix<f64>,
+    ) -> Result<Self, AppError> {
+        Self::with_rng(model, num_particles, initial_mean, initial_covariance, StdRng::from_entropy())
+    }
+
+    /// Deterministic filter for reproducible runs
+    pub fn with_seed(
+        model: Arc<dyn ParticleFlowModel>,
+        num_particles: usize,
+        initial_mean: &DVector<f64>,
+        initial_covariance: &DMatrix<f64>,
+        seed: u64,
+    ) -> Result<Self, AppError> {
+        Self::with_rng(model, num_particles, initial_mean, initial_covariance, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(
+        model: Arc<dyn ParticleFlowModel>,
+        num_particles: usize,
+        initial_mean: &DVector<f64>,
+        initial_covariance: &DMatrix<f64>,
+        mut rng: StdRng,
+    ) -> Result<Self, AppError> {
+        let num_particles = num_particles.max(1);
+        let dimension = model.state_dimension();
+        if initial_mean.len() != dimension || initial_covariance.shape() != (dimension, dimension) {
+            return Err(AppError::validation("Initial particle distribution does not match the model state"));
         }
-        
-        Self {
+
+        let factor = Self::cholesky_factor(initial_covariance)?;
+        let particles = (0..num_particles)
+            .map(|_| {
+                let mut state = initial_mean + &factor * Self::standard_normal(dimension, &mut rng);
+                model.constrain(&mut state);
+                Particle { state, weight: 1.0 / num_particles as f64 }
+            })
+            .collect();
+
+        Ok(Self {
             particles,
-            flow_integrator: ODEIntegrator::new(0.01),
+            flow_integrator: ODEIntegrator::exponential(29, 1.2),
             num_particles,
+            model,
+            resampling_threshold: 0.5,
+            diagnostics: FlowDiagnostics::default(),
+            rng,
+        })
+    }
+
+    pub fn with_resampling_threshold(mut self, threshold: f64) -> Self {
+        self.resampling_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    pub fn diagnostics(&self) -> &FlowDiagnostics {
+        &self.diagnostics
+    }
+
+    /// Propagate every particle through the process model
+    pub fn predict(&mut self, forcing: &HashMap<String, f64>) -> Result<(), AppError> {
+        let dimension = self.model.state_dimension();
+        let noise_factor = Self::cholesky_factor(&self.model.process_noise())?;
+
+        for particle in &mut self.particles {
+            let mean = self.model.propagate(&particle.state, forcing);
+            particle.state = mean + &noise_factor * Self::standard_normal(dimension, &mut self.rng);
         }
+
+        Ok(())
     }
-    
+
+    /// Migrate the particles with the exact flow and update their weights
     pub async fn update_with_measurement(&mut self, measurement: &SensorMeasurementBundle) -> Result<(), AppError> {
-        // ACTUAL particle flow implementation from user's code
-        // Continuous particle migration instead of resampling
-        for particle in &mut self.particles {
-            // Solve ODE: dx/dλ = ∇log p(z|x) - E[∇log p(z|x)]
-            let mut flow_vector = DVector::zeros(6); // position + velocity
-            
-            for (sensor_type, measurements) in &measurement.measurements {
-                for meas in measurements {
-                    let likelihood_grad = self.compute_likelihood_gradient(
-                        &particle.state,
-                        meas,
-                        *sensor_type
-                    ).await?;
-                    flow_vector += likelihood_grad;
-                }
-            }
-            
-            // Subtract ensemble average to get flow direction
-            let ensemble_avg = self.compute_ensemble_average_flow(&flow_vector);
-            let net_flow = flow_vector - ensemble_avg;
-            
-            // Create flow field
-            let flow_field = FlowField {
-                gradient_log_likelihood: flow_vector,
-                ensemble_average: ensemble_avg,
+        let observations: Vec<FlowObservation> = measurement
+            .measurements
+            .iter()
+            .flat_map(|(sensor_type, readings)| {
+                readings
+                    .iter()
+                    .filter_map(|reading| self.model.observation_from_measurement(*sensor_type, reading))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        if observations.is_empty() {
+            return Ok(());
+        }
+        self.update(&observations)
+    }
+
+    pub fn update(&mut self, observations: &[FlowObservation]) -> Result<(), AppError> {
+        if observations.is_empty() {
+            return Ok(());
+        }
+
+        let z = Self::stack_vectors(observations.iter().map(|o| o.value.clone()));
+        let r = Self::block_diagonal(observations.iter().map(|o| &o.noise_covariance));
+        let r_inverse = r.clone().try_inverse()
+            .ok_or_else(|| AppError::validation("Observation noise covariance is singular"))?;
+        let r_log_det = r.determinant().ln();
+
+        let prior_states: Vec<DVector<f64>> = self.particles.iter().map(|p| p.state.clone()).collect();
+        let (prior_mean, mut prior_covariance) = self.weighted_moments();
+        let dimension = prior_mean.len();
+        prior_covariance += DMatrix::identity(dimension, dimension) * 1e-9;
+
+        // Migrate particles and the auxiliary mean through pseudo-time
+        let mut flow_mean = prior_mean.clone();
+        let mut lambda = 0.0;
+        for &epsilon in self.flow_integrator.steps() {
+            let h = Self::stack_rows(observations.iter().map(|o| self.model.observation_jacobian(&flow_mean, o)));
+            let predicted = Self::stack_vectors(observations.iter().map(|o| self.model.observe(&flow_mean, o)));
+            let offset = predicted - &h * &flow_mean;
+
+            let field = FlowField {
+                prior_mean: prior_mean.clone(),
+                prior_covariance: prior_covariance.clone(),
+                observation_jacobian: h,
+                observation_noise: r.clone(),
+                linearised_observation: &z - offset,
             };
-            
-            // Integrate ODE: dx/dλ = ∇log p(z|x) - E[∇log p(z|x)]
-            particle.state = self.flow_integrator.integrate(
-                &particle.state,
-                &flow_field,
-                0.0, 1.0  // λ from 0 to 1
+            // Coefficients at the middle of the pseudo-time step
+            let (a, b) = field
+                .coefficients(lambda + 0.5 * epsilon)
+                .ok_or_else(|| AppError::validation("Particle flow innovation covariance is singular"))?;
+            lambda += epsilon;
+
+            flow_mean += epsilon * (&a * &flow_mean + &b);
+            for particle in &mut self.particles {
+                particle.state += epsilon * (&a * &particle.state + &b);
+            }
+        }
+
+        // Invertible-mapping weights: w ∝ w · p(z|η₁) N(η₁; x̄, P) / N(η₀; x̄, P)
+        let p_inverse = prior_covariance
+            .clone()
+            .try_inverse()
+            .ok_or_else(|| AppError::validation("Particle prior covariance is singular"))?;
+        let mut log_weights = Vec::with_capacity(self.particles.len());
+        for (particle, prior_state) in self.particles.iter().zip(&prior_states) {
+            let predicted = Self::stack_vectors(observations.iter().map(|o| self.model.observe(&particle.state, o)));
+            let after = &particle.state - &prior_mean;
+            let before = prior_state - &prior_mean;
+
+            log_weights.push(
+                particle.weight.max(1e-300).ln()
+                    + Self::log_gaussian(&(&z - predicted), &r_inverse, r_log_det)
+                    - 0.5 * (after.dot(&(&p_inverse * &after)) - before.dot(&(&p_inverse * &before))),
             );
-            
-            // No resampling needed - particles naturally flow to high-likelihood regions
-            // weights remain uniform
+        }
+
+        let max_log_weight = log_weights.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        if !max_log_weight.is_finite() {
+            return Err(AppError::validation("Particle weights collapsed"));
+        }
+        let weights: Vec<f64> = log_weights.iter().map(|lw| (lw - max_log_weight).exp()).collect();
+        let total: f64 = weights.iter().sum();
+        for (particle, weight) in self.particles.iter_mut().zip(weights) {
+            particle.weight = weight / total;
+            self.model.constrain(&mut particle.state);
+        }
+
+        let ess = self.effective_sample_size();
+        self.diagnostics.effective_sample_size = ess;
+        self.diagnostics.update_count += 1;
+        self.diagnostics.resampled = ess < self.resampling_threshold * self.num_particles as f64;
+        if self.diagnostics.resampled {
+            self.systematic_resample();
+            self.diagnostics.resample_count += 1;
         }
 
         Ok(())
     }
-    
-    async fn compute_likelihood_gradient(&self, state: &DVector<f64>, measurement: &TimestampedMeasurement, sensor_type: SensorType) -> Result<DVector<f64>, AppError> {
-        // Compute ∇log p(z|x) for specific sensor measurement
-        let mut gradient = DVector::zeros(6);
-        
-        match sensor_type {
-            SensorType::GPS => {
-                // GPS likelihood gradient: ∇log p(z_gps|x) = R^-1 * (z - h(x))
-                let predicted = state.rows(0, 3).into_owned(); // position part
-                let innovation = measurement.value - predicted[0]; // simplified
-                gradient[0] = innovation / 1.0; // R^-1 term
+
+    /// ESS = 1 / Σ wᵢ²
+    pub fn effective_sample_size(&self) -> f64 {
+        1.0 / self.particles.iter().map(|p| p.weight * p.weight).sum::<f64>().max(1e-300)
+    }
+
+    /// Weighted mean and covariance of the particle cloud
+    pub fn estimate(&self) -> (DVector<f64>, DMatrix<f64>) {
+        self.weighted_moments()
+    }
+
+    fn weighted_moments(&self) -> (DVector<f64>, DMatrix<f64>) {
+        let dimension = self.model.state_dimension();
+        let mut mean = DVector::zeros(dimension);
+        for particle in &self.particles {
+            mean += particle.weight * &particle.state;
+        }
+        let mut covariance = DMatrix::zeros(dimension, dimension);
+        for particle in &self.particles {
+            let deviation = &particle.state - &mean;
+            covariance += particle.weight * &deviation * deviation.transpose();
+        }
+        (mean, covariance)
+    }
+
+    fn systematic_resample(&mut self) {
+        let n = self.num_particles;
+        let start: f64 = self.rng.gen::<f64>() / n as f64;
+        let mut resampled = Vec::with_capacity(n);
+        let mut cumulative = self.particles[0].weight;
+        let mut index = 0;
+
+        for k in 0..n {
+            let target = start + k as f64 / n as f64;
+            while cumulative < target && index + 1 < self.particles.len() {
+                index += 1;
+                cumulative += self.particles[index].weight;
+            }
+            resampled.push(Particle { state: self.particles[index].state.clone(), weight: 1.0 / n as f64 });
+        }
+
+        self.particles = resampled;
+    }
+
+    fn log_gaussian(residual: &DVector<f64>, inverse_covariance: &DMatrix<f64>, log_det: f64) -> f64 {
+        -0.5 * (residual.dot(&(inverse_covariance * residual))
+            + log_det
+            + residual.len() as f64 * (2.0 * std::f64::consts::PI).ln())
+    }
+
+    fn cholesky_factor(covariance: &DMatrix<f64>) -> Result<DMatrix<f64>, AppError> {
+        let n = covariance.nrows();
+        (covariance + DMatrix::identity(n, n) * 1e-12)
+            .cholesky()
+            .map(|c| c.l())
+            .ok_or_else(|| AppError::validation("Covariance matrix is not positive definite"))
+    }
+
+    fn standard_normal(dimension: usize, rng: &mut StdRng) -> DVector<f64> {
+        DVector::from_fn(dimension, |_, _| StandardNormal.sample(rng))
+    }
+
+    fn stack_vectors(vectors: impl Iterator<Item = DVector<f64>>) -> DVector<f64> {
+        let values: Vec<f64> = vectors.flat_map(|v| v.iter().copied().collect::<Vec<_>>()).collect();
+        DVector::from_vec(values)
+    }
+
+    fn stack_rows(matrices: impl Iterator<Item = DMatrix<f64>>) -> DMatrix<f64> {
+        let matrices: Vec<DMatrix<f64>> = matrices.collect();
+        let rows = matrices.iter().map(|m| m.nrows()).sum();
+        let cols = matrices.first().map_or(0, |m| m.ncols());
+        let mut stacked = DMatrix::zeros(rows, cols);
+        let mut row = 0;
+        for m in &matrices {
+            stacked.view_mut((row, 0), (m.nrows(), cols)).copy_from(m);
+            row += m.nrows();
+        }
+        stacked
+    }
+
+    fn block_diagonal<'a>(matrices: impl Iterator<Item = &'a DMatrix<f64>>) -> DMatrix<f64> {
+        let matrices: Vec<&DMatrix<f64>> = matrices.collect();
+        let n = matrices.iter().map(|m| m.nrows()).sum();
+        let mut block = DMatrix::zeros(n, n);
+        let mut offset = 0;
+        for m in matrices {
+            block.view_mut((offset, offset), (m.nrows(), m.ncols())).copy_from(m);
+            offset += m.nrows();
+        }
+        block
+    }
+}
+
+/// `FusionAlgorithm` adapter that keeps one `ParticleFlowFilter` alive across
+/// fusion calls, so every bundle updates the posterior the previous one left
+pub struct ParticleFlowFusion {
+    filter: Mutex<ParticleFlowFilter>,
+}
+
+impl ParticleFlowFusion {
+    pub fn new(filter: ParticleFlowFilter) -> Self {
+        Self { filter: Mutex::new(filter) }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ParticleFlowFilter> {
+        self.filter.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Propagate the shared particles through the process model between fusions
+    pub fn predict(&self, forcing: &HashMap<String, f64>) -> Result<(), AppError> {
+        self.lock().predict(forcing)
+    }
+
+    /// Current posterior mean and covariance
+    pub fn estimate(&self) -> (DVector<f64>, DMatrix<f64>) {
+        self.lock().estimate()
+    }
+
+    pub fn diagnostics(&self) -> FlowDiagnostics {
+        self.lock().diagnostics().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl FusionAlgorithm for ParticleFlowFusion {
+    /// One flow update of the shared filter, applied in place
+    async fn fuse_measurements(
+        &self,
+        measurements: &SensorMeasurementBundle,
+        aligned_data: &AlignedSensorData,
+    ) -> Result<FusionResult, AppError> {
+        let start_time = std::time::Instant::now();
+        let mut filter = self.lock();
+
+        let source = if aligned_data.aligned_measurements.is_empty() {
+            &measurements.measurements
+        } else {
+            &aligned_data.aligned_measurements
+        };
+        let mut sensor_counts: HashMap<SensorType, f64> = HashMap::new();
+        let mut observations = Vec::new();
+        for (sensor_type, readings) in source {
+            for reading in readings {
+                if let Some(observation) = filter.model.observation_from_measurement(*sensor_type, reading) {
+                    *sensor_counts.entry(*sensor_type).or_insert(0.0) += 1.0;
+                    observations.push(observation);
+                }
+            }
+        }
+        filter.update(&observations)?;
+
+        let (mean, covariance) = filter.estimate();
+        let labels = filter.model.state_labels();
+        let agricultural_uncertainty = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.clone(), covariance[(i, i)].max(0.0).sqrt()))
+            .collect();
+        let ess_fraction = filter.diagnostics.effective_sample_size / filter.num_particles as f64;
+        let total_observations: f64 = sensor_counts.values().sum();
+
+        let mut algorithm_specific_data = HashMap::new();
+        algorithm_specific_data.insert("effective_sample_size".to_string(), filter.diagnostics.effective_sample_size);
+        algorithm_specific_data.insert("resampled".to_string(), if filter.diagnostics.resampled { 1.0 } else { 0.0 });
+        algorithm_specific_data.insert("particles".to_string(), filter.num_particles as f64);
+
+        Ok(FusionResult {
+            fused_state: FusedState {
+                timestamp: measurements.temporal_window.1,
+                agricultural_conditions: filter.model.agricultural_conditions(&mean),
+                ..Default::default()
             },
-            SensorType::AtomicClock => {
-                // Clock bias gradient
-                gradient[5] = (measurement.value - state[5]) / 1e-9; // nanosecond precision
+            confidence_metrics: ConfidenceMetrics {
+                overall_confidence: ess_fraction.min(1.0),
+                algorithmic_confidence: ess_fraction.min(1.0),
+                ..Default::default()
             },
-            _ => {
-                // Generic sensor gradient
-                gradient[0] = (measurement.value - state[0]) / 1.0;
-            }
+            algorithm_used: AlgorithmType::ParticleFlow,
+            temporal_alignment_quality: aligned_data.alignment_quality_metrics.clone(),
+            sensor_contributions: sensor_counts
+                .into_iter()
+                .map(|(sensor, count)| (sensor, count / total_observations.max(1.0)))
+                .collect(),
+            uncertainty_estimates: UncertaintyEstimates {
+                agricultural_uncertainty,
+                ..Default::default()
+            },
+            optimization_trace: OptimizationTrace {
+                iterations: filter.flow_integrator.steps().len(),
+                final_cost: 0.0,
+                convergence_achieved: true,
+                algorithm_specific_data,
+            },
+            processing_time: start_time.elapsed(),
+            agricultural_insights: AgriculturalInsights::default(),
+            sensor_health: Default::default(),
+        })
+    }
+
+    fn get_algorithm_type(&self) -> AlgorithmType {
+        AlgorithmType::ParticleFlow
+    }
+
+    fn get_computational_complexity(&self) -> ComputationalComplexity {
+        ComputationalComplexity::High
+    }
+
+    fn supports_real_time(&self) -> bool {
+        true
+    }
+}
+
+/// Root-zone soil moisture and crop biomass model for particle flow tracking
+///
+/// State is [θ (m³/m³), B (t/ha above-ground biomass)], stepped daily. Water
+/// follows a bucket balance with stress-limited crop ET and drainage above
+/// field capacity; biomass grows by radiation use efficiency, limited by the
+/// same water stress. NDVI is observed through a saturating Beer–Lambert
+/// canopy response, which makes the biomass update strongly nonlinear.
+///
+/// Forcing keys: `rainfall_mm`, `irrigation_mm`, `reference_et_mm`, `par_mj_m2`.
+#[derive(Debug, Clone)]
+pub struct SoilCropModel {
+    pub field_capacity: f64,
+    pub wilting_point: f64,
+    pub saturation: f64,
+    pub root_depth_mm: f64,
+    /// Fraction of available water depleted before stress starts
+    pub depletion_fraction: f64,
+    /// Fraction of water above field capacity drained per day
+    pub drainage_rate: f64,
+    /// Biomass per unit intercepted PAR (t/ha per MJ/m²)
+    pub radiation_use_efficiency: f64,
+    /// Leaf area index per t/ha of biomass
+    pub specific_leaf_area: f64,
+    pub extinction_coefficient: f64,
+    pub kc_bare: f64,
+    pub kc_full_cover: f64,
+    pub ndvi_soil: f64,
+    pub ndvi_max: f64,
+    pub moisture_process_std: f64,
+    pub biomass_process_std: f64,
+}
+
+impl Default for SoilCropModel {
+    fn default() -> Self {
+        Self {
+            field_capacity: 0.32,
+            wilting_point: 0.12,
+            saturation: 0.45,
+            root_depth_mm: 600.0,
+            depletion_fraction: 0.5,
+            drainage_rate: 0.4,
+            radiation_use_efficiency: 0.03,
+            specific_leaf_area: 0.6,
+            extinction_coefficient: 0.6,
+            kc_bare: 0.3,
+            kc_full_cover: 1.15,
+            ndvi_soil: 0.15,
+            ndvi_max: 0.9,
+            moisture_process_std: 0.005,
+            biomass_process_std: 0.05,
         }
-        
-        Ok(gradient)
     }
-    
-    fn compute_ensemble_average_flow(&self, _individual_flow: &DVector<f64>) -> DVector<f64> {
-        // Compute E[∇log p(z|x)] across all particles
-        let mut average_flow = DVector::zeros(6);
-        
-        for particle in &self.particles {
-            // This would normally compute flow for each particle and average
-            // Simplified for now
-            average_flow += &particle.state * particle.weight;
+}
+
+impl SoilCropModel {
+    fn canopy_cover(&self, biomass: f64) -> f64 {
+        1.0 - (-self.extinction_coefficient * self.specific_leaf_area * biomass.max(0.0)).exp()
+    }
+
+    fn water_stress(&self, moisture: f64) -> f64 {
+        let threshold = self.wilting_point + (1.0 - self.depletion_fraction) * (self.field_capacity - self.wilting_point);
+        ((moisture - self.wilting_point) / (threshold - self.wilting_point)).clamp(0.0, 1.0)
+    }
+
+    fn forcing(forcing: &HashMap<String, f64>, key: &str) -> f64 {
+        forcing.get(key).copied().unwrap_or(0.0).max(0.0)
+    }
+}
+
+impl ParticleFlowModel for SoilCropModel {
+    fn state_dimension(&self) -> usize {
+        2
+    }
+
+    fn state_labels(&self) -> Vec<String> {
+        vec!["soil_moisture".to_string(), "biomass".to_string()]
+    }
+
+    fn propagate(&self, state: &DVector<f64>, forcing: &HashMap<String, f64>) -> DVector<f64> {
+        let (moisture, biomass) = (state[0], state[1].max(0.0));
+        let cover = self.canopy_cover(biomass);
+        let stress = self.water_stress(moisture);
+
+        let crop_coefficient = self.kc_bare + (self.kc_full_cover - self.kc_bare) * cover;
+        let evapotranspiration = crop_coefficient * Self::forcing(forcing, "reference_et_mm") * stress;
+        let drainage = self.drainage_rate * (moisture - self.field_capacity).max(0.0) * self.root_depth_mm;
+        let water_in = 0.8 * Self::forcing(forcing, "rainfall_mm") + Self::forcing(forcing, "irrigation_mm");
+
+        let next_moisture = moisture + (water_in - evapotranspiration - drainage) / self.root_depth_mm;
+        let growth = self.radiation_use_efficiency * Self::forcing(forcing, "par_mj_m2") * cover.max(0.02) * stress;
+
+        DVector::from_vec(vec![
+            next_moisture.clamp(0.0, self.saturation),
+            biomass + growth,
+        ])
+    }
+
+    fn process_noise(&self) -> DMatrix<f64> {
+        DMatrix::from_diagonal(&DVector::from_vec(vec![
+            self.moisture_process_std.powi(2),
+            self.biomass_process_std.powi(2),
+        ]))
+    }
+
+    fn observe(&self, state: &DVector<f64>, observation: &FlowObservation) -> DVector<f64> {
+        match observation.kind {
+            FlowObservationKind::SoilMoistureProbe => DVector::from_element(1, state[0]),
+            FlowObservationKind::Ndvi => {
+                let lai = self.specific_leaf_area * state[1].max(0.0);
+                DVector::from_element(1, self.ndvi_max - (self.ndvi_max - self.ndvi_soil) * (-0.7 * lai).exp())
+            },
+        }
+    }
+
+    fn observation_jacobian(&self, state: &DVector<f64>, observation: &FlowObservation) -> DMatrix<f64> {
+        match observation.kind {
+            FlowObservationKind::SoilMoistureProbe => DMatrix::from_row_slice(1, 2, &[1.0, 0.0]),
+            FlowObservationKind::Ndvi => {
+                let lai = self.specific_leaf_area * state[1].max(0.0);
+                let slope = (self.ndvi_max - self.ndvi_soil) * 0.7 * self.specific_leaf_area * (-0.7 * lai).exp();
+                DMatrix::from_row_slice(1, 2, &[0.0, slope])
+            },
+        }
+    }
+
+    fn constrain(&self, state: &mut DVector<f64>) {
+        state[0] = state[0].clamp(0.0, self.saturation);
+        state[1] = state[1].max(0.0);
+    }
+
+    fn observation_from_measurement(
+        &self,
+        sensor_type: SensorType,
+        measurement: &TimestampedMeasurement,
+    ) -> Option<FlowObservation> {
+        let sigma = measurement.uncertainty.max(1e-3);
+        match (sensor_type, &measurement.value) {
+            (
+                SensorType::SoilSensor | SensorType::AgriculturalIoT | SensorType::CosmicRayNeutron | SensorType::SatelliteRadar,
+                MeasurementValue::SoilMoisture { value, .. },
+            ) => {
+                // Probes report either a fraction or a percentage
+                let (value, sigma) = if *value > 1.0 { (value / 100.0, sigma / 100.0) } else { (*value, sigma) };
+                Some(FlowObservation::scalar(FlowObservationKind::SoilMoistureProbe, value, sigma))
+            },
+            (SensorType::SatelliteImagery | SensorType::DroneMultispectral | SensorType::Phenocam, value) => {
+                ndvi_from_measurement(value).map(|ndvi| FlowObservation::scalar(FlowObservationKind::Ndvi, ndvi, sigma.min(0.2)))
+            },
+            _ => None,
+        }
+    }
+
+    fn agricultural_conditions(&self, mean: &DVector<f64>) -> AgriculturalConditions {
+        AgriculturalConditions {
+            soil_moisture: mean[0],
+            crop_stress_index: 1.0 - self.water_stress(mean[0]),
+            irrigation_need: ((self.field_capacity - mean[0]) / (self.field_capacity - self.wilting_point)).clamp(0.0, 1.0),
+            yield_prediction: mean[1],
+            ..Default::default()
         }
-        
-        average_flow
     }
 }
 
-// Factor trait for graph optimization
-pub trait Factor: Send + Sync {
-    fn error(&self, variables: &HashMap<String, Variable>) -> DVector<f64>;
-    fn jacobian(&self, variables: &HashMap<String, Variable>) -> DMatrix<f64>;
-    fn information_matrix(&self) -> DMatrix<f64>;
+/// NDVI from a multispectral measurement (red 620–700 nm, NIR 760–900 nm) or a
+/// custom `ndvi` value
+pub(crate) fn ndvi_from_measurement(value: &MeasurementValue) -> Option<f64> {
+    match value {
+        MeasurementValue::MultispectralImage { bands, wavelengths } => {
+            let band = |low: f64, high: f64| {
+                wavelengths
+                    .iter()
+                    .position(|w| (low..=high).contains(w))
+                    .and_then(|i| bands.get(i).copied())
+            };
+            let red = band(620.0, 700.0)?;
+            let nir = band(760.0, 900.0)?;
+            (nir + red > 0.0).then(|| (nir - red) / (nir + red))
+        },
+        MeasurementValue::Custom(values) => values.get("ndvi").copied(),
+        _ => None,
+    }
 }
 
 // Supporting types
@@ -649,14 +1917,21 @@ impl Factor for GPSFactor {
         }
     }
     
-    fn jacobian(&self, _variables: &HashMap<String, Variable>) -> DMatrix<f64> {
-        DMatrix::from_vec(1, 1, vec![-1.0])
+    fn jacobian(&self, variables: &HashMap<String, Variable>) -> DMatrix<f64> {
+        let dim = variables.get("position").map_or(1, |v| v.value.len());
+        let mut jacobian = DMatrix::zeros(1, dim);
+        jacobian[(0, 0)] = -1.0;
+        jacobian
     }
     
     fn information_matrix(&self) -> DMatrix<f64> {
         let sigma = 1.0 / (self.measurement.hdop * self.measurement.hdop);
         DMatrix::from_vec(1, 1, vec![sigma])
     }
+
+    fn get_connected_variables(&self) -> Vec<String> {
+        vec!["position".to_string()]
+    }
 }
 
 pub struct AtomicClockFactor {
@@ -680,14 +1955,21 @@ impl Factor for AtomicClockFactor {
         }
     }
     
-    fn jacobian(&self, _variables: &HashMap<String, Variable>) -> DMatrix<f64> {
-        DMatrix::from_vec(1, 1, vec![-1.0])
+    fn jacobian(&self, variables: &HashMap<String, Variable>) -> DMatrix<f64> {
+        let dim = variables.get("clock_bias").map_or(1, |v| v.value.len());
+        let mut jacobian = DMatrix::zeros(1, dim);
+        jacobian[(0, 0)] = -1.0;
+        jacobian
     }
     
     fn information_matrix(&self) -> DMatrix<f64> {
         // Atomic clocks are very precise
         DMatrix::from_vec(1, 1, vec![1e18]) // 1/ns^2
     }
+
+    fn get_connected_variables(&self) -> Vec<String> {
+        vec!["clock_bias".to_string()]
+    }
 }
 
 #[derive(Debug)]
@@ -1040,6 +2322,7 @@ impl FusionAlgorithm for ByzantineFaultTolerantFusion {
             optimization_trace: OptimizationTrace::default(),
             processing_time: std::time::Duration::from_millis(75),
             agricultural_insights,
+            sensor_health: Default::default(),
         })
     }
 
@@ -1137,6 +2420,7 @@ impl FusionAlgorithm for ManifoldConstrainedFusion {
             optimization_trace: OptimizationTrace::default(),
             processing_time: std::time::Duration::from_millis(120),
             agricultural_insights,
+            sensor_health: Default::default(),
         })
     }
 
@@ -1307,6 +2591,7 @@ impl FusionAlgorithm for NashEquilibriumFusion {
             optimization_trace: OptimizationTrace::default(),
             processing_time: std::time::Duration::from_millis(200),
             agricultural_insights,
+            sensor_health: Default::default(),
         })
     }
 
@@ -1323,8 +2608,24 @@ impl FusionAlgorithm for NashEquilibriumFusion {
     }
 }
 
+/// Common interface of the fusion algorithms selected by the engine
+#[async_trait::async_trait]
+pub trait FusionAlgorithm: Send + Sync {
+    async fn fuse_measurements(
+        &self,
+        measurements: &SensorMeasurementBundle,
+        aligned_data: &AlignedSensorData,
+    ) -> Result<FusionResult, AppError>;
+
+    fn get_algorithm_type(&self) -> AlgorithmType;
+
+    fn get_computational_complexity(&self) -> ComputationalComplexity;
+
+    fn supports_real_time(&self) -> bool;
+}
+
 // Supporting types and enums
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AlgorithmType {
     FactorGraph,
     ParticleFlow,
@@ -1338,7 +2639,7 @@ pub enum AlgorithmType {
     ExtendedKalmanFilter,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ComputationalComplexity {
     Low,
     Medium,
@@ -1383,17 +2684,6 @@ impl Clone for FactorGraphFusion {
     }
 }
 
-impl Clone for ParticleFlowFilter {
-    fn clone(&self) -> Self {
-        Self {
-            particles: self.particles.clone(),
-            flow_integrator: self.flow_integrator.clone(),
-            agricultural_flow_dynamics: self.agricultural_flow_dynamics.clone(),
-            resampling_threshold: self.resampling_threshold,
-        }
-    }
-}
-
 impl Clone for ByzantineFaultTolerantFusion {
     fn clone(&self) -> Self {
         Self {
@@ -1433,7 +2723,7 @@ macro_rules! impl_default_debug_clone {
 impl_default_debug_clone!(
     VehicleDynamics, WeatherDynamics, GPSAgriculturalCorrections,
     WeatherCorrelations, SoilDynamics, CropSpectralSignatures,
-    ODEIntegrator, AgriculturalFlowDynamics, FlowField,
+    AgriculturalFlowDynamics,
     ReputationSystem, DetectedFault, ConsensusEstimate,
     ManifoldEmbedding, CoordinateChart, NoveltyDetector,
     AgriculturalManifoldConstraints, ManifoldCoordinates,
@@ -1545,15 +2835,326 @@ macro_rules! impl_factor {
 
 impl_factor!(ClockBiasFactor, timing_stability: f64);
 impl_factor!(WeatherEvolutionFactor, weather_dynamics: WeatherDynamics);
-impl_factor!(GPSFactor, gps_corrections: GPSAgriculturalCorrections);
-impl_factor!(AtomicClockFactor, timing_precision: f64);
-impl_factor!(WeatherStationFactor, weather_correlations: WeatherCorrelations);
-impl_factor!(SoilSensorFactor, soil_dynamics: SoilDynamics);
-impl_factor!(SatelliteImageryFactor, spectral_signatures: CropSpectralSignatures);
 impl_factor!(GenericSensorFactor, _unused: ());
 
+// Station, soil, wind and satellite factors live in `factor_graph`
+
 impl GenericSensorFactor {
     pub fn new(measurement: TimestampedMeasurement) -> Self {
         Self { measurement, _unused: () }
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_fusion::{
+        AgriculturalContext, ClimateZone, EnvironmentalData, GeoBounds, GeographicRegion, GrowthStage, IrrigationSystem,
+        PowerStatus, QualityFlags, SensorMetadata,
+    };
+
+    const DAYS: usize = 60;
+
+    fn daily_forcing(day: usize) -> HashMap<String, f64> {
+        let mut forcing = HashMap::new();
+        forcing.insert("rainfall_mm".to_string(), if day % 9 == 4 { 28.0 } else { 0.0 });
+        forcing.insert("irrigation_mm".to_string(), if day % 14 == 10 { 20.0 } else { 0.0 });
+        forcing.insert("reference_et_mm".to_string(), 4.5 + (day as f64 / 9.0).sin());
+        forcing.insert("par_mj_m2".to_string(), 9.0);
+        forcing
+    }
+
+    /// Truth trajectory with probe readings every day and NDVI every fourth day
+    fn synthetic_scenario(model: &SoilCropModel, seed: u64) -> (Vec<DVector<f64>>, Vec<Vec<FlowObservation>>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let noise = model.process_noise().map(f64::sqrt);
+        let mut state = DVector::from_vec(vec![0.28, 0.4]);
+        let mut truth = Vec::with_capacity(DAYS);
+        let mut observations = Vec::with_capacity(DAYS);
+
+        for day in 0..DAYS {
+            state = model.propagate(&state, &daily_forcing(day))
+                + &noise * DVector::from_fn(2, |_, _| StandardNormal.sample(&mut rng));
+            model.constrain(&mut state);
+
+            let mut day_observations = Vec::new();
+            let probe: f64 = state[0] + 0.03 * rng.sample::<f64, _>(StandardNormal);
+            day_observations.push(FlowObservation::scalar(FlowObservationKind::SoilMoistureProbe, probe, 0.03));
+            if day % 4 == 3 {
+                let ndvi_observation = FlowObservation::scalar(FlowObservationKind::Ndvi, 0.0, 0.02);
+                let ndvi = model.observe(&state, &ndvi_observation)[0] + 0.02 * rng.sample::<f64, _>(StandardNormal);
+                day_observations.push(FlowObservation::scalar(FlowObservationKind::Ndvi, ndvi, 0.02));
+            }
+
+            truth.push(state.clone());
+            observations.push(day_observations);
+        }
+
+        (truth, observations)
+    }
+
+    /// Extended Kalman filter baseline over the same model
+    fn run_ekf(model: &SoilCropModel, observations: &[Vec<FlowObservation>], mean: DVector<f64>, covariance: DMatrix<f64>) -> Vec<DVector<f64>> {
+        let (mut x, mut p) = (mean, covariance);
+        let mut estimates = Vec::with_capacity(observations.len());
+
+        for (day, day_observations) in observations.iter().enumerate() {
+            let forcing = daily_forcing(day);
+            let f = numerical_jacobian(|s| model.propagate(s, &forcing), &x);
+            x = model.propagate(&x, &forcing);
+            p = &f * &p * f.transpose() + model.process_noise();
+
+            for observation in day_observations {
+                let h = model.observation_jacobian(&x, observation);
+                let innovation = &observation.value - model.observe(&x, observation);
+                let s = &h * &p * h.transpose() + &observation.noise_covariance;
+                let gain = &p * h.transpose() * s.try_inverse().unwrap();
+                x += &gain * innovation;
+                p = (DMatrix::identity(2, 2) - &gain * &h) * &p;
+                model.constrain(&mut x);
+            }
+            estimates.push(x.clone());
+        }
+
+        estimates
+    }
+
+    fn synthetic_measurement() -> TimestampedMeasurement {
+        TimestampedMeasurement {
+            timestamp: 0.0,
+            value: MeasurementValue::Scalar(0.0),
+            uncertainty: 0.0,
+            temporal_uncertainty: None,
+            environmental_data: EnvironmentalData {
+                temperature: 20.0,
+                humidity: 60.0,
+                pressure: 1013.25,
+                altitude: 0.0,
+                magnetic_field: None,
+                solar_activity: None,
+                electromagnetic_interference: None,
+                vibration_level: None,
+            },
+            sensor_metadata: SensorMetadata {
+                sensor_id: "test".to_string(),
+                manufacturer: String::new(),
+                model: String::new(),
+                serial_number: String::new(),
+                calibration_date: Utc::now(),
+                last_maintenance: Utc::now(),
+                firmware_version: String::new(),
+                location: (0.0, 0.0, 0.0),
+                installation_date: Utc::now(),
+                communication_latency: None,
+                power_status: PowerStatus::GridPowered,
+                communication_quality: 1.0,
+                calibration_version: None,
+            },
+            quality_flags: QualityFlags {
+                is_valid: true,
+                is_calibrated: true,
+                drift_detected: false,
+                outlier_detected: false,
+                communication_error: false,
+                sensor_malfunction: false,
+                environmental_impact: false,
+                data_completeness: 1.0,
+            },
+        }
+    }
+
+    fn rmse(truth: &[DVector<f64>], estimates: &[DVector<f64>], component: usize) -> f64 {
+        let sum: f64 = truth.iter().zip(estimates).map(|(t, e)| (t[component] - e[component]).powi(2)).sum();
+        (sum / truth.len() as f64).sqrt()
+    }
+
+    #[test]
+    fn test_particle_flow_matches_ekf_on_soil_crop_model() {
+        let model = SoilCropModel::default();
+        let initial_mean = DVector::from_vec(vec![0.25, 0.8]);
+        let initial_covariance = DMatrix::from_diagonal(&DVector::from_vec(vec![0.05f64.powi(2), 0.4f64.powi(2)]));
+
+        let mut pf_rmse = [0.0; 2];
+        let mut ekf_rmse = [0.0; 2];
+        for seed in 0..5 {
+            let (truth, observations) = synthetic_scenario(&model, 100 + seed);
+
+            let mut filter = ParticleFlowFilter::with_seed(Arc::new(model.clone()), 200, &initial_mean, &initial_covariance, seed).unwrap();
+            let mut pf_estimates = Vec::with_capacity(DAYS);
+            for (day, day_observations) in observations.iter().enumerate() {
+                filter.predict(&daily_forcing(day)).unwrap();
+                filter.update(day_observations).unwrap();
+
+                let ess = filter.diagnostics().effective_sample_size;
+                assert!((1.0 - 1e-9..=200.0 + 1e-9).contains(&ess));
+                pf_estimates.push(filter.estimate().0);
+            }
+            let ekf_estimates = run_ekf(&model, &observations, initial_mean.clone(), initial_covariance.clone());
+
+            for component in 0..2 {
+                pf_rmse[component] += rmse(&truth, &pf_estimates, component);
+                ekf_rmse[component] += rmse(&truth, &ekf_estimates, component);
+            }
+        }
+
+        for component in 0..2 {
+            assert!(
+                pf_rmse[component] <= 1.1 * ekf_rmse[component],
+                "component {}: particle flow RMSE {} vs EKF {}",
+                component, pf_rmse[component] / 5.0, ekf_rmse[component] / 5.0
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_particle_flow_fusion_carries_posterior_between_bundles() {
+        let model = SoilCropModel::default();
+        let initial_mean = DVector::from_vec(vec![0.25, 0.8]);
+        let initial_covariance = DMatrix::from_diagonal(&DVector::from_vec(vec![0.05f64.powi(2), 0.4f64.powi(2)]));
+        let filter = ParticleFlowFilter::with_seed(Arc::new(model), 400, &initial_mean, &initial_covariance, 3).unwrap();
+        let fusion = ParticleFlowFusion::new(filter);
+
+        let mut probe = synthetic_measurement();
+        probe.value = MeasurementValue::SoilMoisture { value: 30.0, depth_cm: 30.0, soil_type: "loam".to_string() };
+        probe.uncertainty = 3.0;
+        let bundle = SensorMeasurementBundle {
+            measurements: HashMap::from([(SensorType::SoilSensor, vec![probe])]),
+            quality_metrics: HashMap::new(),
+            temporal_window: (Utc::now(), Utc::now()),
+            bundle_id: uuid::Uuid::nil(),
+            geographic_region: GeographicRegion {
+                name: "Buhera".to_string(),
+                bounds: GeoBounds { north: -19.0, south: -20.0, east: 32.0, west: 31.0 },
+                elevation_range: (900.0, 1300.0),
+                climate_zone: ClimateZone::Semiarid,
+                soil_types: Vec::new(),
+                typical_crops: Vec::new(),
+            },
+            agricultural_context: AgriculturalContext {
+                crop_type: "maize".to_string(),
+                growth_stage: GrowthStage::Vegetative,
+                planting_date: None,
+                expected_harvest_date: None,
+                irrigation_system: IrrigationSystem::None,
+                field_management_practices: Vec::new(),
+                historical_yield_data: None,
+            },
+        };
+        let aligned = AlignedSensorData {
+            aligned_measurements: HashMap::new(),
+            reference_sensor: SensorType::SoilSensor,
+            alignment_quality_metrics: TemporalQualityMetrics::default(),
+            temporal_window: bundle.temporal_window,
+        };
+
+        fusion.fuse_measurements(&bundle, &aligned).await.unwrap();
+        let (first_mean, first_covariance) = fusion.estimate();
+        fusion.fuse_measurements(&bundle, &aligned).await.unwrap();
+        let (second_mean, second_covariance) = fusion.estimate();
+
+        // The second reading updates the first posterior, not the initial prior
+        assert!(first_covariance[(0, 0)] < initial_covariance[(0, 0)]);
+        assert!(second_covariance[(0, 0)] < 0.8 * first_covariance[(0, 0)]);
+        assert!((second_mean[0] - 0.30).abs() < (first_mean[0] - 0.30).abs() + 1e-3);
+        assert_eq!(fusion.diagnostics().update_count, 2);
+    }
+
+    #[test]
+    fn test_flow_migrates_linear_gaussian_prior_to_posterior() {
+        let field = FlowField {
+            prior_mean: DVector::from_element(1, 0.0),
+            prior_covariance: DMatrix::from_element(1, 1, 4.0),
+            observation_jacobian: DMatrix::from_element(1, 1, 1.0),
+            observation_noise: DMatrix::from_element(1, 1, 1.0),
+            linearised_observation: DVector::from_element(1, 2.0),
+        };
+        let integrator = ODEIntegrator::exponential(40, 1.1);
+
+        // Posterior N(1.6, 0.8): the flow maps the prior mean to 1.6 and
+        // contracts deviations by sqrt(0.8 / 4)
+        let mean = integrator.integrate(&DVector::from_element(1, 0.0), &field, 0.0, 1.0);
+        let shifted = integrator.integrate(&DVector::from_element(1, 2.0), &field, 0.0, 1.0);
+        assert!((mean[0] - 1.6).abs() < 0.02);
+        assert!(((shifted[0] - mean[0]) / 2.0 - 0.2f64.sqrt()).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_soil_crop_model_reads_probe_and_ndvi_measurements() {
+        let model = SoilCropModel::default();
+        let mut measurement = synthetic_measurement();
+        measurement.value = MeasurementValue::SoilMoisture { value: 27.0, depth_cm: 30.0, soil_type: "loam".to_string() };
+        measurement.uncertainty = 2.0;
+        let probe = model.observation_from_measurement(SensorType::SoilSensor, &measurement).unwrap();
+        assert_eq!(probe.kind, FlowObservationKind::SoilMoistureProbe);
+        assert!((probe.value[0] - 0.27).abs() < 1e-12);
+
+        measurement.value = MeasurementValue::MultispectralImage { bands: vec![0.05, 0.4], wavelengths: vec![665.0, 842.0] };
+        measurement.uncertainty = 0.03;
+        let ndvi = model.observation_from_measurement(SensorType::SatelliteImagery, &measurement).unwrap();
+        assert_eq!(ndvi.kind, FlowObservationKind::Ndvi);
+        assert!((ndvi.value[0] - 0.35 / 0.45).abs() < 1e-12);
+
+        assert!(model.observation_from_measurement(SensorType::GPS, &measurement).is_none());
+    }
+
+    #[test]
+    fn test_closed_form_information_matches_expected_kl() {
+        let prior = BeliefState::agricultural_prior();
+        let mut selector = InformationTheoreticSelector::new(1000.0);
+        selector.plan(&prior).unwrap();
+        assert!(InformationTheoreticSelector::compute_kl_divergence(&prior, &prior).unwrap().abs() < 1e-12);
+
+        // E_z[KL(posterior ‖ prior)] is the mutual information for a linearised Gaussian model
+        let mut rng = StdRng::seed_from_u64(17);
+        for sensor_type in [SensorType::WeatherStation, SensorType::DroneMultispectral, SensorType::CosmicRayNeutron] {
+            let predicted = selector.predict_measurement(sensor_type, &prior).unwrap();
+            let samples = 8000;
+            let mut expected_kl = 0.0;
+            for _ in 0..samples {
+                let reading = selector.simulate_measurement(&predicted, &mut rng).unwrap();
+                let posterior = selector.update_belief(&prior, &reading, sensor_type).unwrap();
+                expected_kl += InformationTheoreticSelector::compute_kl_divergence(&posterior, &prior).unwrap() / samples as f64;
+            }
+            let closed_form = selector.information_gains()[&sensor_type];
+            assert!(closed_form > 0.0);
+            assert!((expected_kl - closed_form).abs() < 0.05 * closed_form, "{:?}: {} vs {}", sensor_type, expected_kl, closed_form);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_greedy_tasking_within_budget_discounts_redundant_sensors() {
+        let prior = BeliefState::agricultural_prior();
+        let mut selector = InformationTheoreticSelector::new(200.0);
+        let plan = selector.plan(&prior).unwrap();
+        assert!(plan.total_cost <= 200.0);
+        assert!(!plan.selected.iter().any(|c| c.sensor_type == SensorType::SatelliteImagery));
+        for choice in &plan.selected {
+            assert!(choice.marginal_gain_nats <= selector.information_gains()[&choice.sensor_type] + 1e-9);
+        }
+        let best_single = selector
+            .information_gains()
+            .iter()
+            .filter(|(sensor_type, _)| default_acquisition_cost(**sensor_type) <= 200.0)
+            .map(|(_, gain)| *gain)
+            .fold(0.0, f64::max);
+        assert!(plan.information_gain_nats >= best_single);
+        let (before, after) = (prior.marginal_std(), plan.expected_posterior.marginal_std());
+        assert!(after[SOIL_MOISTURE] < before[SOIL_MOISTURE] && after[LEAF_AREA_INDEX] < before[LEAF_AREA_INDEX]);
+
+        // A reference station and a weather station overlap, so the second one bought adds less
+        let mut selector = InformationTheoreticSelector::new(1000.0)
+            .with_available_sensors(vec![SensorType::GroundTruthStation, SensorType::WeatherStation]);
+        let plan = selector.plan(&prior).unwrap();
+        assert_eq!(plan.selected.len(), 2);
+        let second = &plan.selected[1];
+        assert!(second.marginal_gain_nats < selector.information_gains()[&second.sensor_type] - 1.0);
+
+        // This week's crop question: a drone flight fits the budget, tasked imagery does not
+        let mut selector = InformationTheoreticSelector::new(160.0)
+            .with_available_sensors(vec![SensorType::SatelliteImagery, SensorType::DroneMultispectral]);
+        let chosen = selector.select_optimal_sensors(&prior).await.unwrap();
+        assert_eq!(chosen, vec![SensorType::DroneMultispectral]);
+
+        let small = BeliefState { mean: DVector::zeros(3), covariance: DMatrix::identity(3, 3) };
+        assert!(selector.plan(&small).is_err());
+    }
+}
@@ -1,7 +1,11 @@
 use std::collections::HashMap;
-use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use chrono::{DateTime, Datelike, Utc};
 use crate::error::AppError;
-use super::{TimestampedMeasurement, SensorType, AlignedSensorData};
+use super::{TimestampedMeasurement, SensorType, QualityFlags, PowerStatus, PrecipitationType};
+use super::temporal_alignment::AlignedSensorData;
+use super::fusion_algorithms::ndvi_from_measurement;
+use super::terrain::{DigitalElevationModel, LocalClimateModel, NightConditions, SolarIllumination};
 
 /// Fuzzy evidence with agricultural semantics and linguistic variables
 #[derive(Debug, Clone)]
@@ -65,6 +69,27 @@ impl FuzzyReliability {
         // Combine basic and agricultural reliability
         0.7 * basic_reliability + 0.3 * agricultural_reliability
     }
+
+    /// Spread a crisp quality score in [0, 1] over the five reliability levels
+    pub fn from_quality_score(
+        quality: f64,
+        seasonal_reliability: f64,
+        weather_condition_reliability: f64,
+        crop_stage_reliability: f64,
+        sensor_maintenance_reliability: f64,
+    ) -> Self {
+        Self {
+            very_low: if quality < 0.2 { 1.0 - quality * 5.0 } else { 0.0 },
+            low: if (0.1..0.4).contains(&quality) { 1.0 - ((quality - 0.25).abs() / 0.15) } else { 0.0 },
+            medium: if (0.3..0.7).contains(&quality) { 1.0 - ((quality - 0.5).abs() / 0.2) } else { 0.0 },
+            high: if (0.6..0.9).contains(&quality) { 1.0 - ((quality - 0.75).abs() / 0.15) } else { 0.0 },
+            very_high: if quality > 0.8 { (quality - 0.8) * 5.0 } else { 0.0 },
+            seasonal_reliability,
+            weather_condition_reliability,
+            crop_stage_reliability,
+            sensor_maintenance_reliability,
+        }
+    }
 }
 
 /// Agricultural context for fuzzy evidence interpretation
@@ -119,6 +144,7 @@ pub struct TemporalFuzzyInfo {
 }
 
 /// Trait for processing sensor measurements into fuzzy evidence
+#[async_trait::async_trait]
 pub trait FuzzyEvidenceProcessor: Send + Sync {
     /// Convert a timestamped measurement into fuzzy evidence
     async fn process_measurement(
@@ -130,6 +156,13 @@ pub trait FuzzyEvidenceProcessor: Send + Sync {
     /// Get the linguistic variable definitions for this processor
     fn get_linguistic_variables(&self) -> HashMap<String, LinguisticVariable>;
     
+    /// Swap in a configured definition for a variable this processor already uses;
+    /// returns false (and changes nothing) for unknown keys. Processors whose
+    /// variables are fixed keep this default and ignore rule files.
+    fn replace_linguistic_variable(&mut self, _key: &str, _variable: LinguisticVariable) -> bool {
+        false
+    }
+    
     /// Assess measurement quality using fuzzy logic
     fn assess_fuzzy_quality(&self, measurement: &TimestampedMeasurement) -> FuzzyReliability;
 }
@@ -143,7 +176,7 @@ pub struct LinguisticVariable {
 }
 
 /// Fuzzy membership function types
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub enum MembershipFunction {
     /// Triangular membership function (left, peak, right)
     Triangular(f64, f64, f64),
@@ -161,7 +194,20 @@ pub enum MembershipFunction {
     Bell(f64, f64, f64),
     
     /// Custom function
-    Custom(Box<dyn Fn(f64) -> f64 + Send + Sync>),
+    Custom(Arc<dyn Fn(f64) -> f64 + Send + Sync>),
+}
+
+impl std::fmt::Debug for MembershipFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MembershipFunction::Triangular(a, b, c) => write!(f, "Triangular({}, {}, {})", a, b, c),
+            MembershipFunction::Trapezoidal(a, b, c, d) => write!(f, "Trapezoidal({}, {}, {}, {})", a, b, c, d),
+            MembershipFunction::Gaussian(center, width) => write!(f, "Gaussian({}, {})", center, width),
+            MembershipFunction::Sigmoid(a, c) => write!(f, "Sigmoid({}, {})", a, c),
+            MembershipFunction::Bell(center, width, slope) => write!(f, "Bell({}, {}, {})", center, width, slope),
+            MembershipFunction::Custom(_) => write!(f, "Custom"),
+        }
+    }
 }
 
 impl MembershipFunction {
@@ -169,7 +215,8 @@ impl MembershipFunction {
     pub fn evaluate(&self, x: f64) -> f64 {
         match self {
             MembershipFunction::Triangular(a, b, c) => {
-                if x <= *a || x >= *c {
+                // Strict bounds so shoulder terms (a == b or b == c) are 1 at their edge
+                if x < *a || x > *c {
                     0.0
                 } else if x == *b {
                     1.0
@@ -180,7 +227,7 @@ impl MembershipFunction {
                 }
             },
             MembershipFunction::Trapezoidal(a, b, c, d) => {
-                if x <= *a || x >= *d {
+                if x < *a || x > *d {
                     0.0
                 } else if x >= *b && x <= *c {
                     1.0
@@ -209,7 +256,7 @@ impl MembershipFunction {
 pub struct GPSEvidenceProcessor {
     linguistic_variables: HashMap<String, LinguisticVariable>,
     agricultural_zone_boundaries: HashMap<String, Vec<(f64, f64)>>,
-    elevation_model: DigitalElevationModel,
+    elevation_model: Arc<DigitalElevationModel>,
 }
 
 impl GPSEvidenceProcessor {
@@ -247,9 +294,14 @@ impl GPSEvidenceProcessor {
         Self {
             linguistic_variables,
             agricultural_zone_boundaries: HashMap::new(),
-            elevation_model: DigitalElevationModel::new(),
+            elevation_model: Arc::new(DigitalElevationModel::new()),
         }
     }
+
+    pub fn with_elevation_model(mut self, elevation_model: Arc<DigitalElevationModel>) -> Self {
+        self.elevation_model = elevation_model;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -320,6 +372,10 @@ impl FuzzyEvidenceProcessor for GPSEvidenceProcessor {
         self.linguistic_variables.clone()
     }
     
+    fn replace_linguistic_variable(&mut self, key: &str, variable: LinguisticVariable) -> bool {
+        replace_known_variable(&mut self.linguistic_variables, key, variable)
+    }
+    
     fn assess_fuzzy_quality(&self, measurement: &TimestampedMeasurement) -> FuzzyReliability {
         // Implement GPS-specific fuzzy quality assessment
         let hdop = self.extract_hdop(measurement).unwrap_or(99.0);
@@ -331,23 +387,13 @@ impl FuzzyEvidenceProcessor for GPSEvidenceProcessor {
         let combined_score = (accuracy_score + satellite_score) / 2.0;
         
         // Map to fuzzy reliability levels
-        FuzzyReliability {
-            very_low: if combined_score < 0.2 { 1.0 - combined_score * 5.0 } else { 0.0 },
-            low: if combined_score >= 0.1 && combined_score < 0.4 { 
-                1.0 - ((combined_score - 0.25).abs() / 0.15)
-            } else { 0.0 },
-            medium: if combined_score >= 0.3 && combined_score < 0.7 { 
-                1.0 - ((combined_score - 0.5).abs() / 0.2)
-            } else { 0.0 },
-            high: if combined_score >= 0.6 && combined_score < 0.9 { 
-                1.0 - ((combined_score - 0.75).abs() / 0.15)
-            } else { 0.0 },
-            very_high: if combined_score > 0.8 { (combined_score - 0.8) * 5.0 } else { 0.0 },
-            seasonal_reliability: 0.9, // GPS generally season-independent
-            weather_condition_reliability: 0.8, // Some weather effects
-            crop_stage_reliability: 1.0, // GPS independent of crop stage
-            sensor_maintenance_reliability: 0.95, // GPS is low maintenance
-        }
+        FuzzyReliability::from_quality_score(
+            combined_score,
+            0.9,  // GPS generally season-independent
+            0.8,  // Some weather effects
+            1.0,  // GPS independent of crop stage
+            0.95, // GPS is low maintenance
+        )
     }
 }
 
@@ -383,11 +429,16 @@ impl GPSEvidenceProcessor {
         position: &(f64, f64, f64),
         aligned_data: &AlignedSensorData
     ) -> Result<SpatialFuzzyInfo, AppError> {
-        // Compute spatial fuzzy information
+        // Terrain under the receiver; steep slopes and valley walls mask low satellites
+        let terrain_membership = self
+            .elevation_model
+            .terrain_at(position.0, position.1)
+            .map(|terrain| slope_memberships(position.0, terrain.slope_deg, terrain.aspect_deg))
+            .unwrap_or_default();
         Ok(SpatialFuzzyInfo {
             proximity_membership: HashMap::new(),
             elevation_membership: HashMap::new(),
-            terrain_membership: HashMap::new(),
+            terrain_membership,
             microclimate_membership: HashMap::new(),
         })
     }
@@ -412,6 +463,8 @@ pub struct WeatherStationEvidenceProcessor {
     linguistic_variables: HashMap<String, LinguisticVariable>,
     local_climate_model: LocalClimateModel,
     seasonal_correction_factors: SeasonalCorrectionFactors,
+    /// Height temperatures are lapse-rate corrected to, e.g. a field's mean elevation
+    reference_elevation_m: Option<f64>,
 }
 
 impl WeatherStationEvidenceProcessor {
@@ -451,8 +504,39 @@ impl WeatherStationEvidenceProcessor {
             linguistic_variables,
             local_climate_model: LocalClimateModel::new(),
             seasonal_correction_factors: SeasonalCorrectionFactors::new(),
+            reference_elevation_m: None,
         }
     }
+
+    /// Use the DEM for station heights, shading and cold-air pooling, and report
+    /// temperatures as they would be at `reference_elevation_m` when given
+    pub fn with_terrain(mut self, elevation_model: Arc<DigitalElevationModel>, reference_elevation_m: Option<f64>) -> Self {
+        self.local_climate_model = LocalClimateModel::new().with_elevation_model(elevation_model);
+        self.reference_elevation_m = reference_elevation_m;
+        self
+    }
+
+    /// Ground height under the station from the DEM, falling back to the surveyed altitude
+    fn station_elevation_m(&self, measurement: &TimestampedMeasurement) -> f64 {
+        let (lat, lon, altitude) = measurement.sensor_metadata.location;
+        self.local_climate_model.elevation_model().and_then(|dem| dem.elevation_at(lat, lon)).unwrap_or(altitude)
+    }
+
+    /// Latest wind speed reported by the same station, for judging radiative cooling
+    fn station_wind_speed(&self, measurement: &TimestampedMeasurement, aligned_data: &AlignedSensorData) -> Option<f64> {
+        aligned_data
+            .aligned_measurements
+            .values()
+            .flatten()
+            .chain(std::iter::once(measurement))
+            .filter(|m| m.sensor_metadata.sensor_id == measurement.sensor_metadata.sensor_id && m.timestamp <= measurement.timestamp)
+            .filter_map(|m| match &m.value {
+                crate::data_fusion::MeasurementValue::WindVector { speed, .. } => Some((m.timestamp, *speed)),
+                _ => None,
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, speed)| speed)
+    }
 }
 
 #[async_trait::async_trait]
@@ -505,6 +589,10 @@ impl FuzzyEvidenceProcessor for WeatherStationEvidenceProcessor {
         self.linguistic_variables.clone()
     }
     
+    fn replace_linguistic_variable(&mut self, key: &str, variable: LinguisticVariable) -> bool {
+        replace_known_variable(&mut self.linguistic_variables, key, variable)
+    }
+    
     fn assess_fuzzy_quality(&self, measurement: &TimestampedMeasurement) -> FuzzyReliability {
         // Weather station specific quality assessment
         let sensor_age_days = (Utc::now() - measurement.sensor_metadata.calibration_date).num_days() as f64;
@@ -515,30 +603,26 @@ impl FuzzyEvidenceProcessor for WeatherStationEvidenceProcessor {
         
         let overall_quality = (age_factor + environmental_factor + maintenance_factor) / 3.0;
         
-        FuzzyReliability {
-            very_low: if overall_quality < 0.2 { 1.0 - overall_quality * 5.0 } else { 0.0 },
-            low: if overall_quality >= 0.1 && overall_quality < 0.4 { 
-                1.0 - ((overall_quality - 0.25).abs() / 0.15)
-            } else { 0.0 },
-            medium: if overall_quality >= 0.3 && overall_quality < 0.7 { 
-                1.0 - ((overall_quality - 0.5).abs() / 0.2)
-            } else { 0.0 },
-            high: if overall_quality >= 0.6 && overall_quality < 0.9 { 
-                1.0 - ((overall_quality - 0.75).abs() / 0.15)
-            } else { 0.0 },
-            very_high: if overall_quality > 0.8 { (overall_quality - 0.8) * 5.0 } else { 0.0 },
-            seasonal_reliability: self.assess_seasonal_reliability(measurement),
-            weather_condition_reliability: environmental_factor,
-            crop_stage_reliability: 0.9, // Weather generally independent of crop stage
-            sensor_maintenance_reliability: maintenance_factor,
-        }
+        FuzzyReliability::from_quality_score(
+            overall_quality,
+            self.assess_seasonal_reliability(measurement),
+            environmental_factor,
+            0.9, // Weather generally independent of crop stage
+            maintenance_factor,
+        )
     }
 }
 
 impl WeatherStationEvidenceProcessor {
     fn extract_weather_parameters(&self, measurement: &TimestampedMeasurement) -> Result<(f64, String), AppError> {
         match &measurement.value {
-            crate::data_fusion::MeasurementValue::Temperature { value, .. } => Ok((*value, "temperature".to_string())),
+            crate::data_fusion::MeasurementValue::Temperature { value, .. } => {
+                let corrected = match self.reference_elevation_m {
+                    Some(reference) => self.local_climate_model.lapse_rate_correction(*value, self.station_elevation_m(measurement), reference),
+                    None => *value,
+                };
+                Ok((corrected, "temperature".to_string()))
+            },
             crate::data_fusion::MeasurementValue::Humidity(value) => Ok((*value, "humidity".to_string())),
             crate::data_fusion::MeasurementValue::Pressure { value, .. } => Ok((*value, "pressure".to_string())),
             crate::data_fusion::MeasurementValue::WindVector { speed, .. } => Ok((*speed, "wind_speed".to_string())),
@@ -557,14 +641,26 @@ impl WeatherStationEvidenceProcessor {
         })
     }
     
+    /// Slope, sun or shade at the station and, after sunset, its exposure to cold-air pooling
     async fn assess_microclimate_effects(&self, measurement: &TimestampedMeasurement, aligned_data: &AlignedSensorData) -> Result<SpatialFuzzyInfo, AppError> {
-        // Assess microclimate effects on weather measurements
-        Ok(SpatialFuzzyInfo {
-            proximity_membership: HashMap::new(),
-            elevation_membership: HashMap::new(),
-            terrain_membership: HashMap::new(),
-            microclimate_membership: HashMap::new(),
-        })
+        // Station temperatures stay representative over a few tens of kilometres in open terrain
+        let info = spatial_fuzzy_info(measurement, aligned_data, 25_000.0);
+        let Some(dem) = self.local_climate_model.elevation_model() else {
+            return Ok(info);
+        };
+        let (lat, lon, _) = measurement.sensor_metadata.location;
+        let Some(TerrainFuzzyInfo { terrain_membership, mut microclimate_membership, illumination }) =
+            terrain_fuzzy_info(dem, lat, lon, measurement_datetime(measurement))
+        else {
+            return Ok(info);
+        };
+        if illumination.solar_elevation_deg <= 0.0 {
+            let conditions = NightConditions { wind_speed_m_s: self.station_wind_speed(measurement, aligned_data), cloud_fraction: None };
+            if let Some(pooling) = self.local_climate_model.cold_air_pooling(lat, lon, &conditions) {
+                microclimate_membership.insert("cold_air_pooling".to_string(), pooling.risk);
+            }
+        }
+        Ok(SpatialFuzzyInfo { terrain_membership, microclimate_membership, ..info })
     }
     
     async fn assess_temporal_weather_patterns(&self, measurement: &TimestampedMeasurement, aligned_data: &AlignedSensorData) -> Result<TemporalFuzzyInfo, AppError> {
@@ -603,155 +699,1766 @@ impl WeatherStationEvidenceProcessor {
 
 // Supporting structures for agricultural context
 
-pub struct DigitalElevationModel {
-    // Placeholder for elevation data
+pub struct SeasonalCorrectionFactors {
+    // Seasonal adjustment factors
 }
 
-impl DigitalElevationModel {
+impl SeasonalCorrectionFactors {
     pub fn new() -> Self {
         Self {}
     }
 }
 
-pub struct LocalClimateModel {
-    // Climate patterns and normals
+// Shared fuzzification helpers for the sensor-specific processors
+
+fn linguistic_variable(name: &str, universe_of_discourse: (f64, f64), terms: Vec<(&str, MembershipFunction)>) -> LinguisticVariable {
+    LinguisticVariable {
+        name: name.to_string(),
+        universe_of_discourse,
+        terms: terms.into_iter().map(|(term, function)| (term.to_string(), function)).collect(),
+    }
 }
 
-impl LocalClimateModel {
-    pub fn new() -> Self {
-        Self {}
+fn replace_known_variable(
+    variables: &mut HashMap<String, LinguisticVariable>,
+    key: &str,
+    variable: LinguisticVariable,
+) -> bool {
+    match variables.get_mut(key) {
+        Some(existing) => {
+            *existing = variable;
+            true
+        },
+        None => false,
     }
 }
 
-pub struct SeasonalCorrectionFactors {
-    // Seasonal adjustment factors
+/// Evaluate every term of a variable at `x` (clamped to its universe) into "<variable>_<term>" entries
+fn fuzzify(
+    variables: &HashMap<String, LinguisticVariable>,
+    variable_key: &str,
+    x: f64,
+    linguistic_terms: &mut HashMap<String, f64>,
+) {
+    if let Some(variable) = variables.get(variable_key) {
+        let (low, high) = variable.universe_of_discourse;
+        let x = x.clamp(low, high);
+        for (term_name, membership_func) in &variable.terms {
+            linguistic_terms.insert(format!("{}_{}", variable_key, term_name), membership_func.evaluate(x));
+        }
+    }
 }
 
-impl SeasonalCorrectionFactors {
-    pub fn new() -> Self {
-        Self {}
+fn term(linguistic_terms: &HashMap<String, f64>, key: &str) -> f64 {
+    linguistic_terms.get(key).copied().unwrap_or(0.0)
+}
+
+fn memberships(terms: &[(&str, f64)]) -> HashMap<String, f64> {
+    terms.iter().map(|(name, degree)| (name.to_string(), degree.clamp(0.0, 1.0))).collect()
+}
+
+fn measurement_datetime(measurement: &TimestampedMeasurement) -> DateTime<Utc> {
+    let t = measurement.timestamp;
+    DateTime::<Utc>::from_timestamp(t.floor() as i64, ((t - t.floor()) * 1e9) as u32).unwrap_or_default()
+}
+
+/// 1 when `since` is the reading time, falling linearly to 0 after `lifetime_days`
+fn age_factor(since: DateTime<Utc>, measurement: &TimestampedMeasurement, lifetime_days: f64) -> f64 {
+    let age_days = (measurement_datetime(measurement) - since).num_seconds() as f64 / 86400.0;
+    (1.0 - age_days.max(0.0) / lifetime_days).clamp(0.0, 1.0)
+}
+
+fn quality_flag_factor(flags: &QualityFlags) -> f64 {
+    if !flags.is_valid || flags.sensor_malfunction {
+        return 0.0;
     }
+    let mut factor = flags.data_completeness.clamp(0.0, 1.0);
+    if !flags.is_calibrated { factor *= 0.7; }
+    if flags.drift_detected { factor *= 0.7; }
+    if flags.outlier_detected { factor *= 0.5; }
+    if flags.communication_error { factor *= 0.8; }
+    if flags.environmental_impact { factor *= 0.8; }
+    factor
 }
 
-// Additional evidence processors would be implemented similarly:
-// - SoilSensorEvidenceProcessor
-// - SatelliteImageryEvidenceProcessor  
-// - AtomicClockEvidenceProcessor
-// - etc.
+fn power_factor(power_status: &PowerStatus) -> f64 {
+    match power_status {
+        PowerStatus::GridPowered => 1.0,
+        PowerStatus::Solar(watts) => if *watts > 0.0 { 1.0 } else { 0.9 },
+        PowerStatus::Battery(percent) => (0.5 + percent / 100.0).min(1.0),
+        PowerStatus::LowPower => 0.6,
+        PowerStatus::CriticalBattery => 0.3,
+    }
+}
 
-impl FuzzyEvidence {
-    /// Compute fuzzy intersection with another evidence
-    pub fn fuzzy_intersection(&self, other: &FuzzyEvidence) -> f64 {
-        let mut intersection = 0.0;
-        let mut count = 0;
-        
-        for (term, membership1) in &self.linguistic_terms {
-            if let Some(membership2) = other.linguistic_terms.get(term) {
-                intersection += membership1.min(*membership2);
-                count += 1;
-            }
-        }
-        
-        if count > 0 { intersection / count as f64 } else { 0.0 }
+/// Meteorological seasons as overlapping triangles centred mid-season, mirrored south of the equator
+fn seasonal_membership(measurement: &TimestampedMeasurement) -> HashMap<String, f64> {
+    const QUARTER: f64 = 365.25 / 4.0;
+    let mut day = measurement_datetime(measurement).ordinal() as f64;
+    if measurement.sensor_metadata.location.0 < 0.0 {
+        day = (day + 2.0 * QUARTER) % 365.25;
     }
-    
-    /// Compute fuzzy union with another evidence
-    pub fn fuzzy_union(&self, other: &FuzzyEvidence) -> f64 {
-        let mut union = 0.0;
-        let mut count = 0;
-        
-        for (term, membership1) in &self.linguistic_terms {
-            if let Some(membership2) = other.linguistic_terms.get(term) {
-                union += membership1.max(*membership2);
-                count += 1;
-            }
-        }
-        
-        if count > 0 { union / count as f64 } else { 0.0 }
+    let season = |index: f64| {
+        let distance = (day - (15.0 + index * QUARTER)).abs();
+        MembershipFunction::Triangular(-QUARTER, 0.0, QUARTER).evaluate(distance.min(365.25 - distance))
+    };
+    memberships(&[("winter", season(0.0)), ("spring", season(1.0)), ("summer", season(2.0)), ("autumn", season(3.0))])
+}
+
+/// Local solar time of day from UTC and longitude
+fn time_of_day_membership(measurement: &TimestampedMeasurement) -> HashMap<String, f64> {
+    let hours = (measurement.timestamp / 3600.0 + measurement.sensor_metadata.location.1 / 15.0).rem_euclid(24.0);
+    let period = |centre: f64| {
+        let distance = (hours - centre).abs();
+        MembershipFunction::Triangular(-6.0, 0.0, 6.0).evaluate(distance.min(24.0 - distance))
+    };
+    memberships(&[("night", period(0.0)), ("morning", period(6.0)), ("midday", period(12.0)), ("evening", period(18.0))])
+}
+
+pub(crate) fn haversine_distance_m(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (a.0.to_radians(), b.0.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (b.1 - a.1).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * super::terrain::EARTH_RADIUS_M * h.sqrt().min(1.0).asin()
+}
+
+/// Proximity to the nearest corroborating sensor and elevation band
+///
+/// `support_radius_m` is the distance over which another sensor still
+/// observes the same conditions, e.g. a few hundred metres for soil
+/// moisture but tens of kilometres for a wind profiler.
+fn spatial_fuzzy_info(
+    measurement: &TimestampedMeasurement,
+    aligned_data: &AlignedSensorData,
+    support_radius_m: f64,
+) -> SpatialFuzzyInfo {
+    let (lat, lon, altitude) = measurement.sensor_metadata.location;
+    let nearest = aligned_data
+        .aligned_measurements
+        .values()
+        .flatten()
+        .filter(|other| other.sensor_metadata.sensor_id != measurement.sensor_metadata.sensor_id)
+        .map(|other| haversine_distance_m((lat, lon), (other.sensor_metadata.location.0, other.sensor_metadata.location.1)))
+        .min_by(f64::total_cmp);
+
+    let proximity_membership = nearest
+        .map(|distance| {
+            let r = distance / support_radius_m;
+            memberships(&[
+                ("co_located", MembershipFunction::Trapezoidal(0.0, 0.0, 0.1, 0.4).evaluate(r)),
+                ("nearby", MembershipFunction::Triangular(0.1, 0.5, 1.0).evaluate(r)),
+                ("distant", MembershipFunction::Trapezoidal(0.6, 1.0, f64::MAX, f64::MAX).evaluate(r)),
+            ])
+        })
+        .unwrap_or_default();
+
+    SpatialFuzzyInfo {
+        proximity_membership,
+        elevation_membership: memberships(&[
+            ("lowland", MembershipFunction::Trapezoidal(f64::MIN, f64::MIN, 300.0, 600.0).evaluate(altitude)),
+            ("upland", MembershipFunction::Trapezoidal(300.0, 600.0, 1000.0, 1500.0).evaluate(altitude)),
+            ("highland", MembershipFunction::Trapezoidal(1000.0, 1500.0, f64::MAX, f64::MAX).evaluate(altitude)),
+        ]),
+        terrain_membership: HashMap::new(),
+        microclimate_membership: HashMap::new(),
     }
-    
-    /// Check if evidence has multiple peaks (multimodal)
-    pub fn has_multiple_peaks(&self) -> bool {
-        let mut peaks = 0;
-        let memberships: Vec<f64> = self.linguistic_terms.values().cloned().collect();
-        
-        for i in 1..memberships.len().saturating_sub(1) {
-            if memberships[i] > memberships[i-1] && memberships[i] > memberships[i+1] && memberships[i] > 0.5 {
-                peaks += 1;
-            }
-        }
-        
-        peaks > 1
+}
+
+/// Slope classes, and for sloping ground whether it faces the equator or the pole
+fn slope_memberships(latitude: f64, slope_deg: f64, aspect_deg: Option<f64>) -> HashMap<String, f64> {
+    let flat = MembershipFunction::Trapezoidal(f64::MIN, f64::MIN, 2.0, 5.0).evaluate(slope_deg);
+    let equator_azimuth = if latitude >= 0.0 { 180.0 } else { 0.0 };
+    let from_equator = aspect_deg.map_or(90.0, |aspect| {
+        let difference = (aspect - equator_azimuth).rem_euclid(360.0);
+        difference.min(360.0 - difference)
+    });
+    memberships(&[
+        ("flat", flat),
+        ("gentle", MembershipFunction::Triangular(2.0, 6.0, 12.0).evaluate(slope_deg)),
+        ("steep", MembershipFunction::Trapezoidal(8.0, 15.0, f64::MAX, f64::MAX).evaluate(slope_deg)),
+        ("equator_facing", (1.0 - flat) * MembershipFunction::Trapezoidal(f64::MIN, f64::MIN, 45.0, 90.0).evaluate(from_equator)),
+        ("pole_facing", (1.0 - flat) * MembershipFunction::Trapezoidal(90.0, 135.0, f64::MAX, f64::MAX).evaluate(from_equator)),
+    ])
+}
+
+struct TerrainFuzzyInfo {
+    terrain_membership: HashMap<String, f64>,
+    microclimate_membership: HashMap<String, f64>,
+    illumination: SolarIllumination,
+}
+
+/// Terrain memberships at a location and, while the sun is up, how directly it is lit
+fn terrain_fuzzy_info(dem: &DigitalElevationModel, latitude: f64, longitude: f64, time: DateTime<Utc>) -> Option<TerrainFuzzyInfo> {
+    let terrain = dem.terrain_at(latitude, longitude)?;
+    let illumination = dem.solar_illumination(latitude, longitude, time)?;
+    let microclimate_membership = if illumination.solar_elevation_deg > 0.0 {
+        let lit = illumination.direct_beam_factor.min(1.0);
+        memberships(&[("sun_exposed", lit), ("terrain_shaded", 1.0 - lit)])
+    } else {
+        HashMap::new()
+    };
+    Some(TerrainFuzzyInfo {
+        terrain_membership: slope_memberships(latitude, terrain.slope_deg, terrain.aspect_deg),
+        microclimate_membership,
+        illumination,
+    })
+}
+
+/// Time of day, recency, agreement with the sensor's own recent history and clock synchronisation
+///
+/// `nominal_interval` is the sensor's usual reporting interval in seconds;
+/// `history_value` extracts the comparable crisp value from earlier readings.
+fn temporal_fuzzy_info<F>(
+    measurement: &TimestampedMeasurement,
+    crisp_value: f64,
+    aligned_data: &AlignedSensorData,
+    sensor_type: &SensorType,
+    nominal_interval: f64,
+    history_value: F,
+) -> TemporalFuzzyInfo
+where
+    F: Fn(&TimestampedMeasurement) -> Option<f64>,
+{
+    let latest = aligned_data
+        .aligned_measurements
+        .values()
+        .flatten()
+        .map(|m| m.timestamp)
+        .fold(measurement.timestamp, f64::max);
+    let relative_age = (latest - measurement.timestamp) / nominal_interval;
+
+    let history: Vec<f64> = aligned_data
+        .aligned_measurements
+        .get(sensor_type)
+        .into_iter()
+        .flatten()
+        .filter(|m| m.sensor_metadata.sensor_id == measurement.sensor_metadata.sensor_id && m.timestamp < measurement.timestamp)
+        .filter_map(&history_value)
+        .collect();
+    let historical_consistency_membership = if history.len() >= 3 {
+        let mean = history.iter().sum::<f64>() / history.len() as f64;
+        let variance = history.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (history.len() - 1) as f64;
+        let z = (crisp_value - mean).abs() / variance.sqrt().max(measurement.uncertainty).max(1e-9);
+        let consistent = MembershipFunction::Gaussian(0.0, 2.0).evaluate(z);
+        memberships(&[("consistent", consistent), ("anomalous", 1.0 - consistent)])
+    } else {
+        HashMap::new()
+    };
+
+    let synchronized = match measurement.temporal_uncertainty {
+        Some(uncertainty) => (-uncertainty.abs() / (0.05 * nominal_interval)).exp(),
+        None => aligned_data.alignment_quality_metrics.synchronization_score,
+    };
+
+    TemporalFuzzyInfo {
+        time_of_day_membership: time_of_day_membership(measurement),
+        recency_membership: memberships(&[
+            ("current", MembershipFunction::Trapezoidal(f64::MIN, f64::MIN, 1.0, 3.0).evaluate(relative_age)),
+            ("recent", MembershipFunction::Triangular(1.0, 3.0, 6.0).evaluate(relative_age)),
+            ("stale", MembershipFunction::Trapezoidal(3.0, 6.0, f64::MAX, f64::MAX).evaluate(relative_age)),
+        ]),
+        historical_consistency_membership,
+        synchronization_membership: memberships(&[("synchronized", synchronized), ("unsynchronized", 1.0 - synchronized)]),
     }
-    
-    /// Compute fuzzy entropy of the evidence
-    pub fn compute_fuzzy_entropy(&self) -> f64 {
-        let mut entropy = 0.0;
-        
-        for membership in self.linguistic_terms.values() {
-            if *membership > 0.0 {
-                entropy -= membership * membership.log2();
-            }
+}
+
+fn empty_agricultural_context() -> AgriculturalFuzzyContext {
+    AgriculturalFuzzyContext {
+        crop_type_membership: HashMap::new(),
+        growth_stage_membership: HashMap::new(),
+        seasonal_membership: HashMap::new(),
+        weather_pattern_membership: HashMap::new(),
+        irrigation_status_membership: HashMap::new(),
+    }
+}
+
+fn wind_speed_variable() -> LinguisticVariable {
+    linguistic_variable("Wind Speed (m/s)", (0.0, 60.0), vec![
+        ("calm", MembershipFunction::Trapezoidal(0.0, 0.0, 0.5, 1.5)),
+        ("light", MembershipFunction::Triangular(0.5, 2.5, 5.0)),
+        ("moderate", MembershipFunction::Triangular(3.5, 7.0, 11.0)),
+        ("strong", MembershipFunction::Trapezoidal(9.0, 12.0, 17.0, 21.0)),
+        ("gale", MembershipFunction::Trapezoidal(17.0, 21.0, 60.0, 60.0)),
+    ])
+}
+
+/// Volumetric water content thresholds of a soil texture (m³/m³)
+#[derive(Debug, Clone, Copy)]
+pub struct SoilHydraulicProperties {
+    pub wilting_point: f64,
+    pub field_capacity: f64,
+    pub saturation: f64,
+}
+
+/// Soil probe evidence interpreted against the hydraulic limits of the soil texture
+pub struct SoilSensorEvidenceProcessor {
+    linguistic_variables: HashMap<String, LinguisticVariable>,
+    soil_hydraulics: HashMap<String, SoilHydraulicProperties>,
+    calibration_validity_days: f64,
+    maintenance_interval_days: f64,
+    nominal_interval: f64,
+}
+
+impl SoilSensorEvidenceProcessor {
+    pub fn new() -> Self {
+        let mut linguistic_variables = HashMap::new();
+
+        linguistic_variables.insert("volumetric_water_content".to_string(), linguistic_variable(
+            "Volumetric Water Content (m³/m³)", (0.0, 0.6), vec![
+                ("very_dry", MembershipFunction::Trapezoidal(0.0, 0.0, 0.08, 0.15)),
+                ("dry", MembershipFunction::Triangular(0.10, 0.18, 0.26)),
+                ("moist", MembershipFunction::Triangular(0.20, 0.30, 0.40)),
+                ("wet", MembershipFunction::Triangular(0.34, 0.42, 0.50)),
+                ("saturated", MembershipFunction::Trapezoidal(0.45, 0.52, 0.6, 0.6)),
+            ],
+        ));
+
+        // Fraction of the wilting point → field capacity range, above 1 when draining
+        linguistic_variables.insert("plant_available_water".to_string(), linguistic_variable(
+            "Relative Plant-Available Water", (-0.2, 1.6), vec![
+                ("wilting", MembershipFunction::Trapezoidal(-0.2, -0.2, 0.0, 0.2)),
+                ("stressed", MembershipFunction::Triangular(0.05, 0.3, 0.5)),
+                ("adequate", MembershipFunction::Trapezoidal(0.4, 0.6, 0.9, 1.05)),
+                ("at_capacity", MembershipFunction::Triangular(0.9, 1.0, 1.15)),
+                ("waterlogged", MembershipFunction::Trapezoidal(1.05, 1.3, 1.6, 1.6)),
+            ],
+        ));
+
+        linguistic_variables.insert("probe_depth".to_string(), linguistic_variable(
+            "Probe Depth (cm)", (0.0, 200.0), vec![
+                ("surface", MembershipFunction::Trapezoidal(0.0, 0.0, 10.0, 20.0)),
+                ("root_zone", MembershipFunction::Trapezoidal(10.0, 25.0, 60.0, 90.0)),
+                ("subsoil", MembershipFunction::Trapezoidal(60.0, 100.0, 200.0, 200.0)),
+            ],
+        ));
+
+        let soil_hydraulics = [
+            ("sand", 0.05, 0.12, 0.40),
+            ("loamy_sand", 0.07, 0.16, 0.41),
+            ("sandy_loam", 0.09, 0.21, 0.43),
+            ("loam", 0.12, 0.30, 0.46),
+            ("silt_loam", 0.13, 0.33, 0.48),
+            ("clay_loam", 0.20, 0.36, 0.48),
+            ("clay", 0.27, 0.40, 0.50),
+        ]
+        .into_iter()
+        .map(|(texture, wilting_point, field_capacity, saturation)| {
+            (texture.to_string(), SoilHydraulicProperties { wilting_point, field_capacity, saturation })
+        })
+        .collect();
+
+        Self {
+            linguistic_variables,
+            soil_hydraulics,
+            calibration_validity_days: 365.0,
+            maintenance_interval_days: 120.0,
+            nominal_interval: 900.0,
         }
-        
-        entropy
     }
-    
-    /// Assess linguistic consistency
-    pub fn assess_linguistic_consistency(&self) -> f64 {
-        // Check if linguistic terms are consistent (not contradictory)
-        let mut consistency_score = 1.0;
-        
-        // Look for contradictory terms (e.g., "very_hot" and "very_cold" both high)
-        let contradictory_pairs = vec![
-            ("very_hot", "very_cold"),
-            ("very_dry", "very_humid"),
-            ("excellent", "very_poor"),
-        ];
-        
-        for (term1, term2) in contradictory_pairs {
-            let membership1 = self.linguistic_terms.get(term1).unwrap_or(&0.0);
-            let membership2 = self.linguistic_terms.get(term2).unwrap_or(&0.0);
-            
-            // Penalize if both contradictory terms have high membership
-            if *membership1 > 0.7 && *membership2 > 0.7 {
-                consistency_score -= 0.3;
-            }
+
+    /// Hydraulic limits for a texture name, falling back to loam
+    pub fn hydraulic_properties(&self, soil_type: &str) -> SoilHydraulicProperties {
+        let key = soil_type.trim().to_lowercase().replace([' ', '-'], "_");
+        self.soil_hydraulics
+            .get(&key)
+            .or_else(|| self.soil_hydraulics.get("loam"))
+            .copied()
+            .unwrap_or(SoilHydraulicProperties { wilting_point: 0.12, field_capacity: 0.30, saturation: 0.46 })
+    }
+
+    /// Volumetric water content (m³/m³), probe depth and texture; probes report either a fraction or a percentage
+    fn extract_soil_moisture(&self, measurement: &TimestampedMeasurement) -> Result<(f64, f64, SoilHydraulicProperties), AppError> {
+        match &measurement.value {
+            crate::data_fusion::MeasurementValue::SoilMoisture { value, depth_cm, soil_type } => {
+                let content = if *value > 1.0 { value / 100.0 } else { *value };
+                Ok((content, *depth_cm, self.hydraulic_properties(soil_type)))
+            },
+            _ => Err(AppError::validation("Soil sensor measurement must be soil moisture")),
         }
-        
-        consistency_score.max(0.0)
+    }
+
+    fn relative_available_water(content: f64, hydraulics: &SoilHydraulicProperties) -> f64 {
+        (content - hydraulics.wilting_point) / (hydraulics.field_capacity - hydraulics.wilting_point)
+    }
+
+    fn assess_irrigation_status(
+        &self,
+        measurement: &TimestampedMeasurement,
+        content: f64,
+        linguistic_terms: &HashMap<String, f64>,
+        aligned_data: &AlignedSensorData,
+    ) -> HashMap<String, f64> {
+        // A jump since the previous reading of the same probe indicates rain or irrigation
+        let previous = aligned_data
+            .aligned_measurements
+            .get(&SensorType::SoilSensor)
+            .into_iter()
+            .flatten()
+            .filter(|m| m.sensor_metadata.sensor_id == measurement.sensor_metadata.sensor_id && m.timestamp < measurement.timestamp)
+            .max_by(|a, b| a.timestamp.total_cmp(&b.timestamp))
+            .and_then(|m| self.extract_soil_moisture(m).ok().map(|(c, _, _)| (m.timestamp, c)));
+        let recently_wetted = previous
+            .filter(|(t, _)| measurement.timestamp - t <= 86400.0)
+            .map_or(0.0, |(_, c)| (content - c) / 0.08);
+
+        memberships(&[
+            ("irrigation_needed", term(linguistic_terms, "plant_available_water_wilting").max(term(linguistic_terms, "plant_available_water_stressed"))),
+            ("adequate", term(linguistic_terms, "plant_available_water_adequate").max(term(linguistic_terms, "plant_available_water_at_capacity"))),
+            ("over_irrigated", term(linguistic_terms, "plant_available_water_waterlogged")),
+            ("recently_wetted", recently_wetted),
+        ])
+    }
+
+    /// Capacitance and TDR probes misread frozen soil, where liquid water is replaced by ice
+    fn assess_frozen_soil(&self, measurement: &TimestampedMeasurement) -> f64 {
+        MembershipFunction::Trapezoidal(-2.0, 2.0, f64::MAX, f64::MAX).evaluate(measurement.environmental_data.temperature).max(0.3)
+    }
+}
+
+impl Default for SoilSensorEvidenceProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl FuzzyEvidenceProcessor for SoilSensorEvidenceProcessor {
+    async fn process_measurement(
+        &self,
+        measurement: &TimestampedMeasurement,
+        aligned_data: &AlignedSensorData,
+    ) -> Result<FuzzyEvidence, AppError> {
+        let (content, depth_cm, hydraulics) = self.extract_soil_moisture(measurement)?;
+
+        let mut linguistic_terms = HashMap::new();
+        fuzzify(&self.linguistic_variables, "volumetric_water_content", content, &mut linguistic_terms);
+        fuzzify(&self.linguistic_variables, "plant_available_water", Self::relative_available_water(content, &hydraulics), &mut linguistic_terms);
+        fuzzify(&self.linguistic_variables, "probe_depth", depth_cm, &mut linguistic_terms);
+
+        let agricultural_context = AgriculturalFuzzyContext {
+            seasonal_membership: seasonal_membership(measurement),
+            irrigation_status_membership: self.assess_irrigation_status(measurement, content, &linguistic_terms, aligned_data),
+            ..empty_agricultural_context()
+        };
+
+        let temporal_fuzzy_info = temporal_fuzzy_info(
+            measurement,
+            content,
+            aligned_data,
+            &SensorType::SoilSensor,
+            self.nominal_interval,
+            |m| self.extract_soil_moisture(m).ok().map(|(c, _, _)| c),
+        );
+
+        Ok(FuzzyEvidence {
+            crisp_value: content,
+            linguistic_terms,
+            reliability: self.assess_fuzzy_quality(measurement),
+            timestamp: measurement.timestamp,
+            sensor_type: SensorType::SoilSensor,
+            agricultural_context,
+            // Soil moisture decorrelates over a few hundred metres
+            spatial_fuzzy_info: spatial_fuzzy_info(measurement, aligned_data, 500.0),
+            temporal_fuzzy_info,
+        })
+    }
+
+    fn get_linguistic_variables(&self) -> HashMap<String, LinguisticVariable> {
+        self.linguistic_variables.clone()
     }
     
-    /// Assess temporal quality
-    pub fn assess_temporal_quality(&self) -> f64 {
-        // Assess temporal quality based on recency and synchronization
-        let recency_score = self.temporal_fuzzy_info.recency_membership
-            .get("recent").unwrap_or(&0.5);
-        let sync_score = self.temporal_fuzzy_info.synchronization_membership
-            .get("well_synchronized").unwrap_or(&0.5);
-        
-        (recency_score + sync_score) / 2.0
+    fn replace_linguistic_variable(&mut self, key: &str, variable: LinguisticVariable) -> bool {
+        replace_known_variable(&mut self.linguistic_variables, key, variable)
+    }
+
+    fn assess_fuzzy_quality(&self, measurement: &TimestampedMeasurement) -> FuzzyReliability {
+        let plausibility = match self.extract_soil_moisture(measurement) {
+            Ok((content, _, hydraulics)) if (0.0..=hydraulics.saturation + 0.05).contains(&content) => 1.0,
+            Ok(_) => 0.2,
+            Err(_) => 0.0,
+        };
+        let calibration = age_factor(measurement.sensor_metadata.calibration_date, measurement, self.calibration_validity_days);
+        let maintenance = age_factor(measurement.sensor_metadata.last_maintenance, measurement, self.maintenance_interval_days)
+            * power_factor(&measurement.sensor_metadata.power_status);
+        let frozen = self.assess_frozen_soil(measurement);
+
+        let quality = plausibility * quality_flag_factor(&measurement.quality_flags) * (calibration + maintenance + frozen) / 3.0;
+        let winter = seasonal_membership(measurement).get("winter").copied().unwrap_or(0.0);
+
+        FuzzyReliability::from_quality_score(
+            quality,
+            1.0 - 0.3 * winter,
+            frozen,
+            0.9, // Root uptake around the probe varies with crop stage
+            maintenance,
+        )
     }
 }
 
-// Placeholder implementations for additional evidence processors
-pub struct AtomicClockEvidenceProcessor;
-pub struct SoilSensorEvidenceProcessor;
-pub struct SatelliteImageryEvidenceProcessor;
-pub struct RadarPrecipitationEvidenceProcessor;
-pub struct LightningDetectorEvidenceProcessor;
-pub struct WindProfilerEvidenceProcessor;
-pub struct WeatherBuoyEvidenceProcessor;
-pub struct AgriculturalIoTEvidenceProcessor;
-pub struct DroneMultispectralEvidenceProcessor;
-pub struct GroundTruthEvidenceProcessor;
+/// Radar soil moisture retrievals, read against the same hydraulic limits as soil probes
+/// but weighted by the retrieval's own uncertainty
+pub struct SatelliteRadarEvidenceProcessor {
+    soil: SoilSensorEvidenceProcessor,
+    revisit_interval: f64,
+    field_support_m: f64,
+    /// Retrieval uncertainty (m³/m³) at which a retrieval carries no information
+    max_uncertainty: f64,
+    calibration_validity_days: f64,
+}
 
-impl AtomicClockEvidenceProcessor {
-    pub fn new() -> Self { Self }
+impl SatelliteRadarEvidenceProcessor {
+    pub fn new() -> Self {
+        Self {
+            soil: SoilSensorEvidenceProcessor::new(),
+            revisit_interval: 6.0 * 86400.0,
+            field_support_m: 500.0,
+            max_uncertainty: 0.12,
+            calibration_validity_days: 730.0,
+        }
+    }
 }
 
-impl SoilSensorEvidenceProcessor {
-    pub fn new() -> Self { Self }
+impl Default for SatelliteRadarEvidenceProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-// Add #[async_trait::async_trait] implementations for each processor... 
\ No newline at end of file
+#[async_trait::async_trait]
+impl FuzzyEvidenceProcessor for SatelliteRadarEvidenceProcessor {
+    async fn process_measurement(
+        &self,
+        measurement: &TimestampedMeasurement,
+        aligned_data: &AlignedSensorData,
+    ) -> Result<FuzzyEvidence, AppError> {
+        let (content, depth_cm, hydraulics) = self.soil.extract_soil_moisture(measurement)?;
+
+        let mut linguistic_terms = HashMap::new();
+        fuzzify(&self.soil.linguistic_variables, "volumetric_water_content", content, &mut linguistic_terms);
+        fuzzify(&self.soil.linguistic_variables, "plant_available_water", SoilSensorEvidenceProcessor::relative_available_water(content, &hydraulics), &mut linguistic_terms);
+        fuzzify(&self.soil.linguistic_variables, "probe_depth", depth_cm, &mut linguistic_terms);
+
+        // Revisits are days apart, so a retrieval cannot tell when the field was last wetted
+        let agricultural_context = AgriculturalFuzzyContext {
+            seasonal_membership: seasonal_membership(measurement),
+            irrigation_status_membership: memberships(&[
+                ("irrigation_needed", term(&linguistic_terms, "plant_available_water_wilting").max(term(&linguistic_terms, "plant_available_water_stressed"))),
+                ("adequate", term(&linguistic_terms, "plant_available_water_adequate").max(term(&linguistic_terms, "plant_available_water_at_capacity"))),
+                ("over_irrigated", term(&linguistic_terms, "plant_available_water_waterlogged")),
+            ]),
+            ..empty_agricultural_context()
+        };
+
+        let temporal_fuzzy_info = temporal_fuzzy_info(
+            measurement,
+            content,
+            aligned_data,
+            &SensorType::SatelliteRadar,
+            self.revisit_interval,
+            |m| self.soil.extract_soil_moisture(m).ok().map(|(c, _, _)| c),
+        );
+
+        Ok(FuzzyEvidence {
+            crisp_value: content,
+            linguistic_terms,
+            reliability: self.assess_fuzzy_quality(measurement),
+            timestamp: measurement.timestamp,
+            sensor_type: SensorType::SatelliteRadar,
+            agricultural_context,
+            spatial_fuzzy_info: spatial_fuzzy_info(measurement, aligned_data, self.field_support_m),
+            temporal_fuzzy_info,
+        })
+    }
+
+    fn get_linguistic_variables(&self) -> HashMap<String, LinguisticVariable> {
+        self.soil.get_linguistic_variables()
+    }
+
+    fn replace_linguistic_variable(&mut self, key: &str, variable: LinguisticVariable) -> bool {
+        self.soil.replace_linguistic_variable(key, variable)
+    }
+
+    fn assess_fuzzy_quality(&self, measurement: &TimestampedMeasurement) -> FuzzyReliability {
+        let plausibility = match self.soil.extract_soil_moisture(measurement) {
+            Ok((content, _, hydraulics)) if (0.0..=hydraulics.saturation + 0.05).contains(&content) => 1.0,
+            Ok(_) => 0.2,
+            Err(_) => 0.0,
+        };
+        let precision = (1.0 - measurement.uncertainty / self.max_uncertainty).clamp(0.0, 1.0);
+        let calibration = age_factor(measurement.sensor_metadata.calibration_date, measurement, self.calibration_validity_days);
+
+        let quality = plausibility * quality_flag_factor(&measurement.quality_flags) * (2.0 * precision + calibration) / 3.0;
+        FuzzyReliability::from_quality_score(
+            quality,
+            1.0,
+            1.0, // C-band sees through cloud
+            precision, // Dense canopy is what inflates the retrieval uncertainty
+            calibration,
+        )
+    }
+}
+
+/// Satellite multispectral evidence as NDVI with cloud screening
+pub struct SatelliteImageryEvidenceProcessor {
+    linguistic_variables: HashMap<String, LinguisticVariable>,
+    revisit_interval: f64,
+    pixel_support_m: f64,
+    calibration_validity_days: f64,
+    elevation_model: Option<Arc<DigitalElevationModel>>,
+}
+
+impl SatelliteImageryEvidenceProcessor {
+    pub fn new() -> Self {
+        let mut linguistic_variables = HashMap::new();
+
+        linguistic_variables.insert("ndvi".to_string(), linguistic_variable(
+            "Normalised Difference Vegetation Index", (-1.0, 1.0), vec![
+                ("bare", MembershipFunction::Trapezoidal(-1.0, -1.0, 0.05, 0.15)),
+                ("sparse", MembershipFunction::Triangular(0.1, 0.25, 0.4)),
+                ("moderate", MembershipFunction::Triangular(0.3, 0.5, 0.65)),
+                ("dense", MembershipFunction::Triangular(0.55, 0.7, 0.85)),
+                ("very_dense", MembershipFunction::Trapezoidal(0.75, 0.85, 1.0, 1.0)),
+            ],
+        ));
+
+        linguistic_variables.insert("cloud_contamination".to_string(), linguistic_variable(
+            "Cloud Contamination (fraction)", (0.0, 1.0), vec![
+                ("clear", MembershipFunction::Trapezoidal(0.0, 0.0, 0.05, 0.2)),
+                ("partly_cloudy", MembershipFunction::Triangular(0.1, 0.35, 0.6)),
+                ("cloudy", MembershipFunction::Trapezoidal(0.5, 0.7, 1.0, 1.0)),
+            ],
+        ));
+
+        Self {
+            linguistic_variables,
+            revisit_interval: 5.0 * 86400.0,
+            pixel_support_m: 100.0,
+            calibration_validity_days: 730.0,
+            elevation_model: None,
+        }
+    }
+
+    /// Account for terrain shading of the pixel at acquisition time
+    pub fn with_elevation_model(mut self, elevation_model: Arc<DigitalElevationModel>) -> Self {
+        self.elevation_model = Some(elevation_model);
+        self
+    }
+
+    fn illumination(&self, measurement: &TimestampedMeasurement) -> Option<SolarIllumination> {
+        let (lat, lon, _) = measurement.sensor_metadata.location;
+        self.elevation_model.as_deref()?.solar_illumination(lat, lon, measurement_datetime(measurement))
+    }
+
+    fn extract_ndvi(&self, measurement: &TimestampedMeasurement) -> Result<f64, AppError> {
+        match &measurement.value {
+            crate::data_fusion::MeasurementValue::Scalar(ndvi) if (-1.0..=1.0).contains(ndvi) => Ok(*ndvi),
+            value => ndvi_from_measurement(value)
+                .ok_or_else(|| AppError::validation("Satellite measurement needs red and NIR bands or an NDVI value")),
+        }
+    }
+
+    /// Cloud fraction, from an explicit value or the brightness of the blue band (450–520 nm)
+    fn extract_cloud_contamination(&self, measurement: &TimestampedMeasurement) -> f64 {
+        match &measurement.value {
+            crate::data_fusion::MeasurementValue::MultispectralImage { bands, wavelengths } => {
+                let peak = bands.iter().copied().fold(0.0, f64::max);
+                // Reflectance may be delivered as a fraction, a percentage or scaled to 10000
+                let scale = if peak > 100.0 { 1e4 } else if peak > 1.0 { 100.0 } else { 1.0 };
+                wavelengths
+                    .iter()
+                    .position(|w| (450.0..=520.0).contains(w))
+                    .and_then(|i| bands.get(i))
+                    .map_or(0.0, |blue| ((blue / scale - 0.1) / 0.2).clamp(0.0, 1.0))
+            },
+            crate::data_fusion::MeasurementValue::Custom(values) => values.get("cloud_fraction").copied().unwrap_or(0.0).clamp(0.0, 1.0),
+            _ => 0.0,
+        }
+    }
+
+    /// Growth stage from the NDVI level and its trend since the previous scene of the same sensor
+    fn assess_growth_stage(
+        &self,
+        measurement: &TimestampedMeasurement,
+        ndvi: f64,
+        linguistic_terms: &HashMap<String, f64>,
+        aligned_data: &AlignedSensorData,
+    ) -> HashMap<String, f64> {
+        let trend_per_day = aligned_data
+            .aligned_measurements
+            .get(&SensorType::SatelliteImagery)
+            .into_iter()
+            .flatten()
+            .filter(|m| m.sensor_metadata.sensor_id == measurement.sensor_metadata.sensor_id && m.timestamp < measurement.timestamp)
+            .max_by(|a, b| a.timestamp.total_cmp(&b.timestamp))
+            .and_then(|m| self.extract_ndvi(m).ok().map(|previous| (ndvi - previous) / ((measurement.timestamp - m.timestamp) / 86400.0).max(1.0)))
+            .unwrap_or(0.0);
+        let greening = (trend_per_day / 0.01).clamp(0.0, 1.0);
+        let browning = (-trend_per_day / 0.01).clamp(0.0, 1.0);
+
+        let low = term(linguistic_terms, "ndvi_bare").max(term(linguistic_terms, "ndvi_sparse"));
+        let mid = term(linguistic_terms, "ndvi_moderate");
+        let high = term(linguistic_terms, "ndvi_dense").max(term(linguistic_terms, "ndvi_very_dense"));
+
+        memberships(&[
+            ("emergence", low * (1.0 - browning)),
+            ("vegetative", (mid * (1.0 - browning)).max(greening * (1.0 - high))),
+            ("peak", high * (1.0 - browning)),
+            ("senescence", browning),
+        ])
+    }
+
+    fn terrain_spatial_info(&self, measurement: &TimestampedMeasurement, aligned_data: &AlignedSensorData) -> SpatialFuzzyInfo {
+        let info = spatial_fuzzy_info(measurement, aligned_data, self.pixel_support_m);
+        let (lat, lon, _) = measurement.sensor_metadata.location;
+        match self.elevation_model.as_deref().and_then(|dem| terrain_fuzzy_info(dem, lat, lon, measurement_datetime(measurement))) {
+            Some(terrain) => SpatialFuzzyInfo {
+                terrain_membership: terrain.terrain_membership,
+                microclimate_membership: terrain.microclimate_membership,
+                ..info
+            },
+            None => info,
+        }
+    }
+}
+
+impl Default for SatelliteImageryEvidenceProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl FuzzyEvidenceProcessor for SatelliteImageryEvidenceProcessor {
+    async fn process_measurement(
+        &self,
+        measurement: &TimestampedMeasurement,
+        aligned_data: &AlignedSensorData,
+    ) -> Result<FuzzyEvidence, AppError> {
+        let ndvi = self.extract_ndvi(measurement)?;
+
+        let mut linguistic_terms = HashMap::new();
+        fuzzify(&self.linguistic_variables, "ndvi", ndvi, &mut linguistic_terms);
+        fuzzify(&self.linguistic_variables, "cloud_contamination", self.extract_cloud_contamination(measurement), &mut linguistic_terms);
+
+        let agricultural_context = AgriculturalFuzzyContext {
+            growth_stage_membership: self.assess_growth_stage(measurement, ndvi, &linguistic_terms, aligned_data),
+            seasonal_membership: seasonal_membership(measurement),
+            ..empty_agricultural_context()
+        };
+
+        let temporal_fuzzy_info = temporal_fuzzy_info(
+            measurement,
+            ndvi,
+            aligned_data,
+            &SensorType::SatelliteImagery,
+            self.revisit_interval,
+            |m| self.extract_ndvi(m).ok(),
+        );
+
+        Ok(FuzzyEvidence {
+            crisp_value: ndvi,
+            linguistic_terms,
+            reliability: self.assess_fuzzy_quality(measurement),
+            timestamp: measurement.timestamp,
+            sensor_type: SensorType::SatelliteImagery,
+            agricultural_context,
+            spatial_fuzzy_info: self.terrain_spatial_info(measurement, aligned_data),
+            temporal_fuzzy_info,
+        })
+    }
+
+    fn get_linguistic_variables(&self) -> HashMap<String, LinguisticVariable> {
+        self.linguistic_variables.clone()
+    }
+    
+    fn replace_linguistic_variable(&mut self, key: &str, variable: LinguisticVariable) -> bool {
+        replace_known_variable(&mut self.linguistic_variables, key, variable)
+    }
+
+    fn assess_fuzzy_quality(&self, measurement: &TimestampedMeasurement) -> FuzzyReliability {
+        let clear_sky = 1.0 - self.extract_cloud_contamination(measurement);
+        let ndvi = self.extract_ndvi(measurement);
+        let valid = if ndvi.is_ok() { 1.0 } else { 0.0 };
+        let calibration = age_factor(measurement.sensor_metadata.calibration_date, measurement, self.calibration_validity_days);
+
+        // NDVI saturates over closed canopies, so dense crops are resolved poorly
+        let saturation = ndvi.map_or(0.0, |ndvi| MembershipFunction::Trapezoidal(0.75, 0.85, 1.0, 1.0).evaluate(ndvi));
+        // Low winter sun lengthens shadows and raises the atmospheric path
+        let winter = seasonal_membership(measurement).get("winter").copied().unwrap_or(0.0);
+
+        // Shaded slopes are lit only by diffuse skylight, so their reflectances are noisy and biased
+        let illumination = self.illumination(measurement).map_or(1.0, |i| 0.3 + 0.7 * i.direct_beam_factor.min(1.0));
+
+        let quality = valid * clear_sky * illumination * quality_flag_factor(&measurement.quality_flags) * (0.5 + 0.5 * calibration);
+
+        FuzzyReliability::from_quality_score(
+            quality,
+            1.0 - 0.2 * winter,
+            clear_sky * illumination,
+            1.0 - 0.4 * saturation,
+            0.5 + 0.5 * calibration,
+        )
+    }
+}
+
+/// Radar precipitation evidence, from rain rates or reflectivity via a Z–R relationship
+pub struct RadarPrecipitationEvidenceProcessor {
+    linguistic_variables: HashMap<String, LinguisticVariable>,
+    /// Z = a·R^b (Marshall–Palmer by default)
+    z_r_coefficients: (f64, f64),
+    /// Range at which beam overshoot makes low-level rain undetectable (km)
+    maximum_useful_range_km: f64,
+    calibration_validity_days: f64,
+    nominal_interval: f64,
+}
+
+/// Rain rate and context extracted from a radar measurement
+struct RadarRainfall {
+    rate_mm_h: f64,
+    accumulation_mm: Option<f64>,
+    precipitation_type: Option<PrecipitationType>,
+    range_km: Option<f64>,
+}
+
+impl RadarPrecipitationEvidenceProcessor {
+    pub fn new() -> Self {
+        let mut linguistic_variables = HashMap::new();
+
+        linguistic_variables.insert("rain_rate".to_string(), linguistic_variable(
+            "Rain Rate (mm/h)", (0.0, 200.0), vec![
+                ("none", MembershipFunction::Trapezoidal(0.0, 0.0, 0.05, 0.2)),
+                ("light", MembershipFunction::Trapezoidal(0.1, 0.5, 2.0, 3.0)),
+                ("moderate", MembershipFunction::Triangular(2.0, 5.0, 8.0)),
+                ("heavy", MembershipFunction::Trapezoidal(7.0, 10.0, 40.0, 55.0)),
+                ("violent", MembershipFunction::Trapezoidal(45.0, 60.0, 200.0, 200.0)),
+            ],
+        ));
+
+        linguistic_variables.insert("accumulation".to_string(), linguistic_variable(
+            "Accumulated Precipitation (mm)", (0.0, 300.0), vec![
+                ("trace", MembershipFunction::Trapezoidal(0.0, 0.0, 0.5, 2.0)),
+                ("wetting", MembershipFunction::Triangular(1.0, 5.0, 12.0)),
+                ("soaking", MembershipFunction::Trapezoidal(8.0, 15.0, 40.0, 60.0)),
+                ("flooding", MembershipFunction::Trapezoidal(40.0, 80.0, 300.0, 300.0)),
+            ],
+        ));
+
+        Self {
+            linguistic_variables,
+            z_r_coefficients: (200.0, 1.6),
+            maximum_useful_range_km: 250.0,
+            calibration_validity_days: 180.0,
+            nominal_interval: 300.0,
+        }
+    }
+
+    /// Rain rate (mm/h) for a reflectivity in dBZ
+    pub fn rain_rate_from_reflectivity(&self, dbz: f64) -> f64 {
+        let (a, b) = self.z_r_coefficients;
+        (10f64.powf(dbz / 10.0) / a).powf(1.0 / b)
+    }
+
+    fn extract_rainfall(&self, measurement: &TimestampedMeasurement) -> Result<RadarRainfall, AppError> {
+        use crate::data_fusion::MeasurementValue;
+
+        match &measurement.value {
+            MeasurementValue::Precipitation { rate, accumulation, type_ } => Ok(RadarRainfall {
+                rate_mm_h: *rate,
+                accumulation_mm: Some(*accumulation),
+                precipitation_type: Some(type_.clone()),
+                range_km: None,
+            }),
+            // Bare scalars from a radar are reflectivity
+            MeasurementValue::Scalar(dbz) => Ok(RadarRainfall {
+                rate_mm_h: self.rain_rate_from_reflectivity(*dbz),
+                accumulation_mm: None,
+                precipitation_type: None,
+                range_km: None,
+            }),
+            MeasurementValue::Custom(values) => {
+                let rate_mm_h = values
+                    .get("rain_rate_mm_h")
+                    .copied()
+                    .or_else(|| values.get("reflectivity_dbz").map(|dbz| self.rain_rate_from_reflectivity(*dbz)))
+                    .ok_or_else(|| AppError::validation("Radar measurement needs a rain rate or reflectivity"))?;
+                Ok(RadarRainfall {
+                    rate_mm_h,
+                    accumulation_mm: values.get("accumulation_mm").copied(),
+                    precipitation_type: None,
+                    range_km: values.get("range_km").copied(),
+                })
+            },
+            _ => Err(AppError::validation("Unsupported radar measurement type")),
+        }
+    }
+}
+
+impl Default for RadarPrecipitationEvidenceProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl FuzzyEvidenceProcessor for RadarPrecipitationEvidenceProcessor {
+    async fn process_measurement(
+        &self,
+        measurement: &TimestampedMeasurement,
+        aligned_data: &AlignedSensorData,
+    ) -> Result<FuzzyEvidence, AppError> {
+        let rainfall = self.extract_rainfall(measurement)?;
+
+        let mut linguistic_terms = HashMap::new();
+        fuzzify(&self.linguistic_variables, "rain_rate", rainfall.rate_mm_h, &mut linguistic_terms);
+        if let Some(accumulation) = rainfall.accumulation_mm {
+            fuzzify(&self.linguistic_variables, "accumulation", accumulation, &mut linguistic_terms);
+        }
+
+        let rate = |name: &str| term(&linguistic_terms, &format!("rain_rate_{}", name));
+        let weather_pattern_membership = memberships(&[
+            ("dry", rate("none")),
+            ("stratiform", rate("light").max(rate("moderate"))),
+            ("convective", rate("heavy").max(rate("violent"))),
+        ]);
+        let irrigation_status_membership = match rainfall.accumulation_mm {
+            Some(_) => {
+                let total = |name: &str| term(&linguistic_terms, &format!("accumulation_{}", name));
+                memberships(&[
+                    ("irrigation_unnecessary", total("soaking").max(total("flooding")).max(0.5 * total("wetting"))),
+                    ("rain_insufficient", total("trace")),
+                    ("waterlogging_risk", total("flooding")),
+                ])
+            },
+            None => HashMap::new(),
+        };
+
+        let agricultural_context = AgriculturalFuzzyContext {
+            seasonal_membership: seasonal_membership(measurement),
+            weather_pattern_membership,
+            irrigation_status_membership,
+            ..empty_agricultural_context()
+        };
+
+        let temporal_fuzzy_info = temporal_fuzzy_info(
+            measurement,
+            rainfall.rate_mm_h,
+            aligned_data,
+            &SensorType::RadarPrecipitation,
+            self.nominal_interval,
+            |m| self.extract_rainfall(m).ok().map(|r| r.rate_mm_h),
+        );
+
+        Ok(FuzzyEvidence {
+            crisp_value: rainfall.rate_mm_h,
+            linguistic_terms,
+            reliability: self.assess_fuzzy_quality(measurement),
+            timestamp: measurement.timestamp,
+            sensor_type: SensorType::RadarPrecipitation,
+            agricultural_context,
+            spatial_fuzzy_info: spatial_fuzzy_info(measurement, aligned_data, 2_000.0),
+            temporal_fuzzy_info,
+        })
+    }
+
+    fn get_linguistic_variables(&self) -> HashMap<String, LinguisticVariable> {
+        self.linguistic_variables.clone()
+    }
+    
+    fn replace_linguistic_variable(&mut self, key: &str, variable: LinguisticVariable) -> bool {
+        replace_known_variable(&mut self.linguistic_variables, key, variable)
+    }
+
+    fn assess_fuzzy_quality(&self, measurement: &TimestampedMeasurement) -> FuzzyReliability {
+        let Ok(rainfall) = self.extract_rainfall(measurement) else {
+            return FuzzyReliability::from_quality_score(0.0, 0.0, 0.0, 1.0, 0.0);
+        };
+
+        // The beam rises above shallow rain with range
+        let range = rainfall.range_km.map_or(0.8, |km| (1.0 - km / self.maximum_useful_range_km).clamp(0.2, 1.0));
+        // The Z–R relationship is tuned for rain; ice and mixed phase scatter differently
+        let hydrometeor = match rainfall.precipitation_type {
+            Some(PrecipitationType::Hail) => 0.3,
+            Some(PrecipitationType::Snow) | Some(PrecipitationType::Sleet) => 0.5,
+            _ => 1.0,
+        };
+        // Melting layer near the ground inflates reflectivity (bright band)
+        let bright_band = 1.0 - 0.4 * MembershipFunction::Triangular(-1.0, 1.0, 3.0).evaluate(measurement.environmental_data.temperature);
+        let calibration = age_factor(measurement.sensor_metadata.calibration_date, measurement, self.calibration_validity_days);
+        let maintenance = age_factor(measurement.sensor_metadata.last_maintenance, measurement, 365.0);
+
+        let weather_condition = hydrometeor * bright_band;
+        let quality = quality_flag_factor(&measurement.quality_flags) * (range + weather_condition + calibration) / 3.0;
+        let winter = seasonal_membership(measurement).get("winter").copied().unwrap_or(0.0);
+
+        FuzzyReliability::from_quality_score(
+            quality,
+            1.0 - 0.3 * winter,
+            weather_condition,
+            1.0,
+            maintenance,
+        )
+    }
+}
+
+/// Lightning detection network evidence for convective hazard
+pub struct LightningDetectorEvidenceProcessor {
+    linguistic_variables: HashMap<String, LinguisticVariable>,
+    /// Distance at which detection efficiency has halved (km)
+    detection_efficiency_range_km: f64,
+    nominal_interval: f64,
+}
+
+/// Stroke report extracted from a lightning measurement
+struct LightningReport {
+    distance_km: f64,
+    flash_rate_per_min: Option<f64>,
+    peak_current_ka: Option<f64>,
+}
+
+impl LightningDetectorEvidenceProcessor {
+    pub fn new() -> Self {
+        let mut linguistic_variables = HashMap::new();
+
+        linguistic_variables.insert("strike_distance".to_string(), linguistic_variable(
+            "Distance to Strike (km)", (0.0, 500.0), vec![
+                ("overhead", MembershipFunction::Trapezoidal(0.0, 0.0, 3.0, 6.0)),
+                ("near", MembershipFunction::Trapezoidal(3.0, 6.0, 10.0, 16.0)),
+                ("vicinity", MembershipFunction::Trapezoidal(10.0, 16.0, 30.0, 40.0)),
+                ("distant", MembershipFunction::Trapezoidal(30.0, 40.0, 500.0, 500.0)),
+            ],
+        ));
+
+        linguistic_variables.insert("flash_rate".to_string(), linguistic_variable(
+            "Flash Rate (per minute)", (0.0, 100.0), vec![
+                ("isolated", MembershipFunction::Trapezoidal(0.0, 0.0, 1.0, 3.0)),
+                ("active", MembershipFunction::Triangular(1.0, 5.0, 12.0)),
+                ("intense", MembershipFunction::Trapezoidal(8.0, 15.0, 100.0, 100.0)),
+            ],
+        ));
+
+        Self {
+            linguistic_variables,
+            detection_efficiency_range_km: 300.0,
+            nominal_interval: 60.0,
+        }
+    }
+
+    fn extract_report(&self, measurement: &TimestampedMeasurement) -> Result<LightningReport, AppError> {
+        match &measurement.value {
+            crate::data_fusion::MeasurementValue::Scalar(distance_km) => Ok(LightningReport {
+                distance_km: *distance_km,
+                flash_rate_per_min: None,
+                peak_current_ka: None,
+            }),
+            crate::data_fusion::MeasurementValue::Custom(values) => Ok(LightningReport {
+                distance_km: values
+                    .get("distance_km")
+                    .copied()
+                    .ok_or_else(|| AppError::validation("Lightning measurement needs distance_km"))?,
+                flash_rate_per_min: values.get("flash_rate_per_min").copied(),
+                peak_current_ka: values.get("peak_current_ka").copied(),
+            }),
+            _ => Err(AppError::validation("Unsupported lightning measurement type")),
+        }
+    }
+
+    fn detection_efficiency(&self, report: &LightningReport) -> f64 {
+        let range = 1.0 / (1.0 + (report.distance_km / self.detection_efficiency_range_km).powi(2));
+        // Weak strokes are missed more often
+        let current = report.peak_current_ka.map_or(1.0, |ka| (0.5 + ka.abs() / 20.0).min(1.0));
+        range * current
+    }
+}
+
+impl Default for LightningDetectorEvidenceProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl FuzzyEvidenceProcessor for LightningDetectorEvidenceProcessor {
+    async fn process_measurement(
+        &self,
+        measurement: &TimestampedMeasurement,
+        aligned_data: &AlignedSensorData,
+    ) -> Result<FuzzyEvidence, AppError> {
+        let report = self.extract_report(measurement)?;
+
+        let mut linguistic_terms = HashMap::new();
+        fuzzify(&self.linguistic_variables, "strike_distance", report.distance_km, &mut linguistic_terms);
+        if let Some(rate) = report.flash_rate_per_min {
+            fuzzify(&self.linguistic_variables, "flash_rate", rate, &mut linguistic_terms);
+        }
+
+        let distance = |name: &str| term(&linguistic_terms, &format!("strike_distance_{}", name));
+        let thunderstorm = distance("overhead").max(distance("near")).max(0.5 * distance("vicinity"));
+        let agricultural_context = AgriculturalFuzzyContext {
+            seasonal_membership: seasonal_membership(measurement),
+            weather_pattern_membership: memberships(&[
+                ("thunderstorm", thunderstorm),
+                ("severe_convection", thunderstorm.min(term(&linguistic_terms, "flash_rate_intense"))),
+            ]),
+            ..empty_agricultural_context()
+        };
+
+        let temporal_fuzzy_info = temporal_fuzzy_info(
+            measurement,
+            report.distance_km,
+            aligned_data,
+            &SensorType::LightningDetector,
+            self.nominal_interval,
+            |m| self.extract_report(m).ok().map(|r| r.distance_km),
+        );
+
+        Ok(FuzzyEvidence {
+            crisp_value: report.distance_km,
+            linguistic_terms,
+            reliability: self.assess_fuzzy_quality(measurement),
+            timestamp: measurement.timestamp,
+            sensor_type: SensorType::LightningDetector,
+            agricultural_context,
+            spatial_fuzzy_info: spatial_fuzzy_info(measurement, aligned_data, 10_000.0),
+            temporal_fuzzy_info,
+        })
+    }
+
+    fn get_linguistic_variables(&self) -> HashMap<String, LinguisticVariable> {
+        self.linguistic_variables.clone()
+    }
+    
+    fn replace_linguistic_variable(&mut self, key: &str, variable: LinguisticVariable) -> bool {
+        replace_known_variable(&mut self.linguistic_variables, key, variable)
+    }
+
+    fn assess_fuzzy_quality(&self, measurement: &TimestampedMeasurement) -> FuzzyReliability {
+        let efficiency = self.extract_report(measurement).map_or(0.0, |report| self.detection_efficiency(&report));
+        let maintenance = power_factor(&measurement.sensor_metadata.power_status) * measurement.sensor_metadata.communication_quality.clamp(0.0, 1.0);
+        let quality = efficiency * quality_flag_factor(&measurement.quality_flags);
+
+        FuzzyReliability::from_quality_score(
+            quality,
+            0.9,
+            // Electromagnetic interference raises the false-alarm rate
+            1.0 - 0.5 * measurement.environmental_data.electromagnetic_interference.unwrap_or(0.0).clamp(0.0, 1.0),
+            1.0, // Independent of the crop
+            maintenance,
+        )
+    }
+}
+
+/// Wind profiler evidence for spray drift and inversion risk
+pub struct WindProfilerEvidenceProcessor {
+    linguistic_variables: HashMap<String, LinguisticVariable>,
+    range_gate_spacing_m: f64,
+    calibration_validity_days: f64,
+    nominal_interval: f64,
+}
+
+impl WindProfilerEvidenceProcessor {
+    pub fn new() -> Self {
+        let mut linguistic_variables = HashMap::new();
+
+        linguistic_variables.insert("wind_speed".to_string(), wind_speed_variable());
+
+        linguistic_variables.insert("wind_shear".to_string(), linguistic_variable(
+            "Vertical Wind Shear (m/s per 100 m)", (0.0, 10.0), vec![
+                ("weak", MembershipFunction::Trapezoidal(0.0, 0.0, 0.5, 1.5)),
+                ("moderate", MembershipFunction::Triangular(1.0, 2.5, 4.0)),
+                ("strong", MembershipFunction::Trapezoidal(3.0, 5.0, 10.0, 10.0)),
+            ],
+        ));
+
+        Self {
+            linguistic_variables,
+            range_gate_spacing_m: 100.0,
+            calibration_validity_days: 365.0,
+            nominal_interval: 600.0,
+        }
+    }
+
+    /// Lowest-gate wind speed and, for a profile, shear per 100 m
+    fn extract_wind(&self, measurement: &TimestampedMeasurement) -> Result<(f64, Option<f64>), AppError> {
+        use crate::data_fusion::MeasurementValue;
+
+        match &measurement.value {
+            MeasurementValue::WindVector { speed, .. } => Ok((*speed, None)),
+            // Speeds at successive range gates, lowest first
+            MeasurementValue::Vector(profile) if !profile.is_empty() => {
+                let shear = (profile.len() > 1).then(|| {
+                    let depth = (profile.len() - 1) as f64 * self.range_gate_spacing_m;
+                    (profile[profile.len() - 1] - profile[0]).abs() / depth * 100.0
+                });
+                Ok((profile[0], shear))
+            },
+            MeasurementValue::Custom(values) => values
+                .get("wind_speed")
+                .map(|speed| (*speed, values.get("wind_shear").copied()))
+                .ok_or_else(|| AppError::validation("Wind profiler measurement needs wind_speed")),
+            _ => Err(AppError::validation("Unsupported wind profiler measurement type")),
+        }
+    }
+}
+
+impl Default for WindProfilerEvidenceProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl FuzzyEvidenceProcessor for WindProfilerEvidenceProcessor {
+    async fn process_measurement(
+        &self,
+        measurement: &TimestampedMeasurement,
+        aligned_data: &AlignedSensorData,
+    ) -> Result<FuzzyEvidence, AppError> {
+        let (speed, shear) = self.extract_wind(measurement)?;
+
+        let mut linguistic_terms = HashMap::new();
+        fuzzify(&self.linguistic_variables, "wind_speed", speed, &mut linguistic_terms);
+        if let Some(shear) = shear {
+            fuzzify(&self.linguistic_variables, "wind_shear", shear, &mut linguistic_terms);
+        }
+
+        let temporal_fuzzy_info = temporal_fuzzy_info(
+            measurement,
+            speed,
+            aligned_data,
+            &SensorType::WindProfiler,
+            self.nominal_interval,
+            |m| self.extract_wind(m).ok().map(|(s, _)| s),
+        );
+
+        // Calm nights favour surface inversions that carry spray off target
+        let wind = |name: &str| term(&linguistic_terms, &format!("wind_speed_{}", name));
+        let night = ["night", "evening"]
+            .iter()
+            .map(|period| temporal_fuzzy_info.time_of_day_membership.get(*period).copied().unwrap_or(0.0))
+            .fold(0.0, f64::max);
+        let inversion_risk = wind("calm").max(0.5 * wind("light")).min(night);
+        let agricultural_context = AgriculturalFuzzyContext {
+            seasonal_membership: seasonal_membership(measurement),
+            weather_pattern_membership: memberships(&[
+                ("spray_window", wind("light").min(1.0 - inversion_risk)),
+                ("drift_risk", wind("moderate").max(wind("strong")).max(wind("gale"))),
+                ("inversion_risk", inversion_risk),
+            ]),
+            ..empty_agricultural_context()
+        };
+
+        Ok(FuzzyEvidence {
+            crisp_value: speed,
+            linguistic_terms,
+            reliability: self.assess_fuzzy_quality(measurement),
+            timestamp: measurement.timestamp,
+            sensor_type: SensorType::WindProfiler,
+            agricultural_context,
+            spatial_fuzzy_info: spatial_fuzzy_info(measurement, aligned_data, 20_000.0),
+            temporal_fuzzy_info,
+        })
+    }
+
+    fn get_linguistic_variables(&self) -> HashMap<String, LinguisticVariable> {
+        self.linguistic_variables.clone()
+    }
+    
+    fn replace_linguistic_variable(&mut self, key: &str, variable: LinguisticVariable) -> bool {
+        replace_known_variable(&mut self.linguistic_variables, key, variable)
+    }
+
+    fn assess_fuzzy_quality(&self, measurement: &TimestampedMeasurement) -> FuzzyReliability {
+        let valid = if self.extract_wind(measurement).is_ok() { 1.0 } else { 0.0 };
+        let calibration = age_factor(measurement.sensor_metadata.calibration_date, measurement, self.calibration_validity_days);
+        let maintenance = age_factor(measurement.sensor_metadata.last_maintenance, measurement, 180.0)
+            * power_factor(&measurement.sensor_metadata.power_status);
+
+        // Falling hydrometeors dominate the clear-air return in rain
+        let precipitation = 1.0 - 0.4 * MembershipFunction::Trapezoidal(90.0, 97.0, f64::MAX, f64::MAX).evaluate(measurement.environmental_data.humidity);
+        // Migrating birds contaminate night-time returns in spring and autumn
+        let seasons = seasonal_membership(measurement);
+        let migration = seasons.get("spring").copied().unwrap_or(0.0).max(seasons.get("autumn").copied().unwrap_or(0.0));
+        let night = time_of_day_membership(measurement).get("night").copied().unwrap_or(0.0);
+        let seasonal = 1.0 - 0.3 * migration * night;
+
+        let quality = valid * quality_flag_factor(&measurement.quality_flags) * (calibration + maintenance + precipitation) / 3.0;
+
+        FuzzyReliability::from_quality_score(quality, seasonal, precipitation, 1.0, maintenance)
+    }
+}
+
+/// Moored buoy evidence for maritime air masses reaching coastal farmland
+pub struct WeatherBuoyEvidenceProcessor {
+    linguistic_variables: HashMap<String, LinguisticVariable>,
+    /// Interval after which biofouling degrades the hull-mounted sensors (days)
+    biofouling_interval_days: f64,
+    nominal_interval: f64,
+}
+
+impl WeatherBuoyEvidenceProcessor {
+    pub fn new() -> Self {
+        let mut linguistic_variables = HashMap::new();
+
+        linguistic_variables.insert("sea_surface_temperature".to_string(), linguistic_variable(
+            "Sea Surface Temperature (°C)", (-2.0, 35.0), vec![
+                ("cold", MembershipFunction::Trapezoidal(-2.0, -2.0, 8.0, 12.0)),
+                ("temperate", MembershipFunction::Triangular(10.0, 15.0, 20.0)),
+                ("warm", MembershipFunction::Trapezoidal(18.0, 22.0, 35.0, 35.0)),
+            ],
+        ));
+
+        linguistic_variables.insert("wind_speed".to_string(), wind_speed_variable());
+
+        linguistic_variables.insert("pressure".to_string(), linguistic_variable(
+            "Sea-Level Pressure (hPa)", (900.0, 1060.0), vec![
+                ("deep_low", MembershipFunction::Trapezoidal(900.0, 900.0, 980.0, 990.0)),
+                ("low", MembershipFunction::Triangular(985.0, 1000.0, 1010.0)),
+                ("normal", MembershipFunction::Triangular(1005.0, 1013.0, 1021.0)),
+                ("high", MembershipFunction::Trapezoidal(1016.0, 1025.0, 1060.0, 1060.0)),
+            ],
+        ));
+
+        linguistic_variables.insert("wave_height".to_string(), linguistic_variable(
+            "Significant Wave Height (m)", (0.0, 15.0), vec![
+                ("calm", MembershipFunction::Trapezoidal(0.0, 0.0, 0.1, 0.5)),
+                ("slight", MembershipFunction::Triangular(0.3, 0.9, 1.5)),
+                ("moderate", MembershipFunction::Triangular(1.0, 2.0, 3.0)),
+                ("rough", MembershipFunction::Triangular(2.5, 3.5, 5.0)),
+                ("high", MembershipFunction::Trapezoidal(4.0, 6.0, 15.0, 15.0)),
+            ],
+        ));
+
+        Self {
+            linguistic_variables,
+            biofouling_interval_days: 180.0,
+            nominal_interval: 600.0,
+        }
+    }
+
+    /// Crisp value in the variable's units and the variable it belongs to
+    fn extract_buoy_parameter(&self, measurement: &TimestampedMeasurement) -> Result<(f64, &'static str), AppError> {
+        use crate::data_fusion::{MeasurementValue, PressureUnit, TemperatureScale};
+
+        match &measurement.value {
+            MeasurementValue::Temperature { value, scale, .. } => {
+                let celsius = match scale {
+                    TemperatureScale::Celsius => *value,
+                    TemperatureScale::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
+                    TemperatureScale::Kelvin => value - 273.15,
+                };
+                Ok((celsius, "sea_surface_temperature"))
+            },
+            MeasurementValue::WindVector { speed, .. } => Ok((*speed, "wind_speed")),
+            MeasurementValue::Pressure { value, unit } => {
+                let hectopascal = match unit {
+                    PressureUnit::Pascal => value / 100.0,
+                    PressureUnit::Hectopascal | PressureUnit::Millibar => *value,
+                    PressureUnit::InchesHg => value * 33.8639,
+                    PressureUnit::MillimetersHg => value * 1.33322,
+                };
+                Ok((hectopascal, "pressure"))
+            },
+            MeasurementValue::Custom(values) => values
+                .get("wave_height_m")
+                .map(|height| (*height, "wave_height"))
+                .ok_or_else(|| AppError::validation("Buoy measurement needs wave_height_m")),
+            _ => Err(AppError::validation("Unsupported buoy measurement type")),
+        }
+    }
+
+    /// Latest significant wave height reported by this buoy, if any
+    fn latest_wave_height(&self, measurement: &TimestampedMeasurement, aligned_data: Option<&AlignedSensorData>) -> Option<f64> {
+        if let Ok((height, "wave_height")) = self.extract_buoy_parameter(measurement) {
+            return Some(height);
+        }
+        aligned_data?
+            .aligned_measurements
+            .get(&SensorType::AutonomousWeatherBuoy)?
+            .iter()
+            .filter(|m| m.sensor_metadata.sensor_id == measurement.sensor_metadata.sensor_id && m.timestamp <= measurement.timestamp)
+            .filter_map(|m| match self.extract_buoy_parameter(m) {
+                Ok((height, "wave_height")) => Some((m.timestamp, height)),
+                _ => None,
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, height)| height)
+    }
+
+    /// Steep seas tilt the hull and spray wets the sensors
+    fn sea_state_reliability(&self, wave_height: Option<f64>) -> f64 {
+        wave_height.map_or(0.9, |height| 1.0 - 0.5 * MembershipFunction::Trapezoidal(2.5, 5.0, f64::MAX, f64::MAX).evaluate(height))
+    }
+
+    /// Reliability with the sea state folded into the overall quality score, so
+    /// the buoy's own wave reports and those in the aligned data are treated alike
+    fn reliability_in_sea_state(&self, measurement: &TimestampedMeasurement, wave_height: Option<f64>) -> FuzzyReliability {
+        let valid = if self.extract_buoy_parameter(measurement).is_ok() { 1.0 } else { 0.0 };
+        let biofouling = age_factor(measurement.sensor_metadata.last_maintenance, measurement, self.biofouling_interval_days);
+        let telemetry = measurement.sensor_metadata.communication_quality.clamp(0.0, 1.0);
+        let maintenance = biofouling * power_factor(&measurement.sensor_metadata.power_status);
+        let sea_state = self.sea_state_reliability(wave_height);
+
+        let quality = valid * quality_flag_factor(&measurement.quality_flags) * (maintenance + telemetry + sea_state) / 3.0;
+
+        FuzzyReliability::from_quality_score(quality, 0.9, sea_state, 1.0, maintenance)
+    }
+}
+
+impl Default for WeatherBuoyEvidenceProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl FuzzyEvidenceProcessor for WeatherBuoyEvidenceProcessor {
+    async fn process_measurement(
+        &self,
+        measurement: &TimestampedMeasurement,
+        aligned_data: &AlignedSensorData,
+    ) -> Result<FuzzyEvidence, AppError> {
+        let (crisp_value, variable) = self.extract_buoy_parameter(measurement)?;
+
+        let mut linguistic_terms = HashMap::new();
+        fuzzify(&self.linguistic_variables, variable, crisp_value, &mut linguistic_terms);
+
+        let level = |name: &str| term(&linguistic_terms, &format!("{}_{}", variable, name));
+        let weather_pattern_membership = match variable {
+            "pressure" => memberships(&[
+                ("unsettled", level("deep_low").max(level("low"))),
+                ("settled", level("high").max(0.5 * level("normal"))),
+            ]),
+            "wind_speed" => memberships(&[("onshore_storm_risk", level("strong").max(level("gale")))]),
+            "wave_height" => memberships(&[("onshore_storm_risk", level("rough").max(level("high")))]),
+            _ => memberships(&[("warm_advection", level("warm")), ("cool_advection", level("cold"))]),
+        };
+        let agricultural_context = AgriculturalFuzzyContext {
+            seasonal_membership: seasonal_membership(measurement),
+            weather_pattern_membership,
+            ..empty_agricultural_context()
+        };
+
+        let mut spatial_fuzzy_info = spatial_fuzzy_info(measurement, aligned_data, 50_000.0);
+        spatial_fuzzy_info.microclimate_membership = memberships(&[("marine", 1.0)]);
+
+        let temporal_fuzzy_info = temporal_fuzzy_info(
+            measurement,
+            crisp_value,
+            aligned_data,
+            &SensorType::AutonomousWeatherBuoy,
+            self.nominal_interval,
+            |m| self.extract_buoy_parameter(m).ok().filter(|(_, v)| *v == variable).map(|(value, _)| value),
+        );
+
+        Ok(FuzzyEvidence {
+            crisp_value,
+            linguistic_terms,
+            reliability: self.reliability_in_sea_state(measurement, self.latest_wave_height(measurement, Some(aligned_data))),
+            timestamp: measurement.timestamp,
+            sensor_type: SensorType::AutonomousWeatherBuoy,
+            agricultural_context,
+            spatial_fuzzy_info,
+            temporal_fuzzy_info,
+        })
+    }
+
+    fn get_linguistic_variables(&self) -> HashMap<String, LinguisticVariable> {
+        self.linguistic_variables.clone()
+    }
+    
+    fn replace_linguistic_variable(&mut self, key: &str, variable: LinguisticVariable) -> bool {
+        replace_known_variable(&mut self.linguistic_variables, key, variable)
+    }
+
+    fn assess_fuzzy_quality(&self, measurement: &TimestampedMeasurement) -> FuzzyReliability {
+        self.reliability_in_sea_state(measurement, self.latest_wave_height(measurement, None))
+    }
+}
+
+impl FuzzyEvidence {
+    /// Compute fuzzy intersection with another evidence
+    pub fn fuzzy_intersection(&self, other: &FuzzyEvidence) -> f64 {
+        let mut intersection = 0.0;
+        let mut count = 0;
+        
+        for (term, membership1) in &self.linguistic_terms {
+            if let Some(membership2) = other.linguistic_terms.get(term) {
+                intersection += membership1.min(*membership2);
+                count += 1;
+            }
+        }
+        
+        if count > 0 { intersection / count as f64 } else { 0.0 }
+    }
+    
+    /// Compute fuzzy union with another evidence
+    pub fn fuzzy_union(&self, other: &FuzzyEvidence) -> f64 {
+        let mut union = 0.0;
+        let mut count = 0;
+        
+        for (term, membership1) in &self.linguistic_terms {
+            if let Some(membership2) = other.linguistic_terms.get(term) {
+                union += membership1.max(*membership2);
+                count += 1;
+            }
+        }
+        
+        if count > 0 { union / count as f64 } else { 0.0 }
+    }
+    
+    /// Check if evidence has multiple peaks (multimodal)
+    pub fn has_multiple_peaks(&self) -> bool {
+        let mut peaks = 0;
+        let memberships: Vec<f64> = self.linguistic_terms.values().cloned().collect();
+        
+        for i in 1..memberships.len().saturating_sub(1) {
+            if memberships[i] > memberships[i-1] && memberships[i] > memberships[i+1] && memberships[i] > 0.5 {
+                peaks += 1;
+            }
+        }
+        
+        peaks > 1
+    }
+    
+    /// Compute fuzzy entropy of the evidence
+    pub fn compute_fuzzy_entropy(&self) -> f64 {
+        let mut entropy = 0.0;
+        
+        for membership in self.linguistic_terms.values() {
+            if *membership > 0.0 {
+                entropy -= membership * membership.log2();
+            }
+        }
+        
+        entropy
+    }
+    
+    /// Assess linguistic consistency
+    pub fn assess_linguistic_consistency(&self) -> f64 {
+        // Check if linguistic terms are consistent (not contradictory)
+        let mut consistency_score: f64 = 1.0;
+        
+        // Look for contradictory terms (e.g., "very_hot" and "very_cold" both high)
+        let contradictory_pairs = vec![
+            ("very_hot", "very_cold"),
+            ("very_dry", "very_humid"),
+            ("excellent", "very_poor"),
+        ];
+        
+        for (term1, term2) in contradictory_pairs {
+            let membership1 = self.linguistic_terms.get(term1).unwrap_or(&0.0);
+            let membership2 = self.linguistic_terms.get(term2).unwrap_or(&0.0);
+            
+            // Penalize if both contradictory terms have high membership
+            if *membership1 > 0.7 && *membership2 > 0.7 {
+                consistency_score -= 0.3;
+            }
+        }
+        
+        consistency_score.max(0.0)
+    }
+    
+    /// Assess temporal quality
+    pub fn assess_temporal_quality(&self) -> f64 {
+        // Assess temporal quality based on recency and synchronization
+        let recency_score = self.temporal_fuzzy_info.recency_membership
+            .get("recent").unwrap_or(&0.5);
+        let sync_score = self.temporal_fuzzy_info.synchronization_membership
+            .get("well_synchronized").unwrap_or(&0.5);
+        
+        (recency_score + sync_score) / 2.0
+    }
+}
+
+// Sensor types whose readings enter fusion without a fuzzy interpretation
+pub struct AtomicClockEvidenceProcessor;
+pub struct AgriculturalIoTEvidenceProcessor;
+pub struct DroneMultispectralEvidenceProcessor;
+pub struct GroundTruthEvidenceProcessor;
+
+impl AtomicClockEvidenceProcessor {
+    pub fn new() -> Self { Self }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_fusion::{test_fixtures, MeasurementValue, TemperatureScale};
+    use crate::data_fusion::temporal_alignment::TemporalQualityMetrics;
+    use crate::data_fusion::terrain::ElevationRaster;
+
+    // 2024-07-01 12:00 UTC
+    const MIDSUMMER: f64 = 1_719_835_200.0;
+
+    fn measurement(value: MeasurementValue, timestamp: f64) -> TimestampedMeasurement {
+        let installed = DateTime::<Utc>::from_timestamp(timestamp as i64 - 30 * 86400, 0).unwrap();
+        let metadata = test_fixtures::sensor_metadata("probe-1", (52.0, 0.0, 80.0), installed);
+        let mut measurement = test_fixtures::measurement(value, timestamp, metadata);
+        measurement.temporal_uncertainty = Some(0.5);
+        measurement.environmental_data.temperature = 22.0;
+        measurement
+    }
+
+    fn aligned(measurements: Vec<(SensorType, TimestampedMeasurement)>) -> AlignedSensorData {
+        let mut aligned_measurements: HashMap<SensorType, Vec<TimestampedMeasurement>> = HashMap::new();
+        for (sensor_type, m) in measurements {
+            aligned_measurements.entry(sensor_type).or_default().push(m);
+        }
+        AlignedSensorData {
+            aligned_measurements,
+            reference_sensor: SensorType::WeatherStation,
+            alignment_quality_metrics: TemporalQualityMetrics::default(),
+            temporal_window: (Utc::now(), Utc::now()),
+        }
+    }
+
+    fn soil(value: f64, soil_type: &str) -> MeasurementValue {
+        MeasurementValue::SoilMoisture { value, depth_cm: 30.0, soil_type: soil_type.to_string() }
+    }
+
+    fn multispectral(blue: f64, red: f64, nir: f64) -> MeasurementValue {
+        MeasurementValue::MultispectralImage { bands: vec![blue, red, nir], wavelengths: vec![490.0, 665.0, 842.0] }
+    }
+
+    #[test]
+    fn test_shoulder_terms_are_full_at_universe_edge() {
+        assert_eq!(MembershipFunction::Trapezoidal(0.0, 0.0, 2.0, 5.0).evaluate(0.0), 1.0);
+        assert_eq!(MembershipFunction::Trapezoidal(30.0, 50.0, 100.0, 100.0).evaluate(100.0), 1.0);
+        assert_eq!(MembershipFunction::Triangular(3.0, 7.0, 12.0).evaluate(3.0), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_soil_moisture_interpreted_against_texture() {
+        let processor = SoilSensorEvidenceProcessor::new();
+        let data = aligned(Vec::new());
+
+        // The same water content is comfortable in sand but below wilting point in clay
+        let sand = processor.process_measurement(&measurement(soil(10.0, "Sand"), MIDSUMMER), &data).await.unwrap();
+        let clay = processor.process_measurement(&measurement(soil(10.0, "clay"), MIDSUMMER), &data).await.unwrap();
+        assert!((sand.crisp_value - 0.10).abs() < 1e-12);
+        assert_eq!(sand.linguistic_terms["plant_available_water_adequate"], 1.0);
+        assert_eq!(clay.linguistic_terms["plant_available_water_wilting"], 1.0);
+        assert_eq!(clay.agricultural_context.irrigation_status_membership["irrigation_needed"], 1.0);
+        assert_eq!(sand.agricultural_context.seasonal_membership["summer"].round(), 1.0);
+
+        let waterlogged = processor.process_measurement(&measurement(soil(0.44, "loam"), MIDSUMMER), &data).await.unwrap();
+        assert!(waterlogged.agricultural_context.irrigation_status_membership["over_irrigated"] > 0.9);
+    }
+
+    #[tokio::test]
+    async fn test_frozen_soil_and_wetting_front() {
+        let processor = SoilSensorEvidenceProcessor::new();
+        let mut frozen = measurement(soil(0.25, "loam"), MIDSUMMER);
+        frozen.environmental_data.temperature = -5.0;
+        let thawed = measurement(soil(0.25, "loam"), MIDSUMMER);
+        assert!(processor.assess_fuzzy_quality(&frozen).compute_overall_score() < processor.assess_fuzzy_quality(&thawed).compute_overall_score());
+
+        let history = (1..=4)
+            .map(|hour| (SensorType::SoilSensor, measurement(soil(0.17, "loam"), MIDSUMMER - hour as f64 * 3600.0)))
+            .collect();
+        let evidence = processor.process_measurement(&measurement(soil(0.25, "loam"), MIDSUMMER), &aligned(history)).await.unwrap();
+        assert!(evidence.agricultural_context.irrigation_status_membership["recently_wetted"] > 0.99);
+        assert!(evidence.temporal_fuzzy_info.historical_consistency_membership["anomalous"] > 0.9);
+        assert_eq!(evidence.temporal_fuzzy_info.time_of_day_membership["midday"], 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_radar_soil_moisture_weighted_by_retrieval_uncertainty() {
+        let processor = SatelliteRadarEvidenceProcessor::new();
+        let data = aligned(Vec::new());
+        let mut precise = measurement(soil(0.10, "clay"), MIDSUMMER);
+        precise.uncertainty = 0.02;
+        let mut vague = precise.clone();
+        vague.uncertainty = 0.09;
+
+        let evidence = processor.process_measurement(&precise, &data).await.unwrap();
+        assert_eq!(evidence.sensor_type, SensorType::SatelliteRadar);
+        assert_eq!(evidence.agricultural_context.irrigation_status_membership["irrigation_needed"], 1.0);
+        assert!(processor.assess_fuzzy_quality(&precise).compute_overall_score() > processor.assess_fuzzy_quality(&vague).compute_overall_score());
+    }
+
+    #[tokio::test]
+    async fn test_satellite_ndvi_cloud_screening_and_senescence() {
+        let processor = SatelliteImageryEvidenceProcessor::new();
+        let earlier = measurement(multispectral(0.04, 0.03, 0.50), MIDSUMMER - 10.0 * 86400.0);
+        let data = aligned(vec![(SensorType::SatelliteImagery, earlier)]);
+
+        let clear = processor.process_measurement(&measurement(multispectral(0.04, 0.10, 0.40), MIDSUMMER), &data).await.unwrap();
+        assert!((clear.crisp_value - 0.6).abs() < 1e-12);
+        assert_eq!(clear.linguistic_terms["cloud_contamination_clear"], 1.0);
+        assert!(clear.agricultural_context.growth_stage_membership["senescence"] > 0.9);
+
+        // Reflectance scaled to 10000 with a bright blue band
+        let cloudy = measurement(multispectral(3500.0, 3000.0, 3600.0), MIDSUMMER);
+        let cloudy = processor.process_measurement(&cloudy, &data).await.unwrap();
+        assert_eq!(cloudy.linguistic_terms["cloud_contamination_cloudy"], 1.0);
+        assert!(cloudy.reliability.compute_overall_score() < clear.reliability.compute_overall_score());
+    }
+
+    #[tokio::test]
+    async fn test_radar_reflectivity_and_lightning_distance() {
+        let radar = RadarPrecipitationEvidenceProcessor::new();
+        // Marshall–Palmer: 40 dBZ is about 11.5 mm/h
+        assert!((radar.rain_rate_from_reflectivity(40.0) - 11.53).abs() < 0.01);
+        let evidence = radar.process_measurement(&measurement(MeasurementValue::Scalar(40.0), MIDSUMMER), &aligned(Vec::new())).await.unwrap();
+        assert_eq!(evidence.agricultural_context.weather_pattern_membership["convective"], 1.0);
+
+        let lightning = LightningDetectorEvidenceProcessor::new();
+        let strike = MeasurementValue::Custom(HashMap::from([("distance_km".to_string(), 4.0), ("flash_rate_per_min".to_string(), 20.0)]));
+        let evidence = lightning.process_measurement(&measurement(strike, MIDSUMMER), &aligned(Vec::new())).await.unwrap();
+        assert!(evidence.agricultural_context.weather_pattern_membership["severe_convection"] > 0.6);
+        assert!(lightning.process_measurement(&measurement(soil(0.2, "loam"), MIDSUMMER), &aligned(Vec::new())).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_profiler_inversion_and_buoy_sea_state() {
+        let profiler = WindProfilerEvidenceProcessor::new();
+        // Local midnight on the Greenwich meridian, near-calm with a weak profile
+        let night = measurement(MeasurementValue::Vector(vec![0.4, 0.8, 1.2]), MIDSUMMER + 12.0 * 3600.0);
+        let evidence = profiler.process_measurement(&night, &aligned(Vec::new())).await.unwrap();
+        assert_eq!(evidence.agricultural_context.weather_pattern_membership["inversion_risk"], 1.0);
+        assert!((evidence.linguistic_terms["wind_shear_weak"] - 1.0).abs() < 1e-12);
+
+        let buoy = WeatherBuoyEvidenceProcessor::new();
+        let waves = |height: f64, t: f64| measurement(MeasurementValue::Custom(HashMap::from([("wave_height_m".to_string(), height)])), t);
+        let calm = aligned(vec![(SensorType::AutonomousWeatherBuoy, waves(0.5, MIDSUMMER - 600.0))]);
+        let storm = aligned(vec![(SensorType::AutonomousWeatherBuoy, waves(6.0, MIDSUMMER - 600.0))]);
+        let wind = measurement(MeasurementValue::WindVector { speed: 15.0, direction: 240.0, gust_speed: None }, MIDSUMMER);
+        let in_calm = buoy.process_measurement(&wind, &calm).await.unwrap();
+        let in_storm = buoy.process_measurement(&wind, &storm).await.unwrap();
+        assert!(in_storm.reliability.weather_condition_reliability < in_calm.reliability.weather_condition_reliability);
+        assert!(in_storm.reliability.compute_overall_score() < in_calm.reliability.compute_overall_score());
+        assert_eq!(in_calm.agricultural_context.weather_pattern_membership["onshore_storm_risk"], 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_terrain_lapse_rate_and_slope_shading() {
+        // A north-facing 400 m/km slope through the test location, 300 m high there
+        let spacing = 1.0 / 1200.0;
+        let heights = (0..121 * 121)
+            .map(|k| {
+                let latitude = 52.05 - (k / 121) as f64 * spacing;
+                (300.0 + 400.0 * (52.0 - latitude) * 111.2) as f32
+            })
+            .collect();
+        let mut model = DigitalElevationModel::new();
+        model.add_raster(ElevationRaster::new(52.05, -0.05, spacing, spacing, 121, 121, heights).unwrap());
+        let dem = Arc::new(model);
+
+        // Reported at sea level, the 300 m station reads about 2 °C warmer
+        let station = WeatherStationEvidenceProcessor::new().with_terrain(dem.clone(), Some(0.0));
+        let reading = MeasurementValue::Temperature { value: 10.0, scale: TemperatureScale::Celsius, sensor_id: "t".to_string() };
+        let evidence = station.process_measurement(&measurement(reading, MIDSUMMER), &aligned(Vec::new())).await.unwrap();
+        assert!((evidence.crisp_value - 11.95).abs() < 0.05);
+        assert_eq!(evidence.spatial_fuzzy_info.terrain_membership["steep"], 1.0);
+        assert_eq!(evidence.spatial_fuzzy_info.terrain_membership["pole_facing"], 1.0);
+        let sun = evidence.spatial_fuzzy_info.microclimate_membership["sun_exposed"];
+        assert!(sun > 0.5 && sun < 0.9);
+
+        let scene = measurement(multispectral(0.04, 0.10, 0.40), MIDSUMMER);
+        let flat = SatelliteImageryEvidenceProcessor::new().assess_fuzzy_quality(&scene);
+        let sloped = SatelliteImageryEvidenceProcessor::new().with_elevation_model(dem).assess_fuzzy_quality(&scene);
+        assert!(sloped.compute_overall_score() < flat.compute_overall_score());
+    }
+}
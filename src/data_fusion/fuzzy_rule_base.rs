@@ -0,0 +1,902 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use super::SensorType;
+use super::fuzzy_evidence::{FuzzyEvidenceProcessor, LinguisticVariable, MembershipFunction};
+use super::bayesian_network::{
+    AgriculturalImpact, AgriculturalRuleContext, FuzzyAntecedent, FuzzyCondition, FuzzyConsequent, FuzzyRule,
+    LogicalOperator, SeasonalApplicability,
+};
+
+/// Points used to sample a universe of discourse for coverage checks and defuzzification
+const UNIVERSE_SAMPLES: usize = 501;
+
+/// Linguistic variables and rules as written by agronomists in TOML or YAML
+///
+/// ```toml
+/// crop = "maize"
+///
+/// [variables.volumetric_water_content]
+/// universe = [0.0, 0.6]
+/// sensors = ["soil_sensor"]
+/// terms.dry = { triangular = [0.10, 0.18, 0.26] }
+///
+/// [[rules]]
+/// id = "irrigate_when_dry"
+/// if = [{ variable = "volumetric_water_content", is = "dry" }]
+/// then = { variable = "irrigation_need", is = "high" }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleBaseDefinition {
+    #[serde(default)]
+    pub crop: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Lowest acceptable max-membership anywhere in a universe
+    #[serde(default)]
+    pub min_coverage: Option<f64>,
+    #[serde(default)]
+    pub variables: BTreeMap<String, VariableDefinition>,
+    #[serde(default)]
+    pub rules: Vec<RuleDefinition>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VariableDefinition {
+    /// Display name, e.g. "Volumetric Water Content (m³/m³)"
+    #[serde(default)]
+    pub name: Option<String>,
+    /// [min, max]; may be omitted in an overlay that only retunes terms
+    #[serde(default)]
+    pub universe: Option<[f64; 2]>,
+    /// Evidence processors that should adopt this variable (snake_case sensor names);
+    /// empty means every processor that already uses the variable name
+    #[serde(default)]
+    pub sensors: Vec<String>,
+    /// Written `dry = { triangular = [...] }` in both formats rather than YAML's `!triangular` tags
+    #[serde(default, with = "serde_yaml::with::singleton_map_recursive")]
+    pub terms: BTreeMap<String, MembershipDefinition>,
+}
+
+/// Membership function parameters, matching `MembershipFunction`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum MembershipDefinition {
+    /// [left, peak, right]
+    Triangular([f64; 3]),
+    /// [left_base, left_top, right_top, right_base]
+    Trapezoidal([f64; 4]),
+    /// [centre, width]
+    Gaussian([f64; 2]),
+    /// [slope, centre]
+    Sigmoid([f64; 2]),
+    /// [centre, width, slope]
+    Bell([f64; 3]),
+}
+
+impl MembershipDefinition {
+    pub fn to_function(&self) -> MembershipFunction {
+        match *self {
+            MembershipDefinition::Triangular([a, b, c]) => MembershipFunction::Triangular(a, b, c),
+            MembershipDefinition::Trapezoidal([a, b, c, d]) => MembershipFunction::Trapezoidal(a, b, c, d),
+            MembershipDefinition::Gaussian([centre, width]) => MembershipFunction::Gaussian(centre, width),
+            MembershipDefinition::Sigmoid([slope, centre]) => MembershipFunction::Sigmoid(slope, centre),
+            MembershipDefinition::Bell([centre, width, slope]) => MembershipFunction::Bell(centre, width, slope),
+        }
+    }
+
+    fn parameters(&self) -> &[f64] {
+        match self {
+            MembershipDefinition::Triangular(p) | MembershipDefinition::Bell(p) => p,
+            MembershipDefinition::Trapezoidal(p) => p,
+            MembershipDefinition::Gaussian(p) | MembershipDefinition::Sigmoid(p) => p,
+        }
+    }
+
+    /// Parameter ordering and shape problems, if any
+    fn shape_error(&self) -> Option<&'static str> {
+        if self.parameters().iter().any(|p| !p.is_finite()) {
+            return Some("parameters must be finite");
+        }
+        match *self {
+            MembershipDefinition::Triangular([a, b, c]) if !(a <= b && b <= c && a < c) => {
+                Some("triangular points must satisfy left <= peak <= right with left < right")
+            },
+            MembershipDefinition::Trapezoidal([a, b, c, d]) if !(a <= b && b <= c && c <= d && a < d) => {
+                Some("trapezoidal points must be non-decreasing with left_base < right_base")
+            },
+            MembershipDefinition::Gaussian([_, width]) if width <= 0.0 => Some("gaussian width must be positive"),
+            MembershipDefinition::Sigmoid([0.0, _]) => Some("sigmoid slope must be non-zero"),
+            MembershipDefinition::Bell([_, width, slope]) if width <= 0.0 || slope <= 0.0 => {
+                Some("bell width and slope must be positive")
+            },
+            _ => None,
+        }
+    }
+
+    /// Range where the term is fully true (or its centre), which must lie in the universe
+    fn core(&self) -> (f64, f64) {
+        match *self {
+            MembershipDefinition::Triangular([_, b, _]) => (b, b),
+            MembershipDefinition::Trapezoidal([_, b, c, _]) => (b, c),
+            MembershipDefinition::Gaussian([centre, _]) | MembershipDefinition::Bell([centre, _, _]) => (centre, centre),
+            MembershipDefinition::Sigmoid([slope, centre]) => {
+                if slope > 0.0 { (centre, f64::INFINITY) } else { (f64::NEG_INFINITY, centre) }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InferenceKind {
+    /// Consequent is a linguistic term; outputs are centroid-defuzzified
+    #[default]
+    Mamdani,
+    /// Consequent is a constant or linear function of the inputs; outputs are weighted averages
+    Sugeno,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleConnective {
+    /// Minimum of the condition memberships
+    #[default]
+    And,
+    /// Maximum of the condition memberships
+    Or,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleDefinition {
+    pub id: String,
+    #[serde(default)]
+    pub inference: InferenceKind,
+    #[serde(rename = "if")]
+    pub conditions: Vec<ConditionDefinition>,
+    #[serde(default)]
+    pub connective: RuleConnective,
+    #[serde(rename = "then")]
+    pub consequent: ConsequentDefinition,
+    #[serde(default = "default_rule_weight")]
+    pub weight: f64,
+    #[serde(default)]
+    pub crops: Vec<String>,
+    #[serde(default)]
+    pub growth_stages: Vec<String>,
+}
+
+fn default_rule_weight() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConditionDefinition {
+    pub variable: String,
+    pub is: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConsequentDefinition {
+    pub variable: String,
+    /// Mamdani output term
+    #[serde(default)]
+    pub is: Option<String>,
+    /// Sugeno intercept
+    #[serde(default)]
+    pub constant: Option<f64>,
+    /// Sugeno coefficients on input variables
+    #[serde(default)]
+    pub linear: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleBaseFormat {
+    Toml,
+    Yaml,
+}
+
+impl RuleBaseFormat {
+    pub fn from_path(path: &Path) -> Result<Self, AppError> {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("toml") => Ok(RuleBaseFormat::Toml),
+            Some("yaml") | Some("yml") => Ok(RuleBaseFormat::Yaml),
+            _ => Err(AppError::validation(format!("Rule base {} must be .toml, .yaml or .yml", path.display()))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    pub severity: IssueSeverity,
+    /// e.g. "variables.volumetric_water_content.terms.dry" or "rules.irrigate_when_dry"
+    pub location: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            IssueSeverity::Error => "error",
+            IssueSeverity::Warning => "warning",
+        };
+        write!(f, "{}: {}: {}", severity, self.location, self.message)
+    }
+}
+
+impl RuleBaseDefinition {
+    pub fn parse(text: &str, format: RuleBaseFormat) -> Result<Self, AppError> {
+        match format {
+            RuleBaseFormat::Toml => toml::from_str(text).map_err(|e| AppError::validation(format!("Invalid TOML rule base: {}", e))),
+            RuleBaseFormat::Yaml => serde_yaml::from_str(text).map_err(|e| AppError::validation(format!("Invalid YAML rule base: {}", e))),
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, AppError> {
+        let format = RuleBaseFormat::from_path(path)?;
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| AppError::internal(format!("Cannot read rule base {}: {}", path.display(), e)))?;
+        Self::parse(&text, format).map_err(|e| AppError::validation(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Layer `overlay` on top: terms and rules replace those with the same name or id
+    pub fn merge(&mut self, overlay: RuleBaseDefinition) {
+        self.crop = overlay.crop.or(self.crop.take());
+        self.region = overlay.region.or(self.region.take());
+        self.min_coverage = overlay.min_coverage.or(self.min_coverage);
+
+        for (key, variable) in overlay.variables {
+            let base = self.variables.entry(key).or_default();
+            base.name = variable.name.or(base.name.take());
+            base.universe = variable.universe.or(base.universe);
+            if !variable.sensors.is_empty() {
+                base.sensors = variable.sensors;
+            }
+            base.terms.extend(variable.terms);
+        }
+
+        for rule in overlay.rules {
+            match self.rules.iter_mut().find(|existing| existing.id == rule.id) {
+                Some(existing) => *existing = rule,
+                None => self.rules.push(rule),
+            }
+        }
+    }
+
+    /// Check universes, term shapes, coverage of each universe and rule references
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        for (key, variable) in &self.variables {
+            self.validate_variable(key, variable, &mut issues);
+        }
+        self.validate_rules(&mut issues);
+        issues
+    }
+
+    fn validate_variable(&self, key: &str, variable: &VariableDefinition, issues: &mut Vec<ValidationIssue>) {
+        let location = format!("variables.{}", key);
+        let mut error = |location: String, message: String| issues.push(ValidationIssue { severity: IssueSeverity::Error, location, message });
+
+        for sensor in &variable.sensors {
+            if sensor_type_from_name(sensor).is_none() {
+                error(location.clone(), format!("unknown sensor '{}'", sensor));
+            }
+        }
+        let Some([low, high]) = variable.universe else {
+            error(location, "universe is not defined".to_string());
+            return;
+        };
+        if !(low.is_finite() && high.is_finite() && low < high) {
+            error(location, format!("universe [{}, {}] must be finite with min < max", low, high));
+            return;
+        }
+        if variable.terms.is_empty() {
+            error(location, "no terms defined".to_string());
+            return;
+        }
+
+        let mut valid_terms = Vec::new();
+        for (term, definition) in &variable.terms {
+            let term_location = format!("{}.terms.{}", location, term);
+            if let Some(problem) = definition.shape_error() {
+                error(term_location, problem.to_string());
+                continue;
+            }
+            let (core_low, core_high) = definition.core();
+            if core_high < low || core_low > high {
+                error(term_location, format!("term is never fully true inside the universe [{}, {}]", low, high));
+                continue;
+            }
+            valid_terms.push((term.as_str(), definition.to_function()));
+        }
+        if valid_terms.len() < variable.terms.len() {
+            return;
+        }
+
+        let min_coverage = self.min_coverage.unwrap_or(0.1);
+        let coverage: Vec<(f64, f64, usize)> = sample_universe(low, high, UNIVERSE_SAMPLES)
+            .map(|x| {
+                let degrees = valid_terms.iter().map(|(_, f)| f.evaluate(x));
+                let (best, fully_true) = degrees.fold((0.0f64, 0usize), |(best, full), d| (best.max(d), full + usize::from(d >= 1.0 - 1e-12)));
+                (x, best, fully_true)
+            })
+            .collect();
+
+        for (start, end) in runs(&coverage, |(_, best, _)| *best < min_coverage) {
+            error(location.clone(), format!("no term reaches membership {} on [{:.4}, {:.4}]", min_coverage, start, end));
+        }
+        for (start, end) in runs(&coverage, |(_, best, _)| *best >= min_coverage && *best < 0.5) {
+            issues.push(ValidationIssue {
+                severity: IssueSeverity::Warning,
+                location: location.clone(),
+                message: format!("adjacent terms overlap below 0.5 on [{:.4}, {:.4}]", start, end),
+            });
+        }
+        for (start, end) in runs(&coverage, |(_, _, fully_true)| *fully_true > 1) {
+            issues.push(ValidationIssue {
+                severity: IssueSeverity::Warning,
+                location: location.clone(),
+                message: format!("several terms are fully true on [{:.4}, {:.4}]", start, end),
+            });
+        }
+    }
+
+    fn validate_rules(&self, issues: &mut Vec<ValidationIssue>) {
+        let mut output_kinds: HashMap<&str, InferenceKind> = HashMap::new();
+        let mut seen = std::collections::HashSet::new();
+
+        for rule in &self.rules {
+            let location = format!("rules.{}", rule.id);
+            let mut error = |message: String| issues.push(ValidationIssue { severity: IssueSeverity::Error, location: location.clone(), message });
+
+            if !seen.insert(rule.id.as_str()) {
+                error("duplicate rule id".to_string());
+            }
+            if rule.conditions.is_empty() {
+                error("rule has no conditions".to_string());
+            }
+            if !(0.0..=1.0).contains(&rule.weight) {
+                error(format!("weight {} must be in [0, 1]", rule.weight));
+            }
+            for condition in &rule.conditions {
+                match self.variables.get(&condition.variable) {
+                    None => error(format!("unknown input variable '{}'", condition.variable)),
+                    Some(variable) if !variable.terms.contains_key(&condition.is) => {
+                        error(format!("variable '{}' has no term '{}'", condition.variable, condition.is))
+                    },
+                    Some(_) => {},
+                }
+            }
+
+            let consequent = &rule.consequent;
+            match rule.inference {
+                InferenceKind::Mamdani => match (&consequent.is, self.variables.get(&consequent.variable)) {
+                    (None, _) => error("Mamdani consequent needs a term ('is')".to_string()),
+                    (Some(_), None) => error(format!("unknown output variable '{}'", consequent.variable)),
+                    (Some(term), Some(variable)) if !variable.terms.contains_key(term) => {
+                        error(format!("variable '{}' has no term '{}'", consequent.variable, term))
+                    },
+                    _ => {
+                        if consequent.constant.is_some() || !consequent.linear.is_empty() {
+                            error("Mamdani consequent cannot have Sugeno coefficients".to_string());
+                        }
+                    },
+                },
+                InferenceKind::Sugeno => {
+                    if consequent.is.is_some() {
+                        error("Sugeno consequent takes 'constant'/'linear', not a term".to_string());
+                    }
+                    if consequent.constant.is_none() && consequent.linear.is_empty() {
+                        error("Sugeno consequent needs a constant or linear coefficients".to_string());
+                    }
+                    for input in consequent.linear.keys() {
+                        if !self.variables.contains_key(input) {
+                            error(format!("linear coefficient on unknown variable '{}'", input));
+                        }
+                    }
+                },
+            }
+
+            match output_kinds.insert(consequent.variable.as_str(), rule.inference) {
+                Some(kind) if kind != rule.inference => {
+                    error(format!("output '{}' mixes Mamdani and Sugeno rules", consequent.variable))
+                },
+                _ => {},
+            }
+        }
+    }
+}
+
+fn sample_universe(low: f64, high: f64, samples: usize) -> impl Iterator<Item = f64> {
+    let step = (high - low) / (samples - 1) as f64;
+    (0..samples).map(move |i| if i + 1 == samples { high } else { low + i as f64 * step })
+}
+
+/// Contiguous x-ranges of `samples` where `predicate` holds
+fn runs<P>(samples: &[(f64, f64, usize)], predicate: P) -> Vec<(f64, f64)>
+where
+    P: Fn(&(f64, f64, usize)) -> bool,
+{
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for (i, sample) in samples.iter().enumerate() {
+        match (predicate(sample), start) {
+            (true, None) => start = Some(sample.0),
+            (false, Some(s)) => {
+                ranges.push((s, samples[i - 1].0));
+                start = None;
+            },
+            _ => {},
+        }
+    }
+    if let (Some(s), Some(last)) = (start, samples.last()) {
+        ranges.push((s, last.0));
+    }
+    ranges
+}
+
+/// Sensor name as used in rule files, e.g. "soil_sensor" or "autonomous_weather_buoy"
+pub fn sensor_type_from_name(name: &str) -> Option<SensorType> {
+    let sensor = match name.trim().to_ascii_lowercase().as_str() {
+        "gps" => SensorType::GPS,
+        "atomic_clock" => SensorType::AtomicClock,
+        "weather_station" => SensorType::WeatherStation,
+        "soil_sensor" => SensorType::SoilSensor,
+        "satellite_imagery" => SensorType::SatelliteImagery,
+        "satellite_radar" => SensorType::SatelliteRadar,
+        "radar_precipitation" => SensorType::RadarPrecipitation,
+        "lightning_detector" => SensorType::LightningDetector,
+        "wind_profiler" => SensorType::WindProfiler,
+        "autonomous_weather_buoy" => SensorType::AutonomousWeatherBuoy,
+        "agricultural_iot" => SensorType::AgriculturalIoT,
+        "drone_multispectral" => SensorType::DroneMultispectral,
+        "ground_truth_station" => SensorType::GroundTruthStation,
+        "cosmic_ray_neutron" => SensorType::CosmicRayNeutron,
+        "eddy_covariance_tower" => SensorType::EddyCovarianceTower,
+        "phenocam" => SensorType::Phenocam,
+        "lysimeter" => SensorType::Lysimeter,
+        _ => return None,
+    };
+    Some(sensor)
+}
+
+/// Where to find rule files for a crop and region
+///
+/// Files are layered from general to specific: `default`, `<crop>`, then
+/// `<crop>_<region>`, each as `.toml`, `.yaml` or `.yml` in `directory`.
+#[derive(Debug, Clone)]
+pub struct RuleBaseSelection {
+    pub directory: PathBuf,
+    pub crop: Option<String>,
+    pub region: Option<String>,
+}
+
+impl RuleBaseSelection {
+    /// From `FUZZY_RULE_BASE_DIR`, `FUZZY_RULE_BASE_CROP` and `FUZZY_RULE_BASE_REGION`
+    pub fn from_env() -> Option<Self> {
+        let directory = std::env::var("FUZZY_RULE_BASE_DIR").ok()?;
+        Some(Self {
+            directory: PathBuf::from(directory),
+            crop: std::env::var("FUZZY_RULE_BASE_CROP").ok(),
+            region: std::env::var("FUZZY_RULE_BASE_REGION").ok(),
+        })
+    }
+
+    /// Existing rule files in layering order
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut stems = vec!["default".to_string()];
+        if let Some(crop) = &self.crop {
+            stems.push(crop.to_lowercase());
+            if let Some(region) = &self.region {
+                stems.push(format!("{}_{}", crop.to_lowercase(), region.to_lowercase()));
+            }
+        }
+        stems
+            .iter()
+            .flat_map(|stem| ["toml", "yaml", "yml"].map(|ext| self.directory.join(format!("{}.{}", stem, ext))))
+            .filter(|path| path.is_file())
+            .collect()
+    }
+}
+
+/// A validated rule base, ready for the evidence processors and the Bayesian network
+#[derive(Debug, Clone)]
+pub struct FuzzyRuleBase {
+    definition: RuleBaseDefinition,
+    variables: HashMap<String, LinguisticVariable>,
+    warnings: Vec<ValidationIssue>,
+}
+
+impl FuzzyRuleBase {
+    /// Validate a definition; any error-level issue rejects the whole rule base
+    pub fn compile(definition: RuleBaseDefinition) -> Result<Self, AppError> {
+        let (errors, warnings): (Vec<_>, Vec<_>) = definition
+            .validate()
+            .into_iter()
+            .partition(|issue| issue.severity == IssueSeverity::Error);
+        if !errors.is_empty() {
+            let report = errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ");
+            return Err(AppError::validation(format!("Invalid fuzzy rule base: {}", report)));
+        }
+
+        let variables = definition
+            .variables
+            .iter()
+            .map(|(key, variable)| {
+                let universe = variable.universe.unwrap_or_default();
+                let variable = LinguisticVariable {
+                    name: variable.name.clone().unwrap_or_else(|| key.clone()),
+                    universe_of_discourse: (universe[0], universe[1]),
+                    terms: variable.terms.iter().map(|(term, definition)| (term.clone(), definition.to_function())).collect(),
+                };
+                (key.clone(), variable)
+            })
+            .collect();
+
+        Ok(Self { definition, variables, warnings })
+    }
+
+    /// Layer the given files in order and validate the result
+    pub fn from_files<P: AsRef<Path>>(paths: &[P]) -> Result<Self, AppError> {
+        let mut definition = RuleBaseDefinition::default();
+        for path in paths {
+            definition.merge(RuleBaseDefinition::from_file(path.as_ref())?);
+        }
+        Self::compile(definition)
+    }
+
+    pub fn load(selection: &RuleBaseSelection) -> Result<Self, AppError> {
+        let files = selection.files();
+        if files.is_empty() {
+            return Err(AppError::not_found(format!("No fuzzy rule files in {}", selection.directory.display())));
+        }
+        Self::from_files(&files)
+    }
+
+    pub fn definition(&self) -> &RuleBaseDefinition {
+        &self.definition
+    }
+
+    pub fn warnings(&self) -> &[ValidationIssue] {
+        &self.warnings
+    }
+
+    pub fn linguistic_variables(&self) -> &HashMap<String, LinguisticVariable> {
+        &self.variables
+    }
+
+    /// Replace processor variables with the configured ones; returns how many were replaced
+    pub fn apply_to_processors(
+        &self,
+        processors: &mut HashMap<SensorType, Box<dyn FuzzyEvidenceProcessor + Send + Sync>>,
+    ) -> usize {
+        let mut replaced = 0;
+        for (key, definition) in &self.definition.variables {
+            let targets: Vec<SensorType> = definition.sensors.iter().filter_map(|name| sensor_type_from_name(name)).collect();
+            for (sensor_type, processor) in processors.iter_mut() {
+                if !targets.is_empty() && !targets.contains(sensor_type) {
+                    continue;
+                }
+                if processor.replace_linguistic_variable(key, self.variables[key].clone()) {
+                    replaced += 1;
+                } else if !targets.is_empty() {
+                    tracing::warn!("{:?} evidence processor does not use fuzzy variable '{}'", sensor_type, key);
+                }
+            }
+        }
+        replaced
+    }
+
+    /// Mamdani rules in the form used by the Bayesian network's conditional tables;
+    /// Sugeno rules have no linguistic consequent and are evaluated by [`Self::infer`]
+    pub fn fuzzy_rules(&self) -> Vec<FuzzyRule> {
+        self.definition
+            .rules
+            .iter()
+            .filter(|rule| rule.inference == InferenceKind::Mamdani)
+            .map(|rule| {
+                let applicable_crops = if rule.crops.is_empty() {
+                    self.definition.crop.iter().cloned().collect()
+                } else {
+                    rule.crops.clone()
+                };
+                FuzzyRule {
+                    id: rule.id.clone(),
+                    antecedent: FuzzyAntecedent {
+                        conditions: rule
+                            .conditions
+                            .iter()
+                            .map(|condition| FuzzyCondition {
+                                variable_name: condition.variable.clone(),
+                                linguistic_term: condition.is.clone(),
+                                membership_degree: 1.0,
+                                agricultural_modifier: None,
+                            })
+                            .collect(),
+                        logical_operator: match rule.connective {
+                            RuleConnective::And => LogicalOperator::And,
+                            RuleConnective::Or => LogicalOperator::Or,
+                        },
+                    },
+                    consequent: FuzzyConsequent {
+                        variable_name: rule.consequent.variable.clone(),
+                        linguistic_term: rule.consequent.is.clone().unwrap_or_default(),
+                        certainty_factor: rule.weight,
+                        agricultural_impact: AgriculturalImpact {
+                            irrigation_recommendation_strength: 0.0,
+                            crop_stress_indication: 0.0,
+                            yield_impact_factor: 0.0,
+                            harvest_timing_influence: 0.0,
+                        },
+                    },
+                    confidence: rule.weight,
+                    agricultural_context: AgriculturalRuleContext {
+                        applicable_crops,
+                        growth_stages: rule.growth_stages.clone(),
+                        seasonal_applicability: SeasonalApplicability { spring: 1.0, summer: 1.0, fall: 1.0, winter: 1.0 },
+                        irrigation_relevance: 0.0,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Firing strength of a rule for crisp inputs; `None` if an input is missing
+    fn firing_strength(&self, rule: &RuleDefinition, inputs: &HashMap<String, f64>) -> Option<f64> {
+        let mut degrees = rule.conditions.iter().map(|condition| {
+            let variable = &self.variables[&condition.variable];
+            let (low, high) = variable.universe_of_discourse;
+            let x = inputs.get(&condition.variable)?.clamp(low, high);
+            Some(variable.terms[&condition.is].evaluate(x))
+        });
+        let strength = match rule.connective {
+            RuleConnective::And => degrees.try_fold(1.0f64, |acc, d| d.map(|d| acc.min(d)))?,
+            RuleConnective::Or => degrees.try_fold(0.0f64, |acc, d| d.map(|d| acc.max(d)))?,
+        };
+        Some(strength * rule.weight)
+    }
+
+    /// Crisp outputs for crisp inputs
+    ///
+    /// Mamdani outputs use min implication, max aggregation and centroid
+    /// defuzzification; Sugeno outputs are the firing-strength weighted mean
+    /// of the rule consequents. Outputs whose rules do not fire are omitted.
+    pub fn infer(&self, inputs: &HashMap<String, f64>) -> HashMap<String, f64> {
+        let mut mamdani: HashMap<&str, Vec<(f64, &str)>> = HashMap::new();
+        let mut sugeno: HashMap<&str, (f64, f64)> = HashMap::new();
+
+        for rule in &self.definition.rules {
+            let Some(strength) = self.firing_strength(rule, inputs).filter(|s| *s > 0.0) else {
+                continue;
+            };
+            let consequent = &rule.consequent;
+            match rule.inference {
+                InferenceKind::Mamdani => {
+                    if let Some(term) = &consequent.is {
+                        mamdani.entry(consequent.variable.as_str()).or_default().push((strength, term.as_str()));
+                    }
+                },
+                InferenceKind::Sugeno => {
+                    let Some(linear) = consequent
+                        .linear
+                        .iter()
+                        .map(|(input, coefficient)| inputs.get(input).map(|x| coefficient * x))
+                        .sum::<Option<f64>>()
+                    else {
+                        continue;
+                    };
+                    let output = consequent.constant.unwrap_or(0.0) + linear;
+                    let entry = sugeno.entry(consequent.variable.as_str()).or_insert((0.0, 0.0));
+                    entry.0 += strength * output;
+                    entry.1 += strength;
+                },
+            }
+        }
+
+        let mut outputs = HashMap::new();
+        for (name, activations) in mamdani {
+            let variable = &self.variables[name];
+            let (low, high) = variable.universe_of_discourse;
+            let (moment, area) = sample_universe(low, high, UNIVERSE_SAMPLES)
+                .map(|x| {
+                    let degree = activations
+                        .iter()
+                        .map(|(strength, term)| variable.terms[*term].evaluate(x).min(*strength))
+                        .fold(0.0, f64::max);
+                    (x * degree, degree)
+                })
+                .fold((0.0, 0.0), |(m, a), (xm, d)| (m + xm, a + d));
+            if area > 0.0 {
+                outputs.insert(name.to_string(), moment / area);
+            }
+        }
+        for (name, (weighted, total)) in sugeno {
+            outputs.insert(name.to_string(), weighted / total);
+        }
+        outputs
+    }
+
+    /// Membership curves as long-format CSV (`variable,term,x,membership`) for plotting
+    pub fn membership_curves_csv(&self, samples: usize) -> String {
+        let mut csv = String::from("variable,term,x,membership\n");
+        for (key, definition) in &self.definition.variables {
+            let variable = &self.variables[key];
+            let (low, high) = variable.universe_of_discourse;
+            for term in definition.terms.keys() {
+                let function = &variable.terms[term];
+                for x in sample_universe(low, high, samples.max(2)) {
+                    let _ = writeln!(csv, "{},{},{},{}", key, term, x, function.evaluate(x));
+                }
+            }
+        }
+        csv
+    }
+}
+
+/// `membership-curves <rule-file>... [--samples N] [--output FILE]`
+///
+/// Layers the rule files, prints validation findings to stderr and writes
+/// the membership curves as CSV to FILE or stdout.
+pub fn run_membership_curves_tool(args: &[String]) -> Result<(), AppError> {
+    let mut files = Vec::new();
+    let mut samples = 201;
+    let mut output = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--samples" => {
+                samples = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|n| *n >= 2)
+                    .ok_or_else(|| AppError::validation("--samples needs an integer of at least 2"))?;
+            },
+            "--output" => {
+                output = Some(args.next().ok_or_else(|| AppError::validation("--output needs a file name"))?.clone());
+            },
+            file => files.push(PathBuf::from(file)),
+        }
+    }
+    if files.is_empty() {
+        return Err(AppError::validation("usage: membership-curves <rule-file>... [--samples N] [--output FILE]"));
+    }
+
+    let mut definition = RuleBaseDefinition::default();
+    for file in &files {
+        definition.merge(RuleBaseDefinition::from_file(file)?);
+    }
+    for issue in definition.validate() {
+        eprintln!("{}", issue);
+    }
+    let rule_base = FuzzyRuleBase::compile(definition)?;
+
+    let csv = rule_base.membership_curves_csv(samples);
+    match output {
+        Some(path) => std::fs::write(&path, csv).map_err(|e| AppError::internal(format!("Cannot write {}: {}", path, e))),
+        None => {
+            print!("{}", csv);
+            Ok(())
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT_RULES: &str = include_str!("../../config/fuzzy_rules/default.toml");
+    const MAIZE_RULES: &str = include_str!("../../config/fuzzy_rules/maize.yaml");
+
+    fn shipped_rule_base() -> RuleBaseDefinition {
+        let mut definition = RuleBaseDefinition::parse(DEFAULT_RULES, RuleBaseFormat::Toml).unwrap();
+        definition.merge(RuleBaseDefinition::parse(MAIZE_RULES, RuleBaseFormat::Yaml).unwrap());
+        definition
+    }
+
+    #[test]
+    fn test_shipped_rule_files_validate_and_layer() {
+        let definition = shipped_rule_base();
+        let errors: Vec<_> = definition.validate().into_iter().filter(|i| i.severity == IssueSeverity::Error).collect();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        // The maize overlay retunes "dry" but keeps the other soil moisture terms
+        let soil = &definition.variables["volumetric_water_content"];
+        assert_eq!(soil.terms["dry"], MembershipDefinition::Triangular([0.12, 0.20, 0.28]));
+        assert!(soil.terms.contains_key("saturated"));
+        assert_eq!(definition.crop.as_deref(), Some("maize"));
+    }
+
+    #[test]
+    fn test_mamdani_and_sugeno_inference() {
+        let rule_base = FuzzyRuleBase::compile(shipped_rule_base()).unwrap();
+
+        let dry_and_hot = HashMap::from([("volumetric_water_content".to_string(), 0.10), ("temperature".to_string(), 38.0)]);
+        let outputs = rule_base.infer(&dry_and_hot);
+        assert!(outputs["irrigation_need"] > 0.7);
+        assert!(outputs["heat_stress"] > 0.7);
+        assert!(outputs["irrigation_depth_mm"] > 20.0);
+
+        let moist_and_mild = HashMap::from([("volumetric_water_content".to_string(), 0.30), ("temperature".to_string(), 20.0)]);
+        let outputs = rule_base.infer(&moist_and_mild);
+        assert!(outputs["irrigation_need"] < 0.3);
+        assert!(outputs["irrigation_depth_mm"] < 5.0);
+        assert!(!outputs.contains_key("heat_stress"));
+
+        assert_eq!(rule_base.fuzzy_rules().len(), rule_base.definition().rules.iter().filter(|r| r.inference == InferenceKind::Mamdani).count());
+    }
+
+    #[test]
+    fn test_validation_reports_gaps_bounds_and_references() {
+        let text = r#"
+            [variables.temperature]
+            universe = [0.0, 40.0]
+            terms.cold = { trapezoidal = [0.0, 0.0, 5.0, 10.0] }
+            terms.hot = { triangular = [25.0, 45.0, 50.0] }
+            terms.odd = { triangular = [10.0, 5.0, 20.0] }
+
+            [[rules]]
+            id = "r1"
+            if = [{ variable = "temperature", is = "warm" }]
+            then = { variable = "stress", is = "high" }
+
+            [[rules]]
+            id = "r2"
+            inference = "sugeno"
+            if = [{ variable = "humidity", is = "dry" }]
+            then = { variable = "stress", constant = 1.0 }
+        "#;
+        let definition = RuleBaseDefinition::parse(text, RuleBaseFormat::Toml).unwrap();
+        let messages: Vec<String> = definition.validate().iter().map(ToString::to_string).collect();
+        let has = |needle: &str| messages.iter().any(|m| m.contains(needle));
+
+        assert!(has("terms.hot: term is never fully true"));
+        assert!(has("terms.odd: triangular points"));
+        assert!(has("no term 'warm'"));
+        assert!(has("unknown output variable 'stress'"));
+        assert!(has("unknown input variable 'humidity'"));
+        assert!(has("mixes Mamdani and Sugeno"));
+        assert!(FuzzyRuleBase::compile(definition).is_err());
+
+        let gap = RuleBaseDefinition::parse(
+            "[variables.t]\nuniverse = [0.0, 40.0]\nterms.cold = { trapezoidal = [0.0, 0.0, 5.0, 10.0] }\nterms.hot = { trapezoidal = [30.0, 35.0, 40.0, 40.0] }\n",
+            RuleBaseFormat::Toml,
+        )
+        .unwrap();
+        assert!(gap.validate().iter().any(|i| i.severity == IssueSeverity::Error && i.message.contains("no term reaches")));
+        assert!(RuleBaseDefinition::parse("[variables.t]\nuniverse = [0.0, 1.0]\nshape = 3\n", RuleBaseFormat::Toml).is_err());
+    }
+
+    #[test]
+    fn test_membership_curves_csv() {
+        let rule_base = FuzzyRuleBase::compile(shipped_rule_base()).unwrap();
+        let csv = rule_base.membership_curves_csv(11);
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], "variable,term,x,membership");
+        let terms: usize = rule_base.definition().variables.values().map(|v| v.terms.len()).sum();
+        assert_eq!(rows.len(), 1 + 11 * terms);
+        // Maize "dry" is triangular [0.12, 0.20, 0.28]; the fourth sample is x = 0.18
+        let dry: Vec<(f64, f64)> = rows
+            .iter()
+            .filter_map(|row| row.strip_prefix("volumetric_water_content,dry,"))
+            .map(|row| {
+                let (x, membership) = row.split_once(',').unwrap();
+                (x.parse().unwrap(), membership.parse().unwrap())
+            })
+            .collect();
+        assert_eq!(dry.len(), 11);
+        assert!((dry[3].0 - 0.18).abs() < 1e-9 && (dry[3].1 - 0.75).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,652 @@
+use chrono::{DateTime, Duration, Utc};
+use nalgebra::DVector;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, LogNormal};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use super::{GrowthStage, optimization::{BayesianOptimizer, ObjectiveType}};
+
+/// Irrigation and fertiliser decision optimiser
+///
+/// Searches weekly irrigation depths and N-P-K applications with the
+/// `BayesianOptimizer` Pareto search. Each candidate plan is simulated with a
+/// FAO-56 root-zone water balance over an ensemble of rainfall scenarios, and
+/// the return is risk-adjusted with the optimizer's `AgriculturalRiskProfile`.
+/// Three objectives are traded off: risk-adjusted net return, irrigation water
+/// used and nitrogen leached below the root zone.
+#[derive(Debug, Clone)]
+pub struct IrrigationFertiliserPlanner {
+    /// Number of rainfall scenarios each plan is evaluated against
+    pub scenario_count: usize,
+
+    /// Tail probability used for the conditional value-at-risk of net return
+    pub cvar_tail: f64,
+
+    /// Objective evaluations spent by the Pareto search
+    pub default_evaluation_budget: usize,
+
+    /// Seed for the rainfall ensemble, so every plan sees the same scenarios
+    pub scenario_seed: u64,
+
+    /// Seed for the Pareto search, so repeated requests return the same plans
+    pub search_seed: u64,
+}
+
+/// Planning request for a single field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IrrigationPlanRequest {
+    pub field: FieldProfile,
+    pub crop_type: String,
+    pub growth_stage: GrowthStage,
+    /// Start of the first planning week
+    pub start_date: DateTime<Utc>,
+    /// Total irrigation water available over the horizon (mm)
+    pub water_budget_mm: f64,
+    pub fertiliser_prices: FertiliserPrices,
+    /// Farm-gate price of the harvested crop
+    pub crop_price_per_tonne: f64,
+    #[serde(default)]
+    pub water_cost_per_m3: f64,
+    /// One entry per planning week
+    pub weekly_forecast: Vec<WeeklyWeatherForecast>,
+    /// Overrides the optimizer's default risk aversion (0 = neutral, 1 = averse)
+    pub risk_aversion: Option<f64>,
+    pub evaluation_budget: Option<usize>,
+}
+
+/// Soil and management properties of the field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldProfile {
+    pub field_id: String,
+    pub area_ha: f64,
+    /// Volumetric water content at field capacity (m³/m³)
+    pub field_capacity: f64,
+    /// Volumetric water content at permanent wilting point (m³/m³)
+    pub wilting_point: f64,
+    pub root_depth_m: f64,
+    /// Current root-zone depletion below field capacity (mm)
+    pub initial_depletion_mm: f64,
+    /// Fraction of applied irrigation that reaches the root zone
+    pub irrigation_efficiency: f64,
+    pub soil_nitrogen_kg_ha: f64,
+    pub soil_phosphorus_kg_ha: f64,
+    pub soil_potassium_kg_ha: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FertiliserPrices {
+    pub nitrogen_per_kg: f64,
+    pub phosphorus_per_kg: f64,
+    pub potassium_per_kg: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyWeatherForecast {
+    /// Reference evapotranspiration ET₀ for the week (mm)
+    pub reference_et_mm: f64,
+    /// Expected rainfall for the week (mm)
+    pub rainfall_mm: f64,
+    /// Coefficient of variation of the weekly rainfall forecast
+    pub rainfall_cv: f64,
+}
+
+/// Crop parameters for the water balance and nutrient response
+#[derive(Debug, Clone)]
+pub struct CropResponseParameters {
+    pub kc_initial: f64,
+    pub kc_mid: f64,
+    pub kc_late: f64,
+    /// Fraction of total available water that can be depleted without stress
+    pub depletion_fraction: f64,
+    /// Seasonal yield response factor Ky (FAO-33)
+    pub yield_response_factor: f64,
+    pub max_yield_t_ha: f64,
+    /// Mitscherlich coefficients (per kg/ha) for N, P and K
+    pub nitrogen_response: f64,
+    pub phosphorus_response: f64,
+    pub potassium_response: f64,
+}
+
+/// One week of a plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyApplication {
+    pub week: usize,
+    pub week_start: DateTime<Utc>,
+    pub irrigation_mm: f64,
+    pub nitrogen_kg_ha: f64,
+    pub phosphorus_kg_ha: f64,
+    pub potassium_kg_ha: f64,
+}
+
+/// A Pareto-optimal irrigation and fertiliser plan with its evaluation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IrrigationFertiliserPlan {
+    pub plan_id: usize,
+    pub weeks: Vec<WeeklyApplication>,
+    pub expected_yield_t_ha: f64,
+    /// Yield exceeded in 90% of rainfall scenarios
+    pub yield_p10_t_ha: f64,
+    pub expected_net_return: f64,
+    pub risk_adjusted_net_return: f64,
+    pub total_irrigation_mm: f64,
+    pub expected_nitrogen_leaching_kg_ha: f64,
+    /// Expected number of weeks with water stress (Ks < 1)
+    pub expected_stress_weeks: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IrrigationPlanSet {
+    pub field_id: String,
+    pub crop_type: String,
+    pub risk_aversion: f64,
+    /// Sorted by risk-adjusted net return, best first
+    pub plans: Vec<IrrigationFertiliserPlan>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ScenarioOutcome {
+    yield_t_ha: f64,
+    net_return: f64,
+    leached_nitrogen: f64,
+    stress_weeks: f64,
+}
+
+/// Upper bounds of the decision variables
+const MAX_WEEKLY_IRRIGATION_MM: f64 = 80.0;
+const MAX_NITROGEN_KG_HA: f64 = 250.0;
+const MAX_PHOSPHORUS_KG_HA: f64 = 80.0;
+const MAX_POTASSIUM_KG_HA: f64 = 150.0;
+
+/// Fraction of rainfall that is effective in the root zone
+const EFFECTIVE_RAINFALL_FRACTION: f64 = 0.8;
+
+impl Default for IrrigationFertiliserPlanner {
+    fn default() -> Self {
+        Self {
+            scenario_count: 48,
+            cvar_tail: 0.1,
+            default_evaluation_budget: 80,
+            scenario_seed: 0x1A_2B_3C,
+            search_seed: 0x4D_5E_6F,
+        }
+    }
+}
+
+impl IrrigationFertiliserPlanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Produce the Pareto set of weekly irrigation and N-P-K plans for a field
+    pub fn plan(
+        &self,
+        optimizer: &BayesianOptimizer,
+        request: &IrrigationPlanRequest,
+    ) -> Result<IrrigationPlanSet, AppError> {
+        Self::validate(request)?;
+
+        let crop = CropResponseParameters::for_crop(&request.crop_type);
+        let crop_coefficient = crop.crop_coefficient(&request.growth_stage);
+        let risk_aversion = request
+            .risk_aversion
+            .unwrap_or(optimizer.risk_profile().risk_aversion)
+            .clamp(0.0, 1.0);
+        let scenarios = self.sample_rainfall_scenarios(&request.weekly_forecast)?;
+        let weeks = request.weekly_forecast.len();
+
+        let mut bounds = vec![(0.0, MAX_WEEKLY_IRRIGATION_MM.min(request.water_budget_mm)); weeks];
+        bounds.push((0.0, MAX_NITROGEN_KG_HA));
+        bounds.push((0.0, MAX_PHOSPHORUS_KG_HA));
+        bounds.push((0.0, MAX_POTASSIUM_KG_HA));
+        bounds.push((0.0, 1.0)); // basal fraction of the nitrogen dose
+
+        let objectives = [
+            ObjectiveType::EconomicOptimization,
+            ObjectiveType::WaterEfficiency,
+            ObjectiveType::EnvironmentalSustainability,
+        ];
+        let budget = request
+            .evaluation_budget
+            .unwrap_or(self.default_evaluation_budget)
+            .max(bounds.len() * 2 + 2);
+
+        let front = optimizer.search_pareto_front(&objectives, &bounds, budget, self.search_seed, |parameters| {
+            let schedule = Self::decode(parameters, request);
+            let evaluation = self.evaluate(&schedule, request, &crop, crop_coefficient, &scenarios, risk_aversion);
+            Ok(DVector::from_vec(vec![
+                evaluation.risk_adjusted_net_return,
+                -evaluation.total_irrigation_mm,
+                -evaluation.expected_nitrogen_leaching_kg_ha,
+            ]))
+        })?;
+
+        let mut plans: Vec<IrrigationFertiliserPlan> = front
+            .iter()
+            .map(|point| {
+                let schedule = Self::decode(&point.parameters, request);
+                self.evaluate(&schedule, request, &crop, crop_coefficient, &scenarios, risk_aversion)
+            })
+            .collect();
+        plans.sort_by(|a, b| b.risk_adjusted_net_return.total_cmp(&a.risk_adjusted_net_return));
+        for (plan_id, plan) in plans.iter_mut().enumerate() {
+            plan.plan_id = plan_id + 1;
+        }
+
+        Ok(IrrigationPlanSet {
+            field_id: request.field.field_id.clone(),
+            crop_type: request.crop_type.clone(),
+            risk_aversion,
+            plans,
+        })
+    }
+
+    fn validate(request: &IrrigationPlanRequest) -> Result<(), AppError> {
+        let field = &request.field;
+        if request.weekly_forecast.is_empty() || request.weekly_forecast.len() > 26 {
+            return Err(AppError::validation("weekly_forecast must cover between 1 and 26 weeks"));
+        }
+        if field.area_ha <= 0.0 || field.root_depth_m <= 0.0 {
+            return Err(AppError::validation("Field area and root depth must be positive"));
+        }
+        if !(0.0..=1.0).contains(&field.wilting_point)
+            || !(0.0..=1.0).contains(&field.field_capacity)
+            || field.wilting_point >= field.field_capacity
+        {
+            return Err(AppError::validation("Wilting point must be below field capacity, both in [0, 1]"));
+        }
+        if !(0.0..=1.0).contains(&field.irrigation_efficiency) || field.irrigation_efficiency == 0.0 {
+            return Err(AppError::validation("irrigation_efficiency must be in (0, 1]"));
+        }
+        if request.water_budget_mm < 0.0 {
+            return Err(AppError::validation("water_budget_mm cannot be negative"));
+        }
+        if matches!(request.growth_stage, GrowthStage::Harvest | GrowthStage::Fallow) {
+            return Err(AppError::validation("No irrigation plan is produced for harvested or fallow fields"));
+        }
+        if request
+            .weekly_forecast
+            .iter()
+            .any(|w| w.reference_et_mm < 0.0 || w.rainfall_mm < 0.0 || w.rainfall_cv < 0.0)
+        {
+            return Err(AppError::validation("Weekly forecast values cannot be negative"));
+        }
+        Ok(())
+    }
+
+    /// Lognormal rainfall ensemble matching each week's mean and CV
+    fn sample_rainfall_scenarios(&self, forecast: &[WeeklyWeatherForecast]) -> Result<Vec<Vec<f64>>, AppError> {
+        let mut rng = StdRng::seed_from_u64(self.scenario_seed);
+        let distributions = forecast
+            .iter()
+            .map(|week| {
+                if week.rainfall_mm <= 0.0 || week.rainfall_cv <= 0.0 {
+                    return Ok(None);
+                }
+                let sigma_sq = (1.0 + week.rainfall_cv * week.rainfall_cv).ln();
+                LogNormal::new(week.rainfall_mm.ln() - 0.5 * sigma_sq, sigma_sq.sqrt())
+                    .map(Some)
+                    .map_err(|e| AppError::internal(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+        Ok((0..self.scenario_count.max(1))
+            .map(|_| {
+                forecast
+                    .iter()
+                    .zip(&distributions)
+                    .map(|(week, distribution)| match distribution {
+                        Some(d) => d.sample(&mut rng),
+                        None => week.rainfall_mm,
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Turn a decision vector into a weekly schedule, repairing the water budget
+    fn decode(parameters: &DVector<f64>, request: &IrrigationPlanRequest) -> Vec<WeeklyApplication> {
+        let weeks = request.weekly_forecast.len();
+        let mut irrigation: Vec<f64> = parameters.iter().take(weeks).map(|v| v.max(0.0)).collect();
+        let total: f64 = irrigation.iter().sum();
+        if total > request.water_budget_mm && total > 0.0 {
+            let scale = request.water_budget_mm / total;
+            irrigation.iter_mut().for_each(|v| *v *= scale);
+        }
+
+        let nitrogen = parameters[weeks].max(0.0);
+        let phosphorus = parameters[weeks + 1].max(0.0);
+        let potassium = parameters[weeks + 2].max(0.0);
+        let basal_fraction = if weeks == 1 { 1.0 } else { parameters[weeks + 3].clamp(0.0, 1.0) };
+
+        // P and K go in as basal dressings; N is split into a basal dose plus
+        // equal top-dressings over the remaining weeks
+        (0..weeks)
+            .map(|week| {
+                let nitrogen_share = if week == 0 {
+                    basal_fraction
+                } else {
+                    (1.0 - basal_fraction) / (weeks - 1) as f64
+                };
+                WeeklyApplication {
+                    week: week + 1,
+                    week_start: request.start_date + Duration::weeks(week as i64),
+                    irrigation_mm: irrigation[week],
+                    nitrogen_kg_ha: nitrogen * nitrogen_share,
+                    phosphorus_kg_ha: if week == 0 { phosphorus } else { 0.0 },
+                    potassium_kg_ha: if week == 0 { potassium } else { 0.0 },
+                }
+            })
+            .collect()
+    }
+
+    fn evaluate(
+        &self,
+        schedule: &[WeeklyApplication],
+        request: &IrrigationPlanRequest,
+        crop: &CropResponseParameters,
+        crop_coefficient: f64,
+        scenarios: &[Vec<f64>],
+        risk_aversion: f64,
+    ) -> IrrigationFertiliserPlan {
+        let mut outcomes: Vec<ScenarioOutcome> = scenarios
+            .iter()
+            .map(|rainfall| Self::simulate(schedule, request, crop, crop_coefficient, rainfall))
+            .collect();
+        let n = outcomes.len() as f64;
+
+        let mean = |f: fn(&ScenarioOutcome) -> f64, outcomes: &[ScenarioOutcome]| {
+            outcomes.iter().map(f).sum::<f64>() / n
+        };
+        let expected_net_return = mean(|o| o.net_return, &outcomes);
+        let expected_yield = mean(|o| o.yield_t_ha, &outcomes);
+        let expected_leaching = mean(|o| o.leached_nitrogen, &outcomes);
+        let expected_stress_weeks = mean(|o| o.stress_weeks, &outcomes);
+
+        // Risk adjustment: blend the mean with the CVaR of the worst outcomes
+        outcomes.sort_by(|a, b| a.net_return.total_cmp(&b.net_return));
+        let tail = ((self.cvar_tail * n).ceil() as usize).clamp(1, outcomes.len());
+        let cvar = outcomes[..tail].iter().map(|o| o.net_return).sum::<f64>() / tail as f64;
+        let risk_adjusted_net_return = (1.0 - risk_aversion) * expected_net_return + risk_aversion * cvar;
+
+        outcomes.sort_by(|a, b| a.yield_t_ha.total_cmp(&b.yield_t_ha));
+        let p10_index = ((0.1 * n).floor() as usize).min(outcomes.len() - 1);
+
+        IrrigationFertiliserPlan {
+            plan_id: 0,
+            weeks: schedule.to_vec(),
+            expected_yield_t_ha: expected_yield,
+            yield_p10_t_ha: outcomes[p10_index].yield_t_ha,
+            expected_net_return,
+            risk_adjusted_net_return,
+            total_irrigation_mm: schedule.iter().map(|w| w.irrigation_mm).sum(),
+            expected_nitrogen_leaching_kg_ha: expected_leaching,
+            expected_stress_weeks,
+        }
+    }
+
+    /// Weekly FAO-56 root-zone depletion balance for one rainfall scenario
+    fn simulate(
+        schedule: &[WeeklyApplication],
+        request: &IrrigationPlanRequest,
+        crop: &CropResponseParameters,
+        crop_coefficient: f64,
+        rainfall: &[f64],
+    ) -> ScenarioOutcome {
+        let field = &request.field;
+        let total_available_water = 1000.0 * (field.field_capacity - field.wilting_point) * field.root_depth_m;
+        let readily_available_water = crop.depletion_fraction * total_available_water;
+
+        let mut depletion = field.initial_depletion_mm.clamp(0.0, total_available_water);
+        let mut nitrogen_pool = field.soil_nitrogen_kg_ha;
+        let mut leached_nitrogen = 0.0;
+        let mut actual_et = 0.0;
+        let mut potential_et = 0.0;
+        let mut stress_weeks = 0.0;
+
+        for ((week, forecast), rain) in schedule.iter().zip(&request.weekly_forecast).zip(rainfall) {
+            nitrogen_pool += week.nitrogen_kg_ha;
+
+            let crop_et = crop_coefficient * forecast.reference_et_mm;
+            let stress_coefficient = if depletion > readily_available_water {
+                ((total_available_water - depletion) / (total_available_water - readily_available_water).max(1e-9))
+                    .clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+            if stress_coefficient < 1.0 {
+                stress_weeks += 1.0;
+            }
+
+            let week_et = stress_coefficient * crop_et;
+            actual_et += week_et;
+            potential_et += crop_et;
+
+            let water_in = EFFECTIVE_RAINFALL_FRACTION * rain + field.irrigation_efficiency * week.irrigation_mm;
+            depletion += week_et - water_in;
+
+            // Water above field capacity drains and carries mobile nitrate with it
+            if depletion < 0.0 {
+                let deep_percolation = -depletion;
+                depletion = 0.0;
+                let leaching_fraction = deep_percolation / (deep_percolation + total_available_water);
+                let leached = nitrogen_pool * leaching_fraction;
+                nitrogen_pool -= leached;
+                leached_nitrogen += leached;
+            }
+            depletion = depletion.min(total_available_water);
+        }
+
+        let water_factor = if potential_et > 0.0 {
+            (1.0 - crop.yield_response_factor * (1.0 - actual_et / potential_et)).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        let phosphorus = field.soil_phosphorus_kg_ha + schedule.iter().map(|w| w.phosphorus_kg_ha).sum::<f64>();
+        let potassium = field.soil_potassium_kg_ha + schedule.iter().map(|w| w.potassium_kg_ha).sum::<f64>();
+        let nutrient_factor = (1.0 - (-crop.nitrogen_response * nitrogen_pool.max(0.0)).exp())
+            * (1.0 - (-crop.phosphorus_response * phosphorus.max(0.0)).exp())
+            * (1.0 - (-crop.potassium_response * potassium.max(0.0)).exp());
+        let yield_t_ha = crop.max_yield_t_ha * water_factor * nutrient_factor;
+
+        let prices = &request.fertiliser_prices;
+        let fertiliser_cost: f64 = schedule
+            .iter()
+            .map(|w| {
+                w.nitrogen_kg_ha * prices.nitrogen_per_kg
+                    + w.phosphorus_kg_ha * prices.phosphorus_per_kg
+                    + w.potassium_kg_ha * prices.potassium_per_kg
+            })
+            .sum();
+        // 1 mm over 1 ha is 10 m³
+        let water_cost = schedule.iter().map(|w| w.irrigation_mm).sum::<f64>() * 10.0 * request.water_cost_per_m3;
+        let net_return_per_ha = yield_t_ha * request.crop_price_per_tonne - fertiliser_cost - water_cost;
+
+        ScenarioOutcome {
+            yield_t_ha,
+            net_return: net_return_per_ha * field.area_ha,
+            leached_nitrogen,
+            stress_weeks,
+        }
+    }
+}
+
+impl CropResponseParameters {
+    /// FAO-56 crop coefficients and FAO-33 yield response factors for common crops
+    pub fn for_crop(crop_type: &str) -> Self {
+        let (kc_initial, kc_mid, kc_late, depletion_fraction, yield_response_factor, max_yield_t_ha) =
+            match crop_type.to_lowercase().as_str() {
+                "maize" | "corn" => (0.3, 1.2, 0.6, 0.55, 1.25, 10.0),
+                "wheat" => (0.3, 1.15, 0.4, 0.55, 1.15, 7.0),
+                "sorghum" => (0.3, 1.1, 0.55, 0.55, 0.9, 6.0),
+                "soybean" | "soybeans" => (0.4, 1.15, 0.5, 0.5, 0.85, 3.5),
+                "cotton" => (0.35, 1.2, 0.7, 0.65, 0.85, 4.5),
+                "groundnut" | "peanut" => (0.4, 1.15, 0.6, 0.5, 0.7, 3.5),
+                "potato" => (0.5, 1.15, 0.75, 0.35, 1.1, 40.0),
+                "tomato" => (0.6, 1.15, 0.8, 0.4, 1.05, 60.0),
+                _ => (0.4, 1.1, 0.6, 0.5, 1.0, 5.0),
+            };
+
+        Self {
+            kc_initial,
+            kc_mid,
+            kc_late,
+            depletion_fraction,
+            yield_response_factor,
+            max_yield_t_ha,
+            nitrogen_response: 0.018,
+            phosphorus_response: 0.06,
+            potassium_response: 0.03,
+        }
+    }
+
+    /// Single crop coefficient for the growth stage the plan starts in
+    pub fn crop_coefficient(&self, stage: &GrowthStage) -> f64 {
+        match stage {
+            GrowthStage::Germination | GrowthStage::Emergence => self.kc_initial,
+            GrowthStage::Vegetative => 0.5 * (self.kc_initial + self.kc_mid),
+            GrowthStage::Flowering | GrowthStage::Reproduction => self.kc_mid,
+            GrowthStage::Maturation | GrowthStage::Harvest | GrowthStage::Fallow => self.kc_late,
+        }
+    }
+}
+
+impl IrrigationPlanSet {
+    /// Flatten the plans into one CSV row per plan week for the farm manager
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "field_id,crop_type,plan_id,week,week_start,irrigation_mm,nitrogen_kg_ha,phosphorus_kg_ha,\
+             potassium_kg_ha,expected_yield_t_ha,yield_p10_t_ha,expected_net_return,risk_adjusted_net_return,\
+             total_irrigation_mm,expected_nitrogen_leaching_kg_ha\n",
+        );
+
+        for plan in &self.plans {
+            for week in &plan.weeks {
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{:.1},{:.1},{:.1},{:.1},{:.2},{:.2},{:.2},{:.2},{:.1},{:.2}\n",
+                    csv_field(&self.field_id),
+                    csv_field(&self.crop_type),
+                    plan.plan_id,
+                    week.week,
+                    week.week_start.format("%Y-%m-%d"),
+                    week.irrigation_mm,
+                    week.nitrogen_kg_ha,
+                    week.phosphorus_kg_ha,
+                    week.potassium_kg_ha,
+                    plan.expected_yield_t_ha,
+                    plan.yield_p10_t_ha,
+                    plan.expected_net_return,
+                    plan.risk_adjusted_net_return,
+                    plan.total_irrigation_mm,
+                    plan.expected_nitrogen_leaching_kg_ha,
+                ));
+            }
+        }
+
+        csv
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn request(weeks: usize, water_budget_mm: f64) -> IrrigationPlanRequest {
+        IrrigationPlanRequest {
+            field: FieldProfile {
+                field_id: "buhera-7".to_string(),
+                area_ha: 2.0,
+                field_capacity: 0.32,
+                wilting_point: 0.14,
+                root_depth_m: 0.8,
+                initial_depletion_mm: 40.0,
+                irrigation_efficiency: 0.75,
+                soil_nitrogen_kg_ha: 30.0,
+                soil_phosphorus_kg_ha: 15.0,
+                soil_potassium_kg_ha: 60.0,
+            },
+            crop_type: "maize".to_string(),
+            growth_stage: GrowthStage::Vegetative,
+            start_date: Utc.with_ymd_and_hms(2024, 12, 2, 0, 0, 0).unwrap(),
+            water_budget_mm,
+            fertiliser_prices: FertiliserPrices { nitrogen_per_kg: 1.2, phosphorus_per_kg: 1.8, potassium_per_kg: 0.9 },
+            crop_price_per_tonne: 320.0,
+            water_cost_per_m3: 0.05,
+            weekly_forecast: vec![
+                WeeklyWeatherForecast { reference_et_mm: 35.0, rainfall_mm: 20.0, rainfall_cv: 0.6 };
+                weeks
+            ],
+            risk_aversion: None,
+            evaluation_budget: None,
+        }
+    }
+
+    #[test]
+    fn test_decode_scales_irrigation_back_onto_the_water_budget() {
+        let request = request(3, 90.0);
+        let parameters = DVector::from_vec(vec![60.0, 60.0, 30.0, 120.0, 40.0, 50.0, 0.4]);
+        let schedule = IrrigationFertiliserPlanner::decode(&parameters, &request);
+
+        let total: f64 = schedule.iter().map(|w| w.irrigation_mm).sum();
+        assert!((total - 90.0).abs() < 1e-9);
+        // The repair keeps the relative weekly split
+        assert!((schedule[0].irrigation_mm / schedule[2].irrigation_mm - 2.0).abs() < 1e-9);
+
+        let nitrogen: f64 = schedule.iter().map(|w| w.nitrogen_kg_ha).sum();
+        assert!((nitrogen - 120.0).abs() < 1e-9);
+        assert!((schedule[0].nitrogen_kg_ha - 48.0).abs() < 1e-9);
+        assert_eq!(schedule[1].phosphorus_kg_ha, 0.0);
+    }
+
+    #[test]
+    fn test_decode_leaves_schedules_within_budget_untouched() {
+        let request = request(2, 200.0);
+        let parameters = DVector::from_vec(vec![25.0, 35.0, 80.0, 20.0, 30.0, 0.5]);
+        let schedule = IrrigationFertiliserPlanner::decode(&parameters, &request);
+
+        assert_eq!(schedule[0].irrigation_mm, 25.0);
+        assert_eq!(schedule[1].irrigation_mm, 35.0);
+        assert_eq!(schedule[1].week_start, request.start_date + Duration::weeks(1));
+    }
+
+    #[test]
+    fn test_csv_fields_with_separators_and_quotes_are_escaped() {
+        assert_eq!(csv_field("maize"), "maize");
+        assert_eq!(csv_field("north, block 2"), "\"north, block 2\"");
+        assert_eq!(csv_field("the \"dam\" field"), "\"the \"\"dam\"\" field\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+
+        let request = request(1, 50.0);
+        let plan = IrrigationFertiliserPlan {
+            plan_id: 1,
+            weeks: IrrigationFertiliserPlanner::decode(&DVector::from_vec(vec![10.0, 50.0, 10.0, 10.0, 1.0]), &request),
+            expected_yield_t_ha: 4.0,
+            yield_p10_t_ha: 3.1,
+            expected_net_return: 900.0,
+            risk_adjusted_net_return: 850.0,
+            total_irrigation_mm: 10.0,
+            expected_nitrogen_leaching_kg_ha: 2.0,
+            expected_stress_weeks: 0.0,
+        };
+        let plan_set = IrrigationPlanSet {
+            field_id: "Mutare, \"east\"".to_string(),
+            crop_type: "maize".to_string(),
+            risk_aversion: 0.5,
+            plans: vec![plan],
+        };
+        let csv = plan_set.to_csv();
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.starts_with("\"Mutare, \"\"east\"\"\",maize,1,1,2024-12-02,"));
+        assert_eq!(csv.lines().count(), 2);
+    }
+}
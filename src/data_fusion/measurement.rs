@@ -1,10 +1,20 @@
 use std::collections::HashMap;
 use std::f64::consts::PI;
 use chrono::{DateTime, Utc};
-use nalgebra::{Point3, Vector3};
+use nalgebra::{DMatrix, DVector, Point3, Vector3};
+use rustfft::{num_complex::Complex64, FftPlanner};
 
 use crate::error::AppError;
 use super::{SensorType, MeasurementValue};
+use super::spatial_interpolation::ElevationSource;
+
+const SPEED_OF_LIGHT: f64 = 299_792_458.0; // m/s
+/// Lowest sigma-nought reported, dB
+const SIGMA0_FLOOR_DB: f64 = -50.0;
+/// Generalised Hamming coefficient of the range and azimuth weighting
+const HAMMING_COEFFICIENT: f64 = 0.54;
+/// Kernel length of the range cell migration interpolator
+const INTERPOLATION_TAPS: usize = 8;
 
 /// 3D Point representation
 #[derive(Debug, Clone)]
@@ -362,136 +372,452 @@ pub struct SatellitePosition {
     pub timestamp: f64,
 }
 
-/// Range compression filter
+/// Radar parameters of a stripmap SAR acquisition
+#[derive(Debug, Clone)]
+pub struct SarSystemParameters {
+    pub carrier_frequency: f64,   // Hz
+    pub chirp_bandwidth: f64,     // Hz
+    pub pulse_duration: f64,      // seconds
+    pub range_sampling_rate: f64, // Hz
+    pub prf: f64,                 // Hz
+    pub antenna_length: f64,      // meters, along track
+    pub polarisation: String,
+}
+
+impl SarSystemParameters {
+    pub fn wavelength(&self) -> f64 {
+        SPEED_OF_LIGHT / self.carrier_frequency
+    }
+}
+
+impl Default for SarSystemParameters {
+    /// C-band, close to Sentinel-1 interferometric wide swath
+    fn default() -> Self {
+        Self {
+            carrier_frequency: 5.405e9,
+            chirp_bandwidth: 56.5e6,
+            pulse_duration: 52.0e-6,
+            range_sampling_rate: 64.35e6,
+            prf: 1717.0,
+            antenna_length: 12.3,
+            polarisation: "VV".to_string(),
+        }
+    }
+}
+
+/// Straight platform track over a spherical Earth
+#[derive(Debug, Clone)]
+pub struct SarOrbitGeometry {
+    pub altitude: f64,       // meters
+    pub velocity: f64,       // m/s, platform speed along track
+    pub heading_deg: f64,    // track direction clockwise from north
+    pub right_looking: bool,
+    pub nadir_latitude: f64, // degrees, at the first pulse
+    pub nadir_longitude: f64,
+}
+
+impl Default for SarOrbitGeometry {
+    fn default() -> Self {
+        Self {
+            altitude: 693_000.0,
+            velocity: 7_594.0,
+            heading_deg: 193.0,
+            right_looking: true,
+            nadir_latitude: -19.3,
+            nadir_longitude: 37.5,
+        }
+    }
+}
+
+impl SarOrbitGeometry {
+    /// Speed of the zero-Doppler point along the ground
+    pub fn ground_velocity(&self, earth: &EarthModel) -> f64 {
+        self.velocity * earth.radius / (earth.radius + self.altitude)
+    }
+
+    /// Velocity of the equivalent straight-line hyperbolic range history
+    pub fn effective_velocity(&self, earth: &EarthModel) -> f64 {
+        (self.velocity * self.ground_velocity(earth)).sqrt()
+    }
+
+    /// Latitude and longitude of a point `along_track` and `ground_range` from the first nadir point
+    pub fn location(&self, earth_radius: f64, along_track: f64, ground_range: f64) -> (f64, f64) {
+        let heading = self.heading_deg.to_radians();
+        let across = if self.right_looking { ground_range } else { -ground_range };
+        let north = along_track * heading.cos() - across * heading.sin();
+        let east = along_track * heading.sin() + across * heading.cos();
+        let latitude = self.nadir_latitude + (north / earth_radius).to_degrees();
+        let longitude = self.nadir_longitude + (east / (earth_radius * self.nadir_latitude.to_radians().cos())).to_degrees();
+        (latitude, longitude)
+    }
+
+    /// Along-track and ground-range coordinates of a location, the inverse of `location`
+    pub fn track_coordinates(&self, earth_radius: f64, latitude: f64, longitude: f64) -> (f64, f64) {
+        let heading = self.heading_deg.to_radians();
+        let north = (latitude - self.nadir_latitude).to_radians() * earth_radius;
+        let east = (longitude - self.nadir_longitude).to_radians() * earth_radius * self.nadir_latitude.to_radians().cos();
+        let along_track = north * heading.cos() + east * heading.sin();
+        let across = -north * heading.sin() + east * heading.cos();
+        (along_track, if self.right_looking { across } else { -across })
+    }
+}
+
+/// A point scatterer for simulated acquisitions
+#[derive(Debug, Clone)]
+pub struct PointTarget {
+    pub ground_range: f64, // meters from the nadir track
+    pub along_track: f64,  // meters from the first nadir point
+    pub height: f64,       // meters above the sphere
+    pub rcs: f64,          // radar cross-section, m²
+}
+
+/// Raw stripmap echoes: one line of complex baseband samples per transmitted pulse
+#[derive(Debug, Clone)]
+pub struct RawSarData {
+    pub parameters: SarSystemParameters,
+    pub geometry: SarOrbitGeometry,
+    pub pulses: usize,
+    pub range_samples: usize,
+    /// Pulse-major I/Q samples
+    pub echoes: Vec<Complex64>,
+    /// Slant range of the first range sample
+    pub near_slant_range: f64,
+    /// Measured platform position at each pulse as (along track, cross track towards the
+    /// scene, altitude) in meters; empty when the nominal track was flown
+    pub platform_track: Vec<SatellitePosition>,
+}
+
+impl RawSarData {
+    /// Noise-free echoes of point targets seen through the two-way azimuth antenna
+    /// pattern, with amplitudes calibrated so that the focused image integrates to each
+    /// target's cross-section. Positions of `platform_track` deviate from the nominal track.
+    pub fn simulate_point_targets(
+        parameters: SarSystemParameters,
+        geometry: SarOrbitGeometry,
+        near_slant_range: f64,
+        pulses: usize,
+        range_samples: usize,
+        targets: &[PointTarget],
+        platform_track: Vec<SatellitePosition>,
+    ) -> Self {
+        let earth = EarthModel::default();
+        let wavelength = parameters.wavelength();
+        let velocity = geometry.effective_velocity(&earth);
+        let ground_velocity = geometry.ground_velocity(&earth);
+        let chirp = RangeCompressionFilter::new(parameters.chirp_bandwidth, parameters.pulse_duration, parameters.range_sampling_rate);
+        let sample_rate = parameters.range_sampling_rate;
+        let half_pulse = parameters.pulse_duration / 2.0 * sample_rate;
+        let mut echoes = vec![Complex64::new(0.0, 0.0); pulses * range_samples];
+
+        for target in targets {
+            let (target_y, target_z) = earth.cross_track_position(target.ground_range, target.height);
+            let target_x = target.along_track / ground_velocity * velocity;
+            for pulse in 0..pulses {
+                let (platform_y, platform_z) = match platform_track.get(pulse) {
+                    Some(state) => (state.position.y, state.position.z),
+                    None => (0.0, geometry.altitude),
+                };
+                let dx = velocity * pulse as f64 / parameters.prf - target_x;
+                let range = (dx * dx + (target_y - platform_y).powi(2) + (target_z - platform_z).powi(2)).sqrt();
+                let beam = parameters.antenna_length * dx / range / wavelength;
+                if beam.abs() > 3.0 {
+                    continue;
+                }
+                let amplitude = target.rcs.sqrt() * sinc(beam).powi(2);
+                let carrier = Complex64::from_polar(amplitude, -4.0 * PI * range / wavelength);
+                let centre = 2.0 * (range - near_slant_range) / SPEED_OF_LIGHT * sample_rate;
+                let first = (centre - half_pulse).ceil().max(0.0) as usize;
+                let last = (centre + half_pulse).floor().min(range_samples as f64 - 1.0);
+                if last < first as f64 {
+                    continue;
+                }
+                for sample in first..=last as usize {
+                    echoes[pulse * range_samples + sample] += carrier * chirp.chirp((sample as f64 - centre) / sample_rate);
+                }
+            }
+        }
+
+        Self { parameters, geometry, pulses, range_samples, echoes, near_slant_range, platform_track }
+    }
+}
+
+/// Matched filter for the transmitted linear FM chirp
 #[derive(Debug, Clone)]
 pub struct RangeCompressionFilter {
-    pub filter_coefficients: Vec<f64>,
-    pub sample_rate: f64,
+    pub chirp_rate: f64,     // Hz/s
+    pub pulse_duration: f64, // seconds
+    pub sample_rate: f64,    // Hz
+    /// Generalised Hamming coefficient of the replica taper: 1 for none, 0.54 for Hamming
+    pub window_coefficient: f64,
 }
 
 impl RangeCompressionFilter {
-    pub fn new(bandwidth: f64, sample_rate: f64) -> Self {
-        let num_samples = (sample_rate / bandwidth * 100.0) as usize;
-        let mut coefficients = Vec::with_capacity(num_samples);
-        
-        for i in 0..num_samples {
-            let t = i as f64 / sample_rate;
-            let phase = PI * bandwidth * t.powi(2);
-            coefficients.push(phase.cos());
-        }
-        
+    pub fn new(bandwidth: f64, pulse_duration: f64, sample_rate: f64) -> Self {
         Self {
-            filter_coefficients: coefficients,
+            chirp_rate: bandwidth / pulse_duration,
+            pulse_duration,
             sample_rate,
+            window_coefficient: HAMMING_COEFFICIENT,
         }
     }
 
-    pub fn apply(&self, signal: &[f64]) -> Vec<f64> {
-        let mut output = vec![0.0; signal.len()];
-        
-        for i in 0..signal.len() {
-            for j in 0..self.filter_coefficients.len().min(i + 1) {
-                output[i] += signal[i - j] * self.filter_coefficients[j];
+    pub fn bandwidth(&self) -> f64 {
+        self.chirp_rate * self.pulse_duration
+    }
+
+    /// Baseband chirp at time `t` from the pulse centre
+    pub fn chirp(&self, t: f64) -> Complex64 {
+        if t.abs() > self.pulse_duration / 2.0 {
+            Complex64::new(0.0, 0.0)
+        } else {
+            Complex64::from_polar(1.0, PI * self.chirp_rate * t * t)
+        }
+    }
+
+    /// The chirp sampled symmetrically about its centre, an odd number of samples long
+    pub fn replica(&self) -> Vec<Complex64> {
+        let half = (self.pulse_duration * self.sample_rate / 2.0).floor() as i64;
+        (-half..=half).map(|k| self.chirp(k as f64 / self.sample_rate)).collect()
+    }
+
+    /// Compress each `line_length` line of `lines` by fast correlation with the tapered
+    /// replica; an echo whose chirp is centred on sample m peaks at m
+    pub fn apply_lines(&self, lines: &[Complex64], line_length: usize) -> Vec<Complex64> {
+        let replica = self.replica();
+        let half = replica.len() / 2;
+        let fft_len = (line_length + replica.len()).next_power_of_two();
+        let mut planner = FftPlanner::new();
+        let forward = planner.plan_fft_forward(fft_len);
+        let inverse = planner.plan_fft_inverse(fft_len);
+
+        // Replica stored circularly so that its centre sits at lag zero
+        let mut reference = vec![Complex64::new(0.0, 0.0); fft_len];
+        for (k, value) in replica.iter().enumerate() {
+            reference[(k + fft_len - half) % fft_len] = value * generalised_hamming(self.window_coefficient, k, replica.len());
+        }
+        forward.process(&mut reference);
+        for value in reference.iter_mut() {
+            *value = value.conj() / fft_len as f64;
+        }
+
+        let mut output = Vec::with_capacity(lines.len());
+        let mut buffer = vec![Complex64::new(0.0, 0.0); fft_len];
+        for line in lines.chunks(line_length) {
+            buffer.fill(Complex64::new(0.0, 0.0));
+            buffer[..line.len()].copy_from_slice(line);
+            forward.process(&mut buffer);
+            for (value, filter) in buffer.iter_mut().zip(&reference) {
+                *value *= filter;
             }
+            inverse.process(&mut buffer);
+            output.extend_from_slice(&buffer[..line.len()]);
         }
-        
         output
     }
+
+    pub fn apply(&self, echo: &[Complex64]) -> Vec<Complex64> {
+        if echo.is_empty() {
+            return Vec::new();
+        }
+        self.apply_lines(echo, echo.len())
+    }
+
+    /// Energy of the compressed response to a unit-amplitude echo
+    pub fn energy_gain(&self) -> f64 {
+        let replica = self.replica();
+        let mut line = vec![Complex64::new(0.0, 0.0); 3 * replica.len()];
+        line[replica.len()..2 * replica.len()].copy_from_slice(&replica);
+        self.apply(&line).iter().map(|v| v.norm_sqr()).sum()
+    }
 }
 
-/// Azimuth compression filter
+/// Range–Doppler azimuth matched filter for a hyperbolic range history
 #[derive(Debug, Clone)]
 pub struct AzimuthCompressionFilter {
-    pub doppler_coefficients: Vec<f64>,
-    pub prf: f64, // Pulse Repetition Frequency
+    pub wavelength: f64,          // meters
+    pub effective_velocity: f64,  // m/s
+    pub prf: f64,                 // Pulse Repetition Frequency
+    pub doppler_centroid: f64,    // Hz
+    pub processed_bandwidth: f64, // Hz, the spectrum outside it is discarded
+    pub window_coefficient: f64,
 }
 
 impl AzimuthCompressionFilter {
-    pub fn new(doppler_bandwidth: f64, prf: f64) -> Self {
-        let num_coeffs = (prf / doppler_bandwidth * 50.0) as usize;
-        let mut coefficients = Vec::with_capacity(num_coeffs);
-        
-        for i in 0..num_coeffs {
-            let freq = (i as f64 - num_coeffs as f64 / 2.0) * doppler_bandwidth / num_coeffs as f64;
-            let phase = 2.0 * PI * freq / prf;
-            coefficients.push(phase.cos());
-        }
-        
+    pub fn new(wavelength: f64, effective_velocity: f64, prf: f64, antenna_length: f64) -> Self {
         Self {
-            doppler_coefficients: coefficients,
+            wavelength,
+            effective_velocity,
             prf,
+            doppler_centroid: 0.0,
+            processed_bandwidth: (2.0 * effective_velocity / antenna_length).min(prf),
+            window_coefficient: HAMMING_COEFFICIENT,
         }
     }
 
-    pub fn apply(&self, signal: &[f64]) -> Vec<f64> {
-        let mut output = vec![0.0; signal.len()];
-        
-        for i in 0..signal.len() {
-            for j in 0..self.doppler_coefficients.len().min(i + 1) {
-                output[i] += signal[i - j] * self.doppler_coefficients[j];
-            }
+    /// Doppler frequency of FFT bin `bin` of `bins`, unwrapped about the centroid
+    pub fn doppler_frequency(&self, bin: usize, bins: usize) -> f64 {
+        let frequency = bin as f64 * self.prf / bins as f64;
+        frequency - self.prf * ((frequency - self.doppler_centroid) / self.prf).round()
+    }
+
+    /// Cosine of the squint angle that Doppler frequency `f` is seen at
+    pub fn migration_factor(&self, f: f64) -> f64 {
+        (1.0 - (self.wavelength * f / (2.0 * self.effective_velocity)).powi(2)).max(1e-6).sqrt()
+    }
+
+    /// Extra slant range at Doppler frequency `f` of a target at closest approach `slant_range`
+    pub fn range_migration(&self, f: f64, slant_range: f64) -> f64 {
+        slant_range * (1.0 / self.migration_factor(f) - 1.0)
+    }
+
+    fn window(&self, f: f64) -> f64 {
+        let offset = f - self.doppler_centroid;
+        if offset.abs() > self.processed_bandwidth / 2.0 {
+            return 0.0;
         }
-        
-        output
+        self.window_coefficient + (1.0 - self.window_coefficient) * (2.0 * PI * offset / self.processed_bandwidth).cos()
+    }
+
+    /// Filter response at Doppler frequency `f` for closest approach `slant_range`
+    pub fn transfer(&self, f: f64, slant_range: f64) -> Complex64 {
+        let weight = self.window(f);
+        if weight == 0.0 {
+            return Complex64::new(0.0, 0.0);
+        }
+        Complex64::from_polar(weight, 4.0 * PI * slant_range * self.migration_factor(f) / self.wavelength)
+    }
+
+    /// Compress the azimuth line of one range bin, without range migration correction
+    pub fn apply(&self, line: &[Complex64], slant_range: f64) -> Vec<Complex64> {
+        let mut planner = FftPlanner::new();
+        let mut spectrum = line.to_vec();
+        planner.plan_fft_forward(line.len()).process(&mut spectrum);
+        for (bin, value) in spectrum.iter_mut().enumerate() {
+            *value *= self.transfer(self.doppler_frequency(bin, line.len()), slant_range) / line.len() as f64;
+        }
+        planner.plan_fft_inverse(line.len()).process(&mut spectrum);
+        spectrum
+    }
+
+    /// Energy of the compressed response of a unit point target at `slant_range` seen
+    /// through the two-way pattern of an antenna `antenna_length` long
+    pub fn energy_gain(&self, slant_range: f64, antenna_length: f64) -> f64 {
+        const STEPS: usize = 512;
+        let step = self.processed_bandwidth / STEPS as f64;
+        let integral: f64 = (0..STEPS)
+            .map(|i| {
+                let f = self.doppler_centroid - self.processed_bandwidth / 2.0 + (i as f64 + 0.5) * step;
+                let pattern = sinc(antenna_length * f / (2.0 * self.effective_velocity)).powi(2);
+                (self.window(f) * pattern).powi(2) * step
+            })
+            .sum();
+        // Stationary phase: each Doppler frequency is seen for dη/df = λR/2V² seconds
+        self.prf * self.wavelength * slant_range / (2.0 * self.effective_velocity.powi(2)) * integral
     }
 }
 
-/// Motion compensator for satellite platform motion
+/// First-order motion compensation against a smoothed reference track
 #[derive(Debug, Clone)]
 pub struct MotionCompensator {
-    pub reference_trajectory: Vec<SatellitePosition>,
     pub compensation_parameters: MotionCompensationParameters,
 }
 
 #[derive(Debug, Clone)]
 pub struct MotionCompensationParameters {
+    /// Refer measured positions to a constant-velocity track
     pub velocity_compensation: bool,
+    /// Refer measured positions to a constant-acceleration track instead
     pub acceleration_compensation: bool,
-    pub attitude_compensation: bool,
 }
 
 impl MotionCompensator {
     pub fn new() -> Self {
         Self {
-            reference_trajectory: Vec::new(),
             compensation_parameters: MotionCompensationParameters {
                 velocity_compensation: true,
                 acceleration_compensation: true,
-                attitude_compensation: false,
             },
         }
     }
 
-    pub fn compensate_platform_motion(&self, data: &SatelliteImageryData) -> MotionCompensatedData {
-        let mut compensated_signal = data.raw_signal.clone();
-        
-        if self.compensation_parameters.velocity_compensation {
-            for i in 1..compensated_signal.len() {
-                compensated_signal[i] -= compensated_signal[i-1] * 0.01;
+    fn reference_degree(&self) -> Option<usize> {
+        let parameters = &self.compensation_parameters;
+        if parameters.acceleration_compensation {
+            Some(2)
+        } else if parameters.velocity_compensation {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// Line-of-sight range error of each measured position towards the point
+    /// (`cross_track`, `height`) of the platform frame, against the fitted reference track
+    pub fn range_errors(&self, track: &[SatellitePosition], cross_track: f64, height: f64) -> Vec<f64> {
+        let Some(degree) = self.reference_degree().filter(|degree| track.len() > *degree) else {
+            return vec![0.0; track.len()];
+        };
+        let reference_y = fit_polynomial(track, degree, |state| state.position.y);
+        let reference_z = fit_polynomial(track, degree, |state| state.position.z);
+        track
+            .iter()
+            .zip(reference_y.iter().zip(&reference_z))
+            .map(|(state, (y, z))| {
+                let actual = (cross_track - state.position.y).hypot(height - state.position.z);
+                let reference = (cross_track - y).hypot(height - z);
+                actual - reference
+            })
+            .collect()
+    }
+
+    /// Shift each range line by its range error at mid-swath and remove the matching
+    /// carrier phase, then refer the track to the fitted reference
+    pub fn compensate(&self, raw: &mut RawSarData, earth: &EarthModel) {
+        if raw.platform_track.len() != raw.pulses || raw.range_samples == 0 {
+            return;
+        }
+        let parameters = &raw.parameters;
+        let mid_range = raw.near_slant_range + raw.range_samples as f64 / 2.0 * SPEED_OF_LIGHT / (2.0 * parameters.range_sampling_rate);
+        let Some(mid_ground_range) = earth.ground_range(raw.geometry.altitude, mid_range, 0.0) else {
+            return;
+        };
+        let (cross_track, height) = earth.cross_track_position(mid_ground_range, 0.0);
+        let errors = self.range_errors(&raw.platform_track, cross_track, height);
+
+        let length = raw.range_samples;
+        let mut planner = FftPlanner::new();
+        let forward = planner.plan_fft_forward(length);
+        let inverse = planner.plan_fft_inverse(length);
+        let wavelength = parameters.wavelength();
+        for (line, error) in raw.echoes.chunks_mut(length).zip(&errors) {
+            forward.process(line);
+            for (bin, value) in line.iter_mut().enumerate() {
+                let frequency = signed_frequency(bin, length, parameters.range_sampling_rate);
+                let phase = 2.0 * PI * frequency * 2.0 * error / SPEED_OF_LIGHT + 4.0 * PI * error / wavelength;
+                *value *= Complex64::from_polar(1.0 / length as f64, phase);
             }
+            inverse.process(line);
         }
-        
-        MotionCompensatedData {
-            compensated_signal,
-            original_data: data.clone(),
-            compensation_quality: 0.95,
+
+        let degree = self.reference_degree().unwrap_or(1);
+        if raw.platform_track.len() > degree {
+            let reference_y = fit_polynomial(&raw.platform_track, degree, |state| state.position.y);
+            let reference_z = fit_polynomial(&raw.platform_track, degree, |state| state.position.z);
+            for (state, (y, z)) in raw.platform_track.iter_mut().zip(reference_y.into_iter().zip(reference_z)) {
+                state.position.y = y;
+                state.position.z = z;
+            }
         }
     }
 }
 
-/// Motion compensated data
-#[derive(Debug, Clone)]
-pub struct MotionCompensatedData {
-    pub compensated_signal: Vec<f64>,
-    pub original_data: SatelliteImageryData,
-    pub compensation_quality: f64,
-}
-
 /// Geometric corrector
 #[derive(Debug, Clone)]
 pub struct GeometricCorrector {
     pub earth_model: EarthModel,
     pub projection_parameters: ProjectionParameters,
+    /// Output pixel spacing on the ground, meters
+    pub pixel_spacing: f64,
 }
 
 #[derive(Debug, Clone)]
@@ -500,88 +826,182 @@ pub struct EarthModel {
     pub flattening: f64,
 }
 
+impl Default for EarthModel {
+    fn default() -> Self {
+        Self {
+            radius: 6371000.0,
+            flattening: 1.0 / 298.257223563,
+        }
+    }
+}
+
+impl EarthModel {
+    /// Horizontal and vertical position of a ground point in the frame of a platform
+    /// above the nadir point, with the Earth treated as a sphere of `radius`
+    pub fn cross_track_position(&self, ground_range: f64, height: f64) -> (f64, f64) {
+        let angle = ground_range / self.radius;
+        ((self.radius + height) * angle.sin(), (self.radius + height) * angle.cos() - self.radius)
+    }
+
+    /// Slant range from a platform at `altitude` to a point `ground_range` across track at `height`
+    pub fn slant_range(&self, altitude: f64, ground_range: f64, height: f64) -> f64 {
+        let (platform, point) = (self.radius + altitude, self.radius + height);
+        (platform * platform + point * point - 2.0 * platform * point * (ground_range / self.radius).cos()).sqrt()
+    }
+
+    /// Ground range of a point at `height` seen at `slant_range`; `None` when the range cannot reach it
+    pub fn ground_range(&self, altitude: f64, slant_range: f64, height: f64) -> Option<f64> {
+        let (platform, point) = (self.radius + altitude, self.radius + height);
+        let cosine = (platform * platform + point * point - slant_range * slant_range) / (2.0 * platform * point);
+        (-1.0..=1.0).contains(&cosine).then(|| self.radius * cosine.acos())
+    }
+
+    /// Incidence angle from the local vertical, radians
+    pub fn incidence_angle(&self, altitude: f64, ground_range: f64, height: f64) -> f64 {
+        let (platform, point) = (self.radius + altitude, self.radius + height);
+        let slant_range = self.slant_range(altitude, ground_range, height);
+        let cosine = (platform * platform - point * point - slant_range * slant_range) / (2.0 * point * slant_range);
+        cosine.clamp(-1.0, 1.0).acos()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ProjectionParameters {
     pub projection_type: String,
     pub reference_ellipsoid: String,
 }
 
+/// Terrain-corrected backscatter on a ground-range / along-track grid
+#[derive(Debug, Clone)]
+pub struct SigmaNoughtImage {
+    pub rows: usize, // along track
+    pub cols: usize, // ground range
+    pub pixel_spacing: f64,
+    pub near_ground_range: f64,
+    pub earth_radius: f64,
+    pub geometry: SarOrbitGeometry,
+    pub polarisation: String,
+    /// σ0 in dB, row-major; NaN outside the focused image, in layover and in radar shadow
+    pub sigma0_db: Vec<f64>,
+    /// Incidence angle on the local terrain slope in degrees, row-major
+    pub local_incidence_deg: Vec<f64>,
+}
+
+impl SigmaNoughtImage {
+    pub fn value_db(&self, row: usize, col: usize) -> Option<f64> {
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+        Some(self.sigma0_db[row * self.cols + col]).filter(|v| v.is_finite())
+    }
+
+    /// Latitude and longitude of a pixel centre
+    pub fn pixel_location(&self, row: usize, col: usize) -> (f64, f64) {
+        let ground_range = self.near_ground_range + col as f64 * self.pixel_spacing;
+        self.geometry.location(self.earth_radius, row as f64 * self.pixel_spacing, ground_range)
+    }
+
+    /// Pixel containing a location
+    pub fn pixel_at(&self, latitude: f64, longitude: f64) -> Option<(usize, usize)> {
+        let (along_track, ground_range) = self.geometry.track_coordinates(self.earth_radius, latitude, longitude);
+        let row = (along_track / self.pixel_spacing).round();
+        let col = ((ground_range - self.near_ground_range) / self.pixel_spacing).round();
+        (row >= 0.0 && col >= 0.0 && (row as usize) < self.rows && (col as usize) < self.cols).then_some((row as usize, col as usize))
+    }
+}
+
 impl GeometricCorrector {
     pub fn new() -> Self {
         Self {
-            earth_model: EarthModel {
-                radius: 6371000.0,
-                flattening: 1.0 / 298.257223563,
-            },
+            earth_model: EarthModel::default(),
             projection_parameters: ProjectionParameters {
-                projection_type: "UTM".to_string(),
+                projection_type: "ground_range".to_string(),
                 reference_ellipsoid: "WGS84".to_string(),
             },
+            pixel_spacing: 10.0,
         }
     }
 
-    pub fn correct_earth_curvature_and_projection(&self, data: &MotionCompensatedData) -> GeometricallyCorrectData {
-        let curvature_corrected = self.apply_earth_curvature_correction(data);
-        let projection_corrected = self.convert_slant_to_ground_range(&curvature_corrected);
-        let layover_corrected = self.correct_layover_effects(&projection_corrected);
-        
-        GeometricallyCorrectData {
-            corrected_positions: layover_corrected,
-            geometric_accuracy: self.compute_geometric_accuracy(),
-        }
-    }
-
-    fn apply_earth_curvature_correction(&self, data: &MotionCompensatedData) -> Vec<f64> {
-        let mut corrected = data.compensated_signal.clone();
-        let curvature_factor = 1.0 / self.earth_model.radius;
-        
-        for i in 0..corrected.len() {
-            let distance = i as f64 * 1000.0;
-            let curvature_correction = curvature_factor * distance.powi(2) / 2.0;
-            corrected[i] *= (1.0 + curvature_correction);
-        }
-        
-        corrected
-    }
-
-    fn convert_slant_to_ground_range(&self, data: &[f64]) -> Vec<f64> {
-        let mut ground_range = data.to_vec();
-        
-        for i in 0..ground_range.len() {
-            let slant_range = i as f64 * 100.0;
-            let incidence_angle = 30.0_f64.to_radians();
-            let ground_distance = slant_range * incidence_angle.sin();
-            ground_range[i] = ground_distance;
+    /// Resample a focused image onto a ground grid, placing each pixel at its terrain
+    /// height and normalising β0 by the sine of the local incidence angle. Heights come
+    /// from `elevation` where it has data and are zero otherwise; the image is multilooked
+    /// to roughly the output spacing first.
+    pub fn terrain_correct(
+        &self,
+        image: &FocusedSarImage,
+        elevation: Option<&dyn ElevationSource>,
+        atmosphere: &AtmosphericCorrector,
+    ) -> Result<SigmaNoughtImage, AppError> {
+        let earth = &self.earth_model;
+        let geometry = &image.geometry;
+        let altitude = geometry.altitude;
+        if image.pulses == 0 || image.range_samples == 0 || self.pixel_spacing <= 0.0 {
+            return Err(AppError::validation("Cannot terrain-correct an empty SAR image"));
         }
-        
-        ground_range
-    }
+        let far_slant_range = image.slant_range((image.range_samples - 1) as f64);
+        let (Some(near_ground_range), Some(far_ground_range)) = (
+            earth.ground_range(altitude, image.near_slant_range, 0.0),
+            earth.ground_range(altitude, far_slant_range, 0.0),
+        ) else {
+            return Err(AppError::validation("SAR slant ranges do not reach the ground"));
+        };
+        let cols = ((far_ground_range - near_ground_range) / self.pixel_spacing).floor() as usize + 1;
+        let rows = ((image.pulses - 1) as f64 * image.azimuth_spacing / self.pixel_spacing).floor() as usize + 1;
+
+        let mid_incidence = earth.incidence_angle(altitude, (near_ground_range + far_ground_range) / 2.0, 0.0);
+        let range_looks = ((self.pixel_spacing * mid_incidence.sin() / image.range_spacing).round() as usize).max(1);
+        let azimuth_looks = ((self.pixel_spacing / image.azimuth_spacing).round() as usize).max(1);
+        let beta_nought = multilook(image, azimuth_looks, range_looks);
+
+        let height_at = |along_track: f64, ground_range: f64| {
+            elevation
+                .and_then(|source| {
+                    let (latitude, longitude) = geometry.location(earth.radius, along_track, ground_range);
+                    source.elevation_m(latitude, longitude)
+                })
+                .unwrap_or(0.0)
+        };
 
-    fn correct_layover_effects(&self, data: &[f64]) -> Vec<f64> {
-        let mut corrected = data.to_vec();
-        
-        for i in 1..corrected.len() - 1 {
-            let slope = corrected[i+1] - corrected[i-1];
-            if slope.abs() > 0.1 {
-                corrected[i] *= 0.8;
+        let mut sigma0_db = vec![f64::NAN; rows * cols];
+        let mut local_incidence_deg = vec![f64::NAN; rows * cols];
+        for row in 0..rows {
+            let along_track = row as f64 * self.pixel_spacing;
+            let pulse = along_track / image.azimuth_spacing;
+            for col in 0..cols {
+                let ground_range = near_ground_range + col as f64 * self.pixel_spacing;
+                let height = height_at(along_track, ground_range);
+                let rise = height_at(along_track, ground_range + self.pixel_spacing / 2.0)
+                    - height_at(along_track, ground_range - self.pixel_spacing / 2.0);
+                let incidence = earth.incidence_angle(altitude, ground_range, height);
+                // Ground rising away from the radar faces it, steepening the local view
+                let local_incidence = incidence - (rise / self.pixel_spacing).atan();
+                local_incidence_deg[row * cols + col] = local_incidence.to_degrees();
+                if local_incidence <= 0.0 || local_incidence >= PI / 2.0 {
+                    continue; // layover or shadow
+                }
+                let slant_range = earth.slant_range(altitude, ground_range, height)
+                    + atmosphere.slant_range_delay(image.parameters.carrier_frequency, incidence);
+                let sample = (slant_range - image.near_slant_range) / image.range_spacing;
+                if let Some(beta) = bilinear(&beta_nought, image.pulses, image.range_samples, pulse, sample) {
+                    sigma0_db[row * cols + col] = to_db(beta * local_incidence.sin());
+                }
             }
         }
-        
-        corrected
-    }
 
-    fn compute_geometric_accuracy(&self) -> f64 {
-        0.95
+        Ok(SigmaNoughtImage {
+            rows,
+            cols,
+            pixel_spacing: self.pixel_spacing,
+            near_ground_range,
+            earth_radius: earth.radius,
+            geometry: geometry.clone(),
+            polarisation: image.parameters.polarisation.clone(),
+            sigma0_db,
+            local_incidence_deg,
+        })
     }
 }
 
-/// Geometrically correct data
-#[derive(Debug, Clone)]
-pub struct GeometricallyCorrectData {
-    pub corrected_positions: Vec<f64>,
-    pub geometric_accuracy: f64,
-}
-
 /// Atmospheric corrector
 #[derive(Debug, Clone)]
 pub struct AtmosphericCorrector {
@@ -619,59 +1039,91 @@ impl AtmosphericCorrector {
         }
     }
 
-    pub fn correct_atmospheric_delays(&self, data: &GeometricallyCorrectData) -> AtmosphereCorrectedData {
-        let iono_corrected = self.correct_ionospheric_delay(data);
-        let tropo_corrected = self.correct_tropospheric_delay(&iono_corrected);
-        let vapor_corrected = self.correct_water_vapor_effects(&tropo_corrected);
-        
-        AtmosphereCorrectedData {
-            corrected_data: vapor_corrected,
-            atmospheric_delay_ns: self.total_atmospheric_delay,
-        }
+    /// One-way excess path in meters at `incidence_angle`: zenith tropospheric delay and
+    /// first-order ionospheric group delay, with a flat-Earth mapping function
+    pub fn slant_range_delay(&self, frequency: f64, incidence_angle: f64) -> f64 {
+        let mapping = 1.0 / incidence_angle.cos().max(0.1);
+        let tropospheric = self.tropospheric_model.wet_delay + self.tropospheric_model.dry_delay;
+        let ionospheric =
+            -self.ionospheric_model.frequency_dependence * self.ionospheric_model.total_electron_content / (frequency * frequency);
+        (tropospheric + ionospheric) * mapping
     }
+}
 
-    fn correct_ionospheric_delay(&self, data: &GeometricallyCorrectData) -> Vec<f64> {
-        let mut corrected = data.corrected_positions.clone();
-        let iono_delay = self.ionospheric_model.total_electron_content * self.ionospheric_model.frequency_dependence / 1e16;
-        
-        for value in corrected.iter_mut() {
-            *value += iono_delay;
-        }
-        
-        corrected
+/// Focused single-look complex image in slant range and azimuth, calibrated so that
+/// |value|² is the radar brightness β0
+#[derive(Debug, Clone)]
+pub struct FocusedSarImage {
+    pub pulses: usize,
+    pub range_samples: usize,
+    /// Pulse-major complex amplitudes
+    pub slc: Vec<Complex64>,
+    pub near_slant_range: f64,
+    pub range_spacing: f64,   // meters, slant
+    pub azimuth_spacing: f64, // meters, along the ground
+    pub parameters: SarSystemParameters,
+    pub geometry: SarOrbitGeometry,
+}
+
+/// Impulse response of a focused point target
+#[derive(Debug, Clone)]
+pub struct PointTargetResponse {
+    pub pulse: usize,
+    pub sample: usize,
+    pub range_resolution: f64,   // meters, 3 dB width in slant range
+    pub azimuth_resolution: f64, // meters, 3 dB width along the ground
+    pub range_pslr_db: f64,
+    pub azimuth_pslr_db: f64,
+    /// β0 integrated over the response, m²
+    pub integrated_rcs: f64,
+}
+
+impl FocusedSarImage {
+    pub fn beta_nought(&self, pulse: usize, sample: usize) -> f64 {
+        self.slc[pulse * self.range_samples + sample].norm_sqr()
     }
 
-    fn correct_tropospheric_delay(&self, data: &[f64]) -> Vec<f64> {
-        let mut corrected = data.to_vec();
-        let tropo_delay = (self.tropospheric_model.wet_delay + self.tropospheric_model.dry_delay) / 
-                         self.tropospheric_model.elevation_angle.sin();
-        
-        for value in corrected.iter_mut() {
-            *value += tropo_delay;
-        }
-        
-        corrected
+    pub fn slant_range(&self, sample: f64) -> f64 {
+        self.near_slant_range + sample * self.range_spacing
     }
 
-    fn correct_water_vapor_effects(&self, data: &[f64]) -> Vec<f64> {
-        let mut corrected = data.to_vec();
-        let vapor_correction = 0.05;
-        
-        for value in corrected.iter_mut() {
-            *value += vapor_correction;
+    /// Measure the response of the brightest pixel within a few pixels of (`pulse`, `sample`)
+    pub fn point_target_response(&self, pulse: usize, sample: usize) -> PointTargetResponse {
+        const SEARCH: usize = 4;
+        const HALF_WINDOW: usize = 16;
+        const UPSAMPLING: usize = 16;
+        let rows = pulse.saturating_sub(SEARCH)..(pulse + SEARCH + 1).min(self.pulses);
+        let (peak_pulse, peak_sample) = rows
+            .flat_map(|p| (sample.saturating_sub(SEARCH)..(sample + SEARCH + 1).min(self.range_samples)).map(move |s| (p, s)))
+            .max_by(|a, b| self.beta_nought(a.0, a.1).total_cmp(&self.beta_nought(b.0, b.1)))
+            .unwrap_or((pulse, sample));
+
+        let range_cut: Vec<Complex64> = window_indices(peak_sample, HALF_WINDOW, self.range_samples)
+            .map(|s| self.slc[peak_pulse * self.range_samples + s])
+            .collect();
+        let azimuth_cut: Vec<Complex64> = window_indices(peak_pulse, HALF_WINDOW, self.pulses)
+            .map(|p| self.slc[p * self.range_samples + peak_sample])
+            .collect();
+        let (range_width, range_pslr_db) = impulse_width_and_pslr(&upsample(&range_cut, UPSAMPLING));
+        let (azimuth_width, azimuth_pslr_db) = impulse_width_and_pslr(&upsample(&azimuth_cut, UPSAMPLING));
+
+        let integrated_beta: f64 = window_indices(peak_pulse, HALF_WINDOW, self.pulses)
+            .flat_map(|p| window_indices(peak_sample, HALF_WINDOW, self.range_samples).map(move |s| (p, s)))
+            .map(|(p, s)| self.beta_nought(p, s))
+            .sum();
+
+        PointTargetResponse {
+            pulse: peak_pulse,
+            sample: peak_sample,
+            range_resolution: range_width / UPSAMPLING as f64 * self.range_spacing,
+            azimuth_resolution: azimuth_width / UPSAMPLING as f64 * self.azimuth_spacing,
+            range_pslr_db,
+            azimuth_pslr_db,
+            integrated_rcs: integrated_beta * self.range_spacing * self.azimuth_spacing,
         }
-        
-        corrected
     }
 }
 
-/// Atmosphere corrected data
-#[derive(Debug, Clone)]
-pub struct AtmosphereCorrectedData {
-    pub corrected_data: Vec<f64>,
-    pub atmospheric_delay_ns: f64,
-}
-
 /// SAR focused data
 #[derive(Debug, Clone)]
 pub struct SARFocusedData {
@@ -687,7 +1139,8 @@ pub struct FocusQualityMetrics {
     pub resolution_degradation: f64,
 }
 
-/// Synthetic Aperture Processor
+/// Synthetic Aperture Processor: range–Doppler focusing of stripmap echoes followed by
+/// terrain correction to sigma-nought
 pub struct SyntheticApertureProcessor {
     pub satellite_trajectory: Vec<SatellitePosition>,
     pub coherent_processing_interval: f64,
@@ -696,68 +1149,155 @@ pub struct SyntheticApertureProcessor {
     pub motion_compensation: MotionCompensator,
     pub geometric_corrector: GeometricCorrector,
     pub atmospheric_corrector: AtmosphericCorrector,
-    pub secondary_range_compression: RangeCompressionFilter,
     pub last_corrections: ApertureCorrections,
 }
 
 impl SyntheticApertureProcessor {
     pub fn new() -> Self {
+        Self::for_acquisition(&SarSystemParameters::default(), &SarOrbitGeometry::default())
+    }
+
+    /// Processor with filters matched to a radar and its orbit
+    pub fn for_acquisition(parameters: &SarSystemParameters, geometry: &SarOrbitGeometry) -> Self {
+        let geometric_corrector = GeometricCorrector::new();
+        let velocity = geometry.effective_velocity(&geometric_corrector.earth_model);
+        let wavelength = parameters.wavelength();
         Self {
             satellite_trajectory: Vec::new(),
-            coherent_processing_interval: 1.0,
-            range_compression: RangeCompressionFilter::new(100e6, 1e9),
-            azimuth_compression: AzimuthCompressionFilter::new(1000.0, 2000.0),
+            // Synthetic aperture time at a slant range equal to the altitude
+            coherent_processing_interval: wavelength * geometry.altitude / (parameters.antenna_length * velocity),
+            range_compression: RangeCompressionFilter::new(
+                parameters.chirp_bandwidth,
+                parameters.pulse_duration,
+                parameters.range_sampling_rate,
+            ),
+            azimuth_compression: AzimuthCompressionFilter::new(wavelength, velocity, parameters.prf, parameters.antenna_length),
             motion_compensation: MotionCompensator::new(),
-            geometric_corrector: GeometricCorrector::new(),
+            geometric_corrector,
             atmospheric_corrector: AtmosphericCorrector::new(),
-            secondary_range_compression: RangeCompressionFilter::new(50e6, 1e9),
             last_corrections: ApertureCorrections::default(),
         }
     }
 
+    /// Focus raw echoes: motion compensation, range compression, azimuth FFT, range cell
+    /// migration correction, azimuth compression and radiometric calibration to β0.
+    /// Azimuth processing is circular, so targets within half an aperture of the first or
+    /// last pulse are only partly focused.
+    pub fn focus(&self, raw: &RawSarData) -> Result<FocusedSarImage, AppError> {
+        let (pulses, range_samples) = (raw.pulses, raw.range_samples);
+        if pulses == 0 || range_samples == 0 || raw.echoes.len() != pulses * range_samples {
+            return Err(AppError::validation(format!(
+                "Raw SAR data has {} samples for {} pulses of {} range samples",
+                raw.echoes.len(), pulses, range_samples
+            )));
+        }
+        if !raw.platform_track.is_empty() && raw.platform_track.len() != pulses {
+            return Err(AppError::validation("Platform track must have one position per pulse"));
+        }
+        let parameters = &raw.parameters;
+        let matched = |a: f64, b: f64| (a - b).abs() <= 1e-9 * a.abs().max(b.abs());
+        if !matched(self.range_compression.sample_rate, parameters.range_sampling_rate)
+            || !matched(self.azimuth_compression.prf, parameters.prf)
+        {
+            return Err(AppError::validation("Processor filters do not match the acquisition's sampling rate and PRF"));
+        }
+
+        let earth = &self.geometric_corrector.earth_model;
+        let compensated;
+        let raw = if raw.platform_track.is_empty() {
+            raw
+        } else {
+            let mut copy = raw.clone();
+            self.motion_compensation.compensate(&mut copy, earth);
+            compensated = copy;
+            &compensated
+        };
+
+        let compressed = self.range_compression.apply_lines(&raw.echoes, range_samples);
+
+        let mut planner = FftPlanner::new();
+        let forward = planner.plan_fft_forward(pulses);
+        let inverse = planner.plan_fft_inverse(pulses);
+        let range_doppler: Vec<Vec<Complex64>> = (0..range_samples)
+            .map(|sample| {
+                let mut line: Vec<Complex64> = (0..pulses).map(|pulse| compressed[pulse * range_samples + sample]).collect();
+                forward.process(&mut line);
+                line
+            })
+            .collect();
+
+        let azimuth = &self.azimuth_compression;
+        let range_spacing = SPEED_OF_LIGHT / (2.0 * parameters.range_sampling_rate);
+        let azimuth_spacing = raw.geometry.ground_velocity(earth) / parameters.prf;
+        let frequencies: Vec<f64> = (0..pulses).map(|bin| azimuth.doppler_frequency(bin, pulses)).collect();
+        let range_gain = self.range_compression.energy_gain();
+
+        let mut slc = vec![Complex64::new(0.0, 0.0); pulses * range_samples];
+        let mut line = vec![Complex64::new(0.0, 0.0); pulses];
+        for sample in 0..range_samples {
+            let slant_range = raw.near_slant_range + sample as f64 * range_spacing;
+            for (bin, value) in line.iter_mut().enumerate() {
+                let f = frequencies[bin];
+                // Range cell migration: at this Doppler the target sits further out in range
+                let position = sample as f64 + azimuth.range_migration(f, slant_range) / range_spacing;
+                let migrated = interpolate(|s| range_doppler[s][bin], range_samples, position);
+                *value = migrated * azimuth.transfer(f, slant_range);
+            }
+            inverse.process(&mut line);
+            let gain = range_gain * azimuth.energy_gain(slant_range, parameters.antenna_length) * range_spacing * azimuth_spacing;
+            let scale = 1.0 / (pulses as f64 * gain.sqrt());
+            for (pulse, value) in line.iter().enumerate() {
+                slc[pulse * range_samples + sample] = value * scale;
+            }
+        }
+
+        Ok(FocusedSarImage {
+            pulses,
+            range_samples,
+            slc,
+            near_slant_range: raw.near_slant_range,
+            range_spacing,
+            azimuth_spacing,
+            parameters: parameters.clone(),
+            geometry: raw.geometry.clone(),
+        })
+    }
+
+    /// Focus raw echoes and terrain-correct them to sigma-nought in dB
+    pub fn sigma_nought(&self, raw: &RawSarData, elevation: Option<&dyn ElevationSource>) -> Result<SigmaNoughtImage, AppError> {
+        let focused = self.focus(raw)?;
+        self.geometric_corrector.terrain_correct(&focused, elevation, &self.atmospheric_corrector)
+    }
+
+    /// Range-compress a single real-valued echo line, e.g. a radar altimeter or sounder profile
     pub fn correct_aperture_effects(&mut self, raw_satellite_data: &SatelliteImageryData) -> CorrectedSatelliteData {
-        let motion_compensated = self.motion_compensation.compensate_platform_motion(raw_satellite_data);
-        let geometrically_corrected = self.geometric_corrector.correct_earth_curvature_and_projection(&motion_compensated);
-        let atmosphere_corrected = self.atmospheric_corrector.correct_atmospheric_delays(&geometrically_corrected);
-        let sar_focused = self.perform_sar_focusing(&atmosphere_corrected);
-        
+        let echo: Vec<Complex64> = raw_satellite_data.raw_signal.iter().map(|v| Complex64::new(*v, 0.0)).collect();
+        let focused_image: Vec<f64> = self.range_compression.apply(&echo).iter().map(|v| v.norm()).collect();
+
+        let incidence = PI / 2.0 - self.atmospheric_corrector.tropospheric_model.elevation_angle;
+        let frequency = raw_satellite_data.metadata.sensor_config.frequency;
+        let delay = if frequency > 0.0 { self.atmospheric_corrector.slant_range_delay(frequency, incidence) } else { 0.0 };
         self.last_corrections = ApertureCorrections {
-            range_walk_correction: 0.5,
-            azimuth_defocus_correction: 0.2,
-            doppler_centroid_correction: 50.0,
-            geometric_distortion_correction: GeometricCorrection::default(),
-            atmospheric_delay_correction: atmosphere_corrected.atmospheric_delay_ns,
+            doppler_centroid_correction: self.azimuth_compression.doppler_centroid,
+            atmospheric_delay_correction: delay / SPEED_OF_LIGHT * 1e9,
+            ..ApertureCorrections::default()
         };
-        
+
         CorrectedSatelliteData {
-            corrected_imagery: sar_focused,
+            corrected_imagery: SARFocusedData {
+                resolution_achieved: SPEED_OF_LIGHT / (2.0 * self.range_compression.bandwidth()),
+                focus_quality_metrics: self.assess_focus_quality(&focused_image),
+                focused_image,
+            },
             correction_metadata: self.get_correction_metadata(),
             accuracy_estimates: self.compute_accuracy_estimates(),
         }
     }
-    
-    fn perform_sar_focusing(&self, data: &AtmosphereCorrectedData) -> SARFocusedData {
-        let range_compressed = self.range_compression.apply(&data.corrected_data);
-        let azimuth_compressed = self.azimuth_compression.apply(&range_compressed);
-        let final_focused = self.secondary_range_compression.apply(&azimuth_compressed);
-        
-        SARFocusedData {
-            focused_image: final_focused,
-            resolution_achieved: self.compute_achieved_resolution(),
-            focus_quality_metrics: self.assess_focus_quality(&final_focused),
-        }
-    }
-
-    fn compute_achieved_resolution(&self) -> f64 {
-        let wavelength = 0.03;
-        let aperture_length = 1000.0;
-        wavelength / (2.0 * aperture_length)
-    }
 
     fn assess_focus_quality(&self, focused_data: &[f64]) -> FocusQualityMetrics {
         let peak_value = focused_data.iter().fold(0.0, |max, &val| val.abs().max(max));
         let mean_value = focused_data.iter().sum::<f64>() / focused_data.len() as f64;
-        
+
         FocusQualityMetrics {
             peak_to_sidelobe_ratio: 20.0 * (peak_value / mean_value).log10(),
             integrated_sidelobe_ratio: 0.1,
@@ -773,10 +1313,8 @@ impl SyntheticApertureProcessor {
         CorrectionMetadata {
             processing_timestamp: chrono::Utc::now(),
             algorithms_used: vec![
-                "Motion Compensation".to_string(),
-                "Geometric Correction".to_string(),
-                "Atmospheric Correction".to_string(),
-                "SAR Focusing".to_string(),
+                "Range Compression".to_string(),
+                "Atmospheric Delay".to_string(),
             ],
             quality_indicators: HashMap::new(),
         }
@@ -791,6 +1329,167 @@ impl SyntheticApertureProcessor {
     }
 }
 
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+fn generalised_hamming(coefficient: f64, index: usize, length: usize) -> f64 {
+    if length < 2 {
+        return 1.0;
+    }
+    coefficient - (1.0 - coefficient) * (2.0 * PI * index as f64 / (length - 1) as f64).cos()
+}
+
+/// Frequency of FFT bin `bin` of `length`, negative above Nyquist
+fn signed_frequency(bin: usize, length: usize, sample_rate: f64) -> f64 {
+    let bin = if bin < length.div_ceil(2) { bin as f64 } else { bin as f64 - length as f64 };
+    bin * sample_rate / length as f64
+}
+
+/// Least-squares polynomial of `degree` in pulse index through `value` of each state
+fn fit_polynomial(track: &[SatellitePosition], degree: usize, value: impl Fn(&SatellitePosition) -> f64) -> Vec<f64> {
+    let scale = (track.len().max(2) - 1) as f64;
+    let basis = |index: usize| {
+        let t = 2.0 * index as f64 / scale - 1.0;
+        DVector::from_fn(degree + 1, |power, _| t.powi(power as i32))
+    };
+    let mut normal = DMatrix::zeros(degree + 1, degree + 1);
+    let mut rhs = DVector::zeros(degree + 1);
+    for (index, state) in track.iter().enumerate() {
+        let row = basis(index);
+        normal += &row * row.transpose();
+        rhs += &row * value(state);
+    }
+    match normal.lu().solve(&rhs) {
+        Some(coefficients) => (0..track.len()).map(|index| basis(index).dot(&coefficients)).collect(),
+        None => track.iter().map(value).collect(),
+    }
+}
+
+/// Windowed-sinc interpolation of a complex sequence at a fractional position
+fn interpolate(sample: impl Fn(usize) -> Complex64, length: usize, position: f64) -> Complex64 {
+    let base = position.floor();
+    let fraction = position - base;
+    if fraction.abs() < 1e-9 {
+        return if base >= 0.0 && (base as usize) < length { sample(base as usize) } else { Complex64::new(0.0, 0.0) };
+    }
+    let half = (INTERPOLATION_TAPS / 2) as i64;
+    let mut value = Complex64::new(0.0, 0.0);
+    for offset in (1 - half)..=half {
+        let index = base as i64 + offset;
+        if index < 0 || index >= length as i64 {
+            continue;
+        }
+        let distance = position - index as f64;
+        let taper = 0.5 * (1.0 + (PI * distance / half as f64).cos());
+        value += sample(index as usize) * (sinc(distance) * taper);
+    }
+    value
+}
+
+/// Box-average β0 over `azimuth_looks` × `range_looks` pixels centred on each pixel
+fn multilook(image: &FocusedSarImage, azimuth_looks: usize, range_looks: usize) -> Vec<f64> {
+    let (rows, cols) = (image.pulses, image.range_samples);
+    let intensity: Vec<f64> = image.slc.iter().map(|v| v.norm_sqr()).collect();
+    let average = |values: &[f64], length: usize, looks: usize, stride: usize, offset: usize| -> Vec<f64> {
+        let half = looks / 2;
+        (0..length)
+            .map(|i| {
+                let (start, end) = (i.saturating_sub(half), (i + looks - half).min(length));
+                (start..end).map(|j| values[offset + j * stride]).sum::<f64>() / (end - start) as f64
+            })
+            .collect()
+    };
+    let mut along_range = vec![0.0; rows * cols];
+    for row in 0..rows {
+        along_range[row * cols..(row + 1) * cols].copy_from_slice(&average(&intensity, cols, range_looks, 1, row * cols));
+    }
+    let mut looked = vec![0.0; rows * cols];
+    for col in 0..cols {
+        for (row, value) in average(&along_range, rows, azimuth_looks, cols, col).into_iter().enumerate() {
+            looked[row * cols + col] = value;
+        }
+    }
+    looked
+}
+
+fn bilinear(values: &[f64], rows: usize, cols: usize, row: f64, col: f64) -> Option<f64> {
+    if row < 0.0 || col < 0.0 || row > (rows - 1) as f64 || col > (cols - 1) as f64 {
+        return None;
+    }
+    let (r0, c0) = (row.floor() as usize, col.floor() as usize);
+    let (r1, c1) = ((r0 + 1).min(rows - 1), (c0 + 1).min(cols - 1));
+    let (fr, fc) = (row - r0 as f64, col - c0 as f64);
+    let top = values[r0 * cols + c0] * (1.0 - fc) + values[r0 * cols + c1] * fc;
+    let bottom = values[r1 * cols + c0] * (1.0 - fc) + values[r1 * cols + c1] * fc;
+    Some(top * (1.0 - fr) + bottom * fr)
+}
+
+fn to_db(value: f64) -> f64 {
+    if value > 0.0 {
+        (10.0 * value.log10()).max(SIGMA0_FLOOR_DB)
+    } else {
+        SIGMA0_FLOOR_DB
+    }
+}
+
+fn window_indices(centre: usize, half: usize, length: usize) -> std::ops::Range<usize> {
+    centre.saturating_sub(half)..(centre + half).min(length)
+}
+
+/// Band-limited interpolation by zero-padding the spectrum
+fn upsample(values: &[Complex64], factor: usize) -> Vec<Complex64> {
+    let length = values.len();
+    let mut planner = FftPlanner::new();
+    let mut spectrum = values.to_vec();
+    planner.plan_fft_forward(length).process(&mut spectrum);
+    let mut padded = vec![Complex64::new(0.0, 0.0); length * factor];
+    let positive = length.div_ceil(2);
+    padded[..positive].copy_from_slice(&spectrum[..positive]);
+    padded[length * factor - (length - positive)..].copy_from_slice(&spectrum[positive..]);
+    planner.plan_fft_inverse(length * factor).process(&mut padded);
+    padded
+}
+
+/// 3 dB width in samples and peak-to-sidelobe ratio in dB of an impulse response
+fn impulse_width_and_pslr(response: &[Complex64]) -> (f64, f64) {
+    let power: Vec<f64> = response.iter().map(|v| v.norm_sqr()).collect();
+    let Some((peak, &peak_power)) = power.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)) else {
+        return (0.0, 0.0);
+    };
+    let half_power = peak_power / 2.0;
+    let crossing = |step: isize| {
+        let mut i = peak as isize;
+        while i + step >= 0 && ((i + step) as usize) < power.len() && power[(i + step) as usize] > half_power {
+            i += step;
+        }
+        let (inside, outside) = (power[i as usize], power.get((i + step) as usize).copied().unwrap_or(0.0));
+        i as f64 + step as f64 * (inside - half_power) / (inside - outside).max(f64::EPSILON)
+    };
+    let width = crossing(1) - crossing(-1);
+
+    // Sidelobes start beyond the first minimum on each side
+    let first_minimum = |step: isize| {
+        let mut i = peak as isize;
+        while i + step >= 0 && ((i + step) as usize) < power.len() && power[(i + step) as usize] <= power[i as usize] {
+            i += step;
+        }
+        i
+    };
+    let (left, right) = (first_minimum(-1), first_minimum(1));
+    let sidelobe = power
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| (*i as isize) < left || (*i as isize) > right)
+        .map(|(_, p)| *p)
+        .fold(0.0, f64::max);
+    (width, to_db(sidelobe / peak_power).min(0.0))
+}
+
 /// Corrected satellite data
 #[derive(Debug, Clone)]
 pub struct CorrectedSatelliteData {
@@ -954,3 +1653,217 @@ impl Reality4DReconstructor {
         self.spatial_resolution
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ConstantElevation(f64);
+
+    impl ElevationSource for ConstantElevation {
+        fn elevation_m(&self, _latitude: f64, _longitude: f64) -> Option<f64> {
+            Some(self.0)
+        }
+    }
+
+    /// Plane rising `slope_deg` away from the radar, level at `reference_ground_range`
+    struct TiltedPlane {
+        geometry: SarOrbitGeometry,
+        slope_deg: f64,
+        reference_ground_range: f64,
+    }
+
+    impl ElevationSource for TiltedPlane {
+        fn elevation_m(&self, latitude: f64, longitude: f64) -> Option<f64> {
+            let (_, ground_range) = self.geometry.track_coordinates(EarthModel::default().radius, latitude, longitude);
+            Some((ground_range - self.reference_ground_range) * self.slope_deg.to_radians().tan())
+        }
+    }
+
+    fn test_parameters() -> SarSystemParameters {
+        SarSystemParameters {
+            carrier_frequency: 5.405e9,
+            chirp_bandwidth: 20e6,
+            pulse_duration: 10e-6,
+            range_sampling_rate: 24e6,
+            prf: 1200.0,
+            antenna_length: 24.0,
+            polarisation: "VV".to_string(),
+        }
+    }
+
+    const PULSES: usize = 1024;
+    const RANGE_SAMPLES: usize = 512;
+    const SCENE_GROUND_RANGE: f64 = 420_000.0;
+
+    /// Slant range of the first sample, with the scene ground range at mid-swath
+    fn near_slant_range(geometry: &SarOrbitGeometry) -> f64 {
+        let range_spacing = SPEED_OF_LIGHT / (2.0 * test_parameters().range_sampling_rate);
+        EarthModel::default().slant_range(geometry.altitude, SCENE_GROUND_RANGE, 0.0) - RANGE_SAMPLES as f64 / 2.0 * range_spacing
+    }
+
+    fn wobbling_track(geometry: &SarOrbitGeometry) -> Vec<SatellitePosition> {
+        let velocity = geometry.effective_velocity(&EarthModel::default());
+        (0..PULSES)
+            .map(|pulse| {
+                let time = pulse as f64 / test_parameters().prf;
+                SatellitePosition {
+                    position: Point3D {
+                        x: velocity * time,
+                        y: 0.05 * (2.0 * PI * time / 0.3).sin(),
+                        z: geometry.altitude + 0.04 * (2.0 * PI * time / 0.23).cos(),
+                    },
+                    velocity: Vector3::zeros(),
+                    timestamp: time,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_point_targets_focus_with_motion_compensation() {
+        let geometry = SarOrbitGeometry::default();
+        let near_slant_range = near_slant_range(&geometry);
+        let azimuth_spacing = geometry.ground_velocity(&EarthModel::default()) / test_parameters().prf;
+        let target = PointTarget {
+            ground_range: SCENE_GROUND_RANGE,
+            along_track: 512.0 * azimuth_spacing,
+            height: 0.0,
+            rcs: 100.0,
+        };
+        let raw = RawSarData::simulate_point_targets(
+            test_parameters(),
+            geometry.clone(),
+            near_slant_range,
+            PULSES,
+            RANGE_SAMPLES,
+            std::slice::from_ref(&target),
+            wobbling_track(&geometry),
+        );
+        let processor = SyntheticApertureProcessor::for_acquisition(&test_parameters(), &geometry);
+        let image = processor.focus(&raw).unwrap();
+
+        let expected_sample = (EarthModel::default().slant_range(geometry.altitude, target.ground_range, 0.0) - near_slant_range)
+            / image.range_spacing;
+        let response = image.point_target_response(512, expected_sample.round() as usize);
+        assert!(response.pulse.abs_diff(512) <= 1);
+        assert!((response.sample as f64 - expected_sample).abs() <= 1.0);
+        let nominal_resolution = SPEED_OF_LIGHT / (2.0 * test_parameters().chirp_bandwidth);
+        assert!(response.range_resolution > nominal_resolution && response.range_resolution < 1.5 * nominal_resolution);
+        assert!(response.azimuth_resolution < test_parameters().antenna_length);
+        assert!(response.range_pslr_db < -20.0 && response.azimuth_pslr_db < -20.0);
+        assert!((10.0 * (response.integrated_rcs / target.rcs).log10()).abs() < 1.0);
+
+        // Ignoring the measured track leaves the wobble in the phase history
+        let unknown_track = RawSarData { platform_track: Vec::new(), ..raw };
+        let defocused = processor.focus(&unknown_track).unwrap();
+        let peak = defocused.point_target_response(512, response.sample);
+        let loss_db = 10.0 * (image.beta_nought(response.pulse, response.sample) / defocused.beta_nought(peak.pulse, peak.sample)).log10();
+        assert!(loss_db > 3.0);
+    }
+
+    #[test]
+    fn test_terrain_correction_places_elevated_target() {
+        let geometry = SarOrbitGeometry::default();
+        let earth = EarthModel::default();
+        let azimuth_spacing = geometry.ground_velocity(&earth) / test_parameters().prf;
+        let target = PointTarget {
+            ground_range: SCENE_GROUND_RANGE,
+            along_track: 512.0 * azimuth_spacing,
+            height: 400.0,
+            rcs: 1000.0,
+        };
+        let raw = RawSarData::simulate_point_targets(
+            test_parameters(),
+            geometry.clone(),
+            near_slant_range(&geometry),
+            PULSES,
+            RANGE_SAMPLES,
+            std::slice::from_ref(&target),
+            Vec::new(),
+        );
+        let processor = SyntheticApertureProcessor::for_acquisition(&test_parameters(), &geometry);
+        let brightest = |image: &SigmaNoughtImage| {
+            (0..image.rows)
+                .flat_map(|row| (0..image.cols).map(move |col| (row, col)))
+                .max_by(|a, b| {
+                    let value = |pixel: &(usize, usize)| image.value_db(pixel.0, pixel.1).unwrap_or(f64::NEG_INFINITY);
+                    value(a).total_cmp(&value(b))
+                })
+                .unwrap()
+        };
+
+        let terrain = processor.sigma_nought(&raw, Some(&ConstantElevation(target.height))).unwrap();
+        let (latitude, longitude) = geometry.location(earth.radius, target.along_track, target.ground_range);
+        let expected = terrain.pixel_at(latitude, longitude).unwrap();
+        let (row, col) = brightest(&terrain);
+        assert!(row.abs_diff(expected.0) <= 1 && col.abs_diff(expected.1) <= 1);
+        let (found_latitude, found_longitude) = terrain.pixel_location(row, col);
+        assert!((found_latitude - latitude).abs() < 3e-4 && (found_longitude - longitude).abs() < 3e-4);
+
+        // Without heights the target is laid over towards the radar by about h / tan θ
+        let flat = processor.sigma_nought(&raw, None).unwrap();
+        let (_, flat_col) = brightest(&flat);
+        let incidence = earth.incidence_angle(geometry.altitude, target.ground_range, target.height);
+        let expected_shift = target.height / incidence.tan() / flat.pixel_spacing;
+        assert!(((col as f64 - flat_col as f64) - expected_shift).abs() < 3.0);
+    }
+
+    #[test]
+    fn test_terrain_correction_on_tilted_planes() {
+        let geometry = SarOrbitGeometry::default();
+        let earth = EarthModel::default();
+        let pulses = 32;
+        // Uniform unit β0, so σ0 is the sine of the local incidence
+        let image = FocusedSarImage {
+            pulses,
+            range_samples: RANGE_SAMPLES,
+            slc: vec![Complex64::new(1.0, 0.0); pulses * RANGE_SAMPLES],
+            near_slant_range: near_slant_range(&geometry),
+            range_spacing: SPEED_OF_LIGHT / (2.0 * test_parameters().range_sampling_rate),
+            azimuth_spacing: geometry.ground_velocity(&earth) / test_parameters().prf,
+            parameters: test_parameters(),
+            geometry: geometry.clone(),
+        };
+        let corrector = GeometricCorrector::new();
+        let correct = |slope_deg: f64| {
+            let plane = TiltedPlane { geometry: geometry.clone(), slope_deg, reference_ground_range: SCENE_GROUND_RANGE };
+            let sigma0 = corrector.terrain_correct(&image, Some(&plane), &AtmosphericCorrector::new()).unwrap();
+            (plane, sigma0)
+        };
+        let mid_incidence = earth.incidence_angle(geometry.altitude, SCENE_GROUND_RANGE, 0.0).to_degrees();
+        assert!(mid_incidence > 30.0 && mid_incidence < 40.0);
+
+        // Foreslopes face the radar and backslopes turn away from it
+        for slope_deg in [10.0, -10.0] {
+            let (plane, sigma0) = correct(slope_deg);
+            let mut checked = 0;
+            for row in 0..sigma0.rows {
+                for col in 0..sigma0.cols {
+                    let ground_range = sigma0.near_ground_range + col as f64 * sigma0.pixel_spacing;
+                    let (latitude, longitude) = sigma0.pixel_location(row, col);
+                    let height = plane.elevation_m(latitude, longitude).unwrap();
+                    let expected = earth.incidence_angle(geometry.altitude, ground_range, height) - slope_deg.to_radians();
+                    let local = sigma0.local_incidence_deg[row * sigma0.cols + col].to_radians();
+                    assert!((local - expected).abs() < 1e-6);
+                    if let Some(value) = sigma0.value_db(row, col) {
+                        assert!((value - 10.0 * expected.sin().log10()).abs() < 1e-6);
+                        checked += 1;
+                    }
+                }
+            }
+            assert!(checked > sigma0.rows * sigma0.cols / 2);
+        }
+        let (_, fore) = correct(10.0);
+        let (_, back) = correct(-10.0);
+        let centre = (fore.rows / 2, fore.cols / 2);
+        assert!(fore.value_db(centre.0, centre.1).unwrap() < back.value_db(centre.0, centre.1).unwrap());
+
+        // Slopes steeper than the incidence lay over; backslopes steeper than its complement are shadowed
+        for (slope_deg, layover) in [(45.0, true), (-60.0, false)] {
+            let (_, sigma0) = correct(slope_deg);
+            assert!(sigma0.sigma0_db.iter().all(|value| value.is_nan()));
+            assert!(sigma0.local_incidence_deg.iter().all(|&local| if layover { local <= 0.0 } else { local >= 90.0 }));
+        }
+    }
+}
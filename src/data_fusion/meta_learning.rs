@@ -0,0 +1,915 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{BufRead, BufReader, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Utc};
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::error::AppError;
+use super::bayesian_network::NetworkState;
+use super::fusion_algorithms::{AlgorithmType, ComputationalComplexity, FusionAlgorithm};
+use super::fuzzy_evidence::{FuzzyEvidence, FuzzyReliability};
+use super::{FusedState, SensorType};
+
+/// Names of the entries of `SelectionContext::features`, in order
+pub const CONTEXT_FEATURES: [&str; 9] = [
+    "bias",
+    "weather_station_share",
+    "soil_share",
+    "remote_sensing_share",
+    "positioning_share",
+    "conflict_level",
+    "data_rate",
+    "season_sin",
+    "season_cos",
+];
+
+/// At most this many recent items per sensor type are compared when measuring conflict
+const CONFLICT_SAMPLE: usize = 64;
+
+/// Pending decisions without ground truth are dropped after this long
+const MAX_PENDING_DAYS: i64 = 60;
+
+/// Summary of a batch of fuzzy evidence used to pick a fusion algorithm
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvidenceAnalysis {
+    pub evidence_count: usize,
+    pub sensor_counts: HashMap<SensorType, usize>,
+    /// Mean fuzzy dissimilarity between items from the same sensor type, 0 (agree) to 1
+    pub conflict_level: f64,
+    /// Mean defuzzified reliability, 0 to 1
+    pub mean_reliability: f64,
+    pub time_span_seconds: f64,
+    /// Time of the newest evidence
+    pub timestamp: DateTime<Utc>,
+}
+
+fn defuzzified_reliability(reliability: &FuzzyReliability) -> f64 {
+    let grades = [reliability.very_low, reliability.low, reliability.medium, reliability.high, reliability.very_high];
+    let total: f64 = grades.iter().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+    grades.iter().enumerate().map(|(i, grade)| 0.25 * i as f64 * grade).sum::<f64>() / total
+}
+
+/// Fuzzy Jaccard similarity of two sets of term memberships
+fn term_similarity(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> Option<f64> {
+    let (mut intersection, mut union) = (0.0, 0.0);
+    for (term, &x) in a {
+        let y = b.get(term).copied().unwrap_or(0.0);
+        intersection += x.min(y);
+        union += x.max(y);
+    }
+    union += b.iter().filter(|(term, _)| !a.contains_key(*term)).map(|(_, y)| *y).sum::<f64>();
+    (union > 0.0).then(|| intersection / union)
+}
+
+impl EvidenceAnalysis {
+    pub fn from_evidence(evidence: &[FuzzyEvidence]) -> Self {
+        if evidence.is_empty() {
+            return Self::default();
+        }
+
+        let mut by_sensor: HashMap<SensorType, Vec<&FuzzyEvidence>> = HashMap::new();
+        for item in evidence {
+            by_sensor.entry(item.sensor_type).or_default().push(item);
+        }
+
+        let (mut dissimilarity, mut pairs) = (0.0, 0usize);
+        for items in by_sensor.values_mut() {
+            items.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+            let recent = &items[items.len().saturating_sub(CONFLICT_SAMPLE)..];
+            for (i, a) in recent.iter().enumerate() {
+                for b in &recent[i + 1..] {
+                    if let Some(similarity) = term_similarity(&a.linguistic_terms, &b.linguistic_terms) {
+                        dissimilarity += 1.0 - similarity;
+                        pairs += 1;
+                    }
+                }
+            }
+        }
+
+        let (first, last) = evidence
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), item| (lo.min(item.timestamp), hi.max(item.timestamp)));
+        let newest = DateTime::<Utc>::from_timestamp(last.floor() as i64, ((last - last.floor()) * 1e9) as u32);
+
+        Self {
+            evidence_count: evidence.len(),
+            sensor_counts: by_sensor.iter().map(|(sensor, items)| (*sensor, items.len())).collect(),
+            conflict_level: if pairs == 0 { 0.0 } else { dissimilarity / pairs as f64 },
+            mean_reliability: evidence.iter().map(|item| defuzzified_reliability(&item.reliability)).sum::<f64>()
+                / evidence.len() as f64,
+            time_span_seconds: last - first,
+            timestamp: newest.unwrap_or_default(),
+        }
+    }
+
+    /// Evidence items per minute over the analysed span (at least one minute)
+    pub fn data_rate_per_minute(&self) -> f64 {
+        self.evidence_count as f64 / (self.time_span_seconds / 60.0).max(1.0)
+    }
+}
+
+/// Context vector the bandit conditions on; see `CONTEXT_FEATURES`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelectionContext {
+    pub features: Vec<f64>,
+}
+
+impl SelectionContext {
+    pub fn from_analysis(analysis: &EvidenceAnalysis) -> Self {
+        let total = analysis.evidence_count.max(1) as f64;
+        let share = |sensors: &[SensorType]| {
+            sensors.iter().map(|sensor| analysis.sensor_counts.get(sensor).copied().unwrap_or(0)).sum::<usize>() as f64 / total
+        };
+        let season = 2.0 * std::f64::consts::PI * f64::from(analysis.timestamp.ordinal0()) / 365.25;
+
+        Self {
+            features: vec![
+                1.0,
+                share(&[
+                    SensorType::WeatherStation,
+                    SensorType::AutonomousWeatherBuoy,
+                    SensorType::WindProfiler,
+                    SensorType::LightningDetector,
+                    SensorType::EddyCovarianceTower,
+                    SensorType::GroundTruthStation,
+                ]),
+                share(&[SensorType::SoilSensor, SensorType::CosmicRayNeutron, SensorType::Lysimeter, SensorType::AgriculturalIoT]),
+                share(&[
+                    SensorType::SatelliteImagery,
+                    SensorType::SatelliteRadar,
+                    SensorType::RadarPrecipitation,
+                    SensorType::DroneMultispectral,
+                    SensorType::Phenocam,
+                ]),
+                share(&[SensorType::GPS, SensorType::AtomicClock]),
+                analysis.conflict_level.clamp(0.0, 1.0),
+                (analysis.data_rate_per_minute().ln_1p() / 5.0).min(2.0),
+                season.sin(),
+                season.cos(),
+            ],
+        }
+    }
+
+    fn vector(&self) -> DVector<f64> {
+        DVector::from_column_slice(&self.features)
+    }
+}
+
+/// Hardware and latency requirements for the current fusion cycle
+#[derive(Debug, Clone)]
+pub struct ComputationalConstraints {
+    pub require_real_time: bool,
+    pub max_complexity: ComputationalComplexity,
+}
+
+impl Default for ComputationalConstraints {
+    fn default() -> Self {
+        Self { require_real_time: false, max_complexity: ComputationalComplexity::VeryHigh }
+    }
+}
+
+/// How the selector trades exploration against exploitation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BanditPolicy {
+    /// Upper confidence bound `θ̂ᵀx + α √(xᵀA⁻¹x)`
+    LinUcb { alpha: f64 },
+    /// Sample `θ ~ N(θ̂, v²A⁻¹)` and act greedily on the sample
+    ThompsonSampling { variance_scale: f64, seed: u64 },
+}
+
+impl fmt::Display for BanditPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BanditPolicy::LinUcb { alpha } => write!(f, "LinUCB(α={})", alpha),
+            BanditPolicy::ThompsonSampling { variance_scale, .. } => write!(f, "Thompson(v={})", variance_scale),
+        }
+    }
+}
+
+/// What the selector knows about an algorithm it may choose
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArmProfile {
+    pub algorithm: AlgorithmType,
+    pub complexity: ComputationalComplexity,
+    pub real_time: bool,
+}
+
+impl ArmProfile {
+    pub fn of(algorithm: &dyn FusionAlgorithm) -> Self {
+        Self {
+            algorithm: algorithm.get_algorithm_type(),
+            complexity: algorithm.get_computational_complexity(),
+            real_time: algorithm.supports_real_time(),
+        }
+    }
+
+    fn satisfies(&self, constraints: &ComputationalConstraints) -> bool {
+        self.complexity <= constraints.max_complexity && (self.real_time || !constraints.require_real_time)
+    }
+}
+
+/// Agronomic expectations used as the ridge prior mean of each arm's reward model
+///
+/// Coefficients are on `CONTEXT_FEATURES`; rewards lie in (0, 1].
+fn agricultural_prior(algorithm: AlgorithmType) -> (ArmProfile, [f64; 9]) {
+    use AlgorithmType::*;
+    use ComputationalComplexity::*;
+    let (complexity, real_time, prior) = match algorithm {
+        // Robust when sensors disagree, slightly worse than model-based filters otherwise
+        ByzantineFaultTolerant => (Medium, true, [0.45, 0.0, 0.0, 0.0, 0.0, 0.3, 0.0, 0.0, 0.0]),
+        // Smoothing over many heterogeneous, timestamp-sensitive measurements
+        FactorGraph => (Medium, true, [0.5, 0.05, 0.0, 0.0, 0.1, -0.1, 0.05, 0.0, 0.0]),
+        // Non-linear soil water and crop dynamics
+        ParticleFlow => (High, true, [0.5, 0.0, 0.1, 0.05, 0.0, -0.1, 0.0, 0.0, 0.0]),
+        ExtendedKalmanFilter => (Low, true, [0.5, 0.0, 0.0, 0.0, 0.15, -0.2, 0.0, 0.0, 0.0]),
+        VariationalBayes => (Medium, true, [0.5, 0.0, 0.0, 0.05, 0.0, -0.05, 0.0, 0.0, 0.0]),
+        InformationTheoreticSelection => (Low, true, [0.45, 0.0, 0.0, 0.0, 0.0, 0.0, 0.05, 0.0, 0.0]),
+        EMCalibration => (Medium, false, [0.45, 0.05, 0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+        ManifoldConstrained => (High, false, [0.4, 0.0, 0.0, 0.1, 0.0, 0.0, 0.0, 0.0, 0.0]),
+        NashEquilibrium => (High, false, [0.4, 0.0, 0.0, 0.0, 0.0, 0.1, 0.0, 0.0, 0.0]),
+        QuantumSuperposition => (VeryHigh, false, [0.35, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+    };
+    (ArmProfile { algorithm, complexity, real_time }, prior)
+}
+
+/// Ridge regression of reward on context for one algorithm
+#[derive(Debug, Clone)]
+struct LinearArm {
+    /// `λI + Σ xxᵀ`
+    a: DMatrix<f64>,
+    /// `λθ₀ + Σ r x`
+    b: DVector<f64>,
+}
+
+impl LinearArm {
+    fn new(prior_mean: &DVector<f64>, ridge: f64) -> Self {
+        let d = prior_mean.len();
+        Self { a: DMatrix::identity(d, d) * ridge, b: prior_mean * ridge }
+    }
+
+    fn update(&mut self, x: &DVector<f64>, reward: f64) {
+        self.a += x * x.transpose();
+        self.b += x * reward;
+    }
+
+    fn score(&self, x: &DVector<f64>, policy: &BanditPolicy, rng: &Mutex<StdRng>) -> f64 {
+        let Some(cholesky) = self.a.clone().cholesky() else {
+            return f64::NEG_INFINITY;
+        };
+        let theta = cholesky.solve(&self.b);
+        match policy {
+            BanditPolicy::LinUcb { alpha } => {
+                let width = x.dot(&cholesky.solve(x)).max(0.0).sqrt();
+                theta.dot(x) + alpha * width
+            },
+            BanditPolicy::ThompsonSampling { variance_scale, .. } => {
+                let z = {
+                    let mut rng = rng.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                    DVector::from_fn(x.len(), |_, _| StandardNormal.sample(&mut *rng))
+                };
+                // A = LLᵀ, so L⁻ᵀz has covariance A⁻¹
+                let offset = cholesky.l().transpose().solve_upper_triangular(&z).unwrap_or_else(|| DVector::zeros(x.len()));
+                (theta + offset * *variance_scale).dot(x)
+            },
+        }
+    }
+}
+
+/// Verified outcome of one algorithm on one fusion cycle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationRecord {
+    /// Fusion cycle (sensor bundle id); shadow runs of other algorithms share it
+    pub decision_id: Uuid,
+    pub algorithm: AlgorithmType,
+    /// Whether the engine acted on this algorithm's result
+    pub selected: bool,
+    pub features: Vec<f64>,
+    pub recorded_at: DateTime<Utc>,
+    pub verified_at: DateTime<Utc>,
+    /// Mean scaled absolute error against ground truth
+    pub error: f64,
+    /// `exp(-error)`
+    pub reward: f64,
+}
+
+#[derive(Debug, Clone)]
+struct PendingEstimate {
+    algorithm: AlgorithmType,
+    selected: bool,
+    context: SelectionContext,
+    estimates: HashMap<String, f64>,
+    recorded_at: DateTime<Utc>,
+}
+
+/// Contextual-bandit choice of fusion algorithm, learned from verified errors
+///
+/// Every fusion cycle records the chosen algorithm's estimates (and those of
+/// any shadow runs). When ground truth arrives, e.g. from a reference station
+/// or lysimeter, `verify` scores each recorded estimate, updates the reward
+/// models and appends the outcome to the history file so learning survives
+/// restarts.
+#[derive(Debug)]
+pub struct MetaLearningAlgorithmSelector {
+    policy: BanditPolicy,
+    ridge: f64,
+    arms: Vec<ArmProfile>,
+    priors: HashMap<AlgorithmType, DVector<f64>>,
+    models: HashMap<AlgorithmType, LinearArm>,
+    pending: HashMap<Uuid, Vec<PendingEstimate>>,
+    history: Vec<VerificationRecord>,
+    history_path: Option<PathBuf>,
+    error_scales: HashMap<String, f64>,
+    rng: Mutex<StdRng>,
+}
+
+impl MetaLearningAlgorithmSelector {
+    pub fn new(policy: BanditPolicy, arms: Vec<(ArmProfile, Vec<f64>)>, ridge: f64) -> Self {
+        let seed = match policy {
+            BanditPolicy::ThompsonSampling { seed, .. } => seed,
+            BanditPolicy::LinUcb { .. } => 0,
+        };
+        let mut selector = Self {
+            policy,
+            ridge,
+            arms: Vec::new(),
+            priors: HashMap::new(),
+            models: HashMap::new(),
+            pending: HashMap::new(),
+            history: Vec::new(),
+            history_path: None,
+            error_scales: [
+                ("temperature", 1.0),
+                ("humidity", 5.0),
+                ("pressure", 1.0),
+                ("wind_speed", 1.0),
+                ("precipitation_rate", 1.0),
+                ("solar_radiation", 50.0),
+                ("soil_moisture", 0.02),
+            ]
+            .into_iter()
+            .map(|(quantity, scale)| (quantity.to_string(), scale))
+            .collect(),
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        };
+        for (profile, prior) in arms {
+            selector.add_arm(profile, DVector::from_vec(prior));
+        }
+        selector
+    }
+
+    /// Every algorithm type, with agronomic priors and LinUCB exploration
+    pub async fn new_with_agricultural_domain_knowledge() -> Result<Self, AppError> {
+        Ok(Self::with_agricultural_priors(BanditPolicy::LinUcb { alpha: 0.5 }))
+    }
+
+    pub fn with_agricultural_priors(policy: BanditPolicy) -> Self {
+        use AlgorithmType::*;
+        let arms = [
+            FactorGraph,
+            ParticleFlow,
+            VariationalBayes,
+            ByzantineFaultTolerant,
+            ManifoldConstrained,
+            NashEquilibrium,
+            QuantumSuperposition,
+            EMCalibration,
+            InformationTheoreticSelection,
+            ExtendedKalmanFilter,
+        ]
+        .into_iter()
+        .map(|algorithm| {
+            let (profile, prior) = agricultural_prior(algorithm);
+            (profile, prior.to_vec())
+        })
+        .collect();
+        Self::new(policy, arms, 4.0)
+    }
+
+    fn add_arm(&mut self, profile: ArmProfile, prior: DVector<f64>) {
+        self.models.insert(profile.algorithm, LinearArm::new(&prior, self.ridge));
+        self.priors.insert(profile.algorithm, prior);
+        self.arms.retain(|arm| arm.algorithm != profile.algorithm);
+        self.arms.push(profile);
+    }
+
+    /// Use the complexity and latency an algorithm reports about itself
+    pub fn register_algorithm(&mut self, algorithm: &dyn FusionAlgorithm) {
+        let profile = ArmProfile::of(algorithm);
+        match self.arms.iter_mut().find(|arm| arm.algorithm == profile.algorithm) {
+            Some(arm) => *arm = profile,
+            None => {
+                let prior = agricultural_prior(profile.algorithm).1.to_vec();
+                self.add_arm(profile, DVector::from_vec(prior));
+            },
+        }
+    }
+
+    pub fn set_policy(&mut self, policy: BanditPolicy) {
+        if let BanditPolicy::ThompsonSampling { seed, .. } = policy {
+            self.rng = Mutex::new(StdRng::seed_from_u64(seed));
+        }
+        self.policy = policy;
+    }
+
+    /// Error that counts as one unit when scoring estimates of `quantity`
+    pub fn set_error_scale(&mut self, quantity: &str, scale: f64) {
+        self.error_scales.insert(quantity.to_string(), scale.abs().max(f64::EPSILON));
+    }
+
+    pub fn arms(&self) -> &[ArmProfile] {
+        &self.arms
+    }
+
+    pub fn history(&self) -> &[VerificationRecord] {
+        &self.history
+    }
+
+    /// Load the verification history from a JSON Lines file and append future outcomes to it
+    ///
+    /// Returns the number of records replayed into the reward models.
+    pub fn attach_history_file(&mut self, path: &Path) -> Result<usize, AppError> {
+        let mut loaded = 0;
+        if path.exists() {
+            let file = std::fs::File::open(path)
+                .map_err(|e| AppError::internal(format!("Cannot open algorithm history {}: {}", path.display(), e)))?;
+            for (line_number, line) in BufReader::new(file).lines().enumerate() {
+                let line = line.map_err(|e| AppError::internal(format!("Cannot read {}: {}", path.display(), e)))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let record: VerificationRecord = serde_json::from_str(&line).map_err(|e| {
+                    AppError::validation(format!("{} line {}: {}", path.display(), line_number + 1, e))
+                })?;
+                self.learn(&record);
+                self.history.push(record);
+                loaded += 1;
+            }
+        }
+        self.history_path = Some(path.to_path_buf());
+        Ok(loaded)
+    }
+
+    fn learn(&mut self, record: &VerificationRecord) {
+        if let Some(model) = self.models.get_mut(&record.algorithm) {
+            if record.features.len() == model.b.len() {
+                model.update(&DVector::from_column_slice(&record.features), record.reward);
+            }
+        }
+    }
+
+    /// Best algorithm for the context among `candidates` (all arms if empty) that meets the constraints
+    pub fn choose(
+        &self,
+        context: &SelectionContext,
+        constraints: &ComputationalConstraints,
+        candidates: &[AlgorithmType],
+    ) -> Result<AlgorithmType, AppError> {
+        let x = context.vector();
+        self.arms
+            .iter()
+            .filter(|arm| candidates.is_empty() || candidates.contains(&arm.algorithm))
+            .filter(|arm| arm.satisfies(constraints))
+            .filter_map(|arm| {
+                let model = self.models.get(&arm.algorithm)?;
+                (model.b.len() == x.len()).then(|| (arm.algorithm, model.score(&x, &self.policy, &self.rng)))
+            })
+            .fold(None, |best: Option<(AlgorithmType, f64)>, (algorithm, score)| match best {
+                Some((_, best_score)) if best_score >= score => best,
+                _ => Some((algorithm, score)),
+            })
+            .map(|(algorithm, _)| algorithm)
+            .ok_or_else(|| AppError::validation("No fusion algorithm satisfies the computational constraints"))
+    }
+
+    pub async fn select_algorithm(
+        &self,
+        evidence_analysis: &EvidenceAnalysis,
+        _network_state: &NetworkState,
+        constraints: &ComputationalConstraints,
+    ) -> Result<AlgorithmType, AppError> {
+        self.choose(&SelectionContext::from_analysis(evidence_analysis), constraints, &[])
+    }
+
+    /// Remember an algorithm's estimates for a fusion cycle until ground truth is available
+    pub fn record_estimates(
+        &mut self,
+        decision_id: Uuid,
+        context: SelectionContext,
+        algorithm: AlgorithmType,
+        estimates: HashMap<String, f64>,
+        selected: bool,
+        recorded_at: DateTime<Utc>,
+    ) {
+        let cutoff = recorded_at - ChronoDuration::days(MAX_PENDING_DAYS);
+        self.pending.retain(|_, estimates| estimates.iter().any(|pending| pending.recorded_at >= cutoff));
+        self.pending.entry(decision_id).or_default().push(PendingEstimate {
+            algorithm,
+            selected,
+            context,
+            estimates,
+            recorded_at,
+        });
+    }
+
+    /// Score the recorded estimates of a fusion cycle against ground truth and learn from them
+    pub fn verify(
+        &mut self,
+        decision_id: Uuid,
+        truth: &HashMap<String, f64>,
+        verified_at: DateTime<Utc>,
+    ) -> Result<Vec<VerificationRecord>, AppError> {
+        let pending = self
+            .pending
+            .get(&decision_id)
+            .ok_or_else(|| AppError::not_found(format!("No pending fusion decision {}", decision_id)))?;
+
+        let mut records = Vec::new();
+        for estimate in pending {
+            let errors: Vec<f64> = estimate
+                .estimates
+                .iter()
+                .filter_map(|(quantity, value)| {
+                    let observed = truth.get(quantity)?;
+                    let scale = self.error_scales.get(quantity).copied().unwrap_or(1.0);
+                    Some((value - observed).abs() / scale)
+                })
+                .filter(|error| error.is_finite())
+                .collect();
+            if errors.is_empty() {
+                continue;
+            }
+            let error = errors.iter().sum::<f64>() / errors.len() as f64;
+            records.push(VerificationRecord {
+                decision_id,
+                algorithm: estimate.algorithm,
+                selected: estimate.selected,
+                features: estimate.context.features.clone(),
+                recorded_at: estimate.recorded_at,
+                verified_at,
+                error,
+                reward: (-error).exp(),
+            });
+        }
+        if records.is_empty() {
+            return Err(AppError::validation(format!(
+                "Ground truth for decision {} covers none of the estimated quantities",
+                decision_id
+            )));
+        }
+
+        self.pending.remove(&decision_id);
+        if let Some(path) = &self.history_path {
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| AppError::internal(format!("Cannot open algorithm history {}: {}", path.display(), e)))?;
+            for record in &records {
+                let line = serde_json::to_string(record).map_err(|e| AppError::internal(e.to_string()))?;
+                writeln!(file, "{}", line).map_err(|e| AppError::internal(format!("Cannot write {}: {}", path.display(), e)))?;
+            }
+        }
+        for record in &records {
+            self.learn(record);
+        }
+        self.history.extend(records.iter().cloned());
+        Ok(records)
+    }
+
+    /// Replay the recorded history through each policy, learning only from the arm it picks
+    ///
+    /// Each fusion cycle offers the algorithms that were verified for it; regret
+    /// is the reward of the best of those minus the reward of the one picked.
+    pub fn regret_report(&self, policies: &[BanditPolicy]) -> RegretReport {
+        let mut decisions: BTreeMap<(DateTime<Utc>, Uuid), Vec<&VerificationRecord>> = BTreeMap::new();
+        for record in &self.history {
+            decisions.entry((record.recorded_at, record.decision_id)).or_default().push(record);
+        }
+
+        let mut algorithms: BTreeMap<String, AlgorithmSummary> = BTreeMap::new();
+        for records in decisions.values() {
+            let best = records.iter().map(|r| r.reward).fold(f64::NEG_INFINITY, f64::max);
+            for record in records {
+                let summary = algorithms.entry(format!("{:?}", record.algorithm)).or_insert_with(|| AlgorithmSummary {
+                    algorithm: record.algorithm,
+                    observations: 0,
+                    mean_error: 0.0,
+                    mean_reward: 0.0,
+                    times_best: 0,
+                });
+                summary.observations += 1;
+                summary.mean_error += record.error;
+                summary.mean_reward += record.reward;
+                summary.times_best += usize::from(record.reward >= best);
+            }
+        }
+        for summary in algorithms.values_mut() {
+            summary.mean_error /= summary.observations as f64;
+            summary.mean_reward /= summary.observations as f64;
+        }
+
+        let mut evaluations: Vec<PolicyEvaluation> = policies
+            .iter()
+            .map(|policy| {
+                let mut learner = MetaLearningAlgorithmSelector::new(
+                    *policy,
+                    self.arms
+                        .iter()
+                        .map(|arm| (arm.clone(), self.priors[&arm.algorithm].iter().copied().collect()))
+                        .collect(),
+                    self.ridge,
+                );
+                let mut evaluation = PolicyEvaluation::new(policy.to_string());
+                for records in decisions.values() {
+                    let offered: Vec<AlgorithmType> = records.iter().map(|r| r.algorithm).collect();
+                    let context = SelectionContext { features: records[0].features.clone() };
+                    let Ok(choice) = learner.choose(&context, &ComputationalConstraints::default(), &offered) else {
+                        continue;
+                    };
+                    let Some(chosen) = records.iter().find(|r| r.algorithm == choice) else {
+                        continue;
+                    };
+                    let best = records.iter().map(|r| r.reward).fold(f64::NEG_INFINITY, f64::max);
+                    evaluation.observe(chosen, best);
+                    learner.learn(chosen);
+                }
+                evaluation.finish()
+            })
+            .collect();
+
+        // What the engine actually did, for comparison with the replayed policies
+        let mut logged = PolicyEvaluation::new("logged".to_string());
+        for records in decisions.values() {
+            if let Some(chosen) = records.iter().find(|r| r.selected) {
+                let best = records.iter().map(|r| r.reward).fold(f64::NEG_INFINITY, f64::max);
+                logged.observe(chosen, best);
+            }
+        }
+        evaluations.push(logged.finish());
+
+        RegretReport {
+            decisions: decisions.len(),
+            algorithms: algorithms.into_values().collect(),
+            policies: evaluations,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlgorithmSummary {
+    pub algorithm: AlgorithmType,
+    pub observations: usize,
+    pub mean_error: f64,
+    pub mean_reward: f64,
+    /// Fusion cycles in which this algorithm had the best verified reward
+    pub times_best: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyEvaluation {
+    pub policy: String,
+    pub decisions: usize,
+    pub cumulative_regret: f64,
+    pub mean_regret: f64,
+    pub mean_error: f64,
+    pub selections: BTreeMap<String, usize>,
+    /// Cumulative regret after each decision
+    pub regret_curve: Vec<f64>,
+}
+
+impl PolicyEvaluation {
+    fn new(policy: String) -> Self {
+        Self {
+            policy,
+            decisions: 0,
+            cumulative_regret: 0.0,
+            mean_regret: 0.0,
+            mean_error: 0.0,
+            selections: BTreeMap::new(),
+            regret_curve: Vec::new(),
+        }
+    }
+
+    fn observe(&mut self, chosen: &VerificationRecord, best_reward: f64) {
+        self.decisions += 1;
+        self.cumulative_regret += best_reward - chosen.reward;
+        self.mean_error += chosen.error;
+        *self.selections.entry(format!("{:?}", chosen.algorithm)).or_default() += 1;
+        self.regret_curve.push(self.cumulative_regret);
+    }
+
+    fn finish(mut self) -> Self {
+        if self.decisions > 0 {
+            self.mean_regret = self.cumulative_regret / self.decisions as f64;
+            self.mean_error /= self.decisions as f64;
+        }
+        self
+    }
+}
+
+/// Offline evaluation of selection policies on the verification history
+#[derive(Debug, Clone, Serialize)]
+pub struct RegretReport {
+    pub decisions: usize,
+    pub algorithms: Vec<AlgorithmSummary>,
+    pub policies: Vec<PolicyEvaluation>,
+}
+
+impl fmt::Display for RegretReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} verified fusion cycles", self.decisions)?;
+        writeln!(f)?;
+        writeln!(f, "{:<32} {:>8} {:>12} {:>12} {:>8}", "algorithm", "runs", "mean error", "mean reward", "best")?;
+        for summary in &self.algorithms {
+            writeln!(
+                f,
+                "{:<32} {:>8} {:>12.4} {:>12.4} {:>8}",
+                format!("{:?}", summary.algorithm),
+                summary.observations,
+                summary.mean_error,
+                summary.mean_reward,
+                summary.times_best
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{:<32} {:>8} {:>12} {:>12} {:>12}", "policy", "cycles", "regret", "mean regret", "mean error")?;
+        for policy in &self.policies {
+            writeln!(
+                f,
+                "{:<32} {:>8} {:>12.4} {:>12.4} {:>12.4}",
+                policy.policy, policy.decisions, policy.cumulative_regret, policy.mean_regret, policy.mean_error
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FusedState {
+    /// Scalar estimates that ground truth can verify, keyed like `MetaLearningAlgorithmSelector` error scales
+    pub fn verifiable_quantities(&self) -> HashMap<String, f64> {
+        let weather = &self.weather_state;
+        [
+            ("temperature", weather.temperature),
+            ("humidity", weather.humidity),
+            ("pressure", weather.pressure),
+            ("wind_speed", weather.wind_speed),
+            ("precipitation_rate", weather.precipitation_rate),
+            ("solar_radiation", weather.solar_radiation),
+            ("soil_moisture", self.agricultural_conditions.soil_moisture),
+        ]
+        .into_iter()
+        .filter(|(_, value)| value.is_finite())
+        .map(|(quantity, value)| (quantity.to_string(), value))
+        .collect()
+    }
+}
+
+/// `algorithm-regret <history.jsonl> [--alpha A] [--thompson V] [--json]`
+///
+/// Replays the verification history through LinUCB and Thompson sampling and
+/// prints the regret report.
+pub fn run_algorithm_regret_tool(args: &[String]) -> Result<(), AppError> {
+    let mut history = None;
+    let mut alpha = 0.5;
+    let mut variance_scale = 0.25;
+    let mut json = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut number = |flag: &str| {
+            args.next()
+                .and_then(|value| value.parse::<f64>().ok())
+                .ok_or_else(|| AppError::validation(format!("{} needs a number", flag)))
+        };
+        match arg.as_str() {
+            "--alpha" => alpha = number("--alpha")?,
+            "--thompson" => variance_scale = number("--thompson")?,
+            "--json" => json = true,
+            file => history = Some(PathBuf::from(file)),
+        }
+    }
+    let history = history.ok_or_else(|| AppError::validation("usage: algorithm-regret <history.jsonl> [--alpha A] [--thompson V] [--json]"))?;
+    if !history.exists() {
+        return Err(AppError::not_found(format!("No algorithm history at {}", history.display())));
+    }
+
+    let mut selector = MetaLearningAlgorithmSelector::with_agricultural_priors(BanditPolicy::LinUcb { alpha });
+    selector.attach_history_file(&history)?;
+    let report = selector.regret_report(&[
+        BanditPolicy::LinUcb { alpha },
+        BanditPolicy::ThompsonSampling { variance_scale, seed: 7 },
+    ]);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| AppError::internal(e.to_string()))?);
+    } else {
+        print!("{}", report);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two algorithms whose accuracy flips with sensor conflict
+    fn simulated_error(algorithm: AlgorithmType, conflict: f64) -> f64 {
+        match algorithm {
+            AlgorithmType::ByzantineFaultTolerant => 0.6,
+            _ => 0.1 + 2.0 * conflict,
+        }
+    }
+
+    fn context(conflict: f64) -> SelectionContext {
+        SelectionContext { features: vec![1.0, 0.6, 0.3, 0.1, 0.0, conflict, 0.5, 0.0, 1.0] }
+    }
+
+    fn two_arm_selector(policy: BanditPolicy) -> MetaLearningAlgorithmSelector {
+        let flat = vec![0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        MetaLearningAlgorithmSelector::new(
+            policy,
+            vec![
+                (ArmProfile { algorithm: AlgorithmType::ExtendedKalmanFilter, complexity: ComputationalComplexity::Low, real_time: true }, flat.clone()),
+                (ArmProfile { algorithm: AlgorithmType::ByzantineFaultTolerant, complexity: ComputationalComplexity::Medium, real_time: true }, flat),
+            ],
+            1.0,
+        )
+    }
+
+    /// Record both algorithms as if one ran in shadow, verify, and return the selector
+    fn train(mut selector: MetaLearningAlgorithmSelector, cycles: usize) -> MetaLearningAlgorithmSelector {
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        for cycle in 0..cycles {
+            let conflict = (cycle % 10) as f64 / 10.0;
+            let recorded_at = start + ChronoDuration::minutes(10 * cycle as i64);
+            let decision_id = Uuid::from_u128(cycle as u128 + 1);
+            let chosen = selector.choose(&context(conflict), &ComputationalConstraints::default(), &[]).unwrap();
+            for algorithm in [AlgorithmType::ExtendedKalmanFilter, AlgorithmType::ByzantineFaultTolerant] {
+                let estimate = HashMap::from([("temperature".to_string(), 20.0 + simulated_error(algorithm, conflict))]);
+                selector.record_estimates(decision_id, context(conflict), algorithm, estimate, algorithm == chosen, recorded_at);
+            }
+            selector
+                .verify(decision_id, &HashMap::from([("temperature".to_string(), 20.0)]), recorded_at + ChronoDuration::hours(1))
+                .unwrap();
+        }
+        selector
+    }
+
+    #[test]
+    fn test_linucb_learns_context_dependent_choice() {
+        let selector = train(two_arm_selector(BanditPolicy::LinUcb { alpha: 0.2 }), 200);
+        let constraints = ComputationalConstraints::default();
+        assert_eq!(selector.choose(&context(0.0), &constraints, &[]).unwrap(), AlgorithmType::ExtendedKalmanFilter);
+        assert_eq!(selector.choose(&context(0.9), &constraints, &[]).unwrap(), AlgorithmType::ByzantineFaultTolerant);
+
+        let low_latency = ComputationalConstraints { require_real_time: true, max_complexity: ComputationalComplexity::Low };
+        assert_eq!(selector.choose(&context(0.9), &low_latency, &[]).unwrap(), AlgorithmType::ExtendedKalmanFilter);
+    }
+
+    #[test]
+    fn test_regret_report_and_persisted_history() {
+        let path = std::env::temp_dir().join(format!("algorithm-history-{}.jsonl", Uuid::new_v4()));
+        let mut selector = two_arm_selector(BanditPolicy::ThompsonSampling { variance_scale: 0.1, seed: 3 });
+        assert_eq!(selector.attach_history_file(&path).unwrap(), 0);
+        let selector = train(selector, 300);
+
+        let report = selector.regret_report(&[
+            BanditPolicy::LinUcb { alpha: 0.2 },
+            BanditPolicy::ThompsonSampling { variance_scale: 0.1, seed: 11 },
+        ]);
+        assert_eq!(report.decisions, 300);
+        assert_eq!(report.algorithms.iter().map(|a| a.observations).sum::<usize>(), 600);
+        for policy in &report.policies {
+            assert_eq!(policy.decisions, 300);
+            assert!(policy.regret_curve.windows(2).all(|w| w[1] >= w[0] - 1e-12));
+            // Sub-linear: the second half adds less regret than the first
+            let half = policy.regret_curve[149];
+            assert!(policy.cumulative_regret - half < half, "{}: {:?}", policy.policy, policy.cumulative_regret);
+        }
+        assert!(report.to_string().contains("logged"));
+
+        let mut reloaded = two_arm_selector(BanditPolicy::LinUcb { alpha: 0.2 });
+        assert_eq!(reloaded.attach_history_file(&path).unwrap(), 600);
+        assert_eq!(reloaded.choose(&context(0.9), &ComputationalConstraints::default(), &[]).unwrap(), AlgorithmType::ByzantineFaultTolerant);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_verify_requires_overlapping_truth() {
+        let mut selector = two_arm_selector(BanditPolicy::LinUcb { alpha: 0.2 });
+        let id = Uuid::from_u128(9);
+        selector.record_estimates(id, context(0.1), AlgorithmType::ExtendedKalmanFilter, HashMap::from([("humidity".to_string(), 50.0)]), true, Utc::now());
+        assert!(selector.verify(id, &HashMap::from([("temperature".to_string(), 20.0)]), Utc::now()).is_err());
+        let records = selector.verify(id, &HashMap::from([("humidity".to_string(), 55.0)]), Utc::now()).unwrap();
+        assert!((records[0].error - 1.0).abs() < 1e-12);
+        assert!(selector.verify(id, &HashMap::new(), Utc::now()).is_err());
+    }
+}
@@ -4,28 +4,52 @@ use std::time::Duration;
 use chrono::{DateTime, Utc};
 use nalgebra::{DMatrix, DVector};
 use tokio::sync::RwLock;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error::AppError;
+use crate::data_ingestion::RawDataRecord;
+use crate::reconstruction::CalibrationRegistry;
 
 pub mod temporal_alignment;
 pub mod fusion_algorithms;
+pub mod factor_graph;
 pub mod fuzzy_evidence;
+pub mod fuzzy_rule_base;
+pub mod fault_isolation;
+pub mod meta_learning;
 pub mod optimization;
 pub mod bayesian_network;
+pub mod orbit_propagation;
 pub mod phantom_satellites;
 pub mod phantom_reality_4d;
 pub mod phantom_alignment;
 pub mod measurement;
-pub mod reconstruction;
+pub mod irrigation_planner;
+pub mod clear_sky_forecast;
+pub mod temporal_interpolation;
+pub mod spatial_interpolation;
+pub mod terrain;
+pub mod radar_soil_moisture;
+#[cfg(test)]
+pub(crate) mod test_fixtures;
 
 // Re-exports
 pub use temporal_alignment::*;
 pub use fusion_algorithms::*;
 pub use fuzzy_evidence::*;
+pub use fuzzy_rule_base::*;
+pub use fault_isolation::*;
+pub use meta_learning::*;
 pub use optimization::*;
 pub use bayesian_network::*;
 pub use measurement::*;
+pub use irrigation_planner::*;
+pub use clear_sky_forecast::*;
+pub use temporal_interpolation::*;
+pub use spatial_interpolation::*;
+pub use terrain::*;
+pub use radar_soil_moisture::*;
 
 /// Core Data Fusion Engine - The Heart of Agricultural Weather Intelligence
 /// 
@@ -61,6 +85,18 @@ pub struct DataFusionEngine {
     /// Comprehensive performance monitoring and learning system
     pub performance_monitor: Arc<RwLock<PerformanceMonitor>>,
     
+    /// Residual tracking and quarantine of faulty or tampered sensors
+    pub fault_monitor: Arc<RwLock<SensorFaultMonitor>>,
+    
+    /// Versioned sensor calibrations applied to measurements before fusion
+    pub calibration_registry: Arc<RwLock<CalibrationRegistry>>,
+    
+    /// Soil moisture retrieval from radar backscatter, with each field's change detection history
+    pub radar_retriever: Arc<RwLock<RadarSoilMoistureRetriever>>,
+    
+    /// Agronomist-maintained rule base, evaluated crisply on each fused state
+    pub rule_base: Option<FuzzyRuleBase>,
+    
     /// Configuration parameters for fine-tuning fusion behavior
     pub config: FusionConfig,
 }
@@ -97,6 +133,37 @@ pub struct FusionConfig {
     pub irrigation_priority_weight: f64,
     pub crop_stress_sensitivity: f64,
     pub weather_forecast_horizon_hours: u64,
+    
+    /// Rule files for the fuzzy evidence processors and Bayesian network;
+    /// falls back to `RuleBaseSelection::from_env`, then to the built-in definitions
+    pub rule_base: Option<RuleBaseSelection>,
+    
+    /// JSON Lines file of verified algorithm errors that the meta-learning selector
+    /// replays at startup and appends to
+    pub algorithm_history_path: Option<std::path::PathBuf>,
+    
+    /// JSON Lines file of sensor calibrations that the registry replays at startup and appends to
+    pub calibration_registry_path: Option<std::path::PathBuf>,
+    
+    /// Folder of DEM tiles that the GPS, weather station and satellite imagery
+    /// processors use for station heights, shading and slope corrections
+    pub dem_directory: Option<std::path::PathBuf>,
+    
+    /// Algorithms run in shadow each cycle, next-best first, so that verification
+    /// scores the arms the selector did not pick as well as the one it did
+    pub shadow_algorithms: usize,
+}
+
+impl FusionConfig {
+    /// Fusion settings from the application configuration; the rest keep their defaults
+    pub fn from_app_config(config: &crate::config::Config) -> Self {
+        Self {
+            byzantine_fault_threshold: config.fusion_byzantine_fault_threshold,
+            calibration_registry_path: config.calibration_registry_path.as_ref().map(std::path::PathBuf::from),
+            dem_directory: config.dem_directory.as_ref().map(std::path::PathBuf::from),
+            ..Self::default()
+        }
+    }
 }
 
 impl Default for FusionConfig {
@@ -114,6 +181,11 @@ impl Default for FusionConfig {
             irrigation_priority_weight: 2.0,
             crop_stress_sensitivity: 0.8,
             weather_forecast_horizon_hours: 72,
+            rule_base: None,
+            algorithm_history_path: None,
+            calibration_registry_path: None,
+            dem_directory: None,
+            shadow_algorithms: 2,
         }
     }
 }
@@ -205,6 +277,56 @@ pub enum MeasurementValue {
     Custom(HashMap<String, f64>),
 }
 
+impl MeasurementValue {
+    /// Primary scalar quantity of the measurement, if it has one
+    pub fn scalar_value(&self) -> Option<f64> {
+        match self {
+            MeasurementValue::Scalar(value) | MeasurementValue::Humidity(value) => Some(*value),
+            MeasurementValue::Temperature { value, .. }
+            | MeasurementValue::Pressure { value, .. }
+            | MeasurementValue::SoilMoisture { value, .. } => Some(*value),
+            MeasurementValue::WindVector { speed, .. } => Some(*speed),
+            MeasurementValue::Precipitation { rate, .. } => Some(*rate),
+            MeasurementValue::SolarRadiation { global, .. } => Some(*global),
+            _ => None,
+        }
+    }
+
+    /// All numeric components of the measurement, for multivariate comparison
+    ///
+    /// Wind is returned as (u, v) components so that directions either side
+    /// of north compare as close.
+    pub fn feature_vector(&self) -> Option<Vec<f64>> {
+        match self {
+            MeasurementValue::Vector(values) => Some(values.clone()),
+            MeasurementValue::Position(lat, lon, alt) => Some(vec![*lat, *lon, *alt]),
+            MeasurementValue::WindVector { speed, direction, .. } => {
+                let radians = direction.to_radians();
+                Some(vec![-speed * radians.sin(), -speed * radians.cos()])
+            },
+            MeasurementValue::SolarRadiation { global, direct, diffuse } => Some(vec![*global, *direct, *diffuse]),
+            MeasurementValue::MultispectralImage { bands, .. } => Some(bands.clone()),
+            other => other.scalar_value().map(|value| vec![value]),
+        }
+    }
+
+    /// Copy of the measurement with its primary scalar replaced, keeping units and metadata
+    pub fn with_scalar_value(&self, value: f64) -> MeasurementValue {
+        let mut updated = self.clone();
+        match &mut updated {
+            MeasurementValue::Scalar(v) | MeasurementValue::Humidity(v) => *v = value,
+            MeasurementValue::Temperature { value: v, .. }
+            | MeasurementValue::Pressure { value: v, .. }
+            | MeasurementValue::SoilMoisture { value: v, .. } => *v = value,
+            MeasurementValue::WindVector { speed, .. } => *speed = value,
+            MeasurementValue::Precipitation { rate, .. } => *rate = value,
+            MeasurementValue::SolarRadiation { global, .. } => *global = value,
+            _ => {},
+        }
+        updated
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum TemperatureScale {
     Celsius,
@@ -258,6 +380,8 @@ pub struct SensorMetadata {
     pub communication_latency: Option<f64>,
     pub power_status: PowerStatus,
     pub communication_quality: f64,
+    /// Registry calibration applied to this sensor's readings, e.g. `drone-7@v3`
+    pub calibration_version: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -296,7 +420,7 @@ pub struct QualityMetrics {
 }
 
 /// Agricultural sensor types with specific characteristics
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SensorType {
     /// GPS positioning system
     GPS,
@@ -308,6 +432,8 @@ pub enum SensorType {
     SoilSensor,
     /// Satellite imagery and remote sensing
     SatelliteImagery,
+    /// Satellite synthetic aperture radar
+    SatelliteRadar,
     /// Weather radar for precipitation
     RadarPrecipitation,
     /// Lightning detection network
@@ -375,7 +501,7 @@ pub struct AgriculturalContext {
     pub historical_yield_data: Option<f64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GrowthStage {
     Germination,
     Emergence,
@@ -426,10 +552,13 @@ pub struct FusionResult {
     
     /// Agricultural-specific insights
     pub agricultural_insights: AgriculturalInsights,
+    
+    /// Sensor quarantine status after fault detection for this cycle
+    pub sensor_health: SensorHealthReport,
 }
 
 /// Fused state representing the best estimate of current conditions
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct FusedState {
     /// Geographic position if relevant
     pub position: Option<(f64, f64, f64)>,
@@ -451,7 +580,7 @@ pub struct FusedState {
 }
 
 /// Comprehensive weather state with agricultural relevance
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct WeatherState {
     pub temperature: f64,
     pub humidity: f64,  
@@ -469,7 +598,7 @@ pub struct WeatherState {
 }
 
 /// Agricultural conditions derived from multi-sensor fusion
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct AgriculturalConditions {
     pub soil_moisture: f64,
     pub soil_temperature: f64,
@@ -487,7 +616,7 @@ pub struct AgriculturalConditions {
 }
 
 /// Multi-dimensional confidence metrics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ConfidenceMetrics {
     pub overall_confidence: f64,
     pub temporal_confidence: f64,
@@ -500,7 +629,7 @@ pub struct ConfidenceMetrics {
 }
 
 /// Comprehensive uncertainty quantification
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct UncertaintyEstimates {
     pub position_uncertainty: Option<(f64, f64, f64)>,
     pub weather_uncertainty: HashMap<String, f64>,
@@ -512,13 +641,15 @@ pub struct UncertaintyEstimates {
 }
 
 /// Agricultural insights derived from fusion process
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct AgriculturalInsights {
     pub irrigation_recommendations: Vec<IrrigationRecommendation>,
     pub crop_management_alerts: Vec<CropAlert>,
     pub weather_impact_assessment: WeatherImpactAssessment,
     pub yield_forecast_update: Option<YieldForecast>,
     pub resource_optimization_suggestions: Vec<ResourceOptimization>,
+    /// Crisp outputs of the configured rule base for the fused state, e.g. `irrigation_depth_mm`
+    pub rule_outputs: HashMap<String, f64>,
 }
 
 #[derive(Debug, Clone)]
@@ -552,7 +683,7 @@ pub enum AlertType {
     WeatherDamage,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct WeatherImpactAssessment {
     pub short_term_impact: f64,   // next 24-48 hours
     pub medium_term_impact: f64,  // next week
@@ -598,9 +729,7 @@ impl DataFusionEngine {
     pub async fn new(config: FusionConfig) -> Result<Self, AppError> {
         // Initialize the Bayesian network with agricultural weather domain knowledge
         let network_topology = Self::create_agricultural_weather_network().await?;
-        let bayesian_network = Arc::new(RwLock::new(
-            FuzzyBayesianNetwork::new_with_agricultural_semantics(network_topology).await?
-        ));
+        let mut bayesian_network = FuzzyBayesianNetwork::new_with_agricultural_semantics(network_topology).await?;
 
         // Initialize temporal alignment engine with atomic clock support
         let temporal_engine = Arc::new(RwLock::new(
@@ -610,13 +739,45 @@ impl DataFusionEngine {
         // Initialize the complete suite of fusion algorithms
         let fusion_algorithms = Self::initialize_comprehensive_fusion_algorithms(&config).await?;
 
-        // Initialize meta-learning system with agricultural priors
-        let algorithm_selector = Arc::new(RwLock::new(
-            MetaLearningAlgorithmSelector::new_with_agricultural_domain_knowledge().await?
-        ));
+        // Initialize meta-learning system with agricultural priors and the verified history
+        let mut algorithm_selector = MetaLearningAlgorithmSelector::new_with_agricultural_domain_knowledge().await?;
+        for algorithm in fusion_algorithms.values() {
+            algorithm_selector.register_algorithm(algorithm.as_ref());
+        }
+        if let Some(path) = &config.algorithm_history_path {
+            let replayed = algorithm_selector.attach_history_file(path)?;
+            tracing::info!("Replayed {} verified fusion outcomes from {}", replayed, path.display());
+        }
+        let algorithm_selector = Arc::new(RwLock::new(algorithm_selector));
 
         // Initialize specialized evidence processors for each sensor type
-        let evidence_processors = Self::initialize_agricultural_evidence_processors().await?;
+        let elevation_model = match &config.dem_directory {
+            Some(directory) => {
+                let mut dem = DigitalElevationModel::new();
+                let tiles = dem.load_directory(directory)?;
+                tracing::info!("Loaded {} DEM tiles from {}", tiles, directory.display());
+                Some(Arc::new(dem))
+            }
+            None => None,
+        };
+        let mut evidence_processors = Self::initialize_agricultural_evidence_processors(elevation_model).await?;
+
+        // Override built-in linguistic variables and rules with agronomist-maintained rule files
+        let mut rule_base = None;
+        if let Some(selection) = config.rule_base.clone().or_else(RuleBaseSelection::from_env) {
+            let loaded = FuzzyRuleBase::load(&selection)?;
+            for warning in loaded.warnings() {
+                tracing::warn!("Fuzzy rule base {}", warning);
+            }
+            let variables = loaded.apply_to_processors(&mut evidence_processors);
+            let nodes = bayesian_network.install_rule_base(&loaded);
+            tracing::info!(
+                "Loaded fuzzy rule base from {} ({} processor variables, {} network nodes)",
+                selection.directory.display(), variables, nodes
+            );
+            rule_base = Some(loaded);
+        }
+        let bayesian_network = Arc::new(RwLock::new(bayesian_network));
 
         // Initialize multi-objective Bayesian optimizer
         let optimizer = Arc::new(RwLock::new(
@@ -628,6 +789,17 @@ impl DataFusionEngine {
             PerformanceMonitor::new_with_agricultural_metrics().await?
         ));
 
+        let fault_monitor = Arc::new(RwLock::new(
+            SensorFaultMonitor::new(FaultIsolationConfig::from_fusion_config(&config))
+        ));
+
+        let mut calibration_registry = CalibrationRegistry::new();
+        if let Some(path) = &config.calibration_registry_path {
+            let replayed = calibration_registry.attach_file(path)?;
+            tracing::info!("Replayed {} sensor calibrations from {}", replayed, path.display());
+        }
+        let calibration_registry = Arc::new(RwLock::new(calibration_registry));
+
         Ok(Self {
             bayesian_network,
             temporal_engine,
@@ -636,18 +808,61 @@ impl DataFusionEngine {
             evidence_processors,
             optimizer,
             performance_monitor,
+            fault_monitor,
+            calibration_registry,
+            radar_retriever: Arc::new(RwLock::new(RadarSoilMoistureRetriever::default())),
+            rule_base,
             config,
         })
     }
 
+    /// Share the sensor fault monitor, e.g. with the API that reports and releases quarantines
+    pub fn with_fault_monitor(mut self, fault_monitor: Arc<RwLock<SensorFaultMonitor>>) -> Self {
+        self.fault_monitor = fault_monitor;
+        self
+    }
+
+    /// Share the calibration registry, e.g. with the API that registers new calibrations
+    pub fn with_calibration_registry(mut self, calibration_registry: Arc<RwLock<CalibrationRegistry>>) -> Self {
+        self.calibration_registry = calibration_registry;
+        self
+    }
+
+    /// Retrieve soil moisture from radar field statistics, e.g. a batch collected from
+    /// `SatelliteRadar` sources, and fuse the retrievals; invalid records are skipped
+    pub async fn fuse_radar_records(&self, records: &[RawDataRecord]) -> Result<Option<FusionResult>, AppError> {
+        let retrievals: Vec<SoilMoistureRetrieval> = {
+            let mut retriever = self.radar_retriever.write().await;
+            records
+                .iter()
+                .filter_map(|record| {
+                    BackscatterObservation::from_record(record)
+                        .and_then(|observation| retriever.retrieve(&observation))
+                        .map_err(|e| tracing::warn!("Skipping radar record {}: {}", record.id, e))
+                        .ok()
+                })
+                .collect()
+        };
+        match SoilMoistureRetrieval::bundle(&retrievals) {
+            Some(bundle) => self.fuse_sensor_data(bundle).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
     /// Main fusion processing pipeline - the core intelligence of the system
     pub async fn fuse_sensor_data(
         &self,
         sensor_bundle: SensorMeasurementBundle,
     ) -> Result<FusionResult, AppError> {
         let start_time = std::time::Instant::now();
+        let decision_id = sensor_bundle.bundle_id;
+        
+        // Step 0: Apply registered calibrations, then test sensors against the last fused state and their neighbours, withhold quarantined ones
+        let sensor_bundle = self.calibration_registry.read().await.calibrate_bundle(sensor_bundle);
+        let (sensor_bundle, sensor_health) = self.fault_monitor.write().await.screen(sensor_bundle);
         
         // Step 1: Advanced temporal alignment with nanosecond precision
+        let shadow_bundle = sensor_bundle.clone();
         let aligned_data = self.perform_advanced_temporal_alignment(sensor_bundle).await?;
         
         // Step 2: Convert to fuzzy evidence with agricultural semantics
@@ -676,19 +891,139 @@ impl DataFusionEngine {
         let optimized_state = self.optimize_agricultural_objectives(&network_state).await?;
         
         // Step 8: Generate comprehensive result with agricultural insights
-        let final_result = self.generate_comprehensive_fusion_result(
+        let mut final_result = self.generate_comprehensive_fusion_result(
             optimized_state,
             selected_algorithm,
             &evidence_analysis,
             start_time.elapsed(),
         ).await?;
+        final_result.sensor_health = sensor_health;
+        if let Some(rule_base) = &self.rule_base {
+            // Mamdani rules already shape the network beliefs; this also evaluates the Sugeno rules
+            final_result.agricultural_insights.rule_outputs = rule_base.infer(&Self::rule_inputs(&final_result.fused_state));
+        }
+        self.fault_monitor.write().await.set_fused_reference(
+            &final_result.fused_state,
+            &final_result.uncertainty_estimates,
+        );
         
-        // Step 9: Performance learning and model adaptation
+        // Step 9: Performance learning and model adaptation; shadow runs give the
+        // unpicked algorithms estimates of their own to be verified against
+        let context = SelectionContext::from_analysis(&evidence_analysis);
+        let shadow_estimates = self.run_shadow_algorithms(selected_algorithm, &context, &shadow_bundle, &aligned_data).await;
+        {
+            let recorded_at = Utc::now();
+            let mut selector = self.algorithm_selector.write().await;
+            selector.record_estimates(
+                decision_id,
+                context.clone(),
+                selected_algorithm,
+                final_result.fused_state.verifiable_quantities(),
+                true,
+                recorded_at,
+            );
+            for (algorithm, estimates) in shadow_estimates {
+                selector.record_estimates(decision_id, context.clone(), algorithm, estimates, false, recorded_at);
+            }
+        }
         self.update_performance_and_learn(&final_result).await?;
         
         Ok(final_result)
     }
 
+    /// Score the algorithm used for bundle `decision_id` against later ground truth,
+    /// e.g. a reference station reading keyed like `FusedState::verifiable_quantities`
+    pub async fn verify_fusion_decision(
+        &self,
+        decision_id: Uuid,
+        truth: &HashMap<String, f64>,
+    ) -> Result<Vec<VerificationRecord>, AppError> {
+        self.algorithm_selector.write().await.verify(decision_id, truth, Utc::now())
+    }
+
+    /// Crisp rule-base inputs from a fused state, under the variable names used in the rule files
+    fn rule_inputs(fused_state: &FusedState) -> HashMap<String, f64> {
+        let mut inputs = fused_state.verifiable_quantities();
+        if let Some(&soil_moisture) = inputs.get("soil_moisture") {
+            inputs.insert("volumetric_water_content".to_string(), soil_moisture);
+        }
+        inputs
+    }
+
+    /// Estimates of the `shadow_algorithms` next-best algorithms on the same bundle;
+    /// a failed shadow run is logged and skipped
+    async fn run_shadow_algorithms(
+        &self,
+        selected_algorithm: AlgorithmType,
+        context: &SelectionContext,
+        bundle: &SensorMeasurementBundle,
+        aligned_data: &AlignedSensorData,
+    ) -> Vec<(AlgorithmType, HashMap<String, f64>)> {
+        let mut candidates: Vec<AlgorithmType> =
+            self.fusion_algorithms.keys().copied().filter(|algorithm| *algorithm != selected_algorithm).collect();
+        let mut shadows = Vec::new();
+        {
+            let selector = self.algorithm_selector.read().await;
+            while shadows.len() < self.config.shadow_algorithms && !candidates.is_empty() {
+                let Ok(next) = selector.choose(context, &ComputationalConstraints::default(), &candidates) else {
+                    break;
+                };
+                candidates.retain(|algorithm| *algorithm != next);
+                shadows.push(next);
+            }
+        }
+
+        let mut estimates = Vec::new();
+        for algorithm in shadows {
+            match self.fusion_algorithms[&algorithm].fuse_measurements(bundle, aligned_data).await {
+                Ok(result) => estimates.push((algorithm, result.fused_state.verifiable_quantities())),
+                Err(e) => tracing::warn!("Shadow run of {:?} failed: {}", algorithm, e),
+            }
+        }
+        estimates
+    }
+
+    async fn analyze_evidence_ensemble_comprehensively(&self, evidence: &[FuzzyEvidence]) -> Result<EvidenceAnalysis, AppError> {
+        Ok(EvidenceAnalysis::from_evidence(evidence))
+    }
+
+    /// Contextual-bandit choice among the algorithms this engine has instantiated
+    async fn select_optimal_fusion_algorithm(&self, evidence_analysis: &EvidenceAnalysis) -> Result<AlgorithmType, AppError> {
+        let candidates: Vec<AlgorithmType> = self.fusion_algorithms.keys().copied().collect();
+        self.algorithm_selector.read().await.choose(
+            &SelectionContext::from_analysis(evidence_analysis),
+            &ComputationalConstraints::default(),
+            &candidates,
+        )
+    }
+
+    /// Evidence processors for every sensor type with a fuzzy interpretation; the terrain-aware
+    /// ones use the DEM when one is loaded
+    async fn initialize_agricultural_evidence_processors(
+        elevation_model: Option<Arc<DigitalElevationModel>>,
+    ) -> Result<HashMap<SensorType, Box<dyn FuzzyEvidenceProcessor + Send + Sync>>, AppError> {
+        let mut gps = GPSEvidenceProcessor::new();
+        let mut weather_station = WeatherStationEvidenceProcessor::new();
+        let mut satellite_imagery = SatelliteImageryEvidenceProcessor::new();
+        if let Some(dem) = elevation_model {
+            gps = gps.with_elevation_model(dem.clone());
+            weather_station = weather_station.with_terrain(dem.clone(), None);
+            satellite_imagery = satellite_imagery.with_elevation_model(dem);
+        }
+
+        let mut processors: HashMap<SensorType, Box<dyn FuzzyEvidenceProcessor + Send + Sync>> = HashMap::new();
+        processors.insert(SensorType::GPS, Box::new(gps));
+        processors.insert(SensorType::WeatherStation, Box::new(weather_station));
+        processors.insert(SensorType::SoilSensor, Box::new(SoilSensorEvidenceProcessor::new()));
+        processors.insert(SensorType::SatelliteImagery, Box::new(satellite_imagery));
+        processors.insert(SensorType::SatelliteRadar, Box::new(SatelliteRadarEvidenceProcessor::new()));
+        processors.insert(SensorType::RadarPrecipitation, Box::new(RadarPrecipitationEvidenceProcessor::new()));
+        processors.insert(SensorType::LightningDetector, Box::new(LightningDetectorEvidenceProcessor::new()));
+        processors.insert(SensorType::WindProfiler, Box::new(WindProfilerEvidenceProcessor::new()));
+        processors.insert(SensorType::AutonomousWeatherBuoy, Box::new(WeatherBuoyEvidenceProcessor::new()));
+        Ok(processors)
+    }
+
     // Implementation continues with all the sophisticated methods...
     // This is just the beginning - the full implementation would be several thousand lines
     // covering all the advanced algorithms from the attached files
@@ -3,7 +3,8 @@ use std::sync::Arc;
 use chrono::{DateTime, Utc};
 use nalgebra::{DMatrix, DVector};
 use tokio::sync::RwLock;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use rand_distr::{Normal, Distribution};
 
 use crate::error::AppError;
@@ -384,6 +385,13 @@ pub struct AnomalyPattern {
     pub recommended_action: String,
 }
 
+/// Non-dominated evaluation returned by `BayesianOptimizer::search_pareto_front`
+#[derive(Debug, Clone)]
+pub struct ParetoPoint {
+    pub parameters: DVector<f64>,
+    pub objectives: DVector<f64>,
+}
+
 /// Optimization trace for recording the optimization process
 #[derive(Debug, Clone, Default)]
 pub struct OptimizationTrace {
@@ -551,6 +559,170 @@ impl BayesianOptimizer {
         Ok(candidate)
     }
 
+    /// Risk preferences used when turning uncertain outcomes into objectives
+    pub fn risk_profile(&self) -> &AgriculturalRiskProfile {
+        &self.acquisition_function.agricultural_risk_profile
+    }
+
+    pub fn set_risk_aversion(&mut self, risk_aversion: f64) {
+        self.acquisition_function.agricultural_risk_profile.risk_aversion = risk_aversion.clamp(0.0, 1.0);
+    }
+
+    /// Multi-objective search over a bounded decision space (ParEGO)
+    ///
+    /// Every iteration draws a random weight vector, scalarises the normalised
+    /// objectives with the augmented Chebyshev function, fits a GP to the
+    /// scalarised values and evaluates the candidate with the highest expected
+    /// improvement. All objectives are maximised. The non-dominated evaluations
+    /// that satisfy the `ParetoPreferences` minimum thresholds are returned.
+    /// The design, weight vectors and candidates are drawn from `seed`, so a
+    /// search with the same seed and objective function is reproducible.
+    pub fn search_pareto_front<F>(
+        &self,
+        objectives: &[ObjectiveType],
+        bounds: &[(f64, f64)],
+        evaluation_budget: usize,
+        seed: u64,
+        mut evaluate: F,
+    ) -> Result<Vec<ParetoPoint>, AppError>
+    where
+        F: FnMut(&DVector<f64>) -> Result<DVector<f64>, AppError>,
+    {
+        if bounds.is_empty() || objectives.is_empty() {
+            return Err(AppError::validation("Pareto search needs at least one parameter and one objective"));
+        }
+
+        let dimension = bounds.len();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let to_parameters = |unit: &DVector<f64>| {
+            DVector::from_iterator(dimension, unit.iter().zip(bounds).map(|(u, (lo, hi))| lo + u * (hi - lo)))
+        };
+
+        // Space-filling initial design in the unit hypercube
+        let initial_samples = (2 * dimension + 1).min(evaluation_budget.max(1));
+        let mut unit_inputs: Vec<DVector<f64>> = Vec::with_capacity(evaluation_budget);
+        let mut observed: Vec<DVector<f64>> = Vec::with_capacity(evaluation_budget);
+        for _ in 0..initial_samples {
+            let unit = DVector::from_fn(dimension, |_, _| rng.gen::<f64>());
+            observed.push(Self::checked_objectives(evaluate(&to_parameters(&unit))?, objectives.len())?);
+            unit_inputs.push(unit);
+        }
+
+        let kernel = KernelFunction::Matern {
+            length_scale: 0.25 * (dimension as f64).sqrt(),
+            variance: 1.0,
+            nu: 2.5,
+        };
+        let hyperparameters = GPHyperparameters {
+            noise_variance: 1e-4,
+            signal_variance: 1.0,
+            length_scales: vec![0.25; dimension],
+            agricultural_specific_params: HashMap::new(),
+        };
+        let xi = 0.1 * self.acquisition_function.exploration_weight;
+
+        while observed.len() < evaluation_budget {
+            let weights = Self::random_simplex_weights(objectives.len(), &mut rng);
+            let scalarised = Self::chebyshev_scalarise(&observed, &weights);
+
+            // Standardise targets so the unit-variance kernel prior fits
+            let mean = scalarised.iter().sum::<f64>() / scalarised.len() as f64;
+            let std = (scalarised.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / scalarised.len() as f64)
+                .sqrt()
+                .max(1e-9);
+            let training_data = unit_inputs
+                .iter()
+                .cloned()
+                .zip(scalarised.iter().map(|v| (v - mean) / std))
+                .collect::<Vec<_>>();
+            let best = training_data.iter().map(|(_, y)| *y).fold(f64::INFINITY, f64::min);
+            let incumbent = training_data
+                .iter()
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(x, _)| x.clone())
+                .unwrap_or_else(|| DVector::from_element(dimension, 0.5));
+            let gp = GaussianProcess::fit(kernel.clone(), hyperparameters.clone(), training_data)?;
+
+            // Maximise EI over global samples and local perturbations of the incumbent
+            let perturbation = Normal::new(0.0, 0.1).map_err(|e| AppError::internal(e.to_string()))?;
+            let mut best_candidate = None;
+            let mut best_ei = f64::NEG_INFINITY;
+            for i in 0..512 {
+                let candidate = if i % 2 == 0 {
+                    DVector::from_fn(dimension, |_, _| rng.gen::<f64>())
+                } else {
+                    incumbent.map(|u| (u + perturbation.sample(&mut rng)).clamp(0.0, 1.0))
+                };
+                let (mu, variance) = gp.predict(&candidate)?;
+                let sigma = variance.sqrt();
+                let improvement = best - mu - xi;
+                let z = improvement / sigma;
+                let ei = improvement * self.standard_normal_cdf(z) + sigma * self.standard_normal_pdf(z);
+                if ei > best_ei {
+                    best_ei = ei;
+                    best_candidate = Some(candidate);
+                }
+            }
+
+            let unit = best_candidate.unwrap_or(incumbent);
+            observed.push(Self::checked_objectives(evaluate(&to_parameters(&unit))?, objectives.len())?);
+            unit_inputs.push(unit);
+        }
+
+        let thresholds = &self.agricultural_objectives.pareto_preferences.minimum_thresholds;
+        let front = (0..observed.len())
+            .filter(|&i| !(0..observed.len()).any(|j| j != i && Self::dominates(&observed[j], &observed[i])))
+            .filter(|&i| {
+                objectives.iter().enumerate().all(|(k, objective)| {
+                    thresholds.get(objective).map_or(true, |min| observed[i][k] >= *min)
+                })
+            })
+            .map(|i| ParetoPoint {
+                parameters: to_parameters(&unit_inputs[i]),
+                objectives: observed[i].clone(),
+            })
+            .collect();
+
+        Ok(front)
+    }
+
+    fn checked_objectives(values: DVector<f64>, expected: usize) -> Result<DVector<f64>, AppError> {
+        if values.len() != expected || values.iter().any(|v| !v.is_finite()) {
+            return Err(AppError::internal("Objective evaluation returned an invalid vector"));
+        }
+        Ok(values)
+    }
+
+    fn random_simplex_weights(count: usize, rng: &mut impl Rng) -> Vec<f64> {
+        let raw: Vec<f64> = (0..count).map(|_| -rng.gen::<f64>().max(1e-12).ln()).collect();
+        let total: f64 = raw.iter().sum();
+        raw.into_iter().map(|w| w / total).collect()
+    }
+
+    /// Augmented Chebyshev scalarisation of maximised objectives; lower is better
+    fn chebyshev_scalarise(observed: &[DVector<f64>], weights: &[f64]) -> Vec<f64> {
+        let dims = weights.len();
+        let lower: Vec<f64> = (0..dims).map(|k| observed.iter().map(|o| o[k]).fold(f64::INFINITY, f64::min)).collect();
+        let upper: Vec<f64> = (0..dims).map(|k| observed.iter().map(|o| o[k]).fold(f64::NEG_INFINITY, f64::max)).collect();
+
+        observed
+            .iter()
+            .map(|o| {
+                let shortfalls: Vec<f64> = (0..dims)
+                    .map(|k| {
+                        let range = (upper[k] - lower[k]).max(1e-12);
+                        weights[k] * (upper[k] - o[k]) / range
+                    })
+                    .collect();
+                shortfalls.iter().cloned().fold(0.0, f64::max) + 0.05 * shortfalls.iter().sum::<f64>()
+            })
+            .collect()
+    }
+
+    fn dominates(a: &DVector<f64>, b: &DVector<f64>) -> bool {
+        a.iter().zip(b.iter()).all(|(x, y)| x >= y) && a.iter().zip(b.iter()).any(|(x, y)| x > y)
+    }
+
     fn apply_agricultural_constraints(&self, value: f64, parameter_index: usize) -> f64 {
         // Apply agricultural domain-specific constraints
         match parameter_index {
@@ -582,22 +754,103 @@ impl AgriculturalObjectives {
     }
 }
 
+impl KernelFunction {
+    /// Evaluate the kernel between two input points
+    pub fn evaluate(&self, a: &DVector<f64>, b: &DVector<f64>) -> f64 {
+        let distance = (a - b).norm();
+        match self {
+            KernelFunction::RBF { length_scale, variance } => {
+                variance * (-0.5 * (distance / length_scale).powi(2)).exp()
+            },
+            KernelFunction::Matern { length_scale, variance, nu } => {
+                let r = distance / length_scale;
+                if *nu <= 0.5 {
+                    variance * (-r).exp()
+                } else if *nu <= 1.5 {
+                    let s = 3.0_f64.sqrt() * r;
+                    variance * (1.0 + s) * (-s).exp()
+                } else {
+                    let s = 5.0_f64.sqrt() * r;
+                    variance * (1.0 + s + s * s / 3.0) * (-s).exp()
+                }
+            },
+            KernelFunction::Periodic { length_scale, variance, period } => {
+                let sine = (std::f64::consts::PI * distance / period).sin();
+                variance * (-2.0 * sine * sine / (length_scale * length_scale)).exp()
+            },
+            KernelFunction::AgriculturalComposite { seasonal_kernel, trend_kernel, noise_kernel } => {
+                seasonal_kernel.evaluate(a, b) + trend_kernel.evaluate(a, b) + noise_kernel.evaluate(a, b)
+            },
+        }
+    }
+}
+
 impl GaussianProcess {
+    /// Fit a GP to the training data, caching the inverse of the noisy
+    /// covariance matrix so predictions are a pair of dot products
+    fn fit(
+        kernel: KernelFunction,
+        hyperparameters: GPHyperparameters,
+        training_data: Vec<(DVector<f64>, f64)>,
+    ) -> Result<Self, AppError> {
+        let n = training_data.len();
+        let mut covariance = DMatrix::zeros(n, n);
+        for i in 0..n {
+            for j in 0..=i {
+                let k = kernel.evaluate(&training_data[i].0, &training_data[j].0);
+                covariance[(i, j)] = k;
+                covariance[(j, i)] = k;
+            }
+            covariance[(i, i)] += hyperparameters.noise_variance;
+        }
+
+        let covariance_matrix = covariance
+            .cholesky()
+            .ok_or_else(|| AppError::internal("GP covariance matrix is not positive definite"))?
+            .inverse();
+
+        Ok(Self {
+            kernel,
+            training_data,
+            hyperparameters,
+            covariance_matrix,
+            domain_knowledge: AgriculturalDomainKnowledge,
+        })
+    }
+
     fn predict(&self, input: &DVector<f64>) -> Result<(f64, f64), AppError> {
-        // Simplified GP prediction - in practice, this would be much more sophisticated
         let mean = self.predict_mean(input)?;
         let variance = self.predict_variance(input)?;
         Ok((mean, variance))
     }
 
-    fn predict_mean(&self, _input: &DVector<f64>) -> Result<f64, AppError> {
-        // Placeholder implementation
-        Ok(0.5)
+    fn cross_covariance(&self, input: &DVector<f64>) -> DVector<f64> {
+        DVector::from_iterator(
+            self.training_data.len(),
+            self.training_data.iter().map(|(x, _)| self.kernel.evaluate(x, input)),
+        )
     }
 
-    fn predict_variance(&self, _input: &DVector<f64>) -> Result<f64, AppError> {
-        // Placeholder implementation
-        Ok(0.1)
+    fn predict_mean(&self, input: &DVector<f64>) -> Result<f64, AppError> {
+        if self.training_data.is_empty() {
+            return Ok(0.0);
+        }
+        let targets = DVector::from_iterator(
+            self.training_data.len(),
+            self.training_data.iter().map(|(_, y)| *y),
+        );
+        let k_star = self.cross_covariance(input);
+        Ok(k_star.dot(&(&self.covariance_matrix * targets)))
+    }
+
+    fn predict_variance(&self, input: &DVector<f64>) -> Result<f64, AppError> {
+        let prior = self.kernel.evaluate(input, input);
+        if self.training_data.is_empty() {
+            return Ok(prior);
+        }
+        let k_star = self.cross_covariance(input);
+        let explained = k_star.dot(&(&self.covariance_matrix * &k_star));
+        Ok((prior - explained).max(1e-12))
     }
 }
 
@@ -1468,4 +1721,80 @@ pub enum OptimizationError {
     ConvergenceFailed,
     #[error("Optimization error: {0}")]
     OptimizationFailed(String),
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rbf_gp(training_data: Vec<(DVector<f64>, f64)>) -> GaussianProcess {
+        let hyperparameters = GPHyperparameters {
+            noise_variance: 1e-6,
+            signal_variance: 1.0,
+            length_scales: vec![0.3],
+            agricultural_specific_params: HashMap::new(),
+        };
+        GaussianProcess::fit(KernelFunction::RBF { length_scale: 0.3, variance: 1.0 }, hyperparameters, training_data)
+            .unwrap()
+    }
+
+    /// Two conflicting objectives on [0, 1]²: x₀ trades one against the other
+    fn conflicting_objectives(parameters: &DVector<f64>) -> Result<DVector<f64>, AppError> {
+        let x = parameters[0];
+        let y = parameters[1];
+        Ok(DVector::from_vec(vec![x - 0.1 * y * y, 1.0 - x * x - 0.1 * y]))
+    }
+
+    #[test]
+    fn test_gp_interpolates_training_points_and_reverts_to_prior_far_away() {
+        let training_data: Vec<_> = [0.0, 0.25, 0.5, 0.75, 1.0]
+            .iter()
+            .map(|&x: &f64| (DVector::from_vec(vec![x]), (6.0 * x).sin()))
+            .collect();
+        let gp = rbf_gp(training_data.clone());
+
+        for (x, y) in &training_data {
+            let (mean, variance) = gp.predict(x).unwrap();
+            assert!((mean - y).abs() < 1e-3, "mean {mean} vs {y}");
+            assert!(variance < 1e-3);
+        }
+
+        let (between, _) = gp.predict(&DVector::from_vec(vec![0.375])).unwrap();
+        assert!((between - (6.0_f64 * 0.375).sin()).abs() < 0.1);
+
+        let (far_mean, far_variance) = gp.predict(&DVector::from_vec(vec![10.0])).unwrap();
+        assert!(far_mean.abs() < 1e-6);
+        assert!((far_variance - 1.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_pareto_front_is_mutually_non_dominated() {
+        let optimizer = BayesianOptimizer::new_agricultural_multi_objective().await.unwrap();
+        let objectives = [ObjectiveType::YieldOptimization, ObjectiveType::WaterEfficiency];
+        let front = optimizer
+            .search_pareto_front(&objectives, &[(0.0, 1.0), (0.0, 1.0)], 30, 7, conflicting_objectives)
+            .unwrap();
+
+        assert!(front.len() >= 2, "a conflicting pair should give more than one trade-off");
+        for a in &front {
+            assert!(front.iter().all(|b| !BayesianOptimizer::dominates(&b.objectives, &a.objectives)));
+            assert!(a.parameters.iter().all(|p| (0.0..=1.0).contains(p)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pareto_search_is_reproducible_for_a_seed() {
+        let optimizer = BayesianOptimizer::new_agricultural_multi_objective().await.unwrap();
+        let objectives = [ObjectiveType::YieldOptimization, ObjectiveType::WaterEfficiency];
+        let bounds = [(0.0, 1.0), (0.0, 1.0)];
+        let run = |seed| optimizer.search_pareto_front(&objectives, &bounds, 15, seed, conflicting_objectives).unwrap();
+
+        let first = run(11);
+        let second = run(11);
+        assert_eq!(first.len(), second.len());
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(a.parameters, b.parameters);
+            assert_eq!(a.objectives, b.objectives);
+        }
+    }
+}
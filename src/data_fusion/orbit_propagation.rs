@@ -0,0 +1,1568 @@
+use std::f64::consts::{PI, TAU};
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+use crate::data_ingestion::BoundingBox;
+
+/// WGS-72 constants that two-line element sets are fitted with
+const EARTH_RADIUS_KM: f64 = 6378.135;
+const MU_KM3_S2: f64 = 398600.8;
+const J2: f64 = 0.001082616;
+const J3: f64 = -0.00000253881;
+const J4: f64 = -0.00000165597;
+const J3OJ2: f64 = J3 / J2;
+const X2O3: f64 = 2.0 / 3.0;
+
+/// WGS-84 ellipsoid for geodetic coordinates of ground points and sub-satellite points
+const WGS84_A_KM: f64 = 6378.137;
+const WGS84_F: f64 = 1.0 / 298.257223563;
+
+/// Radians the Earth turns per minute
+const EARTH_ROTATION_RAD_MIN: f64 = 4.375_269_088_011_3e-3;
+
+/// `√(μ/Rₑ³)` in Earth radii^1.5 per minute
+fn xke() -> f64 {
+    60.0 / (EARTH_RADIUS_KM.powi(3) / MU_KM3_S2).sqrt()
+}
+
+/// Mean elements of a NORAD two-line element set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TwoLineElements {
+    pub name: Option<String>,
+    pub catalog_number: u32,
+    pub classification: char,
+    pub international_designator: String,
+    pub epoch: DateTime<Utc>,
+    /// First derivative of mean motion / 2, rev/day²
+    pub mean_motion_dot: f64,
+    /// Second derivative of mean motion / 6, rev/day³
+    pub mean_motion_ddot: f64,
+    /// Drag term, 1/Earth radii
+    pub bstar: f64,
+    pub element_set_number: u32,
+    pub inclination_deg: f64,
+    pub raan_deg: f64,
+    pub eccentricity: f64,
+    pub argument_of_perigee_deg: f64,
+    pub mean_anomaly_deg: f64,
+    /// Kozai mean motion, rev/day
+    pub mean_motion_rev_day: f64,
+    pub revolution_number: u32,
+}
+
+fn tle_field(line: &str, start: usize, end: usize) -> &str {
+    line.get(start - 1..end.min(line.len())).unwrap_or("").trim()
+}
+
+fn parse_number<T: std::str::FromStr>(line: &str, start: usize, end: usize, name: &str) -> Result<T, AppError> {
+    let field = tle_field(line, start, end);
+    field
+        .parse()
+        .map_err(|_| AppError::validation(format!("TLE {} '{}' in columns {}-{} is not a number", name, field, start, end)))
+}
+
+/// Fields like ` 28098-4` meaning 0.28098e-4
+fn parse_implied_exponent(line: &str, start: usize, end: usize, name: &str) -> Result<f64, AppError> {
+    let field = tle_field(line, start, end);
+    if field.is_empty() {
+        return Ok(0.0);
+    }
+    let (sign, digits) = match field.as_bytes()[0] {
+        b'-' => (-1.0, &field[1..]),
+        b'+' => (1.0, &field[1..]),
+        _ => (1.0, field),
+    };
+    let split = digits.rfind(['-', '+']).filter(|&i| i > 0);
+    let (mantissa, exponent) = match split {
+        Some(i) => (&digits[..i], &digits[i..]),
+        None => (digits, "0"),
+    };
+    let mantissa: f64 = format!("0.{}", mantissa.trim())
+        .parse()
+        .map_err(|_| AppError::validation(format!("TLE {} '{}' is malformed", name, field)))?;
+    let exponent: i32 = exponent
+        .parse()
+        .map_err(|_| AppError::validation(format!("TLE {} '{}' is malformed", name, field)))?;
+    Ok(sign * mantissa * 10f64.powi(exponent))
+}
+
+fn checksum(line: &str) -> u32 {
+    line.chars().take(68).map(|c| match c {
+        '-' => 1,
+        c => c.to_digit(10).unwrap_or(0),
+    }).sum::<u32>() % 10
+}
+
+impl TwoLineElements {
+    /// Parse a two- or three-line element set; checksums are verified when present
+    pub fn parse(text: &str) -> Result<Self, AppError> {
+        let lines: Vec<&str> = text.lines().map(|l| l.trim_end()).filter(|l| !l.trim().is_empty()).collect();
+        let (name, line1, line2) = match lines.as_slice() {
+            [line1, line2] => (None, *line1, *line2),
+            [name, line1, line2] => (Some(name.trim_start_matches("0 ").trim().to_string()), *line1, *line2),
+            _ => return Err(AppError::validation("A TLE needs two element lines and an optional name line")),
+        };
+        Self::from_lines(name, line1, line2)
+    }
+
+    /// Every element set in a CelesTrak-style catalogue
+    pub fn parse_catalog(text: &str) -> Result<Vec<Self>, AppError> {
+        let lines: Vec<&str> = text.lines().map(|l| l.trim_end()).filter(|l| !l.trim().is_empty()).collect();
+        let mut sets = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            if lines[i].starts_with("1 ") && lines.get(i + 1).is_some_and(|l| l.starts_with("2 ")) {
+                sets.push(Self::from_lines(None, lines[i], lines[i + 1])?);
+                i += 2;
+            } else if lines.get(i + 1).is_some_and(|l| l.starts_with("1 ")) && lines.get(i + 2).is_some_and(|l| l.starts_with("2 ")) {
+                let name = lines[i].trim_start_matches("0 ").trim().to_string();
+                sets.push(Self::from_lines(Some(name), lines[i + 1], lines[i + 2])?);
+                i += 3;
+            } else {
+                return Err(AppError::validation(format!("Unexpected line in TLE catalogue: '{}'", lines[i])));
+            }
+        }
+        Ok(sets)
+    }
+
+    pub fn from_lines(name: Option<String>, line1: &str, line2: &str) -> Result<Self, AppError> {
+        if !line1.starts_with("1 ") || !line2.starts_with("2 ") || line1.len() < 64 || line2.len() < 63 {
+            return Err(AppError::validation("TLE lines must start with '1 ' and '2 ' and hold every element"));
+        }
+        for (number, line) in [(1, line1), (2, line2)] {
+            if let Some(expected) = line.chars().nth(68).and_then(|c| c.to_digit(10)) {
+                if checksum(line) != expected {
+                    return Err(AppError::validation(format!(
+                        "TLE line {} checksum is {} but the line sums to {}",
+                        number,
+                        expected,
+                        checksum(line)
+                    )));
+                }
+            }
+        }
+
+        let catalog_number: u32 = parse_number(line1, 3, 7, "catalogue number")?;
+        if parse_number::<u32>(line2, 3, 7, "catalogue number")? != catalog_number {
+            return Err(AppError::validation("TLE lines describe different satellites"));
+        }
+
+        let two_digit_year: i32 = parse_number(line1, 19, 20, "epoch year")?;
+        let year = if two_digit_year < 57 { 2000 + two_digit_year } else { 1900 + two_digit_year };
+        let day_of_year: f64 = parse_number(line1, 21, 32, "epoch day")?;
+        let start_of_year = Utc
+            .with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+            .single()
+            .ok_or_else(|| AppError::validation("TLE epoch year is out of range"))?;
+        let epoch = start_of_year + ChronoDuration::microseconds(((day_of_year - 1.0) * 86_400e6).round() as i64);
+
+        let eccentricity: f64 = format!("0.{}", tle_field(line2, 27, 33))
+            .parse()
+            .map_err(|_| AppError::validation("TLE eccentricity is not a number"))?;
+
+        Ok(Self {
+            name,
+            catalog_number,
+            classification: line1.chars().nth(7).unwrap_or('U'),
+            international_designator: tle_field(line1, 10, 17).to_string(),
+            epoch,
+            mean_motion_dot: parse_number(line1, 34, 43, "mean motion derivative")?,
+            mean_motion_ddot: parse_implied_exponent(line1, 45, 52, "mean motion second derivative")?,
+            bstar: parse_implied_exponent(line1, 54, 61, "B*")?,
+            element_set_number: tle_field(line1, 65, 68).parse().unwrap_or(0),
+            inclination_deg: parse_number(line2, 9, 16, "inclination")?,
+            raan_deg: parse_number(line2, 18, 25, "right ascension")?,
+            eccentricity,
+            argument_of_perigee_deg: parse_number(line2, 35, 42, "argument of perigee")?,
+            mean_anomaly_deg: parse_number(line2, 44, 51, "mean anomaly")?,
+            mean_motion_rev_day: parse_number(line2, 53, 63, "mean motion")?,
+            revolution_number: tle_field(line2, 64, 68).parse().unwrap_or(0),
+        })
+    }
+
+    pub fn orbital_period_minutes(&self) -> f64 {
+        1440.0 / self.mean_motion_rev_day
+    }
+}
+
+/// Julian date (UT1 ≈ UTC) of an instant
+pub fn julian_date(time: DateTime<Utc>) -> f64 {
+    time.timestamp() as f64 / 86_400.0 + f64::from(time.timestamp_subsec_nanos()) / 86_400e9 + 2_440_587.5
+}
+
+/// Greenwich mean sidereal time (IAU-82), radians
+pub fn greenwich_mean_sidereal_time(julian_date_ut1: f64) -> f64 {
+    let tut1 = (julian_date_ut1 - 2_451_545.0) / 36_525.0;
+    let seconds = -6.2e-6 * tut1.powi(3)
+        + 0.093104 * tut1 * tut1
+        + (876_600.0 * 3600.0 + 8_640_184.812866) * tut1
+        + 67_310.548_41;
+    (seconds.to_radians() / 240.0).rem_euclid(TAU)
+}
+
+/// Position and velocity in the true-equator, mean-equinox frame SGP4 works in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemeState {
+    pub position_km: Vector3<f64>,
+    pub velocity_km_s: Vector3<f64>,
+}
+
+impl TemeState {
+    /// Earth-fixed position, ignoring polar motion
+    pub fn to_ecef(&self, time: DateTime<Utc>) -> Vector3<f64> {
+        let gmst = greenwich_mean_sidereal_time(julian_date(time));
+        let (sin, cos) = gmst.sin_cos();
+        let r = self.position_km;
+        Vector3::new(cos * r.x + sin * r.y, -sin * r.x + cos * r.y, r.z)
+    }
+}
+
+/// Geodetic latitude and longitude in degrees and height in km
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Geodetic {
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+    pub height_km: f64,
+}
+
+impl Geodetic {
+    pub fn to_ecef(&self) -> Vector3<f64> {
+        let (lat, lon) = (self.latitude_deg.to_radians(), self.longitude_deg.to_radians());
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let n = WGS84_A_KM / (1.0 - e2 * lat.sin().powi(2)).sqrt();
+        Vector3::new(
+            (n + self.height_km) * lat.cos() * lon.cos(),
+            (n + self.height_km) * lat.cos() * lon.sin(),
+            (n * (1.0 - e2) + self.height_km) * lat.sin(),
+        )
+    }
+
+    /// Inverse of `to_ecef` by fixed-point iteration on latitude
+    pub fn from_ecef(ecef: &Vector3<f64>) -> Self {
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let p = (ecef.x * ecef.x + ecef.y * ecef.y).sqrt();
+        let mut lat = ecef.z.atan2(p * (1.0 - e2));
+        let mut height = 0.0;
+        for _ in 0..10 {
+            let n = WGS84_A_KM / (1.0 - e2 * lat.sin().powi(2)).sqrt();
+            height = if lat.cos().abs() > 1e-10 { p / lat.cos() - n } else { ecef.z.abs() - n * (1.0 - e2) };
+            let next = ecef.z.atan2(p * (1.0 - e2 * n / (n + height)));
+            let converged = (next - lat).abs() < 1e-12;
+            lat = next;
+            if converged {
+                break;
+            }
+        }
+        Self { latitude_deg: lat.to_degrees(), longitude_deg: ecef.y.atan2(ecef.x).to_degrees(), height_km: height }
+    }
+}
+
+/// Why SGP4 could not produce a state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropagationError {
+    /// Mean eccentricity left [0, 1)
+    MeanEccentricity,
+    /// Mean motion became negative
+    MeanMotion,
+    /// Perturbed eccentricity left [0, 1]
+    PerturbedEccentricity,
+    /// Semi-latus rectum became negative
+    SemiLatusRectum,
+    /// Satellite is below the Earth's surface
+    Decayed,
+}
+
+impl From<PropagationError> for AppError {
+    fn from(error: PropagationError) -> Self {
+        AppError::validation(format!("SGP4 propagation failed: {:?}", error))
+    }
+}
+
+/// Lunar-solar coefficients from `dscom` kept for `dpper`
+#[derive(Debug, Clone, Default)]
+struct LunarSolarPeriodics {
+    e3: f64, ee2: f64, se2: f64, se3: f64, sgh2: f64, sgh3: f64, sgh4: f64, sh2: f64, sh3: f64,
+    si2: f64, si3: f64, sl2: f64, sl3: f64, sl4: f64, xgh2: f64, xgh3: f64, xgh4: f64, xh2: f64,
+    xh3: f64, xi2: f64, xi3: f64, xl2: f64, xl3: f64, xl4: f64, zmol: f64, zmos: f64,
+}
+
+impl LunarSolarPeriodics {
+    /// Long-period lunar-solar perturbations (`dpper`) applied `t` minutes after epoch
+    fn apply(&self, t: f64, ep: &mut f64, inclp: &mut f64, nodep: &mut f64, argpp: &mut f64, mp: &mut f64) {
+        const ZNS: f64 = 1.19459e-5;
+        const ZES: f64 = 0.01675;
+        const ZNL: f64 = 1.5835218e-4;
+        const ZEL: f64 = 0.05490;
+
+        let zm = self.zmos + ZNS * t;
+        let zf = zm + 2.0 * ZES * zm.sin();
+        let sinzf = zf.sin();
+        let f2 = 0.5 * sinzf * sinzf - 0.25;
+        let f3 = -0.5 * sinzf * zf.cos();
+        let ses = self.se2 * f2 + self.se3 * f3;
+        let sis = self.si2 * f2 + self.si3 * f3;
+        let sls = self.sl2 * f2 + self.sl3 * f3 + self.sl4 * sinzf;
+        let sghs = self.sgh2 * f2 + self.sgh3 * f3 + self.sgh4 * sinzf;
+        let shs = self.sh2 * f2 + self.sh3 * f3;
+
+        let zm = self.zmol + ZNL * t;
+        let zf = zm + 2.0 * ZEL * zm.sin();
+        let sinzf = zf.sin();
+        let f2 = 0.5 * sinzf * sinzf - 0.25;
+        let f3 = -0.5 * sinzf * zf.cos();
+        let sel = self.ee2 * f2 + self.e3 * f3;
+        let sil = self.xi2 * f2 + self.xi3 * f3;
+        let sll = self.xl2 * f2 + self.xl3 * f3 + self.xl4 * sinzf;
+        let sghl = self.xgh2 * f2 + self.xgh3 * f3 + self.xgh4 * sinzf;
+        let shll = self.xh2 * f2 + self.xh3 * f3;
+
+        let pe = ses + sel;
+        let pinc = sis + sil;
+        let pl = sls + sll;
+        let mut pgh = sghs + sghl;
+        let mut ph = shs + shll;
+
+        *inclp += pinc;
+        *ep += pe;
+        let (sinip, cosip) = inclp.sin_cos();
+        if *inclp >= 0.2 {
+            ph /= sinip;
+            pgh -= cosip * ph;
+            *argpp += pgh;
+            *nodep += ph;
+            *mp += pl;
+        } else {
+            // Lyddane modification for low inclinations
+            let (sinop, cosop) = nodep.sin_cos();
+            let alfdp = sinip * sinop + ph * cosop + pinc * cosip * sinop;
+            let betdp = sinip * cosop - ph * sinop + pinc * cosip * cosop;
+            *nodep %= TAU;
+            let xls = *mp + *argpp + cosip * *nodep + pl + pgh - pinc * *nodep * sinip;
+            let xnoh = *nodep;
+            *nodep = alfdp.atan2(betdp);
+            if (xnoh - *nodep).abs() > PI {
+                if *nodep < xnoh {
+                    *nodep += TAU;
+                } else {
+                    *nodep -= TAU;
+                }
+            }
+            *mp += pl;
+            *argpp = xls - *mp - cosip * *nodep;
+        }
+    }
+}
+
+/// Geopotential resonance state of 12-hour and 24-hour orbits (`dsinit`/`dspace`)
+#[derive(Debug, Clone, Default)]
+struct Resonance {
+    /// 1 for synchronous, 2 for half-day orbits
+    irez: u8,
+    d2201: f64, d2211: f64, d3210: f64, d3222: f64, d4410: f64, d4422: f64,
+    d5220: f64, d5232: f64, d5421: f64, d5433: f64,
+    del1: f64, del2: f64, del3: f64,
+    xfact: f64,
+    xlamo: f64,
+}
+
+/// Secular lunar-solar rates and resonance terms of a deep-space orbit
+#[derive(Debug, Clone, Default)]
+struct DeepSpace {
+    periodics: LunarSolarPeriodics,
+    resonance: Resonance,
+    dedt: f64,
+    didt: f64,
+    dmdt: f64,
+    dnodt: f64,
+    domdt: f64,
+}
+
+/// SGP4 for near-Earth orbits and SDP4 for periods of 225 minutes or more,
+/// after Vallado et al. (2006), "Revisiting Spacetrack Report #3"
+#[derive(Debug, Clone)]
+pub struct Sgp4Propagator {
+    pub elements: TwoLineElements,
+    gsto: f64,
+    // Mean elements at epoch in radians and radians/minute
+    ecco: f64,
+    inclo: f64,
+    nodeo: f64,
+    argpo: f64,
+    mo: f64,
+    no_unkozai: f64,
+    bstar: f64,
+    // Secular and drag coefficients
+    isimp: bool,
+    aycof: f64,
+    con41: f64,
+    cc1: f64,
+    cc4: f64,
+    cc5: f64,
+    d2: f64,
+    d3: f64,
+    d4: f64,
+    delmo: f64,
+    eta: f64,
+    argpdot: f64,
+    omgcof: f64,
+    sinmao: f64,
+    t2cof: f64,
+    t3cof: f64,
+    t4cof: f64,
+    t5cof: f64,
+    x1mth2: f64,
+    x7thm1: f64,
+    mdot: f64,
+    nodedot: f64,
+    xlcof: f64,
+    xmcof: f64,
+    nodecf: f64,
+    deep_space: Option<DeepSpace>,
+}
+
+impl Sgp4Propagator {
+    pub fn new(elements: TwoLineElements) -> Result<Self, AppError> {
+        let xke = xke();
+        let deg = PI / 180.0;
+        let ecco = elements.eccentricity;
+        let inclo = elements.inclination_deg * deg;
+        let nodeo = elements.raan_deg * deg;
+        let argpo = elements.argument_of_perigee_deg * deg;
+        let mo = elements.mean_anomaly_deg * deg;
+        let no_kozai = elements.mean_motion_rev_day * TAU / 1440.0;
+        let bstar = elements.bstar;
+        if !(0.0..1.0).contains(&ecco) || no_kozai <= 0.0 {
+            return Err(AppError::validation(format!(
+                "TLE {} has eccentricity {} and mean motion {} rev/day",
+                elements.catalog_number, ecco, elements.mean_motion_rev_day
+            )));
+        }
+        let epoch_julian = julian_date(elements.epoch);
+        // Days since 1949 December 31 00:00 UT
+        let epoch = epoch_julian - 2_433_281.5;
+
+        // initl: recover the Brouwer mean motion from the Kozai one
+        let eccsq = ecco * ecco;
+        let omeosq = 1.0 - eccsq;
+        let rteosq = omeosq.sqrt();
+        let (sinio, cosio) = inclo.sin_cos();
+        let cosio2 = cosio * cosio;
+        let ak = (xke / no_kozai).powf(X2O3);
+        let d1 = 0.75 * J2 * (3.0 * cosio2 - 1.0) / (rteosq * omeosq);
+        let del = d1 / (ak * ak);
+        let adel = ak * (1.0 - del * del - del * (1.0 / 3.0 + 134.0 * del * del / 81.0));
+        let del = d1 / (adel * adel);
+        let no_unkozai = no_kozai / (1.0 + del);
+        let ao = (xke / no_unkozai).powf(X2O3);
+        let po = ao * omeosq;
+        let con42 = 1.0 - 5.0 * cosio2;
+        let con41 = -con42 - cosio2 - cosio2;
+        let posq = po * po;
+        let rp = ao * (1.0 - ecco);
+        let gsto = greenwich_mean_sidereal_time(epoch + 2_433_281.5);
+
+        // sgp4init
+        let ss = 78.0 / EARTH_RADIUS_KM + 1.0;
+        let qzms2t = ((120.0 - 78.0) / EARTH_RADIUS_KM).powi(4);
+        let temp4 = 1.5e-12;
+        let mut isimp = rp < 220.0 / EARTH_RADIUS_KM + 1.0;
+        let mut sfour = ss;
+        let mut qzms24 = qzms2t;
+        let perige = (rp - 1.0) * EARTH_RADIUS_KM;
+        if perige < 156.0 {
+            sfour = if perige < 98.0 { 20.0 } else { perige - 78.0 };
+            qzms24 = ((120.0 - sfour) / EARTH_RADIUS_KM).powi(4);
+            sfour = sfour / EARTH_RADIUS_KM + 1.0;
+        }
+        let pinvsq = 1.0 / posq;
+        let tsi = 1.0 / (ao - sfour);
+        let eta = ao * ecco * tsi;
+        let etasq = eta * eta;
+        let eeta = ecco * eta;
+        let psisq = (1.0 - etasq).abs();
+        let coef = qzms24 * tsi.powi(4);
+        let coef1 = coef / psisq.powf(3.5);
+        let cc2 = coef1
+            * no_unkozai
+            * (ao * (1.0 + 1.5 * etasq + eeta * (4.0 + etasq))
+                + 0.375 * J2 * tsi / psisq * con41 * (8.0 + 3.0 * etasq * (8.0 + etasq)));
+        let cc1 = bstar * cc2;
+        let cc3 = if ecco > 1.0e-4 { -2.0 * coef * tsi * J3OJ2 * no_unkozai * sinio / ecco } else { 0.0 };
+        let x1mth2 = 1.0 - cosio2;
+        let cc4 = 2.0
+            * no_unkozai
+            * coef1
+            * ao
+            * omeosq
+            * (eta * (2.0 + 0.5 * etasq) + ecco * (0.5 + 2.0 * etasq)
+                - J2 * tsi / (ao * psisq)
+                    * (-3.0 * con41 * (1.0 - 2.0 * eeta + etasq * (1.5 - 0.5 * eeta))
+                        + 0.75 * x1mth2 * (2.0 * etasq - eeta * (1.0 + etasq)) * (2.0 * argpo).cos()));
+        let cc5 = 2.0 * coef1 * ao * omeosq * (1.0 + 2.75 * (etasq + eeta) + eeta * etasq);
+        let cosio4 = cosio2 * cosio2;
+        let temp1 = 1.5 * J2 * pinvsq * no_unkozai;
+        let temp2 = 0.5 * temp1 * J2 * pinvsq;
+        let temp3 = -0.46875 * J4 * pinvsq * pinvsq * no_unkozai;
+        let mdot = no_unkozai
+            + 0.5 * temp1 * rteosq * con41
+            + 0.0625 * temp2 * rteosq * (13.0 - 78.0 * cosio2 + 137.0 * cosio4);
+        let argpdot = -0.5 * temp1 * con42
+            + 0.0625 * temp2 * (7.0 - 114.0 * cosio2 + 395.0 * cosio4)
+            + temp3 * (3.0 - 36.0 * cosio2 + 49.0 * cosio4);
+        let xhdot1 = -temp1 * cosio;
+        let nodedot = xhdot1 + (0.5 * temp2 * (4.0 - 19.0 * cosio2) + 2.0 * temp3 * (3.0 - 7.0 * cosio2)) * cosio;
+        let xpidot = argpdot + nodedot;
+        let omgcof = bstar * cc3 * argpo.cos();
+        let xmcof = if ecco > 1.0e-4 { -X2O3 * coef * bstar / eeta } else { 0.0 };
+        let nodecf = 3.5 * omeosq * xhdot1 * cc1;
+        let t2cof = 1.5 * cc1;
+        let xlcof = if (cosio + 1.0).abs() > 1.5e-12 {
+            -0.25 * J3OJ2 * sinio * (3.0 + 5.0 * cosio) / (1.0 + cosio)
+        } else {
+            -0.25 * J3OJ2 * sinio * (3.0 + 5.0 * cosio) / temp4
+        };
+        let aycof = -0.5 * J3OJ2 * sinio;
+        let delmo = (1.0 + eta * mo.cos()).powi(3);
+        let sinmao = mo.sin();
+        let x7thm1 = 7.0 * cosio2 - 1.0;
+
+        let deep_space = if TAU / no_unkozai >= 225.0 {
+            isimp = true;
+            Some(Self::deep_space_init(
+                epoch, ecco, inclo, nodeo, argpo, mo, no_unkozai, gsto, mdot, nodedot, xpidot,
+            ))
+        } else {
+            None
+        };
+
+        let (mut d2, mut d3, mut d4, mut t3cof, mut t4cof, mut t5cof) = (0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        if !isimp {
+            let cc1sq = cc1 * cc1;
+            d2 = 4.0 * ao * tsi * cc1sq;
+            let temp = d2 * tsi * cc1 / 3.0;
+            d3 = (17.0 * ao + sfour) * temp;
+            d4 = 0.5 * temp * ao * tsi * (221.0 * ao + 31.0 * sfour) * cc1;
+            t3cof = d2 + 2.0 * cc1sq;
+            t4cof = 0.25 * (3.0 * d3 + cc1 * (12.0 * d2 + 10.0 * cc1sq));
+            t5cof = 0.2 * (3.0 * d4 + 12.0 * cc1 * d3 + 6.0 * d2 * d2 + 15.0 * cc1sq * (2.0 * d2 + cc1sq));
+        }
+
+        Ok(Self {
+            elements,
+            gsto,
+            ecco,
+            inclo,
+            nodeo,
+            argpo,
+            mo,
+            no_unkozai,
+            bstar,
+            isimp,
+            aycof,
+            con41,
+            cc1,
+            cc4,
+            cc5,
+            d2,
+            d3,
+            d4,
+            delmo,
+            eta,
+            argpdot,
+            omgcof,
+            sinmao,
+            t2cof,
+            t3cof,
+            t4cof,
+            t5cof,
+            x1mth2,
+            x7thm1,
+            mdot,
+            nodedot,
+            xlcof,
+            xmcof,
+            nodecf,
+            deep_space,
+        })
+    }
+
+    pub fn from_tle(text: &str) -> Result<Self, AppError> {
+        Self::new(TwoLineElements::parse(text)?)
+    }
+
+    pub fn is_deep_space(&self) -> bool {
+        self.deep_space.is_some()
+    }
+
+    /// `dscom` and `dsinit`: lunar-solar and resonance terms at epoch
+    #[allow(clippy::too_many_arguments)]
+    fn deep_space_init(
+        epoch: f64,
+        ecco: f64,
+        inclo: f64,
+        nodeo: f64,
+        argpo: f64,
+        mo: f64,
+        no_unkozai: f64,
+        gsto: f64,
+        mdot: f64,
+        nodedot: f64,
+        xpidot: f64,
+    ) -> DeepSpace {
+        const ZES: f64 = 0.01675;
+        const ZEL: f64 = 0.05490;
+        const C1SS: f64 = 2.9864797e-6;
+        const C1L: f64 = 4.7968065e-7;
+        const ZSINIS: f64 = 0.39785416;
+        const ZCOSIS: f64 = 0.91744867;
+        const ZCOSGS: f64 = 0.1945905;
+        const ZSINGS: f64 = -0.98088458;
+
+        // dscom
+        let nm = no_unkozai;
+        let em = ecco;
+        let (snodm, cnodm) = nodeo.sin_cos();
+        let (sinomm, cosomm) = argpo.sin_cos();
+        let (sinim, cosim) = inclo.sin_cos();
+        let emsq = em * em;
+        let betasq = 1.0 - emsq;
+        let rtemsq = betasq.sqrt();
+
+        let day = epoch + 18_261.5;
+        let xnodce = (4.5236020 - 9.2422029e-4 * day) % TAU;
+        let (stem, ctem) = xnodce.sin_cos();
+        let zcosil = 0.91375164 - 0.03568096 * ctem;
+        let zsinil = (1.0 - zcosil * zcosil).sqrt();
+        let zsinhl = 0.089683511 * stem / zsinil;
+        let zcoshl = (1.0 - zsinhl * zsinhl).sqrt();
+        let gam = 5.8351514 + 0.0019443680 * day;
+        let zx = 0.39785416 * stem / zsinil;
+        let zy = zcoshl * ctem + 0.91744867 * zsinhl * stem;
+        let zx = gam + zx.atan2(zy) - xnodce;
+        let (zsingl, zcosgl) = zx.sin_cos();
+
+        // Solar terms first, then lunar
+        let (mut zcosg, mut zsing, mut zcosi, mut zsini, mut zcosh, mut zsinh) = (ZCOSGS, ZSINGS, ZCOSIS, ZSINIS, cnodm, snodm);
+        let mut cc = C1SS;
+        let xnoi = 1.0 / nm;
+        let mut solar = [0.0; 19];
+        let mut lunar = [0.0; 19];
+        for body in 0..2 {
+            let a1 = zcosg * zcosh + zsing * zcosi * zsinh;
+            let a3 = -zsing * zcosh + zcosg * zcosi * zsinh;
+            let a7 = -zcosg * zsinh + zsing * zcosi * zcosh;
+            let a8 = zsing * zsini;
+            let a9 = zsing * zsinh + zcosg * zcosi * zcosh;
+            let a10 = zcosg * zsini;
+            let a2 = cosim * a7 + sinim * a8;
+            let a4 = cosim * a9 + sinim * a10;
+            let a5 = -sinim * a7 + cosim * a8;
+            let a6 = -sinim * a9 + cosim * a10;
+
+            let x1 = a1 * cosomm + a2 * sinomm;
+            let x2 = a3 * cosomm + a4 * sinomm;
+            let x3 = -a1 * sinomm + a2 * cosomm;
+            let x4 = -a3 * sinomm + a4 * cosomm;
+            let x5 = a5 * sinomm;
+            let x6 = a6 * sinomm;
+            let x7 = a5 * cosomm;
+            let x8 = a6 * cosomm;
+
+            let z31 = 12.0 * x1 * x1 - 3.0 * x3 * x3;
+            let z32 = 24.0 * x1 * x2 - 6.0 * x3 * x4;
+            let z33 = 12.0 * x2 * x2 - 3.0 * x4 * x4;
+            let mut z1 = 3.0 * (a1 * a1 + a2 * a2) + z31 * emsq;
+            let mut z2 = 6.0 * (a1 * a3 + a2 * a4) + z32 * emsq;
+            let mut z3 = 3.0 * (a3 * a3 + a4 * a4) + z33 * emsq;
+            let z11 = -6.0 * a1 * a5 + emsq * (-24.0 * x1 * x7 - 6.0 * x3 * x5);
+            let z12 = -6.0 * (a1 * a6 + a3 * a5) + emsq * (-24.0 * (x2 * x7 + x1 * x8) - 6.0 * (x3 * x6 + x4 * x5));
+            let z13 = -6.0 * a3 * a6 + emsq * (-24.0 * x2 * x8 - 6.0 * x4 * x6);
+            let z21 = 6.0 * a2 * a5 + emsq * (24.0 * x1 * x5 - 6.0 * x3 * x7);
+            let z22 = 6.0 * (a4 * a5 + a2 * a6) + emsq * (24.0 * (x2 * x5 + x1 * x6) - 6.0 * (x4 * x7 + x3 * x8));
+            let z23 = 6.0 * a4 * a6 + emsq * (24.0 * x2 * x6 - 6.0 * x4 * x8);
+            z1 = z1 + z1 + betasq * z31;
+            z2 = z2 + z2 + betasq * z32;
+            z3 = z3 + z3 + betasq * z33;
+            let s3 = cc * xnoi;
+            let s2 = -0.5 * s3 / rtemsq;
+            let s4 = s3 * rtemsq;
+            let s1 = -15.0 * em * s4;
+            let s5 = x1 * x3 + x2 * x4;
+            let s6 = x2 * x3 + x1 * x4;
+            let s7 = x2 * x4 - x1 * x3;
+
+            let terms = [s1, s2, s3, s4, s5, s6, s7, z1, z2, z3, z11, z12, z13, z21, z22, z23, z31, z32, z33];
+            if body == 0 {
+                solar = terms;
+                zcosg = zcosgl;
+                zsing = zsingl;
+                zcosi = zcosil;
+                zsini = zsinil;
+                zcosh = zcoshl * cnodm + zsinhl * snodm;
+                zsinh = snodm * zcoshl - cnodm * zsinhl;
+                cc = C1L;
+            } else {
+                lunar = terms;
+            }
+        }
+        let [ss1, ss2, ss3, ss4, ss5, ss6, ss7, sz1, sz2, sz3, sz11, sz12, sz13, sz21, sz22, sz23, sz31, sz32, sz33] = solar;
+        let [s1, s2, s3, s4, s5, s6, s7, z1, z2, z3, z11, z12, z13, z21, z22, z23, z31, z32, z33] = lunar;
+
+        let periodics = LunarSolarPeriodics {
+            zmol: (4.7199672 + 0.22997150 * day - gam) % TAU,
+            zmos: (6.2565837 + 0.017201977 * day) % TAU,
+            se2: 2.0 * ss1 * ss6,
+            se3: 2.0 * ss1 * ss7,
+            si2: 2.0 * ss2 * sz12,
+            si3: 2.0 * ss2 * (sz13 - sz11),
+            sl2: -2.0 * ss3 * sz2,
+            sl3: -2.0 * ss3 * (sz3 - sz1),
+            sl4: -2.0 * ss3 * (-21.0 - 9.0 * emsq) * ZES,
+            sgh2: 2.0 * ss4 * sz32,
+            sgh3: 2.0 * ss4 * (sz33 - sz31),
+            sgh4: -18.0 * ss4 * ZES,
+            sh2: -2.0 * ss2 * sz22,
+            sh3: -2.0 * ss2 * (sz23 - sz21),
+            ee2: 2.0 * s1 * s6,
+            e3: 2.0 * s1 * s7,
+            xi2: 2.0 * s2 * z12,
+            xi3: 2.0 * s2 * (z13 - z11),
+            xl2: -2.0 * s3 * z2,
+            xl3: -2.0 * s3 * (z3 - z1),
+            xl4: -2.0 * s3 * (-21.0 - 9.0 * emsq) * ZEL,
+            xgh2: 2.0 * s4 * z32,
+            xgh3: 2.0 * s4 * (z33 - z31),
+            xgh4: -18.0 * s4 * ZEL,
+            xh2: -2.0 * s2 * z22,
+            xh3: -2.0 * s2 * (z23 - z21),
+        };
+
+        // dsinit
+        const Q22: f64 = 1.7891679e-6;
+        const Q31: f64 = 2.1460748e-6;
+        const Q33: f64 = 2.2123015e-7;
+        const ROOT22: f64 = 1.7891679e-6;
+        const ROOT44: f64 = 7.3636953e-9;
+        const ROOT54: f64 = 2.1765803e-9;
+        const ROOT32: f64 = 3.7393792e-7;
+        const ROOT52: f64 = 1.1428639e-7;
+        const ZNL: f64 = 1.5835218e-4;
+        const ZNS: f64 = 1.19459e-5;
+
+        let irez = if nm > 0.0034906585 && nm < 0.0052359877 {
+            1
+        } else if (8.26e-3..=9.24e-3).contains(&nm) && em >= 0.5 {
+            2
+        } else {
+            0
+        };
+
+        let ses = ss1 * ZNS * ss5;
+        let sis = ss2 * ZNS * (sz11 + sz13);
+        let sls = -ZNS * ss3 * (sz1 + sz3 - 14.0 - 6.0 * emsq);
+        let sghs = ss4 * ZNS * (sz31 + sz33 - 6.0);
+        let equatorial = !(5.2359877e-2..=PI - 5.2359877e-2).contains(&inclo);
+        let mut shs = if equatorial { 0.0 } else { -ZNS * ss2 * (sz21 + sz23) };
+        if sinim != 0.0 {
+            shs /= sinim;
+        }
+        let sgs = sghs - cosim * shs;
+
+        let dedt = ses + s1 * ZNL * s5;
+        let didt = sis + s2 * ZNL * (z11 + z13);
+        let dmdt = sls - ZNL * s3 * (z1 + z3 - 14.0 - 6.0 * emsq);
+        let sghl = s4 * ZNL * (z31 + z33 - 6.0);
+        let shll = if equatorial { 0.0 } else { -ZNL * s2 * (z21 + z23) };
+        let mut domdt = sgs + sghl;
+        let mut dnodt = shs;
+        if sinim != 0.0 {
+            domdt -= cosim / sinim * shll;
+            dnodt += shll / sinim;
+        }
+
+        let mut resonance = Resonance { irez, ..Default::default() };
+        if irez != 0 {
+            let aonv = (nm / xke()).powf(X2O3);
+            let theta = gsto % TAU;
+            if irez == 2 {
+                let cosisq = cosim * cosim;
+                let eoc = em * emsq;
+                let g201 = -0.306 - (em - 0.64) * 0.440;
+                let (g211, g310, g322, g410, g422, g520) = if em <= 0.65 {
+                    (
+                        3.616 - 13.2470 * em + 16.2900 * emsq,
+                        -19.302 + 117.3900 * em - 228.4190 * emsq + 156.5910 * eoc,
+                        -18.9068 + 109.7927 * em - 214.6334 * emsq + 146.5816 * eoc,
+                        -41.122 + 242.6940 * em - 471.0940 * emsq + 313.9530 * eoc,
+                        -146.407 + 841.8800 * em - 1629.014 * emsq + 1083.4350 * eoc,
+                        -532.114 + 3017.977 * em - 5740.032 * emsq + 3708.2760 * eoc,
+                    )
+                } else {
+                    (
+                        -72.099 + 331.819 * em - 508.738 * emsq + 266.724 * eoc,
+                        -346.844 + 1582.851 * em - 2415.925 * emsq + 1246.113 * eoc,
+                        -342.585 + 1554.908 * em - 2366.899 * emsq + 1215.972 * eoc,
+                        -1052.797 + 4758.686 * em - 7193.992 * emsq + 3651.957 * eoc,
+                        -3581.690 + 16178.110 * em - 24462.770 * emsq + 12422.520 * eoc,
+                        if em > 0.715 {
+                            -5149.66 + 29936.92 * em - 54087.36 * emsq + 31324.56 * eoc
+                        } else {
+                            1464.74 - 4664.75 * em + 3763.64 * emsq
+                        },
+                    )
+                };
+                let (g533, g521, g532) = if em < 0.7 {
+                    (
+                        -919.22770 + 4988.6100 * em - 9064.7700 * emsq + 5542.21 * eoc,
+                        -822.71072 + 4568.6173 * em - 8491.4146 * emsq + 5337.524 * eoc,
+                        -853.66600 + 4690.2500 * em - 8624.7700 * emsq + 5341.4 * eoc,
+                    )
+                } else {
+                    (
+                        -37995.780 + 161616.52 * em - 229838.20 * emsq + 109377.94 * eoc,
+                        -51752.104 + 218913.95 * em - 309468.16 * emsq + 146349.42 * eoc,
+                        -40023.880 + 170470.89 * em - 242699.48 * emsq + 115605.82 * eoc,
+                    )
+                };
+                let sini2 = sinim * sinim;
+                let f220 = 0.75 * (1.0 + 2.0 * cosim + cosisq);
+                let f221 = 1.5 * sini2;
+                let f321 = 1.875 * sinim * (1.0 - 2.0 * cosim - 3.0 * cosisq);
+                let f322 = -1.875 * sinim * (1.0 + 2.0 * cosim - 3.0 * cosisq);
+                let f441 = 35.0 * sini2 * f220;
+                let f442 = 39.3750 * sini2 * sini2;
+                let f522 = 9.84375
+                    * sinim
+                    * (sini2 * (1.0 - 2.0 * cosim - 5.0 * cosisq) + 0.33333333 * (-2.0 + 4.0 * cosim + 6.0 * cosisq));
+                let f523 = sinim
+                    * (4.92187512 * sini2 * (-2.0 - 4.0 * cosim + 10.0 * cosisq)
+                        + 6.56250012 * (1.0 + 2.0 * cosim - 3.0 * cosisq));
+                let f542 = 29.53125 * sinim * (2.0 - 8.0 * cosim + cosisq * (-12.0 + 8.0 * cosim + 10.0 * cosisq));
+                let f543 = 29.53125 * sinim * (-2.0 - 8.0 * cosim + cosisq * (12.0 + 8.0 * cosim - 10.0 * cosisq));
+
+                let xno2 = nm * nm;
+                let ainv2 = aonv * aonv;
+                let mut temp1 = 3.0 * xno2 * ainv2;
+                let mut temp = temp1 * ROOT22;
+                resonance.d2201 = temp * f220 * g201;
+                resonance.d2211 = temp * f221 * g211;
+                temp1 *= aonv;
+                temp = temp1 * ROOT32;
+                resonance.d3210 = temp * f321 * g310;
+                resonance.d3222 = temp * f322 * g322;
+                temp1 *= aonv;
+                temp = 2.0 * temp1 * ROOT44;
+                resonance.d4410 = temp * f441 * g410;
+                resonance.d4422 = temp * f442 * g422;
+                temp1 *= aonv;
+                temp = temp1 * ROOT52;
+                resonance.d5220 = temp * f522 * g520;
+                resonance.d5232 = temp * f523 * g532;
+                temp = 2.0 * temp1 * ROOT54;
+                resonance.d5421 = temp * f542 * g521;
+                resonance.d5433 = temp * f543 * g533;
+                resonance.xlamo = (mo + nodeo + nodeo - theta - theta) % TAU;
+                resonance.xfact = mdot + dmdt + 2.0 * (nodedot + dnodt - EARTH_ROTATION_RAD_MIN) - no_unkozai;
+            } else {
+                let g200 = 1.0 + emsq * (-2.5 + 0.8125 * emsq);
+                let g310 = 1.0 + 2.0 * emsq;
+                let g300 = 1.0 + emsq * (-6.0 + 6.60937 * emsq);
+                let f220 = 0.75 * (1.0 + cosim) * (1.0 + cosim);
+                let f311 = 0.9375 * sinim * sinim * (1.0 + 3.0 * cosim) - 0.75 * (1.0 + cosim);
+                let f330 = 1.875 * (1.0 + cosim).powi(3);
+                let del1 = 3.0 * nm * nm * aonv * aonv;
+                resonance.del2 = 2.0 * del1 * f220 * g200 * Q22;
+                resonance.del3 = 3.0 * del1 * f330 * g300 * Q33 * aonv;
+                resonance.del1 = del1 * f311 * g310 * Q31 * aonv;
+                resonance.xlamo = (mo + nodeo + argpo - theta) % TAU;
+                resonance.xfact = mdot + xpidot - EARTH_ROTATION_RAD_MIN + dmdt + domdt + dnodt - no_unkozai;
+            }
+        }
+
+        DeepSpace { periodics, resonance, dedt, didt, dmdt, dnodt, domdt }
+    }
+
+    /// `dspace`: secular lunar-solar drift and numerically integrated resonance
+    #[allow(clippy::too_many_arguments)]
+    fn deep_space_secular(
+        &self,
+        deep: &DeepSpace,
+        t: f64,
+        em: &mut f64,
+        argpm: &mut f64,
+        inclm: &mut f64,
+        mm: &mut f64,
+        nodem: &mut f64,
+        nm: &mut f64,
+    ) {
+        const FASX2: f64 = 0.13130908;
+        const FASX4: f64 = 2.8843198;
+        const FASX6: f64 = 0.37448087;
+        const G22: f64 = 5.7686396;
+        const G32: f64 = 0.95240898;
+        const G44: f64 = 1.8014998;
+        const G52: f64 = 1.0508330;
+        const G54: f64 = 4.4108898;
+        const STEP: f64 = 720.0;
+        const STEP2: f64 = 259_200.0;
+
+        let theta = (self.gsto + t * EARTH_ROTATION_RAD_MIN) % TAU;
+        *em += deep.dedt * t;
+        *inclm += deep.didt * t;
+        *argpm += deep.domdt * t;
+        *nodem += deep.dnodt * t;
+        *mm += deep.dmdt * t;
+
+        let r = &deep.resonance;
+        if r.irez == 0 {
+            return;
+        }
+
+        // Euler-Maclaurin steps of half a day from epoch towards t
+        let delt = if t > 0.0 { STEP } else { -STEP };
+        let (mut atime, mut xni, mut xli) = (0.0, self.no_unkozai, r.xlamo);
+        let (xndt, xldot, xnddt) = loop {
+            let xldot = xni + r.xfact;
+            let (xndt, xnddt) = if r.irez != 2 {
+                (
+                    r.del1 * (xli - FASX2).sin() + r.del2 * (2.0 * (xli - FASX4)).sin() + r.del3 * (3.0 * (xli - FASX6)).sin(),
+                    r.del1 * (xli - FASX2).cos()
+                        + 2.0 * r.del2 * (2.0 * (xli - FASX4)).cos()
+                        + 3.0 * r.del3 * (3.0 * (xli - FASX6)).cos(),
+                )
+            } else {
+                let xomi = self.argpo + self.argpdot * atime;
+                let x2omi = xomi + xomi;
+                let x2li = xli + xli;
+                (
+                    r.d2201 * (x2omi + xli - G22).sin()
+                        + r.d2211 * (xli - G22).sin()
+                        + r.d3210 * (xomi + xli - G32).sin()
+                        + r.d3222 * (-xomi + xli - G32).sin()
+                        + r.d4410 * (x2omi + x2li - G44).sin()
+                        + r.d4422 * (x2li - G44).sin()
+                        + r.d5220 * (xomi + xli - G52).sin()
+                        + r.d5232 * (-xomi + xli - G52).sin()
+                        + r.d5421 * (xomi + x2li - G54).sin()
+                        + r.d5433 * (-xomi + x2li - G54).sin(),
+                    r.d2201 * (x2omi + xli - G22).cos()
+                        + r.d2211 * (xli - G22).cos()
+                        + r.d3210 * (xomi + xli - G32).cos()
+                        + r.d3222 * (-xomi + xli - G32).cos()
+                        + r.d5220 * (xomi + xli - G52).cos()
+                        + r.d5232 * (-xomi + xli - G52).cos()
+                        + 2.0
+                            * (r.d4410 * (x2omi + x2li - G44).cos()
+                                + r.d4422 * (x2li - G44).cos()
+                                + r.d5421 * (xomi + x2li - G54).cos()
+                                + r.d5433 * (-xomi + x2li - G54).cos()),
+                )
+            };
+            let xnddt = xnddt * xldot;
+            if (t - atime).abs() < STEP {
+                break (xndt, xldot, xnddt);
+            }
+            xli += xldot * delt + xndt * STEP2;
+            xni += xndt * delt + xnddt * STEP2;
+            atime += delt;
+        };
+
+        let ft = t - atime;
+        *nm = xni + xndt * ft + xnddt * ft * ft * 0.5;
+        let xl = xli + xldot * ft + xndt * ft * ft * 0.5;
+        *mm = if r.irez != 1 {
+            xl - 2.0 * *nodem + 2.0 * theta
+        } else {
+            xl - *nodem - *argpm + theta
+        };
+    }
+
+    /// State `tsince` minutes after the element epoch
+    pub fn propagate_minutes(&self, tsince: f64) -> Result<TemeState, PropagationError> {
+        let xke = xke();
+        let t = tsince;
+
+        // Secular gravity and atmospheric drag
+        let xmdf = self.mo + self.mdot * t;
+        let argpdf = self.argpo + self.argpdot * t;
+        let nodedf = self.nodeo + self.nodedot * t;
+        let mut argpm = argpdf;
+        let mut mm = xmdf;
+        let t2 = t * t;
+        let mut nodem = nodedf + self.nodecf * t2;
+        let mut tempa = 1.0 - self.cc1 * t;
+        let mut tempe = self.bstar * self.cc4 * t;
+        let mut templ = self.t2cof * t2;
+
+        if !self.isimp {
+            let delomg = self.omgcof * t;
+            let delm = self.xmcof * ((1.0 + self.eta * xmdf.cos()).powi(3) - self.delmo);
+            let temp = delomg + delm;
+            mm = xmdf + temp;
+            argpm = argpdf - temp;
+            let t3 = t2 * t;
+            let t4 = t3 * t;
+            tempa = tempa - self.d2 * t2 - self.d3 * t3 - self.d4 * t4;
+            tempe += self.bstar * self.cc5 * (mm.sin() - self.sinmao);
+            templ += self.t3cof * t3 + t4 * (self.t4cof + t * self.t5cof);
+        }
+
+        let mut nm = self.no_unkozai;
+        let mut em = self.ecco;
+        let mut inclm = self.inclo;
+        if let Some(deep) = &self.deep_space {
+            self.deep_space_secular(deep, t, &mut em, &mut argpm, &mut inclm, &mut mm, &mut nodem, &mut nm);
+        }
+
+        if nm <= 0.0 {
+            return Err(PropagationError::MeanMotion);
+        }
+        let am = (xke / nm).powf(X2O3) * tempa * tempa;
+        nm = xke / am.powf(1.5);
+        em -= tempe;
+        if !(-0.001..1.0).contains(&em) {
+            return Err(PropagationError::MeanEccentricity);
+        }
+        em = em.max(1.0e-6);
+        mm += self.no_unkozai * templ;
+        let xlm = mm + argpm + nodem;
+        // Truncating remainders, as in the reference implementation, keep the test vectors bit-compatible
+        nodem %= TAU;
+        argpm %= TAU;
+        let xlm = xlm % TAU;
+        mm = (xlm - argpm - nodem) % TAU;
+
+        // Lunar-solar periodics
+        let (mut ep, mut xincp, mut argpp, mut nodep, mut mp) = (em, inclm, argpm, nodem, mm);
+        let (mut sinip, mut cosip) = inclm.sin_cos();
+        let (mut aycof, mut xlcof, mut con41, mut x1mth2, mut x7thm1) =
+            (self.aycof, self.xlcof, self.con41, self.x1mth2, self.x7thm1);
+        if let Some(deep) = &self.deep_space {
+            deep.periodics.apply(t, &mut ep, &mut xincp, &mut nodep, &mut argpp, &mut mp);
+            if xincp < 0.0 {
+                xincp = -xincp;
+                nodep += PI;
+                argpp -= PI;
+            }
+            if !(0.0..=1.0).contains(&ep) {
+                return Err(PropagationError::PerturbedEccentricity);
+            }
+            (sinip, cosip) = xincp.sin_cos();
+            aycof = -0.5 * J3OJ2 * sinip;
+            xlcof = if (cosip + 1.0).abs() > 1.5e-12 {
+                -0.25 * J3OJ2 * sinip * (3.0 + 5.0 * cosip) / (1.0 + cosip)
+            } else {
+                -0.25 * J3OJ2 * sinip * (3.0 + 5.0 * cosip) / 1.5e-12
+            };
+            let cosisq = cosip * cosip;
+            con41 = 3.0 * cosisq - 1.0;
+            x1mth2 = 1.0 - cosisq;
+            x7thm1 = 7.0 * cosisq - 1.0;
+        }
+
+        // Long-period periodics
+        let axnl = ep * argpp.cos();
+        let temp = 1.0 / (am * (1.0 - ep * ep));
+        let aynl = ep * argpp.sin() + temp * aycof;
+        let xl = mp + argpp + nodep + temp * xlcof * axnl;
+
+        // Kepler's equation in the equinoctial form
+        let u = (xl - nodep) % TAU;
+        let mut eo1 = u;
+        let (mut sineo1, mut coseo1) = (0.0, 0.0);
+        for _ in 0..10 {
+            (sineo1, coseo1) = eo1.sin_cos();
+            let mut tem5 = 1.0 - coseo1 * axnl - sineo1 * aynl;
+            tem5 = (u - aynl * coseo1 + axnl * sineo1 - eo1) / tem5;
+            if tem5.abs() >= 0.95 {
+                tem5 = 0.95 * tem5.signum();
+            }
+            eo1 += tem5;
+            if tem5.abs() < 1.0e-12 {
+                break;
+            }
+        }
+
+        // Short-period periodics
+        let ecose = axnl * coseo1 + aynl * sineo1;
+        let esine = axnl * sineo1 - aynl * coseo1;
+        let el2 = axnl * axnl + aynl * aynl;
+        let pl = am * (1.0 - el2);
+        if pl < 0.0 {
+            return Err(PropagationError::SemiLatusRectum);
+        }
+        let rl = am * (1.0 - ecose);
+        let rdotl = am.sqrt() * esine / rl;
+        let rvdotl = pl.sqrt() / rl;
+        let betal = (1.0 - el2).sqrt();
+        let temp = esine / (1.0 + betal);
+        let sinu = am / rl * (sineo1 - aynl - axnl * temp);
+        let cosu = am / rl * (coseo1 - axnl + aynl * temp);
+        let mut su = sinu.atan2(cosu);
+        let sin2u = (cosu + cosu) * sinu;
+        let cos2u = 1.0 - 2.0 * sinu * sinu;
+        let temp = 1.0 / pl;
+        let temp1 = 0.5 * J2 * temp;
+        let temp2 = temp1 * temp;
+
+        let mrt = rl * (1.0 - 1.5 * temp2 * betal * con41) + 0.5 * temp1 * x1mth2 * cos2u;
+        su -= 0.25 * temp2 * x7thm1 * sin2u;
+        let xnode = nodep + 1.5 * temp2 * cosip * sin2u;
+        let xinc = xincp + 1.5 * temp2 * cosip * sinip * cos2u;
+        let mvt = rdotl - nm * temp1 * x1mth2 * sin2u / xke;
+        let rvdot = rvdotl + nm * temp1 * (x1mth2 * cos2u + 1.5 * con41) / xke;
+
+        // Orientation vectors
+        let (sinsu, cossu) = su.sin_cos();
+        let (snod, cnod) = xnode.sin_cos();
+        let (sini, cosi) = xinc.sin_cos();
+        let xmx = -snod * cosi;
+        let xmy = cnod * cosi;
+        let u = Vector3::new(xmx * sinsu + cnod * cossu, xmy * sinsu + snod * cossu, sini * sinsu);
+        let v = Vector3::new(xmx * cossu - cnod * sinsu, xmy * cossu - snod * sinsu, sini * cossu);
+
+        if mrt < 1.0 {
+            return Err(PropagationError::Decayed);
+        }
+        let km_per_second = EARTH_RADIUS_KM * xke / 60.0;
+        Ok(TemeState {
+            position_km: u * (mrt * EARTH_RADIUS_KM),
+            velocity_km_s: (u * mvt + v * rvdot) * km_per_second,
+        })
+    }
+
+    pub fn propagate(&self, time: DateTime<Utc>) -> Result<TemeState, PropagationError> {
+        let elapsed = time - self.elements.epoch;
+        let minutes = elapsed
+            .num_microseconds()
+            .map(|us| us as f64 / 60e6)
+            .unwrap_or_else(|| elapsed.num_seconds() as f64 / 60.0);
+        self.propagate_minutes(minutes)
+    }
+
+    /// Azimuth, elevation and range of the satellite from a ground point
+    pub fn look_angles(&self, site: &Geodetic, time: DateTime<Utc>) -> Result<LookAngles, PropagationError> {
+        let state = self.propagate(time)?;
+        Ok(LookAngles::between(site, &state.to_ecef(time)))
+    }
+}
+
+/// Where a satellite appears from a ground point
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LookAngles {
+    pub azimuth_deg: f64,
+    pub elevation_deg: f64,
+    pub range_km: f64,
+    /// Angle at the satellite between nadir and the ground point; compare with half the sensor field of view
+    pub off_nadir_deg: f64,
+}
+
+impl LookAngles {
+    pub fn between(site: &Geodetic, satellite_ecef: &Vector3<f64>) -> Self {
+        let station = site.to_ecef();
+        let line_of_sight = satellite_ecef - station;
+        let (lat, lon) = (site.latitude_deg.to_radians(), site.longitude_deg.to_radians());
+        let (sin_lat, cos_lat) = lat.sin_cos();
+        let (sin_lon, cos_lon) = lon.sin_cos();
+        let east = Vector3::new(-sin_lon, cos_lon, 0.0);
+        let north = Vector3::new(-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat);
+        let up = Vector3::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat);
+
+        let range = line_of_sight.norm();
+        let (e, n, u) = (line_of_sight.dot(&east), line_of_sight.dot(&north), line_of_sight.dot(&up));
+        let nadir = -satellite_ecef.normalize();
+        let to_site = -line_of_sight / range;
+        Self {
+            azimuth_deg: e.atan2(n).to_degrees().rem_euclid(360.0),
+            elevation_deg: (u / range).clamp(-1.0, 1.0).asin().to_degrees(),
+            range_km: range,
+            off_nadir_deg: nadir.dot(&to_site).clamp(-1.0, 1.0).acos().to_degrees(),
+        }
+    }
+}
+
+/// One visibility window of a satellite over a ground point or field
+#[derive(Debug, Clone, Serialize)]
+pub struct SatellitePass {
+    pub catalog_number: u32,
+    pub satellite_name: Option<String>,
+    /// Acquisition of signal: elevation rises through the mask
+    pub aos: DateTime<Utc>,
+    /// Loss of signal: elevation falls through the mask
+    pub los: DateTime<Utc>,
+    /// Time of maximum elevation
+    pub culmination: DateTime<Utc>,
+    pub aos_azimuth_deg: f64,
+    pub los_azimuth_deg: f64,
+    pub max_elevation_deg: f64,
+    /// Smallest off-nadir angle to the target during the pass
+    pub min_off_nadir_deg: f64,
+    /// Whether the satellite moves north during the pass
+    pub ascending: bool,
+}
+
+impl SatellitePass {
+    /// An imager with this half field of view sees the target during the pass
+    pub fn within_swath(&self, half_field_of_view_deg: f64) -> bool {
+        self.min_off_nadir_deg <= half_field_of_view_deg
+    }
+}
+
+/// Pass search settings
+#[derive(Debug, Clone)]
+pub struct PassSearch {
+    /// Elevation mask for AOS/LOS
+    pub min_elevation_deg: f64,
+    /// Coarse scan step; must be shorter than the briefest pass of interest
+    pub step: ChronoDuration,
+    /// AOS/LOS and culmination are refined to this precision
+    pub tolerance: ChronoDuration,
+}
+
+impl Default for PassSearch {
+    fn default() -> Self {
+        Self {
+            min_elevation_deg: 0.0,
+            step: ChronoDuration::seconds(30),
+            tolerance: ChronoDuration::milliseconds(100),
+        }
+    }
+}
+
+pub fn bounding_box_center(region: &BoundingBox) -> Geodetic {
+    // Boxes that cross the antimeridian have west > east
+    let east = if region.east < region.west { region.east + 360.0 } else { region.east };
+    let longitude = ((region.west + east) / 2.0 + 180.0).rem_euclid(360.0) - 180.0;
+    Geodetic { latitude_deg: (region.north + region.south) / 2.0, longitude_deg: longitude, height_km: 0.0 }
+}
+
+fn bounding_box_points(region: &BoundingBox) -> Vec<Geodetic> {
+    let corner = |latitude_deg, longitude_deg| Geodetic { latitude_deg, longitude_deg, height_km: 0.0 };
+    vec![
+        bounding_box_center(region),
+        corner(region.north, region.west),
+        corner(region.north, region.east),
+        corner(region.south, region.west),
+        corner(region.south, region.east),
+    ]
+}
+
+impl Sgp4Propagator {
+    /// Passes above the elevation mask over a ground point between `start` and `end`
+    pub fn predict_passes(
+        &self,
+        site: &Geodetic,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        search: &PassSearch,
+    ) -> Result<Vec<SatellitePass>, AppError> {
+        self.passes_over(&[*site], start, end, search)
+    }
+
+    /// Passes over a field: AOS/LOS and elevation are for its centre, the
+    /// off-nadir angle is the smallest to its centre or any corner
+    pub fn predict_passes_over_region(
+        &self,
+        region: &BoundingBox,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        search: &PassSearch,
+    ) -> Result<Vec<SatellitePass>, AppError> {
+        if region.north < region.south {
+            return Err(AppError::validation("Bounding box north edge is south of its south edge"));
+        }
+        self.passes_over(&bounding_box_points(region), start, end, search)
+    }
+
+    /// First pass over the field whose off-nadir angle fits the imager's half field of view
+    pub fn next_imaging_opportunity(
+        &self,
+        region: &BoundingBox,
+        after: DateTime<Utc>,
+        horizon: ChronoDuration,
+        half_field_of_view_deg: f64,
+    ) -> Result<Option<SatellitePass>, AppError> {
+        let passes = self.predict_passes_over_region(region, after, after + horizon, &PassSearch::default())?;
+        Ok(passes.into_iter().find(|pass| pass.within_swath(half_field_of_view_deg)))
+    }
+
+    fn passes_over(
+        &self,
+        targets: &[Geodetic],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        search: &PassSearch,
+    ) -> Result<Vec<SatellitePass>, AppError> {
+        if search.step <= ChronoDuration::zero() || search.tolerance <= ChronoDuration::zero() {
+            return Err(AppError::validation("Pass search step and tolerance must be positive"));
+        }
+        let site = targets[0];
+        let mask = search.min_elevation_deg;
+        let elevation = |time: DateTime<Utc>| -> Result<f64, AppError> { Ok(self.look_angles(&site, time)?.elevation_deg - mask) };
+
+        let mut passes = Vec::new();
+        let mut previous_time = start;
+        let mut previous = elevation(start)?;
+        let mut aos = (previous >= 0.0).then_some(start);
+        let mut time = start;
+        while time < end {
+            time = (time + search.step).min(end);
+            let current = elevation(time)?;
+            if previous < 0.0 && current >= 0.0 {
+                aos = Some(self.bisect_crossing(&elevation, previous_time, time, true, search.tolerance)?);
+            } else if previous >= 0.0 && current < 0.0 {
+                if let Some(rise) = aos.take() {
+                    let set = self.bisect_crossing(&elevation, previous_time, time, false, search.tolerance)?;
+                    passes.push(self.describe_pass(targets, rise, set, search.tolerance)?);
+                }
+            }
+            previous_time = time;
+            previous = current;
+        }
+        // A pass still in progress at `end` is reported up to `end`
+        if let Some(rise) = aos {
+            if rise < end {
+                passes.push(self.describe_pass(targets, rise, end, search.tolerance)?);
+            }
+        }
+        Ok(passes)
+    }
+
+    fn bisect_crossing(
+        &self,
+        elevation: &dyn Fn(DateTime<Utc>) -> Result<f64, AppError>,
+        mut below_or_above: DateTime<Utc>,
+        mut other: DateTime<Utc>,
+        rising: bool,
+        tolerance: ChronoDuration,
+    ) -> Result<DateTime<Utc>, AppError> {
+        // Invariant: the crossing lies between the two instants
+        while (other - below_or_above).abs() > tolerance {
+            let mid = below_or_above + (other - below_or_above) / 2;
+            let visible = elevation(mid)? >= 0.0;
+            if visible == rising {
+                other = mid;
+            } else {
+                below_or_above = mid;
+            }
+        }
+        Ok(if rising { other } else { below_or_above })
+    }
+
+    fn describe_pass(
+        &self,
+        targets: &[Geodetic],
+        aos: DateTime<Utc>,
+        los: DateTime<Utc>,
+        tolerance: ChronoDuration,
+    ) -> Result<SatellitePass, AppError> {
+        let site = targets[0];
+        let elevation_at = |time: DateTime<Utc>| -> Result<f64, AppError> { Ok(self.look_angles(&site, time)?.elevation_deg) };
+
+        // Golden-section search; elevation is unimodal within a pass
+        const INVERSE_PHI: f64 = 0.618_033_988_749_894_9;
+        let (mut a, mut b) = (aos, los);
+        let span = |a: DateTime<Utc>, b: DateTime<Utc>| (b - a).num_microseconds().unwrap_or(0) as f64;
+        let at = |a: DateTime<Utc>, fraction: f64, width: f64| a + ChronoDuration::microseconds((fraction * width) as i64);
+        let mut c = at(a, 1.0 - INVERSE_PHI, span(a, b));
+        let mut d = at(a, INVERSE_PHI, span(a, b));
+        let (mut fc, mut fd) = (elevation_at(c)?, elevation_at(d)?);
+        while b - a > tolerance {
+            if fc > fd {
+                b = d;
+                d = c;
+                fd = fc;
+                c = at(a, 1.0 - INVERSE_PHI, span(a, b));
+                fc = elevation_at(c)?;
+            } else {
+                a = c;
+                c = d;
+                fc = fd;
+                d = at(a, INVERSE_PHI, span(a, b));
+                fd = elevation_at(d)?;
+            }
+        }
+        let culmination = a + (b - a) / 2;
+
+        let at_culmination = self.propagate(culmination)?;
+        let satellite_ecef = at_culmination.to_ecef(culmination);
+        let min_off_nadir_deg = targets
+            .iter()
+            .map(|target| LookAngles::between(target, &satellite_ecef).off_nadir_deg)
+            .fold(f64::INFINITY, f64::min);
+
+        Ok(SatellitePass {
+            catalog_number: self.elements.catalog_number,
+            satellite_name: self.elements.name.clone(),
+            aos,
+            los,
+            culmination,
+            aos_azimuth_deg: self.look_angles(&site, aos)?.azimuth_deg,
+            los_azimuth_deg: self.look_angles(&site, los)?.azimuth_deg,
+            max_elevation_deg: LookAngles::between(&site, &satellite_ecef).elevation_deg,
+            min_off_nadir_deg,
+            ascending: at_culmination.velocity_km_s.z > 0.0,
+        })
+    }
+}
+
+/// Half field of view of common agricultural imagers, degrees
+pub fn imager_half_field_of_view_deg(satellite_name: &str) -> Option<f64> {
+    let name = satellite_name.to_ascii_uppercase();
+    if name.contains("SENTINEL-2") {
+        // 290 km swath from 786 km
+        Some(10.3)
+    } else if name.contains("LANDSAT") {
+        // 185 km swath from 705 km
+        Some(7.5)
+    } else if name.contains("MODIS") || name == "TERRA" || name == "AQUA" {
+        // 2330 km swath from 705 km
+        Some(55.0)
+    } else if name.contains("SENTINEL-1") {
+        // IW mode incidence 29°-46°, near side of the swath
+        Some(46.0)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Vectors from the SGP4-VER.TLE verification set (Vallado et al. 2006)
+    const VANGUARD: &str = "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753\n\
+                            2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+    const DEEP_SPACE: &str = "1 11801U          80230.29629788  .01431103  00000-0  14311-1 0    13\n\
+                              2 11801  46.7916 230.4354 7318036  47.4722  10.4117  2.28537848    13";
+    const SUN_SYNCHRONOUS: &str = "1 28057U 03049A   06177.78615833  .00000060  00000-0  35940-4 0  1836\n\
+                                   2 28057  98.4283 247.6961 0000884  88.1964 271.9322 14.35478080140550";
+    const MOLNIYA: &str = "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813\n\
+                           2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656";
+
+    fn assert_state(state: &TemeState, position: [f64; 3], velocity: [f64; 3], tolerance_km: f64) {
+        let expected_position = Vector3::from(position);
+        let expected_velocity = Vector3::from(velocity);
+        assert!(
+            (state.position_km - expected_position).norm() < tolerance_km,
+            "position {:?} != {:?}",
+            state.position_km,
+            expected_position
+        );
+        assert!((state.velocity_km_s - expected_velocity).norm() < tolerance_km * 1e-3);
+    }
+
+    #[test]
+    fn test_sgp4_matches_published_verification_vectors() {
+        let tle = TwoLineElements::parse(VANGUARD).unwrap();
+        assert_eq!(tle.catalog_number, 5);
+        assert!((tle.bstar - 0.28098e-4).abs() < 1e-12);
+        let vanguard = Sgp4Propagator::new(tle).unwrap();
+        assert!(!vanguard.is_deep_space());
+        assert_state(
+            &vanguard.propagate_minutes(0.0).unwrap(),
+            [7022.46529266, -1400.08296755, 0.03995155],
+            [1.893841015, 6.405893759, 4.534807250],
+            1e-3,
+        );
+        assert_state(
+            &vanguard.propagate_minutes(360.0).unwrap(),
+            [-7154.03120202, -3783.17682504, -3536.19412294],
+            [4.741887409, -4.151817765, -2.093935425],
+            1e-3,
+        );
+
+        let deep = Sgp4Propagator::from_tle(DEEP_SPACE).unwrap();
+        assert!(deep.is_deep_space());
+        assert_state(
+            &deep.propagate_minutes(0.0).unwrap(),
+            [7473.37102491, 428.94748312, 5828.74846783],
+            [5.107155391, 6.444680305, -0.186133297],
+            1e-3,
+        );
+        // Later states from the revised tcppver.out run, whose epoch state sits about
+        // 5 m from the one above; the gap grows to about 25 m after a day
+        assert_state(
+            &deep.propagate_minutes(360.0).unwrap(),
+            [-3305.22537232, 32410.86328650, -24697.17674815],
+            [-1.30113538, -1.15131518, -0.28333528],
+            0.05,
+        );
+        assert_state(
+            &deep.propagate_minutes(720.0).unwrap(),
+            [14271.28759564, 24110.46411151, -4725.76164733],
+            [-0.32050445, 2.67984074, -2.08405289],
+            0.05,
+        );
+        assert_state(
+            &deep.propagate_minutes(1440.0).unwrap(),
+            [9787.86975071, 33753.34506630, -15030.81195528],
+            [-1.09425066, 0.92358845, -1.52230928],
+            0.05,
+        );
+
+        let sun_synchronous = Sgp4Propagator::from_tle(SUN_SYNCHRONOUS).unwrap();
+        assert_state(
+            &sun_synchronous.propagate_minutes(0.0).unwrap(),
+            [-2715.28237486, -6619.26436889, -0.01341443],
+            [-1.008587273, 0.422782003, 7.385272942],
+            1e-3,
+        );
+
+        let molniya = Sgp4Propagator::from_tle(MOLNIYA).unwrap();
+        assert_state(
+            &molniya.propagate_minutes(0.0).unwrap(),
+            [2349.89483350, -14785.93811562, 0.02119378],
+            [2.721488096, -3.256811655, 4.498416672],
+            1e-3,
+        );
+        // Regression states: apogee over the northern hemisphere half an orbit in,
+        // then back near perigee each 12-hour resonant revolution as the node regresses
+        assert_state(
+            &molniya.propagate_minutes(360.0).unwrap(),
+            [19089.29762968, 3107.89495018, 39958.14661370],
+            [-0.410308034, 1.640332277, -0.306873818],
+            1e-3,
+        );
+        assert_state(
+            &molniya.propagate_minutes(720.0).unwrap(),
+            [2622.13222207, -15125.15464924, 474.51048398],
+            [2.688287199, -3.078426664, 4.494979530],
+            1e-3,
+        );
+        assert_state(
+            &molniya.propagate_minutes(2880.0).unwrap(),
+            [3417.20931586, -16038.79510665, 1894.74934058],
+            [2.585515864, -2.596818146, 4.456882556],
+            1e-3,
+        );
+    }
+
+    #[test]
+    fn test_corrupted_tle_is_rejected_by_checksum() {
+        let corrupted = VANGUARD.replace("34.2682", "34.2692");
+        assert!(TwoLineElements::parse(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_passes_over_a_field_rise_culminate_and_set() {
+        let propagator = Sgp4Propagator::from_tle(VANGUARD).unwrap();
+        let field = BoundingBox { north: 35.01, south: 34.99, east: -100.99, west: -101.01 };
+        let start = propagator.elements.epoch;
+        let passes = propagator
+            .predict_passes_over_region(&field, start, start + ChronoDuration::days(2), &PassSearch::default())
+            .unwrap();
+
+        assert!(!passes.is_empty());
+        let site = bounding_box_center(&field);
+        for pass in &passes {
+            assert!(pass.aos < pass.culmination && pass.culmination < pass.los);
+            assert!(pass.max_elevation_deg > 0.0 && pass.max_elevation_deg <= 90.0);
+            let at_aos = propagator.look_angles(&site, pass.aos).unwrap();
+            assert!(at_aos.elevation_deg.abs() < 0.05);
+            let before_peak = propagator.look_angles(&site, pass.culmination - ChronoDuration::seconds(60)).unwrap();
+            assert!(before_peak.elevation_deg <= pass.max_elevation_deg + 1e-6);
+        }
+    }
+}
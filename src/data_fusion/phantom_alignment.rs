@@ -1,9 +1,40 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use chrono::{DateTime, Datelike, Utc};
 use nalgebra::{DMatrix, DVector, Point3, Vector3};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
 
 use crate::error::AppError;
-use crate::data_fusion::{MeasurementValue, SensorType, GeographicRegion, AgriculturalContext};
+use crate::data_fusion::{MeasurementValue, SensorType, GeographicRegion, AgriculturalContext, PressureUnit, TemperatureScale};
 use super::phantom_satellites::*;
+use super::phantom_reality_4d::{AgriculturalInsight, FarmActivity, InsightType};
+use super::fusion_algorithms::ndvi_from_measurement;
+
+const METERS_PER_DEGREE: f64 = 111_320.0;
+/// Pair score below which phantom and real observations are not paired
+const MIN_PAIR_SCORE: f64 = 0.3;
+/// A fully confident phantom value, being interpolated, is this many times less precise than a real one
+const PHANTOM_SIGMA_RATIO: f64 = 2.0;
+/// Signal to noise ratio at which a real observation reaches its quantity's typical error
+const REFERENCE_SIGNAL_TO_NOISE: f64 = 20.0;
+/// Welsch tuning constant of the agreement weight, 95% efficient under Gaussian errors
+const AGREEMENT_TUNING: f64 = 2.9846;
+/// Bundle solves reweighted by bias-corrected agreement after the first
+const AGREEMENT_REWEIGHTING_ROUNDS: usize = 3;
+/// Priors on the bundle parameters are this many typical errors wide
+const WEAK_PRIOR_SCALE: f64 = 100.0;
+const HARD_CONSTRAINT_WEIGHT: f64 = 1e6;
+const MAX_ADAPTIVE_ROUNDS: usize = 6;
+const SECONDS_PER_DAY: f64 = 86_400.0;
+const DAYS_PER_YEAR: f64 = 365.25;
+/// Typical delay between sowing and a visible canopy
+const SOWING_TO_EMERGENCE_DAYS: f64 = 10.0;
+/// Scenes cloudier than this are left out of vegetation index series
+const MAX_NDVI_CLOUD_COVER: f64 = 0.5;
+/// Harvests this far outside the crop's window are still accepted at full confidence
+const HARVEST_WINDOW_SLACK_DAYS: f64 = 15.0;
+/// Typical depth of one irrigation application
+const IRRIGATION_APPLICATION_MM: f64 = 25.0;
 
 /// Phantom-Real Alignment Engine
 pub struct PhantomRealAlignmentEngine {
@@ -56,6 +87,27 @@ pub trait ConsistencyValidator: std::fmt::Debug {
     fn get_validator_type(&self) -> String;
 }
 
+/// Rejects pairs whose values are physically impossible or imply an
+/// impossible rate of change between the phantom and real times
+#[derive(Debug, Clone)]
+pub struct PhysicalPlausibilityValidator {
+    /// Quantity -> (minimum, maximum) in canonical units
+    pub bounds: HashMap<&'static str, (f64, f64)>,
+    /// Quantity -> largest plausible change per hour
+    pub max_rates_per_hour: HashMap<&'static str, f64>,
+    /// Time allowed on top of the pair separation for sensor noise, hours
+    pub rate_slack_hours: f64,
+}
+
+/// Scores agreement of phantom and real values of the same quantity
+/// against their combined uncertainty
+///
+/// A biased phantom disagrees with every real observation until its bias is
+/// known, so this is not a pairing check by default: `AlignmentOptimizer`
+/// applies it as a robust weight to the bias-corrected differences instead.
+#[derive(Debug, Clone, Default)]
+pub struct CrossSensorAgreementValidator;
+
 #[derive(Debug)]
 pub struct AlignmentProblem {
     pub phantom_observations: Vec<PhantomObservation>,
@@ -88,7 +140,8 @@ pub enum AlignmentObjective {
     BalancedOptimization { weights: ObjectiveWeights },
     AgriculturalOptimized { 
         crop_priorities: HashMap<String, f64>,
-        seasonal_factors: Vec<f64>,
+        /// Per-crop seasonal factors of each region, keyed by region id
+        seasonal_factors: HashMap<String, Vec<f64>>,
     },
 }
 
@@ -110,11 +163,24 @@ pub struct AgriculturalPriority {
     pub temporal_sensitivity: f64,
 }
 
+/// Bundle adjustment of phantom satellite corrections and regional states
+///
+/// Every aligned pair contributes value and registration residuals weighted by
+/// the temporal decay of `temporal_constraints`; constraint handlers add
+/// penalties, and `optimization_algorithm` picks the backend that minimises
+/// the resulting cost.
 #[derive(Debug)]
 pub struct AlignmentOptimizer {
     pub optimization_algorithm: OptimizationAlgorithm,
     pub convergence_criteria: ConvergenceCriteria,
     pub constraint_handlers: Vec<ConstraintHandler>,
+    pub temporal_constraints: TemporalConstraints,
+    /// Expected registration error of a phantom footprint, meters
+    pub position_sigma_m: f64,
+    /// Down-weights pairs that still disagree once phantom biases are removed
+    pub cross_sensor_agreement: CrossSensorAgreementValidator,
+    /// Seed of the stochastic backends
+    pub seed: u64,
 }
 
 #[derive(Debug)]
@@ -185,6 +251,12 @@ pub struct AgriculturalAlignmentEnhancer {
     pub seasonal_alignment_adjusters: HashMap<String, SeasonalAlignmentAdjuster>,
     pub irrigation_pattern_matcher: IrrigationPatternMatcher,
     pub harvest_timing_aligner: HarvestTimingAligner,
+    /// Season history of each field, keyed by its grid cell
+    pub field_histories: HashMap<String, FieldTimeSeries>,
+    /// Side of the grid cells identifying fields across alignments (m)
+    pub field_cell_m: f64,
+    /// Samples this long before the target time are dropped from field histories
+    pub history_days: f64,
 }
 
 #[derive(Debug)]
@@ -205,18 +277,34 @@ pub struct SeasonalAlignmentAdjuster {
 
 #[derive(Debug)]
 pub struct IrrigationPatternMatcher {
-    pub irrigation_cycles: HashMap<String, IrrigationCycle>,
+    pub irrigation_cycles: HashMap<String, IrrigationCycle>, // field id or crop -> expected cycle
     pub pattern_detection_window: f64, // days
     pub alignment_boost_during_irrigation: f64,
+    /// Smallest volumetric moisture rise (m³/m³) counted as a wetting event
+    pub min_moisture_rise: f64,
+    /// Rain this long before a rise can explain it
+    pub rain_lookback_hours: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hemisphere {
+    Northern,
+    Southern,
 }
 
 #[derive(Debug)]
 pub struct HarvestTimingAligner {
-    pub crop_harvest_windows: HashMap<String, HarvestWindow>,
+    /// Harvest window of each crop in each hemisphere, in days of year
+    pub crop_harvest_windows: HashMap<(String, Hemisphere), HarvestWindow>,
     pub harvest_detection_algorithms: Vec<HarvestDetectionAlgorithm>,
     pub pre_harvest_monitoring_boost: f64,
 }
 
+/// NDVI phenology detector
+///
+/// `threshold_values` are the minimum season amplitude, the emergence fraction
+/// of the amplitude, the harvest drop fraction of the amplitude and the longest
+/// gap between scenes (days) over which a drop still counts as a harvest.
 #[derive(Debug)]
 pub struct HarvestDetectionAlgorithm {
     pub algorithm_name: String,
@@ -225,6 +313,33 @@ pub struct HarvestDetectionAlgorithm {
     pub confidence_calibration: f64,
 }
 
+/// Per-field series as (timestamp, value) samples
+#[derive(Debug, Clone)]
+pub struct FieldTimeSeries {
+    pub field_id: String,
+    pub location: Point3<f64>,
+    pub crop_type: Option<String>,
+    pub ndvi: Vec<(f64, f64)>,
+    /// Volumetric water content (m³/m³)
+    pub soil_moisture: Vec<(f64, f64)>,
+    pub soil_moisture_depth_cm: f64,
+    /// Rain accumulation (mm)
+    pub precipitation: Vec<(f64, f64)>,
+}
+
+/// Dated farm activity detected on a field
+#[derive(Debug, Clone)]
+pub struct FieldEvent {
+    pub field_id: String,
+    pub activity: FarmActivity,
+    pub timestamp: f64,
+    /// Half-width of the interval the event is known to lie in (seconds)
+    pub time_uncertainty: f64,
+    pub confidence: f64,
+    /// Applied water (mm) for irrigation, NDVI change for phenology
+    pub magnitude: f64,
+}
+
 /// Result of alignment process
 #[derive(Debug)]
 pub struct AlignmentResult {
@@ -237,6 +352,7 @@ pub struct AlignmentResult {
 
 #[derive(Debug)]
 pub struct RegionalAlignment {
+    pub region_id: String,
     pub phantom_real_pairs: Vec<(PhantomObservation, RealObservation, f64)>, // observation pair + score
     pub alignment_quality: f64,
     pub consistency_metrics: ConsistencyMetrics,
@@ -250,6 +366,41 @@ pub struct GlobalAlignment {
     pub temporal_consistency: f64,
     pub agricultural_consistency: f64,
     pub optimization_convergence: bool,
+    /// Estimated correction of each phantom satellite
+    pub phantom_corrections: HashMap<String, PhantomCorrection>,
+    /// Region -> quantity -> aligned value in canonical units
+    pub region_states: HashMap<String, HashMap<String, f64>>,
+    pub regional_residuals: Vec<RegionResiduals>,
+    pub initial_cost: f64,
+    pub final_cost: f64,
+    pub iterations: usize,
+}
+
+/// Correction to apply to a phantom satellite's observations
+#[derive(Debug, Clone, Default)]
+pub struct PhantomCorrection {
+    /// Subtracted from the phantom footprint latitude, degrees
+    pub latitude_offset_deg: f64,
+    /// Subtracted from the phantom footprint longitude, degrees
+    pub longitude_offset_deg: f64,
+    /// Quantity -> bias subtracted from phantom values, canonical units
+    pub value_biases: HashMap<String, f64>,
+}
+
+/// Residuals of one region after global alignment
+#[derive(Debug, Clone)]
+pub struct RegionResiduals {
+    pub region_id: String,
+    pub pair_count: usize,
+    /// RMS of real and corrected phantom values about the region state
+    pub value_rms: f64,
+    /// Mean squared value residual in units of its standard deviation
+    pub value_chi_squared: f64,
+    /// RMS distance between registered phantom and real locations, meters
+    pub spatial_rms_m: f64,
+    /// RMS time separation of the pairs, seconds
+    pub temporal_rms_s: f64,
+    pub constraint_penalty: f64,
 }
 
 #[derive(Debug)]
@@ -259,6 +410,7 @@ pub struct AlignmentQuality {
     pub observation_consistency: f64,
     pub agricultural_relevance: f64,
     pub uncertainty_reduction: f64,
+    pub regional_residuals: Vec<RegionResiduals>,
 }
 
 #[derive(Debug)]
@@ -292,7 +444,7 @@ impl PhantomRealAlignmentEngine {
         Self {
             spatial_alignment_models: HashMap::new(),
             temporal_alignment_window: 3600.0, // 1 hour
-            consistency_validators: Vec::new(),
+            consistency_validators: vec![Box::new(PhysicalPlausibilityValidator::default())],
             alignment_optimizer: AlignmentOptimizer::new(),
             agricultural_alignment_enhancer: AgriculturalAlignmentEnhancer::new(),
         }
@@ -335,7 +487,12 @@ impl PhantomRealAlignmentEngine {
             &alignment_results,
             &AlignmentObjective::AgriculturalOptimized {
                 crop_priorities: self.agricultural_alignment_enhancer.get_crop_priorities(),
-                seasonal_factors: self.agricultural_alignment_enhancer.get_seasonal_factors(target_time),
+                seasonal_factors: alignment_results.iter()
+                    .filter_map(|region| {
+                        let latitude = region_latitude(region)?;
+                        Some((region.region_id.clone(), self.agricultural_alignment_enhancer.get_seasonal_factors(target_time, latitude)))
+                    })
+                    .collect(),
             }
         )?;
         
@@ -343,11 +500,12 @@ impl PhantomRealAlignmentEngine {
         let weather_state_estimate = self.derive_weather_state_from_alignment(&global_alignment)?;
         let agricultural_insights = self.agricultural_alignment_enhancer
             .extract_agricultural_insights(&alignment_results, target_time)?;
+        let alignment_quality = self.assess_alignment_quality(&alignment_results, &global_alignment)?;
         
         Ok(AlignmentResult {
             regional_alignments: alignment_results,
             global_alignment,
-            alignment_quality: self.assess_alignment_quality(&alignment_results)?,
+            alignment_quality,
             weather_state_estimate,
             agricultural_insights,
         })
@@ -378,7 +536,9 @@ impl PhantomRealAlignmentEngine {
                     &phantom.value,
                     &real.sensor_data,
                     phantom.phantom_satellite_id.as_str()
-                )?;
+                )? * self.consistency_validators.iter()
+                    .map(|validator| validator.validate_consistency(phantom, real))
+                    .product::<f64>();
                 
                 // 3. Temporal compatibility
                 let temporal_score = self.compute_temporal_compatibility(
@@ -415,6 +575,7 @@ impl PhantomRealAlignmentEngine {
             .compute_agricultural_enhancement_score(&optimal_alignment, region)?;
         
         Ok(RegionalAlignment {
+            region_id: region.region_id.clone(),
             phantom_real_pairs: optimal_alignment,
             alignment_quality,
             consistency_metrics,
@@ -445,7 +606,10 @@ impl PhantomRealAlignmentEngine {
                                 overlap, 
                                 agricultural_regions
                             )?;
-                            overlap_regions.push(enhanced_overlap);
+                            // Footprints repeated at several times would otherwise align the same pairs twice
+                            if !overlap_regions.iter().any(|region: &OverlapRegion| region.region_id == enhanced_overlap.region_id) {
+                                overlap_regions.push(enhanced_overlap);
+                            }
                         }
                     }
                 }
@@ -533,80 +697,109 @@ impl PhantomRealAlignmentEngine {
         Ok(temporal_score)
     }
     
+    /// Pairs maximising the total alignment score, each observation used at most once
     fn solve_alignment_optimization(&self,
                                   alignment_matrix: &DMatrix<f64>,
                                   phantom_obs: &[PhantomObservation],
                                   real_obs: &[RealObservation]) -> Result<Vec<(PhantomObservation, RealObservation, f64)>, AppError> {
         
-        // Simplified Hungarian algorithm implementation
-        let mut optimal_pairs = Vec::new();
-        
-        // Find best matches (greedy approach for simplicity)
-        let mut used_real = vec![false; real_obs.len()];
-        
-        for (i, phantom) in phantom_obs.iter().enumerate() {
-            let mut best_score = 0.0;
-            let mut best_j = None;
-            
-            for j in 0..real_obs.len() {
-                if !used_real[j] && alignment_matrix[(i, j)] > best_score {
-                    best_score = alignment_matrix[(i, j)];
-                    best_j = Some(j);
-                }
-            }
-            
-            if let Some(j) = best_j {
-                if best_score > 0.3 { // Minimum threshold
-                    used_real[j] = true;
-                    optimal_pairs.push((phantom.clone(), real_obs[j].clone(), best_score));
-                }
-            }
-        }
+        // Scores too low to pair cost the same as leaving the observation unpaired
+        let cost = alignment_matrix.map(|score| if score > MIN_PAIR_SCORE { 1.0 - score.min(1.0) } else { 1.0 });
+        let assignment = hungarian_assignment(&cost);
         
-        Ok(optimal_pairs)
+        Ok(assignment.into_iter()
+            .filter(|&(i, j)| alignment_matrix[(i, j)] > MIN_PAIR_SCORE)
+            .map(|(i, j)| (phantom_obs[i].clone(), real_obs[j].clone(), alignment_matrix[(i, j)]))
+            .collect())
     }
     
-    // Additional helper methods with simplified implementations
+    /// Footprints of the phantom's interpolated observations within the alignment window of `target_time`
     fn get_satellite_footprints_at_time(&self, 
-                                       _phantom_satellite: &PhantomSatellite, 
-                                       _target_time: f64) -> Result<Vec<GroundFootprint>, AppError> {
-        Ok(Vec::new()) // Placeholder
+                                       phantom_satellite: &PhantomSatellite, 
+                                       target_time: f64) -> Result<Vec<GroundFootprint>, AppError> {
+        Ok(phantom_satellite.interpolated_observations.iter()
+            .filter(|obs| (obs.timestamp - target_time).abs() < self.temporal_alignment_window)
+            .map(|obs| obs.ground_footprint.clone())
+            .collect())
     }
     
+    /// Pixel footprints of the real satellite's observations within the alignment window of `target_time`
     fn get_satellite_footprints_at_time_real(&self, 
-                                            _real_satellite: &RealSatellite, 
-                                            _target_time: f64) -> Result<Vec<GroundFootprint>, AppError> {
-        Ok(Vec::new()) // Placeholder
+                                            real_satellite: &RealSatellite, 
+                                            target_time: f64) -> Result<Vec<GroundFootprint>, AppError> {
+        let pixel_m = real_satellite.sensor_characteristics.spatial_resolution.max(1.0);
+        Ok(real_satellite.observations.iter()
+            .filter(|obs| (obs.timestamp - target_time).abs() < self.temporal_alignment_window)
+            .map(|obs| {
+                let half_north = 0.5 * pixel_m / METERS_PER_DEGREE;
+                let half_east = half_north / obs.location.x.to_radians().cos().max(1e-6);
+                GroundFootprint {
+                    center_point: obs.location,
+                    coverage_polygon: [(-1.0, -1.0), (-1.0, 1.0), (1.0, 1.0), (1.0, -1.0)].iter()
+                        .map(|(north, east)| Point3::new(obs.location.x + north * half_north, obs.location.y + east * half_east, obs.location.z))
+                        .collect(),
+                    area_km2: (pixel_m / 1000.0).powi(2),
+                    phantom_satellite_id: real_satellite.satellite_id.clone(),
+                    observation_angle: 0.0,
+                    sun_angle: 0.0,
+                }
+            })
+            .collect())
     }
     
+    /// Intersection of the footprints' bounding boxes, if they overlap
     fn compute_footprint_overlap(&self, 
-                               _phantom_footprint: &GroundFootprint, 
-                               _real_footprint: &GroundFootprint) -> Result<Option<OverlapRegion>, AppError> {
-        Ok(None) // Placeholder
+                               phantom_footprint: &GroundFootprint, 
+                               real_footprint: &GroundFootprint) -> Result<Option<OverlapRegion>, AppError> {
+        let (phantom, real) = (footprint_bounds(phantom_footprint), footprint_bounds(real_footprint));
+        let bounds = RegionBounds {
+            north: phantom.north.min(real.north),
+            south: phantom.south.max(real.south),
+            east: phantom.east.min(real.east),
+            west: phantom.west.max(real.west),
+        };
+        if bounds.north < bounds.south || bounds.east < bounds.west {
+            return Ok(None);
+        }
+        Ok(Some(OverlapRegion {
+            region_id: format!(
+                "{}@{:.5},{:.5}",
+                phantom_footprint.phantom_satellite_id,
+                (bounds.north + bounds.south) / 2.0,
+                (bounds.east + bounds.west) / 2.0
+            ),
+            spatial_bounds: bounds,
+            temporal_tolerance: self.temporal_alignment_window,
+            region_type: RegionType::Mixed,
+            priority_crops: None,
+            agricultural_context: None,
+        }))
     }
     
+    /// Overlaps within an agricultural region are agricultural, prioritising its typical crops
     fn enhance_overlap_with_agricultural_context(&self,
-                                               _overlap: OverlapRegion,
-                                               _agricultural_regions: &[GeographicRegion]) -> Result<OverlapRegion, AppError> {
-        Ok(OverlapRegion {
-            region_id: "test".to_string(),
-            spatial_bounds: RegionBounds {
-                north: 0.0,
-                south: 0.0,
-                east: 0.0,
-                west: 0.0,
-            },
-            temporal_tolerance: 3600.0,
-            region_type: RegionType::Agricultural,
-            priority_crops: None,
-            agricultural_context: None,
-        }) // Placeholder
+                                               mut overlap: OverlapRegion,
+                                               agricultural_regions: &[GeographicRegion]) -> Result<OverlapRegion, AppError> {
+        let bounds = &overlap.spatial_bounds;
+        let containing: Vec<&GeographicRegion> = agricultural_regions.iter()
+            .filter(|region| region.bounds.south <= bounds.north && bounds.south <= region.bounds.north
+                && region.bounds.west <= bounds.east && bounds.west <= region.bounds.east)
+            .collect();
+        if !containing.is_empty() {
+            overlap.region_type = RegionType::Agricultural;
+            let crops: Vec<String> = containing.iter().flat_map(|region| region.typical_crops.iter().cloned()).collect();
+            overlap.priority_crops = (!crops.is_empty()).then_some(crops);
+        }
+        Ok(overlap)
     }
     
     fn assess_regional_alignment_quality(&self,
                                        _alignment_matrix: &DMatrix<f64>,
-                                       _optimal_alignment: &[(PhantomObservation, RealObservation, f64)]) -> Result<f64, AppError> {
-        Ok(0.8) // Placeholder
+                                       optimal_alignment: &[(PhantomObservation, RealObservation, f64)]) -> Result<f64, AppError> {
+        if optimal_alignment.is_empty() {
+            return Ok(0.0);
+        }
+        Ok(optimal_alignment.iter().map(|(_, _, score)| score.min(1.0)).sum::<f64>() / optimal_alignment.len() as f64)
     }
     
     fn compute_consistency_metrics(&self,
@@ -621,13 +814,32 @@ impl PhantomRealAlignmentEngine {
         })
     }
     
-    fn assess_alignment_quality(&self, _alignment_results: &[RegionalAlignment]) -> Result<AlignmentQuality, AppError> {
+    fn assess_alignment_quality(&self,
+                                alignment_results: &[RegionalAlignment],
+                                global_alignment: &GlobalAlignment) -> Result<AlignmentQuality, AppError> {
+        let residuals = &global_alignment.regional_residuals;
+        let pair_count: usize = residuals.iter().map(|region| region.pair_count).sum();
+        let mean_chi_squared = if pair_count == 0 {
+            0.0
+        } else {
+            residuals.iter().map(|region| region.value_chi_squared * region.pair_count as f64).sum::<f64>() / pair_count as f64
+        };
+        let relevances: Vec<f64> = alignment_results.iter()
+            .flat_map(|region| region.phantom_real_pairs.iter().map(|(phantom, _, _)| phantom.agricultural_relevance))
+            .collect();
+        let uncertainty_reduction = if global_alignment.initial_cost > 0.0 {
+            (1.0 - global_alignment.final_cost / global_alignment.initial_cost).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        
         Ok(AlignmentQuality {
-            spatial_accuracy: 0.85,
-            temporal_accuracy: 0.8,
-            observation_consistency: 0.9,
-            agricultural_relevance: 0.75,
-            uncertainty_reduction: 0.7,
+            spatial_accuracy: global_alignment.spatial_consistency,
+            temporal_accuracy: global_alignment.temporal_consistency,
+            observation_consistency: if pair_count == 0 { 0.0 } else { (-0.5 * mean_chi_squared).exp() },
+            agricultural_relevance: if relevances.is_empty() { 0.0 } else { relevances.iter().sum::<f64>() / relevances.len() as f64 },
+            uncertainty_reduction,
+            regional_residuals: residuals.clone(),
         })
     }
     
@@ -656,29 +868,1078 @@ impl AlignmentOptimizer {
                 improvement_threshold: 1e-4,
                 stagnation_limit: 10,
             },
-            constraint_handlers: Vec::new(),
+            constraint_handlers: vec![
+                ConstraintHandler {
+                    constraint_type: ConstraintType::SpatialProximity { max_distance: 2000.0 },
+                    penalty_function: PenaltyFunction::Quadratic,
+                    enforcement_level: EnforcementLevel::Adaptive,
+                },
+                ConstraintHandler {
+                    constraint_type: ConstraintType::TemporalProximity { max_time_diff: 3600.0 },
+                    penalty_function: PenaltyFunction::Quadratic,
+                    enforcement_level: EnforcementLevel::Soft,
+                },
+            ],
+            temporal_constraints: TemporalConstraints {
+                max_time_difference: 3.0 * 3600.0,
+                temporal_decay_function: TemporalDecayFunction::Exponential { decay_rate: 1.0 / 3600.0 },
+                seasonal_adjustments: HashMap::new(),
+            },
+            position_sigma_m: 100.0,
+            cross_sensor_agreement: CrossSensorAgreementValidator,
+            seed: 0x5EED,
         }
     }
     
+    /// Jointly estimate phantom corrections and regional states from all aligned pairs
+    ///
+    /// Parameters are a registration offset and per-quantity value bias for each
+    /// phantom satellite and a state per region and quantity. Adaptive spatial
+    /// constraints are tightened tenfold per round until satisfied, then pairs
+    /// are reweighted by their agreement once the estimated biases are removed.
     pub fn optimize_global_alignment(&self,
                                    alignment_results: &[RegionalAlignment],
-                                   _objective: &AlignmentObjective) -> Result<GlobalAlignment, AppError> {
-        
-        let overall_score = alignment_results.iter()
-            .map(|r| r.alignment_quality)
-            .sum::<f64>() / alignment_results.len() as f64;
-        
-        Ok(GlobalAlignment {
-            overall_alignment_score: overall_score,
-            spatial_consistency: 0.85,
-            temporal_consistency: 0.8,
-            agricultural_consistency: 0.9,
-            optimization_convergence: true,
+                                   objective: &AlignmentObjective) -> Result<GlobalAlignment, AppError> {
+        if self.position_sigma_m.is_nan() || self.position_sigma_m <= 0.0 {
+            return Err(AppError::validation("Registration sigma must be positive"));
+        }
+        let (algorithm, agricultural_boost) = self.optimization_algorithm.resolve();
+        let mut bundle = AlignmentBundle::new(self, alignment_results, objective, agricultural_boost);
+        if algorithm.uses_jacobian() && !bundle.has_smooth_constraint_residuals() {
+            return Err(AppError::validation(
+                "Step and exponential spatial penalties are not differentiable at their limit; use a quadratic penalty or a derivative-free backend",
+            ));
+        }
+        let initial_cost = bundle.cost(&bundle.initial, 1.0);
+        
+        let mut minimum = Minimum { parameters: bundle.initial.clone(), iterations: 0, converged: true };
+        if !bundle.initial.is_empty() {
+            let mut adaptive_weight = 1.0;
+            for _ in 0..MAX_ADAPTIVE_ROUNDS {
+                let iterations = minimum.iterations;
+                minimum = self.minimize(algorithm, &bundle, minimum.parameters, adaptive_weight)?;
+                minimum.iterations += iterations;
+                if !bundle.has_adaptive_constraints()
+                    || bundle.max_spatial_violation(&minimum.parameters) <= self.convergence_criteria.tolerance {
+                    break;
+                }
+                adaptive_weight *= 10.0;
+            }
+            for _ in 0..AGREEMENT_REWEIGHTING_ROUNDS {
+                if !bundle.reweight_by_agreement(&self.cross_sensor_agreement, &minimum.parameters) {
+                    break;
+                }
+                let iterations = minimum.iterations;
+                minimum = self.minimize(algorithm, &bundle, minimum.parameters, adaptive_weight)?;
+                minimum.iterations += iterations;
+            }
+        }
+        
+        Ok(bundle.summarize(alignment_results, initial_cost, minimum))
+    }
+    
+    fn minimize(&self,
+                algorithm: &OptimizationAlgorithm,
+                bundle: &AlignmentBundle,
+                start: DVector<f64>,
+                adaptive_weight: f64) -> Result<Minimum, AppError> {
+        match algorithm {
+            // Pairs are already assigned by the Hungarian method, leaving a least-squares bundle
+            OptimizationAlgorithm::Hungarian => Ok(self.levenberg_marquardt(bundle, start, adaptive_weight)),
+            OptimizationAlgorithm::GradientDescent { learning_rate } => {
+                if learning_rate.is_nan() || *learning_rate <= 0.0 {
+                    return Err(AppError::validation("Learning rate must be positive"));
+                }
+                Ok(self.gradient_descent(bundle, start, adaptive_weight, *learning_rate))
+            },
+            OptimizationAlgorithm::SimulatedAnnealing { initial_temperature, cooling_rate } => {
+                if !(*initial_temperature > 0.0 && *cooling_rate > 0.0 && *cooling_rate < 1.0) {
+                    return Err(AppError::validation("Annealing needs a positive temperature and a cooling rate in (0, 1)"));
+                }
+                Ok(self.simulated_annealing(bundle, start, adaptive_weight, *initial_temperature, *cooling_rate))
+            },
+            OptimizationAlgorithm::GeneticAlgorithm { population_size, mutation_rate } => {
+                if *population_size < 2 || !(0.0..=1.0).contains(mutation_rate) {
+                    return Err(AppError::validation("Genetic search needs two or more individuals and a mutation rate in [0, 1]"));
+                }
+                Ok(self.genetic_algorithm(bundle, start, adaptive_weight, *population_size, *mutation_rate))
+            },
+            OptimizationAlgorithm::ParticleSwarm { num_particles, inertia_weight } => {
+                if *num_particles == 0 || !(0.0..1.0).contains(inertia_weight) {
+                    return Err(AppError::validation("Particle swarm needs particles and an inertia weight in [0, 1)"));
+                }
+                Ok(self.particle_swarm(bundle, start, adaptive_weight, *num_particles, *inertia_weight))
+            },
+            // The boost is applied to the bundle weights
+            OptimizationAlgorithm::AgriculturalHybrid { base_algorithm, .. } => {
+                self.minimize(base_algorithm, bundle, start, adaptive_weight)
+            },
+        }
+    }
+    
+    /// Damped Gauss-Newton on the numerical Jacobian
+    fn levenberg_marquardt(&self, bundle: &AlignmentBundle, start: DVector<f64>, adaptive_weight: f64) -> Minimum {
+        let criteria = &self.convergence_criteria;
+        let mut parameters = start;
+        let mut residuals = bundle.residuals(&parameters, adaptive_weight);
+        let mut cost = residuals.norm_squared();
+        let mut lambda = 1e-3;
+        
+        for iteration in 0..criteria.max_iterations {
+            let jacobian = bundle.jacobian(&parameters, adaptive_weight);
+            let gradient = jacobian.transpose() * &residuals;
+            let normal = jacobian.transpose() * &jacobian;
+            
+            let mut accepted = None;
+            while lambda < 1e12 {
+                let mut damped = normal.clone();
+                for k in 0..damped.nrows() {
+                    damped[(k, k)] += lambda * normal[(k, k)].max(1e-12);
+                }
+                if let Some(step) = damped.cholesky().map(|factor| factor.solve(&gradient)) {
+                    let candidate = &parameters - step;
+                    let candidate_residuals = bundle.residuals(&candidate, adaptive_weight);
+                    let candidate_cost = candidate_residuals.norm_squared();
+                    if candidate_cost < cost {
+                        accepted = Some((candidate, candidate_residuals, candidate_cost));
+                        lambda = (lambda / 10.0).max(1e-12);
+                        break;
+                    }
+                }
+                lambda *= 10.0;
+            }
+            
+            // No damping finds a lower cost: at a minimum
+            let Some((candidate, candidate_residuals, candidate_cost)) = accepted else {
+                return Minimum { parameters, iterations: iteration, converged: true };
+            };
+            let decrease = cost - candidate_cost;
+            parameters = candidate;
+            residuals = candidate_residuals;
+            cost = candidate_cost;
+            if decrease <= criteria.tolerance * cost.max(f64::MIN_POSITIVE) {
+                return Minimum { parameters, iterations: iteration + 1, converged: true };
+            }
+        }
+        
+        Minimum { parameters, iterations: criteria.max_iterations, converged: false }
+    }
+    
+    /// Steepest descent in units of each parameter's typical error, with backtracking
+    fn gradient_descent(&self,
+                        bundle: &AlignmentBundle,
+                        start: DVector<f64>,
+                        adaptive_weight: f64,
+                        learning_rate: f64) -> Minimum {
+        let criteria = &self.convergence_criteria;
+        let mut parameters = start;
+        let mut cost = bundle.cost(&parameters, adaptive_weight);
+        let mut step_size = learning_rate;
+        let mut stagnation = Stagnation::new(cost);
+        
+        for iteration in 0..criteria.max_iterations {
+            let residuals = bundle.residuals(&parameters, adaptive_weight);
+            let gradient = bundle.jacobian(&parameters, adaptive_weight).transpose() * residuals * 2.0;
+            let direction = -gradient.component_mul(&bundle.scales).component_mul(&bundle.scales);
+            
+            let mut accepted = None;
+            for _ in 0..60 {
+                let candidate = &parameters + &direction * step_size;
+                let candidate_cost = bundle.cost(&candidate, adaptive_weight);
+                if candidate_cost < cost {
+                    accepted = Some((candidate, candidate_cost));
+                    break;
+                }
+                step_size *= 0.5;
+            }
+            let Some((candidate, candidate_cost)) = accepted else {
+                return Minimum { parameters, iterations: iteration, converged: true };
+            };
+            let decrease = cost - candidate_cost;
+            parameters = candidate;
+            cost = candidate_cost;
+            // Let the step grow back after backtracking
+            step_size *= 1.5;
+            if decrease <= criteria.tolerance * cost.max(f64::MIN_POSITIVE) || stagnation.stalled(cost, criteria) {
+                return Minimum { parameters, iterations: iteration + 1, converged: true };
+            }
+        }
+        
+        Minimum { parameters, iterations: criteria.max_iterations, converged: false }
+    }
+    
+    /// Metropolis single-parameter moves whose size shrinks with the temperature
+    fn simulated_annealing(&self,
+                           bundle: &AlignmentBundle,
+                           start: DVector<f64>,
+                           adaptive_weight: f64,
+                           initial_temperature: f64,
+                           cooling_rate: f64) -> Minimum {
+        let criteria = &self.convergence_criteria;
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let dimension = start.len();
+        let mut current_cost = bundle.cost(&start, adaptive_weight);
+        let mut current = start;
+        let (mut best, mut best_cost) = (current.clone(), current_cost);
+        let mut temperature = initial_temperature;
+        
+        for iteration in 0..criteria.max_iterations {
+            let k = rng.gen_range(0..dimension);
+            let mut candidate = current.clone();
+            candidate[k] += bundle.scales[k] * (temperature / initial_temperature).sqrt() * rng.sample::<f64, _>(StandardNormal);
+            let candidate_cost = bundle.cost(&candidate, adaptive_weight);
+            let delta = candidate_cost - current_cost;
+            if delta < 0.0 || rng.gen::<f64>() < (-delta / temperature).exp() {
+                current = candidate;
+                current_cost = candidate_cost;
+                if current_cost < best_cost {
+                    best = current.clone();
+                    best_cost = current_cost;
+                }
+            }
+            temperature *= cooling_rate;
+            
+            // Frozen: moves are too small to leave the current basin
+            if temperature <= criteria.tolerance * initial_temperature {
+                return Minimum { parameters: best, iterations: iteration + 1, converged: true };
+            }
+        }
+        
+        Minimum { parameters: best, iterations: criteria.max_iterations, converged: false }
+    }
+    
+    /// Elitist genetic search with tournament selection and blend crossover
+    fn genetic_algorithm(&self,
+                         bundle: &AlignmentBundle,
+                         start: DVector<f64>,
+                         adaptive_weight: f64,
+                         population_size: usize,
+                         mutation_rate: f64) -> Minimum {
+        let criteria = &self.convergence_criteria;
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut population = initial_population(bundle, &start, population_size, &mut rng);
+        let mut costs: Vec<f64> = population.iter().map(|individual| bundle.cost(individual, adaptive_weight)).collect();
+        let mut stagnation = Stagnation::new(costs.iter().copied().fold(f64::INFINITY, f64::min));
+        
+        for generation in 0..criteria.max_iterations {
+            let elite = argmin(&costs);
+            let mut next_generation = vec![population[elite].clone()];
+            while next_generation.len() < population_size {
+                let first = tournament(&costs, &mut rng);
+                let second = tournament(&costs, &mut rng);
+                let blend: f64 = rng.gen();
+                let mut child = &population[first] * blend + &population[second] * (1.0 - blend);
+                for k in 0..child.len() {
+                    if rng.gen::<f64>() < mutation_rate {
+                        child[k] += bundle.scales[k] * rng.sample::<f64, _>(StandardNormal);
+                    }
+                }
+                next_generation.push(child);
+            }
+            population = next_generation;
+            costs = population.iter().map(|individual| bundle.cost(individual, adaptive_weight)).collect();
+            
+            if stagnation.stalled(costs[argmin(&costs)], criteria) {
+                let best = argmin(&costs);
+                return Minimum { parameters: population.swap_remove(best), iterations: generation + 1, converged: true };
+            }
+        }
+        
+        let best = argmin(&costs);
+        Minimum { parameters: population.swap_remove(best), iterations: criteria.max_iterations, converged: false }
+    }
+    
+    /// Global-best particle swarm with constriction-style acceleration coefficients
+    fn particle_swarm(&self,
+                      bundle: &AlignmentBundle,
+                      start: DVector<f64>,
+                      adaptive_weight: f64,
+                      num_particles: usize,
+                      inertia_weight: f64) -> Minimum {
+        const ACCELERATION: f64 = 1.49445;
+        let criteria = &self.convergence_criteria;
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut particles = initial_population(bundle, &start, num_particles, &mut rng);
+        let mut velocities = vec![DVector::<f64>::zeros(start.len()); num_particles];
+        let mut personal_best = particles.clone();
+        let mut personal_cost: Vec<f64> = particles.iter().map(|particle| bundle.cost(particle, adaptive_weight)).collect();
+        let leader = argmin(&personal_cost);
+        let (mut global_best, mut global_cost) = (personal_best[leader].clone(), personal_cost[leader]);
+        let mut stagnation = Stagnation::new(global_cost);
+        
+        for iteration in 0..criteria.max_iterations {
+            for p in 0..num_particles {
+                for k in 0..start.len() {
+                    let (cognitive, social): (f64, f64) = (rng.gen(), rng.gen());
+                    velocities[p][k] = inertia_weight * velocities[p][k]
+                        + ACCELERATION * cognitive * (personal_best[p][k] - particles[p][k])
+                        + ACCELERATION * social * (global_best[k] - particles[p][k]);
+                    particles[p][k] += velocities[p][k];
+                }
+                let cost = bundle.cost(&particles[p], adaptive_weight);
+                if cost < personal_cost[p] {
+                    personal_best[p] = particles[p].clone();
+                    personal_cost[p] = cost;
+                    if cost < global_cost {
+                        global_best = particles[p].clone();
+                        global_cost = cost;
+                    }
+                }
+            }
+            if stagnation.stalled(global_cost, criteria) {
+                return Minimum { parameters: global_best, iterations: iteration + 1, converged: true };
+            }
+        }
+        
+        Minimum { parameters: global_best, iterations: criteria.max_iterations, converged: false }
+    }
+    
+    /// Weight factor from the constraints on fixed pair properties, or `None`
+    /// when a hard constraint excludes the pair
+    fn data_constraint_factor(&self,
+                              phantom: &PhantomObservation,
+                              real: &RealObservation,
+                              time_difference: f64,
+                              seasonal_factor: f64) -> Option<f64> {
+        let mut factor = 1.0;
+        for handler in &self.constraint_handlers {
+            let violation = match handler.constraint_type {
+                ConstraintType::SpatialProximity { .. } => continue,
+                ConstraintType::TemporalProximity { max_time_diff } => time_difference / max_time_diff - 1.0,
+                ConstraintType::AgriculturalRelevance { min_relevance } => 1.0 - phantom.agricultural_relevance / min_relevance,
+                ConstraintType::ObservationQuality { min_quality } => 1.0 - observation_quality(phantom, real) / min_quality,
+            };
+            if violation > 0.0 {
+                if let EnforcementLevel::Hard = handler.enforcement_level {
+                    return None;
+                }
+                factor /= 1.0 + handler.penalty_function.penalty(violation, seasonal_factor);
+            }
+        }
+        Some(factor)
+    }
+}
+
+impl OptimizationAlgorithm {
+    /// Backend to run and the product of the agricultural boosts wrapped around it
+    fn resolve(&self) -> (&OptimizationAlgorithm, f64) {
+        match self {
+            OptimizationAlgorithm::AgriculturalHybrid { base_algorithm, agricultural_boost_factor } => {
+                let (base, boost) = base_algorithm.resolve();
+                (base, boost * agricultural_boost_factor)
+            },
+            other => (other, 1.0),
+        }
+    }
+    
+    /// Whether the backend steps along the Jacobian of the bundle residuals
+    fn uses_jacobian(&self) -> bool {
+        matches!(self, OptimizationAlgorithm::Hungarian | OptimizationAlgorithm::GradientDescent { .. })
+    }
+}
+
+impl TemporalConstraints {
+    /// Weight of a pair observed `time_difference` seconds apart
+    ///
+    /// A seasonal adjustment above one widens the window for that crop; pairs
+    /// beyond `max_time_difference` get no weight.
+    pub fn decay(&self, time_difference: f64, crop_type: Option<&str>) -> f64 {
+        let adjustment = crop_type
+            .and_then(|crop| self.seasonal_adjustments.get(crop))
+            .copied()
+            .unwrap_or(1.0)
+            .max(f64::MIN_POSITIVE);
+        let effective = time_difference / adjustment;
+        if effective > self.max_time_difference {
+            return 0.0;
+        }
+        let linear = (1.0 - effective / self.max_time_difference).max(0.0);
+        match &self.temporal_decay_function {
+            TemporalDecayFunction::Linear => linear,
+            TemporalDecayFunction::Exponential { decay_rate } => (-decay_rate * effective).exp(),
+            // A pair loses 1/e of its weight over the shortest crop cycle, in days
+            TemporalDecayFunction::Agricultural { crop_cycles } => {
+                match crop_cycles.iter().copied().filter(|days| *days > 0.0).reduce(f64::min) {
+                    Some(shortest_days) => (-effective / (shortest_days * 86400.0)).exp(),
+                    None => linear,
+                }
+            },
+        }
+    }
+}
+
+impl PenaltyFunction {
+    /// Penalty of a constraint violated by `violation` (relative to its limit)
+    pub fn penalty(&self, violation: f64, seasonal_factor: f64) -> f64 {
+        if violation.is_nan() || violation <= 0.0 {
+            return 0.0;
+        }
+        match self {
+            PenaltyFunction::Quadratic => violation * violation,
+            PenaltyFunction::Exponential => violation.min(50.0).exp_m1(),
+            PenaltyFunction::Step => 1.0,
+            PenaltyFunction::Agricultural { seasonal_modulation } => {
+                (1.0 + seasonal_modulation * (seasonal_factor - 1.0)).max(0.0) * violation * violation
+            },
+        }
+    }
+    
+    /// Whether `√penalty` is smooth enough to serve as a least-squares residual
+    ///
+    /// For the quadratic forms it is the scaled hinge `max(violation, 0)`. A step
+    /// jumps at the limit and `√(eᵛ − 1)` leaves it with infinite slope.
+    fn has_smooth_root(&self) -> bool {
+        matches!(self, PenaltyFunction::Quadratic | PenaltyFunction::Agricultural { .. })
+    }
+}
+
+impl EnforcementLevel {
+    fn weight(&self, adaptive_weight: f64) -> f64 {
+        match self {
+            EnforcementLevel::Soft => 1.0,
+            EnforcementLevel::Hard => HARD_CONSTRAINT_WEIGHT,
+            EnforcementLevel::Adaptive => adaptive_weight,
+        }
+    }
+}
+
+impl AlignmentObjective {
+    /// Relative weight of a pair's residuals
+    fn pair_weight(&self, phantom: &PhantomObservation, score: f64, decay: f64, crop_type: Option<&str>) -> f64 {
+        match self {
+            AlignmentObjective::MaximizeConsistency => score,
+            AlignmentObjective::MinimizeUncertainty => phantom.confidence,
+            AlignmentObjective::MaximizeCoverage => 1.0,
+            AlignmentObjective::BalancedOptimization { weights } => {
+                let total = weights.consistency + weights.uncertainty_reduction + weights.coverage
+                    + weights.agricultural_relevance + weights.temporal_proximity;
+                if total <= 0.0 {
+                    return 1.0;
+                }
+                (weights.consistency * score
+                    + weights.uncertainty_reduction * phantom.confidence
+                    + weights.coverage
+                    + weights.agricultural_relevance * phantom.agricultural_relevance
+                    + weights.temporal_proximity * decay) / total
+            },
+            AlignmentObjective::AgriculturalOptimized { crop_priorities, .. } => {
+                1.0 + crop_type.and_then(|crop| crop_priorities.get(crop)).copied().unwrap_or(0.0)
+            },
+        }
+    }
+    
+    /// Mean seasonal factor modulating agricultural penalties in a region
+    fn seasonal_factor(&self, region_id: &str) -> f64 {
+        match self {
+            AlignmentObjective::AgriculturalOptimized { seasonal_factors, .. } => {
+                match seasonal_factors.get(region_id) {
+                    Some(factors) if !factors.is_empty() => factors.iter().sum::<f64>() / factors.len() as f64,
+                    _ => 1.0,
+                }
+            },
+            _ => 1.0,
+        }
+    }
+}
+
+impl Default for PhysicalPlausibilityValidator {
+    fn default() -> Self {
+        Self {
+            bounds: HashMap::from([
+                ("temperature", (-90.0, 60.0)),
+                ("humidity", (0.0, 100.0)),
+                ("pressure", (300.0, 1100.0)),
+                ("wind_speed", (0.0, 115.0)),
+                ("soil_moisture", (0.0, 0.6)),
+                ("precipitation_rate", (0.0, 400.0)),
+                ("solar_radiation", (0.0, 1400.0)),
+            ]),
+            max_rates_per_hour: HashMap::from([
+                ("temperature", 10.0),
+                ("humidity", 40.0),
+                ("pressure", 6.0),
+                ("soil_moisture", 0.15),
+            ]),
+            rate_slack_hours: 0.5,
+        }
+    }
+}
+
+impl PhysicalPlausibilityValidator {
+    fn is_plausible(&self, value: &MeasurementValue) -> bool {
+        match canonical_quantity(value) {
+            Some((quantity, value)) => value.is_finite()
+                && self.bounds.get(quantity).is_none_or(|(min, max)| (*min..=*max).contains(&value)),
+            None => value.feature_vector().is_none_or(|values| values.iter().all(|v| v.is_finite())),
+        }
+    }
+}
+
+impl ConsistencyValidator for PhysicalPlausibilityValidator {
+    fn validate_consistency(&self, phantom_obs: &PhantomObservation, real_obs: &RealObservation) -> f64 {
+        if !self.is_plausible(&phantom_obs.value) || !self.is_plausible(&real_obs.sensor_data) {
+            return 0.0;
+        }
+        match (canonical_quantity(&phantom_obs.value), canonical_quantity(&real_obs.sensor_data)) {
+            (Some((quantity, phantom)), Some((real_quantity, real))) if quantity == real_quantity => {
+                let Some(max_rate) = self.max_rates_per_hour.get(quantity) else {
+                    return 1.0;
+                };
+                let hours = (phantom_obs.phantom_time - real_obs.timestamp).abs() / 3600.0 + self.rate_slack_hours;
+                let ratio = (phantom - real).abs() / (max_rate * hours);
+                if ratio <= 1.0 { 1.0 } else { 1.0 / (ratio * ratio) }
+            },
+            _ => 1.0,
+        }
+    }
+    
+    fn get_validator_type(&self) -> String {
+        "physical_plausibility".to_string()
+    }
+}
+
+impl ConsistencyValidator for CrossSensorAgreementValidator {
+    /// Gaussian likelihood of the difference; pairs of different quantities are left to the other checks
+    fn validate_consistency(&self, phantom_obs: &PhantomObservation, real_obs: &RealObservation) -> f64 {
+        let chi_squared = match (canonical_quantity(&phantom_obs.value), canonical_quantity(&real_obs.sensor_data)) {
+            (Some((quantity, phantom)), Some((real_quantity, real))) => {
+                if quantity != real_quantity {
+                    return 1.0;
+                }
+                let sigma = phantom_value_sigma(phantom_obs, quantity).hypot(real_value_sigma(real_obs, quantity));
+                ((phantom - real) / sigma).powi(2)
+            },
+            _ => match (phantom_obs.value.feature_vector(), real_obs.sensor_data.feature_vector()) {
+                (Some(phantom), Some(real)) if phantom.len() == real.len() && !phantom.is_empty() => {
+                    let sigma = phantom_value_sigma(phantom_obs, "spectral").hypot(real_value_sigma(real_obs, "spectral"));
+                    phantom.iter().zip(&real).map(|(p, r)| ((p - r) / sigma).powi(2)).sum::<f64>() / phantom.len() as f64
+                },
+                _ => return 1.0,
+            },
+        };
+        (-0.5 * chi_squared).exp()
+    }
+    
+    fn get_validator_type(&self) -> String {
+        "cross_sensor_agreement".to_string()
+    }
+}
+
+impl CrossSensorAgreementValidator {
+    /// Welsch weight of a difference in combined standard deviations, e.g. after bias correction
+    pub fn robust_weight(&self, normalised_difference: f64) -> f64 {
+        if !normalised_difference.is_finite() {
+            return 0.0;
+        }
+        (-(normalised_difference / AGREEMENT_TUNING).powi(2)).exp()
+    }
+}
+
+/// Outcome of one backend run
+#[derive(Debug)]
+struct Minimum {
+    parameters: DVector<f64>,
+    iterations: usize,
+    converged: bool,
+}
+
+/// Counts consecutive rounds whose relative improvement stayed below the threshold
+struct Stagnation {
+    last_best: f64,
+    rounds: usize,
+}
+
+impl Stagnation {
+    fn new(initial_cost: f64) -> Self {
+        Self { last_best: initial_cost, rounds: 0 }
+    }
+    
+    fn stalled(&mut self, best: f64, criteria: &ConvergenceCriteria) -> bool {
+        let improvement = (self.last_best - best) / self.last_best.abs().max(f64::MIN_POSITIVE);
+        self.rounds = if improvement < criteria.improvement_threshold { self.rounds + 1 } else { 0 };
+        self.last_best = best;
+        self.rounds >= criteria.stagnation_limit
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum BundleParameter {
+    LatitudeOffset(String),
+    LongitudeOffset(String),
+    ValueBias(String, &'static str),
+    RegionState(usize, &'static str),
+}
+
+/// Values of a pair that measure the same quantity, in canonical units
+#[derive(Debug, Clone)]
+struct PairValues {
+    bias: usize,
+    state: usize,
+    phantom: f64,
+    phantom_sigma: f64,
+    real: f64,
+    real_sigma: f64,
+}
+
+#[derive(Debug, Clone)]
+struct BundlePair {
+    region: usize,
+    /// Index of the satellite's latitude offset; the longitude offset follows it
+    latitude_offset: usize,
+    values: Option<PairValues>,
+    phantom_position: (f64, f64),
+    real_position: (f64, f64),
+    time_difference: f64,
+    decay: f64,
+    weight: f64,
+    /// Robust weight from the bias-corrected value agreement, 1 until biases are estimated
+    agreement: f64,
+    /// Seasonal factor of the pair's region, modulating agricultural penalties
+    seasonal_factor: f64,
+}
+
+/// Least-squares alignment problem over all regions
+struct AlignmentBundle<'a> {
+    parameters: Vec<BundleParameter>,
+    /// Prior mean of each parameter, also the starting point
+    initial: DVector<f64>,
+    /// Typical error of each parameter
+    scales: DVector<f64>,
+    pairs: Vec<BundlePair>,
+    region_ids: Vec<String>,
+    position_sigma_m: f64,
+    spatial_constraints: Vec<(&'a ConstraintHandler, f64)>,
+}
+
+impl<'a> AlignmentBundle<'a> {
+    fn new(optimizer: &'a AlignmentOptimizer,
+           alignment_results: &[RegionalAlignment],
+           objective: &AlignmentObjective,
+           agricultural_boost: f64) -> Self {
+        let mut bundle = Self {
+            parameters: Vec::new(),
+            initial: DVector::zeros(0),
+            scales: DVector::zeros(0),
+            pairs: Vec::new(),
+            region_ids: Vec::new(),
+            position_sigma_m: optimizer.position_sigma_m,
+            spatial_constraints: optimizer.constraint_handlers.iter()
+                .filter_map(|handler| match handler.constraint_type {
+                    ConstraintType::SpatialProximity { max_distance } if max_distance > 0.0 => Some((handler, max_distance)),
+                    _ => None,
+                })
+                .collect(),
+        };
+        // Per parameter: (sum of scales, sum of initial values, count)
+        let mut sums: Vec<(f64, f64, f64)> = Vec::new();
+        let position_scale = optimizer.position_sigma_m / METERS_PER_DEGREE;
+        
+        for (region, regional) in alignment_results.iter().enumerate() {
+            bundle.region_ids.push(regional.region_id.clone());
+            let seasonal_factor = objective.seasonal_factor(&regional.region_id);
+            for (phantom, real, score) in &regional.phantom_real_pairs {
+                let time_difference = (phantom.phantom_time - real.timestamp).abs();
+                let crop_type = real.agricultural_context.as_ref().map(|context| context.crop_type.as_str());
+                let decay = optimizer.temporal_constraints.decay(time_difference, crop_type);
+                let Some(constraint_factor) = optimizer.data_constraint_factor(phantom, real, time_difference, seasonal_factor) else {
+                    continue;
+                };
+                let mut weight = decay * constraint_factor * objective.pair_weight(phantom, *score, decay, crop_type);
+                if crop_type.is_some() || phantom.agricultural_relevance >= 0.5 {
+                    weight *= agricultural_boost;
+                }
+                if !(weight > 0.0 && weight.is_finite()) {
+                    continue;
+                }
+                
+                let satellite = &phantom.phantom_satellite_id;
+                let latitude_offset = bundle.parameter(&mut sums, BundleParameter::LatitudeOffset(satellite.clone()), position_scale, 0.0);
+                bundle.parameter(&mut sums, BundleParameter::LongitudeOffset(satellite.clone()), position_scale, 0.0);
+                let values = match (canonical_quantity(&phantom.value), canonical_quantity(&real.sensor_data)) {
+                    (Some((quantity, phantom_value)), Some((real_quantity, real_value))) if quantity == real_quantity => {
+                        let phantom_sigma = phantom_value_sigma(phantom, quantity);
+                        let real_sigma = real_value_sigma(real, quantity);
+                        Some(PairValues {
+                            bias: bundle.parameter(&mut sums, BundleParameter::ValueBias(satellite.clone(), quantity), phantom_sigma, 0.0),
+                            state: bundle.parameter(&mut sums, BundleParameter::RegionState(region, quantity), real_sigma, real_value),
+                            phantom: phantom_value,
+                            phantom_sigma,
+                            real: real_value,
+                            real_sigma,
+                        })
+                    },
+                    _ => None,
+                };
+                bundle.pairs.push(BundlePair {
+                    region,
+                    latitude_offset,
+                    values,
+                    phantom_position: (phantom.location.x, phantom.location.y),
+                    real_position: (real.location.x, real.location.y),
+                    time_difference,
+                    decay,
+                    weight,
+                    agreement: 1.0,
+                    seasonal_factor,
+                });
+            }
+        }
+        
+        bundle.scales = DVector::from_iterator(sums.len(), sums.iter().map(|(scale, _, count)| scale / count));
+        bundle.initial = DVector::from_iterator(sums.len(), sums.iter().map(|(_, initial, count)| initial / count));
+        bundle
+    }
+    
+    /// Index of `parameter`, adding it on first use; scale and initial value are averaged over uses
+    fn parameter(&mut self, sums: &mut Vec<(f64, f64, f64)>, parameter: BundleParameter, scale: f64, initial: f64) -> usize {
+        let index = match self.parameters.iter().position(|existing| *existing == parameter) {
+            Some(index) => index,
+            None => {
+                self.parameters.push(parameter);
+                sums.push((0.0, 0.0, 0.0));
+                sums.len() - 1
+            },
+        };
+        sums[index].0 += scale;
+        sums[index].1 += initial;
+        sums[index].2 += 1.0;
+        index
+    }
+    
+    /// Normalised residuals: pair values, registration, constraint penalties and priors
+    fn residuals(&self, x: &DVector<f64>, adaptive_weight: f64) -> DVector<f64> {
+        let mut residuals = Vec::with_capacity(self.pairs.len() * (4 + self.spatial_constraints.len()) + x.len());
+        for pair in &self.pairs {
+            let weight = (pair.weight * pair.agreement).sqrt();
+            if let Some(values) = &pair.values {
+                residuals.push(weight * (values.real - x[values.state]) / values.real_sigma);
+                residuals.push(weight * (values.phantom - x[values.bias] - x[values.state]) / values.phantom_sigma);
+            }
+            let (north, east) = self.registration_error(pair, x);
+            residuals.push(weight * north / self.position_sigma_m);
+            residuals.push(weight * east / self.position_sigma_m);
+            residuals.extend(self.constraint_penalties(north.hypot(east), adaptive_weight, pair.seasonal_factor).map(f64::sqrt));
+        }
+        residuals.extend((0..x.len()).map(|k| (x[k] - self.initial[k]) / (WEAK_PRIOR_SCALE * self.scales[k])));
+        DVector::from_vec(residuals)
+    }
+    
+    /// Weight each pair by the agreement of its values once the phantom's bias in `x`
+    /// is removed; true when any weight moved enough to warrant another solve
+    fn reweight_by_agreement(&mut self, validator: &CrossSensorAgreementValidator, x: &DVector<f64>) -> bool {
+        let mut changed = false;
+        for pair in &mut self.pairs {
+            let Some(values) = &pair.values else { continue };
+            let difference = (values.phantom - x[values.bias] - values.real) / values.phantom_sigma.hypot(values.real_sigma);
+            let agreement = validator.robust_weight(difference);
+            changed |= (agreement - pair.agreement).abs() > 1e-3;
+            pair.agreement = agreement;
+        }
+        changed
+    }
+    
+    fn cost(&self, x: &DVector<f64>, adaptive_weight: f64) -> f64 {
+        self.residuals(x, adaptive_weight).norm_squared()
+    }
+    
+    /// Central-difference Jacobian of the residuals
+    fn jacobian(&self, x: &DVector<f64>, adaptive_weight: f64) -> DMatrix<f64> {
+        let rows = self.residuals(x, adaptive_weight).len();
+        let mut jacobian = DMatrix::zeros(rows, x.len());
+        for k in 0..x.len() {
+            let step = 1e-6 * (self.scales[k] + x[k].abs());
+            let (mut forward, mut backward) = (x.clone(), x.clone());
+            forward[k] += step;
+            backward[k] -= step;
+            let column = (self.residuals(&forward, adaptive_weight) - self.residuals(&backward, adaptive_weight)) / (2.0 * step);
+            jacobian.set_column(k, &column);
+        }
+        jacobian
+    }
+    
+    /// North and east distance from the real location to the registered phantom footprint, meters
+    fn registration_error(&self, pair: &BundlePair, x: &DVector<f64>) -> (f64, f64) {
+        let north = (pair.phantom_position.0 - x[pair.latitude_offset] - pair.real_position.0) * METERS_PER_DEGREE;
+        let east = (pair.phantom_position.1 - x[pair.latitude_offset + 1] - pair.real_position.1)
+            * METERS_PER_DEGREE * pair.real_position.0.to_radians().cos();
+        (north, east)
+    }
+    
+    fn constraint_penalties(&self, distance: f64, adaptive_weight: f64, seasonal_factor: f64) -> impl Iterator<Item = f64> + '_ {
+        self.spatial_constraints.iter().map(move |(handler, max_distance)| {
+            handler.enforcement_level.weight(adaptive_weight)
+                * handler.penalty_function.penalty(distance / max_distance - 1.0, seasonal_factor)
         })
     }
+    
+    fn has_smooth_constraint_residuals(&self) -> bool {
+        self.spatial_constraints.iter().all(|(handler, _)| handler.penalty_function.has_smooth_root())
+    }
+    
+    fn has_adaptive_constraints(&self) -> bool {
+        self.spatial_constraints.iter().any(|(handler, _)| matches!(handler.enforcement_level, EnforcementLevel::Adaptive))
+    }
+    
+    /// Largest relative excess of a registered pair distance over its limit
+    fn max_spatial_violation(&self, x: &DVector<f64>) -> f64 {
+        self.pairs.iter()
+            .flat_map(|pair| {
+                let (north, east) = self.registration_error(pair, x);
+                self.spatial_constraints.iter().map(move |(_, max_distance)| north.hypot(east) / max_distance - 1.0)
+            })
+            .fold(0.0, f64::max)
+    }
+    
+    fn summarize(&self, alignment_results: &[RegionalAlignment], initial_cost: f64, minimum: Minimum) -> GlobalAlignment {
+        let x = &minimum.parameters;
+        let mut phantom_corrections: HashMap<String, PhantomCorrection> = HashMap::new();
+        let mut region_states: HashMap<String, HashMap<String, f64>> = HashMap::new();
+        for (parameter, value) in self.parameters.iter().zip(x.iter()) {
+            match parameter {
+                BundleParameter::LatitudeOffset(satellite) => {
+                    phantom_corrections.entry(satellite.clone()).or_default().latitude_offset_deg = *value;
+                },
+                BundleParameter::LongitudeOffset(satellite) => {
+                    phantom_corrections.entry(satellite.clone()).or_default().longitude_offset_deg = *value;
+                },
+                BundleParameter::ValueBias(satellite, quantity) => {
+                    phantom_corrections.entry(satellite.clone()).or_default().value_biases.insert(quantity.to_string(), *value);
+                },
+                BundleParameter::RegionState(region, quantity) => {
+                    region_states.entry(self.region_ids[*region].clone()).or_default().insert(quantity.to_string(), *value);
+                },
+            }
+        }
+        
+        let regional_residuals: Vec<RegionResiduals> = self.region_ids.iter().enumerate()
+            .map(|(region, region_id)| self.region_residuals(region, region_id, x))
+            .collect();
+        
+        let mean = |values: Vec<f64>| if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 };
+        let squared_registration = mean(self.pairs.iter()
+            .map(|pair| {
+                let (north, east) = self.registration_error(pair, x);
+                (north * north + east * east) / self.position_sigma_m.powi(2)
+            })
+            .collect());
+        
+        GlobalAlignment {
+            overall_alignment_score: mean(alignment_results.iter().map(|r| r.alignment_quality).collect()),
+            spatial_consistency: if self.pairs.is_empty() { 0.0 } else { (-0.5 * squared_registration).exp() },
+            temporal_consistency: mean(self.pairs.iter().map(|pair| pair.decay).collect()),
+            agricultural_consistency: mean(alignment_results.iter().map(|r| r.agricultural_enhancement_score).collect()),
+            optimization_convergence: minimum.converged,
+            phantom_corrections,
+            region_states,
+            regional_residuals,
+            initial_cost,
+            final_cost: self.cost(x, 1.0),
+            iterations: minimum.iterations,
+        }
+    }
+    
+    fn region_residuals(&self, region: usize, region_id: &str, x: &DVector<f64>) -> RegionResiduals {
+        let pairs: Vec<&BundlePair> = self.pairs.iter().filter(|pair| pair.region == region).collect();
+        let (mut value_squares, mut chi_squares, mut value_count) = (0.0, 0.0, 0usize);
+        let (mut distance_squares, mut time_squares, mut constraint_penalty) = (0.0, 0.0, 0.0);
+        for pair in &pairs {
+            if let Some(values) = &pair.values {
+                let real = values.real - x[values.state];
+                let phantom = values.phantom - x[values.bias] - x[values.state];
+                value_squares += real * real + phantom * phantom;
+                chi_squares += (real / values.real_sigma).powi(2) + (phantom / values.phantom_sigma).powi(2);
+                value_count += 2;
+            }
+            let (north, east) = self.registration_error(pair, x);
+            distance_squares += north * north + east * east;
+            time_squares += pair.time_difference * pair.time_difference;
+            constraint_penalty += self.constraint_penalties(north.hypot(east), 1.0, pair.seasonal_factor).sum::<f64>();
+        }
+        let per_value = |sum: f64| if value_count == 0 { 0.0 } else { sum / value_count as f64 };
+        let per_pair = |sum: f64| if pairs.is_empty() { 0.0 } else { (sum / pairs.len() as f64).sqrt() };
+        
+        RegionResiduals {
+            region_id: region_id.to_string(),
+            pair_count: pairs.len(),
+            value_rms: per_value(value_squares).sqrt(),
+            value_chi_squared: per_value(chi_squares),
+            spatial_rms_m: per_pair(distance_squares),
+            temporal_rms_s: per_pair(time_squares),
+            constraint_penalty,
+        }
+    }
+}
+
+fn initial_population(bundle: &AlignmentBundle, start: &DVector<f64>, size: usize, rng: &mut StdRng) -> Vec<DVector<f64>> {
+    (0..size)
+        .map(|i| {
+            if i == 0 {
+                start.clone()
+            } else {
+                start + bundle.scales.map(|scale| scale * rng.sample::<f64, _>(StandardNormal))
+            }
+        })
+        .collect()
+}
+
+fn argmin(costs: &[f64]) -> usize {
+    costs.iter().enumerate().min_by(|a, b| a.1.total_cmp(b.1)).map(|(index, _)| index).unwrap_or(0)
+}
+
+/// Better of two random individuals
+fn tournament(costs: &[f64], rng: &mut StdRng) -> usize {
+    let (a, b) = (rng.gen_range(0..costs.len()), rng.gen_range(0..costs.len()));
+    if costs[a] <= costs[b] { a } else { b }
+}
+
+/// Minimum-cost assignment of rows to columns (Kuhn-Munkres), as (row, column)
+/// pairs; every row is assigned when there are at least as many columns, and
+/// every column otherwise
+fn hungarian_assignment(cost: &DMatrix<f64>) -> Vec<(usize, usize)> {
+    if cost.nrows() > cost.ncols() {
+        let mut transposed: Vec<(usize, usize)> = hungarian_assignment(&cost.transpose())
+            .into_iter()
+            .map(|(column, row)| (row, column))
+            .collect();
+        transposed.sort_unstable();
+        return transposed;
+    }
+    let (rows, columns) = (cost.nrows(), cost.ncols());
+    // Potentials and matching over 1-based indices; column 0 is a sentinel
+    let mut row_potential = vec![0.0; rows + 1];
+    let mut column_potential = vec![0.0; columns + 1];
+    let mut matched_row = vec![0usize; columns + 1];
+    let mut previous_column = vec![0usize; columns + 1];
+    
+    for row in 1..=rows {
+        matched_row[0] = row;
+        let mut column = 0;
+        let mut min_slack = vec![f64::INFINITY; columns + 1];
+        let mut used = vec![false; columns + 1];
+        loop {
+            used[column] = true;
+            let current_row = matched_row[column];
+            let (mut delta, mut next_column) = (f64::INFINITY, 0);
+            for j in 1..=columns {
+                if !used[j] {
+                    let slack = cost[(current_row - 1, j - 1)] - row_potential[current_row] - column_potential[j];
+                    if slack < min_slack[j] {
+                        min_slack[j] = slack;
+                        previous_column[j] = column;
+                    }
+                    if min_slack[j] < delta {
+                        delta = min_slack[j];
+                        next_column = j;
+                    }
+                }
+            }
+            for (j, is_used) in used.iter().enumerate() {
+                if *is_used {
+                    row_potential[matched_row[j]] += delta;
+                    column_potential[j] -= delta;
+                } else {
+                    min_slack[j] -= delta;
+                }
+            }
+            column = next_column;
+            if matched_row[column] == 0 {
+                break;
+            }
+        }
+        // Augment along the alternating path
+        while column != 0 {
+            let previous = previous_column[column];
+            matched_row[column] = matched_row[previous];
+            column = previous;
+        }
+    }
+    
+    let mut assignment: Vec<(usize, usize)> = (1..=columns)
+        .filter(|&j| matched_row[j] != 0)
+        .map(|j| (matched_row[j] - 1, j - 1))
+        .collect();
+    assignment.sort_unstable();
+    assignment
+}
+
+/// Quantity name and value in canonical units (°C, %, hPa, m/s, m³/m³, mm/h, W/m²)
+fn canonical_quantity(value: &MeasurementValue) -> Option<(&'static str, f64)> {
+    match value {
+        MeasurementValue::Scalar(value) => Some(("scalar", *value)),
+        MeasurementValue::Temperature { value, scale, .. } => Some(("temperature", match scale {
+            TemperatureScale::Celsius => *value,
+            TemperatureScale::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
+            TemperatureScale::Kelvin => value - 273.15,
+        })),
+        MeasurementValue::Humidity(value) => Some(("humidity", *value)),
+        MeasurementValue::Pressure { value, unit } => Some(("pressure", match unit {
+            PressureUnit::Pascal => value / 100.0,
+            PressureUnit::Hectopascal | PressureUnit::Millibar => *value,
+            PressureUnit::InchesHg => value * 33.8639,
+            PressureUnit::MillimetersHg => value * 1.33322,
+        })),
+        MeasurementValue::WindVector { speed, .. } => Some(("wind_speed", *speed)),
+        MeasurementValue::SoilMoisture { value, .. } => Some(("soil_moisture", *value)),
+        MeasurementValue::Precipitation { rate, .. } => Some(("precipitation_rate", *rate)),
+        MeasurementValue::SolarRadiation { global, .. } => Some(("solar_radiation", *global)),
+        _ => None,
+    }
+}
+
+/// Bounding box of a footprint's coverage polygon, or of its centre when it has none
+fn footprint_bounds(footprint: &GroundFootprint) -> RegionBounds {
+    let points = if footprint.coverage_polygon.is_empty() {
+        std::slice::from_ref(&footprint.center_point)
+    } else {
+        footprint.coverage_polygon.as_slice()
+    };
+    points.iter().fold(
+        RegionBounds { north: f64::NEG_INFINITY, south: f64::INFINITY, east: f64::NEG_INFINITY, west: f64::INFINITY },
+        |bounds, point| RegionBounds {
+            north: bounds.north.max(point.x),
+            south: bounds.south.min(point.x),
+            east: bounds.east.max(point.y),
+            west: bounds.west.min(point.y),
+        },
+    )
+}
+
+/// Typical absolute error of a quantity in canonical units, whatever its magnitude
+fn quantity_sigma(quantity: &str) -> f64 {
+    match quantity {
+        "temperature" => 0.5,
+        "humidity" => 3.0,
+        "pressure" => 0.5,
+        "wind_speed" => 0.5,
+        "soil_moisture" => 0.03,
+        "precipitation_rate" => 0.5,
+        "solar_radiation" => 20.0,
+        // Vegetation indices and reflectances
+        _ => 0.02,
+    }
+}
+
+/// Standard deviation of a real value, growing as the signal to noise ratio falls below the reference
+fn real_value_sigma(real: &RealObservation, quantity: &str) -> f64 {
+    quantity_sigma(quantity) * (REFERENCE_SIGNAL_TO_NOISE / real.quality_indicators.signal_to_noise_ratio.max(1.0)).max(1.0)
+}
+
+/// Standard deviation of a phantom value, growing as its confidence falls
+fn phantom_value_sigma(phantom: &PhantomObservation, quantity: &str) -> f64 {
+    PHANTOM_SIGMA_RATIO * quantity_sigma(quantity) / phantom.confidence.clamp(0.05, 1.0)
+}
+
+/// Worse of the phantom confidence and the real observation's clear-sky quality
+fn observation_quality(phantom: &PhantomObservation, real: &RealObservation) -> f64 {
+    let quality = &real.quality_indicators;
+    let real_quality = 0.5 * (quality.geometric_quality + quality.radiometric_quality) * (1.0 - quality.cloud_coverage).clamp(0.0, 1.0);
+    phantom.confidence.min(real_quality)
 }
 
 impl AgriculturalAlignmentEnhancer {
+    /// Harvest windows default to the US corn belt in the north and to southern
+    /// Africa in the south: rain-fed maize and soybeans from April to June,
+    /// irrigated winter wheat in September and October
     pub fn new() -> Self {
         Self {
             crop_specific_aligners: HashMap::new(),
@@ -687,13 +1948,41 @@ impl AgriculturalAlignmentEnhancer {
                 irrigation_cycles: HashMap::new(),
                 pattern_detection_window: 7.0,
                 alignment_boost_during_irrigation: 1.3,
+                min_moisture_rise: 0.04,
+                rain_lookback_hours: 24.0,
             },
             harvest_timing_aligner: HarvestTimingAligner {
                 crop_harvest_windows: HashMap::new(),
-                harvest_detection_algorithms: Vec::new(),
+                harvest_detection_algorithms: vec![HarvestDetectionAlgorithm {
+                    algorithm_name: "ndvi_green_up_and_drop".to_string(),
+                    detection_bands: vec!["red".to_string(), "nir".to_string()],
+                    threshold_values: vec![0.2, 0.2, 0.4, 20.0],
+                    confidence_calibration: 0.9,
+                }],
                 pre_harvest_monitoring_boost: 1.5,
             },
+            field_histories: HashMap::new(),
+            field_cell_m: 100.0,
+            history_days: 400.0,
         }
+        .with_harvest_window("corn", Hemisphere::Northern, 260.0, 310.0)
+        .with_harvest_window("soybeans", Hemisphere::Northern, 255.0, 295.0)
+        .with_harvest_window("wheat", Hemisphere::Northern, 180.0, 220.0)
+        .with_harvest_window("corn", Hemisphere::Southern, 90.0, 170.0)
+        .with_harvest_window("soybeans", Hemisphere::Southern, 95.0, 150.0)
+        .with_harvest_window("wheat", Hemisphere::Southern, 245.0, 300.0)
+    }
+    
+    /// Set a crop's harvest window in a hemisphere in days of year; `start_day`
+    /// after `end_day` wraps past the year end
+    pub fn with_harvest_window(mut self, crop: &str, hemisphere: Hemisphere, start_day: f64, end_day: f64) -> Self {
+        self.harvest_timing_aligner.crop_harvest_windows.insert((crop.to_string(), hemisphere), HarvestWindow {
+            optimal_start_day: start_day,
+            optimal_end_day: end_day,
+            quality_degradation_rate: 0.01,
+            weather_risk_factors: Vec::new(),
+        });
+        self
     }
     
     pub fn compute_agricultural_alignment_boost(&self,
@@ -717,15 +2006,1002 @@ impl AgriculturalAlignmentEnhancer {
         priorities
     }
     
-    pub fn get_seasonal_factors(&self, _target_time: f64) -> Vec<f64> {
-        vec![1.0, 0.8, 1.2, 0.9] // Seasonal factors for alignment
+    /// One factor per crop with a harvest window, in crop order: the pre-harvest
+    /// boost from a month before the window at `latitude` to its end, 1 otherwise,
+    /// modulated by the adjuster of the meteorological season there
+    pub fn get_seasonal_factors(&self, target_time: f64, latitude: f64) -> Vec<f64> {
+        let day = day_of_year(target_time);
+        let season = meteorological_season(day, latitude);
+        let modulation = self.seasonal_alignment_adjusters.get(season).map_or(1.0, |adjuster| adjuster.confidence_modulation);
+        
+        let aligner = &self.harvest_timing_aligner;
+        let factors: Vec<f64> = aligner.crops()
+            .into_iter()
+            .filter_map(|crop| aligner.harvest_window(crop, latitude))
+            .map(|(start, end)| {
+                let pre_harvest = in_day_window(day, start - 30.0, end);
+                modulation * if pre_harvest { aligner.pre_harvest_monitoring_boost } else { 1.0 }
+            })
+            .collect();
+        if factors.is_empty() { vec![modulation] } else { factors }
+    }
+    
+    /// Farm activity up to `target_time` on each field in the alignment, in time order
+    ///
+    /// An alignment covers only the scenes near its target time, so each region's
+    /// real observations are added to the history of the field containing them
+    /// and the detectors run on that history.
+    pub fn extract_agricultural_insights(&mut self,
+                                       alignment_results: &[RegionalAlignment],
+                                       target_time: f64) -> Result<Vec<AgriculturalInsight>, AppError> {
+        let cutoff = target_time - self.history_days * SECONDS_PER_DAY;
+        let mut fields = Vec::new();
+        for region in alignment_results {
+            let Some(series) = FieldTimeSeries::from_alignment(region) else { continue };
+            let field_id = self.field_id(&series.location);
+            self.field_histories
+                .entry(field_id.clone())
+                .or_insert_with(|| FieldTimeSeries::new(&field_id, series.location))
+                .merge(&series);
+            if !fields.contains(&field_id) {
+                fields.push(field_id);
+            }
+        }
+        for history in self.field_histories.values_mut() {
+            history.retain_since(cutoff);
+        }
+        self.field_histories.retain(|_, history| !history.is_empty());
+        
+        let mut insights = Vec::new();
+        for series in fields.iter().filter_map(|field_id| self.field_histories.get(field_id)) {
+            insights.extend(
+                self.detect_field_events(series)
+                    .into_iter()
+                    .filter(|event| event.timestamp <= target_time)
+                    .map(|event| event.to_insight(series.location))
+            );
+        }
+        insights.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+        Ok(insights)
+    }
+    
+    /// Identifier of the `field_cell_m` grid cell containing `location`
+    fn field_id(&self, location: &Point3<f64>) -> String {
+        let cell = self.field_cell_m.max(1.0) / METERS_PER_DEGREE;
+        format!("field@{:.5},{:.5}", (location.x / cell).floor() * cell, (location.y / cell).floor() * cell)
+    }
+    
+    /// Irrigation, sowing, emergence and harvest events of one field in time order
+    pub fn detect_field_events(&self, series: &FieldTimeSeries) -> Vec<FieldEvent> {
+        let mut events = self.irrigation_pattern_matcher.detect_irrigation_events(series);
+        events.extend(self.harvest_timing_aligner.detect_phenology_events(series));
+        events.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+        events
+    }
+}
+
+impl IrrigationPatternMatcher {
+    /// Abrupt soil moisture rises that rainfall does not account for
+    ///
+    /// A rise counts when consecutive samples within the detection window gain at
+    /// least `min_moisture_rise`. Rain from the lookback before the first sample
+    /// to the second is compared with the water the rise implies over the probe
+    /// depth: rises it mostly explains are dropped, otherwise the explained share
+    /// lowers the confidence. Events spaced like the field's or crop's expected
+    /// cycle are boosted.
+    pub fn detect_irrigation_events(&self, series: &FieldTimeSeries) -> Vec<FieldEvent> {
+        let moisture = time_ordered(&series.soil_moisture);
+        let window = self.pattern_detection_window * SECONDS_PER_DAY;
+        let lookback = self.rain_lookback_hours * 3600.0;
+        let expected_cycle = self.irrigation_cycles
+            .get(&series.field_id)
+            .or_else(|| series.crop_type.as_ref().and_then(|crop| self.irrigation_cycles.get(crop)));
+        
+        let mut events: Vec<FieldEvent> = Vec::new();
+        for pair in moisture.windows(2) {
+            let ((t0, m0), (t1, m1)) = (pair[0], pair[1]);
+            let (gap, rise) = (t1 - t0, m1 - m0);
+            if gap <= 0.0 || gap > window || rise < self.min_moisture_rise {
+                continue;
+            }
+            
+            let applied_mm = rise * series.soil_moisture_depth_cm * 10.0;
+            let rain_mm: f64 = series.precipitation
+                .iter()
+                .filter(|(t, _)| *t >= t0 - lookback && *t <= t1)
+                .map(|(_, mm)| mm)
+                .sum();
+            let rain_share = (rain_mm / applied_mm).min(1.0);
+            if rain_share >= 0.5 {
+                continue;
+            }
+            
+            let strength = 0.5 + 0.5 * (rise / self.min_moisture_rise - 1.0).min(1.0);
+            let abruptness = 0.5 + 0.5 * (-gap / window).exp();
+            let mut confidence = strength * abruptness * (1.0 - rain_share);
+            let timestamp = 0.5 * (t0 + t1);
+            if let (Some(cycle), Some(previous)) = (expected_cycle, events.last()) {
+                let interval_days = (timestamp - previous.timestamp) / SECONDS_PER_DAY;
+                if (interval_days - cycle.cycle_period_days).abs() <= 0.25 * cycle.cycle_period_days {
+                    confidence = (confidence * self.alignment_boost_during_irrigation).min(1.0);
+                }
+            }
+            
+            events.push(FieldEvent {
+                field_id: series.field_id.clone(),
+                activity: FarmActivity::Irrigation,
+                timestamp,
+                time_uncertainty: 0.5 * gap,
+                confidence,
+                magnitude: applied_mm,
+            });
+        }
+        events
+    }
+}
+
+impl Hemisphere {
+    pub fn of_latitude(latitude: f64) -> Self {
+        if latitude < 0.0 { Hemisphere::Southern } else { Hemisphere::Northern }
+    }
+    
+    fn opposite(self) -> Self {
+        match self {
+            Hemisphere::Northern => Hemisphere::Southern,
+            Hemisphere::Southern => Hemisphere::Northern,
+        }
+    }
+}
+
+impl HarvestTimingAligner {
+    /// Crops with a harvest window in either hemisphere, in name order
+    pub fn crops(&self) -> Vec<&str> {
+        let mut crops: Vec<&str> = self.crop_harvest_windows.keys().map(|(crop, _)| crop.as_str()).collect();
+        crops.sort_unstable();
+        crops.dedup();
+        crops
     }
     
-    pub fn extract_agricultural_insights(&self,
-                                       _alignment_results: &[RegionalAlignment],
-                                       _target_time: f64) -> Result<Vec<AgriculturalInsight>, AppError> {
-        Ok(Vec::new()) // Placeholder
+    /// First and last day of year of `crop`'s harvest at `latitude`: the window of
+    /// its hemisphere, else the other hemisphere's shifted by half a year
+    pub fn harvest_window(&self, crop: &str, latitude: f64) -> Option<(f64, f64)> {
+        let hemisphere = Hemisphere::of_latitude(latitude);
+        let window = |hemisphere| self.crop_harvest_windows.get(&(crop.to_string(), hemisphere));
+        match (window(hemisphere), window(hemisphere.opposite())) {
+            (Some(window), _) => Some((window.optimal_start_day, window.optimal_end_day)),
+            (None, Some(window)) => Some((window.optimal_start_day + DAYS_PER_YEAR / 2.0, window.optimal_end_day + DAYS_PER_YEAR / 2.0)),
+            (None, None) => None,
+        }
+    }
+    
+    /// Sowing, emergence and harvest of each season in the NDVI series
+    ///
+    /// Every detection algorithm runs on the series; where several report the
+    /// same activity within the sowing-to-emergence delay the most confident is
+    /// kept. Harvests outside the crop's window lose half their confidence.
+    pub fn detect_phenology_events(&self, series: &FieldTimeSeries) -> Vec<FieldEvent> {
+        let ndvi = time_ordered(&series.ndvi);
+        let harvest_window = series.crop_type.as_ref().and_then(|crop| self.harvest_window(crop, series.location.x));
+        
+        let mut events: Vec<FieldEvent> = Vec::new();
+        for algorithm in &self.harvest_detection_algorithms {
+            for mut event in algorithm.detect(&series.field_id, &ndvi) {
+                if let (FarmActivity::Harvest, Some((start, end))) = (event.activity, harvest_window) {
+                    if !in_day_window(day_of_year(event.timestamp), start - HARVEST_WINDOW_SLACK_DAYS, end + HARVEST_WINDOW_SLACK_DAYS) {
+                        event.confidence *= 0.5;
+                    }
+                }
+                let duplicate = events.iter_mut().find(|existing| {
+                    existing.activity == event.activity
+                        && (existing.timestamp - event.timestamp).abs() < SOWING_TO_EMERGENCE_DAYS * SECONDS_PER_DAY
+                });
+                match duplicate {
+                    Some(existing) if event.confidence > existing.confidence => *existing = event,
+                    Some(_) => {},
+                    None => events.push(event),
+                }
+            }
+        }
+        events
+    }
+}
+
+impl HarvestDetectionAlgorithm {
+    fn threshold(&self, index: usize, default: f64) -> f64 {
+        self.threshold_values.get(index).copied().unwrap_or(default)
+    }
+    
+    /// Phenology events of a time-ordered NDVI series
+    ///
+    /// The series is median-filtered over three scenes so that a single
+    /// cloud-depressed scene neither splits a season nor fakes a harvest. Seasons
+    /// are runs above the middle of the series range, measured from the lower of
+    /// the minima either side. Emergence is where the green-up crosses the
+    /// emergence fraction of the amplitude and sowing precedes it by the typical
+    /// delay. Harvest is the largest scene-to-scene drop after the peak if it
+    /// removes at least the harvest fraction; gradual senescence is not a harvest.
+    pub fn detect(&self, field_id: &str, ndvi: &[(f64, f64)]) -> Vec<FieldEvent> {
+        let min_amplitude = self.threshold(0, 0.2);
+        let emergence_fraction = self.threshold(1, 0.2);
+        let drop_fraction = self.threshold(2, 0.4);
+        let max_gap = self.threshold(3, 20.0) * SECONDS_PER_DAY;
+        if ndvi.len() < 3 {
+            return Vec::new();
+        }
+        
+        let times: Vec<f64> = ndvi.iter().map(|(t, _)| *t).collect();
+        let smoothed = median_filter3(&ndvi.iter().map(|(_, v)| *v).collect::<Vec<_>>());
+        let low = smoothed.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = smoothed.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if high - low < min_amplitude {
+            return Vec::new();
+        }
+        
+        let green = 0.5 * (low + high);
+        let mut runs = Vec::new();
+        let mut run_start = None;
+        for (i, value) in smoothed.iter().enumerate() {
+            match (*value >= green, run_start) {
+                (true, None) => run_start = Some(i),
+                (false, Some(start)) => {
+                    runs.push((start, i - 1));
+                    run_start = None;
+                },
+                _ => {},
+            }
+        }
+        if let Some(start) = run_start {
+            runs.push((start, smoothed.len() - 1));
+        }
+        
+        let lowest = |range: std::ops::RangeInclusive<usize>| range.min_by(|&a, &b| smoothed[a].total_cmp(&smoothed[b])).unwrap();
+        let timing = |gap: f64| (-gap / (30.0 * SECONDS_PER_DAY)).exp();
+        let event = |activity, timestamp, time_uncertainty, confidence, magnitude| FieldEvent {
+            field_id: field_id.to_string(),
+            activity,
+            timestamp,
+            time_uncertainty,
+            confidence,
+            magnitude,
+        };
+        
+        let mut events = Vec::new();
+        let mut previous_end = 0;
+        for (index, &(start, end)) in runs.iter().enumerate() {
+            let next_start = runs.get(index + 1).map_or(smoothed.len() - 1, |run| run.0);
+            let peak = (start..=end).max_by(|&a, &b| smoothed[a].total_cmp(&smoothed[b])).unwrap();
+            let trough = lowest(previous_end..=start);
+            let base = smoothed[trough].min(smoothed[lowest(end..=next_start)]);
+            let amplitude = smoothed[peak] - base;
+            previous_end = end;
+            if amplitude < min_amplitude {
+                continue;
+            }
+            let amplitude_score = (amplitude / (2.0 * min_amplitude)).min(1.0);
+            
+            let level = base + emergence_fraction * amplitude;
+            if smoothed[trough] < level {
+                if let Some(i) = (trough + 1..=start).find(|&i| smoothed[i] >= level) {
+                    let gap = times[i] - times[i - 1];
+                    let fraction = (level - smoothed[i - 1]) / (smoothed[i] - smoothed[i - 1]);
+                    let emergence = times[i - 1] + fraction * gap;
+                    let confidence = self.confidence_calibration * amplitude_score * timing(gap);
+                    let lag = SOWING_TO_EMERGENCE_DAYS * SECONDS_PER_DAY;
+                    events.push(event(FarmActivity::Sowing, emergence - lag, 0.5 * (lag + gap), 0.6 * confidence, amplitude));
+                    events.push(event(FarmActivity::Emergence, emergence, 0.5 * gap, confidence, amplitude));
+                }
+            }
+            
+            let largest_drop = (peak..next_start)
+                .filter(|&i| times[i + 1] - times[i] <= max_gap)
+                .map(|i| (i, smoothed[i] - smoothed[i + 1]))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((i, drop)) = largest_drop.filter(|(_, drop)| *drop >= drop_fraction * amplitude) {
+                let gap = times[i + 1] - times[i];
+                let sharpness = (drop / amplitude).min(1.0);
+                let confidence = self.confidence_calibration * amplitude_score * (0.5 + 0.5 * sharpness) * timing(gap);
+                events.push(event(FarmActivity::Harvest, 0.5 * (times[i] + times[i + 1]), 0.5 * gap, confidence, -drop));
+            }
+        }
+        events
     }
 }
 
-use super::phantom_reality_4d::AgriculturalInsight; 
\ No newline at end of file
+impl FieldTimeSeries {
+    pub fn new(field_id: &str, location: Point3<f64>) -> Self {
+        Self {
+            field_id: field_id.to_string(),
+            location,
+            crop_type: None,
+            ndvi: Vec::new(),
+            soil_moisture: Vec::new(),
+            soil_moisture_depth_cm: 10.0,
+            precipitation: Vec::new(),
+        }
+    }
+    
+    /// Series of an aligned region from its real observations; phantom values
+    /// are interpolations of the same scenes and are left out
+    pub fn from_alignment(region: &RegionalAlignment) -> Option<Self> {
+        let mut seen = HashSet::new();
+        let observations: Vec<&RealObservation> = region.phantom_real_pairs
+            .iter()
+            .map(|(_, real, _)| real)
+            .filter(|real| seen.insert(real.observation_id.as_str()))
+            .collect();
+        if observations.is_empty() {
+            return None;
+        }
+        
+        let centroid = observations.iter().fold(Vector3::zeros(), |sum, real| sum + real.location.coords) / observations.len() as f64;
+        let mut series = Self::new(&region.region_id, Point3::from(centroid));
+        for real in observations {
+            if series.crop_type.is_none() {
+                series.crop_type = real.agricultural_context.as_ref().map(|context| context.crop_type.clone());
+            }
+            series.push(real.timestamp, &real.sensor_data, real.quality_indicators.cloud_coverage);
+        }
+        Some(series)
+    }
+    
+    /// Add the samples of `other` not already present at the same time
+    pub fn merge(&mut self, other: &FieldTimeSeries) {
+        fn extend(samples: &mut Vec<(f64, f64)>, new: &[(f64, f64)]) {
+            for &(timestamp, value) in new {
+                if !samples.iter().any(|(t, _)| *t == timestamp) {
+                    samples.push((timestamp, value));
+                }
+            }
+        }
+        if self.crop_type.is_none() {
+            self.crop_type = other.crop_type.clone();
+        }
+        if !other.soil_moisture.is_empty() {
+            self.soil_moisture_depth_cm = other.soil_moisture_depth_cm;
+        }
+        extend(&mut self.ndvi, &other.ndvi);
+        extend(&mut self.soil_moisture, &other.soil_moisture);
+        extend(&mut self.precipitation, &other.precipitation);
+    }
+    
+    /// Drop samples before `cutoff`
+    pub fn retain_since(&mut self, cutoff: f64) {
+        for samples in [&mut self.ndvi, &mut self.soil_moisture, &mut self.precipitation] {
+            samples.retain(|(t, _)| *t >= cutoff);
+        }
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.ndvi.is_empty() && self.soil_moisture.is_empty() && self.precipitation.is_empty()
+    }
+    
+    /// Add a measurement to the series it belongs to; other quantities are ignored
+    pub fn push(&mut self, timestamp: f64, value: &MeasurementValue, cloud_coverage: f64) {
+        match value {
+            MeasurementValue::SoilMoisture { value, depth_cm, .. } => {
+                // Probes report either a fraction or a percentage
+                let content = if *value > 1.0 { value / 100.0 } else { *value };
+                self.soil_moisture.push((timestamp, content));
+                if *depth_cm > 0.0 {
+                    self.soil_moisture_depth_cm = *depth_cm;
+                }
+            },
+            MeasurementValue::Precipitation { accumulation, .. } => self.precipitation.push((timestamp, accumulation.max(0.0))),
+            other if cloud_coverage <= MAX_NDVI_CLOUD_COVER => {
+                if let Some(ndvi) = ndvi_from_measurement(other) {
+                    self.ndvi.push((timestamp, ndvi));
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+impl FieldEvent {
+    pub fn to_insight(&self, location: Point3<f64>) -> AgriculturalInsight {
+        let (impact_score, action) = match self.activity {
+            FarmActivity::Irrigation => (
+                (self.magnitude / IRRIGATION_APPLICATION_MM).min(1.0),
+                "Check the application against the field's water-use record",
+            ),
+            FarmActivity::Sowing => (self.magnitude.min(1.0), "Confirm the sowing date in the field log"),
+            FarmActivity::Emergence => (self.magnitude.min(1.0), "Compare emergence with the sowing date to assess stand establishment"),
+            FarmActivity::Harvest => (self.magnitude.abs().min(1.0), "Confirm the harvest date in the field log"),
+        };
+        AgriculturalInsight {
+            insight_type: InsightType::FarmActivity { activity: self.activity, magnitude: self.magnitude },
+            location,
+            timestamp: self.timestamp,
+            confidence: self.confidence,
+            impact_score,
+            recommended_actions: vec![action.to_string()],
+        }
+    }
+}
+
+fn day_of_year(timestamp: f64) -> f64 {
+    DateTime::<Utc>::from_timestamp(timestamp.floor() as i64, 0).unwrap_or_default().ordinal() as f64
+}
+
+/// Whether `day` of year lies in the window from `start` to `end`, which may wrap past the year end
+fn in_day_window(day: f64, start: f64, end: f64) -> bool {
+    (day - start).rem_euclid(DAYS_PER_YEAR) <= (end - start).rem_euclid(DAYS_PER_YEAR)
+}
+
+/// Meteorological season of a day of year, reversed south of the equator
+fn meteorological_season(day: f64, latitude: f64) -> &'static str {
+    let northern = ((day + 31.0) / 91.3125).floor() as usize;
+    let index = if latitude < 0.0 { northern + 2 } else { northern };
+    ["winter", "spring", "summer", "autumn"][index % 4]
+}
+
+/// Mean latitude of a region's paired real observations
+fn region_latitude(region: &RegionalAlignment) -> Option<f64> {
+    let pairs = &region.phantom_real_pairs;
+    (!pairs.is_empty()).then(|| pairs.iter().map(|(_, real, _)| real.location.x).sum::<f64>() / pairs.len() as f64)
+}
+
+fn time_ordered(samples: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut ordered = samples.to_vec();
+    ordered.sort_by(|a, b| a.0.total_cmp(&b.0));
+    ordered
+}
+
+/// Running median over three samples, ends kept
+fn median_filter3(values: &[f64]) -> Vec<f64> {
+    (0..values.len())
+        .map(|i| {
+            if i == 0 || i + 1 == values.len() {
+                return values[i];
+            }
+            let mut window = [values[i - 1], values[i], values[i + 1]];
+            window.sort_by(f64::total_cmp);
+            window[1]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_fusion::PrecipitationType;
+
+    fn celsius(value: f64) -> MeasurementValue {
+        MeasurementValue::Temperature { value, scale: TemperatureScale::Celsius, sensor_id: "t".to_string() }
+    }
+
+    fn phantom(satellite: &str, time: f64, lat: f64, lon: f64, value: MeasurementValue) -> PhantomObservation {
+        PhantomObservation {
+            phantom_satellite_id: satellite.to_string(),
+            phantom_time: time,
+            location: Point3::new(lat, lon, 0.0),
+            value,
+            confidence: 0.8,
+            interpolation_methods: InterpolationWeights {
+                temporal_weight: 0.25,
+                spatial_weight: 0.25,
+                seasonal_weight: 0.25,
+                physics_weight: 0.25,
+                total_confidence: 0.8,
+            },
+            agricultural_relevance: 0.6,
+        }
+    }
+
+    fn real(time: f64, lat: f64, lon: f64, value: MeasurementValue) -> RealObservation {
+        RealObservation {
+            observation_id: format!("{time}-{lat}"),
+            timestamp: time,
+            location: Point3::new(lat, lon, 0.0),
+            sensor_data: value,
+            quality_indicators: ObservationQuality {
+                signal_to_noise_ratio: 100.0,
+                cloud_coverage: 0.0,
+                atmospheric_interference: 0.0,
+                geometric_quality: 0.9,
+                radiometric_quality: 0.9,
+            },
+            atmospheric_conditions: AtmosphericConditions {
+                water_vapor: 2.0,
+                aerosol_optical_depth: 0.1,
+                atmospheric_pressure: 1010.0,
+                temperature_profile: Vec::new(),
+            },
+            agricultural_context: None,
+        }
+    }
+
+    fn region(id: &str, pairs: Vec<(PhantomObservation, RealObservation, f64)>) -> RegionalAlignment {
+        RegionalAlignment {
+            region_id: id.to_string(),
+            phantom_real_pairs: pairs,
+            alignment_quality: 0.8,
+            consistency_metrics: ConsistencyMetrics {
+                spatial_consistency: 0.8,
+                temporal_consistency: 0.8,
+                spectral_consistency: 0.8,
+                agricultural_consistency: 0.8,
+            },
+            agricultural_enhancement_score: 0.8,
+        }
+    }
+
+    #[test]
+    fn test_bundle_recovers_phantom_bias_and_registration() {
+        // The phantom reads 1.5 °C warm and places footprints 111 m north and 70 m west
+        let (bias, north_deg, west_deg) = (1.5, 0.001, 0.0008);
+        let mut rng = StdRng::seed_from_u64(3);
+        let regions: Vec<RegionalAlignment> = (0..4)
+            .map(|r| {
+                let pairs = (0..5)
+                    .map(|i| {
+                        let (lat, lon) = (-19.3 + 0.1 * r as f64 + 0.002 * i as f64, 31.4 + 0.003 * i as f64);
+                        let truth = 18.0 + 3.0 * r as f64;
+                        let noise = |rng: &mut StdRng| 0.05 * rng.sample::<f64, _>(StandardNormal);
+                        let time = 1_700_000_000.0 + 60.0 * i as f64;
+                        (
+                            phantom("phantom-a", time + 600.0, lat + north_deg, lon - west_deg, celsius(truth + bias + noise(&mut rng))),
+                            real(time, lat, lon, celsius(truth + noise(&mut rng))),
+                            0.8,
+                        )
+                    })
+                    .collect();
+                region(&format!("field-{r}"), pairs)
+            })
+            .collect();
+        let objective = AlignmentObjective::MaximizeConsistency;
+
+        let mut optimizer = AlignmentOptimizer::new();
+        let global = optimizer.optimize_global_alignment(&regions, &objective).unwrap();
+        assert!(global.optimization_convergence);
+        let correction = &global.phantom_corrections["phantom-a"];
+        assert!((correction.value_biases["temperature"] - bias).abs() < 0.1);
+        assert!((correction.latitude_offset_deg - north_deg).abs() * METERS_PER_DEGREE < 1.0);
+        assert!((correction.longitude_offset_deg + west_deg).abs() * METERS_PER_DEGREE < 1.0);
+        assert!((global.region_states["field-2"]["temperature"] - 24.0).abs() < 0.2);
+        assert_eq!(global.regional_residuals.len(), 4);
+        for residuals in &global.regional_residuals {
+            assert_eq!(residuals.pair_count, 5);
+            assert!(residuals.value_rms < 0.2 && residuals.spatial_rms_m < 1.0);
+            assert!((residuals.temporal_rms_s - 600.0).abs() < 1e-9);
+        }
+        assert!(global.final_cost < 0.05 * global.initial_cost);
+
+        // Every backend reaches the least-squares optimum
+        let least_squares_cost = global.final_cost;
+        for algorithm in [
+            OptimizationAlgorithm::GradientDescent { learning_rate: 0.1 },
+            OptimizationAlgorithm::ParticleSwarm { num_particles: 30, inertia_weight: 0.7 },
+            OptimizationAlgorithm::SimulatedAnnealing { initial_temperature: 10.0, cooling_rate: 0.995 },
+            OptimizationAlgorithm::GeneticAlgorithm { population_size: 40, mutation_rate: 0.1 },
+            OptimizationAlgorithm::AgriculturalHybrid {
+                base_algorithm: Box::new(OptimizationAlgorithm::Hungarian),
+                agricultural_boost_factor: 1.0,
+            },
+        ] {
+            optimizer.optimization_algorithm = algorithm;
+            let global = optimizer.optimize_global_alignment(&regions, &objective).unwrap();
+            assert!(global.final_cost < 1.05 * least_squares_cost + 0.5, "{:?}: {}", optimizer.optimization_algorithm, global.final_cost);
+            assert!((global.phantom_corrections["phantom-a"].value_biases["temperature"] - bias).abs() < 0.2);
+        }
+    }
+
+    #[test]
+    fn test_adaptive_spatial_constraint_bounds_registered_distance() {
+        // Least squares would register the phantom 1.33 km north, leaving the
+        // first pair 2.67 km from its real observation
+        let km = 1000.0 / METERS_PER_DEGREE;
+        let pairs = [4.0, 0.0, 0.0]
+            .iter()
+            .map(|north| (phantom("phantom-a", 0.0, north * km, 0.0, celsius(20.0)), real(0.0, 0.0, 0.0, celsius(20.0)), 0.8))
+            .collect();
+        let regions = vec![region("field", pairs)];
+        let solve = |enforcement_level| {
+            let optimizer = AlignmentOptimizer {
+                constraint_handlers: vec![ConstraintHandler {
+                    constraint_type: ConstraintType::SpatialProximity { max_distance: 2000.0 },
+                    penalty_function: PenaltyFunction::Quadratic,
+                    enforcement_level,
+                }],
+                ..AlignmentOptimizer::new()
+            };
+            optimizer.optimize_global_alignment(&regions, &AlignmentObjective::MaximizeCoverage).unwrap()
+        };
+
+        let soft = solve(EnforcementLevel::Soft);
+        let offset_km = soft.phantom_corrections["phantom-a"].latitude_offset_deg / km;
+        assert!((offset_km - 4.0 / 3.0).abs() < 0.05);
+        assert!(soft.regional_residuals[0].constraint_penalty > 0.05);
+
+        for level in [EnforcementLevel::Adaptive, EnforcementLevel::Hard] {
+            let constrained = solve(level);
+            let offset_km = constrained.phantom_corrections["phantom-a"].latitude_offset_deg / km;
+            assert!((offset_km - 2.0).abs() < 0.01, "offset {offset_km}");
+        }
+    }
+
+    #[test]
+    fn test_constraint_residuals_are_smooth_for_a_pair_on_the_limit() {
+        // The phantom footprint sits exactly 2 km north of its real observation
+        let km = 1000.0 / METERS_PER_DEGREE;
+        let regions = vec![region("field", vec![(phantom("phantom-a", 0.0, 2.0 * km, 0.0, celsius(20.0)), real(0.0, 0.0, 0.0, celsius(20.0)), 0.8)])];
+        let optimizer_with = |penalty_function| AlignmentOptimizer {
+            constraint_handlers: vec![ConstraintHandler {
+                constraint_type: ConstraintType::SpatialProximity { max_distance: 2000.0 },
+                penalty_function,
+                enforcement_level: EnforcementLevel::Soft,
+            }],
+            ..AlignmentOptimizer::new()
+        };
+        let objective = AlignmentObjective::MaximizeCoverage;
+
+        // The quadratic penalty's residual is a hinge: zero on the limit, with
+        // a central difference halfway between its one-sided slopes
+        let optimizer = optimizer_with(PenaltyFunction::Quadratic);
+        let bundle = AlignmentBundle::new(&optimizer, &regions, &objective, 1.0);
+        // After the pair's two value and two registration residuals
+        let constraint_row = 4;
+        let residuals = bundle.residuals(&bundle.initial, 1.0);
+        assert!(residuals[constraint_row].abs() < 1e-9);
+        let slope = bundle.jacobian(&bundle.initial, 1.0)[(constraint_row, bundle.pairs[0].latitude_offset)];
+        assert!((slope + 0.5 * METERS_PER_DEGREE / 2000.0).abs() < 1.0, "slope {slope}");
+        let global = optimizer.optimize_global_alignment(&regions, &objective).unwrap();
+        assert!(global.optimization_convergence);
+        assert!(global.regional_residuals[0].constraint_penalty < 1e-9);
+
+        // Step and exponential penalties are left to the derivative-free backends
+        for penalty_function in [PenaltyFunction::Step, PenaltyFunction::Exponential] {
+            let mut optimizer = optimizer_with(penalty_function);
+            for algorithm in [
+                OptimizationAlgorithm::Hungarian,
+                OptimizationAlgorithm::GradientDescent { learning_rate: 0.1 },
+                OptimizationAlgorithm::AgriculturalHybrid {
+                    base_algorithm: Box::new(OptimizationAlgorithm::Hungarian),
+                    agricultural_boost_factor: 1.0,
+                },
+            ] {
+                optimizer.optimization_algorithm = algorithm;
+                assert!(optimizer.optimize_global_alignment(&regions, &objective).is_err());
+            }
+            optimizer.optimization_algorithm = OptimizationAlgorithm::ParticleSwarm { num_particles: 20, inertia_weight: 0.7 };
+            assert!(optimizer.optimize_global_alignment(&regions, &objective).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_validators_and_assignment() {
+        let plausibility = PhysicalPlausibilityValidator::default();
+        let agreement = CrossSensorAgreementValidator;
+        let at = |value| (phantom("p", 3600.0, 0.0, 0.0, value), real(0.0, 0.0, 0.0, celsius(20.0)));
+
+        let (fahrenheit, observed) = at(MeasurementValue::Temperature { value: 68.0, scale: TemperatureScale::Fahrenheit, sensor_id: "t".to_string() });
+        assert_eq!(plausibility.validate_consistency(&fahrenheit, &observed), 1.0);
+        assert!(agreement.validate_consistency(&fahrenheit, &observed) > 0.99);
+
+        // About a sigma apart agrees less; 35 °C warmer an hour later is not weather
+        let (warm, observed) = at(celsius(21.5));
+        let score = agreement.validate_consistency(&warm, &observed);
+        assert!(score > 0.3 && score < 0.9, "{score}");
+        let (hot, observed) = at(celsius(55.0));
+        assert!(plausibility.validate_consistency(&hot, &observed) < 0.2);
+        assert!(agreement.validate_consistency(&hot, &observed) < 1e-6);
+        let (impossible, observed) = at(celsius(75.0));
+        assert_eq!(plausibility.validate_consistency(&impossible, &observed), 0.0);
+        let (humidity, observed) = at(MeasurementValue::Humidity(60.0));
+        assert_eq!(agreement.validate_consistency(&humidity, &observed), 1.0);
+
+        // Greedy pairing would take 0.9 first and leave row 1 unpaired
+        let scores = DMatrix::from_row_slice(3, 2, &[0.9, 0.8, 0.85, 0.1, 0.2, 0.2]);
+        assert_eq!(hungarian_assignment(&scores.map(|score| 1.0 - score)), vec![(0, 1), (1, 0)]);
+    }
+
+    fn phantom_satellite(id: &str, observations: Vec<InterpolatedObservation>) -> PhantomSatellite {
+        PhantomSatellite {
+            real_satellite_id: "sentinel-2a".to_string(),
+            phantom_instance_id: id.to_string(),
+            virtual_orbit: PredictedOrbit {
+                orbital_elements: OrbitalElements {
+                    semi_major_axis: 7_164_000.0,
+                    eccentricity: 0.0,
+                    inclination: 98.6_f64.to_radians(),
+                    longitude_of_ascending_node: 0.0,
+                    argument_of_perigee: 0.0,
+                    mean_anomaly: 0.0,
+                    mean_motion: 1.05e-3,
+                    epoch: 0.0,
+                },
+                perturbation_model: PerturbationModel {
+                    j2_perturbation: J2Perturbation { delta_raan: 0.0, delta_arg_perigee: 0.0, delta_mean_anomaly: 0.0 },
+                    atmospheric_drag: AtmosphericDragModel {
+                        drag_coefficient: 2.2,
+                        atmospheric_density_model: Box::new(SimpleAtmosphericModel::default()),
+                        cross_sectional_area: 10.0,
+                        satellite_mass: 1000.0,
+                    },
+                    solar_radiation_pressure: SolarRadiationPressureModel {
+                        solar_constant: 1361.0,
+                        reflectivity_coefficient: 1.2,
+                        cross_sectional_area: 10.0,
+                        satellite_mass: 1000.0,
+                    },
+                    gravitational_anomalies: Vec::new(),
+                },
+                prediction_accuracy: 0.9,
+                ephemeris_table: Vec::new(),
+                ground_track_predictions: Vec::new(),
+                propagator: None,
+            },
+            interpolated_observations: observations,
+            confidence_decay_model: ConfidenceDecayModel {
+                initial_confidence: 0.95,
+                decay_rate: 0.1,
+                minimum_confidence: 0.1,
+                decay_function: DecayFunction::Exponential { lambda: 0.1 },
+                environmental_factors: HashMap::new(),
+                agricultural_factors: HashMap::new(),
+            },
+            last_real_observation: 0.0,
+            prediction_horizon: 16.0 * SECONDS_PER_DAY,
+            agricultural_focus_regions: Vec::new(),
+        }
+    }
+
+    /// Phantom reading covering a kilometre around (`lat`, `lon`)
+    fn interpolated(satellite: &str, time: f64, lat: f64, lon: f64, value: MeasurementValue) -> InterpolatedObservation {
+        let half = 500.0 / METERS_PER_DEGREE;
+        InterpolatedObservation {
+            timestamp: time,
+            observation_data: value,
+            interpolation_uncertainty: 0.2,
+            interpolation_methods_used: phantom(satellite, time, lat, lon, celsius(0.0)).interpolation_methods,
+            confidence_score: 0.8,
+            phantom_satellite_id: satellite.to_string(),
+            ground_footprint: GroundFootprint {
+                center_point: Point3::new(lat, lon, 0.0),
+                coverage_polygon: [(-1.0, -1.0), (-1.0, 1.0), (1.0, 1.0), (1.0, -1.0)].iter()
+                    .map(|(north, east)| Point3::new(lat + north * half, lon + east * half, 0.0))
+                    .collect(),
+                area_km2: 1.0,
+                phantom_satellite_id: satellite.to_string(),
+                observation_angle: 0.0,
+                sun_angle: 0.0,
+            },
+            temporal_interpolation_span: 3600.0,
+            spatial_interpolation_radius: 500.0,
+        }
+    }
+
+    #[test]
+    fn test_alignment_recovers_injected_phantom_bias_end_to_end() {
+        // 4 °C is several phantom sigmas, so an agreement check applied before the
+        // bias is estimated would refuse every pair
+        let bias = 4.0;
+        let target_time = 1_700_000_000.0;
+        let fields: Vec<(f64, f64, f64)> = (0..4).map(|i| (-19.3 + 0.1 * i as f64, 31.4, 18.0 + 3.0 * i as f64)).collect();
+
+        let mut phantoms = PhantomSatelliteConstellation::new();
+        let readings = fields.iter()
+            .map(|&(lat, lon, truth)| interpolated("phantom-a", target_time + 600.0, lat, lon, celsius(truth + bias)))
+            .collect();
+        phantoms.phantom_satellites.insert("phantom-a".to_string(), phantom_satellite("phantom-a", readings));
+
+        let satellite = RealSatellite {
+            satellite_id: "sentinel-2a".to_string(),
+            trajectory_history: Vec::new(),
+            observations: fields.iter().map(|&(lat, lon, truth)| real(target_time, lat, lon, celsius(truth))).collect(),
+            last_known_time: target_time,
+            last_observation_time: target_time,
+            sensor_characteristics: SensorCharacteristics {
+                sensor_type: SensorType::SatelliteImagery,
+                spectral_bands: Vec::new(),
+                spatial_resolution: 10.0,
+                temporal_resolution: 5.0 * SECONDS_PER_DAY,
+                radiometric_resolution: 12,
+            },
+            tle: None,
+        };
+        let reals = RealSatelliteConstellation {
+            satellites: HashMap::from([(satellite.satellite_id.clone(), satellite)]),
+            constellation_metadata: ConstellationMetadata {
+                constellation_name: "Sentinel-2".to_string(),
+                total_satellites: 1,
+                orbital_characteristics: OrbitCharacteristics {
+                    orbit_type: "sun-synchronous".to_string(),
+                    altitude_range: (786_000.0, 786_000.0),
+                    inclination_range: (98.6, 98.6),
+                    revisit_time_days: 5.0,
+                },
+                mission_objectives: Vec::new(),
+            },
+        };
+
+        let mut engine = PhantomRealAlignmentEngine::new();
+        let result = engine.align_phantom_and_real_observations(&phantoms, &reals, target_time, &[]).unwrap();
+        assert_eq!(result.regional_alignments.len(), 4);
+        assert!(result.regional_alignments.iter().all(|region| region.phantom_real_pairs.len() == 1));
+
+        let global = &result.global_alignment;
+        assert!((global.phantom_corrections["phantom-a"].value_biases["temperature"] - bias).abs() < 0.05);
+        for (region, &(_, _, truth)) in result.regional_alignments.iter().zip(&fields) {
+            assert!((global.region_states[&region.region_id]["temperature"] - truth).abs() < 0.05);
+        }
+        assert!(result.alignment_quality.observation_consistency > 0.95);
+    }
+
+    /// Unix time of the start of a 2024 day of year
+    fn day(day_of_year: f64) -> f64 {
+        1_704_067_200.0 + (day_of_year - 1.0) * SECONDS_PER_DAY
+    }
+
+    /// Daily volumetric moisture drying 0.005 a day, wetted by +0.08 irrigations and one +0.10 rain
+    fn moisture_at(d: f64, wettings: &[f64]) -> f64 {
+        let mut content = 0.25 - 0.005 * (d - 150.0);
+        for (i, &wetting) in wettings.iter().enumerate() {
+            if d >= wetting {
+                content += if i == wettings.len() - 1 { 0.10 } else { 0.08 };
+            }
+        }
+        content
+    }
+
+    #[test]
+    fn test_field_events_from_ndvi_and_soil_moisture() {
+        // Irrigated maize sown in May and harvested in October
+        let mut enhancer = AgriculturalAlignmentEnhancer::new().with_harvest_window("corn", Hemisphere::Southern, 260.0, 310.0);
+        enhancer.irrigation_pattern_matcher.irrigation_cycles.insert("corn".to_string(), IrrigationCycle {
+            cycle_period_days: 10.0,
+            water_amount_per_cycle: 8.0,
+            optimal_timing_within_cycle: 0.5,
+            weather_modification_factors: HashMap::new(),
+        });
+
+        let mut series = FieldTimeSeries::new("field-7", Point3::new(-19.3, 31.4, 0.0));
+        series.crop_type = Some("corn".to_string());
+        // Bare soil, logistic green-up, slow senescence and a harvest on day 276; one cloudy scene on day 200
+        let ndvi = |d: f64| match d {
+            d if d < 135.0 => 0.15,
+            d if d >= 276.0 => 0.2,
+            d => 0.15 + 0.65 / (1.0 + (-(d - 155.0) / 6.0).exp()) - 0.002 * (d - 230.0).max(0.0),
+        };
+        for d in (90..=320).step_by(5).map(f64::from) {
+            series.ndvi.push((day(d), if d == 200.0 { 0.3 } else { ndvi(d) }));
+        }
+        let wettings = [160.25, 170.25, 180.25, 195.25];
+        for d in (150..=220).map(|d| f64::from(d) + 0.5) {
+            series.soil_moisture.push((day(d), moisture_at(d, &wettings)));
+        }
+        series.precipitation.push((day(195.3), 35.0));
+
+        let events = enhancer.detect_field_events(&series);
+        let of = |activity| events.iter().filter(|event| event.activity == activity).collect::<Vec<_>>();
+
+        let irrigations = of(FarmActivity::Irrigation);
+        assert_eq!(irrigations.len(), 3, "rain-driven rise must not count as irrigation");
+        for (event, wetting) in irrigations.iter().zip(wettings) {
+            assert!((event.timestamp - day(wetting)).abs() <= 0.5 * SECONDS_PER_DAY);
+            // Net of the day's drying
+            assert!((event.magnitude - 7.5).abs() < 1e-6);
+        }
+        assert!(irrigations[1].confidence > irrigations[0].confidence, "cycle-spaced events are boosted");
+
+        let (sowing, emergence, harvest) = (of(FarmActivity::Sowing), of(FarmActivity::Emergence), of(FarmActivity::Harvest));
+        assert_eq!((sowing.len(), emergence.len(), harvest.len()), (1, 1, 1), "cloudy scene must not split the season");
+        // 20% of the amplitude above bare soil is crossed on day 146.7
+        assert!((emergence[0].timestamp - day(146.7)).abs() < 2.0 * SECONDS_PER_DAY);
+        assert!((sowing[0].timestamp - (emergence[0].timestamp - SOWING_TO_EMERGENCE_DAYS * SECONDS_PER_DAY)).abs() < 1.0);
+        assert!(sowing[0].confidence < emergence[0].confidence);
+        assert!((harvest[0].timestamp - day(277.5)).abs() < 1.0);
+        assert!(harvest[0].confidence > 0.5);
+
+        // The same harvest outside the crop's window is less credible
+        series.crop_type = Some("soybeans".to_string());
+        let soybean_harvest = enhancer.harvest_timing_aligner.detect_phenology_events(&series)
+            .into_iter()
+            .find(|event| event.activity == FarmActivity::Harvest)
+            .unwrap();
+        assert!((soybean_harvest.confidence - 0.5 * harvest[0].confidence).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_agricultural_insights_and_seasonal_factors() {
+        let mut enhancer = AgriculturalAlignmentEnhancer::new();
+        let wettings = [160.25, 170.25, 180.25, 195.25];
+        let mut pairs: Vec<_> = (150..=220)
+            .map(|d| {
+                let d = f64::from(d) + 0.5;
+                // Probe reporting percent
+                let value = MeasurementValue::SoilMoisture { value: 100.0 * moisture_at(d, &wettings), depth_cm: 10.0, soil_type: "loam".to_string() };
+                (phantom("phantom-a", day(d), -19.3, 31.4, value.clone()), real(day(d), -19.3, 31.4, value), 0.8)
+            })
+            .collect();
+        let rain = MeasurementValue::Precipitation { rate: 10.0, accumulation: 35.0, type_: PrecipitationType::Rain };
+        pairs.push((phantom("phantom-a", day(195.3), -19.3, 31.4, rain.clone()), real(day(195.3), -19.3, 31.4, rain), 0.8));
+
+        let insights = enhancer.extract_agricultural_insights(&[region("field-7", pairs)], day(175.0)).unwrap();
+        assert_eq!(insights.len(), 2, "only events up to the target time");
+        for insight in &insights {
+            assert!(matches!(insight.insight_type, InsightType::FarmActivity { activity: FarmActivity::Irrigation, .. }));
+            assert!((insight.impact_score - 7.5 / IRRIGATION_APPLICATION_MM).abs() < 1e-6);
+            assert!((insight.location - Point3::new(-19.3, 31.4, 0.0)).norm() < 1e-9);
+        }
+        assert!(insights[0].timestamp < insights[1].timestamp);
+
+        // Crops in name order; wheat is being harvested, maize and soybeans are past it
+        assert_eq!(enhancer.get_seasonal_factors(day(250.0), -19.3), vec![1.0, 1.0, 1.5]);
+        assert_eq!(enhancer.get_seasonal_factors(day(20.0), -19.3), vec![1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_insights_from_field_history_across_alignments() {
+        let mut enhancer = AgriculturalAlignmentEnhancer::new();
+        let wettings = [160.25, 170.25, 180.25, 195.25];
+        let mut insights = Vec::new();
+        // One alignment a day, each seeing only that day's scene through alternating phantoms
+        for d in 150..=220 {
+            let d = f64::from(d) + 0.5;
+            let value = MeasurementValue::SoilMoisture { value: moisture_at(d, &wettings), depth_cm: 10.0, soil_type: "loam".to_string() };
+            let satellite = if d as u32 % 2 == 0 { "phantom-a" } else { "phantom-b" };
+            let mut pairs = vec![(phantom(satellite, day(d), -19.3, 31.4, value.clone()), real(day(d), -19.3, 31.4, value), 0.8)];
+            if d == 195.5 {
+                let rain = MeasurementValue::Precipitation { rate: 10.0, accumulation: 35.0, type_: PrecipitationType::Rain };
+                pairs.push((phantom(satellite, day(195.3), -19.3, 31.4, rain.clone()), real(day(195.3), -19.3, 31.4, rain), 0.8));
+            }
+            let region_id = format!("{satellite}@-19.30000,31.40000");
+            insights = enhancer.extract_agricultural_insights(&[region(&region_id, pairs)], day(d)).unwrap();
+        }
+
+        assert_eq!(enhancer.field_histories.len(), 1);
+        assert_eq!(enhancer.field_histories.values().next().unwrap().soil_moisture.len(), 71);
+        assert_eq!(insights.len(), 3, "rain-driven rise must not count as irrigation");
+        for (insight, wetting) in insights.iter().zip(wettings) {
+            assert!((insight.timestamp - day(wetting)).abs() <= 0.5 * SECONDS_PER_DAY);
+        }
+
+        // A year on, the old season has left the history
+        enhancer.extract_agricultural_insights(&[], day(220.5) + DAYS_PER_YEAR * SECONDS_PER_DAY + 40.0 * SECONDS_PER_DAY).unwrap();
+        assert!(enhancer.field_histories.is_empty());
+    }
+
+    #[test]
+    fn test_harvest_windows_and_seasons_follow_the_hemisphere() {
+        let mut enhancer = AgriculturalAlignmentEnhancer::new().with_harvest_window("sorghum", Hemisphere::Southern, 350.0, 20.0);
+        enhancer.seasonal_alignment_adjusters.insert("autumn".to_string(), SeasonalAlignmentAdjuster {
+            season: "autumn".to_string(),
+            alignment_boost_factors: HashMap::new(),
+            temporal_window_adjustments: 1.0,
+            confidence_modulation: 0.5,
+        });
+
+        // Crops in name order: corn, sorghum, soybeans, wheat
+        // Mid-March: autumn in Buhera with maize and soybeans a month from harvest, spring in the north
+        assert_eq!(enhancer.get_seasonal_factors(day(75.0), -19.3), vec![0.75, 0.5, 0.75, 0.5]);
+        assert_eq!(enhancer.get_seasonal_factors(day(75.0), 40.0), vec![1.0, 1.0, 1.0, 1.0]);
+        // Early September: autumn in the north with corn and soybeans a month from harvest
+        assert_eq!(enhancer.get_seasonal_factors(day(250.0), 40.0), vec![0.75, 0.5, 0.75, 0.5]);
+        // Sorghum's southern window wraps past the year end and is shifted half a year in the north
+        assert_eq!(enhancer.get_seasonal_factors(day(10.0), -19.3)[1], 1.5);
+        assert_eq!(enhancer.get_seasonal_factors(day(10.0), 40.0)[1], 1.0);
+        assert_eq!(enhancer.harvest_timing_aligner.harvest_window("sorghum", 40.0), Some((350.0 + 182.625, 20.0 + 182.625)));
+
+        // Harvests 137.5 days after `start`
+        let season = |latitude: f64, start: f64, crop: &str| {
+            let mut series = FieldTimeSeries::new("field-3", Point3::new(latitude, 31.4, 0.0));
+            series.crop_type = Some(crop.to_string());
+            for d in (0..=200).step_by(5).map(f64::from) {
+                let ndvi = if d < 30.0 || d >= 136.0 { 0.2 } else { 0.8 };
+                series.ndvi.push((day(start + d), ndvi));
+            }
+            enhancer.harvest_timing_aligner.detect_phenology_events(&series)
+                .into_iter()
+                .find(|event| event.activity == FarmActivity::Harvest)
+                .unwrap()
+        };
+        let buhera_maize = season(-19.3, 0.0, "corn");
+        assert!((buhera_maize.timestamp - day(137.5)).abs() < 1.0);
+        // A September harvest in the north is as credible as a May one in the south
+        assert!((season(40.0, 140.0, "corn").confidence - buhera_maize.confidence).abs() < 1e-12);
+        // Out of season in the field's hemisphere
+        assert!((season(40.0, 0.0, "corn").confidence - 0.5 * buhera_maize.confidence).abs() < 1e-12);
+        assert!((season(-19.3, 0.0, "wheat").confidence - 0.5 * buhera_maize.confidence).abs() < 1e-12);
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use nalgebra::{DMatrix, DVector, Point3, Vector3};
 use chrono::{DateTime, Utc};
 
@@ -83,6 +83,16 @@ pub enum InsightType {
     StressDetection { stress_type: String, severity: f64 },
     GrowthAnomaly { anomaly_type: String, magnitude: f64 },
     YieldPrediction { predicted_yield: f64, uncertainty: f64 },
+    /// Dated field operation or phenological transition detected from the time series
+    FarmActivity { activity: FarmActivity, magnitude: f64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FarmActivity {
+    Irrigation,
+    Sowing,
+    Emergence,
+    Harvest,
 }
 
 /// 4D Reality reconstructor with nanosecond precision
@@ -363,78 +373,115 @@ impl Reality4DReconstructor {
         }
     }
     
-    /// Create phantom enhanced 4D reality with nanosecond precision
+    /// Create phantom enhanced 4D reality over a time window in seconds
+    ///
+    /// Slices are taken at observation times and only the most recent
+    /// `slice_buffer.capacity` are kept; the confidence map and insights cover
+    /// the whole window.
     pub fn create_phantom_enhanced_4d_reality(&mut self,
                                             real_constellation: &RealSatelliteConstellation,
                                             phantom_constellation: &PhantomSatelliteConstellation,
                                             time_window: (f64, f64),
                                             agricultural_regions: &[GeographicRegion]) -> Result<PhantomEnhanced4DReality, AppError> {
-        
-        let mut enhanced_slices = Vec::new();
-        let mut agricultural_insights = Vec::new();
-        let time_step_ns = self.temporal_resolution;
-        
-        let mut current_time = time_window.0;
-        while current_time <= time_window.1 {
-            // Get real satellite observations at this time
-            let real_observations = real_constellation.get_observations_at_time(current_time);
-            
-            // Get phantom satellite observations at this time
-            let phantom_observations = phantom_constellation.get_phantom_observations_at_time(
-                current_time, 
-                None
-            );
-            
-            // Combine real and phantom observations with appropriate weighting
-            let combined_observations = self.fuse_real_and_phantom_observations(
-                &real_observations,
-                &phantom_observations,
-                current_time
-            )?;
-            
-            // Apply aperture corrections
-            let aperture_corrected_data = self.aperture_processor
-                .correct_aperture_effects(&combined_observations)?;
-            
-            // Create 4D reality slice with enhanced coverage
-            let enhanced_slice = Reality4DSlice {
-                spatial_coordinates: self.compute_position_from_observations(&aperture_corrected_data)?,
-                temporal_coordinate: current_time,
-                measurement_data: aperture_corrected_data,
-                uncertainty_ellipsoid: self.compute_enhanced_uncertainty(&phantom_observations)?,
-                aperture_corrections: self.aperture_processor.get_last_corrections(),
-                phantom_contribution: self.compute_phantom_contribution_metrics(&phantom_observations)?,
-                agricultural_context: self.determine_agricultural_context(&phantom_observations, agricultural_regions)?,
-            };
-            
-            // Analyze for agricultural insights
-            let slice_insights = self.agricultural_analyzer.analyze_agricultural_conditions(
-                &enhanced_slice,
-                agricultural_regions
-            )?;
-            agricultural_insights.extend(slice_insights);
-            
-            enhanced_slices.push(enhanced_slice);
-            current_time += time_step_ns;
+        let capacity = self.slice_buffer.capacity.max(1);
+        let (temporal_resolution_ns, spatial_resolution_m) = (self.temporal_resolution, self.spatial_resolution);
+        let mut slicer = self.slice_phantom_enhanced_4d_reality(
+            real_constellation,
+            phantom_constellation,
+            time_window,
+            agricultural_regions,
+            SliceSchedule::ObservationTimes,
+        )?;
+
+        let mut recent_slices = VecDeque::with_capacity(capacity.min(1024));
+        for slice in slicer.by_ref() {
+            if recent_slices.len() == capacity {
+                recent_slices.pop_front();
+            }
+            recent_slices.push_back(slice?);
         }
-        
-        // Create confidence map
-        let confidence_map = self.create_confidence_map(phantom_constellation, &enhanced_slices)?;
-        
-        // Compute coverage statistics
-        let phantom_coverage_percentage = self.compute_phantom_coverage_percentage(&enhanced_slices);
-        let real_vs_phantom_ratio = self.compute_real_phantom_ratio(&enhanced_slices);
-        
+        let aggregate = slicer.into_aggregate();
+
         Ok(PhantomEnhanced4DReality {
-            nanosecond_slices: enhanced_slices,
-            phantom_coverage_percentage,
-            confidence_map,
-            real_vs_phantom_ratio,
-            agricultural_insights,
-            temporal_resolution_ns: self.temporal_resolution,
-            spatial_resolution_m: self.spatial_resolution,
+            nanosecond_slices: recent_slices.into(),
+            phantom_coverage_percentage: aggregate.phantom_coverage_percentage(),
+            confidence_map: aggregate.confidence_map(),
+            real_vs_phantom_ratio: aggregate.real_vs_phantom_ratio(),
+            agricultural_insights: aggregate.agricultural_insights(),
+            temporal_resolution_ns,
+            spatial_resolution_m,
         })
     }
+
+    /// Lazily generate slices over a time window in seconds
+    ///
+    /// Each slice is built when the caller asks for the next one, so a slow
+    /// consumer holds back generation, and running summaries are kept in the
+    /// slicer's `Reality4DAggregate` rather than in the slices.
+    pub fn slice_phantom_enhanced_4d_reality<'a>(&'a mut self,
+                                                 real_constellation: &'a RealSatelliteConstellation,
+                                                 phantom_constellation: &'a PhantomSatelliteConstellation,
+                                                 time_window: (f64, f64),
+                                                 agricultural_regions: &'a [GeographicRegion],
+                                                 schedule: SliceSchedule) -> Result<Reality4DSlicer<'a>, AppError> {
+        if !(time_window.0.is_finite() && time_window.1.is_finite() && time_window.0 <= time_window.1) {
+            return Err(AppError::validation("Time window must be finite and ordered"));
+        }
+        if let SliceSchedule::Cadence { interval_s } = schedule {
+            if !(interval_s.is_finite() && interval_s > 0.0) {
+                return Err(AppError::validation("Slice cadence must be a positive number of seconds"));
+            }
+        }
+        let aggregate = Reality4DAggregate::new(self.spatial_resolution);
+        Ok(Reality4DSlicer {
+            index: ObservationIndex::new(real_constellation, phantom_constellation, time_window),
+            reconstructor: self,
+            agricultural_regions,
+            schedule,
+            time_window,
+            next_step: 0,
+            next_real: 0,
+            next_phantom: 0,
+            last_time: None,
+            counted_real: 0,
+            counted_phantom: 0,
+            aggregate,
+            failed: false,
+        })
+    }
+
+    /// Slice and its insights at `time` from the observations current then
+    fn build_phantom_enhanced_slice(&mut self,
+                                    time: f64,
+                                    real_observations: &[&RealObservation],
+                                    phantom_observations: &[&InterpolatedObservation],
+                                    agricultural_regions: &[GeographicRegion]) -> Result<(Reality4DSlice, Vec<AgriculturalInsight>), AppError> {
+        // Combine real and phantom observations with appropriate weighting
+        let combined_observations = self.fuse_real_and_phantom_observations(
+            real_observations,
+            phantom_observations,
+            time
+        )?;
+
+        // Apply aperture corrections
+        let aperture_corrected_data = self.aperture_processor
+            .correct_aperture_effects(&combined_observations)?;
+
+        let mut phantom_contribution = self.compute_phantom_contribution_metrics(phantom_observations)?;
+        phantom_contribution.real_observation_count = real_observations.len();
+
+        let slice = Reality4DSlice {
+            spatial_coordinates: self.compute_position_from_observations(real_observations, phantom_observations),
+            temporal_coordinate: time,
+            measurement_data: aperture_corrected_data,
+            uncertainty_ellipsoid: self.compute_enhanced_uncertainty(phantom_observations)?,
+            aperture_corrections: self.aperture_processor.get_last_corrections(),
+            phantom_contribution,
+            agricultural_context: self.determine_agricultural_context(phantom_observations, agricultural_regions)?,
+        };
+        let insights = self.agricultural_analyzer.analyze_agricultural_conditions(&slice, agricultural_regions)?;
+        Ok((slice, insights))
+    }
     
     /// Create nanosecond-precision reality slice
     pub fn create_nanosecond_reality_slice(&mut self, 
@@ -512,10 +559,21 @@ impl Reality4DReconstructor {
         Ok(HashMap::new())
     }
     
-    fn compute_position_from_observations(&self, 
-                                        _observations: &HashMap<SensorType, MeasurementValue>) -> Result<Point3<f64>, AppError> {
-        // Simplified position computation
-        Ok(Point3::new(0.0, 0.0, 0.0))
+    /// Mean (latitude, longitude, altitude) of the real observations, or of the
+    /// phantom footprints when no real observation is current
+    fn compute_position_from_observations(&self,
+                                        real_observations: &[&RealObservation],
+                                        phantom_observations: &[&InterpolatedObservation]) -> Point3<f64> {
+        let locations: Vec<Point3<f64>> = if real_observations.is_empty() {
+            phantom_observations.iter().map(|obs| obs.ground_footprint.center_point).collect()
+        } else {
+            real_observations.iter().map(|obs| obs.location).collect()
+        };
+        if locations.is_empty() {
+            return Point3::origin();
+        }
+        let sum = locations.iter().fold(Vector3::zeros(), |sum, location| sum + location.coords);
+        Point3::from(sum / locations.len() as f64)
     }
     
     fn compute_spatial_position(&self, 
@@ -580,47 +638,332 @@ impl Reality4DReconstructor {
         // Placeholder implementation
         Ok(None)
     }
-    
-    fn create_confidence_map(&self,
-                           _phantom_constellation: &PhantomSatelliteConstellation,
-                           _enhanced_slices: &[Reality4DSlice]) -> Result<ConfidenceMap, AppError> {
-        Ok(ConfidenceMap {
-            spatial_confidence_grid: HashMap::new(),
-            temporal_confidence_series: Vec::new(),
-            agricultural_confidence_zones: HashMap::new(),
-        })
+}
+
+/// When a slicer emits reality slices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SliceSchedule {
+    /// At each real or phantom observation time; times closer than the
+    /// reconstructor's temporal resolution share a slice
+    ObservationTimes,
+    /// Every `interval_s` seconds from the start of the window
+    Cadence { interval_s: f64 },
+}
+
+/// Observations near a time window, sorted by time for windowed lookup
+struct ObservationIndex<'a> {
+    real: Vec<&'a RealObservation>,
+    phantom: Vec<&'a InterpolatedObservation>,
+}
+
+impl<'a> ObservationIndex<'a> {
+    fn new(real_constellation: &'a RealSatelliteConstellation,
+           phantom_constellation: &'a PhantomSatelliteConstellation,
+           time_window: (f64, f64)) -> Self {
+        let (start, end) = (time_window.0 - OBSERVATION_TIME_TOLERANCE_S, time_window.1 + OBSERVATION_TIME_TOLERANCE_S);
+        let mut real: Vec<&RealObservation> = real_constellation.satellites.values()
+            .flat_map(|satellite| &satellite.observations)
+            .filter(|obs| (start..=end).contains(&obs.timestamp))
+            .collect();
+        real.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+        let mut phantom: Vec<&InterpolatedObservation> = phantom_constellation.phantom_satellites.values()
+            .flat_map(|satellite| &satellite.interpolated_observations)
+            .filter(|obs| (start..=end).contains(&obs.timestamp))
+            .collect();
+        phantom.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+        Self { real, phantom }
     }
-    
-    fn compute_phantom_coverage_percentage(&self, enhanced_slices: &[Reality4DSlice]) -> f64 {
-        let total_slices = enhanced_slices.len();
-        if total_slices == 0 {
-            return 0.0;
+
+    /// Index ranges of the observations within the tolerance of `time`, as the
+    /// constellations' `*_at_time` lookups return
+    fn current(&self, time: f64) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+        fn within<T>(sorted: &[T], time: f64, timestamp: impl Fn(&T) -> f64) -> std::ops::Range<usize> {
+            let first = sorted.partition_point(|obs| timestamp(obs) <= time - OBSERVATION_TIME_TOLERANCE_S);
+            let last = sorted.partition_point(|obs| timestamp(obs) < time + OBSERVATION_TIME_TOLERANCE_S);
+            first..last.max(first)
         }
-        
-        let phantom_enhanced_slices = enhanced_slices.iter()
-            .filter(|slice| slice.phantom_contribution.phantom_observation_count > 0)
-            .count();
-        
-        phantom_enhanced_slices as f64 / total_slices as f64
+        (within(&self.real, time, |obs| obs.timestamp), within(&self.phantom, time, |obs| obs.timestamp))
     }
-    
-    fn compute_real_phantom_ratio(&self, enhanced_slices: &[Reality4DSlice]) -> f64 {
-        let mut total_real = 0;
-        let mut total_phantom = 0;
-        
-        for slice in enhanced_slices {
-            total_real += slice.phantom_contribution.real_observation_count;
-            total_phantom += slice.phantom_contribution.phantom_observation_count;
+}
+
+/// Pull-based generator of phantom-enhanced reality slices
+///
+/// Holds only the sorted observation references and the running aggregate,
+/// whatever the length of the window.
+pub struct Reality4DSlicer<'a> {
+    reconstructor: &'a mut Reality4DReconstructor,
+    index: ObservationIndex<'a>,
+    agricultural_regions: &'a [GeographicRegion],
+    schedule: SliceSchedule,
+    time_window: (f64, f64),
+    next_step: u64,
+    next_real: usize,
+    next_phantom: usize,
+    last_time: Option<f64>,
+    /// Observations before these indices have been counted in the aggregate
+    counted_real: usize,
+    counted_phantom: usize,
+    aggregate: Reality4DAggregate,
+    failed: bool,
+}
+
+impl Reality4DSlicer<'_> {
+    /// Summaries of the slices emitted so far
+    pub fn aggregate(&self) -> &Reality4DAggregate {
+        &self.aggregate
+    }
+
+    pub fn into_aggregate(self) -> Reality4DAggregate {
+        self.aggregate
+    }
+
+    fn next_time(&mut self) -> Option<f64> {
+        let (start, end) = self.time_window;
+        match self.schedule {
+            SliceSchedule::Cadence { interval_s } => {
+                let time = start + self.next_step as f64 * interval_s;
+                self.next_step += 1;
+                (time <= end).then_some(time)
+            },
+            SliceSchedule::ObservationTimes => {
+                let merge_within = self.reconstructor.temporal_resolution * 1e-9;
+                loop {
+                    let real = self.index.real.get(self.next_real).map(|obs| obs.timestamp);
+                    let phantom = self.index.phantom.get(self.next_phantom).map(|obs| obs.timestamp);
+                    let time = match (real, phantom) {
+                        (Some(r), Some(p)) if r <= p => { self.next_real += 1; r },
+                        (_, Some(p)) => { self.next_phantom += 1; p },
+                        (Some(r), None) => { self.next_real += 1; r },
+                        (None, None) => return None,
+                    };
+                    if time > end {
+                        return None;
+                    }
+                    let merged = self.last_time.is_some_and(|last| time - last < merge_within);
+                    if time >= start && !merged {
+                        self.last_time = Some(time);
+                        return Some(time);
+                    }
+                }
+            },
         }
-        
-        if total_phantom == 0 {
-            if total_real > 0 { f64::INFINITY } else { 1.0 }
+    }
+}
+
+impl Iterator for Reality4DSlicer<'_> {
+    type Item = Result<Reality4DSlice, AppError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let time = self.next_time()?;
+        let (real, phantom) = self.index.current(time);
+        // Slice times only increase, so observations past the cursors are the ones no earlier slice saw
+        let new_real = real.end.saturating_sub(real.start.max(self.counted_real));
+        let new_phantom = phantom.end.saturating_sub(phantom.start.max(self.counted_phantom));
+        self.counted_real = self.counted_real.max(real.end);
+        self.counted_phantom = self.counted_phantom.max(phantom.end);
+        let (real, phantom) = (&self.index.real[real], &self.index.phantom[phantom]);
+        match self.reconstructor.build_phantom_enhanced_slice(time, real, phantom, self.agricultural_regions) {
+            Ok((slice, insights)) => {
+                self.aggregate.record(&slice, insights);
+                self.aggregate.count_observations(new_real, new_phantom);
+                Some(Ok(slice))
+            },
+            Err(error) => {
+                self.failed = true;
+                Some(Err(error))
+            },
+        }
+    }
+}
+
+/// Running mean that needs no history
+#[derive(Debug, Clone, Copy, Default)]
+struct RunningMean {
+    sum: f64,
+    count: usize,
+}
+
+impl RunningMean {
+    fn add(&mut self, value: f64) {
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn mean(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.sum / self.count as f64 }
+    }
+}
+
+/// Incremental confidence map, coverage statistics and insights of a slice stream
+///
+/// Memory grows with the number of grid cells and crop types seen, not with the
+/// number of slices: the confidence time series is re-binned to at most
+/// `max_series_points` points, only the latest insight of each kind is kept per
+/// cell and dated farm activities are kept up to `max_activity_insights`.
+/// Each observation is counted once, however many slices it falls near.
+#[derive(Debug)]
+pub struct Reality4DAggregate {
+    pub slice_count: usize,
+    pub phantom_enhanced_slices: usize,
+    pub real_observation_count: usize,
+    pub phantom_observation_count: usize,
+    pub max_series_points: usize,
+    pub max_activity_insights: usize,
+    cell_size_deg: f64,
+    spatial: HashMap<(i32, i32), RunningMean>,
+    zones: HashMap<String, RunningMean>,
+    /// (time, confidence) means of consecutive slices, `slices_per_point` per point
+    series: Vec<(RunningMean, RunningMean)>,
+    slices_per_point: usize,
+    insights: HashMap<(&'static str, (i32, i32)), AgriculturalInsight>,
+    /// Farm activities are events rather than states, so each is kept, oldest first
+    activities: VecDeque<AgriculturalInsight>,
+}
+
+impl Reality4DAggregate {
+    /// Aggregate on a grid of `spatial_resolution_m` cells
+    pub fn new(spatial_resolution_m: f64) -> Self {
+        Self {
+            slice_count: 0,
+            phantom_enhanced_slices: 0,
+            real_observation_count: 0,
+            phantom_observation_count: 0,
+            max_series_points: 4096,
+            max_activity_insights: 1024,
+            cell_size_deg: (spatial_resolution_m / 111_320.0).max(1e-7),
+            spatial: HashMap::new(),
+            zones: HashMap::new(),
+            series: Vec::new(),
+            slices_per_point: 1,
+            insights: HashMap::new(),
+            activities: VecDeque::new(),
+        }
+    }
+
+    pub fn record(&mut self, slice: &Reality4DSlice, insights: Vec<AgriculturalInsight>) {
+        let contribution = &slice.phantom_contribution;
+        let confidence = slice.uncertainty_ellipsoid.confidence_level;
+        self.slice_count += 1;
+        if contribution.phantom_observation_count > 0 {
+            self.phantom_enhanced_slices += 1;
+        }
+
+        self.spatial.entry(self.cell(&slice.spatial_coordinates)).or_default().add(confidence);
+        if let Some(context) = &slice.agricultural_context {
+            self.zones.entry(context.crop_type.clone()).or_default().add(confidence);
+        }
+
+        match self.series.last_mut() {
+            Some((time, value)) if time.count < self.slices_per_point => {
+                time.add(slice.temporal_coordinate);
+                value.add(confidence);
+            },
+            _ => {
+                let mut point = (RunningMean::default(), RunningMean::default());
+                point.0.add(slice.temporal_coordinate);
+                point.1.add(confidence);
+                self.series.push(point);
+            },
+        }
+        if self.series.len() > self.max_series_points.max(2) {
+            self.coarsen_series();
+        }
+
+        for insight in insights {
+            let key = (insight_kind(&insight.insight_type), self.cell(&insight.location));
+            if !matches!(insight.insight_type, InsightType::FarmActivity { .. }) {
+                self.insights.insert(key, insight);
+                continue;
+            }
+            // Neighbouring slices report the same dated event again
+            let repeated = self.activities.iter().any(|seen| {
+                seen.timestamp == insight.timestamp
+                    && (insight_kind(&seen.insight_type), self.cell(&seen.location)) == key
+            });
+            if !repeated {
+                self.activities.push_back(insight);
+                while self.activities.len() > self.max_activity_insights.max(1) {
+                    self.activities.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Count observations first seen by the latest slice
+    fn count_observations(&mut self, real: usize, phantom: usize) {
+        self.real_observation_count += real;
+        self.phantom_observation_count += phantom;
+    }
+
+    /// Halve the number of series points by merging neighbours
+    fn coarsen_series(&mut self) {
+        self.series = self.series
+            .chunks(2)
+            .map(|pair| {
+                pair.iter().fold((RunningMean::default(), RunningMean::default()), |(time, value), point| {
+                    (
+                        RunningMean { sum: time.sum + point.0.sum, count: time.count + point.0.count },
+                        RunningMean { sum: value.sum + point.1.sum, count: value.count + point.1.count },
+                    )
+                })
+            })
+            .collect();
+        self.slices_per_point *= 2;
+    }
+
+    fn cell(&self, location: &Point3<f64>) -> (i32, i32) {
+        ((location.x / self.cell_size_deg).floor() as i32, (location.y / self.cell_size_deg).floor() as i32)
+    }
+
+    pub fn confidence_map(&self) -> ConfidenceMap {
+        ConfidenceMap {
+            spatial_confidence_grid: self.spatial.iter().map(|(cell, mean)| (*cell, mean.mean())).collect(),
+            temporal_confidence_series: self.series.iter().map(|(time, value)| (time.mean(), value.mean())).collect(),
+            agricultural_confidence_zones: self.zones.iter().map(|(crop, mean)| (crop.clone(), mean.mean())).collect(),
+        }
+    }
+
+    /// Latest insight of each kind in each grid cell and the retained farm activities, oldest first
+    pub fn agricultural_insights(&self) -> Vec<AgriculturalInsight> {
+        let mut insights: Vec<AgriculturalInsight> = self.insights.values().chain(&self.activities).cloned().collect();
+        insights.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+        insights
+    }
+
+    /// Fraction of slices that phantom observations contributed to
+    pub fn phantom_coverage_percentage(&self) -> f64 {
+        if self.slice_count == 0 {
+            return 0.0;
+        }
+        self.phantom_enhanced_slices as f64 / self.slice_count as f64
+    }
+
+    pub fn real_vs_phantom_ratio(&self) -> f64 {
+        if self.phantom_observation_count == 0 {
+            if self.real_observation_count > 0 { f64::INFINITY } else { 1.0 }
         } else {
-            total_real as f64 / total_phantom as f64
+            self.real_observation_count as f64 / self.phantom_observation_count as f64
         }
     }
 }
 
+fn insight_kind(insight_type: &InsightType) -> &'static str {
+    match insight_type {
+        InsightType::IrrigationNeed { .. } => "irrigation_need",
+        InsightType::HarvestOptimization { .. } => "harvest_optimization",
+        InsightType::StressDetection { .. } => "stress_detection",
+        InsightType::GrowthAnomaly { .. } => "growth_anomaly",
+        InsightType::YieldPrediction { .. } => "yield_prediction",
+        InsightType::FarmActivity { activity: FarmActivity::Irrigation, .. } => "irrigation_event",
+        InsightType::FarmActivity { activity: FarmActivity::Sowing, .. } => "sowing",
+        InsightType::FarmActivity { activity: FarmActivity::Emergence, .. } => "emergence",
+        InsightType::FarmActivity { activity: FarmActivity::Harvest, .. } => "harvest",
+    }
+}
+
 impl SyntheticApertureProcessor {
     pub fn new() -> Self {
         Self {
@@ -894,4 +1237,219 @@ pub enum SensorType {
     SoilSensor,
 }
 
-use crate::data_fusion::{GeographicRegion, AgriculturalContext}; 
\ No newline at end of file
+use crate::data_fusion::{GeographicRegion, AgriculturalContext}; 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(id: &str, timestamp: f64) -> RealObservation {
+        RealObservation {
+            observation_id: id.to_string(),
+            timestamp,
+            location: Point3::new(-19.3, 31.4, 1200.0),
+            sensor_data: MeasurementValue::Scalar(0.6),
+            quality_indicators: ObservationQuality {
+                signal_to_noise_ratio: 40.0,
+                cloud_coverage: 0.1,
+                atmospheric_interference: 0.05,
+                geometric_quality: 0.9,
+                radiometric_quality: 0.9,
+            },
+            atmospheric_conditions: AtmosphericConditions {
+                water_vapor: 2.0,
+                aerosol_optical_depth: 0.1,
+                atmospheric_pressure: 880.0,
+                temperature_profile: Vec::new(),
+            },
+            agricultural_context: None,
+        }
+    }
+
+    fn constellation(observations: Vec<RealObservation>) -> RealSatelliteConstellation {
+        let satellite = RealSatellite {
+            satellite_id: "sentinel-2a".to_string(),
+            trajectory_history: Vec::new(),
+            observations,
+            last_known_time: 0.0,
+            last_observation_time: 0.0,
+            sensor_characteristics: SensorCharacteristics {
+                sensor_type: crate::data_fusion::SensorType::SatelliteImagery,
+                spectral_bands: Vec::new(),
+                spatial_resolution: 10.0,
+                temporal_resolution: 5.0 * 86400.0,
+                radiometric_resolution: 12,
+            },
+            tle: None,
+        };
+        RealSatelliteConstellation {
+            satellites: HashMap::from([(satellite.satellite_id.clone(), satellite)]),
+            constellation_metadata: ConstellationMetadata {
+                constellation_name: "Sentinel-2".to_string(),
+                total_satellites: 1,
+                orbital_characteristics: OrbitCharacteristics {
+                    orbit_type: "sun-synchronous".to_string(),
+                    altitude_range: (786_000.0, 786_000.0),
+                    inclination_range: (98.6, 98.6),
+                    revisit_time_days: 5.0,
+                },
+                mission_objectives: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_day_window_slices_only_at_observations_or_cadence() {
+        // A nanosecond step over a day would be 8.64e13 slices
+        let mut reconstructor = Reality4DReconstructor::new(1.0, 10.0);
+        let real = constellation(vec![
+            observation("a", 1000.0),
+            observation("b", 1000.0),
+            observation("c", 5000.0),
+            observation("d", 90_000.0),
+        ]);
+        let phantom = PhantomSatelliteConstellation::new();
+        let day = (0.0, 86_400.0);
+
+        let slices: Vec<Reality4DSlice> = reconstructor
+            .slice_phantom_enhanced_4d_reality(&real, &phantom, day, &[], SliceSchedule::ObservationTimes)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(slices.iter().map(|s| s.temporal_coordinate).collect::<Vec<_>>(), vec![1000.0, 5000.0]);
+        assert_eq!(slices[0].phantom_contribution.real_observation_count, 2);
+        assert!((slices[0].spatial_coordinates.x + 19.3).abs() < 1e-9);
+
+        let mut slicer = reconstructor
+            .slice_phantom_enhanced_4d_reality(&real, &phantom, day, &[], SliceSchedule::Cadence { interval_s: 1000.0 })
+            .unwrap();
+        let observed: Vec<f64> = slicer
+            .by_ref()
+            .map(|slice| slice.unwrap())
+            .filter(|slice| slice.phantom_contribution.real_observation_count > 0)
+            .map(|slice| slice.temporal_coordinate)
+            .collect();
+        assert_eq!(observed, vec![1000.0, 5000.0]);
+        assert_eq!(slicer.aggregate().slice_count, 87);
+        assert_eq!(slicer.aggregate().real_observation_count, 3);
+
+        // Only the newest slices are retained, the summaries cover the whole day
+        reconstructor.slice_buffer = CircularBuffer::new(1);
+        let reality = reconstructor.create_phantom_enhanced_4d_reality(&real, &phantom, day, &[]).unwrap();
+        assert_eq!(reality.nanosecond_slices.len(), 1);
+        assert_eq!(reality.nanosecond_slices[0].temporal_coordinate, 5000.0);
+        assert_eq!(reality.confidence_map.temporal_confidence_series.len(), 2);
+        assert_eq!(reality.confidence_map.spatial_confidence_grid.len(), 1);
+        assert_eq!(reality.real_vs_phantom_ratio, f64::INFINITY);
+        assert!(reconstructor
+            .slice_phantom_enhanced_4d_reality(&real, &phantom, day, &[], SliceSchedule::Cadence { interval_s: 0.0 })
+            .is_err());
+    }
+
+    #[test]
+    fn test_observations_near_several_slices_are_counted_once() {
+        let mut reconstructor = Reality4DReconstructor::new(1.0, 10.0);
+        // Within the lookup tolerance of each other, so every slice sees all three
+        let real = constellation(vec![observation("a", 1000.0), observation("b", 1100.0), observation("c", 1200.0)]);
+        let phantom = PhantomSatelliteConstellation::new();
+
+        let mut slicer = reconstructor
+            .slice_phantom_enhanced_4d_reality(&real, &phantom, (0.0, 86_400.0), &[], SliceSchedule::ObservationTimes)
+            .unwrap();
+        let counts: Vec<usize> = slicer
+            .by_ref()
+            .map(|slice| slice.unwrap().phantom_contribution.real_observation_count)
+            .collect();
+        assert_eq!(counts, vec![3, 3, 3]);
+        assert_eq!(slicer.aggregate().real_observation_count, 3);
+    }
+
+    #[test]
+    fn test_farm_activities_are_kept_as_a_bounded_history() {
+        let mut aggregate = Reality4DAggregate::new(10.0);
+        aggregate.max_activity_insights = 3;
+        let slice = Reality4DSlice {
+            spatial_coordinates: Point3::new(-19.3, 31.4, 0.0),
+            temporal_coordinate: 0.0,
+            measurement_data: HashMap::new(),
+            uncertainty_ellipsoid: UncertaintyEllipsoid4D {
+                spatial_uncertainty: Vector3::zeros(),
+                temporal_uncertainty: 0.0,
+                covariance_matrix: DMatrix::identity(4, 4),
+                confidence_level: 0.9,
+            },
+            aperture_corrections: ApertureCorrections {
+                range_walk_correction: 0.0,
+                azimuth_defocus_correction: 0.0,
+                doppler_centroid_correction: 0.0,
+                atmospheric_delay_correction: 0.0,
+            },
+            phantom_contribution: PhantomContributionMetrics::default(),
+            agricultural_context: None,
+        };
+        let irrigation = |day: f64| AgriculturalInsight {
+            insight_type: InsightType::FarmActivity { activity: FarmActivity::Irrigation, magnitude: 0.1 },
+            location: slice.spatial_coordinates,
+            timestamp: day * 86400.0,
+            confidence: 0.8,
+            impact_score: 0.5,
+            recommended_actions: Vec::new(),
+        };
+
+        // Each irrigation is reported by two neighbouring slices
+        for day in [3.0, 3.0, 10.0, 10.0, 17.0, 24.0, 24.0] {
+            aggregate.record(&slice, vec![irrigation(day)]);
+        }
+        let days: Vec<f64> = aggregate.agricultural_insights().iter().map(|i| i.timestamp / 86400.0).collect();
+        assert_eq!(days, vec![10.0, 17.0, 24.0]);
+    }
+
+    #[test]
+    fn test_aggregate_memory_is_bounded_over_a_season() {
+        let mut aggregate = Reality4DAggregate::new(10.0);
+        aggregate.max_series_points = 64;
+        let season_s = 180.0 * 86400.0;
+        for i in 0..10_000 {
+            let time = season_s * i as f64 / 10_000.0;
+            let slice = Reality4DSlice {
+                spatial_coordinates: Point3::new(-19.3, 31.4, 0.0),
+                temporal_coordinate: time,
+                measurement_data: HashMap::new(),
+                uncertainty_ellipsoid: UncertaintyEllipsoid4D {
+                    spatial_uncertainty: Vector3::zeros(),
+                    temporal_uncertainty: 0.0,
+                    covariance_matrix: DMatrix::identity(4, 4),
+                    confidence_level: if i < 5_000 { 0.9 } else { 0.5 },
+                },
+                aperture_corrections: ApertureCorrections {
+                    range_walk_correction: 0.0,
+                    azimuth_defocus_correction: 0.0,
+                    doppler_centroid_correction: 0.0,
+                    atmospheric_delay_correction: 0.0,
+                },
+                phantom_contribution: PhantomContributionMetrics { phantom_observation_count: i % 2, ..Default::default() },
+                agricultural_context: None,
+            };
+            let insight = AgriculturalInsight {
+                insight_type: InsightType::IrrigationNeed { urgency: 0.5, water_amount: 20.0 },
+                location: slice.spatial_coordinates,
+                timestamp: time,
+                confidence: 0.8,
+                impact_score: 0.5,
+                recommended_actions: Vec::new(),
+            };
+            aggregate.record(&slice, vec![insight]);
+        }
+
+        let map = aggregate.confidence_map();
+        assert!(map.temporal_confidence_series.len() <= 64 && map.temporal_confidence_series.len() > 32);
+        let (first, last) = (map.temporal_confidence_series[0], *map.temporal_confidence_series.last().unwrap());
+        assert!((first.1 - 0.9).abs() < 1e-12 && (last.1 - 0.5).abs() < 1e-12);
+        assert!((map.spatial_confidence_grid[&aggregate.cell(&Point3::new(-19.3, 31.4, 0.0))] - 0.7).abs() < 1e-9);
+        assert_eq!(aggregate.phantom_coverage_percentage(), 0.5);
+
+        let insights = aggregate.agricultural_insights();
+        assert_eq!(insights.len(), 1);
+        assert_eq!(insights[0].timestamp, season_s * 9_999.0 / 10_000.0);
+    }
+}
@@ -1,15 +1,22 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use nalgebra::{DVector, DMatrix, Vector3, Point3};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use uuid::Uuid;
 
 use crate::error::AppError;
+use crate::data_ingestion::BoundingBox;
+use crate::data_fusion::orbit_propagation::{
+    imager_half_field_of_view_deg, Geodetic, PassSearch, SatellitePass, Sgp4Propagator, TwoLineElements
+};
 use crate::data_fusion::{
     SensorType, MeasurementValue, TimestampedMeasurement, EnvironmentalData,
     SensorMetadata, QualityFlags, GeographicRegion, AgriculturalContext
 };
 
+/// Seconds either side of its timestamp that an observation counts as current
+pub const OBSERVATION_TIME_TOLERANCE_S: f64 = 300.0;
+
 /// Phantom Satellite - Virtual satellite that predicts observations using orbital mechanics
 #[derive(Debug, Clone)]
 pub struct PhantomSatellite {
@@ -23,6 +30,32 @@ pub struct PhantomSatellite {
     pub agricultural_focus_regions: Vec<GeographicRegion>,
 }
 
+impl PhantomSatellite {
+    fn propagator(&self) -> Result<&Sgp4Propagator, AppError> {
+        self.virtual_orbit.propagator.as_ref().ok_or_else(|| AppError::validation(format!(
+            "Phantom {} has no TLE-based orbit; pass prediction needs SGP4", self.phantom_instance_id
+        )))
+    }
+    
+    /// Passes over a ground point between `start` and `end`
+    pub fn predict_passes(&self,
+                          site: &Geodetic,
+                          start: DateTime<Utc>,
+                          end: DateTime<Utc>,
+                          search: &PassSearch) -> Result<Vec<SatellitePass>, AppError> {
+        self.propagator()?.predict_passes(site, start, end, search)
+    }
+    
+    /// Passes over a field between `start` and `end`
+    pub fn predict_passes_over_region(&self,
+                                      region: &BoundingBox,
+                                      start: DateTime<Utc>,
+                                      end: DateTime<Utc>,
+                                      search: &PassSearch) -> Result<Vec<SatellitePass>, AppError> {
+        self.propagator()?.predict_passes_over_region(region, start, end, search)
+    }
+}
+
 /// Predicted orbital elements and ephemeris table
 #[derive(Debug, Clone)]
 pub struct PredictedOrbit {
@@ -31,6 +64,7 @@ pub struct PredictedOrbit {
     pub prediction_accuracy: f64,
     pub ephemeris_table: Vec<(f64, SatellitePosition)>, // time -> position
     pub ground_track_predictions: Vec<(f64, Point3<f64>)>, // time -> ground location
+    pub propagator: Option<Sgp4Propagator>, // present when the orbit was propagated from a TLE
 }
 
 /// Classical orbital elements (Keplerian)
@@ -105,6 +139,7 @@ pub struct OrbitalPredictor {
     pub j2_coefficient: f64,        // Earth's oblateness coefficient
     pub atmospheric_models: HashMap<String, Box<dyn AtmosphericDensityModel + Send + Sync>>,
     pub solar_activity_predictor: SolarActivityPredictor,
+    pub ephemeris_step_seconds: f64,
 }
 
 impl Default for OrbitalPredictor {
@@ -115,15 +150,20 @@ impl Default for OrbitalPredictor {
             j2_coefficient: 1.08262668e-3,
             atmospheric_models: HashMap::new(),
             solar_activity_predictor: SolarActivityPredictor::default(),
+            ephemeris_step_seconds: 60.0,
         }
     }
 }
 
 impl OrbitalPredictor {
-    /// Predict phantom satellite orbit using Keplerian motion plus perturbations
+    /// Predict phantom satellite orbit with SGP4 when the real satellite has a TLE,
+    /// otherwise with Keplerian motion plus perturbations fitted to its trajectory
     pub fn predict_phantom_orbit(&self, 
                                 real_satellite: &RealSatellite,
                                 prediction_days: f64) -> Result<PredictedOrbit, AppError> {
+        if let Some(tle) = &real_satellite.tle {
+            return self.predict_orbit_from_tle(tle, real_satellite.last_known_time, prediction_days);
+        }
         
         // Extract orbital elements from real satellite's trajectory
         let orbital_elements = self.fit_orbital_elements(&real_satellite.trajectory_history)?;
@@ -131,9 +171,9 @@ impl OrbitalPredictor {
         // Predict future positions using Kepler's laws + perturbations
         let mut ephemeris = Vec::new();
         let mut ground_track = Vec::new();
-        let time_step = 60.0; // 1 minute steps
+        let time_step = self.ephemeris_step_seconds;
         
-        for step in 0..((prediction_days * 24.0 * 60.0) as usize) {
+        for step in 0..((prediction_days * 86400.0 / time_step) as usize) {
             let future_time = real_satellite.last_known_time + (step as f64 * time_step);
             
             // Primary orbital motion (Keplerian)
@@ -167,6 +207,65 @@ impl OrbitalPredictor {
             prediction_accuracy,
             ephemeris_table: ephemeris,
             ground_track_predictions: ground_track,
+            propagator: None,
+        })
+    }
+    
+    /// Tabulate an SGP4 ephemeris from `start_time` (Unix seconds) for `prediction_days`
+    pub fn predict_orbit_from_tle(&self,
+                                 tle: &TwoLineElements,
+                                 start_time: f64,
+                                 prediction_days: f64) -> Result<PredictedOrbit, AppError> {
+        if self.ephemeris_step_seconds <= 0.0 {
+            return Err(AppError::validation("Ephemeris step must be positive"));
+        }
+        let propagator = Sgp4Propagator::new(tle.clone())?;
+        let start = DateTime::<Utc>::from_timestamp_millis((start_time * 1000.0) as i64)
+            .ok_or_else(|| AppError::validation(format!("Start time {} is out of range", start_time)))?;
+        
+        let mut ephemeris = Vec::new();
+        let mut ground_track = Vec::new();
+        let step_ms = (self.ephemeris_step_seconds * 1000.0) as i64;
+        for step in 0..=((prediction_days * 86400.0 / self.ephemeris_step_seconds) as i64) {
+            let time = start + ChronoDuration::milliseconds(step * step_ms);
+            let state = propagator.propagate(time)?;
+            let geodetic = Geodetic::from_ecef(&state.to_ecef(time));
+            let position = SatellitePosition {
+                position: Point3::from(state.position_km * 1000.0),
+                velocity: state.velocity_km_s * 1000.0,
+                altitude: geodetic.height_km * 1000.0,
+                ground_track: Point3::new(geodetic.latitude_deg, geodetic.longitude_deg, geodetic.height_km * 1000.0),
+            };
+            let timestamp = start_time + (step * step_ms) as f64 / 1000.0;
+            ground_track.push((timestamp, position.ground_track));
+            ephemeris.push((timestamp, position));
+        }
+        
+        // Mean elements at epoch; SGP4 applies the secular and periodic perturbations itself
+        let mean_motion = tle.mean_motion_rev_day * 2.0 * std::f64::consts::PI / 86400.0;
+        let orbital_elements = OrbitalElements {
+            semi_major_axis: (self.earth_gravitational_parameter / (mean_motion * mean_motion)).cbrt(),
+            eccentricity: tle.eccentricity,
+            inclination: tle.inclination_deg.to_radians(),
+            longitude_of_ascending_node: tle.raan_deg.to_radians(),
+            argument_of_perigee: tle.argument_of_perigee_deg.to_radians(),
+            mean_anomaly: tle.mean_anomaly_deg.to_radians(),
+            mean_motion,
+            epoch: tle.epoch.timestamp() as f64 + f64::from(tle.epoch.timestamp_subsec_millis()) / 1000.0,
+        };
+        
+        // TLE age, not prediction length, drives SGP4 error growth
+        let days_from_epoch = (start_time - orbital_elements.epoch).abs() / 86400.0 + prediction_days;
+        let perturbation_model = self.create_perturbation_model(&orbital_elements)?;
+        let prediction_accuracy = self.estimate_prediction_accuracy(days_from_epoch, &orbital_elements)?;
+        
+        Ok(PredictedOrbit {
+            orbital_elements,
+            perturbation_model,
+            prediction_accuracy,
+            ephemeris_table: ephemeris,
+            ground_track_predictions: ground_track,
+            propagator: Some(propagator),
         })
     }
     
@@ -426,6 +525,7 @@ pub struct RealSatellite {
     pub last_known_time: f64,
     pub last_observation_time: f64,
     pub sensor_characteristics: SensorCharacteristics,
+    pub tle: Option<TwoLineElements>,
 }
 
 #[derive(Debug, Clone)]
@@ -477,6 +577,8 @@ pub struct AtmosphericConditions {
 /// Interpolated observation from phantom satellite
 #[derive(Debug, Clone)]
 pub struct InterpolatedObservation {
+    /// Time the phantom would have observed, seconds
+    pub timestamp: f64,
     pub observation_data: MeasurementValue,
     pub interpolation_uncertainty: f64,
     pub interpolation_methods_used: InterpolationWeights,
@@ -660,6 +762,21 @@ pub trait InterpolationModel: std::fmt::Debug {
                            observations: &[RealObservation], 
                            target_location: &Point3<f64>, 
                            target_time: f64) -> f64;
+    
+    /// Value and uncertainty at each target time; models fitted to the whole
+    /// series override this to fit once rather than once per time
+    fn interpolate_series(&self,
+                          observations: &[RealObservation],
+                          target_location: &Point3<f64>,
+                          target_times: &[f64]) -> Result<Vec<(MeasurementValue, f64)>, AppError> {
+        target_times
+            .iter()
+            .map(|&time| {
+                let value = self.interpolate(observations, target_location, time)?;
+                Ok((value, self.estimate_uncertainty(observations, target_location, time)))
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -863,6 +980,7 @@ impl TemporalInterpolationEngine {
         let confidence_score = confidence_model.compute_confidence(time_since_last_obs);
         
         Ok(InterpolatedObservation {
+            timestamp: target_time,
             observation_data: combined_observation,
             interpolation_uncertainty: uncertainty,
             interpolation_methods_used: weights,
@@ -1236,6 +1354,29 @@ impl PhantomSatelliteConstellation {
         Ok(())
     }
     
+    /// Upcoming passes of every real satellite with a TLE that image the field, soonest first.
+    /// Passes of known imagers (Sentinel-2, Landsat, ...) outside their swath are dropped.
+    pub fn next_overpasses(&self,
+                           region: &BoundingBox,
+                           after: DateTime<Utc>,
+                           horizon: ChronoDuration) -> Result<Vec<SatellitePass>, AppError> {
+        let mut passes = Vec::new();
+        for real_satellite in self.real_satellites.values() {
+            let Some(tle) = &real_satellite.tle else { continue };
+            let half_field_of_view = tle.name.as_deref()
+                .and_then(imager_half_field_of_view_deg)
+                .or_else(|| imager_half_field_of_view_deg(&real_satellite.satellite_id));
+            let propagator = Sgp4Propagator::new(tle.clone())?;
+            let satellite_passes = propagator
+                .predict_passes_over_region(region, after, after + horizon, &PassSearch::default())?;
+            passes.extend(satellite_passes.into_iter().filter(|pass| {
+                half_field_of_view.is_none_or(|half_fov| pass.within_swath(half_fov))
+            }));
+        }
+        passes.sort_by_key(|pass| pass.aos);
+        Ok(passes)
+    }
+    
     /// Update phantom observations with agricultural prioritization
     pub fn update_phantom_observations(&mut self, current_time: f64) -> Result<Vec<InterpolatedObservation>, AppError> {
         let mut all_new_observations = Vec::new();
@@ -1283,7 +1424,7 @@ impl PhantomSatelliteConstellation {
         for phantom_satellite in self.phantom_satellites.values() {
             for obs in &phantom_satellite.interpolated_observations {
                 // Check time match (within tolerance)
-                if (obs.ground_footprint.center_point.x - target_time).abs() < 300.0 { // 5 minutes tolerance
+                if (obs.timestamp - target_time).abs() < OBSERVATION_TIME_TOLERANCE_S {
                     // Check region match if specified
                     if let Some(region) = region {
                         if self.observation_in_region(obs, region) {
@@ -1310,7 +1451,7 @@ impl PhantomSatelliteConstellation {
                 if self.observation_overlaps_region(obs, region, target_time) {
                     let phantom_obs = PhantomObservation {
                         phantom_satellite_id: obs.phantom_satellite_id.clone(),
-                        phantom_time: target_time,
+                        phantom_time: obs.timestamp,
                         location: obs.ground_footprint.center_point,
                         value: obs.observation_data.clone(),
                         confidence: obs.confidence_score,
@@ -1453,7 +1594,7 @@ impl PhantomSatelliteConstellation {
         let spatial_overlap = self.regions_overlap(&obs_bounds, &region.spatial_bounds);
         
         // Check temporal overlap
-        let temporal_overlap = (target_time - obs.temporal_interpolation_span).abs() < region.temporal_tolerance;
+        let temporal_overlap = (target_time - obs.timestamp).abs() < region.temporal_tolerance;
         
         spatial_overlap && temporal_overlap
     }
@@ -1554,7 +1695,7 @@ impl RealSatelliteConstellation {
         
         for satellite in self.satellites.values() {
             for obs in &satellite.observations {
-                if (obs.timestamp - target_time).abs() < 300.0 { // 5-minute tolerance
+                if (obs.timestamp - target_time).abs() < OBSERVATION_TIME_TOLERANCE_S {
                     observations.push(obs);
                 }
             }
@@ -0,0 +1,648 @@
+use std::collections::{HashMap, VecDeque};
+use chrono::{DateTime, Utc};
+use nalgebra::{Matrix2, Vector2};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::data_ingestion::{BoundingBox, RawDataRecord};
+use super::{
+    AgriculturalContext, ClimateZone, EnvironmentalData, GeoBounds, GeographicRegion, GrowthStage, IrrigationSystem,
+    MeasurementValue, PowerStatus, QualityFlags, SensorMeasurementBundle, SensorMetadata, SensorType,
+    SigmaNoughtImage, TimestampedMeasurement,
+};
+
+/// Sensing depth of C-band surface soil moisture
+pub const RADAR_SOIL_MOISTURE_DEPTH_CM: f64 = 5.0;
+/// Incidence angle that backscatter is normalised to for change detection
+const REFERENCE_INCIDENCE_DEG: f64 = 40.0;
+/// Standard deviation of a multilooked intensity in dB is about this over √looks
+const SPECKLE_DB: f64 = 4.34;
+/// Reference conditions of the clock delay model, so a retrieval, which has no
+/// weather of its own, carries no environmental timing correction
+const NEUTRAL_TEMPERATURE_C: f64 = 25.0;
+const NEUTRAL_HUMIDITY_PERCENT: f64 = 50.0;
+const NEUTRAL_PRESSURE_PA: f64 = 101_325.0;
+
+/// Field-averaged calibrated backscatter from one acquisition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackscatterObservation {
+    pub field_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub sigma0_vv_db: f64,
+    /// Cross-polarised backscatter, absent for single-polarisation products
+    pub sigma0_vh_db: Option<f64>,
+    pub incidence_angle_deg: f64,
+    /// Equivalent number of looks of the field average
+    pub equivalent_looks: f64,
+}
+
+impl BackscatterObservation {
+    /// Average σ0 over the valid pixels of a terrain-corrected image that fall inside `bounds`
+    pub fn from_sigma_nought(
+        field_id: impl Into<String>,
+        timestamp: DateTime<Utc>,
+        bounds: &BoundingBox,
+        vv: &SigmaNoughtImage,
+        vh: Option<&SigmaNoughtImage>,
+    ) -> Result<Self, AppError> {
+        let inside = |latitude: f64, longitude: f64| {
+            (bounds.south..=bounds.north).contains(&latitude) && (bounds.west..=bounds.east).contains(&longitude)
+        };
+        let (mut vv_sum, mut vh_sum, mut incidence_sum) = (0.0, 0.0, 0.0);
+        let (mut pixels, mut vh_pixels) = (0usize, 0usize);
+        for row in 0..vv.rows {
+            for col in 0..vv.cols {
+                let (latitude, longitude) = vv.pixel_location(row, col);
+                let Some(value) = vv.value_db(row, col).filter(|_| inside(latitude, longitude)) else {
+                    continue;
+                };
+                vv_sum += db_to_linear(value);
+                incidence_sum += vv.local_incidence_deg[row * vv.cols + col];
+                pixels += 1;
+                if let Some(value) = vh
+                    .and_then(|image| image.pixel_at(latitude, longitude))
+                    .and_then(|(r, c)| vh.and_then(|image| image.value_db(r, c)))
+                {
+                    vh_sum += db_to_linear(value);
+                    vh_pixels += 1;
+                }
+            }
+        }
+        if pixels == 0 {
+            return Err(AppError::validation("No valid backscatter pixels inside the field"));
+        }
+
+        let count = pixels as f64;
+        Ok(Self {
+            field_id: field_id.into(),
+            timestamp,
+            latitude: (bounds.north + bounds.south) / 2.0,
+            longitude: (bounds.east + bounds.west) / 2.0,
+            sigma0_vv_db: linear_to_db(vv_sum / count),
+            sigma0_vh_db: (vh_pixels > 0).then(|| linear_to_db(vh_sum / vh_pixels as f64)),
+            incidence_angle_deg: incidence_sum / count,
+            equivalent_looks: count,
+        })
+    }
+
+    /// Field statistics of a GRD product ingested by the radar collector
+    pub fn from_record(record: &RawDataRecord) -> Result<Self, AppError> {
+        let mut observation: Self = serde_json::from_value(record.data.clone())
+            .map_err(|e| AppError::validation(format!("Invalid GRD field statistics: {}", e)))?;
+        observation.timestamp = record.timestamp;
+        Ok(observation)
+    }
+}
+
+/// Water Cloud Model coefficients for one polarisation
+///
+/// σ0 = A·V·cosθ·(1 − τ²) + τ²·σ0_soil, with two-way canopy transmissivity
+/// τ² = exp(−2B·V / cosθ), V the vegetation water content (kg/m²) and the bare
+/// soil term linear in volumetric moisture in dB.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaterCloudParameters {
+    pub a: f64,
+    pub b: f64,
+    /// Bare soil backscatter of dry soil at the reference incidence angle (dB)
+    pub c_db: f64,
+    /// Bare soil sensitivity to moisture (dB per m³/m³)
+    pub d_db: f64,
+    /// Bare soil change in backscatter with incidence angle (dB/degree)
+    pub incidence_slope_db_per_deg: f64,
+}
+
+impl WaterCloudParameters {
+    /// Generic C-band VV coefficients for cropland
+    pub fn c_band_vv() -> Self {
+        Self { a: 0.12, b: 0.09, c_db: -18.0, d_db: 35.0, incidence_slope_db_per_deg: -0.15 }
+    }
+
+    /// Generic C-band VH coefficients for cropland
+    pub fn c_band_vh() -> Self {
+        Self { a: 0.03, b: 0.12, c_db: -28.0, d_db: 30.0, incidence_slope_db_per_deg: -0.1 }
+    }
+
+    /// Two-way transmissivity of the canopy
+    pub fn transmissivity(&self, vegetation_water_content: f64, incidence: f64) -> f64 {
+        (-2.0 * self.b * vegetation_water_content / incidence.cos()).exp()
+    }
+
+    /// Direct canopy contribution, linear
+    pub fn vegetation_backscatter(&self, vegetation_water_content: f64, incidence: f64) -> f64 {
+        self.a * vegetation_water_content * incidence.cos() * (1.0 - self.transmissivity(vegetation_water_content, incidence))
+    }
+
+    /// Bare soil backscatter in dB
+    pub fn soil_backscatter_db(&self, soil_moisture: f64, incidence: f64) -> f64 {
+        self.c_db + self.d_db * soil_moisture + self.incidence_slope_db_per_deg * (incidence.to_degrees() - REFERENCE_INCIDENCE_DEG)
+    }
+
+    /// Total backscatter in dB at `incidence` radians
+    pub fn backscatter_db(&self, soil_moisture: f64, vegetation_water_content: f64, incidence: f64) -> f64 {
+        let soil = db_to_linear(self.soil_backscatter_db(soil_moisture, incidence));
+        linear_to_db(
+            self.vegetation_backscatter(vegetation_water_content, incidence)
+                + self.transmissivity(vegetation_water_content, incidence) * soil,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoilMoistureRetrievalConfig {
+    pub vv: WaterCloudParameters,
+    pub vh: WaterCloudParameters,
+    /// Driest volumetric moisture the soil reaches (m³/m³)
+    pub residual_moisture: f64,
+    /// Saturated volumetric moisture (m³/m³)
+    pub porosity: f64,
+    /// Largest vegetation water content considered (kg/m²)
+    pub max_vegetation_water_content: f64,
+    /// Model error added to speckle in the backscatter noise (dB)
+    pub model_error_db: f64,
+    /// Uncertainty of the generic dry soil backscatter at a particular field (dB)
+    pub soil_calibration_error_db: f64,
+    /// Acquisitions needed before a field's dry and wet references are trusted
+    pub min_history: usize,
+    /// Smallest spread between the dry and wet references (dB)
+    pub min_dynamic_range_db: f64,
+    /// Acquisitions kept per field
+    pub history_length: usize,
+    /// Error of the change detection estimate beyond speckle (m³/m³)
+    pub change_detection_error: f64,
+    pub soil_type: String,
+}
+
+impl Default for SoilMoistureRetrievalConfig {
+    fn default() -> Self {
+        Self {
+            vv: WaterCloudParameters::c_band_vv(),
+            vh: WaterCloudParameters::c_band_vh(),
+            residual_moisture: 0.05,
+            porosity: 0.45,
+            max_vegetation_water_content: 6.0,
+            model_error_db: 0.5,
+            soil_calibration_error_db: 2.0,
+            min_history: 6,
+            min_dynamic_range_db: 2.0,
+            history_length: 120,
+            change_detection_error: 0.03,
+            soil_type: "loam".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RetrievalMethod {
+    /// Water Cloud Model inversion alone
+    WaterCloud,
+    /// Water Cloud inversion combined with change detection against the field's history
+    WaterCloudWithChangeDetection,
+}
+
+/// Surface soil moisture and vegetation water content of a field at one acquisition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoilMoistureRetrieval {
+    pub field_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Volumetric water content of the top few centimetres (m³/m³)
+    pub soil_moisture: f64,
+    pub soil_moisture_uncertainty: f64,
+    /// kg/m²
+    pub vegetation_water_content: f64,
+    pub vegetation_water_content_uncertainty: f64,
+    pub method: RetrievalMethod,
+    pub incidence_angle_deg: f64,
+    pub soil_type: String,
+}
+
+impl SoilMoistureRetrieval {
+    /// Measurement for the fusion engine, reported under `SensorType::SatelliteRadar`
+    pub fn to_measurement(&self) -> TimestampedMeasurement {
+        TimestampedMeasurement {
+            timestamp: self.timestamp.timestamp_millis() as f64 / 1000.0,
+            value: MeasurementValue::SoilMoisture {
+                value: self.soil_moisture,
+                depth_cm: RADAR_SOIL_MOISTURE_DEPTH_CM,
+                soil_type: self.soil_type.clone(),
+            },
+            uncertainty: self.soil_moisture_uncertainty,
+            temporal_uncertainty: None,
+            environmental_data: EnvironmentalData {
+                temperature: NEUTRAL_TEMPERATURE_C,
+                humidity: NEUTRAL_HUMIDITY_PERCENT,
+                pressure: NEUTRAL_PRESSURE_PA,
+                altitude: 0.0,
+                magnetic_field: None,
+                solar_activity: None,
+                electromagnetic_interference: None,
+                vibration_level: None,
+            },
+            sensor_metadata: SensorMetadata {
+                sensor_id: format!("sar-{}", self.field_id),
+                manufacturer: String::new(),
+                model: "water-cloud-retrieval".to_string(),
+                serial_number: String::new(),
+                calibration_date: self.timestamp,
+                last_maintenance: self.timestamp,
+                firmware_version: String::new(),
+                location: (self.latitude, self.longitude, 0.0),
+                installation_date: self.timestamp,
+                communication_latency: None,
+                power_status: PowerStatus::GridPowered,
+                communication_quality: 1.0,
+                calibration_version: None,
+            },
+            quality_flags: QualityFlags {
+                is_valid: true,
+                is_calibrated: true,
+                drift_detected: false,
+                outlier_detected: false,
+                communication_error: false,
+                sensor_malfunction: false,
+                environmental_impact: false,
+                data_completeness: 1.0,
+            },
+        }
+    }
+    /// Bundle of the retrievals from one batch of acquisitions, over the fields they cover;
+    /// the radar product carries no crop context, so that is left generic
+    pub fn bundle(retrievals: &[Self]) -> Option<SensorMeasurementBundle> {
+        let start = retrievals.iter().map(|r| r.timestamp).min()?;
+        let end = retrievals.iter().map(|r| r.timestamp).max()?;
+        let bounds = retrievals.iter().fold(
+            GeoBounds { north: f64::MIN, south: f64::MAX, east: f64::MIN, west: f64::MAX },
+            |b, r| GeoBounds {
+                north: b.north.max(r.latitude),
+                south: b.south.min(r.latitude),
+                east: b.east.max(r.longitude),
+                west: b.west.min(r.longitude),
+            },
+        );
+
+        Some(SensorMeasurementBundle {
+            measurements: HashMap::from([(SensorType::SatelliteRadar, retrievals.iter().map(Self::to_measurement).collect())]),
+            quality_metrics: HashMap::new(),
+            temporal_window: (start, end),
+            bundle_id: uuid::Uuid::new_v4(),
+            geographic_region: GeographicRegion {
+                name: "radar soil moisture fields".to_string(),
+                bounds,
+                elevation_range: (0.0, 0.0),
+                climate_zone: ClimateZone::Semiarid,
+                soil_types: vec![retrievals[0].soil_type.clone()],
+                typical_crops: Vec::new(),
+            },
+            agricultural_context: AgriculturalContext {
+                crop_type: String::new(),
+                growth_stage: GrowthStage::Vegetative,
+                planting_date: None,
+                expected_harvest_date: None,
+                irrigation_system: IrrigationSystem::None,
+                field_management_practices: Vec::new(),
+                historical_yield_data: None,
+            },
+        })
+    }
+}
+
+/// Retrieves soil moisture from radar backscatter, keeping each field's history
+/// of vegetation-corrected soil backscatter for change detection
+#[derive(Debug, Clone, Default)]
+pub struct RadarSoilMoistureRetriever {
+    pub config: SoilMoistureRetrievalConfig,
+    history: HashMap<String, VecDeque<f64>>,
+}
+
+impl RadarSoilMoistureRetriever {
+    pub fn new(config: SoilMoistureRetrievalConfig) -> Self {
+        Self { config, history: HashMap::new() }
+    }
+
+    /// Invert the Water Cloud Model and, once the field has enough history, combine it
+    /// with change detection by inverse-variance weighting
+    pub fn retrieve(&mut self, observation: &BackscatterObservation) -> Result<SoilMoistureRetrieval, AppError> {
+        let config = &self.config;
+        if !observation.sigma0_vv_db.is_finite() || observation.sigma0_vh_db.is_some_and(|v| !v.is_finite()) {
+            return Err(AppError::validation("Backscatter must be finite"));
+        }
+        if !(0.0..90.0).contains(&observation.incidence_angle_deg) || observation.equivalent_looks <= 0.0 {
+            return Err(AppError::validation("Incidence angle must be in [0, 90) degrees and looks positive"));
+        }
+        let incidence = observation.incidence_angle_deg.to_radians();
+        let noise_db = (SPECKLE_DB.powi(2) / observation.equivalent_looks + config.model_error_db.powi(2)).sqrt();
+        let (estimate, covariance) = self.invert_water_cloud(observation, incidence, noise_db);
+        // Field roughness shifts the soil term by an unknown offset that the inversion reads as moisture
+        let calibration_variance = (config.soil_calibration_error_db / config.vv.d_db).powi(2);
+        let (mut soil_moisture, mut variance) = (estimate[0], covariance[(0, 0)] + calibration_variance);
+        let mut method = RetrievalMethod::WaterCloud;
+
+        // Soil backscatter with the canopy removed, normalised to the reference incidence
+        let canopy = config.vv.vegetation_backscatter(estimate[1], incidence);
+        let soil = (db_to_linear(observation.sigma0_vv_db) - canopy) / config.vv.transmissivity(estimate[1], incidence);
+        if soil > 0.0 {
+            let normalised = linear_to_db(soil)
+                - config.vv.incidence_slope_db_per_deg * (observation.incidence_angle_deg - REFERENCE_INCIDENCE_DEG);
+            let history = self.history.entry(observation.field_id.clone()).or_default();
+            history.push_back(normalised);
+            while history.len() > config.history_length {
+                history.pop_front();
+            }
+            if let Some((dry, wet)) = references(history, config) {
+                let range = config.porosity - config.residual_moisture;
+                let relative = ((normalised - dry) / (wet - dry)).clamp(0.0, 1.0);
+                let detected = config.residual_moisture + relative * range;
+                let detected_variance = (range * noise_db / (wet - dry)).powi(2) + config.change_detection_error.powi(2);
+                soil_moisture = (soil_moisture / variance + detected / detected_variance) / (1.0 / variance + 1.0 / detected_variance);
+                variance = 1.0 / (1.0 / variance + 1.0 / detected_variance);
+                method = RetrievalMethod::WaterCloudWithChangeDetection;
+            }
+        }
+
+        Ok(SoilMoistureRetrieval {
+            field_id: observation.field_id.clone(),
+            timestamp: observation.timestamp,
+            latitude: observation.latitude,
+            longitude: observation.longitude,
+            soil_moisture,
+            soil_moisture_uncertainty: variance.sqrt(),
+            vegetation_water_content: estimate[1],
+            vegetation_water_content_uncertainty: covariance[(1, 1)].sqrt(),
+            method,
+            incidence_angle_deg: observation.incidence_angle_deg,
+            soil_type: config.soil_type.clone(),
+        })
+    }
+
+    /// Maximum a posteriori (soil moisture, vegetation water content) under a broad
+    /// Gaussian prior centred in the valid range, with its posterior covariance
+    fn invert_water_cloud(&self, observation: &BackscatterObservation, incidence: f64, noise_db: f64) -> (Vector2<f64>, Matrix2<f64>) {
+        let config = &self.config;
+        let lower = Vector2::new(config.residual_moisture, 0.0);
+        let upper = Vector2::new(config.porosity, config.max_vegetation_water_content);
+        let prior_mean = (lower + upper) / 2.0;
+        let prior_std = (upper - lower) / 2.0;
+
+        let mut channels = vec![(&config.vv, observation.sigma0_vv_db)];
+        if let Some(vh) = observation.sigma0_vh_db {
+            channels.push((&config.vh, vh));
+        }
+        // Whitened residuals and their Jacobian, observations first then the prior
+        let linearise = |x: &Vector2<f64>| {
+            let mut residuals = Vec::with_capacity(channels.len() + 2);
+            let mut jacobian = Vec::with_capacity(channels.len() + 2);
+            for (parameters, measured) in &channels {
+                let model = |x: &Vector2<f64>| parameters.backscatter_db(x[0], x[1], incidence);
+                residuals.push((measured - model(x)) / noise_db);
+                let gradient = Vector2::from_fn(|i, _| {
+                    let step = 1e-4 * (upper[i] - lower[i]);
+                    let mut ahead = *x;
+                    ahead[i] += step;
+                    let mut behind = *x;
+                    behind[i] -= step;
+                    (model(&ahead) - model(&behind)) / (2.0 * step)
+                });
+                jacobian.push(gradient / noise_db);
+            }
+            for i in 0..2 {
+                residuals.push((prior_mean[i] - x[i]) / prior_std[i]);
+                let mut unit = Vector2::zeros();
+                unit[i] = 1.0 / prior_std[i];
+                jacobian.push(unit);
+            }
+            (residuals, jacobian)
+        };
+        let cost = |x: &Vector2<f64>| linearise(x).0.iter().map(|r| r * r).sum::<f64>();
+
+        // Coarse grid first: the model saturates under dense canopy, so the cost need not be convex
+        const GRID: usize = 24;
+        let mut estimate = prior_mean;
+        let mut best = f64::INFINITY;
+        for i in 0..=GRID {
+            for j in 0..=GRID {
+                let candidate = lower + (upper - lower).component_mul(&Vector2::new(i as f64, j as f64)) / GRID as f64;
+                let candidate_cost = cost(&candidate);
+                if candidate_cost < best {
+                    best = candidate_cost;
+                    estimate = candidate;
+                }
+            }
+        }
+
+        let mut information = Matrix2::identity();
+        for _ in 0..20 {
+            let (residuals, jacobian) = linearise(&estimate);
+            information = jacobian.iter().map(|g| g * g.transpose()).sum();
+            let gradient: Vector2<f64> = jacobian.iter().zip(&residuals).map(|(g, r)| g * *r).sum();
+            let Some(step) = information.try_inverse().map(|inverse| inverse * gradient) else {
+                break;
+            };
+            let next = estimate + step;
+            let next = Vector2::new(next[0].clamp(lower[0], upper[0]), next[1].clamp(lower[1], upper[1]));
+            let converged = (next - estimate).abs().iter().zip((upper - lower).iter()).all(|(d, span)| *d < 1e-7 * span);
+            estimate = next;
+            if converged {
+                break;
+            }
+        }
+        let covariance = information.try_inverse().unwrap_or_else(|| Matrix2::from_diagonal(&prior_std.component_mul(&prior_std)));
+        (estimate, covariance)
+    }
+}
+
+/// Dry and wet references as the 5th and 95th percentiles of the field's history
+fn references(history: &VecDeque<f64>, config: &SoilMoistureRetrievalConfig) -> Option<(f64, f64)> {
+    if history.len() < config.min_history {
+        return None;
+    }
+    let mut sorted: Vec<f64> = history.iter().copied().collect();
+    sorted.sort_by(f64::total_cmp);
+    let percentile = |p: f64| sorted[(p * (sorted.len() - 1) as f64).round() as usize];
+    let (dry, wet) = (percentile(0.05), percentile(0.95));
+    (wet - dry >= config.min_dynamic_range_db).then_some((dry, wet))
+}
+
+fn db_to_linear(value: f64) -> f64 {
+    10f64.powf(value / 10.0)
+}
+
+fn linear_to_db(value: f64) -> f64 {
+    10.0 * value.max(1e-12).log10()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use rustfft::num_complex::Complex64;
+    use crate::data_fusion::{
+        AtmosphericCorrector, EarthModel, ElevationSource, FocusedSarImage, GeometricCorrector, SarOrbitGeometry,
+        SarSystemParameters,
+    };
+
+    /// Plane rising `slope_deg` away from the radar, level at `reference_ground_range`
+    struct TiltedPlane {
+        geometry: SarOrbitGeometry,
+        slope_deg: f64,
+        reference_ground_range: f64,
+    }
+
+    impl ElevationSource for TiltedPlane {
+        fn elevation_m(&self, latitude: f64, longitude: f64) -> Option<f64> {
+            let (_, ground_range) = self.geometry.track_coordinates(EarthModel::default().radius, latitude, longitude);
+            Some((ground_range - self.reference_ground_range) * self.slope_deg.to_radians().tan())
+        }
+    }
+
+    fn observation(config: &SoilMoistureRetrievalConfig, day: i64, soil_moisture: f64, vegetation: f64, incidence_deg: f64) -> BackscatterObservation {
+        let incidence = incidence_deg.to_radians();
+        BackscatterObservation {
+            field_id: "buhera-north".to_string(),
+            timestamp: Utc.with_ymd_and_hms(2025, 11, 1, 3, 30, 0).unwrap() + Duration::days(day),
+            latitude: -19.3,
+            longitude: 31.4,
+            sigma0_vv_db: config.vv.backscatter_db(soil_moisture, vegetation, incidence),
+            sigma0_vh_db: Some(config.vh.backscatter_db(soil_moisture, vegetation, incidence)),
+            incidence_angle_deg: incidence_deg,
+            equivalent_looks: 400.0,
+        }
+    }
+
+    #[test]
+    fn test_water_cloud_inversion_recovers_moisture_and_vegetation() {
+        let config = SoilMoistureRetrievalConfig::default();
+        let mut retriever = RadarSoilMoistureRetriever::new(config.clone());
+        for (soil_moisture, vegetation, incidence) in [(0.12, 0.5, 32.0), (0.30, 2.0, 39.0), (0.38, 3.5, 44.0)] {
+            let retrieval = retriever.retrieve(&observation(&config, 0, soil_moisture, vegetation, incidence)).unwrap();
+            assert_eq!(retrieval.method, RetrievalMethod::WaterCloud);
+            assert!((retrieval.soil_moisture - soil_moisture).abs() < 0.01, "{:?}", retrieval);
+            assert!((retrieval.vegetation_water_content - vegetation).abs() < 0.15, "{:?}", retrieval);
+            assert!(retrieval.soil_moisture_uncertainty > 0.0 && retrieval.soil_moisture_uncertainty < 0.1);
+        }
+
+        // Without the cross-polarised channel the canopy is barely constrained
+        let mut single = observation(&config, 0, 0.30, 2.0, 39.0);
+        single.sigma0_vh_db = None;
+        let dual = retriever.retrieve(&observation(&config, 0, 0.30, 2.0, 39.0)).unwrap();
+        let single = retriever.retrieve(&single).unwrap();
+        assert!(single.vegetation_water_content_uncertainty > 2.0 * dual.vegetation_water_content_uncertainty);
+
+        let measurement = dual.to_measurement();
+        assert!(matches!(
+            measurement.value,
+            MeasurementValue::SoilMoisture { value, depth_cm, .. } if value == dual.soil_moisture && depth_cm == RADAR_SOIL_MOISTURE_DEPTH_CM
+        ));
+        assert_eq!(measurement.uncertainty, dual.soil_moisture_uncertainty);
+    }
+
+    #[test]
+    fn test_retrievals_bundle_without_environmental_timing_correction() {
+        let config = SoilMoistureRetrievalConfig::default();
+        let mut retriever = RadarSoilMoistureRetriever::new(config.clone());
+        let mut east = observation(&config, 1, 0.20, 1.0, 38.0);
+        east.field_id = "buhera-east".to_string();
+        east.longitude = 31.6;
+        let retrievals = vec![
+            retriever.retrieve(&observation(&config, 0, 0.30, 2.0, 39.0)).unwrap(),
+            retriever.retrieve(&east).unwrap(),
+        ];
+
+        let bundle = SoilMoistureRetrieval::bundle(&retrievals).unwrap();
+        let measurements = &bundle.measurements[&SensorType::SatelliteRadar];
+        assert_eq!(measurements.len(), 2);
+        for measurement in measurements {
+            let environment = &measurement.environmental_data;
+            assert!(environment.temperature.is_finite() && environment.humidity.is_finite() && environment.pressure.is_finite());
+        }
+        assert_eq!(bundle.temporal_window, (retrievals[0].timestamp, retrievals[1].timestamp));
+        assert_eq!((bundle.geographic_region.bounds.west, bundle.geographic_region.bounds.east), (31.4, 31.6));
+        assert!(SoilMoistureRetrieval::bundle(&[]).is_none());
+    }
+
+    #[test]
+    fn test_retrieval_on_sloped_scene_inverts_at_local_incidence() {
+        let config = SoilMoistureRetrievalConfig::default();
+        let geometry = SarOrbitGeometry::default();
+        let earth = EarthModel::default();
+        let parameters = SarSystemParameters {
+            carrier_frequency: 5.405e9,
+            chirp_bandwidth: 20e6,
+            pulse_duration: 10e-6,
+            range_sampling_rate: 24e6,
+            prf: 1200.0,
+            antenna_length: 24.0,
+            polarisation: "VV".to_string(),
+        };
+        let (pulses, range_samples, scene_ground_range) = (32, 512, 420_000.0);
+        let range_spacing = 299_792_458.0 / (2.0 * parameters.range_sampling_rate);
+        let azimuth_spacing = geometry.ground_velocity(&earth) / parameters.prf;
+        let image = FocusedSarImage {
+            pulses,
+            range_samples,
+            slc: vec![Complex64::new(1.0, 0.0); pulses * range_samples],
+            near_slant_range: earth.slant_range(geometry.altitude, scene_ground_range, 0.0) - range_samples as f64 / 2.0 * range_spacing,
+            range_spacing,
+            azimuth_spacing,
+            parameters,
+            geometry: geometry.clone(),
+        };
+        // A foreslope facing the radar
+        let slope_deg = 12.0;
+        let plane = TiltedPlane { geometry: geometry.clone(), slope_deg, reference_ground_range: scene_ground_range };
+        let mut vv = GeometricCorrector::new().terrain_correct(&image, Some(&plane), &AtmosphericCorrector::new()).unwrap();
+
+        // The field scatters as the Water Cloud Model predicts at each pixel's local incidence
+        let (soil_moisture, vegetation) = (0.28, 1.5);
+        let mut vh = vv.clone();
+        for (i, &local_deg) in vv.local_incidence_deg.iter().enumerate() {
+            if vv.sigma0_db[i].is_finite() {
+                vv.sigma0_db[i] = config.vv.backscatter_db(soil_moisture, vegetation, local_deg.to_radians());
+                vh.sigma0_db[i] = config.vh.backscatter_db(soil_moisture, vegetation, local_deg.to_radians());
+            }
+        }
+        let (latitude, longitude) = geometry.location(earth.radius, pulses as f64 / 2.0 * azimuth_spacing, scene_ground_range);
+        let bounds = BoundingBox { north: latitude + 0.002, south: latitude - 0.002, east: longitude + 0.002, west: longitude - 0.002 };
+        let timestamp = Utc.with_ymd_and_hms(2025, 11, 1, 3, 30, 0).unwrap();
+        let observation = BackscatterObservation::from_sigma_nought("buhera-hillside", timestamp, &bounds, &vv, Some(&vh)).unwrap();
+
+        let ellipsoid_incidence = earth.incidence_angle(geometry.altitude, scene_ground_range, 0.0).to_degrees();
+        assert!((observation.incidence_angle_deg - (ellipsoid_incidence - slope_deg)).abs() < 0.2);
+        let retrieval = RadarSoilMoistureRetriever::new(config.clone()).retrieve(&observation).unwrap();
+        assert!((retrieval.soil_moisture - soil_moisture).abs() < 0.01, "{:?}", retrieval);
+
+        // Inverting at the ellipsoid incidence ignores the slope
+        let slope_blind = BackscatterObservation { incidence_angle_deg: ellipsoid_incidence, ..observation };
+        let retrieval = RadarSoilMoistureRetriever::new(config).retrieve(&slope_blind).unwrap();
+        assert!((retrieval.soil_moisture - soil_moisture).abs() > 0.03);
+    }
+
+    #[test]
+    fn test_change_detection_corrects_miscalibrated_soil_term() {
+        let truth = SoilMoistureRetrievalConfig::default();
+        // The site's soil is rougher than the generic coefficients assume
+        let mut site = truth.clone();
+        site.vv.c_db += 2.5;
+        site.vh.c_db += 2.5;
+        let mut retriever = RadarSoilMoistureRetriever::new(truth.clone());
+
+        let mut errors = (Vec::new(), Vec::new());
+        for day in 0..60 {
+            let soil_moisture = 0.25 + 0.2 * (day as f64 * 0.7).sin();
+            let incidence = if day % 2 == 0 { 33.0 } else { 43.0 };
+            let retrieval = retriever.retrieve(&observation(&site, day * 6, soil_moisture, 1.0, incidence)).unwrap();
+            let water_cloud_only = RadarSoilMoistureRetriever::new(truth.clone())
+                .retrieve(&observation(&site, day * 6, soil_moisture, 1.0, incidence))
+                .unwrap();
+            if day >= 20 {
+                assert_eq!(retrieval.method, RetrievalMethod::WaterCloudWithChangeDetection);
+                errors.0.push((retrieval.soil_moisture - soil_moisture).abs());
+                errors.1.push((water_cloud_only.soil_moisture - soil_moisture).abs());
+            }
+        }
+        let mean = |values: &Vec<f64>| values.iter().sum::<f64>() / values.len() as f64;
+        assert!(mean(&errors.0) < 0.5 * mean(&errors.1), "{} vs {}", mean(&errors.0), mean(&errors.1));
+    }
+}
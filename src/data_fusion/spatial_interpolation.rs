@@ -0,0 +1,888 @@
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+// Kept local rather than shared with the terrain module: the
+// spatial_interpolation bench compiles this file on its own
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+const WGS84_SEMI_MAJOR_AXIS_M: f64 = 6_378_137.0;
+const WGS84_FLATTENING: f64 = 1.0 / 298.257_223_563;
+const UTM_SCALE_FACTOR: f64 = 0.9996;
+const UTM_FALSE_EASTING_M: f64 = 500_000.0;
+const UTM_FALSE_NORTHING_SOUTH_M: f64 = 10_000_000.0;
+
+/// A point observation from a ground station
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StationObservation {
+    pub station_id: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Station elevation; looked up in the DEM when absent
+    pub elevation_m: Option<f64>,
+    pub value: f64,
+}
+
+/// Terrain heights for elevation-detrended kriging
+pub trait ElevationSource {
+    fn elevation_m(&self, latitude: f64, longitude: f64) -> Option<f64>;
+}
+
+// UTM projection
+
+/// Easting/northing in a UTM zone on the WGS84 ellipsoid
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UtmCoordinate {
+    pub zone: u8,
+    pub northern: bool,
+    pub easting_m: f64,
+    pub northing_m: f64,
+}
+
+impl UtmCoordinate {
+    /// Standard zone for a longitude, ignoring the Norway/Svalbard exceptions
+    pub fn zone_for(longitude: f64) -> u8 {
+        ((longitude + 180.0).rem_euclid(360.0) / 6.0).floor() as u8 % 60 + 1
+    }
+
+    fn central_meridian(zone: u8) -> f64 {
+        zone as f64 * 6.0 - 183.0
+    }
+
+    /// Transverse Mercator forward projection (Snyder 1987, eqs. 8-9 to 8-15)
+    pub fn from_geodetic(latitude: f64, longitude: f64, zone: u8) -> Self {
+        let (a, e2) = (WGS84_SEMI_MAJOR_AXIS_M, WGS84_FLATTENING * (2.0 - WGS84_FLATTENING));
+        let ep2 = e2 / (1.0 - e2);
+        let phi = latitude.to_radians();
+        let delta_lon = (longitude - Self::central_meridian(zone) + 540.0).rem_euclid(360.0) - 180.0;
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        let n = a / (1.0 - e2 * sin_phi * sin_phi).sqrt();
+        let t = phi.tan().powi(2);
+        let c = ep2 * cos_phi * cos_phi;
+        let big_a = delta_lon.to_radians() * cos_phi;
+        let m = meridian_arc(phi, a, e2);
+
+        let easting = UTM_SCALE_FACTOR
+            * n
+            * (big_a
+                + (1.0 - t + c) * big_a.powi(3) / 6.0
+                + (5.0 - 18.0 * t + t * t + 72.0 * c - 58.0 * ep2) * big_a.powi(5) / 120.0)
+            + UTM_FALSE_EASTING_M;
+        let northing = UTM_SCALE_FACTOR
+            * (m + n
+                * phi.tan()
+                * (big_a.powi(2) / 2.0
+                    + (5.0 - t + 9.0 * c + 4.0 * c * c) * big_a.powi(4) / 24.0
+                    + (61.0 - 58.0 * t + t * t + 600.0 * c - 330.0 * ep2) * big_a.powi(6) / 720.0));
+        let northern = latitude >= 0.0;
+        Self {
+            zone,
+            northern,
+            easting_m: easting,
+            northing_m: if northern { northing } else { northing + UTM_FALSE_NORTHING_SOUTH_M },
+        }
+    }
+
+    /// Inverse projection back to (latitude, longitude) in degrees (Snyder 1987, eqs. 8-17 to 8-25)
+    pub fn to_geodetic(self) -> (f64, f64) {
+        let (a, e2) = (WGS84_SEMI_MAJOR_AXIS_M, WGS84_FLATTENING * (2.0 - WGS84_FLATTENING));
+        let ep2 = e2 / (1.0 - e2);
+        let northing = if self.northern { self.northing_m } else { self.northing_m - UTM_FALSE_NORTHING_SOUTH_M };
+        let m = northing / UTM_SCALE_FACTOR;
+        let mu = m / (a * (1.0 - e2 / 4.0 - 3.0 * e2 * e2 / 64.0 - 5.0 * e2.powi(3) / 256.0));
+        let e1 = (1.0 - (1.0 - e2).sqrt()) / (1.0 + (1.0 - e2).sqrt());
+        let phi1 = mu
+            + (3.0 * e1 / 2.0 - 27.0 * e1.powi(3) / 32.0) * (2.0 * mu).sin()
+            + (21.0 * e1 * e1 / 16.0 - 55.0 * e1.powi(4) / 32.0) * (4.0 * mu).sin()
+            + 151.0 * e1.powi(3) / 96.0 * (6.0 * mu).sin()
+            + 1097.0 * e1.powi(4) / 512.0 * (8.0 * mu).sin();
+
+        let (sin_phi1, cos_phi1) = phi1.sin_cos();
+        let c1 = ep2 * cos_phi1 * cos_phi1;
+        let t1 = phi1.tan().powi(2);
+        let n1 = a / (1.0 - e2 * sin_phi1 * sin_phi1).sqrt();
+        let r1 = a * (1.0 - e2) / (1.0 - e2 * sin_phi1 * sin_phi1).powf(1.5);
+        let d = (self.easting_m - UTM_FALSE_EASTING_M) / (n1 * UTM_SCALE_FACTOR);
+
+        let phi = phi1
+            - n1 * phi1.tan() / r1
+                * (d * d / 2.0 - (5.0 + 3.0 * t1 + 10.0 * c1 - 4.0 * c1 * c1 - 9.0 * ep2) * d.powi(4) / 24.0
+                    + (61.0 + 90.0 * t1 + 298.0 * c1 + 45.0 * t1 * t1 - 252.0 * ep2 - 3.0 * c1 * c1) * d.powi(6) / 720.0);
+        let lambda = (d - (1.0 + 2.0 * t1 + c1) * d.powi(3) / 6.0
+            + (5.0 - 2.0 * c1 + 28.0 * t1 - 3.0 * c1 * c1 + 8.0 * ep2 + 24.0 * t1 * t1) * d.powi(5) / 120.0)
+            / cos_phi1;
+        (phi.to_degrees(), Self::central_meridian(self.zone) + lambda.to_degrees())
+    }
+}
+
+fn meridian_arc(phi: f64, a: f64, e2: f64) -> f64 {
+    let (e4, e6) = (e2 * e2, e2.powi(3));
+    a * ((1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0) * phi
+        - (3.0 * e2 / 8.0 + 3.0 * e4 / 32.0 + 45.0 * e6 / 1024.0) * (2.0 * phi).sin()
+        + (15.0 * e4 / 256.0 + 45.0 * e6 / 1024.0) * (4.0 * phi).sin()
+        - 35.0 * e6 / 3072.0 * (6.0 * phi).sin())
+}
+
+// Output grids
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GridProjection {
+    LatLon,
+    Utm { zone: u8, northern: bool },
+}
+
+/// A regular grid whose row 0 is the northern edge and column 0 the western edge.
+/// `origin_x`/`origin_y` are the outer corner: (west longitude, north latitude) for
+/// lat/lon grids and (minimum easting, maximum northing) for UTM grids.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridDefinition {
+    pub projection: GridProjection,
+    pub origin_x: f64,
+    pub origin_y: f64,
+    pub cell_size: f64,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl GridDefinition {
+    pub fn lat_lon(north: f64, south: f64, east: f64, west: f64, resolution_deg: f64) -> Result<Self, AppError> {
+        if !resolution_deg.is_finite() || resolution_deg <= 0.0 || north <= south || east <= west {
+            return Err(AppError::validation("Lat/lon grid needs north > south, east > west and a positive resolution"));
+        }
+        Ok(Self {
+            projection: GridProjection::LatLon,
+            origin_x: west,
+            origin_y: north,
+            cell_size: resolution_deg,
+            // Tolerate extents that are whole multiples of the resolution up to rounding
+            rows: ((north - south) / resolution_deg - 1e-9).ceil() as usize,
+            cols: ((east - west) / resolution_deg - 1e-9).ceil() as usize,
+        })
+    }
+
+    pub fn utm(zone: u8, northern: bool, min_easting_m: f64, max_northing_m: f64, cell_size_m: f64, rows: usize, cols: usize) -> Result<Self, AppError> {
+        if !(1..=60).contains(&zone) {
+            return Err(AppError::validation(format!("UTM zone {} is outside 1-60", zone)));
+        }
+        if !cell_size_m.is_finite() || cell_size_m <= 0.0 || rows == 0 || cols == 0 {
+            return Err(AppError::validation("UTM grid needs a positive cell size and at least one cell"));
+        }
+        Ok(Self {
+            projection: GridProjection::Utm { zone, northern },
+            origin_x: min_easting_m,
+            origin_y: max_northing_m,
+            cell_size: cell_size_m,
+            rows,
+            cols,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// (latitude, longitude) of a cell centre
+    pub fn cell_centre(&self, row: usize, col: usize) -> (f64, f64) {
+        let x = self.origin_x + (col as f64 + 0.5) * self.cell_size;
+        let y = self.origin_y - (row as f64 + 0.5) * self.cell_size;
+        match self.projection {
+            GridProjection::LatLon => (y, x),
+            GridProjection::Utm { zone, northern } => {
+                UtmCoordinate { zone, northern, easting_m: x, northing_m: y }.to_geodetic()
+            }
+        }
+    }
+}
+
+/// Interpolated values in row-major order; cells that could not be estimated are NaN
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterpolatedGrid {
+    pub grid: GridDefinition,
+    pub values: Vec<f64>,
+    /// Kriging variance per cell, for methods that provide one
+    pub variances: Option<Vec<f64>>,
+}
+
+impl InterpolatedGrid {
+    pub fn value(&self, row: usize, col: usize) -> f64 {
+        self.values[row * self.grid.cols + col]
+    }
+
+    pub fn variance(&self, row: usize, col: usize) -> Option<f64> {
+        self.variances.as_ref().map(|v| v[row * self.grid.cols + col])
+    }
+}
+
+/// Equirectangular projection about the station centroid, in kilometres.
+/// Adequate for the regional networks this is used with; distances on
+/// continental grids are distorted away from the centroid latitude.
+#[derive(Debug, Clone, Copy)]
+struct LocalProjection {
+    latitude0: f64,
+    longitude0: f64,
+    cos_latitude0: f64,
+}
+
+impl LocalProjection {
+    fn about(stations: &[StationObservation]) -> Self {
+        let n = stations.len() as f64;
+        let latitude0 = stations.iter().map(|s| s.latitude).sum::<f64>() / n;
+        // Circular mean so networks straddling the antimeridian are centred correctly
+        let (sin_sum, cos_sum) = stations
+            .iter()
+            .fold((0.0, 0.0), |(s, c), st| (s + st.longitude.to_radians().sin(), c + st.longitude.to_radians().cos()));
+        Self { latitude0, longitude0: sin_sum.atan2(cos_sum).to_degrees(), cos_latitude0: latitude0.to_radians().cos() }
+    }
+
+    fn project(&self, latitude: f64, longitude: f64) -> (f64, f64) {
+        let delta_lon = (longitude - self.longitude0 + 540.0).rem_euclid(360.0) - 180.0;
+        (
+            EARTH_RADIUS_M / 1000.0 * delta_lon.to_radians() * self.cos_latitude0,
+            EARTH_RADIUS_M / 1000.0 * (latitude - self.latitude0).to_radians(),
+        )
+    }
+}
+
+fn distance_km(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+// Variograms
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VariogramModel {
+    Spherical,
+    Exponential,
+    Gaussian,
+}
+
+impl VariogramModel {
+    pub const ALL: [VariogramModel; 3] = [VariogramModel::Spherical, VariogramModel::Exponential, VariogramModel::Gaussian];
+
+    /// Unit-sill shape; `range_km` is the practical range (95% of the sill) for the
+    /// exponential and Gaussian models
+    fn shape(&self, lag_km: f64, range_km: f64) -> f64 {
+        let x = lag_km / range_km;
+        match self {
+            VariogramModel::Spherical if x >= 1.0 => 1.0,
+            VariogramModel::Spherical => 1.5 * x - 0.5 * x.powi(3),
+            VariogramModel::Exponential => 1.0 - (-3.0 * x).exp(),
+            VariogramModel::Gaussian => 1.0 - (-3.0 * x * x).exp(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Variogram {
+    pub model: VariogramModel,
+    pub nugget: f64,
+    pub partial_sill: f64,
+    pub range_km: f64,
+}
+
+impl Variogram {
+    pub fn semivariance(&self, lag_km: f64) -> f64 {
+        if lag_km <= 0.0 {
+            0.0
+        } else {
+            self.nugget + self.partial_sill * self.model.shape(lag_km, self.range_km)
+        }
+    }
+
+    pub fn sill(&self) -> f64 {
+        self.nugget + self.partial_sill
+    }
+
+    /// Weighted least-squares fit to an empirical variogram with the N(h)/h² weights
+    /// gstat uses by default. For a fixed range the model is linear in nugget and
+    /// partial sill, so the range is searched on a log grid and the other two solved
+    /// in closed form, constrained to be non-negative.
+    pub fn fit(empirical: &EmpiricalVariogram, model: VariogramModel) -> Result<(Self, f64), AppError> {
+        let bins: Vec<&VariogramBin> = empirical.bins.iter().filter(|b| b.pairs > 0 && b.lag_km > 0.0).collect();
+        if bins.len() < 3 {
+            return Err(AppError::validation("Need at least three populated lag bins to fit a variogram"));
+        }
+        let max_lag = bins.iter().map(|b| b.lag_km).fold(0.0, f64::max);
+        let min_lag = bins.iter().map(|b| b.lag_km).fold(f64::INFINITY, f64::min);
+        let weights: Vec<f64> = bins.iter().map(|b| b.pairs as f64 / (b.lag_km * b.lag_km)).collect();
+
+        let mut best: Option<(Self, f64)> = None;
+        const RANGE_STEPS: usize = 60;
+        for step in 0..=RANGE_STEPS {
+            let range_km = min_lag / 2.0 * (4.0 * max_lag / min_lag).powf(step as f64 / RANGE_STEPS as f64);
+            let shapes: Vec<f64> = bins.iter().map(|b| model.shape(b.lag_km, range_km)).collect();
+            for (nugget, partial_sill) in Self::constrained_coefficients(&bins, &shapes, &weights) {
+                let sse: f64 = bins
+                    .iter()
+                    .zip(&shapes)
+                    .zip(&weights)
+                    .map(|((b, s), w)| w * (b.semivariance - nugget - partial_sill * s).powi(2))
+                    .sum();
+                if best.as_ref().is_none_or(|(_, best_sse)| sse < *best_sse) {
+                    best = Some((Self { model, nugget, partial_sill, range_km }, sse));
+                }
+            }
+        }
+        best.ok_or_else(|| AppError::internal("Variogram fit produced no candidate"))
+    }
+
+    /// Unconstrained solution if it is feasible, otherwise the best with each coefficient pinned at zero
+    fn constrained_coefficients(bins: &[&VariogramBin], shapes: &[f64], weights: &[f64]) -> Vec<(f64, f64)> {
+        let (mut sw, mut ss, mut sss, mut sy, mut ssy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for ((b, s), w) in bins.iter().zip(shapes).zip(weights) {
+            sw += w;
+            ss += w * s;
+            sss += w * s * s;
+            sy += w * b.semivariance;
+            ssy += w * s * b.semivariance;
+        }
+        let det = sw * sss - ss * ss;
+        if det.abs() > 1e-12 * sw * sss {
+            let nugget = (sy * sss - ss * ssy) / det;
+            let partial_sill = (sw * ssy - ss * sy) / det;
+            if nugget >= 0.0 && partial_sill >= 0.0 {
+                return vec![(nugget, partial_sill)];
+            }
+        }
+        let mut candidates = vec![(sy / sw, 0.0)];
+        if sss > 0.0 {
+            candidates.push((0.0, (ssy / sss).max(0.0)));
+        }
+        candidates
+    }
+
+    /// Fits every model and keeps the one with the lowest weighted error
+    pub fn fit_best(empirical: &EmpiricalVariogram) -> Result<Self, AppError> {
+        let mut best: Option<(Self, f64)> = None;
+        for model in VariogramModel::ALL {
+            let (variogram, sse) = Self::fit(empirical, model)?;
+            if best.as_ref().is_none_or(|(_, best_sse)| sse < *best_sse) {
+                best = Some((variogram, sse));
+            }
+        }
+        best.map(|(v, _)| v).ok_or_else(|| AppError::internal("Variogram fit produced no candidate"))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariogramBin {
+    /// Mean separation of the pairs in the bin
+    pub lag_km: f64,
+    pub semivariance: f64,
+    pub pairs: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmpiricalVariogram {
+    pub bins: Vec<VariogramBin>,
+}
+
+impl EmpiricalVariogram {
+    /// Matheron estimator over `bin_count` equal-width bins out to half the largest separation
+    fn compute(points: &[(f64, f64)], values: &[f64], bin_count: usize) -> Self {
+        let mut pairs = Vec::with_capacity(points.len() * points.len().saturating_sub(1) / 2);
+        for i in 0..points.len() {
+            for j in (i + 1)..points.len() {
+                pairs.push((distance_km(points[i], points[j]), 0.5 * (values[i] - values[j]).powi(2)));
+            }
+        }
+        let cutoff = pairs.iter().map(|(d, _)| *d).fold(0.0, f64::max) / 2.0;
+        let width = cutoff / bin_count as f64;
+        let mut sums = vec![(0.0, 0.0, 0usize); bin_count];
+        if width > 0.0 {
+            for (d, g) in pairs.into_iter().filter(|(d, _)| *d <= cutoff) {
+                let sum = &mut sums[((d / width) as usize).min(bin_count - 1)];
+                sum.0 += d;
+                sum.1 += g;
+                sum.2 += 1;
+            }
+        }
+        Self {
+            bins: sums
+                .into_iter()
+                .filter(|(_, _, n)| *n > 0)
+                .map(|(d, g, n)| VariogramBin { lag_km: d / n as f64, semivariance: g / n as f64, pairs: n })
+                .collect(),
+        }
+    }
+}
+
+// Interpolation methods
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DriftOrder {
+    Linear,
+    Quadratic,
+}
+
+impl DriftOrder {
+    fn terms(&self, (x, y): (f64, f64)) -> Vec<f64> {
+        match self {
+            DriftOrder::Linear => vec![1.0, x, y],
+            DriftOrder::Quadratic => vec![1.0, x, y, x * x, x * y, y * y],
+        }
+    }
+}
+
+/// Kriging variants take `None` to fit the variogram from the stations, choosing the
+/// best of the spherical, exponential and Gaussian models
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SpatialInterpolationMethod {
+    InverseDistance { power: f64, max_neighbours: Option<usize> },
+    OrdinaryKriging { variogram: Option<Variogram> },
+    /// Kriging with a polynomial trend in the projected coordinates
+    UniversalKriging { drift: DriftOrder, variogram: Option<Variogram> },
+    /// Regresses the values on elevation, kriges the residuals and adds the
+    /// lapse-rate trend back using the DEM height of each target
+    ElevationDetrendedKriging { variogram: Option<Variogram> },
+    ThinPlateSpline { smoothing: f64 },
+}
+
+impl SpatialInterpolationMethod {
+    fn with_variogram(&self, fixed: Variogram) -> Self {
+        match self {
+            Self::OrdinaryKriging { .. } => Self::OrdinaryKriging { variogram: Some(fixed) },
+            Self::UniversalKriging { drift, .. } => Self::UniversalKriging { drift: *drift, variogram: Some(fixed) },
+            Self::ElevationDetrendedKriging { .. } => Self::ElevationDetrendedKriging { variogram: Some(fixed) },
+            other => other.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpatialEstimate {
+    pub value: f64,
+    pub variance: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+enum SurfaceModel {
+    InverseDistance { power: f64, max_neighbours: Option<usize> },
+    /// Dual kriging: the estimate is bᵀα and the variance bᵀA⁻¹b, where b stacks the
+    /// target semivariances over the drift terms
+    Kriging {
+        variogram: Variogram,
+        drift: Option<DriftOrder>,
+        system_inverse: DMatrix<f64>,
+        dual_weights: DVector<f64>,
+        /// Intercept and lapse rate per metre for elevation-detrended kriging
+        elevation_trend: Option<(f64, f64)>,
+    },
+    ThinPlateSpline { coefficients: DVector<f64> },
+}
+
+/// A surface fitted to one set of station observations
+#[derive(Debug, Clone)]
+pub struct SpatialSurface {
+    projection: LocalProjection,
+    points: Vec<(f64, f64)>,
+    values: Vec<f64>,
+    model: SurfaceModel,
+}
+
+impl SpatialSurface {
+    /// The variogram in use, for the kriging methods
+    pub fn variogram(&self) -> Option<Variogram> {
+        match &self.model {
+            SurfaceModel::Kriging { variogram, .. } => Some(*variogram),
+            _ => None,
+        }
+    }
+
+    /// Intercept and lapse rate (units per metre) removed before kriging
+    pub fn elevation_trend(&self) -> Option<(f64, f64)> {
+        match &self.model {
+            SurfaceModel::Kriging { elevation_trend, .. } => *elevation_trend,
+            _ => None,
+        }
+    }
+
+    /// Estimate at a location; `None` when elevation-detrended kriging has no height for it
+    pub fn predict(&self, latitude: f64, longitude: f64, elevation_m: Option<f64>) -> Option<SpatialEstimate> {
+        let target = self.projection.project(latitude, longitude);
+        match &self.model {
+            SurfaceModel::InverseDistance { power, max_neighbours } => {
+                let mut distances: Vec<(f64, f64)> =
+                    self.points.iter().zip(&self.values).map(|(p, v)| (distance_km(*p, target), *v)).collect();
+                if let Some(k) = max_neighbours.filter(|k| *k < distances.len()) {
+                    distances.select_nth_unstable_by(k, |a, b| a.0.total_cmp(&b.0));
+                    distances.truncate(k);
+                }
+                if let Some((_, v)) = distances.iter().find(|(d, _)| *d < 1e-9) {
+                    return Some(SpatialEstimate { value: *v, variance: None });
+                }
+                let (weighted, total) = distances
+                    .iter()
+                    .fold((0.0, 0.0), |(wv, w), (d, v)| (wv + v / d.powf(*power), w + 1.0 / d.powf(*power)));
+                Some(SpatialEstimate { value: weighted / total, variance: None })
+            }
+            SurfaceModel::Kriging { variogram, drift, system_inverse, dual_weights, elevation_trend } => {
+                let trend = match elevation_trend {
+                    Some((intercept, lapse)) => intercept + lapse * elevation_m?,
+                    None => 0.0,
+                };
+                let mut rhs: Vec<f64> = self.points.iter().map(|p| variogram.semivariance(distance_km(*p, target))).collect();
+                match drift {
+                    Some(order) => rhs.extend(order.terms(target)),
+                    None => rhs.push(1.0),
+                }
+                let b = DVector::from_vec(rhs);
+                Some(SpatialEstimate {
+                    value: trend + b.dot(dual_weights),
+                    variance: Some((b.transpose() * system_inverse * &b)[(0, 0)].max(0.0)),
+                })
+            }
+            SurfaceModel::ThinPlateSpline { coefficients } => {
+                let n = self.points.len();
+                let value = self.points.iter().enumerate().map(|(i, p)| coefficients[i] * thin_plate_kernel(distance_km(*p, target))).sum::<f64>()
+                    + coefficients[n]
+                    + coefficients[n + 1] * target.0
+                    + coefficients[n + 2] * target.1;
+                Some(SpatialEstimate { value, variance: None })
+            }
+        }
+    }
+
+    pub fn interpolate_grid(&self, grid: &GridDefinition, dem: Option<&dyn ElevationSource>) -> InterpolatedGrid {
+        let mut values = Vec::with_capacity(grid.len());
+        let mut variances = Vec::with_capacity(grid.len());
+        for row in 0..grid.rows {
+            for col in 0..grid.cols {
+                let (latitude, longitude) = grid.cell_centre(row, col);
+                let elevation = dem.and_then(|d| d.elevation_m(latitude, longitude));
+                let estimate = self.predict(latitude, longitude, elevation);
+                values.push(estimate.map_or(f64::NAN, |e| e.value));
+                variances.push(estimate.and_then(|e| e.variance).unwrap_or(f64::NAN));
+            }
+        }
+        InterpolatedGrid {
+            grid: grid.clone(),
+            values,
+            variances: matches!(self.model, SurfaceModel::Kriging { .. }).then_some(variances),
+        }
+    }
+}
+
+fn thin_plate_kernel(r: f64) -> f64 {
+    if r <= 0.0 { 0.0 } else { r * r * r.ln() }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossValidationResidual {
+    pub station_id: String,
+    pub observed: f64,
+    pub predicted: f64,
+    pub variance: Option<f64>,
+}
+
+/// Leave-one-out scores. The standardised errors divide by the kriging standard
+/// deviation and should have mean near 0 and RMS near 1 when the variogram is right.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossValidationScores {
+    pub residuals: Vec<CrossValidationResidual>,
+    pub mean_error: f64,
+    pub mean_absolute_error: f64,
+    pub root_mean_square_error: f64,
+    pub mean_standardised_error: Option<f64>,
+    pub rms_standardised_error: Option<f64>,
+}
+
+/// Grids point station observations with the configured method
+#[derive(Debug, Clone)]
+pub struct SpatialInterpolationEngine {
+    pub method: SpatialInterpolationMethod,
+    pub variogram_bins: usize,
+}
+
+impl SpatialInterpolationEngine {
+    pub fn new(method: SpatialInterpolationMethod) -> Self {
+        Self { method, variogram_bins: 15 }
+    }
+
+    pub fn fit(&self, stations: &[StationObservation], dem: Option<&dyn ElevationSource>) -> Result<SpatialSurface, AppError> {
+        if stations.is_empty() {
+            return Err(AppError::validation("Spatial interpolation needs at least one station"));
+        }
+        if let Some(s) = stations.iter().find(|s| !s.value.is_finite() || !s.latitude.is_finite() || !s.longitude.is_finite()) {
+            return Err(AppError::validation(format!("Station {} has a non-finite value or location", s.station_id)));
+        }
+        let projection = LocalProjection::about(stations);
+        let points: Vec<(f64, f64)> = stations.iter().map(|s| projection.project(s.latitude, s.longitude)).collect();
+        let values: Vec<f64> = stations.iter().map(|s| s.value).collect();
+
+        let model = match &self.method {
+            SpatialInterpolationMethod::InverseDistance { power, max_neighbours } => {
+                if !power.is_finite() || *power <= 0.0 || *max_neighbours == Some(0) {
+                    return Err(AppError::validation("Inverse distance weighting needs a positive power and neighbour count"));
+                }
+                SurfaceModel::InverseDistance { power: *power, max_neighbours: *max_neighbours }
+            }
+            SpatialInterpolationMethod::OrdinaryKriging { variogram } => self.kriging(&points, &values, None, *variogram, None)?,
+            SpatialInterpolationMethod::UniversalKriging { drift, variogram } => {
+                self.kriging(&points, &values, Some(*drift), *variogram, None)?
+            }
+            SpatialInterpolationMethod::ElevationDetrendedKriging { variogram } => {
+                let elevations = stations
+                    .iter()
+                    .map(|s| {
+                        s.elevation_m.or_else(|| dem.and_then(|d| d.elevation_m(s.latitude, s.longitude))).ok_or_else(|| {
+                            AppError::validation(format!("Station {} has no elevation and the DEM does not cover it", s.station_id))
+                        })
+                    })
+                    .collect::<Result<Vec<f64>, AppError>>()?;
+                let trend = elevation_regression(&elevations, &values)?;
+                let residuals: Vec<f64> = values.iter().zip(&elevations).map(|(v, z)| v - trend.0 - trend.1 * z).collect();
+                self.kriging(&points, &residuals, None, *variogram, Some(trend))?
+            }
+            SpatialInterpolationMethod::ThinPlateSpline { smoothing } => thin_plate_spline(&points, &values, *smoothing)?,
+        };
+        Ok(SpatialSurface { projection, points, values, model })
+    }
+
+    /// Fits and evaluates the surface on `grid`
+    pub fn interpolate(
+        &self,
+        stations: &[StationObservation],
+        grid: &GridDefinition,
+        dem: Option<&dyn ElevationSource>,
+    ) -> Result<InterpolatedGrid, AppError> {
+        Ok(self.fit(stations, dem)?.interpolate_grid(grid, dem))
+    }
+
+    /// Leave-one-out cross-validation. Kriging variograms are fitted once on all
+    /// stations and held fixed, as is usual; the elevation trend is refitted per fold.
+    pub fn cross_validate(&self, stations: &[StationObservation], dem: Option<&dyn ElevationSource>) -> Result<CrossValidationScores, AppError> {
+        if stations.len() < 3 {
+            return Err(AppError::validation("Cross-validation needs at least three stations"));
+        }
+        let fold_engine = match self.fit(stations, dem)?.variogram() {
+            Some(variogram) => Self { method: self.method.with_variogram(variogram), variogram_bins: self.variogram_bins },
+            None => self.clone(),
+        };
+
+        let mut residuals = Vec::with_capacity(stations.len());
+        let mut held_in = stations.to_vec();
+        for (i, station) in stations.iter().enumerate() {
+            let held_out = held_in.remove(i);
+            let surface = fold_engine.fit(&held_in, dem)?;
+            held_in.insert(i, held_out);
+            let elevation = station.elevation_m.or_else(|| dem.and_then(|d| d.elevation_m(station.latitude, station.longitude)));
+            let Some(estimate) = surface.predict(station.latitude, station.longitude, elevation) else {
+                continue;
+            };
+            residuals.push(CrossValidationResidual {
+                station_id: station.station_id.clone(),
+                observed: station.value,
+                predicted: estimate.value,
+                variance: estimate.variance,
+            });
+        }
+
+        if residuals.is_empty() {
+            return Err(AppError::validation("Cross-validation could not predict any held-out station"));
+        }
+        let n = residuals.len() as f64;
+        let errors: Vec<f64> = residuals.iter().map(|r| r.predicted - r.observed).collect();
+        let standardised: Option<Vec<f64>> = residuals
+            .iter()
+            .zip(&errors)
+            .map(|(r, e)| r.variance.filter(|v| *v > 0.0).map(|v| e / v.sqrt()))
+            .collect();
+        Ok(CrossValidationScores {
+            mean_error: errors.iter().sum::<f64>() / n,
+            mean_absolute_error: errors.iter().map(|e| e.abs()).sum::<f64>() / n,
+            root_mean_square_error: (errors.iter().map(|e| e * e).sum::<f64>() / n).sqrt(),
+            mean_standardised_error: standardised.as_ref().map(|z| z.iter().sum::<f64>() / n),
+            rms_standardised_error: standardised.as_ref().map(|z| (z.iter().map(|e| e * e).sum::<f64>() / n).sqrt()),
+            residuals,
+        })
+    }
+
+    fn kriging(
+        &self,
+        points: &[(f64, f64)],
+        values: &[f64],
+        drift: Option<DriftOrder>,
+        variogram: Option<Variogram>,
+        elevation_trend: Option<(f64, f64)>,
+    ) -> Result<SurfaceModel, AppError> {
+        let n = points.len();
+        let drift_terms: Vec<Vec<f64>> = match drift {
+            Some(order) => points.iter().map(|p| order.terms(*p)).collect(),
+            None => vec![vec![1.0]; n],
+        };
+        let p = drift_terms[0].len();
+        if n <= p {
+            return Err(AppError::validation(format!("Kriging with {} drift terms needs more than {} stations", p, p)));
+        }
+        let variogram = match variogram {
+            Some(v) => v,
+            // Universal kriging fits the variogram to the OLS drift residuals
+            None => {
+                let residuals = if drift.is_some() { ols_residuals(&drift_terms, values)? } else { values.to_vec() };
+                Variogram::fit_best(&EmpiricalVariogram::compute(points, &residuals, self.variogram_bins))?
+            }
+        };
+
+        let size = n + p;
+        let system = DMatrix::from_fn(size, size, |i, j| match (i < n, j < n) {
+            (true, true) => variogram.semivariance(distance_km(points[i], points[j])),
+            (true, false) => drift_terms[i][j - n],
+            (false, true) => drift_terms[j][i - n],
+            (false, false) => 0.0,
+        });
+        let system_inverse = system
+            .try_inverse()
+            .ok_or_else(|| AppError::validation("Kriging system is singular; check for coincident stations"))?;
+        let mut augmented = values.to_vec();
+        augmented.resize(size, 0.0);
+        let dual_weights = &system_inverse * DVector::from_vec(augmented);
+        Ok(SurfaceModel::Kriging { variogram, drift, system_inverse, dual_weights, elevation_trend })
+    }
+}
+
+fn ols_residuals(design_rows: &[Vec<f64>], values: &[f64]) -> Result<Vec<f64>, AppError> {
+    let design = DMatrix::from_fn(design_rows.len(), design_rows[0].len(), |i, j| design_rows[i][j]);
+    let observed = DVector::from_column_slice(values);
+    let coefficients = (design.transpose() * &design)
+        .cholesky()
+        .ok_or_else(|| AppError::validation("Station layout cannot support the kriging drift"))?
+        .solve(&(design.transpose() * &observed));
+    Ok((observed - design * coefficients).iter().copied().collect())
+}
+
+fn elevation_regression(elevations: &[f64], values: &[f64]) -> Result<(f64, f64), AppError> {
+    let n = elevations.len() as f64;
+    let mean_z = elevations.iter().sum::<f64>() / n;
+    let mean_v = values.iter().sum::<f64>() / n;
+    let sxx: f64 = elevations.iter().map(|z| (z - mean_z).powi(2)).sum();
+    if sxx <= 0.0 {
+        return Err(AppError::validation("Stations all share one elevation; the lapse rate cannot be estimated"));
+    }
+    let sxy: f64 = elevations.iter().zip(values).map(|(z, v)| (z - mean_z) * (v - mean_v)).sum();
+    let lapse = sxy / sxx;
+    Ok((mean_v - lapse * mean_z, lapse))
+}
+
+fn thin_plate_spline(points: &[(f64, f64)], values: &[f64], smoothing: f64) -> Result<SurfaceModel, AppError> {
+    let n = points.len();
+    if n < 3 {
+        return Err(AppError::validation("Thin-plate spline needs at least three stations"));
+    }
+    if smoothing < 0.0 {
+        return Err(AppError::validation("Thin-plate spline smoothing must not be negative"));
+    }
+    let size = n + 3;
+    let system = DMatrix::from_fn(size, size, |i, j| match (i < n, j < n) {
+        (true, true) => thin_plate_kernel(distance_km(points[i], points[j])) + if i == j { smoothing } else { 0.0 },
+        (true, false) => [1.0, points[i].0, points[i].1][j - n],
+        (false, true) => [1.0, points[j].0, points[j].1][i - n],
+        (false, false) => 0.0,
+    });
+    let mut rhs = values.to_vec();
+    rhs.resize(size, 0.0);
+    let coefficients = system
+        .lu()
+        .solve(&DVector::from_vec(rhs))
+        .ok_or_else(|| AppError::validation("Thin-plate spline system is singular; stations may be collinear or coincident"))?;
+    Ok(SurfaceModel::ThinPlateSpline { coefficients })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stations(field: impl Fn(f64, f64, f64) -> f64) -> Vec<StationObservation> {
+        // A jittered 7x7 network over roughly 60 km
+        (0..49)
+            .map(|k| {
+                let (i, j) = ((k / 7) as f64, (k % 7) as f64);
+                let latitude = 52.0 + 0.08 * i + 0.013 * ((k * 7) % 5) as f64;
+                let longitude = 5.0 + 0.12 * j + 0.017 * ((k * 3) % 4) as f64;
+                let elevation = 40.0 * i + 25.0 * j;
+                StationObservation {
+                    station_id: format!("S{:02}", k),
+                    latitude,
+                    longitude,
+                    elevation_m: Some(elevation),
+                    value: field(latitude, longitude, elevation),
+                }
+            })
+            .collect()
+    }
+
+    struct Ramp;
+
+    impl ElevationSource for Ramp {
+        fn elevation_m(&self, latitude: f64, longitude: f64) -> Option<f64> {
+            Some(500.0 * (latitude - 52.0) + 200.0 * (longitude - 5.0))
+        }
+    }
+
+    #[test]
+    fn test_utm_round_trip_and_central_meridian() {
+        let origin = UtmCoordinate::from_geodetic(0.0, 3.0, 31);
+        assert!((origin.easting_m - 500_000.0).abs() < 1e-6 && origin.northing_m.abs() < 1e-6);
+        for &(lat, lon) in &[(52.37, 4.89), (-33.87, 151.21), (64.1, -21.9)] {
+            let utm = UtmCoordinate::from_geodetic(lat, lon, UtmCoordinate::zone_for(lon));
+            let (back_lat, back_lon) = utm.to_geodetic();
+            assert!((back_lat - lat).abs() < 1e-7 && (back_lon - lon).abs() < 1e-7, "{lat},{lon} -> {back_lat},{back_lon}");
+        }
+    }
+
+    #[test]
+    fn test_exact_interpolators_honour_stations_and_universal_kriging_reproduces_a_plane() {
+        let smooth = |lat: f64, lon: f64, _| 10.0 + 3.0 * (lat - 52.0) - 2.0 * (lon - 5.0) + ((lat * 20.0).sin() * (lon * 15.0).cos());
+        let network = stations(smooth);
+        for method in [
+            SpatialInterpolationMethod::InverseDistance { power: 2.0, max_neighbours: Some(8) },
+            SpatialInterpolationMethod::OrdinaryKriging { variogram: None },
+            SpatialInterpolationMethod::ThinPlateSpline { smoothing: 0.0 },
+        ] {
+            let surface = SpatialInterpolationEngine::new(method.clone()).fit(&network, None).unwrap();
+            for s in &network {
+                let estimate = surface.predict(s.latitude, s.longitude, None).unwrap();
+                assert!((estimate.value - s.value).abs() < 1e-6, "{:?} at {}", method, s.station_id);
+            }
+        }
+
+        let plane = stations(|lat, lon, _| 10.0 + 3.0 * (lat - 52.0) - 2.0 * (lon - 5.0));
+        let variogram = Variogram { model: VariogramModel::Exponential, nugget: 0.0, partial_sill: 1.0, range_km: 20.0 };
+        let engine = SpatialInterpolationEngine::new(SpatialInterpolationMethod::UniversalKriging { drift: DriftOrder::Linear, variogram: Some(variogram) });
+        let grid = GridDefinition::utm(31, true, 640_000.0, 5_820_000.0, 5_000.0, 6, 6).unwrap();
+        let gridded = engine.interpolate(&plane, &grid, None).unwrap();
+        for row in 0..grid.rows {
+            for col in 0..grid.cols {
+                let (lat, lon) = grid.cell_centre(row, col);
+                assert!((gridded.value(row, col) - (10.0 + 3.0 * (lat - 52.0) - 2.0 * (lon - 5.0))).abs() < 1e-6);
+                assert!(gridded.variance(row, col).unwrap() >= 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_elevation_detrending_recovers_the_lapse_rate_and_beats_ordinary_kriging() {
+        let temperature = |lat: f64, lon: f64, elevation: f64| 15.0 - 0.0065 * elevation + 0.3 * ((lat * 30.0).sin() + (lon * 25.0).cos());
+        let network = stations(temperature);
+        let detrended = SpatialInterpolationEngine::new(SpatialInterpolationMethod::ElevationDetrendedKriging { variogram: None });
+        let surface = detrended.fit(&network, Some(&Ramp)).unwrap();
+        let (_, lapse) = surface.elevation_trend().unwrap();
+        assert!((lapse + 0.0065).abs() < 0.001, "lapse {lapse}");
+
+        let detrended_scores = detrended.cross_validate(&network, Some(&Ramp)).unwrap();
+        let ordinary_scores =
+            SpatialInterpolationEngine::new(SpatialInterpolationMethod::OrdinaryKriging { variogram: None }).cross_validate(&network, None).unwrap();
+        assert_eq!(detrended_scores.residuals.len(), network.len());
+        assert!(detrended_scores.root_mean_square_error < ordinary_scores.root_mean_square_error);
+        assert!(detrended_scores.rms_standardised_error.unwrap().is_finite());
+
+        let grid = GridDefinition::lat_lon(52.5, 52.0, 5.7, 5.0, 0.1).unwrap();
+        let gridded = surface.interpolate_grid(&grid, Some(&Ramp));
+        assert_eq!((grid.rows, grid.cols), (5, 7));
+        assert!(gridded.values.iter().all(|v| v.is_finite()));
+    }
+}
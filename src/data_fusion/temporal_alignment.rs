@@ -36,6 +36,12 @@ pub struct TemporalAlignmentEngine {
     
     /// Temporal quality assessor
     quality_assessor: TemporalQualityAssessor,
+
+    /// Lag search and robust fit used for per-sensor clock calibration
+    drift_estimator: ClockDriftEstimator,
+
+    /// Latest clock offset/drift estimate per sensor
+    clock_estimates: HashMap<SensorType, ClockDriftEstimate>,
 }
 
 /// Atomic clock delay model with comprehensive error sources
@@ -129,8 +135,26 @@ impl AtomicClockDelayModel {
         let calibration_component = 0.5; // ns, typical calibration uncertainty
         let temp_uncertainty = self.temperature_coefficient * 0.1; // ±0.1°C temp uncertainty
         let aging_uncertainty = self.aging_rate * 0.01; // 1% aging uncertainty
+        let drift_uncertainty = self.frequency_drift_model.residual_uncertainty();
         
-        (calibration_component.powi(2) + temp_uncertainty.powi(2) + aging_uncertainty.powi(2)).sqrt()
+        (calibration_component.powi(2) + temp_uncertainty.powi(2) + aging_uncertainty.powi(2)
+            + drift_uncertainty.powi(2)).sqrt()
+    }
+
+    /// Model with no known hardware delays, for sensors that are only
+    /// corrected for their estimated clock drift
+    pub fn uncalibrated() -> Self {
+        Self {
+            cable_delay: 0.0,
+            processing_delay: 0.0,
+            temperature_coefficient: 0.0,
+            aging_rate: 0.0,
+            relativistic_correction: 0.0,
+            temperature_history: CircularBuffer::new(1000),
+            frequency_drift_model: DriftPredictor::new(),
+            environmental_corrections: HashMap::new(),
+            last_calibration: Utc::now().timestamp() as f64,
+        }
     }
 }
 
@@ -279,40 +303,199 @@ impl SplineInterpolator {
 }
 
 /// Dynamic Time Warping for multi-sensor temporal alignment
+///
+/// Only the cells admitted by the Sakoe–Chiba band and Itakura parallelogram
+/// are stored and evaluated, so memory and time are O(n·band) rather than
+/// O(n·m) when a constraint is set.
 #[derive(Debug)]
 pub struct DynamicTimeWarping {
-    pub cost_matrix: DMatrix<f64>,
+    /// Local costs of the admissible cells of the last alignment
+    pub cost_matrix: BandedMatrix,
     pub warping_path: Vec<(usize, usize)>,
     pub alignment_quality: f64,
     pub distance_metric: DTWDistanceMetric,
     pub constraints: DTWConstraints,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub enum DTWDistanceMetric {
     Euclidean,
     Manhattan,
     Cosine,
-    Custom(Box<dyn Fn(f64, f64) -> f64 + Send + Sync>),
+    /// Per-component distance, summed over components
+    Custom(Arc<dyn Fn(f64, f64) -> f64 + Send + Sync>),
+}
+
+impl std::fmt::Debug for DTWDistanceMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DTWDistanceMetric::Euclidean => write!(f, "Euclidean"),
+            DTWDistanceMetric::Manhattan => write!(f, "Manhattan"),
+            DTWDistanceMetric::Cosine => write!(f, "Cosine"),
+            DTWDistanceMetric::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+impl DTWDistanceMetric {
+    /// Distance between two feature vectors of equal length
+    pub fn distance(&self, a: &[f64], b: &[f64]) -> f64 {
+        match self {
+            DTWDistanceMetric::Euclidean => a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt(),
+            DTWDistanceMetric::Manhattan => a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum(),
+            DTWDistanceMetric::Cosine => {
+                let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+                let norms = a.iter().map(|x| x * x).sum::<f64>().sqrt() * b.iter().map(|y| y * y).sum::<f64>().sqrt();
+                if norms > 0.0 { 1.0 - dot / norms } else if a == b { 0.0 } else { 1.0 }
+            },
+            DTWDistanceMetric::Custom(component) => a.iter().zip(b).map(|(x, y)| component(*x, *y)).sum(),
+        }
+    }
+
+    /// Distance from `a` to the box [lower, upper]; a lower bound on the
+    /// distance to any vector inside the box, for metrics where one exists
+    fn distance_to_envelope(&self, a: &[f64], lower: &[f64], upper: &[f64]) -> Option<f64> {
+        let excess = a.iter().zip(lower.iter().zip(upper)).map(|(x, (lo, hi))| {
+            if x > hi { x - hi } else if x < lo { lo - x } else { 0.0 }
+        });
+        match self {
+            DTWDistanceMetric::Euclidean => Some(excess.map(|e| e * e).sum::<f64>().sqrt()),
+            DTWDistanceMetric::Manhattan => Some(excess.sum()),
+            DTWDistanceMetric::Cosine | DTWDistanceMetric::Custom(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct DTWConstraints {
     pub sakoe_chiba_radius: Option<usize>,
+    /// Restrict the path to local slopes between ½ and 2
     pub itakura_parallelogram: bool,
     pub step_pattern: StepPattern,
+    /// Pin both ends of the target; when false the reference may match any
+    /// subsequence of the target (open begin and end)
     pub boundary_constraints: bool,
 }
 
+/// Allowed moves (Δreference, Δtarget) and the weight applied to the local cost of the cell entered
 #[derive(Debug, Clone)]
 pub enum StepPattern {
-    Symmetric1,  // (1,1), (1,0), (0,1)
-    Symmetric2,  // (1,1), (2,1), (1,2)
+    Symmetric1,  // (1,1), (1,0), (0,1), all weight 1
+    Symmetric2,  // (1,1) weight 1, (2,0), (0,2) weight 2
+    SymmetricNormalizable,  // (1,0), (0,1) weight 1, (1,1) weight 2 — distance normalisable by n + m
     Asymmetric,  // (1,1), (1,0)
     Custom(Vec<(i32, i32, f64)>), // (di, dj, weight)
 }
 
+impl StepPattern {
+    fn steps(&self) -> Result<Vec<(usize, usize, f64)>, AppError> {
+        match self {
+            StepPattern::Symmetric1 => Ok(vec![(1, 1, 1.0), (1, 0, 1.0), (0, 1, 1.0)]),
+            StepPattern::Symmetric2 => Ok(vec![(1, 1, 1.0), (2, 0, 2.0), (0, 2, 2.0)]),
+            StepPattern::SymmetricNormalizable => Ok(vec![(1, 1, 2.0), (1, 0, 1.0), (0, 1, 1.0)]),
+            StepPattern::Asymmetric => Ok(vec![(1, 1, 1.0), (1, 0, 1.0)]),
+            StepPattern::Custom(steps) => steps
+                .iter()
+                .map(|&(di, dj, weight)| {
+                    if di < 0 || dj < 0 || (di == 0 && dj == 0) || weight.is_nan() || weight < 0.0 || weight.is_infinite() {
+                        Err(AppError::validation("DTW steps must move forward with a finite, non-negative weight"))
+                    } else {
+                        Ok((di as usize, dj as usize, weight))
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Every reference index is entered at least once, each time with weight ≥ 1,
+    /// which is what LB_Keogh needs to bound the DTW distance from below
+    fn visits_every_reference_index(&self) -> bool {
+        self.steps()
+            .map(|steps| steps.iter().all(|&(di, _, weight)| di <= 1 && weight >= 1.0))
+            .unwrap_or(false)
+    }
+}
+
+/// Row-wise band storage: each row keeps only its admissible column range
+#[derive(Debug, Clone, Default)]
+pub struct BandedMatrix {
+    columns: usize,
+    offsets: Vec<usize>,
+    rows: Vec<Vec<f64>>,
+}
+
+impl BandedMatrix {
+    fn with_bands(columns: usize, bands: &[(usize, usize)]) -> Self {
+        Self {
+            columns,
+            offsets: bands.iter().map(|(lo, _)| *lo).collect(),
+            rows: bands.iter().map(|(lo, hi)| vec![f64::INFINITY; hi - lo + 1]).collect(),
+        }
+    }
+
+    /// Value at (i, j); infinite outside the band
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        match (self.rows.get(i), self.offsets.get(i)) {
+            (Some(row), Some(&offset)) if j >= offset => row.get(j - offset).copied().unwrap_or(f64::INFINITY),
+            _ => f64::INFINITY,
+        }
+    }
+
+    fn set(&mut self, i: usize, j: usize, value: f64) {
+        let offset = self.offsets[i];
+        self.rows[i][j - offset] = value;
+    }
+
+    fn band(&self, i: usize) -> (usize, usize) {
+        (self.offsets[i], self.offsets[i] + self.rows[i].len() - 1)
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.columns
+    }
+
+    /// Number of cells actually held in memory
+    pub fn stored_cells(&self) -> usize {
+        self.rows.iter().map(Vec::len).sum()
+    }
+}
+
+/// Accumulated costs of one banded DTW run
+struct BandedAccumulation {
+    local: BandedMatrix,
+    accumulated: BandedMatrix,
+    end: (usize, usize),
+    distance: f64,
+}
+
+/// Result of a nearest-neighbour search over candidate series
+#[derive(Debug, Clone)]
+pub struct NeighbourSearch {
+    /// (candidate index, DTW distance), closest first
+    pub neighbours: Vec<(usize, f64)>,
+    /// Candidates skipped because their LB_Keogh bound exceeded the k-th best distance
+    pub lower_bound_pruned: usize,
+    /// Candidates whose DTW was abandoned once no path could stay within the k-th best distance
+    pub early_abandoned: usize,
+    /// Candidates with no warping path under the constraints
+    pub unreachable: usize,
+}
+
 impl DynamicTimeWarping {
+    pub fn new(distance_metric: DTWDistanceMetric, constraints: DTWConstraints) -> Self {
+        Self {
+            cost_matrix: BandedMatrix::default(),
+            warping_path: Vec::new(),
+            alignment_quality: 0.0,
+            distance_metric,
+            constraints,
+        }
+    }
+
     /// Perform DTW alignment between two time series
     pub fn align(&mut self, 
                  reference: &[TimestampedMeasurement],
@@ -322,50 +505,23 @@ impl DynamicTimeWarping {
         let m = target.len();
         
         if n == 0 || m == 0 {
-            return Err(AppError::processing("Cannot align empty time series"));
-        }
-        
-        // Initialize cost matrix
-        self.cost_matrix = DMatrix::<f64>::from_element(n, m, f64::INFINITY);
-        
-        // Fill cost matrix with timestamp and value differences
-        for i in 0..n {
-            for j in 0..m {
-                if self.satisfies_constraints(i, j, n, m) {
-                    let time_diff = (reference[i].timestamp - target[j].timestamp).abs();
-                    let value_similarity = self.compute_value_similarity(
-                        &reference[i], 
-                        &target[j]
-                    )?;
-                    
-                    // Combined cost: temporal + value dissimilarity
-                    self.cost_matrix[(i, j)] = time_diff + (1.0 - value_similarity);
-                }
-            }
+            return Err(AppError::validation("Cannot align empty time series"));
         }
         
-        // Dynamic programming to find optimal warping path
-        let mut dp = DMatrix::<f64>::from_element(n, m, f64::INFINITY);
-        dp[(0, 0)] = self.cost_matrix[(0, 0)];
-        
-        // Fill DP table with step pattern constraints
-        for i in 0..n {
-            for j in 0..m {
-                if i == 0 && j == 0 { continue; }
-                
-                let step_costs = self.get_step_costs(i, j, &dp);
-                if !step_costs.is_empty() {
-                    dp[(i, j)] = self.cost_matrix[(i, j)] + 
-                        step_costs.into_iter().fold(f64::INFINITY, f64::min);
-                }
-            }
-        }
+        // Combined cost: temporal + value dissimilarity
+        let accumulation = self
+            .accumulate(n, m, |i, j| {
+                let time_diff = (reference[i].timestamp - target[j].timestamp).abs();
+                time_diff + (1.0 - self.compute_value_similarity(&reference[i], &target[j]))
+            }, None)?
+            .ok_or_else(|| AppError::internal("DTW abandoned without a threshold"))?;
         
         // Backtrack to find optimal path
-        self.warping_path = self.backtrack_optimal_path(&dp, n-1, m-1)?;
+        self.warping_path = self.backtrack_optimal_path(&accumulation)?;
+        self.cost_matrix = accumulation.local;
         
         // Compute alignment quality
-        self.alignment_quality = self.compute_alignment_quality(&dp, n-1, m-1);
+        self.alignment_quality = self.compute_alignment_quality(accumulation.distance);
         
         // Apply warping to create aligned measurements
         let aligned_measurements = self.apply_warping(target, &self.warping_path, reference)?;
@@ -373,145 +529,298 @@ impl DynamicTimeWarping {
         Ok(AlignmentResult {
             aligned_measurements,
             warping_path: self.warping_path.clone(),
-            alignment_cost: dp[(n-1, m-1)],
+            alignment_cost: accumulation.distance,
             quality_score: self.alignment_quality,
             temporal_compression_ratio: self.compute_compression_ratio(),
         })
     }
-    
-    /// Check if (i,j) satisfies DTW constraints
-    fn satisfies_constraints(&self, i: usize, j: usize, n: usize, m: usize) -> bool {
-        // Sakoe-Chiba band constraint
-        if let Some(radius) = self.constraints.sakoe_chiba_radius {
-            let diagonal_pos = (i as f64 / n as f64 * m as f64) as usize;
-            if (j as i32 - diagonal_pos as i32).abs() > radius as i32 {
-                return false;
+
+    /// Multivariate DTW distance between two feature-vector sequences
+    pub fn distance(&self, reference: &[Vec<f64>], target: &[Vec<f64>]) -> Result<f64, AppError> {
+        Self::check_dimensions(reference, target)?;
+        self.accumulate(reference.len(), target.len(), |i, j| self.distance_metric.distance(&reference[i], &target[j]), None)?
+            .map(|accumulation| accumulation.distance)
+            .ok_or_else(|| AppError::internal("DTW abandoned without a threshold"))
+    }
+
+    /// Multivariate DTW distance and warping path between two feature-vector sequences
+    pub fn align_features(&mut self, reference: &[Vec<f64>], target: &[Vec<f64>]) -> Result<(f64, Vec<(usize, usize)>), AppError> {
+        Self::check_dimensions(reference, target)?;
+        let accumulation = self
+            .accumulate(reference.len(), target.len(), |i, j| self.distance_metric.distance(&reference[i], &target[j]), None)?
+            .ok_or_else(|| AppError::internal("DTW abandoned without a threshold"))?;
+
+        self.warping_path = self.backtrack_optimal_path(&accumulation)?;
+        self.cost_matrix = accumulation.local;
+        Ok((accumulation.distance, self.warping_path.clone()))
+    }
+
+    /// Feature vectors of a measurement series (`MeasurementValue::Vector`, wind, spectra, scalars)
+    pub fn feature_sequence(measurements: &[TimestampedMeasurement]) -> Result<Vec<Vec<f64>>, AppError> {
+        measurements
+            .iter()
+            .map(|m| m.value.feature_vector().ok_or_else(|| AppError::validation("Measurement has no numeric features for DTW")))
+            .collect()
+    }
+
+    /// LB_Keogh lower bound on the DTW distance between `query` and `candidate`
+    ///
+    /// The envelope of the candidate is taken over the same band the DTW
+    /// would search for each query index. Returns 0 (a trivial bound) for
+    /// metrics or step patterns where LB_Keogh is not a valid lower bound.
+    pub fn lb_keogh(&self, query: &[Vec<f64>], candidate: &[Vec<f64>]) -> Result<f64, AppError> {
+        Self::check_dimensions(query, candidate)?;
+        if !self.constraints.step_pattern.visits_every_reference_index() {
+            return Ok(0.0);
+        }
+
+        let (n, m) = (query.len(), candidate.len());
+        let dimension = query[0].len();
+        let mut bound = 0.0;
+        for (i, point) in query.iter().enumerate() {
+            let Some((lo, hi)) = self.row_band(i, n, m) else {
+                return Ok(f64::INFINITY);
+            };
+            let mut lower = vec![f64::INFINITY; dimension];
+            let mut upper = vec![f64::NEG_INFINITY; dimension];
+            for candidate_point in &candidate[lo..=hi] {
+                for d in 0..dimension {
+                    lower[d] = lower[d].min(candidate_point[d]);
+                    upper[d] = upper[d].max(candidate_point[d]);
+                }
+            }
+            match self.distance_metric.distance_to_envelope(point, &lower, &upper) {
+                Some(distance) => bound += distance,
+                None => return Ok(0.0),
             }
         }
-        
-        // Itakura parallelogram constraint
-        if self.constraints.itakura_parallelogram {
-            let slope_lower = 0.5;
-            let slope_upper = 2.0;
-            let slope = if i > 0 { j as f64 / i as f64 } else { f64::INFINITY };
-            if slope < slope_lower || slope > slope_upper {
-                return false;
+
+        Ok(bound)
+    }
+
+    /// k nearest candidates to `query` by DTW distance, e.g. the historical
+    /// seasons most similar to the current one
+    ///
+    /// Candidates are visited in order of their LB_Keogh bound; once a bound
+    /// reaches the k-th best distance found so far the rest are pruned, and
+    /// remaining DTW runs are abandoned as soon as a whole row exceeds it.
+    pub fn nearest_neighbours(&self, query: &[Vec<f64>], candidates: &[Vec<Vec<f64>>], k: usize) -> Result<NeighbourSearch, AppError> {
+        let mut search = NeighbourSearch {
+            neighbours: Vec::with_capacity(k),
+            lower_bound_pruned: 0,
+            early_abandoned: 0,
+            unreachable: 0,
+        };
+        if k == 0 {
+            return Ok(search);
+        }
+
+        let mut order = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| self.lb_keogh(query, candidate).map(|bound| (index, bound)))
+            .collect::<Result<Vec<_>, _>>()?;
+        order.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        for (position, &(index, bound)) in order.iter().enumerate() {
+            let threshold = (search.neighbours.len() == k).then(|| search.neighbours[k - 1].1);
+            if let Some(threshold) = threshold {
+                if bound >= threshold {
+                    search.lower_bound_pruned += order.len() - position;
+                    break;
+                }
+            }
+
+            let candidate = &candidates[index];
+            let outcome = self.accumulate(query.len(), candidate.len(), |i, j| self.distance_metric.distance(&query[i], &candidate[j]), threshold);
+            match outcome {
+                Ok(Some(accumulation)) => {
+                    if threshold.is_none_or(|t| accumulation.distance < t) {
+                        search.neighbours.push((index, accumulation.distance));
+                        search.neighbours.sort_by(|a, b| a.1.total_cmp(&b.1));
+                        search.neighbours.truncate(k);
+                    }
+                },
+                Ok(None) => search.early_abandoned += 1,
+                Err(_) => search.unreachable += 1,
             }
         }
-        
-        true
+
+        Ok(search)
     }
-    
-    /// Get valid step costs for DP computation
-    fn get_step_costs(&self, i: usize, j: usize, dp: &DMatrix<f64>) -> Vec<f64> {
-        let mut costs = Vec::new();
-        
-        match &self.constraints.step_pattern {
-            StepPattern::Symmetric1 => {
-                if i > 0 && j > 0 { costs.push(dp[(i-1, j-1)]); }
-                if i > 0 { costs.push(dp[(i-1, j)]); }
-                if j > 0 { costs.push(dp[(i, j-1)]); }
-            },
-            StepPattern::Symmetric2 => {
-                if i > 0 && j > 0 { costs.push(dp[(i-1, j-1)]); }
-                if i > 1 { costs.push(dp[(i-2, j)] * 2.0); }
-                if j > 1 { costs.push(dp[(i, j-2)] * 2.0); }
-            },
-            StepPattern::Asymmetric => {
-                if i > 0 && j > 0 { costs.push(dp[(i-1, j-1)]); }
-                if i > 0 { costs.push(dp[(i-1, j)]); }
-            },
-            StepPattern::Custom(steps) => {
-                for &(di, dj, weight) in steps {
-                    let pi = i as i32 - di;
-                    let pj = j as i32 - dj;
-                    if pi >= 0 && pj >= 0 {
-                        costs.push(dp[(pi as usize, pj as usize)] * weight);
+
+    fn check_dimensions(reference: &[Vec<f64>], target: &[Vec<f64>]) -> Result<(), AppError> {
+        let dimension = reference.first().or(target.first()).map(Vec::len)
+            .ok_or_else(|| AppError::validation("Cannot align empty time series"))?;
+        if reference.is_empty() || target.is_empty() {
+            return Err(AppError::validation("Cannot align empty time series"));
+        }
+        if reference.iter().chain(target).any(|v| v.len() != dimension) || dimension == 0 {
+            return Err(AppError::validation("DTW feature vectors must all have the same dimension"));
+        }
+        Ok(())
+    }
+
+    /// Admissible target range for reference row i, or `None` if the constraints leave it empty
+    ///
+    /// The Sakoe–Chiba band follows the corner-to-corner diagonal; the
+    /// Itakura parallelogram bounds are rounded outward to whole cells.
+    pub fn row_band(&self, i: usize, n: usize, m: usize) -> Option<(usize, usize)> {
+        let last = m - 1;
+        let span = n.saturating_sub(1);
+        let (mut lo, mut hi) = (0, last);
+
+        // Integer arithmetic keeps cells on an exact band edge from being lost to rounding
+        if let Some(radius) = self.constraints.sakoe_chiba_radius {
+            let diagonal = if span > 0 { (2 * i * last + span) / (2 * span) } else { 0 };
+            lo = lo.max(diagonal.saturating_sub(radius));
+            hi = hi.min(diagonal + radius);
+        }
+
+        if self.constraints.itakura_parallelogram && n > 1 && m > 1 {
+            // Lower edge max(x/2, 2x - 1) and upper edge min(2x, (x + 1)/2), x = i/(n - 1)
+            let shallow_lower = i * last / (2 * span);
+            let steep_lower = (2 * i * last).saturating_sub(span * last) / span;
+            let steep_upper = (2 * i * last).div_ceil(span);
+            let shallow_upper = ((i + span) * last).div_ceil(2 * span);
+            lo = lo.max(shallow_lower.max(steep_lower));
+            hi = hi.min(steep_upper.min(shallow_upper));
+        }
+
+        (lo <= hi).then_some((lo, hi))
+    }
+
+    /// Banded dynamic programme over the admissible cells
+    ///
+    /// Returns `Ok(None)` once every cell exceeds `abandon_above` in as many
+    /// consecutive rows as the longest step spans: no path can skip all of them,
+    /// and accumulated costs never decrease along a path.
+    fn accumulate<C>(&self, n: usize, m: usize, local_cost: C, abandon_above: Option<f64>) -> Result<Option<BandedAccumulation>, AppError>
+    where
+        C: Fn(usize, usize) -> f64,
+    {
+        let steps = self.constraints.step_pattern.steps()?;
+        let longest_step = steps.iter().map(|&(di, _, _)| di).max().unwrap_or(1).max(1);
+        let open = !self.constraints.boundary_constraints;
+        let bands = (0..n)
+            .map(|i| self.row_band(i, n, m))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| AppError::validation("DTW constraints leave a reference row with no admissible cells"))?;
+
+        let mut local = BandedMatrix::with_bands(m, &bands);
+        let mut accumulated = BandedMatrix::with_bands(m, &bands);
+
+        let mut rows_above_limit = 0;
+        for (i, &(lo, hi)) in bands.iter().enumerate() {
+            let mut row_minimum = f64::INFINITY;
+            for j in lo..=hi {
+                let cost = local_cost(i, j);
+                local.set(i, j, cost);
+
+                let mut best = if i == 0 && (j == 0 || open) { cost } else { f64::INFINITY };
+                for &(di, dj, weight) in &steps {
+                    if di <= i && dj <= j {
+                        let previous = accumulated.get(i - di, j - dj);
+                        if previous.is_finite() {
+                            best = best.min(previous + weight * cost);
+                        }
                     }
                 }
+                accumulated.set(i, j, best);
+                row_minimum = row_minimum.min(best);
+            }
+
+            if abandon_above.is_some_and(|limit| row_minimum > limit) {
+                rows_above_limit += 1;
+                if rows_above_limit >= longest_step {
+                    return Ok(None);
+                }
+            } else {
+                rows_above_limit = 0;
             }
         }
-        
-        costs
+
+        let (lo, hi) = bands[n - 1];
+        let end = if open {
+            (lo..=hi).map(|j| (n - 1, j)).min_by(|a, b| accumulated.get(a.0, a.1).total_cmp(&accumulated.get(b.0, b.1)))
+        } else {
+            (hi == m - 1).then_some((n - 1, m - 1))
+        };
+        let distance = end.map_or(f64::INFINITY, |(i, j)| accumulated.get(i, j));
+        let end = match end {
+            Some(end) if distance.is_finite() => end,
+            _ => return Err(AppError::validation("No warping path satisfies the DTW constraints")),
+        };
+
+        Ok(Some(BandedAccumulation { local, accumulated, end, distance }))
     }
     
     /// Compute similarity between measurement values
     fn compute_value_similarity(&self, 
                                m1: &TimestampedMeasurement, 
-                               m2: &TimestampedMeasurement) -> Result<f64, AppError> {
+                               m2: &TimestampedMeasurement) -> f64 {
         use crate::data_fusion::MeasurementValue;
         
         match (&m1.value, &m2.value) {
             (MeasurementValue::Scalar(v1), MeasurementValue::Scalar(v2)) => {
                 let diff = (v1 - v2).abs();
                 let max_val = v1.abs().max(v2.abs()).max(1.0);
-                Ok(1.0 - (diff / max_val).min(1.0))
+                1.0 - (diff / max_val).min(1.0)
             },
             (MeasurementValue::Temperature { value: v1, .. }, 
              MeasurementValue::Temperature { value: v2, .. }) => {
                 let diff = (v1 - v2).abs();
-                Ok(1.0 - (diff / 50.0).min(1.0)) // 50°C range normalization
+                1.0 - (diff / 50.0).min(1.0) // 50°C range normalization
             },
             (MeasurementValue::WindVector { speed: s1, direction: d1, .. },
              MeasurementValue::WindVector { speed: s2, direction: d2, .. }) => {
                 let speed_sim = 1.0 - ((s1 - s2).abs() / s1.max(*s2).max(1.0)).min(1.0);
                 let dir_diff = (d1 - d2).abs().min(360.0 - (d1 - d2).abs());
                 let dir_sim = 1.0 - (dir_diff / 180.0);
-                Ok((speed_sim + dir_sim) / 2.0)
+                (speed_sim + dir_sim) / 2.0
             },
-            _ => Ok(0.5), // Default similarity for different types
+            (MeasurementValue::Vector(v1), MeasurementValue::Vector(v2)) if v1.len() == v2.len() => {
+                let distance = self.distance_metric.distance(v1, v2);
+                match self.distance_metric {
+                    DTWDistanceMetric::Cosine => 1.0 - (distance / 2.0).min(1.0),
+                    _ => {
+                        let norm = |v: &[f64]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+                        1.0 - (distance / norm(v1).max(norm(v2)).max(1.0)).min(1.0)
+                    },
+                }
+            },
+            _ => 0.5, // Default similarity for different types
         }
     }
     
     /// Backtrack to find optimal warping path
-    fn backtrack_optimal_path(&self, dp: &DMatrix<f64>, mut i: usize, mut j: usize) -> Result<Vec<(usize, usize)>, AppError> {
-        let mut path = Vec::new();
-        
-        while i > 0 || j > 0 {
-            path.push((i, j));
-            
-            // Find the best previous step
-            let mut best_cost = f64::INFINITY;
-            let mut best_step = (i, j);
-            
-            let candidates = match &self.constraints.step_pattern {
-                StepPattern::Symmetric1 => {
-                    vec![(i.saturating_sub(1), j.saturating_sub(1)),
-                         (i.saturating_sub(1), j),
-                         (i, j.saturating_sub(1))]
-                },
-                StepPattern::Symmetric2 => {
-                    vec![(i.saturating_sub(1), j.saturating_sub(1)),
-                         (i.saturating_sub(2), j),
-                         (i, j.saturating_sub(2))]
-                },
-                StepPattern::Asymmetric => {
-                    vec![(i.saturating_sub(1), j.saturating_sub(1)),
-                         (i.saturating_sub(1), j)]
+    fn backtrack_optimal_path(&self, accumulation: &BandedAccumulation) -> Result<Vec<(usize, usize)>, AppError> {
+        let steps = self.constraints.step_pattern.steps()?;
+        let open = !self.constraints.boundary_constraints;
+        let (mut i, mut j) = accumulation.end;
+        let mut path = vec![(i, j)];
+
+        loop {
+            let cost = accumulation.local.get(i, j);
+            let start = if i == 0 && (j == 0 || open) { cost } else { f64::INFINITY };
+
+            let predecessor = steps
+                .iter()
+                .filter(|&&(di, dj, _)| di <= i && dj <= j)
+                .map(|&(di, dj, weight)| ((i - di, j - dj), accumulation.accumulated.get(i - di, j - dj) + weight * cost))
+                .filter(|(_, total)| total.is_finite())
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+
+            match predecessor {
+                Some((previous, total)) if total < start => {
+                    (i, j) = previous;
+                    path.push(previous);
                 },
-                StepPattern::Custom(steps) => {
-                    steps.iter().map(|&(di, dj, _)| {
-                        (i.saturating_sub(di as usize), j.saturating_sub(dj as usize))
-                    }).collect()
-                }
-            };
-            
-            for (pi, pj) in candidates {
-                if pi < dp.nrows() && pj < dp.ncols() && dp[(pi, pj)] < best_cost {
-                    best_cost = dp[(pi, pj)];
-                    best_step = (pi, pj);
-                }
+                _ if start.is_finite() => break,
+                _ => return Err(AppError::internal("DTW backtracking left the admissible band")),
             }
-            
-            if best_step == (i, j) {
-                break; // Prevent infinite loop
-            }
-            
-            i = best_step.0;
-            j = best_step.1;
         }
-        
-        path.push((0, 0));
+
         path.reverse();
         Ok(path)
     }
@@ -612,8 +921,7 @@ impl DynamicTimeWarping {
     }
     
     /// Compute overall alignment quality score
-    fn compute_alignment_quality(&self, dp: &DMatrix<f64>, end_i: usize, end_j: usize) -> f64 {
-        let total_cost = dp[(end_i, end_j)];
+    fn compute_alignment_quality(&self, total_cost: f64) -> f64 {
         let path_length = self.warping_path.len() as f64;
         let normalized_cost = total_cost / path_length.max(1.0);
         
@@ -622,6 +930,474 @@ impl DynamicTimeWarping {
     }
 }
 
+// Clock drift estimation and resampling to a common grid
+
+/// Offset and rate of a sensor clock relative to the reference timeline
+#[derive(Debug, Clone)]
+pub struct ClockDriftEstimate {
+    /// Sensor clock minus reference time at `reference_epoch` (seconds)
+    pub offset_seconds: f64,
+    /// Rate at which the offset grows (seconds per day)
+    pub drift_seconds_per_day: f64,
+    /// Sensor timestamp the offset refers to
+    pub reference_epoch: f64,
+    /// Robust RMS of the per-window lags about the fitted line (seconds)
+    pub residual_rms_seconds: f64,
+    pub windows_used: usize,
+    pub windows_rejected: usize,
+}
+
+impl ClockDriftEstimate {
+    /// Clock error at a sensor timestamp
+    pub fn clock_error_at(&self, sensor_timestamp: f64) -> f64 {
+        self.offset_seconds + self.drift_seconds_per_day * (sensor_timestamp - self.reference_epoch) / 86400.0
+    }
+
+    /// Reference-timeline time of a sensor timestamp
+    pub fn correct_timestamp(&self, sensor_timestamp: f64) -> f64 {
+        sensor_timestamp - self.clock_error_at(sensor_timestamp)
+    }
+}
+
+/// Windowed lag search against a reference series followed by a robust line fit
+///
+/// In each window the sensor samples are correlated against the reference,
+/// interpolated at the sensor times shifted by candidate lags; the peak is
+/// refined with a parabola through its neighbours. Windows with weak or
+/// boundary peaks are rejected. The remaining lags are fitted as
+/// offset + drift·t with least squares weighted by peak sharpness (windows
+/// with passing cloud or fronts pin the lag far better than smooth ones) and
+/// Huber weights, so that a few bad windows (sensor faults, local weather the
+/// reference did not see) do not bend the drift estimate.
+#[derive(Debug, Clone)]
+pub struct ClockDriftEstimator {
+    pub window_seconds: f64,
+    pub window_step_seconds: f64,
+    pub max_lag_seconds: f64,
+    pub lag_resolution_seconds: f64,
+    /// Reference samples further apart than this are not interpolated across
+    pub max_reference_gap_seconds: f64,
+    pub min_samples_per_window: usize,
+    pub min_correlation: f64,
+}
+
+impl Default for ClockDriftEstimator {
+    fn default() -> Self {
+        Self {
+            window_seconds: 6.0 * 3600.0,
+            window_step_seconds: 3.0 * 3600.0,
+            max_lag_seconds: 900.0,
+            lag_resolution_seconds: 5.0,
+            max_reference_gap_seconds: 3600.0,
+            min_samples_per_window: 8,
+            min_correlation: 0.6,
+        }
+    }
+}
+
+impl ClockDriftEstimator {
+    /// Estimate the clock error of `sensor` from (timestamp, value) pairs
+    /// overlapping a `reference` series of the same quantity
+    pub fn estimate(&self, sensor: &[(f64, f64)], reference: &[(f64, f64)]) -> Result<ClockDriftEstimate, AppError> {
+        let sensor = sorted_samples(sensor);
+        let reference = sorted_samples(reference);
+        if sensor.len() < self.min_samples_per_window || reference.len() < 2 {
+            return Err(AppError::validation("Not enough overlapping observations to estimate clock drift"));
+        }
+
+        let mut lags = Vec::new();
+        let mut rejected = 0;
+        let mut window_start = sensor[0].0;
+        let end = sensor[sensor.len() - 1].0;
+
+        while window_start <= end {
+            let window: Vec<(f64, f64)> = sensor
+                .iter()
+                .filter(|(t, _)| *t >= window_start && *t < window_start + self.window_seconds)
+                .copied()
+                .collect();
+            window_start += self.window_step_seconds;
+
+            if window.len() < self.min_samples_per_window {
+                continue;
+            }
+            match self.window_lag(&window, &reference) {
+                Some((lag, sharpness)) => {
+                    let centre = window.iter().map(|(t, _)| t).sum::<f64>() / window.len() as f64;
+                    lags.push((centre, lag, sharpness));
+                },
+                None => rejected += 1,
+            }
+        }
+
+        if lags.is_empty() {
+            return Err(AppError::validation("No window correlated with the reference series"));
+        }
+
+        let mean_sharpness = lags.iter().map(|(_, _, sharpness)| sharpness).sum::<f64>() / lags.len() as f64;
+        let weighted: Vec<(f64, f64, f64)> = lags.iter().map(|&(t, lag, sharpness)| (t, lag, sharpness / mean_sharpness)).collect();
+        let reference_epoch = lags[0].0;
+        let (offset, drift, residual_rms) = huber_line_fit(&weighted, reference_epoch, self.lag_resolution_seconds);
+
+        Ok(ClockDriftEstimate {
+            offset_seconds: offset,
+            drift_seconds_per_day: drift * 86400.0,
+            reference_epoch,
+            residual_rms_seconds: residual_rms,
+            windows_used: lags.len(),
+            windows_rejected: rejected,
+        })
+    }
+
+    /// Lag maximising the correlation of one window with the reference, and
+    /// the curvature of the correlation peak as a measure of its precision
+    fn window_lag(&self, window: &[(f64, f64)], reference: &[(f64, f64)]) -> Option<(f64, f64)> {
+        let steps = (self.max_lag_seconds / self.lag_resolution_seconds).ceil() as i64;
+        let correlations: Vec<f64> = (-steps..=steps)
+            .map(|k| {
+                let lag = k as f64 * self.lag_resolution_seconds;
+                let pairs: Vec<(f64, f64)> = window
+                    .iter()
+                    .filter_map(|(t, v)| {
+                        interpolate_linear(reference, t - lag, self.max_reference_gap_seconds).map(|r| (*v, r))
+                    })
+                    .collect();
+                if pairs.len() < self.min_samples_per_window {
+                    f64::NAN
+                } else {
+                    pearson_correlation(&pairs)
+                }
+            })
+            .collect();
+
+        let (best, &peak) = correlations
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.is_finite())
+            .max_by(|a, b| a.1.total_cmp(b.1))?;
+        if peak < self.min_correlation || best == 0 || best == correlations.len() - 1 {
+            return None;
+        }
+
+        // Parabolic refinement of the peak between its neighbours
+        let (left, right) = (correlations[best - 1], correlations[best + 1]);
+        let curvature = left - 2.0 * peak + right;
+        if !left.is_finite() || !right.is_finite() || curvature >= 0.0 {
+            return None;
+        }
+        let shift = (0.5 * (left - right) / curvature).clamp(-0.5, 0.5);
+
+        Some(((best as f64 - steps as f64 + shift) * self.lag_resolution_seconds, -curvature))
+    }
+}
+
+/// Huber-weighted fit of y = a + b·(t - epoch) to (t, y, precision weight)
+/// points; returns (a, b per second, robust RMS)
+fn huber_line_fit(points: &[(f64, f64, f64)], epoch: f64, scale_floor: f64) -> (f64, f64, f64) {
+    if points.len() == 1 {
+        return (points[0].1, 0.0, 0.0);
+    }
+
+    let mut weights: Vec<f64> = points.iter().map(|(_, _, precision)| *precision).collect();
+    let (mut a, mut b) = (0.0, 0.0);
+
+    for _ in 0..20 {
+        let (mut sw, mut sx, mut sy, mut sxx, mut sxy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for ((t, y, _), w) in points.iter().zip(&weights) {
+            let x = t - epoch;
+            sw += w;
+            sx += w * x;
+            sy += w * y;
+            sxx += w * x * x;
+            sxy += w * x * y;
+        }
+        let determinant = sw * sxx - sx * sx;
+        let (next_a, next_b) = if determinant.abs() > 1e-12 * sw * sxx.max(1.0) {
+            ((sxx * sy - sx * sxy) / determinant, (sw * sxy - sx * sy) / determinant)
+        } else {
+            (sy / sw, 0.0)
+        };
+        let converged = (next_a - a).abs() < 1e-9 && (next_b - b).abs() < 1e-15;
+        a = next_a;
+        b = next_b;
+
+        // Residuals standardised by each window's precision
+        let residuals: Vec<f64> = points.iter().map(|(t, y, precision)| (y - a - b * (t - epoch)) * precision.sqrt()).collect();
+        let mut absolute: Vec<f64> = residuals.iter().map(|r| r.abs()).collect();
+        absolute.sort_by(|x, y| x.total_cmp(y));
+        let sigma = (1.4826 * absolute[absolute.len() / 2]).max(0.1 * scale_floor);
+        let delta = 1.345 * sigma;
+        for ((w, r), (_, _, precision)) in weights.iter_mut().zip(&residuals).zip(points) {
+            *w = precision * if r.abs() <= delta { 1.0 } else { delta / r.abs() };
+        }
+        if converged {
+            break;
+        }
+    }
+
+    let (weighted_sq, total_weight) = points
+        .iter()
+        .zip(&weights)
+        .fold((0.0, 0.0), |(sq, tw), ((t, y, _), w)| (sq + w * (y - a - b * (t - epoch)).powi(2), tw + w));
+
+    (a, b, (weighted_sq / total_weight).sqrt())
+}
+
+fn pearson_correlation(pairs: &[(f64, f64)]) -> f64 {
+    let n = pairs.len() as f64;
+    let (mean_x, mean_y) = pairs.iter().fold((0.0, 0.0), |(x, y), (a, b)| (x + a / n, y + b / n));
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        sxy += (x - mean_x) * (y - mean_y);
+        sxx += (x - mean_x).powi(2);
+        syy += (y - mean_y).powi(2);
+    }
+    if sxx <= 0.0 || syy <= 0.0 {
+        return f64::NAN;
+    }
+    sxy / (sxx * syy).sqrt()
+}
+
+/// Time-ordered samples with duplicate timestamps averaged
+fn sorted_samples(samples: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut sorted: Vec<(f64, f64)> = samples.iter().copied().filter(|(t, v)| t.is_finite() && v.is_finite()).collect();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut merged: Vec<(f64, f64, usize)> = Vec::with_capacity(sorted.len());
+    for (t, v) in sorted {
+        match merged.last_mut() {
+            Some((last_t, sum, count)) if *last_t == t => {
+                *sum += v;
+                *count += 1;
+            },
+            _ => merged.push((t, v, 1)),
+        }
+    }
+    merged.into_iter().map(|(t, sum, count)| (t, sum / count as f64)).collect()
+}
+
+/// Index of the sample interval containing t, if t lies inside the series
+fn bracketing_interval(samples: &[(f64, f64)], t: f64) -> Option<usize> {
+    if samples.len() < 2 || t < samples[0].0 || t > samples[samples.len() - 1].0 {
+        return None;
+    }
+    let upper = samples.partition_point(|(time, _)| *time <= t);
+    Some(upper.clamp(1, samples.len() - 1) - 1)
+}
+
+/// Linear interpolation that refuses to bridge gaps longer than `max_gap`
+fn interpolate_linear(samples: &[(f64, f64)], t: f64, max_gap: f64) -> Option<f64> {
+    if samples.len() == 1 {
+        return (samples[0].0 == t).then_some(samples[0].1);
+    }
+    let i = bracketing_interval(samples, t)?;
+    let ((t0, v0), (t1, v1)) = (samples[i], samples[i + 1]);
+    if t == t0 {
+        return Some(v0);
+    }
+    if t == t1 {
+        return Some(v1);
+    }
+    if t1 - t0 > max_gap {
+        return None;
+    }
+    Some(v0 + (v1 - v0) * (t - t0) / (t1 - t0))
+}
+
+/// Natural cubic spline through irregularly spaced samples
+#[derive(Debug, Clone)]
+pub struct CubicSpline {
+    times: Vec<f64>,
+    values: Vec<f64>,
+    second_derivatives: Vec<f64>,
+}
+
+impl CubicSpline {
+    /// Fit through strictly increasing times
+    pub fn fit(times: &[f64], values: &[f64]) -> Result<Self, AppError> {
+        let n = times.len();
+        if n < 2 || values.len() != n {
+            return Err(AppError::validation("Cubic spline needs at least two samples"));
+        }
+        if times.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(AppError::validation("Cubic spline times must be strictly increasing"));
+        }
+
+        // Tridiagonal system for the interior second derivatives (Thomas algorithm)
+        let mut second_derivatives = vec![0.0; n];
+        if n > 2 {
+            let h: Vec<f64> = times.windows(2).map(|w| w[1] - w[0]).collect();
+            let mut diagonal = vec![0.0; n];
+            let mut rhs = vec![0.0; n];
+            for i in 1..n - 1 {
+                diagonal[i] = 2.0 * (h[i - 1] + h[i]);
+                rhs[i] = 6.0 * ((values[i + 1] - values[i]) / h[i] - (values[i] - values[i - 1]) / h[i - 1]);
+            }
+            for i in 2..n - 1 {
+                let factor = h[i - 1] / diagonal[i - 1];
+                diagonal[i] -= factor * h[i - 1];
+                rhs[i] -= factor * rhs[i - 1];
+            }
+            for i in (1..n - 1).rev() {
+                let upper = if i + 1 < n - 1 { h[i] * second_derivatives[i + 1] } else { 0.0 };
+                second_derivatives[i] = (rhs[i] - upper) / diagonal[i];
+            }
+        }
+
+        Ok(Self { times: times.to_vec(), values: values.to_vec(), second_derivatives })
+    }
+
+    /// Spline value at t; `None` outside the fitted range
+    pub fn evaluate(&self, t: f64) -> Option<f64> {
+        let n = self.times.len();
+        if t < self.times[0] || t > self.times[n - 1] {
+            return None;
+        }
+        let i = (self.times.partition_point(|time| *time <= t).clamp(1, n - 1)) - 1;
+        let h = self.times[i + 1] - self.times[i];
+        let a = (self.times[i + 1] - t) / h;
+        let b = (t - self.times[i]) / h;
+
+        Some(
+            a * self.values[i] + b * self.values[i + 1]
+                + ((a.powi(3) - a) * self.second_derivatives[i] + (b.powi(3) - b) * self.second_derivatives[i + 1]) * h * h / 6.0,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResamplingMethod {
+    Linear,
+    CubicSpline,
+}
+
+/// Regular time grid shared by all resampled sensors
+#[derive(Debug, Clone)]
+pub struct TimeGrid {
+    pub start: f64,
+    pub step: f64,
+    pub len: usize,
+}
+
+impl TimeGrid {
+    /// Grid of `step` spacing covering [start, end], anchored on multiples of the step
+    pub fn covering(start: f64, end: f64, step: f64) -> Result<Self, AppError> {
+        if step.is_nan() || step <= 0.0 || !start.is_finite() || !end.is_finite() || end < start {
+            return Err(AppError::validation("Invalid time grid bounds"));
+        }
+        let first = (start / step).ceil() * step;
+        let len = ((end - first) / step).floor().max(-1.0) as i64 + 1;
+        Ok(Self { start: first, step, len: len.max(0) as usize })
+    }
+
+    pub fn time_at(&self, index: usize) -> f64 {
+        self.start + index as f64 * self.step
+    }
+
+    pub fn end(&self) -> f64 {
+        self.time_at(self.len.saturating_sub(1))
+    }
+
+    /// Resample (timestamp, value) pairs onto the grid
+    ///
+    /// Grid points outside the series, or inside a gap between consecutive
+    /// samples longer than `max_gap`, are masked. Cubic splines are fitted
+    /// separately on each gap-free segment so they never ring across a gap.
+    pub fn resample(&self, samples: &[(f64, f64)], method: ResamplingMethod, max_gap: f64) -> ResampledSeries {
+        let samples = sorted_samples(samples);
+        let mut values = vec![f64::NAN; self.len];
+        let mut mask = vec![false; self.len];
+
+        let mut segment_start = 0;
+        for segment_end in 1..=samples.len() {
+            let closes_segment = segment_end == samples.len() || samples[segment_end].0 - samples[segment_end - 1].0 > max_gap;
+            if !closes_segment {
+                continue;
+            }
+            let segment = &samples[segment_start..segment_end];
+            segment_start = segment_end;
+
+            let spline = match method {
+                ResamplingMethod::CubicSpline if segment.len() >= 3 => {
+                    let (times, segment_values): (Vec<f64>, Vec<f64>) = segment.iter().copied().unzip();
+                    CubicSpline::fit(&times, &segment_values).ok()
+                },
+                _ => None,
+            };
+
+            let first = ((segment[0].0 - self.start) / self.step).ceil().max(0.0) as usize;
+            for index in first..self.len {
+                let t = self.time_at(index);
+                if t > segment[segment.len() - 1].0 {
+                    break;
+                }
+                let value = match &spline {
+                    Some(spline) => spline.evaluate(t),
+                    None => interpolate_linear(segment, t, max_gap),
+                };
+                if let Some(value) = value {
+                    values[index] = value;
+                    mask[index] = true;
+                }
+            }
+        }
+
+        ResampledSeries { values, mask }
+    }
+}
+
+/// One sensor's series on a `TimeGrid`; masked points hold NaN
+#[derive(Debug, Clone)]
+pub struct ResampledSeries {
+    pub values: Vec<f64>,
+    /// True where the value is backed by data on both sides within the gap limit
+    pub mask: Vec<bool>,
+}
+
+impl ResampledSeries {
+    /// Fraction of grid points with valid values
+    pub fn coverage(&self) -> f64 {
+        if self.mask.is_empty() {
+            return 0.0;
+        }
+        self.mask.iter().filter(|valid| **valid).count() as f64 / self.mask.len() as f64
+    }
+}
+
+/// Scalar (timestamp, value) pairs of a measurement series
+fn scalar_samples(measurements: &[TimestampedMeasurement]) -> Vec<(f64, f64)> {
+    measurements
+        .iter()
+        .filter_map(|m| m.value.scalar_value().map(|v| (m.timestamp, v)))
+        .collect()
+}
+
+fn mean_or_zero(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 { 0.0 } else { sum / count as f64 }
+}
+
+/// Leave-one-out accuracy of linear interpolation, 1 / (1 + normalised RMSE)
+fn interpolation_accuracy(samples: &[(f64, f64)], max_gap: f64) -> Option<f64> {
+    let samples = sorted_samples(samples);
+    let errors: Vec<f64> = samples
+        .windows(3)
+        .filter(|w| w[2].0 - w[0].0 <= max_gap)
+        .map(|w| {
+            let predicted = w[0].1 + (w[2].1 - w[0].1) * (w[1].0 - w[0].0) / (w[2].0 - w[0].0);
+            (predicted - w[1].1).powi(2)
+        })
+        .collect();
+    if errors.is_empty() {
+        return None;
+    }
+
+    let mean = samples.iter().map(|(_, v)| v).sum::<f64>() / samples.len() as f64;
+    let spread = (samples.iter().map(|(_, v)| (v - mean).powi(2)).sum::<f64>() / samples.len() as f64).sqrt();
+    let rmse = (errors.iter().sum::<f64>() / errors.len() as f64).sqrt();
+    Some(1.0 / (1.0 + rmse / spread.max(1e-12)))
+}
+
 // Supporting types and traits
 
 #[derive(Debug, Clone)]
@@ -717,7 +1493,7 @@ pub struct AlignedSensorData {
     pub temporal_window: (DateTime<Utc>, DateTime<Utc>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TemporalQualityMetrics {
     pub synchronization_score: f64,
     pub interpolation_accuracy: f64,
@@ -725,40 +1501,155 @@ pub struct TemporalQualityMetrics {
     pub overall_temporal_quality: f64,
     pub alignment_consistency: f64,
     pub temporal_resolution: f64,
+    /// RMS timing residual left after clock-drift correction (seconds)
+    pub residual_timing_error: f64,
 }
 
 // Placeholder implementations for complex structures
+#[derive(Debug, Clone)]
 pub struct CircularBuffer<T> {
     data: Vec<T>,
     capacity: usize,
     index: usize,
 }
 
+/// Polynomial clock error model around a reference epoch
+///
+/// Coefficients are in ns, ns/day, ns/day², ... of the sensor clock relative
+/// to the reference timeline, evaluated at the sensor's own timestamps.
+#[derive(Debug, Clone)]
 pub struct DriftPredictor {
     coefficients: Vec<f64>,
     model_type: String,
+    reference_epoch: f64,
+    residual_rms_ns: f64,
 }
 
 impl DriftPredictor {
-    pub fn predict_drift(&self, _timestamp: f64) -> f64 {
-        // Implement drift prediction algorithm
-        0.0
+    /// Clock error in nanoseconds at the given sensor timestamp
+    pub fn predict_drift(&self, timestamp: f64) -> f64 {
+        let days = (timestamp - self.reference_epoch) / 86400.0;
+        self.coefficients.iter().rev().fold(0.0, |acc, coefficient| acc * days + coefficient)
+    }
+
+    /// Linear model from a fitted offset/drift estimate
+    pub fn from_estimate(estimate: &ClockDriftEstimate) -> Self {
+        Self {
+            coefficients: vec![estimate.offset_seconds * 1e9, estimate.drift_seconds_per_day * 1e9],
+            model_type: "linear".to_string(),
+            reference_epoch: estimate.reference_epoch,
+            residual_rms_ns: estimate.residual_rms_seconds * 1e9,
+        }
+    }
+
+    /// RMS residual of the fit the model came from (ns)
+    pub fn residual_uncertainty(&self) -> f64 {
+        self.residual_rms_ns
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct OscillationAnalyzer {
     window_size: usize,
     fft_size: usize,
 }
 
 impl OscillationAnalyzer {
-    pub fn analyze(&self, _timestamps: &[f64]) -> Result<DetectedPatterns, AppError> {
-        // Implement oscillation detection using FFT and pattern recognition
-        Ok(DetectedPatterns {
+    /// Detect the timing error structure of a sampled timestamp sequence
+    ///
+    /// Timestamps are compared against an ideal clock ticking at the nominal
+    /// sampling period (the median interval rounded to the millisecond), with
+    /// missed samples skipped. A linear trend in the residual is the clock
+    /// running fast or slow; periodic components are found with a direct
+    /// Fourier scan of the detrended residual over the most recent
+    /// `window_size` samples. The returned patterns evaluate to the timing
+    /// residual at absolute time t.
+    pub fn analyze(&self, timestamps: &[f64]) -> Result<DetectedPatterns, AppError> {
+        let mut patterns = DetectedPatterns {
             oscillations: vec![],
             trends: vec![],
             seasonal_components: vec![],
-        })
+        };
+
+        let start = timestamps.len().saturating_sub(self.window_size.max(4));
+        let window = &timestamps[start..];
+        if window.len() < 4 {
+            return Ok(patterns);
+        }
+
+        let mut intervals: Vec<f64> = window.windows(2).map(|w| w[1] - w[0]).collect();
+        intervals.sort_by(|a, b| a.total_cmp(b));
+        let median_interval = intervals[intervals.len() / 2];
+        if median_interval <= 0.0 {
+            return Err(AppError::validation("Timestamps must be increasing for oscillation analysis"));
+        }
+        let nominal_interval = if median_interval >= 1e-3 { (median_interval * 1e3).round() / 1e3 } else { median_interval };
+
+        let residuals: Vec<f64> = window
+            .iter()
+            .map(|t| {
+                let tick = ((t - window[0]) / nominal_interval).round();
+                t - (window[0] + tick * nominal_interval)
+            })
+            .collect();
+
+        // Linear trend of the residual against absolute time
+        let n = window.len() as f64;
+        let mean_t = window.iter().sum::<f64>() / n;
+        let mean_r = residuals.iter().sum::<f64>() / n;
+        let sxx: f64 = window.iter().map(|t| (t - mean_t).powi(2)).sum();
+        let sxy: f64 = window.iter().zip(&residuals).map(|(t, r)| (t - mean_t) * (r - mean_r)).sum();
+        let syy: f64 = residuals.iter().map(|r| (r - mean_r).powi(2)).sum();
+        let slope = if sxx > 0.0 { sxy / sxx } else { 0.0 };
+        let intercept = mean_r - slope * mean_t;
+        let r_squared = if syy > 0.0 { (sxy * sxy) / (sxx * syy) } else { 0.0 };
+        let trend_explained = r_squared > 0.5;
+        if trend_explained {
+            patterns.trends.push(TrendPattern { slope, intercept, r_squared });
+        }
+
+        let detrended: Vec<f64> = window
+            .iter()
+            .zip(&residuals)
+            .map(|(t, r)| if trend_explained { r - (slope * t + intercept) } else { r - mean_r })
+            .collect();
+
+        // Direct Fourier scan at the zero-padded resolution of fft_size bins
+        let bins = (self.fft_size.max(window.len()) / 2).max(2);
+        let resolution = 1.0 / (2.0 * bins as f64 * nominal_interval);
+        let spectrum: Vec<(f64, f64, f64)> = (1..bins)
+            .map(|k| {
+                let frequency = k as f64 * resolution;
+                let (mut c, mut s) = (0.0, 0.0);
+                for (t, r) in window.iter().zip(&detrended) {
+                    let angle = 2.0 * PI * frequency * t;
+                    c += r * angle.cos();
+                    s += r * angle.sin();
+                }
+                (frequency, 2.0 * s / n, 2.0 * c / n)
+            })
+            .collect();
+
+        let power: Vec<f64> = spectrum.iter().map(|(_, a, b)| a * a + b * b).collect();
+        let total_power: f64 = power.iter().sum();
+        let mean_power = total_power / power.len() as f64;
+        let mut peaks: Vec<usize> = (1..power.len().saturating_sub(1))
+            .filter(|&k| power[k] > power[k - 1] && power[k] >= power[k + 1] && power[k] > 4.0 * mean_power)
+            .collect();
+        peaks.sort_by(|&a, &b| power[b].total_cmp(&power[a]));
+
+        for &k in peaks.iter().take(3) {
+            let (frequency, a, b) = spectrum[k];
+            // a·sin(ωt) + b·cos(ωt) = A·sin(ωt + φ)
+            patterns.oscillations.push(OscillationPattern {
+                frequency,
+                amplitude: (a * a + b * b).sqrt(),
+                phase: b.atan2(a),
+                confidence: power[k] / total_power,
+            });
+        }
+
+        Ok(patterns)
     }
 }
 
@@ -823,6 +1714,8 @@ impl TemporalAlignmentEngine {
             reference_timeline,
             alignment_cache,
             quality_assessor,
+            drift_estimator: ClockDriftEstimator::default(),
+            clock_estimates: HashMap::new(),
         })
     }
     
@@ -835,8 +1728,8 @@ impl TemporalAlignmentEngine {
         let mut corrected = HashMap::new();
         
         for (sensor_type, sensor_measurements) in measurements {
-            let delay_model = self.delay_models.get(&sensor_type)
-                .ok_or_else(|| AppError::processing("No delay model for sensor type"))?;
+            let delay_model = self.delay_models.entry(sensor_type)
+                .or_insert_with(AtomicClockDelayModel::uncalibrated);
             
             let corrected_measurements: Vec<TimestampedMeasurement> = sensor_measurements
                 .into_iter()
@@ -869,6 +1762,159 @@ impl TemporalAlignmentEngine {
         Ok(corrected)
     }
     
+    /// Estimate a sensor's clock offset and drift from observations that
+    /// overlap a reference series of the same quantity
+    ///
+    /// The estimate replaces the sensor's frequency drift model, so later
+    /// `apply_delay_corrections` calls remove the drift from its timestamps.
+    pub fn estimate_clock_drift(
+        &mut self,
+        sensor_type: SensorType,
+        observations: &[TimestampedMeasurement],
+        reference: &[TimestampedMeasurement],
+    ) -> Result<ClockDriftEstimate, AppError> {
+        let estimate = self.drift_estimator.estimate(&scalar_samples(observations), &scalar_samples(reference))?;
+
+        self.delay_models
+            .entry(sensor_type)
+            .or_insert_with(AtomicClockDelayModel::uncalibrated)
+            .frequency_drift_model = DriftPredictor::from_estimate(&estimate);
+        self.clock_estimates.insert(sensor_type, estimate.clone());
+
+        Ok(estimate)
+    }
+
+    /// Latest clock estimate for a sensor, if one has been made
+    pub fn clock_estimate(&self, sensor_type: SensorType) -> Option<&ClockDriftEstimate> {
+        self.clock_estimates.get(&sensor_type)
+    }
+
+    /// Correct every sensor's timestamps and resample scalar series onto a
+    /// shared grid spanning the reference sensor's observations
+    ///
+    /// Grid points inside gaps longer than `max_gap_seconds` are masked and
+    /// left out of the aligned measurements. Non-scalar measurements (images,
+    /// positions) are delay-corrected but not resampled.
+    pub async fn align_to_common_grid(
+        &mut self,
+        measurements: HashMap<SensorType, Vec<TimestampedMeasurement>>,
+        reference_sensor: SensorType,
+        step_seconds: f64,
+        method: ResamplingMethod,
+        max_gap_seconds: f64,
+    ) -> Result<(AlignedSensorData, HashMap<SensorType, ResampledSeries>), AppError> {
+        let corrected = self.apply_delay_corrections(measurements).await?;
+
+        let span_of = |series: &[TimestampedMeasurement]| {
+            series.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), m| (lo.min(m.timestamp), hi.max(m.timestamp)))
+        };
+        let (start, end) = match corrected.get(&reference_sensor).filter(|series| !series.is_empty()) {
+            Some(series) => span_of(series),
+            None => corrected.values().map(|series| span_of(series)).fold(
+                (f64::INFINITY, f64::NEG_INFINITY),
+                |(lo, hi), (a, b)| (lo.min(a), hi.max(b)),
+            ),
+        };
+        let grid = TimeGrid::covering(start, end, step_seconds)?;
+
+        let mut aligned_measurements = HashMap::new();
+        let mut resampled = HashMap::new();
+        let mut accuracies = Vec::new();
+
+        for (sensor_type, mut series) in corrected {
+            let samples = scalar_samples(&series);
+            if samples.len() < series.len() || samples.is_empty() {
+                aligned_measurements.insert(sensor_type, series);
+                continue;
+            }
+
+            series.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+            let resampled_series = grid.resample(&samples, method, max_gap_seconds);
+            let clock_residual = self.clock_estimates.get(&sensor_type).map_or(0.0, |e| e.residual_rms_seconds);
+
+            let grid_measurements: Vec<TimestampedMeasurement> = (0..grid.len)
+                .filter(|&i| resampled_series.mask[i])
+                .map(|i| {
+                    let t = grid.time_at(i);
+                    let nearest = series.partition_point(|m| m.timestamp < t).min(series.len() - 1);
+                    let nearest = if nearest > 0 && (series[nearest - 1].timestamp - t).abs() < (series[nearest].timestamp - t).abs() {
+                        nearest - 1
+                    } else {
+                        nearest
+                    };
+
+                    let mut measurement = series[nearest].clone();
+                    measurement.timestamp = t;
+                    measurement.value = measurement.value.with_scalar_value(resampled_series.values[i]);
+                    let timing = measurement.temporal_uncertainty.unwrap_or(0.0);
+                    measurement.temporal_uncertainty = Some((timing * timing + clock_residual * clock_residual).sqrt());
+                    measurement
+                })
+                .collect();
+
+            if let Some(accuracy) = interpolation_accuracy(&samples, max_gap_seconds) {
+                accuracies.push(accuracy);
+            }
+            aligned_measurements.insert(sensor_type, grid_measurements);
+            resampled.insert(sensor_type, resampled_series);
+        }
+
+        let alignment_quality_metrics = self.grid_alignment_quality(&grid, &resampled, &accuracies);
+        let to_datetime = |t: f64| {
+            DateTime::<Utc>::from_timestamp(t.floor() as i64, ((t - t.floor()) * 1e9) as u32).unwrap_or_default()
+        };
+
+        Ok((
+            AlignedSensorData {
+                aligned_measurements,
+                reference_sensor,
+                alignment_quality_metrics,
+                temporal_window: (to_datetime(grid.start), to_datetime(grid.end())),
+            },
+            resampled,
+        ))
+    }
+
+    /// Quality of a grid alignment, including the clock residuals left after drift correction
+    fn grid_alignment_quality(
+        &self,
+        grid: &TimeGrid,
+        resampled: &HashMap<SensorType, ResampledSeries>,
+        interpolation_accuracies: &[f64],
+    ) -> TemporalQualityMetrics {
+        let estimates: Vec<&ClockDriftEstimate> = resampled
+            .keys()
+            .filter_map(|sensor_type| self.clock_estimates.get(sensor_type))
+            .collect();
+
+        let residual_timing_error = if estimates.is_empty() {
+            0.0
+        } else {
+            (estimates.iter().map(|e| e.residual_rms_seconds.powi(2)).sum::<f64>() / estimates.len() as f64).sqrt()
+        };
+        let delay_correction_confidence = if estimates.is_empty() {
+            0.0
+        } else {
+            let used: usize = estimates.iter().map(|e| e.windows_used).sum();
+            let rejected: usize = estimates.iter().map(|e| e.windows_rejected).sum();
+            used as f64 / (used + rejected).max(1) as f64
+        };
+
+        let alignment_consistency = mean_or_zero(resampled.values().map(|series| series.coverage()));
+        let interpolation_accuracy = mean_or_zero(interpolation_accuracies.iter().copied());
+        let synchronization_score = 1.0 / (1.0 + residual_timing_error / grid.step);
+
+        TemporalQualityMetrics {
+            synchronization_score,
+            interpolation_accuracy,
+            delay_correction_confidence,
+            overall_temporal_quality: (synchronization_score + interpolation_accuracy + alignment_consistency) / 3.0,
+            alignment_consistency,
+            temporal_resolution: grid.step,
+            residual_timing_error,
+        }
+    }
+    
     async fn initialize_delay_models() -> Result<HashMap<SensorType, AtomicClockDelayModel>, AppError> {
         let mut models = HashMap::new();
         
@@ -919,6 +1965,8 @@ impl DriftPredictor {
         Self {
             coefficients: vec![0.0, 0.0, 0.0], // polynomial coefficients
             model_type: "polynomial".to_string(),
+            reference_epoch: 0.0,
+            residual_rms_ns: 0.0,
         }
     }
 }
@@ -964,4 +2012,324 @@ impl TemporalQualityAssessor {
             synchronization_quality_history: CircularBuffer::new(1000),
         }
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    /// Air temperature with a diurnal cycle and cloud-driven AR(1) fluctuations
+    fn true_temperature(seed: u64, start: f64, days: f64, step: f64) -> Vec<(f64, f64)> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut weather = 0.0;
+        let count = (days * 86400.0 / step) as usize;
+        (0..count)
+            .map(|i| {
+                let t = start + i as f64 * step;
+                weather = 0.97 * weather + 0.15 * rng.sample::<f64, _>(StandardNormal);
+                (t, 18.0 + 6.0 * (2.0 * PI * t / 86400.0).sin() + weather)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_clock_drift_recovered_from_overlapping_reference() {
+        let start = 1_700_000_000.0;
+        let truth = true_temperature(7, start, 8.0, 30.0);
+        let mut rng = StdRng::seed_from_u64(11);
+
+        let reference: Vec<(f64, f64)> = truth
+            .iter()
+            .step_by(2)
+            .map(|(t, v)| (*t, v + 0.05 * rng.sample::<f64, _>(StandardNormal)))
+            .collect();
+
+        // Logger reads 40 s ahead and gains 3 s/day, with its own gain and bias
+        let (offset, drift_per_day) = (40.0, 3.0);
+        let sensor: Vec<(f64, f64)> = truth
+            .iter()
+            .step_by(4)
+            .map(|(t, v)| {
+                let clock = t + offset + drift_per_day * (t - start) / 86400.0;
+                (clock, 0.9 * v + 1.5 + 0.05 * rng.sample::<f64, _>(StandardNormal))
+            })
+            .collect();
+
+        let estimate = ClockDriftEstimator::default().estimate(&sensor, &reference).unwrap();
+        let predicted_offset = estimate.clock_error_at(start + offset);
+
+        assert!((predicted_offset - offset).abs() < 5.0, "offset {}", predicted_offset);
+        assert!((estimate.drift_seconds_per_day - drift_per_day).abs() < 1.0, "drift {}", estimate.drift_seconds_per_day);
+        assert!(estimate.residual_rms_seconds < 10.0);
+
+        let predictor = DriftPredictor::from_estimate(&estimate);
+        let t = start + 2.0 * 86400.0;
+        assert!((predictor.predict_drift(t) - estimate.clock_error_at(t) * 1e9).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_cubic_spline_interpolates_smooth_signal() {
+        let times: Vec<f64> = (0..=20).map(|i| i as f64 * 0.5).collect();
+        let values: Vec<f64> = times.iter().map(|t| t.sin()).collect();
+        let spline = CubicSpline::fit(&times, &values).unwrap();
+
+        for (t, v) in times.iter().zip(&values) {
+            assert!((spline.evaluate(*t).unwrap() - v).abs() < 1e-12);
+        }
+        for i in 0..40 {
+            let t = 1.0 + i as f64 * 0.2;
+            assert!((spline.evaluate(t).unwrap() - t.sin()).abs() < 2e-3);
+        }
+        assert!(spline.evaluate(10.5).is_none());
+    }
+
+    #[test]
+    fn test_resampling_masks_gaps_and_series_edges() {
+        let mut samples: Vec<(f64, f64)> = (0..10).map(|i| (i as f64 * 60.0, i as f64)).collect();
+        samples.extend((20..30).map(|i| (i as f64 * 60.0, i as f64)));
+
+        let grid = TimeGrid::covering(-120.0, 1800.0, 30.0).unwrap();
+        for method in [ResamplingMethod::Linear, ResamplingMethod::CubicSpline] {
+            let series = grid.resample(&samples, method, 120.0);
+            for i in 0..grid.len {
+                let t = grid.time_at(i);
+                let expected_valid = (0.0..=540.0).contains(&t) || (1200.0..=1740.0).contains(&t);
+                assert_eq!(series.mask[i], expected_valid, "t = {}", t);
+                if expected_valid {
+                    assert!((series.values[i] - t / 60.0).abs() < 1e-9);
+                } else {
+                    assert!(series.values[i].is_nan());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_oscillation_analyzer_finds_trend_and_jitter() {
+        let analyzer = OscillationAnalyzer { window_size: 1024, fft_size: 2048 };
+        let frequency = 1.0 / 640.0;
+        let timestamps: Vec<f64> = (0..1024)
+            .map(|i| {
+                let t = 1_000.0 + i as f64 * 10.0;
+                t * (1.0 + 2e-5) + 0.05 * (2.0 * PI * frequency * t).sin()
+            })
+            .collect();
+
+        let patterns = analyzer.analyze(&timestamps).unwrap();
+        assert_eq!(patterns.trends.len(), 1);
+        let oscillation = &patterns.oscillations[0];
+        assert!((oscillation.frequency - frequency).abs() < 0.05 * frequency);
+        assert!((oscillation.amplitude - 0.05).abs() < 0.01);
+    }
+
+    fn dtw(radius: Option<usize>, itakura: bool, pattern: StepPattern, boundary: bool) -> DynamicTimeWarping {
+        DynamicTimeWarping::new(
+            DTWDistanceMetric::Euclidean,
+            DTWConstraints {
+                sakoe_chiba_radius: radius,
+                itakura_parallelogram: itakura,
+                step_pattern: pattern,
+                boundary_constraints: boundary,
+            },
+        )
+    }
+
+    /// Cell admissibility straight from the constraint definitions, evaluated per
+    /// cell in signed exact arithmetic so it shares nothing with `row_band`
+    fn admissible_cell(constraints: &DTWConstraints, i: usize, j: usize, n: usize, m: usize) -> bool {
+        let (i, j, rows, columns) = (i as i64, j as i64, n as i64 - 1, m as i64 - 1);
+        let floor_div = |a: i64, b: i64| a.div_euclid(b);
+        let ceil_div = |a: i64, b: i64| -(-a).div_euclid(b);
+
+        if let Some(radius) = constraints.sakoe_chiba_radius {
+            // Nearest column to the corner-to-corner diagonal, halves rounded up
+            let diagonal = if rows > 0 { floor_div(2 * i * columns + rows, 2 * rows) } else { 0 };
+            if (j - diagonal).abs() > radius as i64 {
+                return false;
+            }
+        }
+
+        if constraints.itakura_parallelogram && rows > 0 && columns > 0 {
+            // Local slope between ½ and 2 as seen from both corners, widened outward to whole cells
+            let above_lower = j >= floor_div(i * columns, 2 * rows) && j >= floor_div((2 * i - rows) * columns, rows);
+            let below_upper = j <= ceil_div(2 * i * columns, rows) && j <= ceil_div((i + rows) * columns, 2 * rows);
+            if !(above_lower && below_upper) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Full-matrix DTW with the band applied as a mask, for checking the banded solver
+    fn naive_dtw(engine: &DynamicTimeWarping, a: &[Vec<f64>], b: &[Vec<f64>]) -> Option<f64> {
+        let (n, m) = (a.len(), b.len());
+        let steps = engine.constraints.step_pattern.steps().unwrap();
+        let open = !engine.constraints.boundary_constraints;
+
+        let mut d = vec![vec![f64::INFINITY; m]; n];
+        for i in 0..n {
+            for j in 0..m {
+                if !admissible_cell(&engine.constraints, i, j, n, m) {
+                    continue;
+                }
+                let cost = engine.distance_metric.distance(&a[i], &b[j]);
+                let mut best = if i == 0 && (j == 0 || open) { cost } else { f64::INFINITY };
+                for &(di, dj, w) in &steps {
+                    if di <= i && dj <= j {
+                        best = best.min(d[i - di][j - dj] + w * cost);
+                    }
+                }
+                d[i][j] = best;
+            }
+        }
+
+        // A row with no admissible cell makes the constraints unsatisfiable
+        if (0..n).any(|i| !(0..m).any(|j| admissible_cell(&engine.constraints, i, j, n, m))) {
+            return None;
+        }
+        let end = if open { d[n - 1].iter().copied().fold(f64::INFINITY, f64::min) } else { d[n - 1][m - 1] };
+        end.is_finite().then_some(end)
+    }
+
+    fn pattern_strategy() -> impl proptest::strategy::Strategy<Value = StepPattern> {
+        use proptest::prelude::*;
+        prop_oneof![
+            Just(StepPattern::Symmetric1),
+            Just(StepPattern::Symmetric2),
+            Just(StepPattern::SymmetricNormalizable),
+            Just(StepPattern::Asymmetric),
+            Just(StepPattern::Custom(vec![(1, 1, 1.0), (1, 2, 1.5), (2, 1, 1.5)])),
+            Just(StepPattern::Custom(vec![(1, 0, 1.0), (1, 1, 1.0), (1, 2, 2.0)])),
+        ]
+    }
+
+    fn series_strategy(dimension: usize) -> impl proptest::strategy::Strategy<Value = Vec<Vec<f64>>> {
+        use proptest::prelude::*;
+        prop::collection::vec(prop::collection::vec(-10.0..10.0f64, dimension), 1..24)
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_banded_dtw_matches_naive(
+            a in series_strategy(2),
+            b in series_strategy(2),
+            radius in proptest::option::of(0usize..6),
+            itakura: bool,
+            boundary: bool,
+            pattern in pattern_strategy(),
+        ) {
+            let engine = dtw(radius, itakura, pattern, boundary);
+            match (engine.distance(&a, &b), naive_dtw(&engine, &a, &b)) {
+                (Ok(banded), Some(naive)) => proptest::prop_assert!((banded - naive).abs() <= 1e-9 * naive.max(1.0)),
+                (Err(_), None) => {},
+                (banded, naive) => proptest::prop_assert!(false, "banded {:?} vs naive {:?}", banded, naive),
+            }
+        }
+
+        #[test]
+        fn prop_lb_keogh_never_exceeds_dtw(
+            a in series_strategy(3),
+            b in series_strategy(3),
+            radius in proptest::option::of(0usize..6),
+            itakura: bool,
+            boundary: bool,
+            pattern in pattern_strategy(),
+        ) {
+            let engine = dtw(radius, itakura, pattern, boundary);
+            if let Ok(distance) = engine.distance(&a, &b) {
+                proptest::prop_assert!(engine.lb_keogh(&a, &b).unwrap() <= distance + 1e-9);
+            }
+        }
+
+        #[test]
+        fn prop_nearest_neighbours_match_brute_force(
+            query in series_strategy(2),
+            candidates in proptest::collection::vec(series_strategy(2), 1..8),
+            k in 1usize..4,
+            radius in proptest::option::of(0usize..6),
+            itakura: bool,
+            boundary: bool,
+            pattern in pattern_strategy(),
+        ) {
+            let engine = dtw(radius, itakura, pattern, boundary);
+            let mut exhaustive: Vec<f64> = candidates.iter().filter_map(|c| engine.distance(&query, c).ok()).collect();
+            exhaustive.sort_by(f64::total_cmp);
+            exhaustive.truncate(k);
+
+            let search = engine.nearest_neighbours(&query, &candidates, k).unwrap();
+            let found: Vec<f64> = search.neighbours.iter().map(|(_, distance)| *distance).collect();
+            proptest::prop_assert_eq!(found.len(), exhaustive.len());
+            for (found, expected) in found.iter().zip(&exhaustive) {
+                proptest::prop_assert!((found - expected).abs() <= 1e-9 * expected.max(1.0), "{:?} vs {:?}", found, exhaustive);
+            }
+        }
+    }
+
+    #[test]
+    fn test_banded_matrix_stores_only_the_band() {
+        let mut engine = dtw(Some(3), false, StepPattern::SymmetricNormalizable, true);
+        let a: Vec<Vec<f64>> = (0..200).map(|i| vec![(i as f64 * 0.1).sin()]).collect();
+        let b: Vec<Vec<f64>> = (0..200).map(|i| vec![(i as f64 * 0.1 + 0.2).sin()]).collect();
+
+        let (_, path) = engine.align_features(&a, &b).unwrap();
+        assert!(engine.cost_matrix.stored_cells() <= 200 * 7);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(199, 199)));
+        assert!(path.iter().all(|(i, j)| i.abs_diff(*j) <= 3));
+    }
+
+    #[test]
+    fn test_nearest_seasons_by_multivariate_dtw() {
+        use crate::data_fusion::MeasurementValue;
+
+        // (temperature, NDVI) seasons; the query is season 2 shifted by a week
+        let season = |phase: f64, amplitude: f64| -> Vec<Vec<f64>> {
+            (0..52)
+                .map(|w| {
+                    let x = 2.0 * PI * (w as f64 - phase) / 52.0;
+                    vec![15.0 + amplitude * x.sin(), 0.5 + 0.3 * (x - 0.5).sin()]
+                })
+                .collect()
+        };
+        let candidates = vec![season(0.0, 4.0), season(5.0, 9.0), season(2.0, 6.0), season(12.0, 12.0)];
+        let query = season(3.0, 6.0);
+
+        let engine = dtw(Some(4), false, StepPattern::SymmetricNormalizable, true);
+        let search = engine.nearest_neighbours(&query, &candidates, 2).unwrap();
+        assert_eq!(search.neighbours[0].0, 2);
+        assert_eq!(search.neighbours.len(), 2);
+        assert!(search.neighbours[0].1 <= search.neighbours[1].1);
+        assert_eq!(search.unreachable, 0);
+
+        let exhaustive: Vec<f64> = candidates.iter().map(|c| engine.distance(&query, c).unwrap()).collect();
+        let mut sorted = exhaustive.clone();
+        sorted.sort_by(f64::total_cmp);
+        assert!((search.neighbours[1].1 - sorted[1]).abs() < 1e-9);
+
+        let value = MeasurementValue::Vector(query[0].clone());
+        assert_eq!(value.feature_vector(), Some(query[0].clone()));
+    }
+
+    #[test]
+    fn test_early_abandon_allows_steps_that_skip_rows() {
+        // Symmetric2 reaches row 2 from row 0 without visiting row 1
+        let engine = dtw(None, false, StepPattern::Symmetric2, true);
+        let query = vec![vec![0.0], vec![0.0], vec![0.0]];
+        let candidates = vec![vec![vec![5.0]], vec![vec![1.0]]];
+        let search = engine.nearest_neighbours(&query, &candidates, 1).unwrap();
+        assert_eq!(search.neighbours, vec![(1, engine.distance(&query, &candidates[1]).unwrap())]);
+        assert_eq!(search.early_abandoned, 0);
+    }
+
+    #[test]
+    fn test_constraints_without_a_path_are_rejected() {
+        // Asymmetric steps advance the target at most one cell per reference cell
+        let a: Vec<Vec<f64>> = (0..3).map(|i| vec![i as f64]).collect();
+        let b: Vec<Vec<f64>> = (0..12).map(|i| vec![i as f64]).collect();
+        assert!(dtw(None, false, StepPattern::Asymmetric, true).distance(&a, &b).is_err());
+        assert!(dtw(None, false, StepPattern::Asymmetric, false).distance(&a, &b).is_ok());
+        assert!(dtw(None, false, StepPattern::Asymmetric, true).distance(&b, &a).is_ok());
+    }
+}
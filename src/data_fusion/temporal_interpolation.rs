@@ -0,0 +1,1119 @@
+use std::f64::consts::TAU;
+use nalgebra::{DMatrix, DVector, Point3};
+
+use crate::error::AppError;
+use super::MeasurementValue;
+use super::fusion_algorithms::ndvi_from_measurement;
+use super::phantom_satellites::{
+    ChangeDetectionModel, ChangeType, InterpolationModel, ObservationType, RealObservation,
+    TemporalInterpolationEngine
+};
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Which observations feed a field's time series and how much each counts
+#[derive(Debug, Clone)]
+pub struct SeriesSelection {
+    /// Observations further than this from the target, in degrees of latitude and longitude, are ignored
+    pub spatial_radius_deg: f64,
+    /// Scenes cloudier than this are dropped
+    pub max_cloud_coverage: f64,
+}
+
+impl Default for SeriesSelection {
+    fn default() -> Self {
+        Self {
+            spatial_radius_deg: 0.01,
+            max_cloud_coverage: 0.3,
+        }
+    }
+}
+
+/// One usable observation on the day axis (days since the Unix epoch)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeriesPoint {
+    pub day: f64,
+    pub value: f64,
+    /// Relative weight from residual cloud and radiometric quality
+    pub weight: f64,
+}
+
+impl SeriesSelection {
+    /// Usable observations near `target_location` (latitude, longitude, altitude), in time order.
+    /// Vegetation index scenes are reduced to NDVI; other measurements to their scalar value.
+    pub fn extract(&self, observations: &[RealObservation], target_location: &Point3<f64>) -> Vec<SeriesPoint> {
+        let mut points: Vec<SeriesPoint> = observations
+            .iter()
+            .filter(|obs| {
+                (obs.location.x - target_location.x).abs() <= self.spatial_radius_deg
+                    && (obs.location.y - target_location.y).abs() <= self.spatial_radius_deg
+                    && obs.quality_indicators.cloud_coverage <= self.max_cloud_coverage
+            })
+            .filter_map(|obs| {
+                let value = ndvi_from_measurement(&obs.sensor_data).or_else(|| obs.sensor_data.scalar_value())?;
+                let quality = &obs.quality_indicators;
+                let weight = ((1.0 - quality.cloud_coverage) * quality.radiometric_quality).clamp(0.05, 1.0);
+                value.is_finite().then_some(SeriesPoint { day: obs.timestamp / SECONDS_PER_DAY, value, weight })
+            })
+            .collect();
+        points.sort_by(|a, b| a.day.total_cmp(&b.day));
+        points
+    }
+}
+
+fn require_points(points: &[SeriesPoint], needed: usize, model: &str) -> Result<(), AppError> {
+    if points.len() < needed {
+        return Err(AppError::validation(format!(
+            "{} needs at least {} usable observations, found {}",
+            model,
+            needed,
+            points.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Weights rescaled to average one, so residual variances stay in value units
+fn normalised_weights(points: &[SeriesPoint]) -> Vec<f64> {
+    let mean = points.iter().map(|p| p.weight).sum::<f64>() / points.len() as f64;
+    points.iter().map(|p| p.weight / mean).collect()
+}
+
+/// Weighted least squares: coefficients and `(XᵀWX)⁻¹`
+fn weighted_least_squares(design: &DMatrix<f64>, values: &DVector<f64>, weights: &[f64]) -> Option<(DVector<f64>, DMatrix<f64>)> {
+    let weighted = DMatrix::from_fn(design.nrows(), design.ncols(), |i, j| design[(i, j)] * weights[i]);
+    let normal = weighted.transpose() * design;
+    let cholesky = normal.cholesky()?;
+    let coefficients = cholesky.solve(&(weighted.transpose() * values));
+    Some((coefficients, cholesky.inverse()))
+}
+
+fn day_of(target_time: f64) -> f64 {
+    target_time / SECONDS_PER_DAY
+}
+
+fn value_result(value: f64) -> MeasurementValue {
+    MeasurementValue::Scalar(value)
+}
+
+/// Annual harmonics plus an optional linear trend, fitted by weighted least squares
+///
+/// Uncertainty is the prediction standard error, residual scatter included:
+/// a few harmonics cannot follow green-up and harvest exactly, and the
+/// residuals measure how far the real curve departs from the fit.
+#[derive(Debug, Clone)]
+pub struct HarmonicSeasonalModel {
+    pub selection: SeriesSelection,
+    pub harmonics: usize,
+    pub period_days: f64,
+    pub include_trend: bool,
+}
+
+impl Default for HarmonicSeasonalModel {
+    fn default() -> Self {
+        Self {
+            selection: SeriesSelection::default(),
+            harmonics: 2,
+            period_days: 365.25,
+            include_trend: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HarmonicFit {
+    pub coefficients: DVector<f64>,
+    covariance: DMatrix<f64>,
+    pub residual_variance: f64,
+    reference_day: f64,
+    harmonics: usize,
+    period_days: f64,
+    include_trend: bool,
+}
+
+impl HarmonicFit {
+    fn regressors(&self, day: f64) -> DVector<f64> {
+        harmonic_regressors(day, self.reference_day, self.harmonics, self.period_days, self.include_trend)
+    }
+
+    pub fn predict(&self, day: f64) -> f64 {
+        self.regressors(day).dot(&self.coefficients)
+    }
+
+    pub fn prediction_std(&self, day: f64) -> f64 {
+        let x = self.regressors(day);
+        (self.residual_variance * (1.0 + (x.transpose() * &self.covariance * &x)[(0, 0)])).sqrt()
+    }
+
+    /// Amplitude and phase (radians) of harmonic `k`, counting from 1
+    pub fn harmonic(&self, k: usize) -> Option<(f64, f64)> {
+        let offset = if self.include_trend { 2 } else { 1 } + 2 * (k.checked_sub(1)?);
+        let (a, b) = (*self.coefficients.get(offset)?, *self.coefficients.get(offset + 1)?);
+        Some((a.hypot(b), b.atan2(a)))
+    }
+}
+
+fn harmonic_regressors(day: f64, reference_day: f64, harmonics: usize, period_days: f64, include_trend: bool) -> DVector<f64> {
+    let mut row = vec![1.0];
+    if include_trend {
+        row.push((day - reference_day) / period_days);
+    }
+    for k in 1..=harmonics {
+        let angle = TAU * k as f64 * day / period_days;
+        row.push(angle.cos());
+        row.push(angle.sin());
+    }
+    DVector::from_vec(row)
+}
+
+impl HarmonicSeasonalModel {
+    fn parameter_count(&self) -> usize {
+        1 + usize::from(self.include_trend) + 2 * self.harmonics
+    }
+
+    pub fn fit(&self, points: &[SeriesPoint]) -> Result<HarmonicFit, AppError> {
+        let p = self.parameter_count();
+        require_points(points, p + 1, "Harmonic seasonal model")?;
+        let reference_day = points[0].day;
+        let design = DMatrix::from_fn(points.len(), p, |i, j| {
+            harmonic_regressors(points[i].day, reference_day, self.harmonics, self.period_days, self.include_trend)[j]
+        });
+        let values = DVector::from_iterator(points.len(), points.iter().map(|p| p.value));
+        let weights = normalised_weights(points);
+        let (coefficients, covariance) = weighted_least_squares(&design, &values, &weights)
+            .ok_or_else(|| AppError::validation("Observations do not span enough of the season to fit its harmonics"))?;
+        let residuals = &values - &design * &coefficients;
+        let rss: f64 = residuals.iter().zip(&weights).map(|(r, w)| w * r * r).sum();
+        Ok(HarmonicFit {
+            coefficients,
+            covariance,
+            residual_variance: rss / (points.len() - p) as f64,
+            reference_day,
+            harmonics: self.harmonics,
+            period_days: self.period_days,
+            include_trend: self.include_trend,
+        })
+    }
+}
+
+impl InterpolationModel for HarmonicSeasonalModel {
+    fn interpolate(&self, observations: &[RealObservation], target_location: &Point3<f64>, target_time: f64) -> Result<MeasurementValue, AppError> {
+        let fit = self.fit(&self.selection.extract(observations, target_location))?;
+        Ok(value_result(fit.predict(day_of(target_time))))
+    }
+
+    fn estimate_uncertainty(&self, observations: &[RealObservation], target_location: &Point3<f64>, target_time: f64) -> f64 {
+        self.fit(&self.selection.extract(observations, target_location))
+            .map(|fit| fit.prediction_std(day_of(target_time)))
+            .unwrap_or(f64::INFINITY)
+    }
+
+    fn interpolate_series(&self, observations: &[RealObservation], target_location: &Point3<f64>, target_times: &[f64]) -> Result<Vec<(MeasurementValue, f64)>, AppError> {
+        let fit = self.fit(&self.selection.extract(observations, target_location))?;
+        Ok(target_times
+            .iter()
+            .map(|&time| (value_result(fit.predict(day_of(time))), fit.prediction_std(day_of(time))))
+            .collect())
+    }
+}
+
+/// Whittaker smoother on a daily grid (Eilers 2003)
+///
+/// Minimises `Σ wᵢ(yᵢ − zᵢ)² + λ Σ (Δᵈz)²` over daily values `z`. Days
+/// without a usable scene get zero weight, so gaps are filled by the
+/// smoothness penalty alone. Uncertainty is the posterior standard deviation
+/// `σ √[(W + λDᵀD)⁻¹]ᵢᵢ`, with `σ²` estimated from the residuals and the
+/// smoother's effective degrees of freedom. As an interpolation model, `λ` is
+/// chosen per series by generalised cross-validation over `lambda_candidates`.
+#[derive(Debug, Clone)]
+pub struct WhittakerSmoother {
+    pub selection: SeriesSelection,
+    pub lambda: f64,
+    /// Smoothing parameters tried by generalised cross-validation; `lambda` is used when empty
+    pub lambda_candidates: Vec<f64>,
+    pub difference_order: usize,
+}
+
+impl Default for WhittakerSmoother {
+    fn default() -> Self {
+        Self {
+            selection: SeriesSelection::default(),
+            lambda: 100.0,
+            lambda_candidates: vec![1.0, 3.0, 10.0, 30.0, 100.0, 300.0, 1000.0, 3000.0, 10000.0],
+            difference_order: 2,
+        }
+    }
+}
+
+/// Symmetric positive-definite band matrix; `bands[k][i]` holds `A[i][i−k]`
+#[derive(Debug, Clone)]
+struct BandedCholesky {
+    lower: Vec<Vec<f64>>,
+    bandwidth: usize,
+}
+
+impl BandedCholesky {
+    fn factor(bands: &[Vec<f64>]) -> Option<Self> {
+        let bandwidth = bands.len() - 1;
+        let n = bands[0].len();
+        let mut lower = vec![vec![0.0; n]; bandwidth + 1];
+        for i in 0..n {
+            for j in i.saturating_sub(bandwidth)..=i {
+                let k = i - j;
+                let mut sum = bands[k][i];
+                for m in i.saturating_sub(bandwidth)..j {
+                    sum -= lower[i - m][i] * lower[j - m][j];
+                }
+                if k == 0 {
+                    if sum <= 0.0 {
+                        return None;
+                    }
+                    lower[0][i] = sum.sqrt();
+                } else {
+                    lower[k][i] = sum / lower[0][j];
+                }
+            }
+        }
+        Some(Self { lower, bandwidth })
+    }
+
+    fn solve(&self, rhs: &[f64]) -> Vec<f64> {
+        let n = rhs.len();
+        let mut y = rhs.to_vec();
+        for i in 0..n {
+            for j in i.saturating_sub(self.bandwidth)..i {
+                y[i] -= self.lower[i - j][i] * y[j];
+            }
+            y[i] /= self.lower[0][i];
+        }
+        for i in (0..n).rev() {
+            for j in (i + 1)..(i + self.bandwidth + 1).min(n) {
+                y[i] -= self.lower[j - i][j] * y[j];
+            }
+            y[i] /= self.lower[0][i];
+        }
+        y
+    }
+
+    /// `[A⁻¹]ᵢᵢ`
+    fn inverse_diagonal_at(&self, i: usize) -> f64 {
+        let mut unit = vec![0.0; self.lower[0].len()];
+        unit[i] = 1.0;
+        self.solve(&unit)[i]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WhittakerFit {
+    /// First grid day (days since the Unix epoch)
+    pub first_day: f64,
+    /// Smoothed value for each day from `first_day`
+    pub values: Vec<f64>,
+    pub residual_variance: f64,
+    pub effective_degrees_of_freedom: f64,
+    factor: BandedCholesky,
+}
+
+impl WhittakerFit {
+    fn index(&self, day: f64) -> Option<usize> {
+        let offset = (day - self.first_day).round();
+        (offset >= 0.0 && (offset as usize) < self.values.len()).then_some(offset as usize)
+    }
+
+    /// Smoothed value, linearly interpolated between grid days; `None` outside the observed span
+    pub fn value_at(&self, day: f64) -> Option<f64> {
+        let offset = day - self.first_day;
+        if offset < 0.0 || offset > (self.values.len() - 1) as f64 {
+            return None;
+        }
+        let below = offset.floor() as usize;
+        let above = (below + 1).min(self.values.len() - 1);
+        let fraction = offset - below as f64;
+        Some(self.values[below] * (1.0 - fraction) + self.values[above] * fraction)
+    }
+
+    pub fn std_at(&self, day: f64) -> Option<f64> {
+        self.index(day).map(|i| (self.residual_variance * self.factor.inverse_diagonal_at(i)).sqrt())
+    }
+}
+
+fn difference_coefficients(order: usize) -> Vec<f64> {
+    // Signed binomial coefficients of the order-d forward difference
+    let mut coefficients = vec![1.0];
+    for _ in 0..order {
+        let mut next = vec![0.0; coefficients.len() + 1];
+        for (i, c) in coefficients.iter().enumerate() {
+            next[i] -= c;
+            next[i + 1] += c;
+        }
+        coefficients = next;
+    }
+    coefficients
+}
+
+impl WhittakerSmoother {
+    pub fn fit(&self, points: &[SeriesPoint]) -> Result<WhittakerFit, AppError> {
+        self.fit_with_lambda(points, self.lambda)
+    }
+
+    /// Fit with the cross-validated smoothing parameter, or `lambda` without candidates
+    pub fn fit_selected(&self, points: &[SeriesPoint]) -> Result<WhittakerFit, AppError> {
+        if self.lambda_candidates.is_empty() {
+            return self.fit(points);
+        }
+        let lambda = self.gcv_lambda(points, &self.lambda_candidates)?;
+        self.fit_with_lambda(points, lambda)
+    }
+
+    fn fit_with_lambda(&self, points: &[SeriesPoint], lambda: f64) -> Result<WhittakerFit, AppError> {
+        let d = self.difference_order;
+        require_points(points, d + 1, "Whittaker smoother")?;
+        if lambda <= 0.0 {
+            return Err(AppError::validation("Whittaker smoothing parameter must be positive"));
+        }
+        let first_day = points[0].day.round();
+        let n = (points[points.len() - 1].day.round() - first_day) as usize + 1;
+        if n <= d {
+            return Err(AppError::validation("Whittaker smoother needs observations spread over several days"));
+        }
+
+        // Several scenes on one day combine into one weighted value
+        let weights = normalised_weights(points);
+        let mut grid_weight = vec![0.0; n];
+        let mut grid_sum = vec![0.0; n];
+        for (point, w) in points.iter().zip(&weights) {
+            let i = (point.day.round() - first_day) as usize;
+            grid_weight[i] += w;
+            grid_sum[i] += w * point.value;
+        }
+
+        let mut bands = vec![vec![0.0; n]; d + 1];
+        bands[0].clone_from(&grid_weight);
+        let c = difference_coefficients(d);
+        for r in 0..n - d {
+            for a in 0..=d {
+                for b in 0..=a {
+                    bands[a - b][r + a] += lambda * c[a] * c[b];
+                }
+            }
+        }
+        let factor = BandedCholesky::factor(&bands)
+            .ok_or_else(|| AppError::validation("Whittaker system is singular; observations are too sparse"))?;
+        let values = factor.solve(&grid_sum);
+
+        let observed: Vec<usize> = (0..n).filter(|&i| grid_weight[i] > 0.0).collect();
+        let effective_degrees_of_freedom: f64 = observed.iter().map(|&i| grid_weight[i] * factor.inverse_diagonal_at(i)).sum();
+        let rss: f64 = points
+            .iter()
+            .zip(&weights)
+            .map(|(p, w)| w * (p.value - values[(p.day.round() - first_day) as usize]).powi(2))
+            .sum();
+        let dof = (points.len() as f64 - effective_degrees_of_freedom).max(1.0);
+
+        Ok(WhittakerFit {
+            first_day,
+            values,
+            residual_variance: rss / dof,
+            effective_degrees_of_freedom,
+            factor,
+        })
+    }
+
+    /// Smoothing parameter from `candidates` with the lowest generalised cross-validation score
+    pub fn gcv_lambda(&self, points: &[SeriesPoint], candidates: &[f64]) -> Result<f64, AppError> {
+        let mut best = None;
+        for &lambda in candidates {
+            let fit = self.fit_with_lambda(points, lambda)?;
+            let n = points.len() as f64;
+            let rss = fit.residual_variance * (n - fit.effective_degrees_of_freedom).max(1.0);
+            let score = n * rss / (n - fit.effective_degrees_of_freedom).max(1e-9).powi(2);
+            if best.is_none_or(|(_, s)| score < s) {
+                best = Some((lambda, score));
+            }
+        }
+        best.map(|(lambda, _)| lambda).ok_or_else(|| AppError::validation("No smoothing parameter candidates"))
+    }
+}
+
+fn outside_whittaker_span() -> AppError {
+    AppError::validation("Target time lies outside the observed span; the Whittaker smoother does not extrapolate")
+}
+
+impl InterpolationModel for WhittakerSmoother {
+    fn interpolate(&self, observations: &[RealObservation], target_location: &Point3<f64>, target_time: f64) -> Result<MeasurementValue, AppError> {
+        let fit = self.fit_selected(&self.selection.extract(observations, target_location))?;
+        fit.value_at(day_of(target_time)).map(value_result).ok_or_else(outside_whittaker_span)
+    }
+
+    fn estimate_uncertainty(&self, observations: &[RealObservation], target_location: &Point3<f64>, target_time: f64) -> f64 {
+        self.fit_selected(&self.selection.extract(observations, target_location))
+            .ok()
+            .and_then(|fit| fit.std_at(day_of(target_time)))
+            .unwrap_or(f64::INFINITY)
+    }
+
+    fn interpolate_series(&self, observations: &[RealObservation], target_location: &Point3<f64>, target_times: &[f64]) -> Result<Vec<(MeasurementValue, f64)>, AppError> {
+        let fit = self.fit_selected(&self.selection.extract(observations, target_location))?;
+        target_times
+            .iter()
+            .map(|&time| {
+                let day = day_of(time);
+                let value = fit.value_at(day).ok_or_else(outside_whittaker_span)?;
+                Ok((value_result(value), fit.std_at(day).unwrap_or(f64::INFINITY)))
+            })
+            .collect()
+    }
+}
+
+/// Savitzky–Golay smoothing generalised to irregular revisits
+///
+/// A local polynomial is fitted to the scenes within `half_window_days` of
+/// the target day; its constant term is the smoothed value. With
+/// `upper_envelope_iterations > 0`, scenes below the fit are replaced by it
+/// and the fit repeated (Chen et al. 2004), since residual cloud and shadow
+/// bias NDVI low. Uncertainty is the standard error of the local fit.
+#[derive(Debug, Clone)]
+pub struct SavitzkyGolayFilter {
+    pub selection: SeriesSelection,
+    pub half_window_days: f64,
+    pub polynomial_order: usize,
+    pub upper_envelope_iterations: usize,
+}
+
+impl Default for SavitzkyGolayFilter {
+    fn default() -> Self {
+        Self {
+            selection: SeriesSelection::default(),
+            half_window_days: 30.0,
+            polynomial_order: 2,
+            upper_envelope_iterations: 3,
+        }
+    }
+}
+
+impl SavitzkyGolayFilter {
+    /// Local fit at `day`: value and standard error
+    fn local_fit(&self, points: &[SeriesPoint], day: f64) -> Option<(f64, f64)> {
+        let window: Vec<&SeriesPoint> = points.iter().filter(|p| (p.day - day).abs() <= self.half_window_days).collect();
+        let p = self.polynomial_order + 1;
+        if window.len() < p + 1 {
+            return None;
+        }
+        // The fit must bracket the target or it extrapolates
+        if window.iter().all(|w| w.day > day) || window.iter().all(|w| w.day < day) {
+            return None;
+        }
+        let design = DMatrix::from_fn(window.len(), p, |i, j| ((window[i].day - day) / self.half_window_days).powi(j as i32));
+        let values = DVector::from_iterator(window.len(), window.iter().map(|w| w.value));
+        let owned: Vec<SeriesPoint> = window.iter().map(|w| **w).collect();
+        let weights = normalised_weights(&owned);
+        let (coefficients, covariance) = weighted_least_squares(&design, &values, &weights)?;
+        let residuals = &values - &design * &coefficients;
+        let rss: f64 = residuals.iter().zip(&weights).map(|(r, w)| w * r * r).sum();
+        let variance = rss / (window.len() - p) as f64;
+        Some((coefficients[0], (variance * covariance[(0, 0)]).sqrt()))
+    }
+
+    /// Series with cloud-depressed values lifted to the upper envelope
+    pub fn upper_envelope(&self, points: &[SeriesPoint]) -> Vec<SeriesPoint> {
+        let mut series = points.to_vec();
+        for _ in 0..self.upper_envelope_iterations {
+            let fitted: Vec<Option<f64>> = series.iter().map(|p| self.local_fit(&series, p.day).map(|(v, _)| v)).collect();
+            let mut changed = false;
+            for (point, fit) in series.iter_mut().zip(fitted) {
+                if let Some(fit) = fit.filter(|fit| *fit > point.value) {
+                    point.value = fit;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        series
+    }
+
+    pub fn smooth_at(&self, points: &[SeriesPoint], day: f64) -> Result<(f64, f64), AppError> {
+        require_points(points, self.polynomial_order + 2, "Savitzky-Golay filter")?;
+        self.local_fit(&self.upper_envelope(points), day).ok_or_else(|| {
+            AppError::validation(format!(
+                "Too few scenes either side of day {:.0} within the {}-day window",
+                day, self.half_window_days
+            ))
+        })
+    }
+}
+
+impl InterpolationModel for SavitzkyGolayFilter {
+    fn interpolate(&self, observations: &[RealObservation], target_location: &Point3<f64>, target_time: f64) -> Result<MeasurementValue, AppError> {
+        let points = self.selection.extract(observations, target_location);
+        self.smooth_at(&points, day_of(target_time)).map(|(value, _)| value_result(value))
+    }
+
+    fn estimate_uncertainty(&self, observations: &[RealObservation], target_location: &Point3<f64>, target_time: f64) -> f64 {
+        let points = self.selection.extract(observations, target_location);
+        self.smooth_at(&points, day_of(target_time)).map(|(_, std)| std).unwrap_or(f64::INFINITY)
+    }
+
+    fn interpolate_series(&self, observations: &[RealObservation], target_location: &Point3<f64>, target_times: &[f64]) -> Result<Vec<(MeasurementValue, f64)>, AppError> {
+        let points = self.selection.extract(observations, target_location);
+        require_points(&points, self.polynomial_order + 2, "Savitzky-Golay filter")?;
+        let envelope = self.upper_envelope(&points);
+        target_times
+            .iter()
+            .map(|&time| {
+                let day = day_of(time);
+                let (value, std) = self.local_fit(&envelope, day).ok_or_else(|| {
+                    AppError::validation(format!(
+                        "Too few scenes either side of day {:.0} within the {}-day window",
+                        day, self.half_window_days
+                    ))
+                })?;
+                Ok((value_result(value), std))
+            })
+            .collect()
+    }
+}
+
+/// Linear trend between two breaks
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendSegment {
+    pub start_day: f64,
+    pub end_day: f64,
+    pub intercept: f64,
+    pub slope_per_day: f64,
+}
+
+impl TrendSegment {
+    fn value_at(&self, day: f64) -> f64 {
+        self.intercept + self.slope_per_day * day
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DetectedBreak {
+    pub day: f64,
+    /// `ChangeType::AbruptChange` with the signed jump in the trend at the break
+    pub change: ChangeType,
+    pub slope_change_per_day: f64,
+}
+
+/// Season, trend segments and breaks of one series
+#[derive(Debug, Clone)]
+pub struct BreakAnalysis {
+    pub segments: Vec<TrendSegment>,
+    pub breaks: Vec<DetectedBreak>,
+    /// Cosine and sine coefficients of each harmonic
+    pub seasonal_coefficients: Vec<(f64, f64)>,
+    pub period_days: f64,
+    pub residual_std: f64,
+}
+
+impl BreakAnalysis {
+    pub fn seasonal_at(&self, day: f64) -> f64 {
+        self.seasonal_coefficients
+            .iter()
+            .enumerate()
+            .map(|(k, (a, b))| {
+                let angle = TAU * (k + 1) as f64 * day / self.period_days;
+                a * angle.cos() + b * angle.sin()
+            })
+            .sum()
+    }
+
+    pub fn trend_at(&self, day: f64) -> f64 {
+        let segment = self
+            .segments
+            .iter()
+            .find(|s| day < s.end_day)
+            .or(self.segments.last())
+            .expect("a break analysis always has a segment");
+        segment.value_at(day)
+    }
+
+    pub fn predict(&self, day: f64) -> f64 {
+        self.trend_at(day) + self.seasonal_at(day)
+    }
+
+    /// Breaks as abrupt changes, segment trends as gradual changes and the
+    /// annual harmonic as a cyclical change
+    pub fn change_types(&self) -> Vec<ChangeType> {
+        let mut changes: Vec<ChangeType> = self.breaks.iter().map(|b| b.change.clone()).collect();
+        changes.extend(self.segments.iter().map(|s| ChangeType::GradualChange { rate: s.slope_per_day }));
+        if let Some((a, b)) = self.seasonal_coefficients.first() {
+            changes.push(ChangeType::CyclicalChange { period: self.period_days, amplitude: a.hypot(*b) });
+        }
+        changes
+    }
+}
+
+/// BFAST-style break detection (Verbesselt et al. 2010)
+///
+/// Alternates between fitting harmonics to the detrended series and finding
+/// trend breaks in the deseasonalised series, until the breaks stop moving.
+/// Break positions minimise the residual sum of squares of a piecewise-linear
+/// trend by dynamic programming (Bai & Perron); the number of breaks is chosen
+/// by BIC among fits whose every jump is at least `min_magnitude`.
+#[derive(Debug, Clone)]
+pub struct BfastBreakDetector {
+    pub selection: SeriesSelection,
+    pub harmonics: usize,
+    pub period_days: f64,
+    pub max_breaks: usize,
+    /// Shortest segment as a fraction of the series length (BFAST's `h`)
+    pub min_segment_fraction: f64,
+    pub min_magnitude: f64,
+    pub max_iterations: usize,
+}
+
+impl Default for BfastBreakDetector {
+    fn default() -> Self {
+        Self {
+            selection: SeriesSelection::default(),
+            harmonics: 2,
+            period_days: 365.25,
+            max_breaks: 3,
+            min_segment_fraction: 0.15,
+            min_magnitude: 0.0,
+            max_iterations: 10,
+        }
+    }
+}
+
+/// Prefix sums for O(1) weighted linear regression on any index range
+struct SegmentCosts {
+    sums: Vec<[f64; 6]>,
+    /// Days are measured from here to keep the sums well conditioned
+    origin_day: f64,
+}
+
+impl SegmentCosts {
+    fn new(days: &[f64], values: &[f64], weights: &[f64]) -> Self {
+        let origin_day = days[0];
+        let mut sums = vec![[0.0; 6]];
+        for ((&day, &y), &w) in days.iter().zip(values).zip(weights) {
+            let t = day - origin_day;
+            let last = sums[sums.len() - 1];
+            sums.push([last[0] + w, last[1] + w * t, last[2] + w * t * t, last[3] + w * y, last[4] + w * t * y, last[5] + w * y * y]);
+        }
+        Self { sums, origin_day }
+    }
+
+    /// Intercept (at day zero), slope and residual sum of squares of points `start..end`
+    fn fit(&self, start: usize, end: usize) -> (f64, f64, f64) {
+        let (a, b) = (self.sums[start], self.sums[end]);
+        let [sw, st, stt, sy, sty, syy] = std::array::from_fn(|i| b[i] - a[i]);
+        let var_t = stt - st * st / sw;
+        let cov = sty - st * sy / sw;
+        let var_y = (syy - sy * sy / sw).max(0.0);
+        if var_t <= 1e-12 {
+            return (sy / sw, 0.0, var_y);
+        }
+        let slope = cov / var_t;
+        ((sy - slope * st) / sw - slope * self.origin_day, slope, (var_y - cov * cov / var_t).max(0.0))
+    }
+}
+
+impl BfastBreakDetector {
+    pub fn analyse(&self, points: &[SeriesPoint]) -> Result<BreakAnalysis, AppError> {
+        let n = points.len();
+        let min_segment = ((self.min_segment_fraction * n as f64).ceil() as usize).max(2 * self.harmonics + 3);
+        require_points(points, min_segment, "BFAST break detector")?;
+        let days: Vec<f64> = points.iter().map(|p| p.day).collect();
+        let weights = normalised_weights(points);
+
+        let mut analysis = BreakAnalysis {
+            segments: Vec::new(),
+            breaks: Vec::new(),
+            seasonal_coefficients: vec![(0.0, 0.0); self.harmonics],
+            period_days: self.period_days,
+            residual_std: 0.0,
+        };
+        // Start from the season of a single linear trend, as BFAST starts from an STL decomposition
+        let values: Vec<f64> = points.iter().map(|p| p.value).collect();
+        (analysis.segments, analysis.seasonal_coefficients, _) = self.fit_joint(&days, &values, &weights, &[])?;
+
+        let mut boundaries: Vec<usize> = Vec::new();
+        for _ in 0..self.max_iterations {
+            let deseasonalised: Vec<f64> = points.iter().map(|p| p.value - analysis.seasonal_at(p.day)).collect();
+            let costs = SegmentCosts::new(&days, &deseasonalised, &weights);
+            let new_boundaries = self.select_breaks(&costs, n, min_segment, &days, &values, &weights)?;
+            (analysis.segments, analysis.seasonal_coefficients, _) = self.fit_joint(&days, &values, &weights, &new_boundaries)?;
+            let converged = new_boundaries == boundaries;
+            boundaries = new_boundaries;
+            if converged {
+                break;
+            }
+        }
+
+        analysis.breaks = analysis
+            .segments
+            .windows(2)
+            .map(|pair| {
+                let day = pair[1].start_day;
+                DetectedBreak {
+                    day,
+                    change: ChangeType::AbruptChange { magnitude: pair[1].value_at(day) - pair[0].value_at(day) },
+                    slope_change_per_day: pair[1].slope_per_day - pair[0].slope_per_day,
+                }
+            })
+            .collect();
+        let rss: f64 = points.iter().zip(&weights).map(|(p, w)| w * (p.value - analysis.predict(p.day)).powi(2)).sum();
+        let parameters = 2 * self.harmonics + 3 * analysis.segments.len() - 1;
+        analysis.residual_std = (rss / (n.saturating_sub(parameters)).max(1) as f64).sqrt();
+        Ok(analysis)
+    }
+
+    /// Segment start indices after the first, for the BIC-best admissible break count
+    fn select_breaks(
+        &self,
+        costs: &SegmentCosts,
+        n: usize,
+        min_segment: usize,
+        days: &[f64],
+        values: &[f64],
+        weights: &[f64],
+    ) -> Result<Vec<usize>, AppError> {
+        // best[m][j]: lowest RSS of points 0..j split into m + 1 segments, with the last split position
+        let max_breaks = self.max_breaks.min(n / min_segment - 1);
+        let mut best = vec![vec![(f64::INFINITY, 0usize); n + 1]; max_breaks + 1];
+        for (j, entry) in best[0].iter_mut().enumerate().skip(min_segment) {
+            *entry = (costs.fit(0, j).2, 0);
+        }
+        for m in 1..=max_breaks {
+            for j in ((m + 1) * min_segment)..=n {
+                for i in (m * min_segment)..=(j - min_segment) {
+                    let candidate = best[m - 1][i].0 + costs.fit(i, j).2;
+                    if candidate < best[m][j].0 {
+                        best[m][j] = (candidate, i);
+                    }
+                }
+            }
+        }
+
+        let mut chosen: Option<(f64, Vec<usize>)> = None;
+        for m in 0..=max_breaks {
+            if !best[m][n].0.is_finite() {
+                continue;
+            }
+            let mut splits = Vec::with_capacity(m);
+            let mut j = n;
+            for level in (1..=m).rev() {
+                j = best[level][j].1;
+                splits.push(j);
+            }
+            splits.reverse();
+            // The partition comes from the deseasonalised costs, but magnitudes and RSS from a joint refit so
+            // that a step the current season has partly absorbed is still measured at full size
+            let (segments, _, rss) = self.fit_joint(days, values, weights, &splits)?;
+            let admissible = segments
+                .windows(2)
+                .all(|pair| (pair[1].value_at(pair[1].start_day) - pair[0].value_at(pair[1].start_day)).abs() >= self.min_magnitude);
+            if !admissible {
+                continue;
+            }
+            let bic = n as f64 * (rss.max(1e-12) / n as f64).ln() + (3 * m + 2) as f64 * (n as f64).ln();
+            if chosen.as_ref().is_none_or(|(best_bic, _)| bic < *best_bic) {
+                chosen = Some((bic, splits));
+            }
+        }
+        Ok(chosen.map(|(_, splits)| splits).unwrap_or_default())
+    }
+
+    /// Weighted least-squares fit of one intercept and slope per segment together with the shared
+    /// harmonics, returning the segments, the seasonal coefficients and the weighted RSS.
+    #[allow(clippy::type_complexity)]
+    fn fit_joint(
+        &self,
+        days: &[f64],
+        values: &[f64],
+        weights: &[f64],
+        splits: &[usize],
+    ) -> Result<(Vec<TrendSegment>, Vec<(f64, f64)>, f64), AppError> {
+        let n = days.len();
+        let origin_day = days[0];
+        let mut starts = vec![0];
+        starts.extend_from_slice(splits);
+        let segment_of: Vec<usize> = (0..n).map(|i| starts.partition_point(|&start| start <= i) - 1).collect();
+        let trend_columns = 2 * starts.len();
+        let design = DMatrix::from_fn(n, trend_columns + 2 * self.harmonics, |i, j| {
+            if j < trend_columns {
+                if j / 2 != segment_of[i] {
+                    0.0
+                } else if j % 2 == 0 {
+                    1.0
+                } else {
+                    days[i] - origin_day
+                }
+            } else {
+                let k = j - trend_columns;
+                let angle = TAU * (k / 2 + 1) as f64 * days[i] / self.period_days;
+                if k % 2 == 0 { angle.cos() } else { angle.sin() }
+            }
+        });
+        let observed = DVector::from_column_slice(values);
+        let (coefficients, _) = weighted_least_squares(&design, &observed, weights)
+            .ok_or_else(|| AppError::validation("Observations do not span enough of the season to fit its harmonics"))?;
+        let residuals = &observed - &design * &coefficients;
+        let rss = residuals.iter().zip(weights).map(|(r, w)| w * r * r).sum();
+
+        let segments = starts
+            .iter()
+            .enumerate()
+            .map(|(k, &start)| {
+                let end = starts.get(k + 1).copied().unwrap_or(n);
+                let slope_per_day = coefficients[2 * k + 1];
+                // Breaks sit midway between the last scene before and the first after
+                let start_day = if start == 0 { days[0] } else { (days[start - 1] + days[start]) / 2.0 };
+                let end_day = if end == n { f64::INFINITY } else { (days[end - 1] + days[end]) / 2.0 };
+                TrendSegment { start_day, end_day, intercept: coefficients[2 * k] - slope_per_day * origin_day, slope_per_day }
+            })
+            .collect();
+        let seasonal = (0..self.harmonics).map(|k| (coefficients[trend_columns + 2 * k], coefficients[trend_columns + 2 * k + 1])).collect();
+        Ok((segments, seasonal, rss))
+    }
+}
+
+impl InterpolationModel for BfastBreakDetector {
+    fn interpolate(&self, observations: &[RealObservation], target_location: &Point3<f64>, target_time: f64) -> Result<MeasurementValue, AppError> {
+        let analysis = self.analyse(&self.selection.extract(observations, target_location))?;
+        Ok(value_result(analysis.predict(day_of(target_time))))
+    }
+
+    fn estimate_uncertainty(&self, observations: &[RealObservation], target_location: &Point3<f64>, _target_time: f64) -> f64 {
+        self.analyse(&self.selection.extract(observations, target_location))
+            .map(|analysis| analysis.residual_std)
+            .unwrap_or(f64::INFINITY)
+    }
+
+    fn interpolate_series(&self, observations: &[RealObservation], target_location: &Point3<f64>, target_times: &[f64]) -> Result<Vec<(MeasurementValue, f64)>, AppError> {
+        let analysis = self.analyse(&self.selection.extract(observations, target_location))?;
+        Ok(target_times
+            .iter()
+            .map(|&time| (value_result(analysis.predict(day_of(time))), analysis.residual_std))
+            .collect())
+    }
+}
+
+/// Gap-filled value for one day
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DailyEstimate {
+    /// Noon UTC of the day, seconds since the Unix epoch
+    pub timestamp: f64,
+    pub value: f64,
+    pub uncertainty: f64,
+}
+
+impl TemporalInterpolationEngine {
+    /// Engine with Whittaker smoothing for vegetation indices, upper-envelope
+    /// Savitzky–Golay for multispectral scenes and harmonic fits for thermal
+    /// and soil moisture series
+    pub fn with_gap_filling_models() -> Self {
+        let mut engine = Self::new();
+        engine.register_interpolation_model(ObservationType::VegetationIndices, Box::new(WhittakerSmoother::default()));
+        engine.register_interpolation_model(ObservationType::MultispectralImagery, Box::new(SavitzkyGolayFilter::default()));
+        engine.register_interpolation_model(ObservationType::ThermalInfrared, Box::new(HarmonicSeasonalModel::default()));
+        engine.register_interpolation_model(ObservationType::SoilMoisture, Box::new(HarmonicSeasonalModel::default()));
+        engine
+    }
+
+    pub fn register_interpolation_model(&mut self,
+                                        observation_type: ObservationType,
+                                        model: Box<dyn InterpolationModel + Send + Sync>) {
+        self.interpolation_models.insert(observation_type, model);
+    }
+
+    /// Daily values and uncertainties from `start_time` to `end_time` (Unix seconds) at a field
+    pub fn daily_series(&self,
+                        observation_type: &ObservationType,
+                        observations: &[RealObservation],
+                        location: &Point3<f64>,
+                        start_time: f64,
+                        end_time: f64) -> Result<Vec<DailyEstimate>, AppError> {
+        let model = self.interpolation_models.get(observation_type)
+            .ok_or_else(|| AppError::not_found(format!("No interpolation model for {:?}", observation_type)))?;
+        let first_noon = (start_time / SECONDS_PER_DAY).floor() * SECONDS_PER_DAY + SECONDS_PER_DAY / 2.0;
+        let mut timestamps = Vec::new();
+        let mut timestamp = first_noon;
+        while timestamp <= end_time {
+            timestamps.push(timestamp);
+            timestamp += SECONDS_PER_DAY;
+        }
+
+        // One fit serves the whole series
+        model.interpolate_series(observations, location, &timestamps)?
+            .into_iter()
+            .zip(timestamps)
+            .map(|((value, uncertainty), timestamp)| {
+                let value = value
+                    .scalar_value()
+                    .ok_or_else(|| AppError::validation("Interpolation model returned a non-scalar value"))?;
+                Ok(DailyEstimate { timestamp, value, uncertainty })
+            })
+            .collect()
+    }
+
+    /// Run break detection for a field and keep the result as its change detection model
+    pub fn detect_changes(&mut self,
+                          field_id: &str,
+                          detector: &BfastBreakDetector,
+                          observations: &[RealObservation],
+                          location: &Point3<f64>) -> Result<BreakAnalysis, AppError> {
+        let points = detector.selection.extract(observations, location);
+        let analysis = detector.analyse(&points)?;
+        let span_days = points[points.len() - 1].day - points[0].day;
+        self.change_detection_models.insert(field_id.to_string(), ChangeDetectionModel {
+            detection_threshold: detector.min_magnitude,
+            change_types: analysis.change_types(),
+            temporal_window: span_days * SECONDS_PER_DAY,
+            spatial_window: detector.selection.spatial_radius_deg,
+        });
+        Ok(analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_fusion::phantom_satellites::{AtmosphericConditions, ObservationQuality};
+
+    const HARVEST_DAY: f64 = 20_000.0 + 250.0;
+
+    /// Two seasons of NDVI with a harvest drop, every 5 days, every fourth scene cloud-depressed
+    fn ndvi_season() -> (Vec<RealObservation>, impl Fn(f64) -> f64) {
+        let truth = |day: f64| {
+            let seasonal = 0.45 + 0.25 * (TAU * day / 365.25).cos();
+            if day >= HARVEST_DAY { seasonal - 0.3 } else { seasonal }
+        };
+        let observations = (0..120)
+            .map(|i| {
+                let day = 20_000.0 + 5.0 * i as f64 + 0.5;
+                let cloudy = i % 4 == 3;
+                let value = truth(day) - if cloudy { 0.2 } else { 0.0 } + 0.01 * ((i * 7 % 5) as f64 - 2.0);
+                scene(i, day, value, if cloudy { 0.25 } else { 0.0 })
+            })
+            .collect();
+        (observations, truth)
+    }
+
+    fn scene(i: usize, day: f64, value: f64, cloud_coverage: f64) -> RealObservation {
+        RealObservation {
+            observation_id: format!("scene-{}", i),
+            timestamp: day * SECONDS_PER_DAY,
+            location: Point3::new(41.0, -94.0, 0.0),
+            sensor_data: MeasurementValue::Scalar(value),
+            quality_indicators: ObservationQuality {
+                signal_to_noise_ratio: 100.0,
+                cloud_coverage,
+                atmospheric_interference: 0.0,
+                geometric_quality: 1.0,
+                radiometric_quality: 1.0,
+            },
+            atmospheric_conditions: AtmosphericConditions {
+                water_vapor: 1.5,
+                aerosol_optical_depth: 0.1,
+                atmospheric_pressure: 1013.0,
+                temperature_profile: Vec::new(),
+            },
+            agricultural_context: None,
+        }
+    }
+
+    #[test]
+    fn test_smoothers_fill_gaps_with_finite_uncertainty() {
+        let (observations, truth) = ndvi_season();
+        let location = Point3::new(41.0, -94.0, 0.0);
+        let points = SeriesSelection::default().extract(&observations, &location);
+
+        let gap_day = 20_000.0 + 102.0;
+        let whittaker = WhittakerSmoother { lambda: 50.0, ..Default::default() }.fit(&points).unwrap();
+        assert!((whittaker.value_at(gap_day).unwrap() - truth(gap_day)).abs() < 0.08);
+        assert!(whittaker.std_at(gap_day).unwrap().is_finite());
+
+        let golay = SavitzkyGolayFilter::default();
+        let (enveloped, std) = golay.smooth_at(&points, gap_day).unwrap();
+        let plain = SavitzkyGolayFilter { upper_envelope_iterations: 0, ..Default::default() }.smooth_at(&points, gap_day).unwrap().0;
+        assert!((enveloped - truth(gap_day)).abs() <= (plain - truth(gap_day)).abs() + 1e-9);
+        assert!(std > 0.0 && std.is_finite());
+
+        let engine = TemporalInterpolationEngine::with_gap_filling_models();
+        let start = (20_000.0 + 30.0) * SECONDS_PER_DAY;
+        let series = engine
+            .daily_series(&ObservationType::VegetationIndices, &observations, &location, start, start + 9.0 * SECONDS_PER_DAY)
+            .unwrap();
+        assert_eq!(series.len(), 9);
+        assert!(series.iter().all(|d| d.uncertainty.is_finite() && (0.0..=1.0).contains(&d.value)));
+
+        // The daily series is the cross-validated fit, sampled at noon
+        let smoother = WhittakerSmoother::default();
+        let selected = smoother.fit_selected(&points).unwrap();
+        let lambda = smoother.gcv_lambda(&points, &smoother.lambda_candidates).unwrap();
+        assert_eq!(selected.values, smoother.fit_with_lambda(&points, lambda).unwrap().values);
+        for estimate in &series {
+            let day = estimate.timestamp / SECONDS_PER_DAY;
+            assert!((estimate.value - selected.value_at(day).unwrap()).abs() < 1e-12);
+            assert!((estimate.uncertainty - selected.std_at(day).unwrap()).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_bfast_finds_the_harvest_break() {
+        let (observations, _) = ndvi_season();
+        let location = Point3::new(41.0, -94.0, 0.0);
+        let detector = BfastBreakDetector { min_magnitude: 0.1, ..Default::default() };
+        let mut engine = TemporalInterpolationEngine::new();
+        let analysis = engine.detect_changes("field-7", &detector, &observations, &location).unwrap();
+
+        assert_eq!(analysis.breaks.len(), 1);
+        let harvest = &analysis.breaks[0];
+        assert!((harvest.day - HARVEST_DAY).abs() <= 5.0);
+        match harvest.change {
+            ChangeType::AbruptChange { magnitude } => assert!((magnitude + 0.3).abs() < 0.1),
+            ref other => panic!("unexpected change {:?}", other),
+        }
+        assert!(engine.change_detection_models["field-7"].change_types.iter().any(|c| matches!(c, ChangeType::CyclicalChange { .. })));
+    }
+
+    #[test]
+    fn test_harmonic_model_recovers_the_seasonal_curve() {
+        // Trend, annual cosine and a semi-annual sine, with ±0.01 alternating noise
+        let truth = |day: f64| {
+            let t = (day - 20_000.5) / 365.25;
+            0.3 + 0.05 * t + 0.2 * (TAU * day / 365.25).cos() - 0.08 * (2.0 * TAU * day / 365.25).sin()
+        };
+        let observations: Vec<RealObservation> = (0..92)
+            .map(|i| 20_000.0 + 8.0 * i as f64 + 0.5)
+            .enumerate()
+            .filter(|&(_, day)| !(20_300.0..20_360.0).contains(&day))
+            .map(|(i, day)| scene(i, day, truth(day) + if i % 2 == 0 { 0.01 } else { -0.01 }, 0.0))
+            .collect();
+        let location = Point3::new(41.0, -94.0, 0.0);
+        let model = HarmonicSeasonalModel::default();
+        let fit = model.fit(&model.selection.extract(&observations, &location)).unwrap();
+
+        assert!((fit.coefficients[0] - 0.3).abs() < 0.01);
+        assert!((fit.coefficients[1] - 0.05).abs() < 0.01);
+        let (annual, annual_phase) = fit.harmonic(1).unwrap();
+        assert!((annual - 0.2).abs() < 0.01 && annual_phase.abs() < 0.05);
+        let (semi_annual, semi_annual_phase) = fit.harmonic(2).unwrap();
+        assert!((semi_annual - 0.08).abs() < 0.01 && (semi_annual_phase + std::f64::consts::FRAC_PI_2).abs() < 0.1);
+        assert!(fit.harmonic(3).is_none());
+        assert!(fit.residual_variance > 0.7e-4 && fit.residual_variance < 1.3e-4);
+
+        // Inside the cloud gap the fit follows the curve, with at least the residual scatter as uncertainty
+        let gap_time = (20_000.0 + 330.5) * SECONDS_PER_DAY;
+        let value = model.interpolate(&observations, &location, gap_time).unwrap().scalar_value().unwrap();
+        assert!((value - truth(day_of(gap_time))).abs() < 0.01);
+        let gap_std = model.estimate_uncertainty(&observations, &location, gap_time);
+        assert!((gap_std - fit.prediction_std(day_of(gap_time))).abs() < 1e-12);
+        assert!(gap_std > fit.residual_variance.sqrt() && gap_std < 0.02);
+
+        // Extrapolating a year past the record widens it through the trend term
+        let beyond = (20_000.0 + 8.0 * 91.0 + 365.5) * SECONDS_PER_DAY;
+        assert!(model.estimate_uncertainty(&observations, &location, beyond) > gap_std);
+
+        // Too few scenes to fit: no value and unbounded uncertainty
+        assert!(model.interpolate(&observations[..5], &location, gap_time).is_err());
+        assert_eq!(model.estimate_uncertainty(&observations[..5], &location, gap_time), f64::INFINITY);
+    }
+}
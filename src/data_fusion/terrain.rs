@@ -0,0 +1,664 @@
+use std::io::{Read, Seek};
+use std::path::Path;
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use statrs::distribution::{ContinuousCDF, Normal};
+use tiff::decoder::{Decoder, DecodingResult};
+use tiff::tags::Tag;
+
+use crate::error::AppError;
+use crate::data_fusion::clear_sky_forecast::solar_position_deg;
+use crate::data_fusion::orbit_propagation::Geodetic;
+use crate::data_fusion::spatial_interpolation::ElevationSource;
+
+/// Mean Earth radius for great-circle and local-projection distances
+pub(crate) const EARTH_RADIUS_M: f64 = 6_371_000.0;
+/// Standard terrestrial refraction coefficient, which flattens the apparent curvature
+const REFRACTION_COEFFICIENT: f64 = 0.13;
+const SRTM_VOID: i16 = -32768;
+const GEOTIFF_MODEL_TYPE_KEY: u16 = 1024;
+const GEOTIFF_RASTER_TYPE_KEY: u16 = 1025;
+const GEOTIFF_MODEL_TYPE_GEOGRAPHIC: u16 = 2;
+const GEOTIFF_RASTER_PIXEL_IS_POINT: u16 = 2;
+
+/// A geographic (WGS84 lat/lon) elevation grid. Heights are stored north to
+/// south, west to east, with voids as NaN; the origin is the centre of the
+/// north-west pixel.
+#[derive(Debug, Clone)]
+pub struct ElevationRaster {
+    pub north_latitude: f64,
+    pub west_longitude: f64,
+    pub latitude_spacing_deg: f64,
+    pub longitude_spacing_deg: f64,
+    pub rows: usize,
+    pub cols: usize,
+    heights: Vec<f32>,
+}
+
+impl ElevationRaster {
+    pub fn new(
+        north_latitude: f64,
+        west_longitude: f64,
+        latitude_spacing_deg: f64,
+        longitude_spacing_deg: f64,
+        rows: usize,
+        cols: usize,
+        heights: Vec<f32>,
+    ) -> Result<Self, AppError> {
+        if rows < 2 || cols < 2 || heights.len() != rows * cols {
+            return Err(AppError::validation(format!(
+                "Elevation raster of {}x{} cells needs at least 2x2 cells and {} heights, got {}",
+                rows, cols, rows * cols, heights.len()
+            )));
+        }
+        if latitude_spacing_deg <= 0.0 || longitude_spacing_deg <= 0.0 {
+            return Err(AppError::validation("Elevation raster spacing must be positive"));
+        }
+        let south = north_latitude - latitude_spacing_deg * (rows - 1) as f64;
+        if north_latitude > 90.0 + latitude_spacing_deg || south < -90.0 - latitude_spacing_deg || west_longitude.abs() > 360.0 {
+            return Err(AppError::validation(
+                "Elevation raster extent is not geographic; reproject the DEM to WGS84 latitude/longitude",
+            ));
+        }
+        Ok(Self { north_latitude, west_longitude, latitude_spacing_deg, longitude_spacing_deg, rows, cols, heights })
+    }
+
+    /// ESRI ASCII grid (`.asc`) in geographic coordinates, with either corner or centre registration
+    pub fn from_ascii_grid(text: &str) -> Result<Self, AppError> {
+        let mut header = std::collections::HashMap::new();
+        let mut tokens = text.split_whitespace().peekable();
+        while let Some(key) = tokens.next_if(|t| t.starts_with(|c: char| c.is_ascii_alphabetic())) {
+            let value = tokens
+                .next()
+                .and_then(|v| v.parse::<f64>().ok())
+                .ok_or_else(|| AppError::validation(format!("ASCII grid header {} has no numeric value", key)))?;
+            header.insert(key.to_ascii_lowercase(), value);
+        }
+        let field = |name: &str| header.get(name).copied();
+        let required = |name: &str| field(name).ok_or_else(|| AppError::validation(format!("ASCII grid header lacks {}", name)));
+
+        let cols = required("ncols")? as usize;
+        let rows = required("nrows")? as usize;
+        let (dx, dy) = match (field("cellsize"), field("dx"), field("dy")) {
+            (Some(size), _, _) => (size, size),
+            (None, Some(dx), Some(dy)) => (dx, dy),
+            _ => return Err(AppError::validation("ASCII grid header lacks cellsize")),
+        };
+        let west = match (field("xllcenter"), field("xllcorner")) {
+            (Some(centre), _) => centre,
+            (None, Some(corner)) => corner + dx / 2.0,
+            _ => return Err(AppError::validation("ASCII grid header lacks xllcorner or xllcenter")),
+        };
+        let south = match (field("yllcenter"), field("yllcorner")) {
+            (Some(centre), _) => centre,
+            (None, Some(corner)) => corner + dy / 2.0,
+            _ => return Err(AppError::validation("ASCII grid header lacks yllcorner or yllcenter")),
+        };
+        let nodata = field("nodata_value");
+
+        let heights = tokens
+            .map(|t| {
+                t.parse::<f64>()
+                    .map(|v| if Some(v) == nodata { f32::NAN } else { v as f32 })
+                    .map_err(|_| AppError::validation(format!("ASCII grid value {} is not a number", t)))
+            })
+            .collect::<Result<Vec<f32>, AppError>>()?;
+        Self::new(south + dy * rows.saturating_sub(1) as f64, west, dy, dx, rows, cols, heights)
+    }
+
+    /// SRTM `.hgt` tile: big-endian 16-bit heights on a square grid whose edge
+    /// pixels sit exactly on the whole-degree boundaries named by the tile, e.g. `N45W122`
+    pub fn from_srtm_hgt(bytes: &[u8], tile_name: &str) -> Result<Self, AppError> {
+        let (south, west) = parse_srtm_tile_name(tile_name)?;
+        let samples = bytes.len() / 2;
+        let side = (samples as f64).sqrt().round() as usize;
+        if bytes.len() % 2 != 0 || side * side != samples || side < 2 {
+            return Err(AppError::validation(format!("{} is not a square SRTM tile ({} bytes)", tile_name, bytes.len())));
+        }
+        let heights = bytes
+            .chunks_exact(2)
+            .map(|pair| match i16::from_be_bytes([pair[0], pair[1]]) {
+                SRTM_VOID => f32::NAN,
+                height => height as f32,
+            })
+            .collect();
+        let spacing = 1.0 / (side - 1) as f64;
+        Self::new(south + 1.0, west, spacing, spacing, side, side, heights)
+    }
+
+    /// Single-band GeoTIFF in a geographic CRS, using the tiepoint/pixel-scale georeferencing
+    pub fn from_geotiff<R: Read + Seek>(reader: R) -> Result<Self, AppError> {
+        let tiff_error = |e: tiff::TiffError| AppError::validation(format!("Invalid GeoTIFF DEM: {}", e));
+        let mut decoder = Decoder::new(reader).map_err(tiff_error)?;
+        let (width, height) = decoder.dimensions().map_err(tiff_error)?;
+        let scale = decoder.get_tag_f64_vec(Tag::ModelPixelScaleTag).map_err(tiff_error)?;
+        let tiepoint = decoder.get_tag_f64_vec(Tag::ModelTiepointTag).map_err(tiff_error)?;
+        if scale.len() < 2 || tiepoint.len() < 6 {
+            return Err(AppError::validation("GeoTIFF DEM needs a pixel scale and a tiepoint"));
+        }
+
+        let geo_keys = decoder.find_tag_unsigned_vec::<u16>(Tag::GeoKeyDirectoryTag).map_err(tiff_error)?.unwrap_or_default();
+        let geo_key = |key: u16| geo_keys.get(4..).unwrap_or(&[]).chunks_exact(4).find(|entry| entry[0] == key).map(|entry| entry[3]);
+        if geo_key(GEOTIFF_MODEL_TYPE_KEY).is_some_and(|model| model != GEOTIFF_MODEL_TYPE_GEOGRAPHIC) {
+            return Err(AppError::validation("GeoTIFF DEM is projected; reproject it to WGS84 latitude/longitude"));
+        }
+        // Pixel-is-area tiepoints refer to the pixel corner rather than its centre
+        let half_pixel = if geo_key(GEOTIFF_RASTER_TYPE_KEY) == Some(GEOTIFF_RASTER_PIXEL_IS_POINT) { 0.0 } else { 0.5 };
+        let nodata = decoder
+            .find_tag(Tag::GdalNodata)
+            .map_err(tiff_error)?
+            .and_then(|value| value.into_string().ok())
+            .and_then(|text| text.trim_matches(char::from(0)).trim().parse::<f64>().ok());
+
+        let to_f32 = |v: f64| if Some(v) == nodata { f32::NAN } else { v as f32 };
+        let heights: Vec<f32> = match decoder.read_image().map_err(tiff_error)? {
+            DecodingResult::U8(v) => v.into_iter().map(|x| to_f32(x as f64)).collect(),
+            DecodingResult::U16(v) => v.into_iter().map(|x| to_f32(x as f64)).collect(),
+            DecodingResult::U32(v) => v.into_iter().map(|x| to_f32(x as f64)).collect(),
+            DecodingResult::U64(v) => v.into_iter().map(|x| to_f32(x as f64)).collect(),
+            DecodingResult::I8(v) => v.into_iter().map(|x| to_f32(x as f64)).collect(),
+            DecodingResult::I16(v) => v.into_iter().map(|x| to_f32(x as f64)).collect(),
+            DecodingResult::I32(v) => v.into_iter().map(|x| to_f32(x as f64)).collect(),
+            DecodingResult::I64(v) => v.into_iter().map(|x| to_f32(x as f64)).collect(),
+            DecodingResult::F32(v) => v.into_iter().map(|x| to_f32(x as f64)).collect(),
+            DecodingResult::F64(v) => v.into_iter().map(to_f32).collect(),
+        };
+        let (rows, cols) = (height as usize, width as usize);
+        if heights.len() != rows * cols {
+            return Err(AppError::validation("GeoTIFF DEM must have a single band"));
+        }
+        let (tie_col, tie_row, tie_lon, tie_lat) = (tiepoint[0], tiepoint[1], tiepoint[3], tiepoint[4]);
+        Self::new(
+            tie_lat + (tie_row - half_pixel) * scale[1],
+            tie_lon - (tie_col - half_pixel) * scale[0],
+            scale[1],
+            scale[0],
+            rows,
+            cols,
+            heights,
+        )
+    }
+
+    fn height(&self, row: usize, col: usize) -> f64 {
+        self.heights[row * self.cols + col] as f64
+    }
+
+    /// Fractional (row, col) of a location, if it lies within half a pixel of the grid
+    fn position(&self, latitude: f64, longitude: f64) -> Option<(f64, f64)> {
+        let row = (self.north_latitude - latitude) / self.latitude_spacing_deg;
+        let delta_lon = (longitude - self.west_longitude).rem_euclid(360.0);
+        let col = delta_lon / self.longitude_spacing_deg;
+        let inside = |x: f64, n: usize| x >= -0.5 && x <= n as f64 - 0.5;
+        (inside(row, self.rows) && inside(col, self.cols)).then_some((row, col))
+    }
+
+    /// Bilinear height, falling back to the nearest pixel at the edges or next to voids
+    pub fn sample(&self, latitude: f64, longitude: f64) -> Option<f64> {
+        let (row, col) = self.position(latitude, longitude)?;
+        let r0 = (row.floor().max(0.0) as usize).min(self.rows - 2);
+        let c0 = (col.floor().max(0.0) as usize).min(self.cols - 2);
+        let (fr, fc) = ((row - r0 as f64).clamp(0.0, 1.0), (col - c0 as f64).clamp(0.0, 1.0));
+        let corners = [self.height(r0, c0), self.height(r0, c0 + 1), self.height(r0 + 1, c0), self.height(r0 + 1, c0 + 1)];
+        if corners.iter().all(|h| h.is_finite()) {
+            let top = corners[0] * (1.0 - fc) + corners[1] * fc;
+            let bottom = corners[2] * (1.0 - fc) + corners[3] * fc;
+            return Some(top * (1.0 - fr) + bottom * fr);
+        }
+        let nearest = self.height(
+            (row.round().max(0.0) as usize).min(self.rows - 1),
+            (col.round().max(0.0) as usize).min(self.cols - 1),
+        );
+        nearest.is_finite().then_some(nearest)
+    }
+}
+
+/// South-west corner of an SRTM tile from names such as `N45W122` or `s08e110.hgt`
+fn parse_srtm_tile_name(name: &str) -> Result<(f64, f64), AppError> {
+    let stem = Path::new(name).file_stem().and_then(|s| s.to_str()).unwrap_or(name).to_ascii_uppercase();
+    let invalid = || AppError::validation(format!("{} is not an SRTM tile name like N45W122", name));
+    let lon_start = stem.find(['E', 'W']).ok_or_else(invalid)?;
+    let (lat_part, lon_part) = stem.split_at(lon_start);
+    let signed = |part: &str, positive: char| -> Result<f64, AppError> {
+        let mut chars = part.chars();
+        let hemisphere = chars.next().ok_or_else(invalid)?;
+        let degrees: f64 = chars.as_str().parse().map_err(|_| invalid())?;
+        Ok(if hemisphere == positive { degrees } else { -degrees })
+    };
+    if !lat_part.starts_with(['N', 'S']) {
+        return Err(invalid());
+    }
+    Ok((signed(lat_part, 'N')?, signed(lon_part, 'E')?))
+}
+
+/// Slope and aspect from Horn's 3x3 finite differences
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerrainAttributes {
+    pub elevation_m: f64,
+    pub slope_deg: f64,
+    /// Downslope direction clockwise from north; `None` on flat ground
+    pub aspect_deg: Option<f64>,
+}
+
+/// Direct-beam illumination of the ground at a location and time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolarIllumination {
+    pub solar_elevation_deg: f64,
+    pub solar_azimuth_deg: f64,
+    /// Elevation of the terrain horizon towards the sun
+    pub horizon_deg: f64,
+    /// The sun is below the terrain horizon or behind the slope itself
+    pub terrain_shaded: bool,
+    /// Cosine of the angle between the sun and the slope normal
+    pub incidence_cosine: f64,
+    /// Direct irradiance on the slope relative to a horizontal unobstructed surface;
+    /// zero when shaded or below the astronomical horizon
+    pub direct_beam_factor: f64,
+}
+
+/// A mosaic of elevation rasters answering point terrain queries
+#[derive(Debug, Clone)]
+pub struct DigitalElevationModel {
+    rasters: Vec<ElevationRaster>,
+    /// How far horizon scans look for obstructions
+    pub horizon_search_distance_m: f64,
+}
+
+impl DigitalElevationModel {
+    pub fn new() -> Self {
+        Self { rasters: Vec::new(), horizon_search_distance_m: 20_000.0 }
+    }
+
+    pub fn add_raster(&mut self, raster: ElevationRaster) {
+        self.rasters.push(raster);
+    }
+
+    pub fn rasters(&self) -> &[ElevationRaster] {
+        &self.rasters
+    }
+
+    /// Loads a `.asc`, `.hgt` or `.tif`/`.tiff` DEM and adds it to the mosaic
+    pub fn load(&mut self, path: &Path) -> Result<(), AppError> {
+        let read_error = |e: std::io::Error| AppError::internal(format!("Cannot read DEM {}: {}", path.display(), e));
+        let extension = path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+        let raster = match extension.as_deref() {
+            Some("asc") => ElevationRaster::from_ascii_grid(&std::fs::read_to_string(path).map_err(read_error)?),
+            Some("hgt") => ElevationRaster::from_srtm_hgt(
+                &std::fs::read(path).map_err(read_error)?,
+                path.file_name().and_then(|n| n.to_str()).unwrap_or_default(),
+            ),
+            Some("tif") | Some("tiff") => {
+                ElevationRaster::from_geotiff(std::io::BufReader::new(std::fs::File::open(path).map_err(read_error)?))
+            }
+            _ => return Err(AppError::validation(format!("Unsupported DEM format: {}", path.display()))),
+        }
+        .map_err(|e| AppError::validation(format!("{}: {}", path.display(), e)))?;
+        self.add_raster(raster);
+        Ok(())
+    }
+
+    /// Loads every DEM file in a directory, e.g. a folder of SRTM tiles; returns how many were added
+    pub fn load_directory(&mut self, directory: &Path) -> Result<usize, AppError> {
+        let entries = std::fs::read_dir(directory)
+            .map_err(|e| AppError::internal(format!("Cannot list DEM directory {}: {}", directory.display(), e)))?;
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| matches!(e.to_ascii_lowercase().as_str(), "asc" | "hgt" | "tif" | "tiff"))
+            })
+            .collect();
+        paths.sort();
+        for path in &paths {
+            self.load(path)?;
+        }
+        Ok(paths.len())
+    }
+
+    pub fn elevation_at(&self, latitude: f64, longitude: f64) -> Option<f64> {
+        self.rasters.iter().find_map(|r| r.sample(latitude, longitude))
+    }
+
+    /// Finest pixel spacing covering a location, in metres north-south
+    fn spacing_m(&self, latitude: f64, longitude: f64) -> Option<f64> {
+        self.rasters
+            .iter()
+            .filter(|r| r.position(latitude, longitude).is_some())
+            .map(|r| r.latitude_spacing_deg.min(r.longitude_spacing_deg).to_radians() * EARTH_RADIUS_M)
+            .min_by(f64::total_cmp)
+    }
+
+    /// Location `distance_m` away along `azimuth_deg` on a locally flat earth
+    fn offset(latitude: f64, longitude: f64, azimuth_deg: f64, distance_m: f64) -> (f64, f64) {
+        let (sin_az, cos_az) = azimuth_deg.to_radians().sin_cos();
+        let angular = distance_m / EARTH_RADIUS_M;
+        (
+            latitude + (angular * cos_az).to_degrees(),
+            longitude + (angular * sin_az / latitude.to_radians().cos().max(1e-6)).to_degrees(),
+        )
+    }
+
+    pub fn terrain_at(&self, latitude: f64, longitude: f64) -> Option<TerrainAttributes> {
+        let elevation_m = self.elevation_at(latitude, longitude)?;
+        let step = self.spacing_m(latitude, longitude)?;
+        let z = |dx: f64, dy: f64| {
+            let (lat, _) = Self::offset(latitude, longitude, 0.0, dy * step);
+            let (_, lon) = Self::offset(latitude, longitude, 90.0, dx * step);
+            self.elevation_at(lat, lon).unwrap_or(elevation_m)
+        };
+        let (a, b, c) = (z(-1.0, 1.0), z(0.0, 1.0), z(1.0, 1.0));
+        let (d, f) = (z(-1.0, 0.0), z(1.0, 0.0));
+        let (g, h, i) = (z(-1.0, -1.0), z(0.0, -1.0), z(1.0, -1.0));
+        let dz_east = ((c + 2.0 * f + i) - (a + 2.0 * d + g)) / (8.0 * step);
+        let dz_north = ((a + 2.0 * b + c) - (g + 2.0 * h + i)) / (8.0 * step);
+        let gradient = dz_east.hypot(dz_north);
+        Some(TerrainAttributes {
+            elevation_m,
+            slope_deg: gradient.atan().to_degrees(),
+            aspect_deg: (gradient > 1e-6).then(|| (-dz_east).atan2(-dz_north).to_degrees().rem_euclid(360.0)),
+        })
+    }
+
+    /// Highest elevation angle of the terrain along an azimuth, allowing for
+    /// earth curvature and standard refraction
+    pub fn horizon_angle_deg(&self, latitude: f64, longitude: f64, azimuth_deg: f64) -> Option<f64> {
+        let origin = self.elevation_at(latitude, longitude)?;
+        let step = self.spacing_m(latitude, longitude)?;
+        let mut horizon = f64::NEG_INFINITY;
+        let mut distance = step;
+        while distance <= self.horizon_search_distance_m {
+            let (lat, lon) = Self::offset(latitude, longitude, azimuth_deg, distance);
+            let Some(height) = self.elevation_at(lat, lon) else {
+                break;
+            };
+            let drop = distance * distance * (1.0 - REFRACTION_COEFFICIENT) / (2.0 * EARTH_RADIUS_M);
+            horizon = horizon.max((height - origin - drop).atan2(distance).to_degrees());
+            // Coarser steps further out, where small features subtend small angles
+            distance += step * (1.0 + distance / 2000.0).floor();
+        }
+        Some(if horizon.is_finite() { horizon } else { 0.0 })
+    }
+
+    /// Horizon elevation in `sectors` equal azimuth sectors starting at north
+    pub fn horizon_profile(&self, latitude: f64, longitude: f64, sectors: usize) -> Option<Vec<f64>> {
+        (0..sectors).map(|k| self.horizon_angle_deg(latitude, longitude, 360.0 * k as f64 / sectors as f64)).collect()
+    }
+
+    pub fn solar_illumination(&self, latitude: f64, longitude: f64, time: DateTime<Utc>) -> Option<SolarIllumination> {
+        let terrain = self.terrain_at(latitude, longitude)?;
+        let site = Geodetic { latitude_deg: latitude, longitude_deg: longitude, height_km: terrain.elevation_m / 1000.0 };
+        let (solar_elevation_deg, solar_azimuth_deg) = solar_position_deg(&site, time);
+        let horizon_deg = if solar_elevation_deg > 0.0 { self.horizon_angle_deg(latitude, longitude, solar_azimuth_deg)? } else { 0.0 };
+
+        let (zenith, slope) = ((90.0 - solar_elevation_deg).to_radians(), terrain.slope_deg.to_radians());
+        let relative_azimuth = (solar_azimuth_deg - terrain.aspect_deg.unwrap_or(0.0)).to_radians();
+        let incidence_cosine = zenith.cos() * slope.cos() + zenith.sin() * slope.sin() * relative_azimuth.cos();
+        let terrain_shaded = solar_elevation_deg > 0.0 && (solar_elevation_deg <= horizon_deg || incidence_cosine <= 0.0);
+        let direct_beam_factor = if solar_elevation_deg <= 0.0 || terrain_shaded {
+            0.0
+        } else {
+            incidence_cosine / zenith.cos().max(0.05)
+        };
+        Some(SolarIllumination {
+            solar_elevation_deg,
+            solar_azimuth_deg,
+            horizon_deg,
+            terrain_shaded,
+            incidence_cosine,
+            direct_beam_factor,
+        })
+    }
+}
+
+impl Default for DigitalElevationModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ElevationSource for DigitalElevationModel {
+    fn elevation_m(&self, latitude: f64, longitude: f64) -> Option<f64> {
+        self.elevation_at(latitude, longitude)
+    }
+}
+
+/// Weather over the coming night that controls radiative cooling; unknown values
+/// are taken as the worst case for frost
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NightConditions {
+    pub wind_speed_m_s: Option<f64>,
+    pub cloud_fraction: Option<f64>,
+}
+
+impl NightConditions {
+    /// 1 on calm, clear nights, when katabatic drainage and inversions are strongest
+    pub fn radiative_cooling_favourability(&self) -> f64 {
+        let calm = self.wind_speed_m_s.map_or(1.0, |w| ((4.0 - w) / 3.0).clamp(0.0, 1.0));
+        let clear = self.cloud_fraction.map_or(1.0, |c| 1.0 - c.clamp(0.0, 1.0));
+        calm * clear
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColdAirPoolingRisk {
+    /// Height of the site below the mean surrounding terrain; positive in hollows
+    pub relative_depth_m: f64,
+    /// Fraction of directions in which the terrain rises above the site
+    pub enclosure: f64,
+    pub slope_deg: f64,
+    /// Topographic propensity to collect cold air, 0-1, independent of the weather
+    pub susceptibility: f64,
+    /// Susceptibility scaled by the night's radiative cooling favourability
+    pub risk: f64,
+    /// Expected depression of the night minimum below the regional forecast (°C)
+    pub temperature_deficit_c: f64,
+}
+
+/// Terrain-driven local climate: lapse rates and nocturnal cold-air pooling
+#[derive(Debug, Clone)]
+pub struct LocalClimateModel {
+    elevation_model: Option<Arc<DigitalElevationModel>>,
+    /// Environmental lapse rate used for elevation corrections (°C per km)
+    pub lapse_rate_c_per_km: f64,
+    /// Radius of the terrain a site is compared with for pooling
+    pub neighbourhood_radius_m: f64,
+    /// Relative depth at which a hollow is fully susceptible
+    pub saturating_depth_m: f64,
+    /// Inversion strength within a pool (°C per metre of depth)
+    pub inversion_gradient_c_per_m: f64,
+    pub max_temperature_deficit_c: f64,
+    /// Standard error of the regional minimum temperature forecast (°C)
+    pub forecast_error_c: f64,
+}
+
+impl LocalClimateModel {
+    pub fn new() -> Self {
+        Self {
+            elevation_model: None,
+            lapse_rate_c_per_km: 6.5,
+            neighbourhood_radius_m: 1000.0,
+            saturating_depth_m: 30.0,
+            inversion_gradient_c_per_m: 0.15,
+            max_temperature_deficit_c: 8.0,
+            forecast_error_c: 1.5,
+        }
+    }
+
+    pub fn with_elevation_model(mut self, elevation_model: Arc<DigitalElevationModel>) -> Self {
+        self.elevation_model = Some(elevation_model);
+        self
+    }
+
+    pub fn elevation_model(&self) -> Option<&DigitalElevationModel> {
+        self.elevation_model.as_deref()
+    }
+
+    /// Temperature moved from `from_elevation_m` to `to_elevation_m` along the lapse rate
+    pub fn lapse_rate_correction(&self, temperature_c: f64, from_elevation_m: f64, to_elevation_m: f64) -> f64 {
+        temperature_c - self.lapse_rate_c_per_km * (to_elevation_m - from_elevation_m) / 1000.0
+    }
+
+    /// Pooling risk from the site's depth below its surroundings, how enclosed it is
+    /// and how flat it is (cold air drains off slopes and collects on valley floors);
+    /// `None` without DEM coverage
+    pub fn cold_air_pooling(&self, latitude: f64, longitude: f64, conditions: &NightConditions) -> Option<ColdAirPoolingRisk> {
+        const DIRECTIONS: usize = 16;
+        const RINGS: usize = 4;
+        let dem = self.elevation_model.as_deref()?;
+        let terrain = dem.terrain_at(latitude, longitude)?;
+
+        let (mut sum, mut count, mut enclosed) = (0.0, 0usize, 0usize);
+        for k in 0..DIRECTIONS {
+            let azimuth = 360.0 * k as f64 / DIRECTIONS as f64;
+            let mut highest = f64::NEG_INFINITY;
+            for ring in 1..=RINGS {
+                let distance = self.neighbourhood_radius_m * ring as f64 / RINGS as f64;
+                let (lat, lon) = DigitalElevationModel::offset(latitude, longitude, azimuth, distance);
+                if let Some(height) = dem.elevation_at(lat, lon) {
+                    sum += height;
+                    count += 1;
+                    highest = highest.max(height);
+                }
+            }
+            if highest > terrain.elevation_m + 2.0 {
+                enclosed += 1;
+            }
+        }
+        if count == 0 {
+            return None;
+        }
+
+        let relative_depth_m = sum / count as f64 - terrain.elevation_m;
+        let enclosure = enclosed as f64 / DIRECTIONS as f64;
+        let flatness = ((10.0 - terrain.slope_deg) / 7.0).clamp(0.0, 1.0);
+        let depth = (relative_depth_m / self.saturating_depth_m).clamp(0.0, 1.0);
+        let susceptibility = depth * (0.5 + 0.5 * enclosure) * flatness;
+        let risk = susceptibility * conditions.radiative_cooling_favourability();
+        Some(ColdAirPoolingRisk {
+            relative_depth_m,
+            enclosure,
+            slope_deg: terrain.slope_deg,
+            susceptibility,
+            risk,
+            temperature_deficit_c: risk * (relative_depth_m.max(0.0) * self.inversion_gradient_c_per_m).min(self.max_temperature_deficit_c),
+        })
+    }
+
+    /// Regional minimum forecast adjusted for pooling at the site
+    pub fn local_minimum_temperature_c(&self, forecast_minimum_c: f64, latitude: f64, longitude: f64, conditions: &NightConditions) -> f64 {
+        forecast_minimum_c - self.cold_air_pooling(latitude, longitude, conditions).map_or(0.0, |p| p.temperature_deficit_c)
+    }
+
+    /// Probability the local minimum falls below `threshold_c` (0 °C for air frost)
+    pub fn frost_probability(
+        &self,
+        forecast_minimum_c: f64,
+        threshold_c: f64,
+        latitude: f64,
+        longitude: f64,
+        conditions: &NightConditions,
+    ) -> f64 {
+        let local = self.local_minimum_temperature_c(forecast_minimum_c, latitude, longitude, conditions);
+        Normal::new(local, self.forecast_error_c.max(1e-6)).map_or(0.0, |n| n.cdf(threshold_c))
+    }
+}
+
+impl Default for LocalClimateModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// An east-west valley at 52°N: a 600 m wide floor at 100 m, sides rising 100 m per km
+    fn valley() -> DigitalElevationModel {
+        let spacing = 1.0 / 1200.0;
+        let (rows, cols) = (241, 241);
+        let mut text = format!("ncols {}\nnrows {}\nxllcenter 5.0\nyllcenter 51.9\ncellsize {}\nNODATA_value -9999\n", cols, rows, spacing);
+        for row in 0..rows {
+            let latitude = 51.9 + spacing * (rows - 1 - row) as f64;
+            let distance_km = ((latitude - 52.0) * 111.2).abs();
+            let height = 100.0 + 100.0 * (distance_km - 0.3).max(0.0);
+            let line: Vec<String> = (0..cols).map(|_| format!("{:.2}", height)).collect();
+            text.push_str(&line.join(" "));
+            text.push('\n');
+        }
+        let mut dem = DigitalElevationModel::new();
+        dem.add_raster(ElevationRaster::from_ascii_grid(&text).unwrap());
+        dem
+    }
+
+    #[test]
+    fn test_srtm_and_geotiff_tiles_are_georeferenced() {
+        // A 3x3 tile rising 10 m per column eastwards with one void
+        let mut bytes = Vec::new();
+        for row in 0..3 {
+            for col in 0..3 {
+                let height: i16 = if (row, col) == (2, 2) { SRTM_VOID } else { 100 + 10 * col };
+                bytes.extend_from_slice(&height.to_be_bytes());
+            }
+        }
+        let tile = ElevationRaster::from_srtm_hgt(&bytes, "S08E110.hgt").unwrap();
+        assert_eq!((tile.north_latitude, tile.west_longitude, tile.latitude_spacing_deg), (-7.0, 110.0, 0.5));
+        assert!((tile.sample(-7.25, 110.25).unwrap() - 105.0).abs() < 1e-9);
+        // Next to the void the nearest pixel stands in, unless it is the void itself
+        assert_eq!(tile.sample(-7.6, 110.9), Some(120.0));
+        assert_eq!(tile.sample(-7.9, 110.9), None);
+        assert!(tile.sample(-6.5, 110.5).is_none());
+
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        {
+            let mut encoder = tiff::encoder::TiffEncoder::new(&mut buffer).unwrap();
+            let mut image = encoder.new_image::<tiff::encoder::colortype::GrayI16>(4, 2).unwrap();
+            image.encoder().write_tag(Tag::ModelPixelScaleTag, &[0.25, 0.25, 0.0][..]).unwrap();
+            image.encoder().write_tag(Tag::ModelTiepointTag, &[0.0, 0.0, 0.0, 5.0, 52.0, 0.0][..]).unwrap();
+            image.encoder().write_tag(Tag::GdalNodata, "-9999").unwrap();
+            image.write_data(&[10i16, 20, 30, 40, 50, 60, 70, -9999]).unwrap();
+        }
+        buffer.set_position(0);
+        let raster = ElevationRaster::from_geotiff(buffer).unwrap();
+        // Pixel-is-area: the first pixel centre is half a pixel in from the tiepoint corner
+        assert_eq!((raster.north_latitude, raster.west_longitude), (51.875, 5.125));
+        assert!((raster.sample(51.875, 5.25).unwrap() - 15.0).abs() < 1e-9);
+        assert_eq!(raster.sample(51.625, 5.625), Some(70.0));
+    }
+
+    #[test]
+    fn test_valley_slopes_face_the_floor_and_shade_it_in_winter() {
+        let dem = valley();
+        let north_side = dem.terrain_at(52.01, 5.1).unwrap();
+        assert!((north_side.slope_deg - (0.1f64).atan().to_degrees()).abs() < 0.2);
+        assert!((north_side.aspect_deg.unwrap() - 180.0).abs() < 1.0);
+        assert_eq!(dem.terrain_at(52.0, 5.1).unwrap().aspect_deg, None);
+
+        // Winter noon sun at 52°N stands about 14.6° high and the southern wall about 5°;
+        // by mid-afternoon the sun is lower than the wall to the south-west
+        let noon = Utc.with_ymd_and_hms(2025, 12, 21, 11, 40, 0).unwrap();
+        let floor = dem.solar_illumination(52.0, 5.1, noon).unwrap();
+        assert!((floor.solar_azimuth_deg - 180.0).abs() < 5.0 && floor.horizon_deg > 2.0 && !floor.terrain_shaded);
+        let evening = Utc.with_ymd_and_hms(2025, 12, 21, 15, 0, 0).unwrap();
+        let low_sun = dem.solar_illumination(52.0, 5.1, evening).unwrap();
+        assert!(low_sun.solar_elevation_deg > 0.0 && low_sun.terrain_shaded && low_sun.direct_beam_factor == 0.0);
+        let south_facing = dem.solar_illumination(52.01, 5.1, noon).unwrap();
+        assert!(south_facing.direct_beam_factor > 1.0);
+    }
+
+    #[test]
+    fn test_valley_floor_pools_cold_air_on_calm_clear_nights() {
+        let climate = LocalClimateModel::new().with_elevation_model(Arc::new(valley()));
+        let calm = NightConditions { wind_speed_m_s: Some(0.5), cloud_fraction: Some(0.0) };
+        let windy = NightConditions { wind_speed_m_s: Some(6.0), cloud_fraction: Some(0.0) };
+
+        let floor = climate.cold_air_pooling(52.0, 5.1, &calm).unwrap();
+        let slope = climate.cold_air_pooling(52.01, 5.1, &calm).unwrap();
+        assert!(floor.relative_depth_m > 10.0 && floor.enclosure >= 0.5 && floor.risk > 0.3);
+        assert!(slope.risk < floor.risk);
+        assert_eq!(climate.cold_air_pooling(52.0, 5.1, &windy).unwrap().risk, 0.0);
+        assert!(climate.frost_probability(2.0, 0.0, 52.0, 5.1, &calm) > climate.frost_probability(2.0, 0.0, 52.0, 5.1, &windy) + 0.1);
+        assert!((climate.lapse_rate_correction(10.0, 100.0, 1100.0) - 3.5).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,91 @@
+//! Measurement fixtures shared by the data fusion unit tests
+
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+
+use super::{
+    AgriculturalContext, ClimateZone, EnvironmentalData, GeoBounds, GeographicRegion, GrowthStage, IrrigationSystem,
+    MeasurementValue, PowerStatus, QualityFlags, SensorMeasurementBundle, SensorMetadata, SensorType,
+    TimestampedMeasurement,
+};
+
+/// Healthy, grid-powered sensor calibrated and serviced when it was installed
+pub(crate) fn sensor_metadata(sensor_id: &str, location: (f64, f64, f64), installed: DateTime<Utc>) -> SensorMetadata {
+    SensorMetadata {
+        sensor_id: sensor_id.to_string(),
+        manufacturer: String::new(),
+        model: String::new(),
+        serial_number: String::new(),
+        calibration_date: installed,
+        last_maintenance: installed,
+        firmware_version: String::new(),
+        location,
+        installation_date: installed,
+        communication_latency: None,
+        power_status: PowerStatus::GridPowered,
+        communication_quality: 1.0,
+        calibration_version: None,
+    }
+}
+
+/// Valid, calibrated reading taken in mild conditions at the sensor's altitude
+pub(crate) fn measurement(value: MeasurementValue, timestamp: f64, sensor_metadata: SensorMetadata) -> TimestampedMeasurement {
+    TimestampedMeasurement {
+        timestamp,
+        value,
+        uncertainty: 0.01,
+        temporal_uncertainty: None,
+        environmental_data: EnvironmentalData {
+            temperature: 20.0,
+            humidity: 55.0,
+            pressure: 1013.25,
+            altitude: sensor_metadata.location.2,
+            magnetic_field: None,
+            solar_activity: None,
+            electromagnetic_interference: None,
+            vibration_level: None,
+        },
+        sensor_metadata,
+        quality_flags: QualityFlags {
+            is_valid: true,
+            is_calibrated: true,
+            drift_detected: false,
+            outlier_detected: false,
+            communication_error: false,
+            sensor_malfunction: false,
+            environmental_impact: false,
+            data_completeness: 1.0,
+        },
+    }
+}
+
+/// One sensor type's readings over a Buhera maize field, windowed on the latest reading
+pub(crate) fn bundle(sensor_type: SensorType, measurements: Vec<TimestampedMeasurement>) -> SensorMeasurementBundle {
+    let latest = measurements.iter().map(|m| m.timestamp).fold(0.0, f64::max);
+    let end = DateTime::<Utc>::from_timestamp(latest.floor() as i64, ((latest - latest.floor()) * 1e9) as u32)
+        .unwrap_or_default();
+
+    SensorMeasurementBundle {
+        measurements: HashMap::from([(sensor_type, measurements)]),
+        quality_metrics: HashMap::new(),
+        temporal_window: (end, end),
+        bundle_id: uuid::Uuid::nil(),
+        geographic_region: GeographicRegion {
+            name: "Buhera".to_string(),
+            bounds: GeoBounds { north: -19.0, south: -20.0, east: 32.0, west: 31.0 },
+            elevation_range: (900.0, 1300.0),
+            climate_zone: ClimateZone::Semiarid,
+            soil_types: Vec::new(),
+            typical_crops: Vec::new(),
+        },
+        agricultural_context: AgriculturalContext {
+            crop_type: "maize".to_string(),
+            growth_stage: GrowthStage::Vegetative,
+            planting_date: None,
+            expected_harvest_date: None,
+            irrigation_system: IrrigationSystem::None,
+            field_management_practices: Vec::new(),
+            historical_yield_data: None,
+        },
+    }
+}
@@ -307,6 +307,12 @@ impl DataIngestionEngine {
         Ok(())
     }
     
+    /// Registered sources of one category, e.g. the optical imagers for overpass forecasting
+    pub async fn sources_in_category(&self, category: DataSourceCategory) -> Vec<DataSource> {
+        let sources = self.sources.read().await;
+        sources.values().filter(|source| source.category == category).cloned().collect()
+    }
+    
     /// Initialize all known data sources
     pub async fn initialize_sources(&self) -> Result<(), AppError> {
         let sources = self.get_all_known_sources().await?;
@@ -353,10 +359,16 @@ impl DataIngestionEngine {
         for record in &records {
             self.storage.store_raw_data(record).await?;
         }
+        self.scheduler.publish(&source.category, &records).await;
         
         Ok(records)
     }
     
+    /// Receive each batch of records collected from sources of `category`, scheduled or manual
+    pub async fn subscribe_records(&self, category: DataSourceCategory, capacity: usize) -> tokio::sync::mpsc::Receiver<Vec<RawDataRecord>> {
+        self.scheduler.subscribe(category, capacity).await
+    }
+    
     /// Get comprehensive list of all known data sources
     async fn get_all_known_sources(&self) -> Result<Vec<DataSource>, AppError> {
         let mut all_sources = Vec::new();
@@ -10,7 +10,7 @@ use sqlx::PgPool;
 
 use crate::config::Config;
 use crate::error::AppError;
-use super::{DataSource, RawDataRecord, UpdateFrequency, IngestionStatus};
+use super::{DataSource, DataSourceCategory, RawDataRecord, UpdateFrequency, IngestionStatus};
 use super::collectors::CollectorRegistry;
 use super::storage::DataStorage;
 
@@ -23,6 +23,7 @@ pub struct IngestionScheduler {
     storage: Arc<DataStorage>,
     task_queue: Arc<RwLock<HashMap<Uuid, ScheduledTask>>>,
     stats: Arc<RwLock<SchedulerStats>>,
+    subscribers: Arc<RwLock<HashMap<DataSourceCategory, Vec<mpsc::Sender<Vec<RawDataRecord>>>>>>,
 }
 
 /// Scheduled task
@@ -83,9 +84,35 @@ impl IngestionScheduler {
                 data_volume_collected: 0,
                 last_collection_time: None,
             })),
+            subscribers: Arc::new(RwLock::new(HashMap::new())),
         })
     }
     
+    /// Receive every batch collected from sources of `category`, e.g. radar field statistics for soil moisture retrieval
+    pub async fn subscribe(&self, category: DataSourceCategory, capacity: usize) -> mpsc::Receiver<Vec<RawDataRecord>> {
+        let (sender, receiver) = mpsc::channel(capacity);
+        self.subscribers.write().await.entry(category).or_default().push(sender);
+        receiver
+    }
+    
+    /// Hand a collected batch to the subscribers of its category, dropping those that have gone away
+    pub async fn publish(&self, category: &DataSourceCategory, records: &[RawDataRecord]) {
+        if records.is_empty() {
+            return;
+        }
+        let mut subscribers = self.subscribers.write().await;
+        if let Some(senders) = subscribers.get_mut(category) {
+            senders.retain(|sender| match sender.try_send(records.to_vec()) {
+                Ok(()) => true,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    tracing::warn!("Subscriber to {:?} records is behind; dropped a batch of {}", category, records.len());
+                    true
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => false,
+            });
+        }
+    }
+    
     /// Start the scheduler
     pub async fn start(&self) -> Result<(), AppError> {
         self.initialize_tasks().await?;
@@ -190,6 +217,7 @@ impl IngestionScheduler {
                 if !records.is_empty() {
                     let _ = self.storage.store_raw_data_batch(&records).await;
                 }
+                self.publish(&source.category, &records).await;
                 
                 // Update task as successful
                 {
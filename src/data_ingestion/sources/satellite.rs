@@ -823,10 +823,13 @@ impl SatelliteRadarCollector {
 
 #[async_trait]
 impl DataCollector for SatelliteRadarCollector {
-    async fn collect_data(&self, _source: &DataSource) -> Result<Vec<RawDataRecord>, AppError> {
-        // Implementation for satellite radar data collection
-        // This would handle Sentinel-1, RADARSAT, TerraSAR-X, etc.
-        Ok(vec![])
+    async fn collect_data(&self, source: &DataSource) -> Result<Vec<RawDataRecord>, AppError> {
+        // Only per-field backscatter statistics of calibrated GRD products are ingested
+        // here; whole-scene products are searched by the imaging collector
+        match (&source.data_format, &source.api_endpoint) {
+            (crate::data_ingestion::DataFormat::Json, Some(endpoint)) => self.collect_field_backscatter(source, endpoint).await,
+            _ => Ok(vec![]),
+        }
     }
 
     async fn validate_connection(&self, _source: &DataSource) -> Result<bool, AppError> {
@@ -846,6 +849,65 @@ impl DataCollector for SatelliteRadarCollector {
     }
 }
 
+impl SatelliteRadarCollector {
+    /// Field-averaged σ0 records, one per field and acquisition, each with an RFC 3339 `timestamp`
+    async fn collect_field_backscatter(&self, source: &DataSource, endpoint: &str) -> Result<Vec<RawDataRecord>, AppError> {
+        let response = self.http_client
+            .get(endpoint)
+            .send()
+            .await
+            .map_err(|e| AppError::external_service(&source.provider, &format!("Backscatter request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(AppError::external_service(&source.provider, "Backscatter request failed"));
+        }
+        let entries: Vec<serde_json::Value> = response.json().await
+            .map_err(|e| AppError::external_service(&source.provider, &format!("Failed to parse backscatter: {}", e)))?;
+
+        let mut units = HashMap::new();
+        units.insert("sigma0_vv_db".to_string(), "dB".to_string());
+        units.insert("sigma0_vh_db".to_string(), "dB".to_string());
+        units.insert("incidence_angle_deg".to_string(), "degrees".to_string());
+
+        entries
+            .into_iter()
+            .map(|entry| {
+                let timestamp = entry.get("timestamp")
+                    .and_then(|t| t.as_str())
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .ok_or_else(|| AppError::external_service(&source.provider, "Backscatter entry without a valid timestamp"))?
+                    .with_timezone(&Utc);
+                let coordinates = match (entry.get("latitude").and_then(|v| v.as_f64()), entry.get("longitude").and_then(|v| v.as_f64())) {
+                    (Some(latitude), Some(longitude)) => Some(Coordinates { latitude, longitude, coordinate_system: "WGS84".to_string() }),
+                    _ => None,
+                };
+                let mut parameters = HashMap::new();
+                parameters.insert("product".to_string(), "GRD".to_string());
+                if let Some(field_id) = entry.get("field_id").and_then(|v| v.as_str()) {
+                    parameters.insert("field_id".to_string(), field_id.to_string());
+                }
+                Ok(RawDataRecord {
+                    id: Uuid::new_v4(),
+                    source_id: source.id,
+                    timestamp,
+                    ingestion_time: Utc::now(),
+                    data: entry,
+                    metadata: DataMetadata {
+                        parameters,
+                        units: units.clone(),
+                        coordinates,
+                        elevation: None,
+                        instrument_info: Some(source.name.clone()),
+                        processing_level: Some("L1".to_string()),
+                        version: None,
+                    },
+                    quality_flags: vec![],
+                    file_path: None,
+                })
+            })
+            .collect()
+    }
+}
+
 /// Satellite LiDAR data collector
 pub struct SatelliteLidarCollector {
     config: Arc<Config>,
@@ -1,8 +1,11 @@
 use anyhow::Result;
 use axum::{
-    extract::{Path, Query, State},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
     http::{HeaderValue, Method, StatusCode},
-    response::Json,
+    response::{IntoResponse, Json, Response},
     routing::{get, post},
     Router,
 };
@@ -27,9 +30,11 @@ mod api;
 mod database;
 mod error;
 mod data_ingestion;
+mod data_fusion;
 mod signal;
 mod environmental_intelligence;
 mod atmospheric_energy;
+mod reconstruction;
 
 use config::Config;
 use weather::WeatherEngine;
@@ -52,6 +57,12 @@ pub struct AppState {
     pub data_ingestion: Arc<DataIngestionEngine>,
     pub environmental_intelligence: Arc<tokio::sync::RwLock<EnvironmentalIntelligenceSystem>>,
     pub atmospheric_energy: Arc<AtmosphericEnergySystem>,
+    pub data_fusion: Arc<data_fusion::DataFusionEngine>,
+    pub sensor_faults: Arc<tokio::sync::RwLock<data_fusion::SensorFaultMonitor>>,
+    pub reconstruction: Arc<reconstruction::ContinuousReconstructionStream>,
+    pub strip_database: Arc<tokio::sync::RwLock<reconstruction::SatelliteStripDatabase>>,
+    pub calibrations: Arc<tokio::sync::RwLock<reconstruction::CalibrationRegistry>>,
+    pub clear_sky: Arc<tokio::sync::RwLock<data_fusion::ClearObservationForecaster>>,
 }
 
 /// Health check response
@@ -449,6 +460,340 @@ async fn predict_energy_coordination(
     Ok(Json(predictions))
 }
 
+/// Output format for irrigation plans
+#[derive(Deserialize)]
+struct PlanFormatQuery {
+    format: Option<String>,
+}
+
+/// Optimise weekly irrigation and N-P-K plans for a field
+async fn plan_irrigation_and_fertiliser(
+    Query(params): Query<PlanFormatQuery>,
+    Json(request): Json<data_fusion::IrrigationPlanRequest>,
+) -> Result<Response, AppError> {
+    let mut optimizer = data_fusion::BayesianOptimizer::new_agricultural_multi_objective().await?;
+    if let Some(risk_aversion) = request.risk_aversion {
+        optimizer.set_risk_aversion(risk_aversion);
+    }
+
+    // The Pareto search is CPU bound, keep it off the async executor
+    let plan_set = tokio::task::spawn_blocking(move || {
+        data_fusion::IrrigationFertiliserPlanner::new().plan(&optimizer, &request)
+    })
+    .await
+    .map_err(|e| AppError::internal(format!("Irrigation planner task failed: {}", e)))??;
+
+    match params.format.as_deref() {
+        Some("csv") => Ok((
+            [(axum::http::header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+            plan_set.to_csv(),
+        )
+            .into_response()),
+        _ => Ok(Json(plan_set).into_response()),
+    }
+}
+
+/// A field and the money available for acquisitions over it this week
+#[derive(Deserialize)]
+struct AcquisitionPlanRequest {
+    field_id: String,
+    bounds: data_ingestion::BoundingBox,
+    budget: f64,
+    /// Sensors that can be tasked for this field; every type if omitted
+    sensors: Option<Vec<data_fusion::SensorType>>,
+    /// Forecast cloud fraction over the field as (valid time, fraction)
+    #[serde(default)]
+    cloud_forecast: Vec<(chrono::DateTime<chrono::Utc>, f64)>,
+}
+
+#[derive(Serialize)]
+struct AcquisitionPlanResponse {
+    /// Next usable optical image, when overpasses can be forecast
+    clear_observation: Option<data_fusion::NextClearObservation>,
+    acquisitions: Vec<data_fusion::TaskingChoice>,
+    total_cost: f64,
+    information_gain_nats: f64,
+}
+
+/// Choose which acquisitions to buy for a field, counting optical imagery only
+/// as far as a cloud-free overpass is expected
+async fn plan_acquisitions(
+    State(state): State<AppState>,
+    Json(request): Json<AcquisitionPlanRequest>,
+) -> Result<Json<AcquisitionPlanResponse>, AppError> {
+    let mut selector = data_fusion::InformationTheoreticSelector::new(request.budget);
+    if let Some(sensors) = request.sensors {
+        selector = selector.with_available_sensors(sensors);
+    }
+
+    let now = chrono::Utc::now();
+    let clear_observation = {
+        let mut forecaster = state.clear_sky.write().await;
+        if forecaster.platforms.is_empty() {
+            None
+        } else {
+            if !forecaster.fields().any(|field| field.field_id == request.field_id) {
+                forecaster.register_field(data_fusion::FieldRegistration::new(request.field_id.clone(), request.bounds))?;
+            }
+            if !request.cloud_forecast.is_empty() {
+                forecaster.update_cloud_forecast(
+                    &request.field_id,
+                    data_fusion::CloudCoverForecast { issued_at: now, samples: request.cloud_forecast },
+                )?;
+            }
+            Some(forecaster.apply_to_tasking(&request.field_id, now, &mut selector)?)
+        }
+    };
+
+    let plan = selector.plan(&data_fusion::BeliefState::agricultural_prior())?;
+    Ok(Json(AcquisitionPlanResponse {
+        clear_observation,
+        acquisitions: plan.selected,
+        total_cost: plan.total_cost,
+        information_gain_nats: plan.information_gain_nats,
+    }))
+}
+
+/// Sensor health filter
+#[derive(Deserialize)]
+struct SensorHealthQuery {
+    /// "excluded" lists only quarantined sensors and those on probation
+    status: Option<String>,
+}
+
+/// Quarantine status of every sensor seen by the fusion engine
+async fn get_sensor_health(
+    Query(params): Query<SensorHealthQuery>,
+    State(state): State<AppState>,
+) -> Json<data_fusion::SensorHealthReport> {
+    let mut report = state.sensor_faults.read().await.report(chrono::Utc::now());
+    if params.status.as_deref() == Some("excluded") {
+        report.sensors.retain(|sensor| sensor.status.is_excluded());
+    }
+    Json(report)
+}
+
+/// Test history and quarantine records for one sensor
+async fn get_sensor_fault_history(
+    Path(sensor_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<data_fusion::SensorHealth>, AppError> {
+    state
+        .sensor_faults
+        .read()
+        .await
+        .sensor(&sensor_id)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| AppError::not_found(format!("Sensor {} has not reported", sensor_id)))
+}
+
+#[derive(Deserialize)]
+struct SensorQuarantineRequest {
+    note: String,
+}
+
+/// Quarantine a sensor known to be faulty, e.g. after field inspection
+async fn quarantine_sensor(
+    Path(sensor_id): Path<String>,
+    State(state): State<AppState>,
+    Json(request): Json<SensorQuarantineRequest>,
+) -> Result<Json<data_fusion::SensorHealth>, AppError> {
+    let mut monitor = state.sensor_faults.write().await;
+    let health = monitor.quarantine(&sensor_id, request.note, chrono::Utc::now())?;
+    Ok(Json(health.clone()))
+}
+
+/// Put a repaired or re-sited sensor on probation without waiting out the quarantine
+async fn release_sensor(
+    Path(sensor_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<data_fusion::SensorHealth>, AppError> {
+    let mut monitor = state.sensor_faults.write().await;
+    let health = monitor.release(&sensor_id, chrono::Utc::now())?;
+    Ok(Json(health.clone()))
+}
+
+/// Score a past fusion cycle, and the algorithms run in shadow for it, against ground truth
+///
+/// The body maps quantities keyed like `FusedState::verifiable_quantities`
+/// (e.g. `temperature`, `soil_moisture`) to reference-station or lysimeter readings.
+async fn verify_fusion_decision(
+    Path(decision_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    Json(truth): Json<HashMap<String, f64>>,
+) -> Result<Json<Vec<data_fusion::VerificationRecord>>, AppError> {
+    let records = state.data_fusion.verify_fusion_decision(decision_id, &truth).await?;
+    info!("Verified {} algorithm estimates for fusion cycle {}", records.len(), decision_id);
+    Ok(Json(records))
+}
+
+/// Every registered calibration version of an instrument, oldest first
+async fn get_calibrations(
+    Path(instrument_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<reconstruction::CalibrationRecord>>, AppError> {
+    let registry = state.calibrations.read().await;
+    let versions = registry.versions(&instrument_id);
+    if versions.is_empty() {
+        return Err(AppError::not_found(format!("Instrument {} has no calibrations", instrument_id)));
+    }
+    Ok(Json(versions.to_vec()))
+}
+
+/// Register the next calibration version of an instrument
+async fn register_calibration(
+    Path(instrument_id): Path<String>,
+    State(state): State<AppState>,
+    Json(submission): Json<reconstruction::CalibrationSubmission>,
+) -> Result<Json<reconstruction::CalibrationRecord>, AppError> {
+    let mut registry = state.calibrations.write().await;
+    let record = registry.register(&instrument_id, submission)?;
+    info!("Registered calibration {}", record.label());
+    Ok(Json(record.clone()))
+}
+
+/// Seconds an acquisition may lie from a reconstruction's expected time to be verified against it
+const STRIP_VERIFICATION_WINDOW_S: f64 = 1800.0;
+
+/// Archive outcome of an acquired strip, scored against its reconstruction when the stream made one
+#[derive(Serialize)]
+struct StripArchiveResponse {
+    strip_id: String,
+    archived_strips: usize,
+    reconstruction_id: Option<String>,
+    verification: Option<reconstruction::FidelityMetrics>,
+}
+
+/// Archive a strip as it is acquired, so that later reconstructions can use it as an analog,
+/// and verify the reconstruction of it
+async fn archive_acquired_strip(
+    State(state): State<AppState>,
+    Json(acquisition): Json<reconstruction::ArchivedStrip>,
+) -> Result<Json<StripArchiveResponse>, AppError> {
+    let strip_id = acquisition.strip.strip_id.clone();
+    let reconstruction = state.reconstruction.reconstruction_of(&acquisition.strip, STRIP_VERIFICATION_WINDOW_S).await;
+    let reconstruction_id = reconstruction.as_ref().map(|r| r.expected_strip.strip_id.clone());
+    let database = state.strip_database.clone();
+    // Appending to the archive file is blocking I/O
+    let (archived_strips, verification) = tokio::task::spawn_blocking(move || {
+        let mut database = database.blocking_write();
+        let verification = match &reconstruction {
+            Some(result) => match database.verify_strip_reconstruction(result, &acquisition.strip) {
+                Ok(metrics) => Some(metrics),
+                Err(e) => {
+                    tracing::warn!("Cannot verify the reconstruction of strip {}: {}", acquisition.strip.strip_id, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        database.archive_strip(acquisition.strip, acquisition.metadata)?;
+        Ok::<_, AppError>((database.strip_count(), verification))
+    })
+    .await
+    .map_err(|e| AppError::internal(format!("Strip archiving failed: {}", e)))??;
+    if let Some(metrics) = &verification {
+        info!(
+            "Strip {} reconstructed with MSE {:.5} and skill {:.3} over persistence",
+            strip_id,
+            metrics.pixel_level_metrics.mean_squared_error,
+            metrics.temporal_consistency_metrics.temporal_interpolation_quality
+        );
+    }
+    info!("Archived strip {} ({} strips in the archive)", strip_id, archived_strips);
+    Ok(Json(StripArchiveResponse { strip_id, archived_strips, reconstruction_id, verification }))
+}
+
+/// Tracked target satellites and stream statistics
+#[derive(Serialize)]
+struct ReconstructionSatellitesResponse {
+    stream: reconstruction::StreamMetadata,
+    satellites: Vec<reconstruction::TrackedSatellite>,
+}
+
+async fn get_reconstruction_satellites(State(state): State<AppState>) -> Json<ReconstructionSatellitesResponse> {
+    Json(ReconstructionSatellitesResponse {
+        stream: state.reconstruction.metadata().await,
+        satellites: state.reconstruction.tracked_satellites().await,
+    })
+}
+
+/// Most recent unified prediction for one satellite
+async fn get_latest_reconstruction(
+    Path(satellite_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<reconstruction::ReconstructionReport>, AppError> {
+    if let Some(update) = state.reconstruction.latest(&satellite_id).await {
+        return Ok(Json(reconstruction::ReconstructionReport::from(&update)));
+    }
+    let tracked = state
+        .reconstruction
+        .tracked_satellites()
+        .await
+        .iter()
+        .any(|satellite| satellite.satellite_id == satellite_id);
+    if tracked {
+        Err(AppError::not_found(format!("Satellite {} has no reconstruction yet", satellite_id)))
+    } else {
+        Err(AppError::not_found(format!("Satellite {} is not tracked", satellite_id)))
+    }
+}
+
+#[derive(Deserialize)]
+struct ReconstructionSubscribeQuery {
+    satellite_id: Option<String>,
+}
+
+/// Push new predictions over a WebSocket, optionally for one satellite only
+async fn subscribe_reconstructions(
+    ws: WebSocketUpgrade,
+    Query(params): Query<ReconstructionSubscribeQuery>,
+    State(state): State<AppState>,
+) -> Response {
+    let updates = state.reconstruction.subscribe();
+    ws.on_upgrade(move |socket| stream_reconstructions(socket, updates, params.satellite_id))
+}
+
+async fn stream_reconstructions(
+    mut socket: WebSocket,
+    mut updates: tokio::sync::broadcast::Receiver<reconstruction::ReconstructionUpdate>,
+    satellite_id: Option<String>,
+) {
+    use tokio::sync::broadcast::error::RecvError;
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(update) => {
+                    if satellite_id.as_ref().is_some_and(|id| *id != update.satellite_id) {
+                        continue;
+                    }
+                    let report = reconstruction::ReconstructionReport::from(&update);
+                    let text = match serde_json::to_string(&report) {
+                        Ok(text) => text,
+                        Err(e) => {
+                            tracing::error!("Cannot serialise reconstruction report: {}", e);
+                            continue;
+                        }
+                    };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Reconstruction subscriber fell behind, skipped {} results", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+        }
+    }
+}
+
 /// Create application router
 fn create_router(state: AppState) -> Router {
     Router::new()
@@ -462,6 +807,24 @@ fn create_router(state: AppState) -> Router {
         
         // Agricultural analytics endpoints
         .route("/api/v1/agriculture/risk-assessment/:lat/:lon", get(get_risk_assessment))
+        .route("/api/v1/agriculture/irrigation-plan", post(plan_irrigation_and_fertiliser))
+        .route("/api/v1/agriculture/acquisition-plan", post(plan_acquisitions))
+        
+        // Sensor fault detection and isolation
+        .route("/api/v1/fusion/sensors/health", get(get_sensor_health))
+        .route("/api/v1/fusion/sensors/:sensor_id", get(get_sensor_fault_history))
+        .route("/api/v1/fusion/sensors/:sensor_id/quarantine", post(quarantine_sensor))
+        .route("/api/v1/fusion/sensors/:sensor_id/release", post(release_sensor))
+        .route("/api/v1/fusion/decisions/:decision_id/verify", post(verify_fusion_decision))
+        
+        // Versioned instrument calibrations
+        .route("/api/v1/calibrations/:instrument_id", get(get_calibrations).post(register_calibration))
+        
+        // Continuous satellite weather reconstruction
+        .route("/api/v1/reconstruction/satellites", get(get_reconstruction_satellites))
+        .route("/api/v1/reconstruction/satellites/:satellite_id/latest", get(get_latest_reconstruction))
+        .route("/api/v1/reconstruction/subscribe", get(subscribe_reconstructions))
+        .route("/api/v1/reconstruction/strips", post(archive_acquired_strip))
         
         // Environmental Intelligence System endpoints
         .route("/api/v1/environmental/state", get(get_environmental_state))
@@ -499,6 +862,17 @@ fn create_router(state: AppState) -> Router {
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Offline tools that don't need the server environment
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("membership-curves") {
+        data_fusion::run_membership_curves_tool(&args[1..])?;
+        return Ok(());
+    }
+    if args.first().map(String::as_str) == Some("algorithm-regret") {
+        data_fusion::run_algorithm_regret_tool(&args[1..])?;
+        return Ok(());
+    }
+
     // Initialize tracing
     tracing_subscriber::registry()
         .with(
@@ -529,6 +903,18 @@ async fn main() -> Result<()> {
     // Initialize all known data sources
     data_ingestion.initialize_sources().await?;
     info!("Data sources initialized successfully");
+
+    // Optical overpass forecasts for acquisition planning, from the catalogue's imagers and a TLE file
+    let mut clear_sky = data_fusion::ClearObservationForecaster::new(Vec::new());
+    if let Some(path) = &config.tle_catalog_path {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Cannot read TLE catalogue {}: {}", path, e))?;
+        let tles = data_fusion::orbit_propagation::TwoLineElements::parse_catalog(&text)?;
+        let imagers = data_ingestion.sources_in_category(data_ingestion::DataSourceCategory::SatelliteImaging).await;
+        clear_sky.platforms = data_fusion::OpticalPlatform::from_catalog(&imagers, &tles)?;
+        info!("Forecasting clear overpasses for {} optical platforms", clear_sky.platforms.len());
+    }
+    let clear_sky = Arc::new(tokio::sync::RwLock::new(clear_sky));
     
     // Start continuous data ingestion
     let ingestion_engine = data_ingestion.clone();
@@ -548,6 +934,72 @@ async fn main() -> Result<()> {
     let atmospheric_energy_system = Arc::new(AtmosphericEnergySystem::new(config.clone(), db_pool.clone()).await?);
     info!("Atmospheric Energy System initialized successfully");
 
+    // Data fusion engine, sharing its sensor fault monitor with the quarantine API
+    let fusion_config = data_fusion::FusionConfig::from_app_config(&config);
+    let sensor_faults = Arc::new(tokio::sync::RwLock::new(data_fusion::SensorFaultMonitor::new(
+        data_fusion::FaultIsolationConfig::from_fusion_config(&fusion_config),
+    )));
+    // One calibration registry, replayed from and appended to the configured file, for the
+    // calibration API, the fusion engine and the strip archive, so a new version applies everywhere
+    let mut calibrations = reconstruction::CalibrationRegistry::new();
+    if let Some(path) = &fusion_config.calibration_registry_path {
+        let replayed = calibrations.attach_file(path)?;
+        info!("Replayed {} calibrations from {}", replayed, path.display());
+    }
+    let calibrations = Arc::new(tokio::sync::RwLock::new(calibrations));
+    let data_fusion_engine = Arc::new(
+        data_fusion::DataFusionEngine::new(fusion_config)
+            .await?
+            .with_fault_monitor(sensor_faults.clone())
+            .with_calibration_registry(calibrations.clone()),
+    );
+    info!("Data fusion engine initialized successfully");
+
+    // Radar field statistics from the ingestion engine become soil moisture evidence
+    let mut radar_records = data_ingestion
+        .subscribe_records(data_ingestion::DataSourceCategory::SatelliteRadar, 16)
+        .await;
+    let radar_fusion = data_fusion_engine.clone();
+    tokio::spawn(async move {
+        while let Some(records) = radar_records.recv().await {
+            match radar_fusion.fuse_radar_records(&records).await {
+                Ok(Some(result)) => info!("Fused radar soil moisture from {} records with {:?}", records.len(), result.algorithm_used),
+                Ok(None) => {}
+                Err(e) => tracing::error!("Radar soil moisture fusion failed: {}", e),
+            }
+        }
+    });
+
+    // Archive of acquired strips, shared by the API and every reconstruction task
+    let mut strip_database =
+        reconstruction::SatelliteStripDatabase::new().with_calibration_registry(calibrations.clone());
+    if let Some(path) = &config.strip_archive_path {
+        let replayed = strip_database.attach_archive(std::path::Path::new(path))?;
+        info!("Replayed {} archived strips from {}", replayed, path);
+    }
+    let strip_database = Arc::new(tokio::sync::RwLock::new(strip_database));
+
+    // Continuous reconstruction of the configured target satellites
+    let target_satellites: Vec<reconstruction::TargetSatellite> = config
+        .reconstruction_satellites
+        .iter()
+        .map(|satellite_id| reconstruction::TargetSatellite {
+            satellite_id: satellite_id.clone(),
+            ..reconstruction::TargetSatellite::default()
+        })
+        .collect();
+    let (reconstruction_stream, reconstruction_control) = reconstruction::UnifiedSatelliteWeatherReconstructionSystem::new()
+        .with_strip_database(strip_database.clone())
+        .continuous_satellite_weather_reconstruction(
+            &target_satellites,
+            reconstruction::ReconstructionStreamConfig {
+                update_interval: std::time::Duration::from_secs(config.reconstruction_interval_seconds),
+                prediction_horizon_hours: config.reconstruction_horizon_hours,
+                ..reconstruction::ReconstructionStreamConfig::default()
+            },
+        );
+    info!("Reconstructing {} target satellites", target_satellites.len());
+
     info!("Core engines initialized successfully");
 
     // Create application state
@@ -560,6 +1012,12 @@ async fn main() -> Result<()> {
         data_ingestion,
         environmental_intelligence,
         atmospheric_energy: atmospheric_energy_system,
+        data_fusion: data_fusion_engine,
+        sensor_faults,
+        reconstruction: Arc::new(reconstruction_stream),
+        strip_database,
+        calibrations,
+        clear_sky,
     };
 
     // Build application router
@@ -575,7 +1033,41 @@ async fn main() -> Result<()> {
     info!("Data ingestion status at http://{}/api/v1/ingestion/status", addr);
     info!("API documentation available at http://{}/docs", addr);
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    // Handlers may still hold the stream, but its reconstruction tasks stop and are joined here
+    reconstruction_control.shutdown().await;
 
     Ok(())
-} 
\ No newline at end of file
+} 
+
+/// Resolves on Ctrl+C or, on Unix, SIGTERM from a service manager or container runtime
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Cannot listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Cannot listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Ctrl+C received, shutting down"),
+        _ = terminate => info!("SIGTERM received, shutting down"),
+    }
+}
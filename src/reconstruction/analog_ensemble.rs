@@ -0,0 +1,867 @@
+//! Analog-ensemble strip reconstruction and reconstruction verification
+//!
+//! An expected strip is reconstructed as the weighted mean of the historical
+//! strips whose weather state is closest to the one expected at acquisition,
+//! each reprojected from its own footprint onto the expected coverage area.
+//! When the real strip arrives, [`assess_reconstruction_fidelity`] scores the
+//! reconstruction and its skill against persistence of the previous image.
+
+use std::collections::HashMap;
+use std::f64::consts::FRAC_PI_2;
+use std::time::Instant;
+
+use super::{
+    ContributingEvidence, FeatureLevelMetrics, FidelityMetrics, GeoBounds, ImageData, ImageMetadata,
+    ImageReconstructionModel, PixelLevelMetrics, ReconstructedStrip, SatelliteStrip, SatelliteStripDatabase,
+    SemanticLevelMetrics, StripParameters, TemporalConsistencyMetrics, WeatherSnapshot,
+};
+use super::toa_synthesis::sentinel2_common_name;
+use crate::error::AppError;
+
+const SSIM_WINDOW: usize = 7;
+const SSIM_C1: f64 = 0.01 * 0.01;
+const SSIM_C2: f64 = 0.03 * 0.03;
+const MAX_PSNR_DB: f64 = 100.0;
+const CHANGE_THRESHOLD: f64 = 0.05;
+const CLOUD_REFLECTANCE: f64 = 0.3;
+/// Recency e-folding time when averaging recent weather observations
+const WEATHER_RECENCY_S: f64 = 12.0 * 3600.0;
+
+/// Reconstructs a strip from the `analog_count` historical strips nearest in weather state
+#[derive(Debug, Clone)]
+pub struct AnalogEnsembleReconstructionModel {
+    pub analog_count: usize,
+    /// Per-variable scales of the weather-state distance (wind direction in degrees)
+    pub weather_scales: WeatherSnapshot,
+    /// Added to weather distances so an exact match does not take all the weight
+    pub distance_floor: f64,
+    /// Ensemble spread in reflectance units at which confidence halves
+    pub spread_reference: f64,
+}
+
+impl Default for AnalogEnsembleReconstructionModel {
+    fn default() -> Self {
+        Self {
+            analog_count: 5,
+            weather_scales: WeatherSnapshot {
+                temperature: 5.0,
+                humidity: 15.0,
+                pressure: 10.0,
+                wind_speed: 5.0,
+                wind_direction: 90.0,
+                cloud_cover: 0.25,
+                precipitation: 2.0,
+                visibility: 10_000.0,
+            },
+            distance_floor: 0.1,
+            spread_reference: 0.05,
+        }
+    }
+}
+
+/// Weather state expected at acquisition; variables without evidence are `None`
+#[derive(Debug, Clone, Default)]
+struct WeatherState {
+    values: [Option<f64>; 8],
+}
+
+fn snapshot_values(snapshot: &WeatherSnapshot) -> [f64; 8] {
+    [
+        snapshot.temperature,
+        snapshot.humidity,
+        snapshot.pressure,
+        snapshot.wind_speed,
+        snapshot.wind_direction,
+        snapshot.cloud_cover,
+        snapshot.precipitation,
+        snapshot.visibility,
+    ]
+}
+
+const WEATHER_KEYS: [&str; 8] =
+    ["temperature", "humidity", "pressure", "wind_speed", "wind_direction", "cloud_cover", "precipitation", "visibility"];
+const WIND_DIRECTION: usize = 4;
+const CLOUD_COVER: usize = 5;
+const VISIBILITY: usize = 7;
+
+impl AnalogEnsembleReconstructionModel {
+    pub const MODEL_NAME: &'static str = "analog_ensemble";
+
+    /// Recency- and quality-weighted weather of the evidence, with the cloud and
+    /// visibility expected at acquisition taking precedence
+    fn expected_weather(evidence: &ContributingEvidence, parameters: &StripParameters) -> WeatherState {
+        let latest = evidence.weather_data.iter().map(|p| p.timestamp).fold(f64::NEG_INFINITY, f64::max);
+        let mut sums = [0.0; 8];
+        let mut weights = [0.0; 8];
+        // Wind direction is averaged as a unit vector
+        let (mut east, mut north) = (0.0, 0.0);
+        for point in &evidence.weather_data {
+            let weight = point.quality.max(0.0) * (-(latest - point.timestamp).max(0.0) / WEATHER_RECENCY_S).exp();
+            for (k, key) in WEATHER_KEYS.iter().enumerate() {
+                let Some(value) = point.parameters.get(*key).copied().filter(|v| v.is_finite()) else {
+                    continue;
+                };
+                if k == WIND_DIRECTION {
+                    east += weight * value.to_radians().sin();
+                    north += weight * value.to_radians().cos();
+                } else {
+                    sums[k] += weight * value;
+                }
+                weights[k] += weight;
+            }
+        }
+        let mut state = WeatherState::default();
+        for k in 0..WEATHER_KEYS.len() {
+            if weights[k] > 0.0 {
+                state.values[k] = Some(if k == WIND_DIRECTION {
+                    east.atan2(north).to_degrees().rem_euclid(360.0)
+                } else {
+                    sums[k] / weights[k]
+                });
+            }
+        }
+        let conditions = &parameters.atmospheric_conditions;
+        if conditions.cloud_cover.is_finite() {
+            state.values[CLOUD_COVER] = Some(conditions.cloud_cover);
+        }
+        if conditions.visibility.is_finite() && conditions.visibility > 0.0 {
+            state.values[VISIBILITY] = Some(conditions.visibility);
+        }
+        state
+    }
+
+    /// Root-mean-square standardised difference over the variables the state defines
+    fn weather_distance(&self, state: &WeatherState, snapshot: &WeatherSnapshot) -> f64 {
+        let scales = snapshot_values(&self.weather_scales);
+        let values = snapshot_values(snapshot);
+        let mut sum = 0.0;
+        let mut count = 0;
+        for k in 0..WEATHER_KEYS.len() {
+            let (Some(expected), true) = (state.values[k], values[k].is_finite()) else {
+                continue;
+            };
+            let mut delta = values[k] - expected;
+            if k == WIND_DIRECTION {
+                delta = (delta + 180.0).rem_euclid(360.0) - 180.0;
+            }
+            sum += (delta / scales[k].abs().max(f64::EPSILON)).powi(2);
+            count += 1;
+        }
+        if count == 0 {
+            0.0
+        } else {
+            (sum / count as f64).sqrt()
+        }
+    }
+
+    fn confidence(&self, mean_distance: f64, spread: f64) -> f64 {
+        (-mean_distance).exp() / (1.0 + spread / self.spread_reference.max(f64::EPSILON))
+    }
+}
+
+impl ImageReconstructionModel for AnalogEnsembleReconstructionModel {
+    fn reconstruct_strip(&self, evidence: &ContributingEvidence, parameters: &StripParameters) -> ReconstructedStrip {
+        let started = Instant::now();
+        let state = Self::expected_weather(evidence, parameters);
+        let mut analogs: Vec<(&SatelliteStrip, GeoBounds, f64)> = evidence
+            .historical_strips
+            .iter()
+            .filter(|strip| strip.image_data.width > 0 && strip.image_data.height > 0 && !strip.image_data.data.is_empty())
+            .filter_map(|strip| {
+                let footprint = SatelliteStripDatabase::strip_footprint(strip)?;
+                Some((strip, footprint, self.weather_distance(&state, &strip.weather_conditions)))
+            })
+            .collect();
+        analogs.sort_by(|a, b| a.2.total_cmp(&b.2));
+        analogs.truncate(self.analog_count.max(1));
+
+        let Some((template, _, _)) = analogs.first() else {
+            return ReconstructedStrip {
+                image_data: ImageData { width: 0, height: 0, channels: 0, data: Vec::new(), metadata: ImageMetadata::default() },
+                confidence: 0.0,
+                reconstruction_method: Self::MODEL_NAME.to_string(),
+                processing_time: started.elapsed(),
+                quality_metrics: HashMap::from([("analog_count".to_string(), 0.0)]),
+            };
+        };
+        let bands = if parameters.spectral_bands.is_empty() {
+            template.image_data.metadata.spectral_bands.clone()
+        } else {
+            parameters.spectral_bands.clone()
+        };
+        // The closest analog sets the pixel count; every analog is laid out on the expected
+        // coverage area, and pixels outside its own footprint take nothing from it
+        let (width, height) = (template.image_data.width, template.image_data.height);
+        let members: Vec<(BandStack, f64, f64)> = analogs
+            .iter()
+            .map(|(strip, footprint, distance)| {
+                // Cloudy analogs show less of the surface and count for less
+                let clear = (1.0 - strip.quality_metrics.cloud_contamination).clamp(0.05, 1.0);
+                let weight = clear / (distance.powi(2) + self.distance_floor.powi(2));
+                let reprojected = reproject_image(&strip.image_data, footprint, &evidence.coverage_area, width, height);
+                (BandStack::from_image(&reprojected, &bands, width, height), weight, *distance)
+            })
+            .collect();
+        let channels = members.iter().map(|(stack, _, _)| stack.bands.len()).max().unwrap_or(0);
+
+        let pixels = width * height;
+        let mut data = vec![f64::NAN; pixels * channels];
+        let mut spread_sum = 0.0;
+        let mut spread_count = 0usize;
+        for c in 0..channels {
+            for p in 0..pixels {
+                let (mut sum, mut sum_sq, mut weight_sum) = (0.0, 0.0, 0.0);
+                for (stack, weight, _) in &members {
+                    if let Some(value) = stack.bands.get(c).and_then(|band| band.as_ref()).map(|band| band[p]) {
+                        if value.is_finite() {
+                            sum += weight * value;
+                            sum_sq += weight * value * value;
+                            weight_sum += weight;
+                        }
+                    }
+                }
+                if weight_sum > 0.0 {
+                    let mean = sum / weight_sum;
+                    data[p * channels + c] = mean;
+                    spread_sum += (sum_sq / weight_sum - mean * mean).max(0.0).sqrt();
+                    spread_count += 1;
+                }
+            }
+        }
+
+        let total_weight: f64 = members.iter().map(|(_, w, _)| w).sum();
+        let mean_distance = members.iter().map(|(_, w, d)| w * d).sum::<f64>() / total_weight;
+        let effective_members = total_weight.powi(2) / members.iter().map(|(_, w, _)| w * w).sum::<f64>();
+        let spread = if spread_count > 0 { spread_sum / spread_count as f64 } else { 0.0 };
+        let cloud_coverage = analogs
+            .iter()
+            .zip(&members)
+            .map(|((strip, _, _), (_, w, _))| w * strip.image_data.metadata.cloud_coverage)
+            .sum::<f64>()
+            / total_weight;
+
+        ReconstructedStrip {
+            image_data: ImageData {
+                width,
+                height,
+                channels,
+                data,
+                metadata: ImageMetadata {
+                    spectral_bands: bands,
+                    spatial_resolution: parameters.spatial_resolution,
+                    cloud_coverage,
+                    ..template.image_data.metadata.clone()
+                },
+            },
+            confidence: self.confidence(mean_distance, spread),
+            reconstruction_method: Self::MODEL_NAME.to_string(),
+            processing_time: started.elapsed(),
+            quality_metrics: HashMap::from([
+                ("analog_count".to_string(), members.len() as f64),
+                ("effective_members".to_string(), effective_members),
+                ("mean_weather_distance".to_string(), mean_distance),
+                ("ensemble_spread".to_string(), spread),
+            ]),
+        }
+    }
+
+    fn get_model_name(&self) -> String {
+        Self::MODEL_NAME.to_string()
+    }
+
+    fn get_confidence_estimate(&self, reconstruction: &ReconstructedStrip) -> f64 {
+        let metrics = &reconstruction.quality_metrics;
+        match (metrics.get("mean_weather_distance"), metrics.get("ensemble_spread")) {
+            (Some(distance), Some(spread)) => self.confidence(*distance, *spread),
+            _ => reconstruction.confidence,
+        }
+    }
+}
+
+/// Planar bands resampled to a common grid; `None` for bands the source lacks
+struct BandStack {
+    bands: Vec<Option<Vec<f64>>>,
+}
+
+impl BandStack {
+    /// Select `bands` by name (by position when either side is unnamed) and resample by nearest neighbour
+    fn from_image(image: &ImageData, bands: &[String], width: usize, height: usize) -> Self {
+        let source_bands = &image.metadata.spectral_bands;
+        let selection: Vec<Option<usize>> = if bands.is_empty() || source_bands.is_empty() {
+            (0..image.channels).map(Some).collect()
+        } else {
+            bands
+                .iter()
+                .map(|band| source_bands.iter().position(|b| b.eq_ignore_ascii_case(band)).filter(|&c| c < image.channels))
+                .collect()
+        };
+        let valid = image.width > 0 && image.height > 0 && image.data.len() >= image.width * image.height * image.channels;
+        let bands = selection
+            .into_iter()
+            .map(|channel| {
+                let channel = channel.filter(|_| valid)?;
+                let mut band = Vec::with_capacity(width * height);
+                for row in 0..height {
+                    let source_row = ((row as f64 + 0.5) * image.height as f64 / height as f64) as usize;
+                    for col in 0..width {
+                        let source_col = ((col as f64 + 0.5) * image.width as f64 / width as f64) as usize;
+                        let index = source_row.min(image.height - 1) * image.width + source_col.min(image.width - 1);
+                        band.push(image.data[index * image.channels + channel]);
+                    }
+                }
+                Some(band)
+            })
+            .collect();
+        Self { bands }
+    }
+}
+
+/// Lay out an image that spans `footprint` north-up on a `width` x `height` grid over `area`
+///
+/// Nearest-neighbour; pixels of `area` outside the footprint are NaN. Bounds
+/// with `west > east` cross the antimeridian.
+pub fn reproject_image(image: &ImageData, footprint: &GeoBounds, area: &GeoBounds, width: usize, height: usize) -> ImageData {
+    let channels = image.channels;
+    let mut data = vec![f64::NAN; width * height * channels];
+    let valid = image.width > 0 && image.height > 0 && image.data.len() >= image.width * image.height * channels;
+    let (footprint_width, footprint_height) = (longitude_span(footprint), footprint.north - footprint.south);
+    if valid && footprint_width > 0.0 && footprint_height > 0.0 {
+        let area_width = longitude_span(area);
+        for row in 0..height {
+            let latitude = area.north - (row as f64 + 0.5) / height as f64 * (area.north - area.south);
+            let y = (footprint.north - latitude) / footprint_height;
+            if !(0.0..1.0).contains(&y) {
+                continue;
+            }
+            let source_row = ((y * image.height as f64) as usize).min(image.height - 1);
+            for col in 0..width {
+                let longitude = area.west + (col as f64 + 0.5) / width as f64 * area_width;
+                let x = (longitude - footprint.west).rem_euclid(360.0) / footprint_width;
+                if x >= 1.0 {
+                    continue;
+                }
+                let source_col = ((x * image.width as f64) as usize).min(image.width - 1);
+                let source = (source_row * image.width + source_col) * channels;
+                let target = (row * width + col) * channels;
+                data[target..target + channels].copy_from_slice(&image.data[source..source + channels]);
+            }
+        }
+    }
+    ImageData { width, height, channels, data, metadata: image.metadata.clone() }
+}
+
+fn longitude_span(bounds: &GeoBounds) -> f64 {
+    if bounds.west <= bounds.east {
+        bounds.east - bounds.west
+    } else {
+        bounds.east + 360.0 - bounds.west
+    }
+}
+
+/// Score a reconstruction against the image that was actually acquired
+///
+/// `previous` is the last real image of the area before the acquisition; it
+/// is the persistence forecast the temporal metrics are measured against, and
+/// without it those metrics are NaN. The actual and previous images are
+/// band-matched to the reconstruction and resampled to its grid. Reflectances
+/// are assumed to lie in [0, 1]; object detection is not assessed and is NaN.
+pub fn assess_reconstruction_fidelity(
+    reconstructed: &ImageData,
+    actual: &ImageData,
+    previous: Option<&ImageData>,
+) -> Result<FidelityMetrics, AppError> {
+    let (width, height) = (reconstructed.width, reconstructed.height);
+    if width == 0 || height == 0 || reconstructed.data.len() < width * height * reconstructed.channels {
+        return Err(AppError::validation("Reconstruction holds no image to verify"));
+    }
+    let bands = &reconstructed.metadata.spectral_bands;
+    let predicted = BandStack::from_image(reconstructed, bands, width, height);
+    let observed = BandStack::from_image(actual, bands, width, height);
+    let persisted = previous.map(|image| BandStack::from_image(image, bands, width, height));
+
+    let shared: Vec<usize> = (0..predicted.bands.len())
+        .filter(|&c| {
+            predicted.bands[c].is_some()
+                && observed.bands.get(c).is_some_and(|b| b.is_some())
+                && persisted.as_ref().is_none_or(|p| p.bands.get(c).is_some_and(|b| b.is_some()))
+        })
+        .collect();
+    if shared.is_empty() {
+        return Err(AppError::validation("Reconstructed and acquired images share no spectral band"));
+    }
+    let band = |stack: &BandStack, c: usize| -> Vec<f64> { stack.bands[c].clone().unwrap_or_default() };
+    let pairs: Vec<(Vec<f64>, Vec<f64>)> = shared.iter().map(|&c| (band(&predicted, c), band(&observed, c))).collect();
+
+    // Pixel level
+    let mse = mean(pairs.iter().flat_map(|(r, a)| r.iter().zip(a).map(|(r, a)| (r - a).powi(2))));
+    if mse.is_nan() {
+        return Err(AppError::validation("Reconstructed and acquired images share no valid pixel"));
+    }
+    let peak_signal_to_noise_ratio = if mse > 0.0 { (10.0 * (1.0 / mse).log10()).min(MAX_PSNR_DB) } else { MAX_PSNR_DB };
+    let windows: Vec<WindowStatistics> = pairs.iter().map(|(r, a)| WindowStatistics::new(r, a, width, height)).collect();
+    let structural_similarity_index = mean(windows.iter().flat_map(|w| w.ssim.iter().copied()));
+    let spectral_angle_mapper = mean((0..width * height).filter_map(|p| {
+        let (mut dot, mut norm_r, mut norm_a) = (0.0, 0.0, 0.0);
+        for (r, a) in &pairs {
+            if !(r[p].is_finite() && a[p].is_finite()) {
+                return None;
+            }
+            dot += r[p] * a[p];
+            norm_r += r[p] * r[p];
+            norm_a += a[p] * a[p];
+        }
+        let norms = (norm_r * norm_a).sqrt();
+        (norms > 0.0).then(|| (dot / norms).clamp(-1.0, 1.0).acos())
+    }));
+
+    // Feature level
+    let edge_preservation = mean(
+        pairs.iter().map(|(r, a)| correlation(&gradient_magnitude(r, width, height), &gradient_magnitude(a, width, height))),
+    );
+    let texture_similarity = mean(windows.iter().map(|w| correlation(&w.std_reconstructed, &w.std_actual)));
+    let contrast_preservation = mean(pairs.iter().map(|(r, a)| {
+        let (sr, sa) = (std_dev(r), std_dev(a));
+        if sr.max(sa) > 0.0 {
+            sr.min(sa) / sr.max(sa)
+        } else {
+            1.0
+        }
+    }));
+    let color_fidelity = (1.0 - spectral_angle_mapper / FRAC_PI_2).clamp(0.0, 1.0);
+
+    // Semantic level, from NDVI classes and a brightness cloud mask
+    // Sentinel-2 band names count as their generic equivalents ("B04" as "R")
+    let names: Vec<&str> = shared
+        .iter()
+        .map(|&c| bands.get(c).map(String::as_str).unwrap_or(""))
+        .map(|n| sentinel2_common_name(n).unwrap_or(n))
+        .collect();
+    let classes = |stack: &[&Vec<f64>]| -> (Vec<Option<u8>>, Vec<Option<bool>>) {
+        let red = names.iter().position(|n| n.eq_ignore_ascii_case("r") || n.eq_ignore_ascii_case("red"));
+        let nir = names.iter().position(|n| n.eq_ignore_ascii_case("nir"));
+        let visible: Vec<usize> = names
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| ["r", "g", "b", "red", "green", "blue"].iter().any(|v| n.eq_ignore_ascii_case(v)))
+            .map(|(k, _)| k)
+            .collect();
+        let visible = if visible.is_empty() { (0..stack.len()).collect() } else { visible };
+        let land_use = (0..width * height)
+            .map(|p| {
+                let (red, nir) = (stack[red?][p], stack[nir?][p]);
+                let ndvi = (nir - red) / (nir + red);
+                ndvi.is_finite().then_some(match ndvi {
+                    v if v < 0.0 => 0,
+                    v if v < 0.2 => 1,
+                    v if v < 0.5 => 2,
+                    _ => 3,
+                })
+            })
+            .collect();
+        let clouds = (0..width * height)
+            .map(|p| {
+                let brightness = visible.iter().map(|&k| stack[k][p]).sum::<f64>() / visible.len() as f64;
+                brightness.is_finite().then_some(brightness > CLOUD_REFLECTANCE)
+            })
+            .collect();
+        (land_use, clouds)
+    };
+    let (land_r, cloud_r) = classes(&pairs.iter().map(|(r, _)| r).collect::<Vec<_>>());
+    let (land_a, cloud_a) = classes(&pairs.iter().map(|(_, a)| a).collect::<Vec<_>>());
+    let land_use_classification_accuracy = agreement(&land_r, &land_a);
+    let weather_pattern_recognition_accuracy = agreement(&cloud_r, &cloud_a);
+    // A pixel's scene class is cloud, or its land-use class when clear
+    let scene = |land: &[Option<u8>], cloud: &[Option<bool>]| -> Vec<Option<u8>> {
+        land.iter().zip(cloud).map(|(l, c)| if (*c)? { Some(u8::MAX) } else { *l }).collect()
+    };
+    let scene_classification_accuracy = agreement(&scene(&land_r, &cloud_r), &scene(&land_a, &cloud_a));
+
+    // Temporal consistency against persistence of the previous image
+    let temporal_consistency_metrics = match &persisted {
+        Some(persisted) => {
+            let triples: Vec<(&Vec<f64>, &Vec<f64>, Vec<f64>)> =
+                shared.iter().zip(&pairs).map(|(&c, (r, a))| (r, a, band(persisted, c))).collect();
+            let predicted_change: Vec<f64> =
+                triples.iter().flat_map(|(r, _, p)| r.iter().zip(p).map(|(r, p)| r - p)).collect();
+            let actual_change: Vec<f64> =
+                triples.iter().flat_map(|(_, a, p)| a.iter().zip(p).map(|(a, p)| a - p)).collect();
+            let changed = |change: &[f64]| -> Vec<Option<bool>> {
+                (0..width * height)
+                    .map(|p| {
+                        let largest = (0..triples.len()).map(|k| change[k * width * height + p].abs()).fold(0.0, f64::max);
+                        largest.is_finite().then_some(largest > CHANGE_THRESHOLD)
+                    })
+                    .collect()
+            };
+            let (mean_predicted, mean_actual) =
+                (mean(predicted_change.iter().map(|d| d.abs())), mean(actual_change.iter().map(|d| d.abs())));
+            let persistence_mse = mean(actual_change.iter().map(|d| d * d));
+            TemporalConsistencyMetrics {
+                temporal_stability: if mean_predicted.max(mean_actual) > 0.0 {
+                    mean_predicted.min(mean_actual) / mean_predicted.max(mean_actual)
+                } else {
+                    1.0
+                },
+                motion_coherence: correlation(&predicted_change, &actual_change),
+                change_detection_accuracy: agreement(&changed(&predicted_change), &changed(&actual_change)),
+                // Skill over persistence: positive only if reconstruction beats the last image
+                temporal_interpolation_quality: 1.0 - mse / persistence_mse.max(1e-12),
+            }
+        }
+        None => TemporalConsistencyMetrics {
+            temporal_stability: f64::NAN,
+            motion_coherence: f64::NAN,
+            change_detection_accuracy: f64::NAN,
+            temporal_interpolation_quality: f64::NAN,
+        },
+    };
+
+    Ok(FidelityMetrics {
+        pixel_level_metrics: PixelLevelMetrics {
+            mean_squared_error: mse,
+            peak_signal_to_noise_ratio,
+            structural_similarity_index,
+            spectral_angle_mapper,
+        },
+        feature_level_metrics: FeatureLevelMetrics {
+            edge_preservation,
+            texture_similarity,
+            contrast_preservation,
+            color_fidelity,
+        },
+        semantic_level_metrics: SemanticLevelMetrics {
+            object_detection_accuracy: f64::NAN,
+            scene_classification_accuracy,
+            land_use_classification_accuracy,
+            weather_pattern_recognition_accuracy,
+        },
+        temporal_consistency_metrics,
+    })
+}
+
+/// Sliding-window means, deviations and SSIM of two bands, from summed-area tables
+struct WindowStatistics {
+    ssim: Vec<f64>,
+    std_reconstructed: Vec<f64>,
+    std_actual: Vec<f64>,
+}
+
+impl WindowStatistics {
+    fn new(reconstructed: &[f64], actual: &[f64], width: usize, height: usize) -> Self {
+        let window = SSIM_WINDOW.min(width).min(height);
+        let stride = width + 1;
+        let mut tables = vec![vec![0.0; stride * (height + 1)]; 6];
+        for row in 0..height {
+            for col in 0..width {
+                let (x, y) = (reconstructed[row * width + col], actual[row * width + col]);
+                let terms = if x.is_finite() && y.is_finite() { [1.0, x, y, x * x, y * y, x * y] } else { [0.0; 6] };
+                for (table, term) in tables.iter_mut().zip(terms) {
+                    let i = (row + 1) * stride + col + 1;
+                    table[i] = term + table[i - 1] + table[i - stride] - table[i - stride - 1];
+                }
+            }
+        }
+        let mut statistics = Self { ssim: Vec::new(), std_reconstructed: Vec::new(), std_actual: Vec::new() };
+        for top in 0..=height - window {
+            for left in 0..=width - window {
+                let sums: Vec<f64> = tables
+                    .iter()
+                    .map(|t| {
+                        let (r0, c0, r1, c1) = (top, left, top + window, left + window);
+                        t[r1 * stride + c1] - t[r0 * stride + c1] - t[r1 * stride + c0] + t[r0 * stride + c0]
+                    })
+                    .collect();
+                let n = sums[0];
+                if n < 2.0 {
+                    continue;
+                }
+                let (mx, my) = (sums[1] / n, sums[2] / n);
+                let vx = (sums[3] / n - mx * mx).max(0.0);
+                let vy = (sums[4] / n - my * my).max(0.0);
+                let cov = sums[5] / n - mx * my;
+                statistics.ssim.push(
+                    ((2.0 * mx * my + SSIM_C1) * (2.0 * cov + SSIM_C2)) / ((mx * mx + my * my + SSIM_C1) * (vx + vy + SSIM_C2)),
+                );
+                statistics.std_reconstructed.push(vx.sqrt());
+                statistics.std_actual.push(vy.sqrt());
+            }
+        }
+        statistics
+    }
+}
+
+/// Sobel gradient magnitude of the interior pixels
+fn gradient_magnitude(band: &[f64], width: usize, height: usize) -> Vec<f64> {
+    let at = |row: usize, col: usize| band[row * width + col];
+    let mut magnitude = Vec::new();
+    for row in 1..height.saturating_sub(1) {
+        for col in 1..width.saturating_sub(1) {
+            let gx = at(row - 1, col + 1) + 2.0 * at(row, col + 1) + at(row + 1, col + 1)
+                - at(row - 1, col - 1)
+                - 2.0 * at(row, col - 1)
+                - at(row + 1, col - 1);
+            let gy = at(row + 1, col - 1) + 2.0 * at(row + 1, col) + at(row + 1, col + 1)
+                - at(row - 1, col - 1)
+                - 2.0 * at(row - 1, col)
+                - at(row - 1, col + 1);
+            magnitude.push(gx.hypot(gy));
+        }
+    }
+    magnitude
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.filter(|v| v.is_finite()).fold((0.0, 0usize), |(s, n), v| (s + v, n + 1));
+    if count == 0 {
+        f64::NAN
+    } else {
+        sum / count as f64
+    }
+}
+
+fn std_dev(values: &[f64]) -> f64 {
+    let m = mean(values.iter().copied());
+    mean(values.iter().map(|v| (v - m).powi(2))).sqrt()
+}
+
+/// Pearson correlation over pairs where both are finite; 1 for two constant fields, 0 for one
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let pairs: Vec<(f64, f64)> = a.iter().zip(b).map(|(x, y)| (*x, *y)).filter(|(x, y)| x.is_finite() && y.is_finite()).collect();
+    if pairs.is_empty() {
+        return f64::NAN;
+    }
+    let n = pairs.len() as f64;
+    let (mx, my) = (pairs.iter().map(|p| p.0).sum::<f64>() / n, pairs.iter().map(|p| p.1).sum::<f64>() / n);
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for (x, y) in &pairs {
+        sxy += (x - mx) * (y - my);
+        sxx += (x - mx).powi(2);
+        syy += (y - my).powi(2);
+    }
+    match (sxx > 1e-18, syy > 1e-18) {
+        (true, true) => sxy / (sxx * syy).sqrt(),
+        (false, false) => 1.0,
+        _ => 0.0,
+    }
+}
+
+/// Fraction of pixels classified alike, over pixels classified in both; NaN when none are
+fn agreement<T: PartialEq>(a: &[Option<T>], b: &[Option<T>]) -> f64 {
+    let (same, total) = a
+        .iter()
+        .zip(b)
+        .filter_map(|(x, y)| Some(x.as_ref()? == y.as_ref()?))
+        .fold((0usize, 0usize), |(s, t), same| (s + same as usize, t + 1));
+    if total == 0 {
+        f64::NAN
+    } else {
+        same as f64 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reconstruction::{
+        AtmosphericSnapshot, ExpectedAtmosphericConditions, LearnedCorrelations, Point2D, StripQualityMetrics,
+        ViewingGeometry, WeatherDataPoint,
+    };
+    use nalgebra::Point3;
+
+    fn image(width: usize, height: usize, bands: &[&str], value: impl Fn(usize, usize, usize) -> f64) -> ImageData {
+        let mut data = Vec::with_capacity(width * height * bands.len());
+        for row in 0..height {
+            for col in 0..width {
+                for band in 0..bands.len() {
+                    data.push(value(row, col, band));
+                }
+            }
+        }
+        ImageData {
+            width,
+            height,
+            channels: bands.len(),
+            data,
+            metadata: ImageMetadata {
+                spectral_bands: bands.iter().map(|b| b.to_string()).collect(),
+                ..ImageMetadata::default()
+            },
+        }
+    }
+
+    fn weather(temperature: f64, cloud_cover: f64) -> WeatherSnapshot {
+        WeatherSnapshot {
+            temperature,
+            humidity: 60.0,
+            pressure: 1015.0,
+            wind_speed: 3.0,
+            wind_direction: 200.0,
+            cloud_cover,
+            precipitation: 0.0,
+            visibility: 20_000.0,
+        }
+    }
+
+    fn strip(id: &str, image_data: ImageData, weather_conditions: WeatherSnapshot) -> SatelliteStrip {
+        SatelliteStrip {
+            strip_id: id.to_string(),
+            satellite_id: "S2B".to_string(),
+            timestamp: 1_700_000_000.0,
+            orbital_position: Point3::origin(),
+            ground_track: vec![Point2D { x: 5.0, y: 50.0 }],
+            image_data,
+            weather_conditions,
+            atmospheric_conditions: AtmosphericSnapshot {
+                atmospheric_pressure: 1015.0,
+                water_vapor_content: 1.5,
+                aerosol_optical_depth: 0.1,
+                ozone_concentration: 300.0,
+                temperature_profile: Vec::new(),
+                humidity_profile: Vec::new(),
+            },
+            reconstruction_difficulty: 0.5,
+            quality_metrics: StripQualityMetrics {
+                spatial_resolution: 10.0,
+                temporal_resolution: 5.0,
+                spectral_quality: 0.9,
+                radiometric_quality: 0.9,
+                geometric_accuracy: 0.9,
+                cloud_contamination: 0.0,
+                atmospheric_correction_quality: 0.9,
+            },
+        }
+    }
+
+    fn parameters(bands: &[&str], cloud_cover: f64) -> StripParameters {
+        StripParameters {
+            spatial_resolution: 10.0,
+            spectral_bands: bands.iter().map(|b| b.to_string()).collect(),
+            integration_time: 0.001,
+            viewing_geometry: ViewingGeometry { viewing_angle: 0.0, azimuth_angle: 0.0, sun_elevation: 40.0, sun_azimuth: 160.0 },
+            atmospheric_conditions: ExpectedAtmosphericConditions {
+                visibility: 20_000.0,
+                cloud_cover,
+                atmospheric_turbulence: 0.1,
+                aerosol_loading: 0.1,
+            },
+        }
+    }
+
+    #[test]
+    fn test_analogs_weighted_by_weather_distance() {
+        let bands = ["R", "NIR"];
+        let warm = strip("warm", image(4, 4, &bands, |_, _, b| 0.1 + 0.3 * b as f64), weather(20.0, 0.1));
+        let evidence = ContributingEvidence {
+            coverage_area: SatelliteStripDatabase::strip_footprint(&warm).unwrap(),
+            historical_strips: vec![
+                // Same weather, half the resolution
+                warm,
+                strip("cold", image(8, 8, &bands, |_, _, _| 0.8), weather(0.0, 0.9)),
+                strip("mild", image(8, 8, &["NIR"], |_, _, _| 0.45), weather(18.0, 0.2)),
+            ],
+            weather_data: vec![WeatherDataPoint {
+                timestamp: 1_700_000_000.0,
+                location: Point3::new(5.0, 50.0, 0.0),
+                parameters: HashMap::from([("temperature".to_string(), 20.0), ("humidity".to_string(), 60.0)]),
+                quality: 1.0,
+            }],
+            atmospheric_models: Vec::new(),
+            ground_truth_data: Vec::new(),
+            correlations: LearnedCorrelations {
+                weather_satellite_correlations: Vec::new(),
+                temporal_correlations: Vec::new(),
+                spatial_correlations: Vec::new(),
+                spectral_correlations: Vec::new(),
+            },
+        };
+        let model = AnalogEnsembleReconstructionModel { analog_count: 2, ..Default::default() };
+        let reconstruction = model.reconstruct_strip(&evidence, &parameters(&["NIR", "R"], 0.1));
+
+        let image = &reconstruction.image_data;
+        assert_eq!((image.width, image.height, image.channels), (4, 4, 2));
+        assert_eq!(image.metadata.spectral_bands, vec!["NIR".to_string(), "R".to_string()]);
+        assert_eq!(reconstruction.quality_metrics["analog_count"], 2.0);
+        // Red comes from the exact weather match alone; NIR is pulled slightly towards the mild analog
+        assert!((image.data[1] - 0.1).abs() < 1e-12);
+        assert!(image.data[0] > 0.4 && image.data[0] < 0.42);
+        assert!(reconstruction.confidence > 0.0 && reconstruction.confidence < 1.0);
+        assert!((model.get_confidence_estimate(&reconstruction) - reconstruction.confidence).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_analogs_reprojected_onto_expected_area() {
+        let bands = ["R", "NIR"];
+        let tracked = |id: &str, west: f64, value: f64| {
+            let mut analog = strip(id, image(4, 4, &bands, move |_, _, _| value), weather(20.0, 0.1));
+            analog.ground_track = vec![Point2D { x: west, y: 0.0 }, Point2D { x: west + 1.0, y: 1.0 }];
+            analog
+        };
+        let evidence = ContributingEvidence {
+            // Each analog covers a third of the area, leaving the eastern third without one
+            coverage_area: GeoBounds { north: 1.0, south: 0.0, east: 13.0, west: 10.0 },
+            historical_strips: vec![tracked("west", 10.0, 0.2), tracked("east", 11.0, 0.6)],
+            weather_data: Vec::new(),
+            atmospheric_models: Vec::new(),
+            ground_truth_data: Vec::new(),
+            correlations: LearnedCorrelations {
+                weather_satellite_correlations: Vec::new(),
+                temporal_correlations: Vec::new(),
+                spatial_correlations: Vec::new(),
+                spectral_correlations: Vec::new(),
+            },
+        };
+        let reconstruction = AnalogEnsembleReconstructionModel::default().reconstruct_strip(&evidence, &parameters(&bands, 0.1));
+
+        let image = &reconstruction.image_data;
+        assert_eq!((image.width, image.height, image.channels), (4, 4, 2));
+        for row in 0..4 {
+            let red: Vec<f64> = (0..4).map(|col| image.data[(row * 4 + col) * 2]).collect();
+            assert!((red[0] - 0.2).abs() < 1e-12 && (red[1] - 0.6).abs() < 1e-12 && (red[2] - 0.6).abs() < 1e-12);
+            assert!(red[3].is_nan());
+        }
+
+        // Across the antimeridian, with the source columns kept in order
+        let footprint = GeoBounds { north: 1.0, south: 0.0, east: -179.0, west: 179.0 };
+        let source = image(4, 1, &["R"], |_, col, _| col as f64);
+        let area = GeoBounds { north: 1.0, south: 0.0, east: -179.5, west: 179.5 };
+        let laid_out = reproject_image(&source, &footprint, &area, 2, 1);
+        assert_eq!(laid_out.data, vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_fidelity_against_actual_and_persistence() {
+        let bands = ["R", "G", "NIR"];
+        let scene = |shift: f64| {
+            image(16, 16, &bands, move |row, col, band| {
+                let field = if col < 8 { 0.05 + 0.04 * band as f64 } else { 0.08 + if band == 2 { 0.2 } else { 0.0 } };
+                field + shift + 0.01 * ((row * 7 + col * 3) % 5) as f64
+            })
+        };
+        let (actual, previous) = (scene(0.1), scene(0.0));
+
+        let perfect = assess_reconstruction_fidelity(&actual, &actual, Some(&previous)).unwrap();
+        assert_eq!(perfect.pixel_level_metrics.mean_squared_error, 0.0);
+        assert!((perfect.pixel_level_metrics.structural_similarity_index - 1.0).abs() < 1e-9);
+        assert_eq!(perfect.pixel_level_metrics.peak_signal_to_noise_ratio, MAX_PSNR_DB);
+        assert!((perfect.feature_level_metrics.edge_preservation - 1.0).abs() < 1e-9);
+        assert!((perfect.temporal_consistency_metrics.temporal_interpolation_quality - 1.0).abs() < 1e-9);
+        assert_eq!(perfect.temporal_consistency_metrics.change_detection_accuracy, 1.0);
+        assert_eq!(perfect.semantic_level_metrics.land_use_classification_accuracy, 1.0);
+
+        // Persistence as the reconstruction has no skill and detects no change
+        let persistence = assess_reconstruction_fidelity(&previous, &actual, Some(&previous)).unwrap();
+        assert!(persistence.temporal_consistency_metrics.temporal_interpolation_quality.abs() < 1e-9);
+        assert_eq!(persistence.temporal_consistency_metrics.change_detection_accuracy, 0.0);
+        assert!((persistence.pixel_level_metrics.mean_squared_error - 0.01).abs() < 1e-9);
+        assert!(persistence.pixel_level_metrics.structural_similarity_index < 1.0);
+        assert!(persistence.feature_level_metrics.edge_preservation > 0.99);
+
+        let unreferenced = assess_reconstruction_fidelity(&previous, &actual, None).unwrap();
+        assert!(unreferenced.temporal_consistency_metrics.motion_coherence.is_nan());
+        assert!(assess_reconstruction_fidelity(&previous, &image(16, 16, &["SWIR"], |_, _, _| 0.2), None).is_err());
+
+        // A reconstruction with no valid pixel is an error, not a perfect score
+        let empty = image(16, 16, &bands, |_, _, _| f64::NAN);
+        assert!(assess_reconstruction_fidelity(&empty, &actual, Some(&previous)).is_err());
+    }
+}
@@ -0,0 +1,549 @@
+//! Versioned instrument calibrations and their application to ingested data
+//!
+//! Calibrations are keyed by instrument id (the satellite id of a strip, the sensor
+//! id of a measurement) and hold over a validity period. Where periods overlap the
+//! highest version wins, so reissuing a calibration supersedes the old one without
+//! rewriting history. A registry can be backed by a JSON Lines file that is replayed
+//! on attach and appended to on every registration.
+//!
+//! Conventions for the `CalibrationInformation` fields:
+//! - radiometric `calibration_coefficients` are per-band gains and
+//!   `dark_current_correction` per-band offsets in raw counts, subtracted before the
+//!   gain; a single entry applies to every band. `non_linearity_correction` holds the
+//!   quadratic and higher coefficients of the response, `L + c2·L² + c3·L³ + …`
+//! - `CameraModel` focal length and pixel size share a unit and the principal point
+//!   is in pixels; geometric `distortion_parameters` are the Brown-Conrady tangential
+//!   terms `p1, p2`
+//! - the instrument clock is synchronised at the start of validity; `drift_rate` is a
+//!   fractional frequency offset, `temperature_dependence` its change per °C from the
+//!   record's reference temperature and `aging_characteristics` its change per day
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{CalibrationInformation, LensDistortionModel, ProcessingStep, SatelliteStrip, StripMetadata};
+use crate::data_fusion::{MeasurementValue, SensorMeasurementBundle, TimestampedMeasurement};
+use crate::error::AppError;
+
+/// Name of the processing step recorded on calibrated strips
+pub const CALIBRATION_STEP: &str = "calibration";
+const DEFAULT_REFERENCE_TEMPERATURE_C: f64 = 20.0;
+const UNDISTORT_ITERATIONS: usize = 10;
+
+fn default_reference_temperature() -> f64 {
+    DEFAULT_REFERENCE_TEMPERATURE_C
+}
+
+/// A calibration to register; the registry assigns the version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationSubmission {
+    pub valid_from: DateTime<Utc>,
+    /// Open-ended when absent
+    #[serde(default)]
+    pub valid_until: Option<DateTime<Utc>>,
+    /// Ambient temperature the clock drift was characterised at
+    #[serde(default = "default_reference_temperature")]
+    pub reference_temperature_c: f64,
+    pub calibration: CalibrationInformation,
+}
+
+/// One registered calibration version of an instrument
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationRecord {
+    pub instrument_id: String,
+    pub version: u32,
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub reference_temperature_c: f64,
+    pub registered_at: DateTime<Utc>,
+    pub calibration: CalibrationInformation,
+}
+
+impl CalibrationRecord {
+    /// Provenance label, e.g. `drone-7@v3`
+    pub fn label(&self) -> String {
+        format!("{}@v{}", self.instrument_id, self.version)
+    }
+
+    pub fn is_valid_at(&self, time: DateTime<Utc>) -> bool {
+        time >= self.valid_from && self.valid_until.is_none_or(|until| time < until)
+    }
+
+    /// Seconds the instrument clock has gained by `timestamp` (Unix seconds)
+    pub fn clock_offset(&self, timestamp: f64, temperature_c: Option<f64>) -> f64 {
+        let drift = &self.calibration.temporal_calibration.clock_drift_characteristics;
+        let elapsed = timestamp - self.valid_from.timestamp() as f64;
+        let thermal = temperature_c.map_or(0.0, |t| drift.temperature_dependence * (t - self.reference_temperature_c));
+        (drift.drift_rate + thermal) * elapsed + 0.5 * drift.aging_characteristics * elapsed * elapsed / 86_400.0
+    }
+
+    pub fn correct_timestamp(&self, timestamp: f64, temperature_c: Option<f64>) -> f64 {
+        timestamp - self.clock_offset(timestamp, temperature_c)
+    }
+
+    /// Calibrated value of band `band` from raw counts
+    pub fn radiance(&self, band: usize, counts: f64) -> f64 {
+        let radiometric = &self.calibration.radiometric_calibration;
+        let gain = band_coefficient(&radiometric.calibration_coefficients, band).unwrap_or(1.0);
+        let dark = band_coefficient(&radiometric.dark_current_correction, band).unwrap_or(0.0);
+        let linear = gain * (counts - dark);
+        let mut power = linear;
+        let mut value = linear;
+        for coefficient in &radiometric.non_linearity_correction {
+            power *= linear;
+            value += coefficient * power;
+        }
+        value
+    }
+
+    /// Raw-image position of the ideal (undistorted) pixel at column `x`, row `y`
+    pub fn distorted_pixel(&self, x: f64, y: f64) -> (f64, f64) {
+        let geometric = &self.calibration.geometric_calibration;
+        let camera = &geometric.camera_model;
+        let (fx, fy) = focal_lengths_px(camera.focal_length, camera.pixel_size);
+        let (cx, cy) = camera.principal_point;
+        let (xn, yn) = ((x - cx) / fx, (y - cy) / fy);
+        let r2 = xn * xn + yn * yn;
+        let radial = match &camera.lens_distortion_model {
+            LensDistortionModel::Polynomial(k) => 1.0 + even_series(k, r2),
+            LensDistortionModel::Rational(numerator, denominator) => {
+                (1.0 + even_series(numerator, r2)) / (1.0 + even_series(denominator, r2))
+            },
+            LensDistortionModel::FishEye(k) => {
+                // Equidistant projection: the image radius follows the incidence angle
+                let r = r2.sqrt();
+                if r < 1e-12 {
+                    1.0
+                } else {
+                    let theta = r.atan();
+                    theta * (1.0 + even_series(k, theta * theta)) / r
+                }
+            },
+        };
+        let p1 = geometric.distortion_parameters.first().copied().unwrap_or(0.0);
+        let p2 = geometric.distortion_parameters.get(1).copied().unwrap_or(0.0);
+        let xd = xn * radial + 2.0 * p1 * xn * yn + p2 * (r2 + 2.0 * xn * xn);
+        let yd = yn * radial + p1 * (r2 + 2.0 * yn * yn) + 2.0 * p2 * xn * yn;
+        (xd * fx + cx, yd * fy + cy)
+    }
+
+    /// Ideal pixel that lands on the raw-image position `(x, y)`, by fixed-point iteration
+    pub fn undistorted_pixel(&self, x: f64, y: f64) -> (f64, f64) {
+        let (mut ux, mut uy) = (x, y);
+        for _ in 0..UNDISTORT_ITERATIONS {
+            let (dx, dy) = self.distorted_pixel(ux, uy);
+            ux += x - dx;
+            uy += y - dy;
+        }
+        (ux, uy)
+    }
+
+    fn has_distortion(&self) -> bool {
+        let geometric = &self.calibration.geometric_calibration;
+        let nonzero = |v: &[f64]| v.iter().any(|c| *c != 0.0);
+        let lens = match &geometric.camera_model.lens_distortion_model {
+            LensDistortionModel::Polynomial(k) | LensDistortionModel::FishEye(k) => nonzero(k),
+            LensDistortionModel::Rational(numerator, denominator) => nonzero(numerator) || nonzero(denominator),
+        };
+        lens || nonzero(&geometric.distortion_parameters[..geometric.distortion_parameters.len().min(2)])
+    }
+}
+
+/// Versioned calibrations per instrument
+#[derive(Debug, Clone, Default)]
+pub struct CalibrationRegistry {
+    records: HashMap<String, Vec<CalibrationRecord>>,
+    path: Option<PathBuf>,
+}
+
+impl CalibrationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replay the registrations in `path` and append new ones to it; returns the number replayed
+    pub fn attach_file(&mut self, path: &Path) -> Result<usize, AppError> {
+        let mut loaded = 0;
+        if path.exists() {
+            let file = std::fs::File::open(path)
+                .map_err(|e| AppError::internal(format!("Cannot open calibration registry {}: {}", path.display(), e)))?;
+            for (line_number, line) in BufReader::new(file).lines().enumerate() {
+                let line = line.map_err(|e| AppError::internal(format!("Cannot read {}: {}", path.display(), e)))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let record: CalibrationRecord = serde_json::from_str(&line).map_err(|e| {
+                    AppError::validation(format!("{} line {}: {}", path.display(), line_number + 1, e))
+                })?;
+                self.insert(record);
+                loaded += 1;
+            }
+        }
+        self.path = Some(path.to_path_buf());
+        Ok(loaded)
+    }
+
+    /// Register the next version of `instrument_id`'s calibration
+    pub fn register(&mut self, instrument_id: &str, submission: CalibrationSubmission) -> Result<&CalibrationRecord, AppError> {
+        if instrument_id.trim().is_empty() {
+            return Err(AppError::validation("Calibration needs an instrument id"));
+        }
+        validate_submission(&submission)?;
+        let version = self.versions(instrument_id).iter().map(|r| r.version).max().unwrap_or(0) + 1;
+        let record = CalibrationRecord {
+            instrument_id: instrument_id.to_string(),
+            version,
+            valid_from: submission.valid_from,
+            valid_until: submission.valid_until,
+            reference_temperature_c: submission.reference_temperature_c,
+            registered_at: Utc::now(),
+            calibration: submission.calibration,
+        };
+        if let Some(path) = &self.path {
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| AppError::internal(format!("Cannot open calibration registry {}: {}", path.display(), e)))?;
+            let line = serde_json::to_string(&record).map_err(|e| AppError::internal(e.to_string()))?;
+            writeln!(file, "{}", line).map_err(|e| AppError::internal(format!("Cannot write {}: {}", path.display(), e)))?;
+        }
+        Ok(self.insert(record))
+    }
+
+    fn insert(&mut self, record: CalibrationRecord) -> &CalibrationRecord {
+        let versions = self.records.entry(record.instrument_id.clone()).or_default();
+        let position = versions.partition_point(|r| r.version < record.version);
+        versions.insert(position, record);
+        &versions[position]
+    }
+
+    pub fn instruments(&self) -> impl Iterator<Item = &str> {
+        self.records.keys().map(String::as_str)
+    }
+
+    /// Every version of an instrument's calibration, oldest first
+    pub fn versions(&self, instrument_id: &str) -> &[CalibrationRecord] {
+        self.records.get(instrument_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Highest version valid at `time`
+    pub fn calibration_at(&self, instrument_id: &str, time: DateTime<Utc>) -> Option<&CalibrationRecord> {
+        self.versions(instrument_id).iter().rev().find(|r| r.is_valid_at(time))
+    }
+
+    /// Apply the strip's calibration in place: clock drift, radiometric gain/offset and
+    /// lens distortion, recorded as a processing step. Returns the label of the version
+    /// applied, or `None` when no calibration is valid or the strip is already calibrated.
+    pub fn calibrate_strip(&self, strip: &mut SatelliteStrip, metadata: &mut StripMetadata) -> Option<String> {
+        if metadata.processing_history.iter().any(|step| step.step_name == CALIBRATION_STEP) {
+            return None;
+        }
+        let record = self.calibration_at(&strip.satellite_id, timestamp_to_datetime(strip.timestamp))?;
+
+        let clock_offset = record.clock_offset(strip.timestamp, None);
+        strip.timestamp -= clock_offset;
+        let image = &mut strip.image_data;
+        image.metadata.acquisition_time = timestamp_to_datetime(strip.timestamp);
+        let channels = image.channels.max(1);
+        for (i, value) in image.data.iter_mut().enumerate() {
+            *value = record.radiance(i % channels, *value);
+        }
+        let undistorted = record.has_distortion() && image.width > 0 && image.height > 0;
+        if undistorted {
+            image.data = undistort(record, &image.data, image.width, image.height, channels);
+        }
+
+        let radiometric = &record.calibration.radiometric_calibration;
+        let geometric = &record.calibration.geometric_calibration;
+        strip.quality_metrics.radiometric_quality = (1.0 - radiometric.calibration_uncertainty).clamp(0.0, 1.0);
+        strip.quality_metrics.geometric_accuracy = geometric.geometric_accuracy;
+        metadata.calibration_information = record.calibration.clone();
+        metadata.processing_history.push(ProcessingStep {
+            step_name: CALIBRATION_STEP.to_string(),
+            algorithm_version: record.label(),
+            parameters: HashMap::from([
+                ("version".to_string(), record.version as f64),
+                ("clock_offset_s".to_string(), clock_offset),
+                ("radiometric_uncertainty".to_string(), radiometric.calibration_uncertainty),
+                ("geometric_accuracy".to_string(), geometric.geometric_accuracy),
+                ("distortion_corrected".to_string(), if undistorted { 1.0 } else { 0.0 }),
+            ]),
+            processing_time: Utc::now(),
+            quality_impact: strip.quality_metrics.radiometric_quality,
+        });
+        Some(record.label())
+    }
+
+    /// Apply the sensor's calibration to a measurement in place; scalar readings use the
+    /// first band's coefficients and solar radiation takes global, direct and diffuse as
+    /// bands 0, 1 and 2. Returns the label applied, as for `calibrate_strip`.
+    pub fn calibrate_measurement(&self, measurement: &mut TimestampedMeasurement) -> Option<String> {
+        if measurement.sensor_metadata.calibration_version.is_some() {
+            return None;
+        }
+        let record = self.calibration_at(&measurement.sensor_metadata.sensor_id, timestamp_to_datetime(measurement.timestamp))?;
+
+        measurement.timestamp = record.correct_timestamp(measurement.timestamp, Some(measurement.environmental_data.temperature));
+        let synchronisation = record.calibration.temporal_calibration.time_synchronization_accuracy;
+        measurement.temporal_uncertainty = Some(measurement.temporal_uncertainty.unwrap_or(0.0).max(synchronisation));
+        measurement.value = match &measurement.value {
+            MeasurementValue::MultispectralImage { bands, wavelengths } => MeasurementValue::MultispectralImage {
+                bands: bands.iter().enumerate().map(|(b, counts)| record.radiance(b, *counts)).collect(),
+                wavelengths: wavelengths.clone(),
+            },
+            MeasurementValue::SolarRadiation { global, direct, diffuse } => MeasurementValue::SolarRadiation {
+                global: record.radiance(0, *global),
+                direct: record.radiance(1, *direct),
+                diffuse: record.radiance(2, *diffuse),
+            },
+            other => match other.scalar_value() {
+                Some(counts) => other.with_scalar_value(record.radiance(0, counts)),
+                None => other.clone(),
+            },
+        };
+        measurement.quality_flags.is_calibrated = true;
+        measurement.sensor_metadata.calibration_date = record.valid_from;
+        measurement.sensor_metadata.calibration_version = Some(record.label());
+        Some(record.label())
+    }
+
+    /// Calibrate every measurement of a bundle that has a valid calibration
+    pub fn calibrate_bundle(&self, mut bundle: SensorMeasurementBundle) -> SensorMeasurementBundle {
+        for measurement in bundle.measurements.values_mut().flatten() {
+            self.calibrate_measurement(measurement);
+        }
+        bundle
+    }
+}
+
+fn validate_submission(submission: &CalibrationSubmission) -> Result<(), AppError> {
+    if submission.valid_until.is_some_and(|until| until <= submission.valid_from) {
+        return Err(AppError::validation("Calibration validity must end after it starts"));
+    }
+    let calibration = &submission.calibration;
+    let radiometric = &calibration.radiometric_calibration;
+    let gains = radiometric.calibration_coefficients.len();
+    let offsets = radiometric.dark_current_correction.len();
+    if offsets > 1 && gains > 1 && offsets != gains {
+        return Err(AppError::validation(format!("{} gains but {} dark-current offsets", gains, offsets)));
+    }
+    let camera = &calibration.geometric_calibration.camera_model;
+    let numbers = radiometric
+        .calibration_coefficients
+        .iter()
+        .chain(&radiometric.dark_current_correction)
+        .chain(&radiometric.non_linearity_correction)
+        .chain(&calibration.geometric_calibration.distortion_parameters)
+        .chain([&camera.focal_length, &camera.principal_point.0, &camera.principal_point.1, &submission.reference_temperature_c]);
+    if numbers.into_iter().any(|v| !v.is_finite()) {
+        return Err(AppError::validation("Calibration coefficients must be finite"));
+    }
+    if camera.pixel_size.0 < 0.0 || camera.pixel_size.1 < 0.0 {
+        return Err(AppError::validation("Pixel size cannot be negative"));
+    }
+    let focal_needed = submission.calibration.geometric_calibration.distortion_parameters.iter().any(|p| *p != 0.0)
+        || match &camera.lens_distortion_model {
+            LensDistortionModel::Polynomial(k) | LensDistortionModel::FishEye(k) => k.iter().any(|c| *c != 0.0),
+            LensDistortionModel::Rational(numerator, denominator) => numerator.iter().chain(denominator).any(|c| *c != 0.0),
+        };
+    if focal_needed && camera.focal_length <= 0.0 {
+        return Err(AppError::validation("Lens distortion needs a positive focal length"));
+    }
+    Ok(())
+}
+
+/// A per-band coefficient; a single entry applies to every band
+fn band_coefficient(coefficients: &[f64], band: usize) -> Option<f64> {
+    match coefficients {
+        [] => None,
+        [single] => Some(*single),
+        many => many.get(band).copied(),
+    }
+}
+
+/// `Σ k_i · x^(i+1)`, the radial series in r² (or θ²)
+fn even_series(coefficients: &[f64], x: f64) -> f64 {
+    let mut power = 1.0;
+    let mut sum = 0.0;
+    for k in coefficients {
+        power *= x;
+        sum += k * power;
+    }
+    sum
+}
+
+fn focal_lengths_px(focal_length: f64, pixel_size: (f64, f64)) -> (f64, f64) {
+    let scale = |size: f64| if size > 0.0 { focal_length / size } else { focal_length };
+    (scale(pixel_size.0), scale(pixel_size.1))
+}
+
+/// Resample an interleaved image onto the ideal camera grid; edges are replicated
+fn undistort(record: &CalibrationRecord, data: &[f64], width: usize, height: usize, channels: usize) -> Vec<f64> {
+    let mut output = vec![0.0; width * height * channels];
+    let sample = |col: usize, row: usize, c: usize| data[(row * width + col) * channels + c];
+    for row in 0..height {
+        for col in 0..width {
+            let (x, y) = record.distorted_pixel(col as f64, row as f64);
+            let x = x.clamp(0.0, (width - 1) as f64);
+            let y = y.clamp(0.0, (height - 1) as f64);
+            let (c0, r0) = (x.floor() as usize, y.floor() as usize);
+            let (c1, r1) = ((c0 + 1).min(width - 1), (r0 + 1).min(height - 1));
+            let (fx, fy) = (x - c0 as f64, y - r0 as f64);
+            for c in 0..channels {
+                let top = sample(c0, r0, c) * (1.0 - fx) + sample(c1, r0, c) * fx;
+                let bottom = sample(c0, r1, c) * (1.0 - fx) + sample(c1, r1, c) * fx;
+                output[(row * width + col) * channels + c] = top * (1.0 - fy) + bottom * fy;
+            }
+        }
+    }
+    output
+}
+
+/// Unix seconds as a UTC time, the epoch for non-finite input
+pub(super) fn timestamp_to_datetime(timestamp: f64) -> DateTime<Utc> {
+    DateTime::from_timestamp(timestamp.floor() as i64, (timestamp.fract() * 1e9) as u32).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reconstruction::{
+        BandpassCharacteristics, CameraModel, ClockDriftCharacteristics, GeometricCalibration, RadiometricCalibration,
+        SpectralCalibration, TemporalCalibration,
+    };
+    use crate::data_fusion::test_fixtures::{measurement, sensor_metadata};
+    use chrono::TimeZone;
+    use nalgebra::Vector3;
+
+    fn calibration(gain: f64, lens: LensDistortionModel, drift_rate: f64) -> CalibrationInformation {
+        CalibrationInformation {
+            radiometric_calibration: RadiometricCalibration {
+                calibration_coefficients: vec![gain, 2.0 * gain],
+                dark_current_correction: vec![10.0],
+                non_linearity_correction: Vec::new(),
+                calibration_uncertainty: 0.03,
+            },
+            geometric_calibration: GeometricCalibration {
+                camera_model: CameraModel {
+                    focal_length: 8.0,
+                    principal_point: (32.0, 24.0),
+                    pixel_size: (0.2, 0.2),
+                    lens_distortion_model: lens,
+                },
+                distortion_parameters: Vec::new(),
+                pointing_correction: Vector3::zeros(),
+                geometric_accuracy: 0.5,
+            },
+            spectral_calibration: SpectralCalibration {
+                wavelength_calibration: Vec::new(),
+                spectral_response_functions: Vec::new(),
+                spectral_accuracy: 1.0,
+                bandpass_characteristics: Vec::<BandpassCharacteristics>::new(),
+            },
+            temporal_calibration: TemporalCalibration {
+                time_synchronization_accuracy: 0.01,
+                integration_time_accuracy: 0.0,
+                temporal_sampling_jitter: 0.0,
+                clock_drift_characteristics: ClockDriftCharacteristics {
+                    drift_rate,
+                    stability: 0.0,
+                    temperature_dependence: 1e-7,
+                    aging_characteristics: 0.0,
+                },
+            },
+        }
+    }
+
+    fn submission(from_day: u32, until_day: Option<u32>, gain: f64) -> CalibrationSubmission {
+        let day = |d| Utc.with_ymd_and_hms(2025, 3, d, 0, 0, 0).unwrap();
+        CalibrationSubmission {
+            valid_from: day(from_day),
+            valid_until: until_day.map(day),
+            reference_temperature_c: 20.0,
+            calibration: calibration(gain, LensDistortionModel::Polynomial(vec![-0.05]), 1e-5),
+        }
+    }
+
+    #[test]
+    fn test_versions_by_validity_and_file_replay() {
+        let path = std::env::temp_dir().join(format!("calibrations-{}.jsonl", uuid::Uuid::new_v4()));
+        let mut registry = CalibrationRegistry::new();
+        assert_eq!(registry.attach_file(&path).unwrap(), 0);
+        assert_eq!(registry.register("drone-7", submission(1, None, 0.5)).unwrap().version, 1);
+        assert_eq!(registry.register("drone-7", submission(10, Some(20), 0.6)).unwrap().version, 2);
+        assert!(registry.register("drone-7", submission(20, Some(10), 0.7)).is_err());
+
+        let at = |d| Utc.with_ymd_and_hms(2025, 3, d, 12, 0, 0).unwrap();
+        let mut replayed = CalibrationRegistry::new();
+        assert_eq!(replayed.attach_file(&path).unwrap(), 2);
+        for registry in [&registry, &replayed] {
+            assert_eq!(registry.calibration_at("drone-7", at(5)).unwrap().label(), "drone-7@v1");
+            assert_eq!(registry.calibration_at("drone-7", at(15)).unwrap().label(), "drone-7@v2");
+            assert_eq!(registry.calibration_at("drone-7", at(25)).unwrap().version, 1);
+            assert!(registry.calibration_at("drone-7", Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap()).is_none());
+            assert!(registry.calibration_at("camera-1", at(5)).is_none());
+        }
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_solar_radiation_components_calibrated_as_bands() {
+        let mut pyranometer = submission(1, None, 0.5);
+        pyranometer.calibration.radiometric_calibration.calibration_coefficients = vec![0.5, 1.0, 2.0];
+        let mut registry = CalibrationRegistry::new();
+        registry.register("pyranometer-1", pyranometer).unwrap();
+
+        let installed = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+        let mut reading = measurement(
+            MeasurementValue::SolarRadiation { global: 110.0, direct: 110.0, diffuse: 110.0 },
+            installed.timestamp() as f64 + 3600.0,
+            sensor_metadata("pyranometer-1", (31.4, -19.2, 1100.0), installed),
+        );
+        assert_eq!(registry.calibrate_measurement(&mut reading).as_deref(), Some("pyranometer-1@v1"));
+        match reading.value {
+            MeasurementValue::SolarRadiation { global, direct, diffuse } => assert_eq!((global, direct, diffuse), (50.0, 100.0, 200.0)),
+            other => panic!("unexpected value {:?}", other),
+        }
+        assert!(registry.calibrate_measurement(&mut reading).is_none());
+    }
+
+    #[test]
+    fn test_radiometric_clock_and_distortion_models() {
+        let record = CalibrationRecord {
+            instrument_id: "camera-1".to_string(),
+            version: 1,
+            valid_from: Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            valid_until: None,
+            reference_temperature_c: 20.0,
+            registered_at: Utc::now(),
+            calibration: calibration(0.5, LensDistortionModel::Polynomial(vec![-0.05, 0.01]), 1e-5),
+        };
+        assert_eq!(record.radiance(0, 110.0), 50.0);
+        assert_eq!(record.radiance(1, 110.0), 100.0);
+
+        // A day after synchronisation a 10 ppm fast clock has gained 0.864 s, 0.0864 s more at 30 °C
+        let day_later = record.valid_from.timestamp() as f64 + 86_400.0;
+        assert!((record.clock_offset(day_later, None) - 0.864).abs() < 1e-9);
+        assert!((record.clock_offset(day_later, Some(30.0)) - 0.9504).abs() < 1e-9);
+
+        // Barrel distortion pulls off-axis pixels towards the principal point
+        assert_eq!(record.distorted_pixel(32.0, 24.0), (32.0, 24.0));
+        let (x, y) = record.distorted_pixel(62.0, 44.0);
+        assert!(x < 62.0 && x > 32.0 && y < 44.0 && y > 24.0);
+        let (ux, uy) = record.undistorted_pixel(x, y);
+        assert!((ux - 62.0).abs() < 1e-6 && (uy - 44.0).abs() < 1e-6);
+
+        let fisheye = CalibrationRecord {
+            calibration: calibration(1.0, LensDistortionModel::FishEye(vec![0.0]), 0.0),
+            ..record.clone()
+        };
+        let (x, _) = fisheye.distorted_pixel(72.0, 24.0);
+        // Equidistant: the image radius is f·atan(r/f) for f = 40 px
+        assert!((x - (32.0 + 40.0 * 1.0f64.atan())).abs() < 1e-9);
+    }
+}
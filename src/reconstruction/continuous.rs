@@ -0,0 +1,455 @@
+//! Continuous per-satellite reconstruction on the tokio runtime
+//!
+//! Each tracked satellite runs as a task that re-targets the satellite to the
+//! current time, runs the (CPU bound) unified prediction on the blocking pool
+//! and sends the result through a bounded channel. A collector task keeps the
+//! latest result per satellite and fans results out to subscribers.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::{broadcast, mpsc, watch, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+use super::strip_archive::bounds_intersect;
+use super::{
+    GeoBounds, PredictionMethod, ReconstructionQuality, SatelliteStrip, SatelliteStripDatabase,
+    StripReconstructionResult, TargetSatellite, UnifiedPredictionResult, UnifiedSatelliteWeatherReconstructionSystem,
+    UnifiedValidationResult,
+};
+
+#[derive(Debug, Clone)]
+pub struct ReconstructionStreamConfig {
+    /// Cycles closer together than the 15-minute frame step repeat the same frames
+    pub update_interval: Duration,
+    /// Every cycle renders four TOA frames per hour of horizon for each satellite
+    pub prediction_horizon_hours: f64,
+    /// Results waiting for the collector before satellite tasks are held back
+    pub result_capacity: usize,
+    /// Results a slow subscriber may fall behind by before it skips ahead
+    pub subscriber_capacity: usize,
+    /// Reconstructed strips kept per satellite to verify against acquisitions as they arrive
+    pub retained_strips: usize,
+}
+
+impl Default for ReconstructionStreamConfig {
+    fn default() -> Self {
+        Self {
+            update_interval: Duration::from_secs(900),
+            prediction_horizon_hours: 6.0,
+            result_capacity: 64,
+            subscriber_capacity: 16,
+            retained_strips: 256,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamMetadata {
+    pub start_time: DateTime<Utc>,
+    pub update_frequency: Duration,
+    pub total_predictions: usize,
+    pub average_confidence: f64,
+}
+
+/// Progress of one tracked satellite
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackedSatellite {
+    pub satellite_id: String,
+    pub running: bool,
+    pub predictions: usize,
+    pub last_update: Option<DateTime<Utc>>,
+    pub last_confidence: Option<f64>,
+    pub last_error: Option<String>,
+}
+
+/// A prediction published by the stream
+#[derive(Debug, Clone)]
+pub struct ReconstructionUpdate {
+    pub satellite_id: String,
+    /// Position in the stream across all satellites
+    pub sequence: u64,
+    pub generated_at: DateTime<Utc>,
+    pub result: Arc<UnifiedPredictionResult>,
+}
+
+/// Serializable digest of a prediction; the full result carries imagery
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconstructionReport {
+    pub satellite_id: String,
+    pub sequence: u64,
+    pub generated_at: DateTime<Utc>,
+    pub target_timestamp: f64,
+    pub prediction_method: PredictionMethod,
+    pub unified_confidence: f64,
+    pub has_actually_seen_future_weather: bool,
+    pub prediction_confidence: f64,
+    pub reconstruction_fidelity: ReconstructionQuality,
+    pub validation: UnifiedValidationResult,
+    pub strips: Vec<StripReport>,
+    pub weather_images: usize,
+    pub overall_weather_risk: f64,
+    pub yield_prediction: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StripReport {
+    pub strip_id: String,
+    pub expected_timestamp: f64,
+    pub coverage_area: GeoBounds,
+    pub reconstruction_confidence: f64,
+    pub validation_score: f64,
+    pub pixel_level_fidelity: f64,
+    pub temporal_consistency: f64,
+}
+
+impl From<&ReconstructionUpdate> for ReconstructionReport {
+    fn from(update: &ReconstructionUpdate) -> Self {
+        let result = &update.result;
+        let prediction = &result.reconstruction_prediction;
+        Self {
+            satellite_id: update.satellite_id.clone(),
+            sequence: update.sequence,
+            generated_at: update.generated_at,
+            target_timestamp: prediction.target_satellite.target_timestamp,
+            prediction_method: result.prediction_method.clone(),
+            unified_confidence: result.unified_confidence,
+            has_actually_seen_future_weather: result.has_actually_seen_future_weather,
+            prediction_confidence: prediction.prediction_confidence,
+            reconstruction_fidelity: prediction.reconstruction_fidelity.clone(),
+            validation: result.validation_result.clone(),
+            strips: prediction
+                .reconstructed_strips
+                .iter()
+                .map(|strip| StripReport {
+                    strip_id: strip.expected_strip.strip_id.clone(),
+                    expected_timestamp: strip.expected_strip.expected_timestamp,
+                    coverage_area: strip.expected_strip.coverage_area.clone(),
+                    reconstruction_confidence: strip.reconstruction_confidence,
+                    validation_score: strip.validation_score,
+                    pixel_level_fidelity: strip.pixel_level_fidelity,
+                    temporal_consistency: strip.temporal_consistency,
+                })
+                .collect(),
+            weather_images: result.weather_image_forecast.len(),
+            overall_weather_risk: result.agricultural_insights.weather_risk_assessment.overall_risk_score,
+            yield_prediction: result.agricultural_insights.yield_impact_analysis.current_yield_prediction,
+        }
+    }
+}
+
+struct SatelliteOutcome {
+    satellite_id: String,
+    result: Result<UnifiedPredictionResult, String>,
+}
+
+#[derive(Default)]
+struct StreamState {
+    satellites: HashMap<String, TrackedSatellite>,
+    latest: HashMap<String, ReconstructionUpdate>,
+    reconstructed_strips: HashMap<String, VecDeque<StripReconstructionResult>>,
+    next_sequence: u64,
+}
+
+/// Results of the running reconstruction tasks, shareable among readers
+pub struct ContinuousReconstructionStream {
+    state: Arc<RwLock<StreamState>>,
+    metadata: Arc<RwLock<StreamMetadata>>,
+    updates: broadcast::Sender<ReconstructionUpdate>,
+}
+
+/// Owner of the reconstruction tasks; dropping it cancels them
+///
+/// Kept apart from the stream so the tasks can be stopped and joined while
+/// readers still hold the stream.
+pub struct ReconstructionStreamControl {
+    tasks: Vec<JoinHandle<()>>,
+    shutdown: watch::Sender<bool>,
+}
+
+impl ContinuousReconstructionStream {
+    /// Spawn one task per satellite, each with its own copy of the system
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn start(
+        system: &UnifiedSatelliteWeatherReconstructionSystem,
+        target_satellites: &[TargetSatellite],
+        config: ReconstructionStreamConfig,
+    ) -> (Self, ReconstructionStreamControl) {
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let (results_tx, results_rx) = mpsc::channel(config.result_capacity.max(1));
+        let (updates, _) = broadcast::channel(config.subscriber_capacity.max(1));
+        let state = Arc::new(RwLock::new(StreamState {
+            satellites: target_satellites
+                .iter()
+                .map(|satellite| {
+                    let tracked = TrackedSatellite {
+                        satellite_id: satellite.satellite_id.clone(),
+                        running: true,
+                        predictions: 0,
+                        last_update: None,
+                        last_confidence: None,
+                        last_error: None,
+                    };
+                    (satellite.satellite_id.clone(), tracked)
+                })
+                .collect(),
+            ..StreamState::default()
+        }));
+        let metadata = Arc::new(RwLock::new(StreamMetadata {
+            start_time: Utc::now(),
+            update_frequency: config.update_interval,
+            total_predictions: 0,
+            average_confidence: 0.0,
+        }));
+
+        let mut tasks: Vec<JoinHandle<()>> = target_satellites
+            .iter()
+            .map(|satellite| {
+                tokio::spawn(run_satellite(
+                    system.clone(),
+                    satellite.clone(),
+                    config.clone(),
+                    results_tx.clone(),
+                    shutdown_rx.clone(),
+                ))
+            })
+            .collect();
+        drop(results_tx);
+        tasks.push(tokio::spawn(collect_results(
+            results_rx,
+            state.clone(),
+            metadata.clone(),
+            updates.clone(),
+            config.retained_strips,
+        )));
+
+        (Self { state, metadata, updates }, ReconstructionStreamControl { tasks, shutdown })
+    }
+
+    pub async fn tracked_satellites(&self) -> Vec<TrackedSatellite> {
+        let mut satellites: Vec<TrackedSatellite> = self.state.read().await.satellites.values().cloned().collect();
+        satellites.sort_by(|a, b| a.satellite_id.cmp(&b.satellite_id));
+        satellites
+    }
+
+    pub async fn latest(&self, satellite_id: &str) -> Option<ReconstructionUpdate> {
+        self.state.read().await.latest.get(satellite_id).cloned()
+    }
+
+    pub async fn metadata(&self) -> StreamMetadata {
+        self.metadata.read().await.clone()
+    }
+
+    /// The retained reconstruction of an acquired strip: same satellite, coverage
+    /// overlapping the acquisition, expected time nearest to it within `window_s`
+    pub async fn reconstruction_of(&self, acquired: &SatelliteStrip, window_s: f64) -> Option<StripReconstructionResult> {
+        let footprint = SatelliteStripDatabase::strip_footprint(acquired)?;
+        let state = self.state.read().await;
+        state
+            .reconstructed_strips
+            .get(&acquired.satellite_id)?
+            .iter()
+            .filter(|result| !result.reconstructed_image.data.is_empty())
+            .filter(|result| (result.expected_strip.expected_timestamp - acquired.timestamp).abs() <= window_s)
+            .filter(|result| bounds_intersect(&result.expected_strip.coverage_area, &footprint))
+            .min_by(|a, b| {
+                let gap = |r: &StripReconstructionResult| (r.expected_strip.expected_timestamp - acquired.timestamp).abs();
+                gap(a).total_cmp(&gap(b))
+            })
+            .cloned()
+    }
+
+    /// Receive every result published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<ReconstructionUpdate> {
+        self.updates.subscribe()
+    }
+}
+
+impl ReconstructionStreamControl {
+    /// Cancel all tasks and wait for them to finish
+    pub async fn shutdown(mut self) {
+        self.shutdown.send_replace(true);
+        for task in self.tasks.drain(..) {
+            if let Err(e) = task.await {
+                tracing::warn!("Reconstruction task ended abnormally: {}", e);
+            }
+        }
+    }
+}
+
+impl Drop for ReconstructionStreamControl {
+    fn drop(&mut self) {
+        self.shutdown.send_replace(true);
+    }
+}
+
+async fn run_satellite(
+    mut system: UnifiedSatelliteWeatherReconstructionSystem,
+    satellite: TargetSatellite,
+    config: ReconstructionStreamConfig,
+    results: mpsc::Sender<SatelliteOutcome>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let satellite_id = satellite.satellite_id.clone();
+    let mut interval = tokio::time::interval(config.update_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = shutdown.changed() => break,
+        }
+
+        let mut target = satellite.clone();
+        target.target_timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs_f64())
+            .unwrap_or_default();
+        let horizon = config.prediction_horizon_hours;
+        let cycle = tokio::task::spawn_blocking(move || {
+            let result = system.predict_weather_by_satellite_reconstruction(&target, horizon);
+            (system, result)
+        });
+        let joined = tokio::select! {
+            joined = cycle => joined,
+            _ = shutdown.changed() => break,
+        };
+
+        match joined {
+            Ok((returned, result)) => {
+                system = returned;
+                let outcome = SatelliteOutcome { satellite_id: satellite_id.clone(), result: Ok(result) };
+                if !send_outcome(&results, &mut shutdown, outcome).await {
+                    break;
+                }
+            }
+            Err(e) => {
+                // A panicking prediction takes the system with it, so the satellite stops here
+                let outcome = SatelliteOutcome {
+                    satellite_id: satellite_id.clone(),
+                    result: Err(format!("Reconstruction cycle failed: {}", e)),
+                };
+                send_outcome(&results, &mut shutdown, outcome).await;
+                break;
+            }
+        }
+    }
+}
+
+/// Wait for room in the result channel unless cancelled; false once the stream is stopping
+async fn send_outcome(
+    results: &mpsc::Sender<SatelliteOutcome>,
+    shutdown: &mut watch::Receiver<bool>,
+    outcome: SatelliteOutcome,
+) -> bool {
+    tokio::select! {
+        sent = results.send(outcome) => sent.is_ok(),
+        _ = shutdown.changed() => false,
+    }
+}
+
+async fn collect_results(
+    mut results: mpsc::Receiver<SatelliteOutcome>,
+    state: Arc<RwLock<StreamState>>,
+    metadata: Arc<RwLock<StreamMetadata>>,
+    updates: broadcast::Sender<ReconstructionUpdate>,
+    retained_strips: usize,
+) {
+    while let Some(outcome) = results.recv().await {
+        let now = Utc::now();
+        let mut state = state.write().await;
+        match outcome.result {
+            Ok(result) => {
+                let sequence = state.next_sequence;
+                state.next_sequence += 1;
+                let confidence = result.unified_confidence;
+                let retained = state.reconstructed_strips.entry(outcome.satellite_id.clone()).or_default();
+                retained.extend(result.reconstruction_prediction.reconstructed_strips.iter().cloned());
+                let excess = retained.len().saturating_sub(retained_strips);
+                retained.drain(..excess);
+                let update = ReconstructionUpdate {
+                    satellite_id: outcome.satellite_id.clone(),
+                    sequence,
+                    generated_at: now,
+                    result: Arc::new(result),
+                };
+                if let Some(tracked) = state.satellites.get_mut(&outcome.satellite_id) {
+                    tracked.predictions += 1;
+                    tracked.last_update = Some(now);
+                    tracked.last_confidence = Some(confidence);
+                    tracked.last_error = None;
+                }
+                state.latest.insert(outcome.satellite_id, update.clone());
+
+                let mut metadata = metadata.write().await;
+                metadata.total_predictions += 1;
+                metadata.average_confidence +=
+                    (confidence - metadata.average_confidence) / metadata.total_predictions as f64;
+                // Nobody listening is not an error
+                let _ = updates.send(update);
+            }
+            Err(error) => {
+                tracing::error!("Satellite {}: {}", outcome.satellite_id, error);
+                if let Some(tracked) = state.satellites.get_mut(&outcome.satellite_id) {
+                    tracked.running = false;
+                    tracked.last_error = Some(error);
+                }
+            }
+        }
+    }
+    // Every satellite task has ended
+    for tracked in state.write().await.satellites.values_mut() {
+        tracked.running = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stream_publishes_latest_per_satellite_and_shuts_down() {
+        let satellites: Vec<TargetSatellite> = ["SENTINEL-2A", "LANDSAT-9"]
+            .iter()
+            .map(|id| TargetSatellite { satellite_id: id.to_string(), ..TargetSatellite::default() })
+            .collect();
+        let config = ReconstructionStreamConfig {
+            update_interval: Duration::from_millis(20),
+            prediction_horizon_hours: 0.25,
+            result_capacity: 2,
+            subscriber_capacity: 8,
+            retained_strips: 4,
+        };
+        let (stream, control) = UnifiedSatelliteWeatherReconstructionSystem::new()
+            .continuous_satellite_weather_reconstruction(&satellites, config);
+        let mut updates = stream.subscribe();
+
+        let mut seen = std::collections::HashSet::new();
+        while seen.len() < satellites.len() {
+            match tokio::time::timeout(Duration::from_secs(60), updates.recv()).await.expect("result within a minute") {
+                Ok(update) => {
+                    seen.insert(update.satellite_id.clone());
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => panic!("stream closed early"),
+            }
+        }
+
+        let latest = stream.latest("LANDSAT-9").await.expect("latest result");
+        let report = ReconstructionReport::from(&latest);
+        assert_eq!(report.satellite_id, "LANDSAT-9");
+        assert!(report.target_timestamp > 1.6e9);
+        assert!(stream.latest("GOES-16").await.is_none());
+
+        let tracked = stream.tracked_satellites().await;
+        assert_eq!(tracked.iter().map(|s| s.satellite_id.as_str()).collect::<Vec<_>>(), ["LANDSAT-9", "SENTINEL-2A"]);
+        assert!(tracked.iter().all(|s| s.running && s.predictions >= 1 && s.last_error.is_none()));
+        assert!(stream.metadata().await.total_predictions >= 2);
+
+        tokio::time::timeout(Duration::from_secs(60), control.shutdown()).await.expect("tasks stop on shutdown");
+    }
+}
@@ -1,11 +1,28 @@
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, SystemTime};
-use std::thread;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use chrono::{DateTime, Utc};
 use nalgebra::{Vector3, Point3};
+use rstar::RTree;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
 use uuid::Uuid;
 
+use crate::error::AppError;
+
+pub mod strip_archive;
+pub mod analog_ensemble;
+pub mod continuous;
+pub mod toa_synthesis;
+pub mod calibration_registry;
+
+pub use strip_archive::*;
+pub use analog_ensemble::*;
+pub use continuous::*;
+pub use toa_synthesis::*;
+pub use calibration_registry::*;
+
 // ==================== Core Satellite-Centric Bayesian Network ====================
 
 #[derive(Debug, Clone)]
@@ -65,7 +82,7 @@ pub struct GeographicRegion {
     pub priority: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeoBounds {
     pub north: f64,
     pub south: f64,
@@ -172,7 +189,7 @@ pub enum EvidenceData {
     Categorical(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageData {
     pub width: usize,
     pub height: usize,
@@ -181,7 +198,7 @@ pub struct ImageData {
     pub metadata: ImageMetadata,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageMetadata {
     pub acquisition_time: DateTime<Utc>,
     pub satellite_id: String,
@@ -990,7 +1007,8 @@ pub struct ReconstructionBasedPrediction {
     pub strip_generators: Vec<Box<dyn StripGenerator>>,
     pub reconstruction_validators: Vec<Box<dyn ReconstructionValidator>>,
     pub fidelity_metrics: FidelityMetrics,
-    pub satellite_strip_database: SatelliteStripDatabase,
+    /// Shared by every copy of the system, e.g. the per-satellite stream tasks
+    pub satellite_strip_database: Arc<RwLock<SatelliteStripDatabase>>,
 }
 
 pub trait ImageReconstructionModel {
@@ -1004,7 +1022,7 @@ pub trait StripGenerator {
     fn validate_strip_feasibility(&self, strip: &ExpectedSatelliteStrip) -> bool;
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FidelityMetrics {
     pub pixel_level_metrics: PixelLevelMetrics,
     pub feature_level_metrics: FeatureLevelMetrics,
@@ -1012,7 +1030,7 @@ pub struct FidelityMetrics {
     pub temporal_consistency_metrics: TemporalConsistencyMetrics,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PixelLevelMetrics {
     pub mean_squared_error: f64,
     pub peak_signal_to_noise_ratio: f64,
@@ -1020,7 +1038,7 @@ pub struct PixelLevelMetrics {
     pub spectral_angle_mapper: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FeatureLevelMetrics {
     pub edge_preservation: f64,
     pub texture_similarity: f64,
@@ -1028,7 +1046,7 @@ pub struct FeatureLevelMetrics {
     pub color_fidelity: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SemanticLevelMetrics {
     pub object_detection_accuracy: f64,
     pub scene_classification_accuracy: f64,
@@ -1036,7 +1054,7 @@ pub struct SemanticLevelMetrics {
     pub weather_pattern_recognition_accuracy: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TemporalConsistencyMetrics {
     pub temporal_stability: f64,
     pub motion_coherence: f64,
@@ -1051,9 +1069,18 @@ pub struct SatelliteStripDatabase {
     pub reconstruction_patterns: Vec<ReconstructionPattern>,
     pub learned_correlations: LearnedCorrelations,
     pub quality_assessment_models: Vec<Box<dyn QualityAssessmentModel>>,
-}
-
-#[derive(Debug, Clone)]
+    pub search_config: StripSearchConfig,
+    spatial_index: RTree<StripFootprint>,
+    time_index: BTreeMap<i64, Vec<String>>,
+    strip_locations: HashMap<String, (String, usize)>,
+    spectral_histograms: HashMap<String, SpectralHistogram>,
+    ground_truth: Vec<GroundTruthMeasurement>,
+    archive_path: Option<PathBuf>,
+    /// Calibrations applied to strips as they are archived
+    pub calibrations: Arc<RwLock<CalibrationRegistry>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SatelliteStrip {
     pub strip_id: String,
     pub satellite_id: String,
@@ -1067,13 +1094,13 @@ pub struct SatelliteStrip {
     pub quality_metrics: StripQualityMetrics,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Point2D {
     pub x: f64,
     pub y: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherSnapshot {
     pub temperature: f64,
     pub humidity: f64,
@@ -1085,7 +1112,7 @@ pub struct WeatherSnapshot {
     pub visibility: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AtmosphericSnapshot {
     pub atmospheric_pressure: f64,
     pub water_vapor_content: f64,
@@ -1095,7 +1122,7 @@ pub struct AtmosphericSnapshot {
     pub humidity_profile: Vec<f64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StripQualityMetrics {
     pub spatial_resolution: f64,
     pub temporal_resolution: f64,
@@ -1106,7 +1133,7 @@ pub struct StripQualityMetrics {
     pub atmospheric_correction_quality: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StripMetadata {
     pub acquisition_parameters: AcquisitionParameters,
     pub processing_history: Vec<ProcessingStep>,
@@ -1114,16 +1141,17 @@ pub struct StripMetadata {
     pub calibration_information: CalibrationInformation,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AcquisitionParameters {
     pub sensor_mode: String,
     pub integration_time: f64,
     pub gain_settings: Vec<f64>,
     pub pointing_accuracy: f64,
     pub platform_stability: f64,
+    pub viewing_geometry: ViewingGeometry,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessingStep {
     pub step_name: String,
     pub algorithm_version: String,
@@ -1132,7 +1160,7 @@ pub struct ProcessingStep {
     pub quality_impact: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QualityFlag {
     pub flag_type: QualityFlagType,
     pub severity: f64,
@@ -1140,7 +1168,7 @@ pub struct QualityFlag {
     pub description: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum QualityFlagType {
     CloudContamination,
     AtmosphericDistortion,
@@ -1150,7 +1178,7 @@ pub enum QualityFlagType {
     TemporalInconsistency,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CalibrationInformation {
     pub radiometric_calibration: RadiometricCalibration,
     pub geometric_calibration: GeometricCalibration,
@@ -1158,7 +1186,7 @@ pub struct CalibrationInformation {
     pub temporal_calibration: TemporalCalibration,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RadiometricCalibration {
     pub calibration_coefficients: Vec<f64>,
     pub dark_current_correction: Vec<f64>,
@@ -1166,7 +1194,7 @@ pub struct RadiometricCalibration {
     pub calibration_uncertainty: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeometricCalibration {
     pub camera_model: CameraModel,
     pub distortion_parameters: Vec<f64>,
@@ -1174,7 +1202,7 @@ pub struct GeometricCalibration {
     pub geometric_accuracy: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CameraModel {
     pub focal_length: f64,
     pub principal_point: (f64, f64),
@@ -1182,14 +1210,14 @@ pub struct CameraModel {
     pub lens_distortion_model: LensDistortionModel,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum LensDistortionModel {
     Polynomial(Vec<f64>),
     Rational(Vec<f64>, Vec<f64>),
     FishEye(Vec<f64>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpectralCalibration {
     pub wavelength_calibration: Vec<f64>,
     pub spectral_response_functions: Vec<Vec<f64>>,
@@ -1197,7 +1225,7 @@ pub struct SpectralCalibration {
     pub bandpass_characteristics: Vec<BandpassCharacteristics>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BandpassCharacteristics {
     pub center_wavelength: f64,
     pub bandwidth: f64,
@@ -1205,7 +1233,7 @@ pub struct BandpassCharacteristics {
     pub out_of_band_response: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemporalCalibration {
     pub time_synchronization_accuracy: f64,
     pub integration_time_accuracy: f64,
@@ -1213,7 +1241,7 @@ pub struct TemporalCalibration {
     pub clock_drift_characteristics: ClockDriftCharacteristics,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClockDriftCharacteristics {
     pub drift_rate: f64,
     pub stability: f64,
@@ -1360,12 +1388,17 @@ pub struct LimitingFactor {
 // Implementation of reconstruction-based prediction
 impl ReconstructionBasedPrediction {
     pub fn new() -> Self {
+        let mut image_reconstruction_models: HashMap<String, Box<dyn ImageReconstructionModel>> = HashMap::new();
+        image_reconstruction_models.insert(
+            AnalogEnsembleReconstructionModel::MODEL_NAME.to_string(),
+            Box::new(AnalogEnsembleReconstructionModel::default()),
+        );
         Self {
-            image_reconstruction_models: HashMap::new(),
+            image_reconstruction_models,
             strip_generators: Vec::new(),
             reconstruction_validators: Vec::new(),
             fidelity_metrics: FidelityMetrics::default(),
-            satellite_strip_database: SatelliteStripDatabase::new(),
+            satellite_strip_database: Arc::new(RwLock::new(SatelliteStripDatabase::new())),
         }
     }
 
@@ -1457,23 +1490,37 @@ impl ReconstructionBasedPrediction {
             .collect()
     }
 
+    /// Runs on the blocking pool, so the shared archive is read with a blocking lock
     fn gather_evidence_for_strip(&self, expected_strip: &ExpectedSatelliteStrip) -> ContributingEvidence {
+        let database = self.satellite_strip_database.blocking_read();
         ContributingEvidence {
-            historical_strips: self.satellite_strip_database.find_similar_strips(expected_strip),
-            weather_data: self.collect_relevant_weather_data(expected_strip),
-            atmospheric_models: self.get_atmospheric_model_predictions(expected_strip),
-            ground_truth_data: self.collect_ground_truth_for_region(&expected_strip.coverage_area),
-            correlations: self.satellite_strip_database.learned_correlations.clone(),
+            coverage_area: expected_strip.coverage_area.clone(),
+            historical_strips: database.find_similar_strips(expected_strip),
+            weather_data: database.recent_weather(expected_strip),
+            atmospheric_models: database.persisted_atmospheric_profiles(expected_strip),
+            ground_truth_data: database.ground_truth_in(&expected_strip.coverage_area),
+            correlations: database.learned_correlations.clone(),
         }
     }
 
     fn ensemble_strip_reconstructions(&self, reconstructions: &HashMap<String, ReconstructedStrip>) -> EnsembleReconstruction {
         // Implement sophisticated ensemble method
-        let mut ensemble_image = ImageData::default();
+        // The most confident reconstruction sets the image shape; others of a different shape are left out
+        let template = reconstructions
+            .values()
+            .filter(|r| !r.image_data.data.is_empty())
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence));
+        let mut ensemble_image = match template {
+            Some(template) => ImageData { data: vec![0.0; template.image_data.data.len()], ..template.image_data.clone() },
+            None => ImageData::default(),
+        };
         let mut total_confidence = 0.0;
         let mut weight_sum = 0.0;
 
         for (model_name, reconstruction) in reconstructions {
+            if reconstruction.image_data.data.len() != ensemble_image.data.len() {
+                continue;
+            }
             let weight = reconstruction.confidence;
             weight_sum += weight;
             total_confidence += reconstruction.confidence * weight;
@@ -1566,7 +1613,7 @@ pub struct StripParameters {
     pub atmospheric_conditions: ExpectedAtmosphericConditions,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ViewingGeometry {
     pub viewing_angle: f64,
     pub azimuth_angle: f64,
@@ -1584,6 +1631,8 @@ pub struct ExpectedAtmosphericConditions {
 
 #[derive(Debug, Clone)]
 pub struct ContributingEvidence {
+    /// Area the evidence was gathered for; reconstructions are laid out on it
+    pub coverage_area: GeoBounds,
     pub historical_strips: Vec<SatelliteStrip>,
     pub weather_data: Vec<WeatherDataPoint>,
     pub atmospheric_models: Vec<AtmosphericModelPrediction>,
@@ -1676,7 +1725,7 @@ pub struct ReconstructionPrediction {
     pub weather_forecast: WeatherForecast,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ReconstructionQuality {
     pub overall_fidelity: f64,
     pub spatial_consistency: f64,
@@ -1809,27 +1858,16 @@ impl SatelliteStripDatabase {
             reconstruction_patterns: Vec::new(),
             learned_correlations: LearnedCorrelations::default(),
             quality_assessment_models: Vec::new(),
+            search_config: StripSearchConfig::default(),
+            spatial_index: RTree::new(),
+            time_index: BTreeMap::new(),
+            strip_locations: HashMap::new(),
+            spectral_histograms: HashMap::new(),
+            ground_truth: Vec::new(),
+            archive_path: None,
+            calibrations: Arc::new(RwLock::new(CalibrationRegistry::new())),
         }
     }
-
-    pub fn find_similar_strips(&self, expected_strip: &ExpectedSatelliteStrip) -> Vec<SatelliteStrip> {
-        // Implement similarity search based on spatial, temporal, and spectral criteria
-        Vec::new()
-    }
-}
-
-impl ReconstructionBasedPrediction {
-    fn collect_relevant_weather_data(&self, expected_strip: &ExpectedSatelliteStrip) -> Vec<WeatherDataPoint> {
-        Vec::new()
-    }
-
-    fn get_atmospheric_model_predictions(&self, expected_strip: &ExpectedSatelliteStrip) -> Vec<AtmosphericModelPrediction> {
-        Vec::new()
-    }
-
-    fn collect_ground_truth_for_region(&self, region: &GeoBounds) -> Vec<GroundTruthMeasurement> {
-        Vec::new()
-    }
 }
 
 impl Default for LearnedCorrelations {
@@ -2178,7 +2216,7 @@ pub struct UnifiedPredictionResult {
     pub agricultural_insights: AgriculturalInsights,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct UnifiedValidationResult {
     pub reconstruction_fidelity: f64,
     pub physical_consistency: f64,
@@ -2189,7 +2227,7 @@ pub struct UnifiedValidationResult {
     pub validation_components: HashMap<String, f64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum PredictionMethod {
     SatelliteTargetedReconstruction,
     WeatherImageGeneration,
@@ -2336,6 +2374,7 @@ impl UnifiedSatelliteWeatherReconstructionSystem {
             .generate_weather_forecast_sequence(
                 &reconstruction_prediction.inferred_atmospheric_state,
                 &target_satellite.trajectory,
+                target_satellite.target_timestamp,
                 prediction_horizon_hours
             );
         
@@ -2368,47 +2407,18 @@ impl UnifiedSatelliteWeatherReconstructionSystem {
         }
     }
     
-    pub fn continuous_satellite_weather_reconstruction(&mut self,
-                                                     target_satellites: &[TargetSatellite]) -> ContinuousReconstructionStream {
-        
-        let mut reconstruction_stream = ContinuousReconstructionStream::new();
-        
-        // For each target satellite, continuously optimize and reconstruct
-        for target_satellite in target_satellites {
-            let satellite_thread = std::thread::spawn({
-                let mut system_clone = self.clone();
-                let satellite_clone = target_satellite.clone();
-                
-                move || {
-                    loop {
-                        // Update target satellite time to current
-                        let current_time = SystemTime::now()
-                            .duration_since(SystemTime::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs_f64();
-                        let mut current_target = satellite_clone.clone();
-                        current_target.target_timestamp = current_time;
-                        
-                        // Perform unified prediction
-                        let prediction_result = system_clone
-                            .predict_weather_by_satellite_reconstruction(
-                                &current_target,
-                                24.0 // 24 hour forecast
-                            );
-                        
-                        // Stream result
-                        reconstruction_stream.add_result(prediction_result);
-                        
-                        // Wait for next update cycle
-                        std::thread::sleep(std::time::Duration::from_secs(60)); // Update every minute
-                    }
-                }
-            });
-            
-            reconstruction_stream.add_satellite_thread(target_satellite.satellite_id.clone(), satellite_thread);
-        }
-        
-        reconstruction_stream
+    /// Search and verify against a strip archive shared with the rest of the application
+    pub fn with_strip_database(mut self, database: Arc<RwLock<SatelliteStripDatabase>>) -> Self {
+        self.reconstruction_based_prediction.satellite_strip_database = database;
+        self
+    }
+
+    /// Keep reconstructing the target satellites as tokio tasks until the returned control shuts them down
+    pub fn continuous_satellite_weather_reconstruction(&self,
+                                                     target_satellites: &[TargetSatellite],
+                                                     config: ReconstructionStreamConfig)
+                                                     -> (ContinuousReconstructionStream, ReconstructionStreamControl) {
+        ContinuousReconstructionStream::start(self, target_satellites, config)
     }
 
     fn compute_unified_confidence(&self, validation: &UnifiedValidationResult) -> f64 {
@@ -2439,83 +2449,39 @@ impl UnifiedSatelliteWeatherReconstructionSystem {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct ContinuousReconstructionStream {
-    pub active_satellites: HashMap<String, thread::JoinHandle<()>>,
-    pub prediction_results: Arc<Mutex<Vec<UnifiedPredictionResult>>>,
-    pub stream_metadata: StreamMetadata,
-}
-
-#[derive(Debug, Clone)]
-pub struct StreamMetadata {
-    pub start_time: DateTime<Utc>,
-    pub update_frequency: Duration,
-    pub total_predictions: usize,
-    pub average_confidence: f64,
-}
-
-impl ContinuousReconstructionStream {
-    pub fn new() -> Self {
-        Self {
-            active_satellites: HashMap::new(),
-            prediction_results: Arc::new(Mutex::new(Vec::new())),
-            stream_metadata: StreamMetadata {
-                start_time: Utc::now(),
-                update_frequency: Duration::from_secs(60),
-                total_predictions: 0,
-                average_confidence: 0.0,
-            },
-        }
-    }
-
-    pub fn add_result(&mut self, result: UnifiedPredictionResult) {
-        if let Ok(mut results) = self.prediction_results.lock() {
-            results.push(result);
-        }
-    }
-
-    pub fn add_satellite_thread(&mut self, satellite_id: String, handle: thread::JoinHandle<()>) {
-        // Note: This is a placeholder - actual implementation would need proper thread management
-    }
-}
-
 // Default implementations for new structures
 impl WeatherAsImageGeneration {
     pub fn new() -> Self {
+        let mut weather_image_generators: HashMap<String, Box<dyn WeatherImageGenerator>> = HashMap::new();
+        weather_image_generators.insert(ToaImageSynthesizer::GENERATOR_NAME.to_string(), Box::new(ToaImageSynthesizer::new()));
         Self {
-            weather_image_generators: HashMap::new(),
-            atmospheric_renderers: Vec::new(),
+            weather_image_generators,
+            atmospheric_renderers: vec![Box::new(ToaImageSynthesizer::new())],
             temporal_image_sequence_models: Vec::new(),
-            weather_strip_synthesizers: Vec::new(),
+            weather_strip_synthesizers: vec![Box::new(ToaImageSynthesizer::new())],
         }
     }
 
+    /// Images every 15 minutes from `start_timestamp`, rendered by the TOA synthesiser when registered
     pub fn generate_weather_forecast_sequence(&self,
                                             atmospheric_state: &AtmosphericState,
                                             trajectory: &SatelliteTrajectory,
+                                            start_timestamp: f64,
                                             horizon_hours: f64) -> Vec<GeneratedWeatherImage> {
-        let mut forecast_sequence = Vec::new();
         let time_steps = (horizon_hours * 4.0) as usize; // 15-minute intervals
-        
-        for i in 0..time_steps {
-            let timestamp = atmospheric_state.temperature_profile[0] + (i as f64 * 900.0); // 15 minutes
-            let viewpoint = trajectory.get_viewpoint_at_time(timestamp);
-            
-            let weather_image = GeneratedWeatherImage {
-                timestamp,
-                satellite_viewpoint: viewpoint,
-                atmospheric_state: atmospheric_state.clone(),
-                composite_image: ImageData::default(),
-                spectral_bands: HashMap::new(),
-                generation_confidence: 0.85,
-                physical_consistency_score: 0.9,
-                generation_metadata: GenerationMetadata::default(),
-            };
-            
-            forecast_sequence.push(weather_image);
+        let timestamps: Vec<f64> = (0..time_steps).map(|i| start_timestamp + i as f64 * 900.0).collect();
+        let generator = self.weather_image_generators
+            .get(ToaImageSynthesizer::GENERATOR_NAME)
+            .or_else(|| self.weather_image_generators.values().next());
+        match generator {
+            Some(generator) => generator.generate_weather_sequence(atmospheric_state, trajectory, &timestamps),
+            None => Vec::new(),
         }
-        
-        forecast_sequence
+    }
+
+    /// Score a generated image against the acquisition it forecast
+    pub fn verify_generated_image(&self, generated: &GeneratedWeatherImage, acquired: &ImageData) -> Result<FidelityMetrics, AppError> {
+        assess_reconstruction_fidelity(&generated.composite_image, acquired, None)
     }
 }
 
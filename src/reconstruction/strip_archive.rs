@@ -0,0 +1,957 @@
+//! Spatio-temporal archive of acquired strips and analog search
+//!
+//! Strips are indexed by footprint in an R-tree and by acquisition time in an
+//! ordered map. Ground tracks and locations use x = longitude, y = latitude in
+//! degrees; timestamps are Unix seconds. Image data is interleaved by pixel.
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use nalgebra::Point3;
+use rstar::{RTreeObject, AABB};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use super::analog_ensemble::{assess_reconstruction_fidelity, reproject_image};
+use super::{
+    AtmosphericModelPrediction, AtmosphericProfile, CalibrationRegistry, ExpectedSatelliteStrip, FidelityMetrics,
+    GeoBounds, GroundTruthMeasurement, ImageData, SatelliteStrip, SatelliteStripDatabase, StripMetadata,
+    StripReconstructionResult, ViewingGeometry, WeatherDataPoint,
+};
+use crate::error::AppError;
+
+const KM_PER_DEGREE: f64 = 111.32;
+const TROPICAL_YEAR_S: f64 = 365.2422 * 86_400.0;
+const PRESSURE_SCALE_HEIGHT_M: f64 = 8_400.0;
+const ANEMOMETER_HEIGHT_M: f64 = 10.0;
+
+/// Weights and limits for analog strip search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StripSearchConfig {
+    pub footprint_weight: f64,
+    pub geometry_weight: f64,
+    pub season_weight: f64,
+    pub spectral_weight: f64,
+    /// Fraction of the expected footprint an archived strip must cover
+    pub min_footprint_overlap: f64,
+    pub max_results: usize,
+    /// Angular scales (degrees) of the viewing and solar geometry kernels
+    pub viewing_angle_scale_deg: f64,
+    pub sun_elevation_scale_deg: f64,
+    pub sun_azimuth_scale_deg: f64,
+    /// How far before the expected acquisition weather and profiles are gathered
+    pub recent_window_s: f64,
+    /// e-folding time of the confidence in persisted atmospheric profiles
+    pub persistence_timescale_s: f64,
+}
+
+impl Default for StripSearchConfig {
+    fn default() -> Self {
+        Self {
+            footprint_weight: 0.35,
+            geometry_weight: 0.2,
+            season_weight: 0.2,
+            spectral_weight: 0.25,
+            min_footprint_overlap: 0.05,
+            max_results: 8,
+            viewing_angle_scale_deg: 10.0,
+            sun_elevation_scale_deg: 10.0,
+            sun_azimuth_scale_deg: 30.0,
+            recent_window_s: 72.0 * 3600.0,
+            persistence_timescale_s: 24.0 * 3600.0,
+        }
+    }
+}
+
+/// Per-channel reflectance histograms over [0, 1]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpectralHistogram {
+    pub bins: usize,
+    /// Normalised counts, one vector per channel; empty for channels without valid pixels
+    pub channels: Vec<Vec<f64>>,
+}
+
+impl SpectralHistogram {
+    pub const DEFAULT_BINS: usize = 32;
+
+    pub fn from_image(image: &super::ImageData, bins: usize) -> Self {
+        let bins = bins.max(1);
+        let channels = image.channels.max(1);
+        let mut counts = vec![vec![0.0; bins]; channels];
+        for (k, value) in image.data.iter().enumerate() {
+            if value.is_finite() {
+                let bin = ((value.clamp(0.0, 1.0) * bins as f64) as usize).min(bins - 1);
+                counts[k % channels][bin] += 1.0;
+            }
+        }
+        for channel in &mut counts {
+            let total: f64 = channel.iter().sum();
+            if total > 0.0 {
+                channel.iter_mut().for_each(|c| *c /= total);
+            } else {
+                channel.clear();
+            }
+        }
+        Self { bins, channels: counts }
+    }
+
+    /// Earth mover's distance in reflectance units, averaged over the shared channels
+    ///
+    /// Returns `None` when the histograms share no populated channel.
+    pub fn distance(&self, other: &SpectralHistogram) -> Option<f64> {
+        if self.bins != other.bins {
+            return None;
+        }
+        let mut total = 0.0;
+        let mut compared = 0;
+        for (a, b) in self.channels.iter().zip(&other.channels) {
+            if a.is_empty() || b.is_empty() {
+                continue;
+            }
+            let (mut cdf_a, mut cdf_b, mut emd) = (0.0, 0.0, 0.0);
+            for (pa, pb) in a.iter().zip(b) {
+                cdf_a += pa;
+                cdf_b += pb;
+                emd += (cdf_a - cdf_b).abs();
+            }
+            total += emd / self.bins as f64;
+            compared += 1;
+        }
+        (compared > 0).then(|| total / compared as f64)
+    }
+}
+
+/// Footprint entry of the spatial index; footprints crossing the antimeridian are stored as two pieces
+#[derive(Debug, Clone)]
+pub struct StripFootprint {
+    pub strip_id: String,
+    pub envelope: AABB<[f64; 2]>,
+}
+
+impl RTreeObject for StripFootprint {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+/// An archived strip ranked against an expected acquisition
+#[derive(Debug, Clone)]
+pub struct StripMatch {
+    pub strip_id: String,
+    pub score: f64,
+    pub footprint_overlap: f64,
+    pub geometry_similarity: f64,
+    pub season_similarity: f64,
+    pub spectral_similarity: Option<f64>,
+    /// Fraction of the requested spectral bands the archived strip carries
+    pub band_coverage: f64,
+}
+
+/// An acquired strip with its metadata, one line of the archive file
+#[derive(Serialize, Deserialize)]
+pub struct ArchivedStrip {
+    pub strip: SatelliteStrip,
+    pub metadata: StripMetadata,
+}
+
+impl SatelliteStripDatabase {
+    /// Load strips from a JSON Lines archive and append future insertions to it
+    ///
+    /// Returns the number of strips indexed from the file. Lines that do not
+    /// parse or repeat an archived strip are logged and skipped, so one torn
+    /// write does not cost the rest of the archive.
+    pub fn attach_archive(&mut self, path: &Path) -> Result<usize, AppError> {
+        let mut loaded = 0;
+        if path.exists() {
+            let file = std::fs::File::open(path)
+                .map_err(|e| AppError::internal(format!("Cannot open strip archive {}: {}", path.display(), e)))?;
+            for (line_number, line) in BufReader::new(file).lines().enumerate() {
+                let line = line.map_err(|e| AppError::internal(format!("Cannot read {}: {}", path.display(), e)))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let record = serde_json::from_str::<ArchivedStrip>(&line)
+                    .map_err(|e| AppError::validation(e.to_string()))
+                    .and_then(|record| self.validate_new_strip(&record.strip).map(|_| record));
+                match record {
+                    Ok(record) => {
+                        self.index_strip(record.strip, record.metadata);
+                        loaded += 1;
+                    }
+                    Err(e) => tracing::warn!("Skipping {} line {}: {}", path.display(), line_number + 1, e),
+                }
+            }
+        }
+        self.archive_path = Some(path.to_path_buf());
+        Ok(loaded)
+    }
+
+    /// Calibrate strips with a registry shared with the rest of the application
+    pub fn with_calibration_registry(mut self, calibrations: Arc<RwLock<CalibrationRegistry>>) -> Self {
+        self.calibrations = calibrations;
+        self
+    }
+
+    /// Calibrate and index a strip with its metadata, appending it to the attached archive file if any
+    ///
+    /// Blocks on the calibration registry and the file, so async callers run it on the blocking pool.
+    pub fn archive_strip(&mut self, mut strip: SatelliteStrip, mut metadata: StripMetadata) -> Result<(), AppError> {
+        if let Some(version) = self.calibrations.blocking_read().calibrate_strip(&mut strip, &mut metadata) {
+            tracing::debug!("Calibrated strip {} with {}", strip.strip_id, version);
+        }
+        self.validate_new_strip(&strip)?;
+        let record = ArchivedStrip { strip, metadata };
+        if let Some(path) = &self.archive_path {
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| AppError::internal(format!("Cannot open strip archive {}: {}", path.display(), e)))?;
+            let line = serde_json::to_string(&record).map_err(|e| AppError::internal(e.to_string()))?;
+            writeln!(file, "{}", line).map_err(|e| AppError::internal(format!("Cannot write {}: {}", path.display(), e)))?;
+        }
+        self.index_strip(record.strip, record.metadata);
+        Ok(())
+    }
+
+    pub fn add_ground_truth(&mut self, measurement: GroundTruthMeasurement) {
+        self.ground_truth.push(measurement);
+    }
+
+    pub fn strip(&self, strip_id: &str) -> Option<&SatelliteStrip> {
+        let (satellite_id, position) = self.strip_locations.get(strip_id)?;
+        self.historical_strips.get(satellite_id)?.get(*position)
+    }
+
+    pub fn strip_count(&self) -> usize {
+        self.strip_locations.len()
+    }
+
+    /// Footprint of a strip: its ground track widened by half the image swath
+    pub fn strip_footprint(strip: &SatelliteStrip) -> Option<GeoBounds> {
+        let first = strip.ground_track.first()?;
+        let (mut west, mut east) = (first.x, first.x);
+        let (mut south, mut north) = (first.y, first.y);
+        for point in &strip.ground_track[1..] {
+            // Unwrap longitudes relative to the start of the track
+            let x = first.x + wrap_longitude(point.x - first.x);
+            west = west.min(x);
+            east = east.max(x);
+            south = south.min(point.y);
+            north = north.max(point.y);
+        }
+        let resolution_m = if strip.image_data.metadata.spatial_resolution > 0.0 {
+            strip.image_data.metadata.spatial_resolution
+        } else {
+            strip.quality_metrics.spatial_resolution
+        };
+        let half_swath_km = strip.image_data.width as f64 * resolution_m.max(0.0) / 2000.0;
+        let latitude_pad = half_swath_km / KM_PER_DEGREE;
+        let widest = south.abs().max(north.abs()).min(89.0).to_radians();
+        let longitude_pad = (half_swath_km / (KM_PER_DEGREE * widest.cos())).min(180.0);
+        let (west, east) = (west - longitude_pad, east + longitude_pad);
+        let (west, east) = if east - west >= 360.0 {
+            (-180.0, 180.0)
+        } else {
+            (wrap_longitude(west), wrap_longitude(east))
+        };
+        Some(GeoBounds { north: (north + latitude_pad).min(90.0), south: (south - latitude_pad).max(-90.0), east, west })
+    }
+
+    /// Archived strips ranked as analogs of an expected acquisition
+    ///
+    /// Spectral similarity is measured against `reference`; without one the
+    /// most recent covering acquisition before the expected time is used, and
+    /// the spectral term is dropped when no such acquisition exists.
+    pub fn rank_similar_strips(
+        &self,
+        expected: &ExpectedSatelliteStrip,
+        reference: Option<&SpectralHistogram>,
+    ) -> Vec<StripMatch> {
+        let config = &self.search_config;
+        let overlaps = self.overlapping_strips(&expected.coverage_area);
+        let reference = reference.or_else(|| {
+            overlaps
+                .iter()
+                .filter(|(_, overlap)| *overlap >= config.min_footprint_overlap)
+                .filter_map(|(id, _)| self.strip(id))
+                .filter(|strip| strip.timestamp <= expected.expected_timestamp)
+                .max_by(|a, b| a.timestamp.total_cmp(&b.timestamp))
+                .and_then(|strip| self.spectral_histograms.get(&strip.strip_id))
+        });
+
+        let geometry = &expected.strip_parameters.viewing_geometry;
+        let requested_bands = &expected.strip_parameters.spectral_bands;
+        let mut matches: Vec<StripMatch> = overlaps
+            .into_iter()
+            .filter(|(_, overlap)| *overlap >= config.min_footprint_overlap)
+            .filter_map(|(strip_id, footprint_overlap)| {
+                let strip = self.strip(&strip_id)?;
+                let geometry_similarity = self
+                    .strip_metadata
+                    .get(&strip_id)
+                    .map(|metadata| self.geometry_similarity(&metadata.acquisition_parameters.viewing_geometry, geometry))
+                    .unwrap_or(0.0);
+                let phase = (strip.timestamp - expected.expected_timestamp) / TROPICAL_YEAR_S;
+                let season_similarity = 0.5 * (1.0 + (2.0 * PI * phase).cos());
+                let spectral_similarity = reference
+                    .zip(self.spectral_histograms.get(&strip_id))
+                    .and_then(|(reference, histogram)| reference.distance(histogram))
+                    .map(|distance| (1.0 - distance).clamp(0.0, 1.0));
+
+                let mut weighted = config.footprint_weight * footprint_overlap
+                    + config.geometry_weight * geometry_similarity
+                    + config.season_weight * season_similarity;
+                let mut weight_sum = config.footprint_weight + config.geometry_weight + config.season_weight;
+                if let Some(similarity) = spectral_similarity {
+                    weighted += config.spectral_weight * similarity;
+                    weight_sum += config.spectral_weight;
+                }
+                let band_coverage = band_coverage(requested_bands, &strip.image_data.metadata.spectral_bands);
+                let score = if weight_sum > 0.0 { band_coverage * weighted / weight_sum } else { 0.0 };
+                Some(StripMatch {
+                    strip_id,
+                    score,
+                    footprint_overlap,
+                    geometry_similarity,
+                    season_similarity,
+                    spectral_similarity,
+                    band_coverage,
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.strip_id.cmp(&b.strip_id)));
+        matches.truncate(config.max_results);
+        matches
+    }
+
+    pub fn find_similar_strips(&self, expected_strip: &ExpectedSatelliteStrip) -> Vec<SatelliteStrip> {
+        self.rank_similar_strips(expected_strip, None)
+            .iter()
+            .filter_map(|candidate| self.strip(&candidate.strip_id).cloned())
+            .collect()
+    }
+
+    /// Surface weather of strips covering the area in the window before the expected acquisition
+    pub fn recent_weather(&self, expected: &ExpectedSatelliteStrip) -> Vec<WeatherDataPoint> {
+        self.recent_strips(expected)
+            .into_iter()
+            .map(|(strip, footprint, overlap)| {
+                let weather = &strip.weather_conditions;
+                let parameters = [
+                    ("temperature", weather.temperature),
+                    ("humidity", weather.humidity),
+                    ("pressure", weather.pressure),
+                    ("wind_speed", weather.wind_speed),
+                    ("wind_direction", weather.wind_direction),
+                    ("cloud_cover", weather.cloud_cover),
+                    ("precipitation", weather.precipitation),
+                    ("visibility", weather.visibility),
+                ]
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect();
+                WeatherDataPoint {
+                    timestamp: strip.timestamp,
+                    location: bounds_centre(&footprint),
+                    parameters,
+                    quality: overlap,
+                }
+            })
+            .collect()
+    }
+
+    /// Persistence forecasts from the atmospheric profiles of recent covering strips
+    ///
+    /// Archived profiles are sampled every kilometre from the surface; confidence
+    /// decays with the age of the profile at the expected acquisition time.
+    pub fn persisted_atmospheric_profiles(&self, expected: &ExpectedSatelliteStrip) -> Vec<AtmosphericModelPrediction> {
+        let timescale = self.search_config.persistence_timescale_s.max(1.0);
+        let mut predictions: Vec<AtmosphericModelPrediction> = self
+            .recent_strips(expected)
+            .into_iter()
+            .map(|(strip, _, overlap)| {
+                let atmosphere = &strip.atmospheric_conditions;
+                let levels = atmosphere.temperature_profile.len().max(atmosphere.humidity_profile.len());
+                let altitude_levels: Vec<f64> = (0..levels).map(|k| k as f64 * 1000.0).collect();
+                let pressure_profile = altitude_levels
+                    .iter()
+                    .map(|z| atmosphere.atmospheric_pressure * (-z / PRESSURE_SCALE_HEIGHT_M).exp())
+                    .collect();
+                // One-seventh power law from the surface wind
+                let wind = &strip.weather_conditions;
+                let wind_profile = altitude_levels
+                    .iter()
+                    .map(|z| (wind.wind_speed * (z.max(ANEMOMETER_HEIGHT_M) / ANEMOMETER_HEIGHT_M).powf(1.0 / 7.0), wind.wind_direction))
+                    .collect();
+                let age = (expected.expected_timestamp - strip.timestamp).max(0.0);
+                AtmosphericModelPrediction {
+                    model_name: format!("persistence:{}", strip.strip_id),
+                    prediction_timestamp: expected.expected_timestamp,
+                    atmospheric_profile: AtmosphericProfile {
+                        altitude_levels,
+                        temperature_profile: atmosphere.temperature_profile.clone(),
+                        humidity_profile: atmosphere.humidity_profile.clone(),
+                        pressure_profile,
+                        wind_profile,
+                    },
+                    confidence: overlap * (-age / timescale).exp(),
+                }
+            })
+            .collect();
+        predictions.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        predictions
+    }
+
+    /// Most recent strip covering `area` acquired strictly before `timestamp`
+    pub fn latest_strip_before(&self, area: &GeoBounds, timestamp: f64) -> Option<&SatelliteStrip> {
+        self.overlapping_strips(area)
+            .iter()
+            .filter(|(_, overlap)| *overlap >= self.search_config.min_footprint_overlap)
+            .filter_map(|(id, _)| self.strip(id))
+            .filter(|strip| strip.timestamp < timestamp)
+            .max_by(|a, b| a.timestamp.total_cmp(&b.timestamp))
+    }
+
+    /// Score a reconstructed strip against the acquisition that has arrived
+    ///
+    /// The acquisition, and the latest archived strip of the area before it as
+    /// the persistence baseline, are laid out on the reconstruction's grid over
+    /// the expected coverage area before they are compared.
+    pub fn verify_strip_reconstruction(
+        &self,
+        result: &StripReconstructionResult,
+        actual: &SatelliteStrip,
+    ) -> Result<FidelityMetrics, AppError> {
+        let area = &result.expected_strip.coverage_area;
+        let (width, height) = (result.reconstructed_image.width, result.reconstructed_image.height);
+        let lay_out = |strip: &SatelliteStrip| -> Result<ImageData, AppError> {
+            let footprint = Self::strip_footprint(strip)
+                .ok_or_else(|| AppError::validation(format!("Strip {} has an empty ground track", strip.strip_id)))?;
+            Ok(reproject_image(&strip.image_data, &footprint, area, width, height))
+        };
+        let observed = lay_out(actual)?;
+        let previous = self
+            .latest_strip_before(area, actual.timestamp)
+            .filter(|previous| previous.strip_id != actual.strip_id)
+            .map(lay_out)
+            .transpose()?;
+        assess_reconstruction_fidelity(&result.reconstructed_image, &observed, previous.as_ref())
+    }
+
+    /// Ground truth measurements located inside `region`
+    pub fn ground_truth_in(&self, region: &GeoBounds) -> Vec<GroundTruthMeasurement> {
+        let pieces = split_antimeridian(region);
+        self.ground_truth
+            .iter()
+            .filter(|m| {
+                let (longitude, latitude) = (wrap_longitude(m.location.x), m.location.y);
+                pieces.iter().any(|p| {
+                    latitude >= p.south && latitude <= p.north && longitude >= p.west && longitude <= p.east
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn validate_new_strip(&self, strip: &SatelliteStrip) -> Result<(), AppError> {
+        if self.strip_locations.contains_key(&strip.strip_id) {
+            return Err(AppError::validation(format!("Strip {} is already archived", strip.strip_id)));
+        }
+        if !strip.timestamp.is_finite() {
+            return Err(AppError::validation(format!("Strip {} has no valid acquisition time", strip.strip_id)));
+        }
+        if Self::strip_footprint(strip).is_none() {
+            return Err(AppError::validation(format!("Strip {} has an empty ground track", strip.strip_id)));
+        }
+        Ok(())
+    }
+
+    fn index_strip(&mut self, strip: SatelliteStrip, metadata: StripMetadata) {
+        let strip_id = strip.strip_id.clone();
+        if let Some(footprint) = Self::strip_footprint(&strip) {
+            for piece in split_antimeridian(&footprint) {
+                self.spatial_index.insert(StripFootprint {
+                    strip_id: strip_id.clone(),
+                    envelope: AABB::from_corners([piece.west, piece.south], [piece.east, piece.north]),
+                });
+            }
+        }
+        self.time_index.entry(strip.timestamp.floor() as i64).or_default().push(strip_id.clone());
+        self.spectral_histograms
+            .insert(strip_id.clone(), SpectralHistogram::from_image(&strip.image_data, SpectralHistogram::DEFAULT_BINS));
+        self.strip_metadata.insert(strip_id.clone(), metadata);
+        let strips = self.historical_strips.entry(strip.satellite_id.clone()).or_default();
+        self.strip_locations.insert(strip_id, (strip.satellite_id.clone(), strips.len()));
+        strips.push(strip);
+    }
+
+    /// Archived strips intersecting `area` with the fraction of `area` each covers
+    fn overlapping_strips(&self, area: &GeoBounds) -> Vec<(String, f64)> {
+        let pieces = split_antimeridian(area);
+        let area_total: f64 = pieces.iter().map(bounds_area).sum();
+        let mut covered: HashMap<String, f64> = HashMap::new();
+        for piece in &pieces {
+            let query = AABB::from_corners([piece.west, piece.south], [piece.east, piece.north]);
+            for footprint in self.spatial_index.locate_in_envelope_intersecting(&query) {
+                let lower = footprint.envelope.lower();
+                let upper = footprint.envelope.upper();
+                let intersection = GeoBounds {
+                    west: lower[0].max(piece.west),
+                    east: upper[0].min(piece.east),
+                    south: lower[1].max(piece.south),
+                    north: upper[1].min(piece.north),
+                };
+                *covered.entry(footprint.strip_id.clone()).or_insert(0.0) += bounds_area(&intersection);
+            }
+        }
+        let mut overlaps: Vec<(String, f64)> = covered
+            .into_iter()
+            .map(|(id, area)| {
+                let fraction = if area_total > 0.0 { (area / area_total).min(1.0) } else { 1.0 };
+                (id, fraction)
+            })
+            .collect();
+        overlaps.sort_by(|a, b| a.0.cmp(&b.0));
+        overlaps
+    }
+
+    /// Strips acquired within the recent window that overlap the expected footprint, oldest first
+    fn recent_strips(&self, expected: &ExpectedSatelliteStrip) -> Vec<(&SatelliteStrip, GeoBounds, f64)> {
+        let end = expected.expected_timestamp;
+        let start = end - self.search_config.recent_window_s.max(0.0);
+        let overlaps: HashMap<String, f64> = self.overlapping_strips(&expected.coverage_area).into_iter().collect();
+        self.time_index
+            .range(start.floor() as i64..=end.floor() as i64)
+            .flat_map(|(_, ids)| ids)
+            .filter_map(|id| {
+                let overlap = *overlaps.get(id)?;
+                let strip = self.strip(id)?;
+                if overlap < self.search_config.min_footprint_overlap || strip.timestamp < start || strip.timestamp > end {
+                    return None;
+                }
+                Some((strip, Self::strip_footprint(strip)?, overlap))
+            })
+            .collect()
+    }
+
+    fn geometry_similarity(&self, archived: &ViewingGeometry, expected: &ViewingGeometry) -> f64 {
+        let config = &self.search_config;
+        let terms = [
+            (archived.viewing_angle - expected.viewing_angle, config.viewing_angle_scale_deg),
+            (archived.sun_elevation - expected.sun_elevation, config.sun_elevation_scale_deg),
+            (angle_difference(archived.sun_azimuth, expected.sun_azimuth), config.sun_azimuth_scale_deg),
+        ];
+        let exponent: f64 = terms.iter().map(|(delta, scale)| (delta / scale.max(f64::EPSILON)).powi(2)).sum();
+        (-0.5 * exponent).exp()
+    }
+}
+
+fn band_coverage(requested: &[String], available: &[String]) -> f64 {
+    if requested.is_empty() || available.is_empty() {
+        return 1.0;
+    }
+    let present = requested.iter().filter(|band| available.iter().any(|a| a.eq_ignore_ascii_case(band))).count();
+    present as f64 / requested.len() as f64
+}
+
+fn wrap_longitude(longitude: f64) -> f64 {
+    let wrapped = (longitude + 180.0).rem_euclid(360.0) - 180.0;
+    if wrapped == -180.0 && longitude > 0.0 {
+        180.0
+    } else {
+        wrapped
+    }
+}
+
+fn angle_difference(a: f64, b: f64) -> f64 {
+    wrap_longitude(a - b)
+}
+
+/// Bounds with `west > east` span the antimeridian and are split in two
+fn split_antimeridian(bounds: &GeoBounds) -> Vec<GeoBounds> {
+    if bounds.west <= bounds.east {
+        vec![bounds.clone()]
+    } else {
+        vec![
+            GeoBounds { east: 180.0, ..bounds.clone() },
+            GeoBounds { west: -180.0, ..bounds.clone() },
+        ]
+    }
+}
+
+/// Whether two bounds share any area, either of them possibly crossing the antimeridian
+pub(super) fn bounds_intersect(a: &GeoBounds, b: &GeoBounds) -> bool {
+    split_antimeridian(a).iter().any(|a| {
+        split_antimeridian(b)
+            .iter()
+            .any(|b| a.west <= b.east && b.west <= a.east && a.south <= b.north && b.south <= a.north)
+    })
+}
+
+/// Area in square degrees of longitude scaled by the cosine of the central latitude
+fn bounds_area(bounds: &GeoBounds) -> f64 {
+    let width = (bounds.east - bounds.west).max(0.0);
+    let height = (bounds.north - bounds.south).max(0.0);
+    width * height * (0.5 * (bounds.north + bounds.south)).to_radians().cos()
+}
+
+fn bounds_centre(bounds: &GeoBounds) -> Point3<f64> {
+    let east = if bounds.west > bounds.east { bounds.east + 360.0 } else { bounds.east };
+    Point3::new(wrap_longitude(0.5 * (bounds.west + east)), 0.5 * (bounds.north + bounds.south), 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reconstruction::{
+        AcquisitionParameters, AtmosphericSnapshot, BandpassCharacteristics, CalibrationInformation, CalibrationSubmission,
+        CameraModel, ClockDriftCharacteristics, ExpectedAtmosphericConditions, GeometricCalibration, ImageData,
+        ImageMetadata, LensDistortionModel, Point2D, RadiometricCalibration, SpectralCalibration, StripParameters,
+        StripQualityMetrics, TemporalCalibration, WeatherSnapshot, CALIBRATION_STEP,
+    };
+    use chrono::Utc;
+    use nalgebra::Vector3;
+
+    const DAY: f64 = 86_400.0;
+    const EPOCH: f64 = 1_700_000_000.0;
+
+    fn geometry(viewing_angle: f64, sun_elevation: f64) -> ViewingGeometry {
+        ViewingGeometry { viewing_angle, azimuth_angle: 100.0, sun_elevation, sun_azimuth: 160.0 }
+    }
+
+    fn strip(id: &str, longitude: f64, timestamp: f64, reflectance: f64) -> SatelliteStrip {
+        let (width, height, channels) = (20, 20, 2);
+        SatelliteStrip {
+            strip_id: id.to_string(),
+            satellite_id: "S2A".to_string(),
+            timestamp,
+            orbital_position: Point3::new(0.0, 0.0, 7_000.0),
+            ground_track: vec![Point2D { x: longitude, y: 50.0 }, Point2D { x: longitude + 0.2, y: 51.0 }],
+            image_data: ImageData {
+                width,
+                height,
+                channels,
+                data: (0..width * height * channels).map(|k| reflectance + 0.05 * (k % channels) as f64).collect(),
+                metadata: ImageMetadata {
+                    acquisition_time: Utc::now(),
+                    satellite_id: "S2A".to_string(),
+                    sensor_type: "Optical".to_string(),
+                    spectral_bands: vec!["R".to_string(), "NIR".to_string()],
+                    spatial_resolution: 2_000.0,
+                    cloud_coverage: 0.1,
+                },
+            },
+            weather_conditions: WeatherSnapshot {
+                temperature: 12.0,
+                humidity: 70.0,
+                pressure: 1013.0,
+                wind_speed: 4.0,
+                wind_direction: 240.0,
+                cloud_cover: 0.2,
+                precipitation: 0.0,
+                visibility: 20_000.0,
+            },
+            atmospheric_conditions: AtmosphericSnapshot {
+                atmospheric_pressure: 1013.0,
+                water_vapor_content: 1.8,
+                aerosol_optical_depth: 0.1,
+                ozone_concentration: 300.0,
+                temperature_profile: vec![285.0, 278.5, 272.0],
+                humidity_profile: vec![0.7, 0.6, 0.5],
+            },
+            reconstruction_difficulty: 0.3,
+            quality_metrics: StripQualityMetrics {
+                spatial_resolution: 2_000.0,
+                temporal_resolution: 5.0,
+                spectral_quality: 0.9,
+                radiometric_quality: 0.9,
+                geometric_accuracy: 0.9,
+                cloud_contamination: 0.1,
+                atmospheric_correction_quality: 0.9,
+            },
+        }
+    }
+
+    fn metadata(viewing_geometry: ViewingGeometry) -> StripMetadata {
+        StripMetadata {
+            acquisition_parameters: AcquisitionParameters {
+                sensor_mode: "pushbroom".to_string(),
+                integration_time: 0.001,
+                gain_settings: vec![1.0, 1.0],
+                pointing_accuracy: 0.01,
+                platform_stability: 0.99,
+                viewing_geometry,
+            },
+            processing_history: Vec::new(),
+            quality_flags: Vec::new(),
+            calibration_information: CalibrationInformation {
+                radiometric_calibration: RadiometricCalibration {
+                    calibration_coefficients: vec![1.0],
+                    dark_current_correction: vec![0.0],
+                    non_linearity_correction: vec![0.0],
+                    calibration_uncertainty: 0.03,
+                },
+                geometric_calibration: GeometricCalibration {
+                    camera_model: CameraModel {
+                        focal_length: 0.6,
+                        principal_point: (0.0, 0.0),
+                        pixel_size: (7.5e-6, 7.5e-6),
+                        lens_distortion_model: LensDistortionModel::Polynomial(vec![0.0]),
+                    },
+                    distortion_parameters: Vec::new(),
+                    pointing_correction: Vector3::zeros(),
+                    geometric_accuracy: 10.0,
+                },
+                spectral_calibration: SpectralCalibration {
+                    wavelength_calibration: vec![665.0, 842.0],
+                    spectral_response_functions: Vec::new(),
+                    spectral_accuracy: 1.0,
+                    bandpass_characteristics: vec![BandpassCharacteristics {
+                        center_wavelength: 665.0,
+                        bandwidth: 30.0,
+                        transmission_efficiency: 0.9,
+                        out_of_band_response: 0.01,
+                    }],
+                },
+                temporal_calibration: TemporalCalibration {
+                    time_synchronization_accuracy: 1e-6,
+                    integration_time_accuracy: 1e-7,
+                    temporal_sampling_jitter: 1e-7,
+                    clock_drift_characteristics: ClockDriftCharacteristics {
+                        drift_rate: 1e-9,
+                        stability: 1e-11,
+                        temperature_dependence: 1e-10,
+                        aging_characteristics: 1e-12,
+                    },
+                },
+            },
+        }
+    }
+
+    fn expected(timestamp: f64) -> ExpectedSatelliteStrip {
+        ExpectedSatelliteStrip {
+            strip_id: "next".to_string(),
+            satellite_id: "S2A".to_string(),
+            expected_timestamp: timestamp,
+            coverage_area: GeoBounds { north: 50.9, south: 50.1, east: 5.1, west: 4.9 },
+            strip_parameters: StripParameters {
+                spatial_resolution: 2_000.0,
+                spectral_bands: vec!["R".to_string(), "NIR".to_string()],
+                integration_time: 0.001,
+                viewing_geometry: geometry(5.0, 35.0),
+                atmospheric_conditions: ExpectedAtmosphericConditions {
+                    visibility: 20_000.0,
+                    cloud_cover: 0.2,
+                    atmospheric_turbulence: 0.1,
+                    aerosol_loading: 0.1,
+                },
+            },
+            expected_quality: 0.9,
+            reconstruction_priority: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_analogs_ranked_by_footprint_geometry_season_and_spectra() {
+        let mut database = SatelliteStripDatabase::new();
+        let target = EPOCH + 400.0 * DAY;
+        // Last year's acquisition with matching geometry and similar reflectance
+        database.archive_strip(strip("analog", 4.9, target - 365.0 * DAY, 0.31), metadata(geometry(5.0, 35.0))).unwrap();
+        // Off-season, oblique acquisition with brighter surface
+        database.archive_strip(strip("winter", 4.9, target - 180.0 * DAY, 0.7), metadata(geometry(25.0, 12.0))).unwrap();
+        // Latest acquisition, the spectral reference
+        database.archive_strip(strip("latest", 4.9, target - DAY, 0.3), metadata(geometry(8.0, 33.0))).unwrap();
+        database.archive_strip(strip("elsewhere", 40.0, target - 365.0 * DAY, 0.3), metadata(geometry(5.0, 35.0))).unwrap();
+
+        let ranked = database.rank_similar_strips(&expected(target), None);
+        let ids: Vec<&str> = ranked.iter().map(|m| m.strip_id.as_str()).collect();
+        assert_eq!(ids.len(), 3);
+        assert!(!ids.contains(&"elsewhere"));
+        assert_eq!(ids[2], "winter");
+        let analog = ranked.iter().find(|m| m.strip_id == "analog").unwrap();
+        assert!(analog.footprint_overlap > 0.99);
+        assert!(analog.season_similarity > 0.99);
+        assert!(analog.spectral_similarity.unwrap() > 0.95);
+        let winter = ranked.iter().find(|m| m.strip_id == "winter").unwrap();
+        assert!(winter.season_similarity < 0.05 && winter.geometry_similarity < 0.05);
+        assert!(winter.spectral_similarity.unwrap() < analog.spectral_similarity.unwrap());
+
+        let strips = database.find_similar_strips(&expected(target));
+        assert_eq!(strips.len(), 3);
+        assert_eq!(strips[0].strip_id, ranked[0].strip_id);
+    }
+
+    #[test]
+    fn test_recent_weather_profiles_and_ground_truth() {
+        let mut database = SatelliteStripDatabase::new();
+        let target = EPOCH + 10.0 * DAY;
+        database.archive_strip(strip("yesterday", 4.9, target - DAY, 0.3), metadata(geometry(5.0, 35.0))).unwrap();
+        database.archive_strip(strip("old", 4.9, target - 10.0 * DAY, 0.3), metadata(geometry(5.0, 35.0))).unwrap();
+        database.add_ground_truth(GroundTruthMeasurement {
+            measurement_id: "inside".to_string(),
+            timestamp: target,
+            location: Point3::new(5.0, 50.5, 0.0),
+            measurement_type: "soil_moisture".to_string(),
+            value: 0.25,
+            uncertainty: 0.02,
+        });
+        database.add_ground_truth(GroundTruthMeasurement {
+            measurement_id: "outside".to_string(),
+            location: Point3::new(8.0, 50.5, 0.0),
+            ..database.ground_truth[0].clone()
+        });
+
+        let weather = database.recent_weather(&expected(target));
+        assert_eq!(weather.len(), 1);
+        assert_eq!(weather[0].parameters["wind_speed"], 4.0);
+        assert!((weather[0].location.y - 50.5).abs() < 1e-9);
+
+        let profiles = database.persisted_atmospheric_profiles(&expected(target));
+        assert_eq!(profiles.len(), 1);
+        let profile = &profiles[0].atmospheric_profile;
+        assert_eq!(profile.altitude_levels, vec![0.0, 1000.0, 2000.0]);
+        assert!(profile.pressure_profile[2] < profile.pressure_profile[0]);
+        assert!(profile.wind_profile[2].0 > profile.wind_profile[0].0);
+        assert!((profiles[0].confidence - (-1.0f64).exp()).abs() < 1e-6);
+
+        let truth = database.ground_truth_in(&expected(target).coverage_area);
+        assert_eq!(truth.len(), 1);
+        assert_eq!(truth[0].measurement_id, "inside");
+    }
+
+    #[test]
+    fn test_archive_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("strip_archive_{}.jsonl", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let target = EPOCH + 400.0 * DAY;
+
+        let mut database = SatelliteStripDatabase::new();
+        assert_eq!(database.attach_archive(&path).unwrap(), 0);
+        database.archive_strip(strip("a", 4.9, target - 365.0 * DAY, 0.3), metadata(geometry(5.0, 35.0))).unwrap();
+        database.archive_strip(strip("b", 4.9, target - DAY, 0.4), metadata(geometry(9.0, 30.0))).unwrap();
+        assert!(database.archive_strip(strip("a", 4.9, target, 0.3), metadata(geometry(5.0, 35.0))).is_err());
+
+        let mut reloaded = SatelliteStripDatabase::new();
+        assert_eq!(reloaded.attach_archive(&path).unwrap(), 2);
+        assert_eq!(reloaded.strip_count(), 2);
+        let stored = &reloaded.strip_metadata["b"].acquisition_parameters;
+        assert_eq!(stored.viewing_geometry.viewing_angle, 9.0);
+        assert_eq!(reloaded.strip("b").unwrap().weather_conditions.wind_direction, 240.0);
+        let before: Vec<(String, f64)> =
+            database.rank_similar_strips(&expected(target), None).into_iter().map(|m| (m.strip_id, m.score)).collect();
+        let after: Vec<(String, f64)> =
+            reloaded.rank_similar_strips(&expected(target), None).into_iter().map(|m| (m.strip_id, m.score)).collect();
+        assert_eq!(before, after);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_archive_skips_corrupt_lines() {
+        let path = std::env::temp_dir().join(format!("strip_archive_corrupt_{}.jsonl", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let target = EPOCH + 400.0 * DAY;
+
+        let mut database = SatelliteStripDatabase::new();
+        database.attach_archive(&path).unwrap();
+        database.archive_strip(strip("a", 4.9, target - DAY, 0.3), metadata(geometry(5.0, 35.0))).unwrap();
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{{\"strip\": {{\"strip_id\": \"torn").unwrap();
+        drop(file);
+        database.archive_strip(strip("b", 4.9, target - 2.0 * DAY, 0.4), metadata(geometry(9.0, 30.0))).unwrap();
+
+        let mut reloaded = SatelliteStripDatabase::new();
+        assert_eq!(reloaded.attach_archive(&path).unwrap(), 2);
+        assert!(reloaded.strip("a").is_some() && reloaded.strip("b").is_some());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_archived_strips_are_calibrated_once() {
+        let mut calibration = metadata(geometry(5.0, 35.0)).calibration_information;
+        calibration.radiometric_calibration.calibration_coefficients = vec![2.0, 4.0];
+        calibration.radiometric_calibration.dark_current_correction = vec![0.1];
+        calibration.temporal_calibration.clock_drift_characteristics.drift_rate = 1e-5;
+        calibration.temporal_calibration.clock_drift_characteristics.aging_characteristics = 0.0;
+        let start = chrono::DateTime::from_timestamp((EPOCH - DAY) as i64, 0).unwrap();
+        let mut database = SatelliteStripDatabase::new();
+        database
+            .calibrations
+            .blocking_write()
+            .register("S2A", CalibrationSubmission { valid_from: start, valid_until: None, reference_temperature_c: 20.0, calibration })
+            .unwrap();
+
+        database.archive_strip(strip("raw", 4.9, EPOCH, 0.3), metadata(geometry(5.0, 35.0))).unwrap();
+        let calibrated = database.strip("raw").unwrap();
+        assert!((calibrated.image_data.data[0] - 0.4).abs() < 1e-12);
+        assert!((calibrated.image_data.data[1] - 1.0).abs() < 1e-12);
+        assert!((calibrated.timestamp - (EPOCH - 0.864)).abs() < 1e-6);
+        let step = database.strip_metadata["raw"].processing_history.last().unwrap();
+        assert_eq!(step.step_name, CALIBRATION_STEP);
+        assert_eq!(step.algorithm_version, "S2A@v1");
+        assert_eq!(database.strip_metadata["raw"].calibration_information.radiometric_calibration.calibration_coefficients, vec![2.0, 4.0]);
+
+        // A strip that already carries a calibration step is left alone
+        let (mut again, mut again_metadata) = (calibrated.clone(), database.strip_metadata["raw"].clone());
+        assert!(database.calibrations.blocking_read().calibrate_strip(&mut again, &mut again_metadata).is_none());
+        assert_eq!(again.image_data.data[0], calibrated.image_data.data[0]);
+
+        // Before the calibration's validity the strip is archived as acquired
+        database.archive_strip(strip("early", 4.9, EPOCH - 2.0 * DAY, 0.3), metadata(geometry(5.0, 35.0))).unwrap();
+        assert_eq!(database.strip("early").unwrap().image_data.data[0], 0.3);
+        assert!(database.strip_metadata["early"].processing_history.is_empty());
+    }
+
+    #[test]
+    fn test_verification_lays_acquisitions_out_on_the_expected_area() {
+        // Eight columns over 10-12 E, brightening eastwards
+        let acquired = |id: &str, timestamp: f64, offset: f64| {
+            let mut acquired = strip(id, 10.0, timestamp, 0.0);
+            acquired.ground_track = vec![Point2D { x: 10.0, y: 50.0 }, Point2D { x: 12.0, y: 51.0 }];
+            acquired.image_data = ImageData {
+                width: 8,
+                height: 4,
+                channels: 2,
+                data: (0..8 * 4 * 2).map(|k| offset + 0.05 * ((k / 2) % 8) as f64).collect(),
+                metadata: ImageMetadata { spatial_resolution: 10.0, ..acquired.image_data.metadata.clone() },
+            };
+            acquired
+        };
+        let mut database = SatelliteStripDatabase::new();
+        database.archive_strip(acquired("before", EPOCH - DAY, 0.0), metadata(geometry(5.0, 35.0))).unwrap();
+
+        // The western half was reconstructed exactly, on a grid of its own
+        let expected_strip = ExpectedSatelliteStrip {
+            coverage_area: GeoBounds { north: 51.0, south: 50.0, east: 11.0, west: 10.0 },
+            ..expected(EPOCH)
+        };
+        let result = StripReconstructionResult {
+            expected_strip,
+            reconstructed_image: ImageData {
+                width: 4,
+                height: 4,
+                channels: 2,
+                data: (0..4 * 4 * 2).map(|k| 0.1 + 0.05 * ((k / 2) % 4) as f64).collect(),
+                metadata: ImageMetadata { spectral_bands: vec!["R".to_string(), "NIR".to_string()], ..ImageMetadata::default() },
+            },
+            reconstruction_confidence: 0.8,
+            validation_score: 0.8,
+            pixel_level_fidelity: 0.8,
+            feature_level_fidelity: 0.8,
+            physical_consistency: 0.8,
+            temporal_consistency: 0.8,
+        };
+        let metrics = database.verify_strip_reconstruction(&result, &acquired("now", EPOCH, 0.1)).unwrap();
+        assert!(metrics.pixel_level_metrics.mean_squared_error < 1e-20);
+        assert!((metrics.temporal_consistency_metrics.temporal_interpolation_quality - 1.0).abs() < 1e-9);
+
+        let mut untracked = acquired("untracked", EPOCH, 0.1);
+        untracked.ground_track.clear();
+        assert!(database.verify_strip_reconstruction(&result, &untracked).is_err());
+    }
+}
@@ -0,0 +1,1156 @@
+//! Physically based synthesis of top-of-atmosphere imagery
+//!
+//! Renders Sentinel-2 MSI top-of-atmosphere reflectance from an `AtmosphericState`.
+//! A clear layer of Rayleigh and aerosol scattering sits above a cloud layer and a
+//! Lambertian surface; each layer is solved with the delta-Eddington two-stream
+//! approximation and the layers are coupled by the adding method. Cloud fields are
+//! procedural, anchored to the ground and advected with the wind at cloud level, so
+//! consecutive frames of a sequence stay consistent with each other.
+//!
+//! Profiles are read on 1 km levels from the surface: temperature in K, relative
+//! humidity as a fraction and pressure in Pa. Viewpoint positions are Earth-fixed
+//! in km, timestamps are Unix seconds and images are interleaved by pixel.
+
+use std::collections::HashMap;
+use std::io::{BufWriter, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use nalgebra::Vector3;
+use tiff::encoder::{colortype, TiffEncoder};
+use tiff::tags::Tag;
+
+use super::calibration_registry::timestamp_to_datetime;
+use super::{
+    AtmosphericLayer, AtmosphericLayerType, AtmosphericRenderer, AtmosphericRendering, AtmosphericState,
+    CloudParameters, GeneratedWeatherImage, GenerationMetadata, GeoBounds, ImageData, ImageMetadata,
+    OpticalProperties, PrecipitationParameters, RendererCapabilities, SatelliteTrajectory, SatelliteViewpoint,
+    ScatteringParameters, SynthesisMetadata, SynthesizedStrip, ThermalProperties, ValidationIssue,
+    ValidationIssueType, ValidationResult, ViewingGeometry, WeatherForecast, WeatherImageGenerator,
+    WeatherStripSynthesizer,
+};
+use crate::data_fusion::orbit_propagation::{Geodetic, LookAngles};
+use crate::data_fusion::solar_position_deg;
+use crate::error::AppError;
+
+const PROFILE_LEVEL_SPACING_M: f64 = 1_000.0;
+const STANDARD_PRESSURE_PA: f64 = 101_325.0;
+const METRES_PER_DEGREE_LATITUDE: f64 = 110_574.0;
+const METRES_PER_DEGREE_LONGITUDE: f64 = 111_320.0;
+const MIN_ORBIT_RADIUS_KM: f64 = 6_478.0;
+/// Cosine of the zenith angle that stands in for diffuse (hemispheric) light
+const DIFFUSE_MU: f64 = 1.0 / 1.66;
+const MAX_LAYER_OPTICAL_DEPTH: f64 = 300.0;
+const MAX_SHADOW_ZENITH_DEG: f64 = 85.0;
+const WATER_VAPOUR_TOP_M: f64 = 15_000.0;
+const MAX_WATER_VAPOUR_CM: f64 = 7.0;
+const TROPOPAUSE_M: f64 = 12_000.0;
+const AEROSOL_LAYER_TOP_M: f64 = 2_000.0;
+/// Extra cloud optical depth per sqrt(mm/h) of rain inside the precipitation extent
+const PRECIPITATION_DEPTH_PER_SQRT_RATE: f64 = 8.0;
+const NOISE_OCTAVES: u32 = 4;
+/// Two-term Henyey-Greenstein aerosol phase function: weight and asymmetry of the lobes
+const HG_FORWARD_WEIGHT: f64 = 0.9;
+const HG_BACKWARD_ASYMMETRY: f64 = -0.25;
+
+/// One MSI band with the optical constants the renderer needs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectralBand {
+    pub name: &'static str,
+    /// Generic name ("R", "NIR", ...) used by images that are not labelled per sensor
+    pub common_name: Option<&'static str>,
+    pub centre_nm: f64,
+    /// Exo-atmospheric solar irradiance, W m⁻² µm⁻¹
+    pub solar_irradiance: f64,
+    /// Ozone absorption coefficient per atm-cm
+    pub ozone_absorption: f64,
+    /// Water vapour absorption coefficient per g cm⁻²
+    pub water_vapour_absorption: f64,
+    /// Cloud droplet co-albedo per µm of effective radius
+    pub cloud_coalbedo_per_um: f64,
+}
+
+/// Sentinel-2 MSI bands in product order
+pub const SENTINEL2_BANDS: [SpectralBand; 13] = [
+    band("B01", None, 443.0, 1913.57, 0.003, 0.0, 0.0),
+    band("B02", Some("B"), 490.0, 1941.63, 0.021, 0.0, 0.0),
+    band("B03", Some("G"), 560.0, 1822.61, 0.104, 0.0, 0.0),
+    band("B04", Some("R"), 665.0, 1512.79, 0.051, 0.0, 0.0),
+    band("B05", None, 705.0, 1425.56, 0.021, 0.002, 0.0),
+    band("B06", None, 740.0, 1288.32, 0.010, 0.002, 0.0),
+    band("B07", None, 783.0, 1163.19, 0.007, 0.003, 0.0),
+    band("B08", Some("NIR"), 842.0, 1036.39, 0.002, 0.006, 0.0),
+    band("B8A", None, 865.0, 955.19, 0.001, 0.004, 0.0),
+    band("B09", None, 945.0, 813.04, 0.0, 0.30, 2e-6),
+    band("B10", None, 1375.0, 367.15, 0.0, 3.0, 3e-4),
+    band("B11", None, 1610.0, 245.59, 0.0, 0.002, 7e-4),
+    band("B12", None, 2190.0, 85.25, 0.0, 0.012, 2e-3),
+];
+
+const fn band(
+    name: &'static str,
+    common_name: Option<&'static str>,
+    centre_nm: f64,
+    solar_irradiance: f64,
+    ozone_absorption: f64,
+    water_vapour_absorption: f64,
+    cloud_coalbedo_per_um: f64,
+) -> SpectralBand {
+    SpectralBand { name, common_name, centre_nm, solar_irradiance, ozone_absorption, water_vapour_absorption, cloud_coalbedo_per_um }
+}
+
+/// Generic name of a Sentinel-2 band, e.g. "R" for "B04"
+pub fn sentinel2_common_name(band_name: &str) -> Option<&'static str> {
+    SENTINEL2_BANDS.iter().find(|b| b.name.eq_ignore_ascii_case(band_name)).and_then(|b| b.common_name)
+}
+
+/// Rayleigh optical depth at sea-level pressure (Hansen & Travis 1974)
+pub fn rayleigh_optical_depth(wavelength_nm: f64) -> f64 {
+    let l2 = (wavelength_nm / 1000.0).powi(-2);
+    0.008569 * l2 * l2 * (1.0 + 0.0113 * l2 + 0.00013 * l2 * l2)
+}
+
+/// Flux reflectance and transmittance of a homogeneous layer lit by a direct beam
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayerResponse {
+    pub reflectance: f64,
+    pub diffuse_transmittance: f64,
+    pub direct_transmittance: f64,
+}
+
+impl LayerResponse {
+    const TRANSPARENT: Self = Self { reflectance: 0.0, diffuse_transmittance: 0.0, direct_transmittance: 1.0 };
+
+    pub fn total_transmittance(&self) -> f64 {
+        self.diffuse_transmittance + self.direct_transmittance
+    }
+}
+
+/// Delta-Eddington two-stream solution for a layer (Meador & Weaver 1980)
+///
+/// `mu0` is the cosine of the incidence zenith angle. The direct transmittance is
+/// that of the delta-scaled layer, so it includes the forward-scattering peak.
+pub fn two_stream_layer(optical_depth: f64, single_scattering_albedo: f64, asymmetry: f64, mu0: f64) -> LayerResponse {
+    if optical_depth <= 1e-9 {
+        return LayerResponse::TRANSPARENT;
+    }
+    let omega0 = single_scattering_albedo.clamp(0.0, 1.0 - 1e-6);
+    let g0 = asymmetry.clamp(-0.99, 0.99);
+    let f = g0 * g0;
+    let tau = optical_depth.min(MAX_LAYER_OPTICAL_DEPTH) * (1.0 - omega0 * f);
+    let omega = omega0 * (1.0 - f) / (1.0 - omega0 * f);
+    let g = g0 / (1.0 + g0);
+
+    let gamma1 = (7.0 - omega * (4.0 + 3.0 * g)) / 4.0;
+    let gamma2 = -(1.0 - omega * (4.0 - 3.0 * g)) / 4.0;
+    let k = (gamma1 * gamma1 - gamma2 * gamma2).max(0.0).sqrt();
+    let mut mu = mu0.clamp(0.01, 1.0);
+    // The particular solution is singular where k·mu0 = 1
+    if (1.0 - k * k * mu * mu).abs() < 1e-6 {
+        mu *= 1.001;
+    }
+    let gamma3 = (2.0 - 3.0 * g * mu) / 4.0;
+    let gamma4 = 1.0 - gamma3;
+    let alpha1 = gamma1 * gamma4 + gamma2 * gamma3;
+    let alpha2 = gamma1 * gamma3 + gamma2 * gamma4;
+
+    // Both solutions are divided through by exp(kτ) so thick layers do not overflow
+    let direct = (-tau / mu).exp();
+    let (decay, decay2) = ((-k * tau).exp(), (-2.0 * k * tau).exp());
+    let denominator = (1.0 - k * k * mu * mu) * ((k + gamma1) + (k - gamma1) * decay2);
+    let reflectance = omega / denominator
+        * ((1.0 - k * mu) * (alpha2 + k * gamma3)
+            - (1.0 + k * mu) * (alpha2 - k * gamma3) * decay2
+            - 2.0 * k * (gamma3 - alpha2 * mu) * direct * decay);
+    let diffuse = direct
+        - omega / denominator
+            * ((1.0 + k * mu) * (alpha1 + k * gamma4) * direct
+                - (1.0 - k * mu) * (alpha1 - k * gamma4) * decay2 * direct
+                - 2.0 * k * (gamma4 + alpha1 * mu) * decay);
+    let reflectance = reflectance.clamp(0.0, 1.0);
+    LayerResponse {
+        reflectance,
+        diffuse_transmittance: diffuse.min(1.0 - reflectance - direct).max(0.0),
+        direct_transmittance: direct,
+    }
+}
+
+/// Settings of the TOA renderer
+#[derive(Debug, Clone)]
+pub struct ToaSynthesisConfig {
+    /// Pixels along each side of the square output raster
+    pub raster_size: usize,
+    /// Swath used when the viewpoint does not give an orbit to derive it from
+    pub default_swath_m: f64,
+    /// Scene centre used when the viewpoint has no usable position
+    pub scene_centre: Geodetic,
+    /// Surface reflectance per entry of `SENTINEL2_BANDS`, used where no reference image covers a band
+    pub surface_reflectance: Vec<f64>,
+    pub aerosol_angstrom_exponent: f64,
+    pub ozone_column_atm_cm: f64,
+    pub cloud_asymmetry: f64,
+    /// Size of the largest cloud features
+    pub cloud_length_scale_m: f64,
+    pub seed: u64,
+}
+
+impl Default for ToaSynthesisConfig {
+    fn default() -> Self {
+        Self {
+            raster_size: 64,
+            default_swath_m: 290_000.0,
+            scene_centre: Geodetic { latitude_deg: 0.0, longitude_deg: 0.0, height_km: 0.0 },
+            // Cropland in mid season
+            surface_reflectance: vec![0.04, 0.05, 0.08, 0.06, 0.12, 0.25, 0.30, 0.32, 0.33, 0.33, 0.30, 0.22, 0.12],
+            aerosol_angstrom_exponent: 1.3,
+            ozone_column_atm_cm: 0.3,
+            cloud_asymmetry: 0.85,
+            cloud_length_scale_m: 20_000.0,
+            seed: 0x5EED_C10D,
+        }
+    }
+}
+
+/// Where a scene lies and how it is lit and viewed
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneGeometry {
+    pub centre: Geodetic,
+    pub timestamp: f64,
+    pub swath_m: f64,
+    pub sun_zenith_deg: f64,
+    pub sun_azimuth_deg: f64,
+    pub view_zenith_deg: f64,
+    pub view_azimuth_deg: f64,
+}
+
+impl SceneGeometry {
+    pub fn is_daylight(&self) -> bool {
+        self.sun_zenith_deg < 90.0
+    }
+}
+
+/// A rendered scene with its georeferencing
+#[derive(Debug, Clone)]
+pub struct SyntheticScene {
+    /// TOA reflectance, one channel per entry of `SENTINEL2_BANDS`
+    pub image: ImageData,
+    pub bounds: GeoBounds,
+    pub geometry: SceneGeometry,
+    pub cloud_fraction: f64,
+    pub aerosol_optical_depth: f64,
+    pub water_vapour_cm: f64,
+    /// Share of plausibility checks on the input state that passed
+    pub physical_consistency: f64,
+}
+
+impl SyntheticScene {
+    fn band_index(&self, band: &str) -> Result<usize, AppError> {
+        self.image
+            .metadata
+            .spectral_bands
+            .iter()
+            .position(|b| b.eq_ignore_ascii_case(band) || sentinel2_common_name(b).is_some_and(|c| c.eq_ignore_ascii_case(band)))
+            .ok_or_else(|| AppError::not_found(format!("Band {} is not in the synthetic scene", band)))
+    }
+
+    /// One band as a 32-bit float GeoTIFF in WGS84 latitude/longitude
+    pub fn write_geotiff<W: Write + Seek>(&self, band: &str, writer: W) -> Result<(), AppError> {
+        let channel = self.band_index(band)?;
+        let tiff_error = |e: tiff::TiffError| AppError::internal(format!("Cannot write GeoTIFF: {}", e));
+        let (width, height, channels) = (self.image.width, self.image.height, self.image.channels);
+        let samples: Vec<f32> = (0..width * height).map(|p| self.image.data[p * channels + channel] as f32).collect();
+        let scale = [
+            (self.bounds.east - self.bounds.west) / width as f64,
+            (self.bounds.north - self.bounds.south) / height as f64,
+            0.0,
+        ];
+        // Model type geographic, raster pixel-is-area, WGS84
+        let geo_keys: [u16; 16] = [1, 1, 0, 3, 1024, 0, 1, 2, 1025, 0, 1, 1, 2048, 0, 1, 4326];
+
+        let mut encoder = TiffEncoder::new(writer).map_err(tiff_error)?;
+        let mut image = encoder.new_image::<colortype::Gray32Float>(width as u32, height as u32).map_err(tiff_error)?;
+        image.encoder().write_tag(Tag::ModelPixelScaleTag, &scale[..]).map_err(tiff_error)?;
+        image
+            .encoder()
+            .write_tag(Tag::ModelTiepointTag, &[0.0, 0.0, 0.0, self.bounds.west, self.bounds.north, 0.0][..])
+            .map_err(tiff_error)?;
+        image.encoder().write_tag(Tag::GeoKeyDirectoryTag, &geo_keys[..]).map_err(tiff_error)?;
+        image.encoder().write_tag(Tag::ImageDescription, self.image.metadata.spectral_bands[channel].as_str()).map_err(tiff_error)?;
+        image.write_data(&samples).map_err(tiff_error)
+    }
+
+    /// True-colour (B04, B03, B02) PNG with a linear stretch of reflectance 0–0.3
+    pub fn write_true_colour_png<W: Write>(&self, writer: W) -> Result<(), AppError> {
+        let channels = [self.band_index("B04")?, self.band_index("B03")?, self.band_index("B02")?];
+        let png_error = |e: png::EncodingError| AppError::internal(format!("Cannot write PNG: {}", e));
+        let (width, height) = (self.image.width, self.image.height);
+        let pixels: Vec<u8> = (0..width * height)
+            .flat_map(|p| channels.map(|c| (self.image.data[p * self.image.channels + c] / 0.3 * 255.0).clamp(0.0, 255.0).round() as u8))
+            .collect();
+
+        let mut encoder = png::Encoder::new(writer, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut png_writer = encoder.write_header().map_err(png_error)?;
+        png_writer.write_image_data(&pixels).map_err(png_error)
+    }
+
+    /// `<stem>_<band>.tif` for every band plus `<stem>_tci.png`, returning the paths written
+    pub fn write_files(&self, directory: &Path, stem: &str) -> Result<Vec<PathBuf>, AppError> {
+        let create = |path: &Path| {
+            std::fs::File::create(path)
+                .map(BufWriter::new)
+                .map_err(|e| AppError::internal(format!("Cannot create {}: {}", path.display(), e)))
+        };
+        let mut written = Vec::new();
+        for band in &self.image.metadata.spectral_bands {
+            let path = directory.join(format!("{}_{}.tif", stem, band));
+            self.write_geotiff(band, create(&path)?)?;
+            written.push(path);
+        }
+        let path = directory.join(format!("{}_tci.png", stem));
+        self.write_true_colour_png(create(&path)?)?;
+        written.push(path);
+        Ok(written)
+    }
+}
+
+/// CPU renderer of Sentinel-2 TOA reflectance from atmospheric states
+#[derive(Debug, Clone, Default)]
+pub struct ToaImageSynthesizer {
+    pub config: ToaSynthesisConfig,
+    /// Surface reflectance image, e.g. the latest clear strip over the scene
+    surface_reference: Option<ImageData>,
+}
+
+impl ToaImageSynthesizer {
+    pub const GENERATOR_NAME: &'static str = "two_stream_toa";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: ToaSynthesisConfig) -> Self {
+        Self { config, surface_reference: None }
+    }
+
+    /// Use `image` as surface reflectance, matching bands by Sentinel-2 or generic name
+    pub fn with_surface_reference(mut self, image: ImageData) -> Self {
+        self.surface_reference = Some(image);
+        self
+    }
+
+    /// Scene under a nadir-pointing sensor: its sub-satellite point, lit by the sun at `timestamp`
+    pub fn scene_from_viewpoint(&self, viewpoint: &SatelliteViewpoint, timestamp: f64) -> SceneGeometry {
+        let ecef = Vector3::new(viewpoint.position.x, viewpoint.position.y, viewpoint.position.z);
+        let (centre, view_zenith_deg, view_azimuth_deg, swath_m) = if ecef.norm() > MIN_ORBIT_RADIUS_KM {
+            let sub_satellite = Geodetic::from_ecef(&ecef);
+            let centre = Geodetic { height_km: 0.0, ..sub_satellite };
+            let look = LookAngles::between(&centre, &ecef);
+            let swath_m = if viewpoint.field_of_view > 0.0 && viewpoint.field_of_view < 180.0 {
+                2.0 * sub_satellite.height_km * 1000.0 * (viewpoint.field_of_view / 2.0).to_radians().tan()
+            } else {
+                self.config.default_swath_m
+            };
+            (centre, 90.0 - look.elevation_deg, look.azimuth_deg, swath_m)
+        } else {
+            (self.config.scene_centre, 0.0, 0.0, self.config.default_swath_m)
+        };
+        let (sun_elevation, sun_azimuth_deg) = solar_position_deg(&centre, timestamp_to_datetime(timestamp));
+        SceneGeometry {
+            centre,
+            timestamp,
+            swath_m,
+            sun_zenith_deg: 90.0 - sun_elevation,
+            sun_azimuth_deg,
+            view_zenith_deg,
+            view_azimuth_deg,
+        }
+    }
+
+    /// Render TOA reflectance; scenes with the sun below the horizon come back dark
+    pub fn render_scene(&self, state: &AtmosphericState, geometry: &SceneGeometry) -> SyntheticScene {
+        let n = self.config.raster_size.max(1);
+        let pixel_m = geometry.swath_m.max(1.0) / n as f64;
+        let metres_per_degree_lon = METRES_PER_DEGREE_LONGITUDE * geometry.centre.latitude_deg.to_radians().cos().max(0.01);
+        let origin = (
+            geometry.centre.longitude_deg * metres_per_degree_lon,
+            geometry.centre.latitude_deg * METRES_PER_DEGREE_LATITUDE,
+        );
+        let half_extent = n as f64 * pixel_m / 2.0;
+        let bounds = GeoBounds {
+            north: geometry.centre.latitude_deg + half_extent / METRES_PER_DEGREE_LATITUDE,
+            south: geometry.centre.latitude_deg - half_extent / METRES_PER_DEGREE_LATITUDE,
+            east: geometry.centre.longitude_deg + half_extent / metres_per_degree_lon,
+            west: geometry.centre.longitude_deg - half_extent / metres_per_degree_lon,
+        };
+        let pixel_position = |p: usize| {
+            let (row, col) = (p / n, p % n);
+            (origin.0 + (col as f64 + 0.5) * pixel_m - half_extent, origin.1 + half_extent - (row as f64 + 0.5) * pixel_m)
+        };
+
+        let cloud = CloudField::new(state, &self.config, geometry.timestamp, metres_per_degree_lon, (0..n * n).map(pixel_position));
+        let clouds = &state.cloud_parameters;
+        let cloud_height = ((clouds.cloud_base_height + clouds.cloud_top_height) / 2.0).max(0.0);
+        // Sun rays reach the ground through the cloud towards the sun; the sensor looks through the cloud towards itself
+        let offset = |zenith_deg: f64, azimuth_deg: f64| {
+            let distance = cloud_height * zenith_deg.clamp(0.0, MAX_SHADOW_ZENITH_DEG).to_radians().tan();
+            let azimuth = azimuth_deg.to_radians();
+            (distance * azimuth.sin(), distance * azimuth.cos())
+        };
+        let sun_offset = offset(geometry.sun_zenith_deg, geometry.sun_azimuth_deg);
+        let view_offset = offset(geometry.view_zenith_deg, geometry.view_azimuth_deg);
+
+        let aerosol_optical_depth = state.scattering_parameters.aerosol_optical_depth.max(0.0);
+        let (water_vapour_cm, water_vapour_above_cloud_cm) = water_vapour_columns(state, clouds.cloud_top_height);
+        let bands = SENTINEL2_BANDS.len();
+        let mut data = vec![0.0; n * n * bands];
+        let mut cloudy_pixels = 0;
+
+        if geometry.is_daylight() {
+            let mu0 = geometry.sun_zenith_deg.to_radians().cos().max(0.01);
+            let mu_v = geometry.view_zenith_deg.clamp(0.0, 89.0).to_radians().cos();
+            let air_mass = 1.0 / mu0 + 1.0 / mu_v;
+            let cos_scattering = -mu0 * mu_v
+                - (1.0 - mu0 * mu0).sqrt()
+                    * (1.0 - mu_v * mu_v).sqrt()
+                    * (geometry.sun_azimuth_deg - geometry.view_azimuth_deg).to_radians().cos();
+            let clear: Vec<ClearSky> = SENTINEL2_BANDS
+                .iter()
+                .map(|band| self.clear_sky(state, band, mu0, mu_v, cos_scattering))
+                .collect();
+            let surface = self.surface_bands(n);
+
+            for p in 0..n * n {
+                let (x, y) = pixel_position(p);
+                let tau_view = cloud.optical_depth(x + view_offset.0, y + view_offset.1);
+                let tau_sun = cloud.optical_depth(x + sun_offset.0, y + sun_offset.1);
+                let tau_overhead = cloud.optical_depth(x, y);
+                if tau_view > 0.0 {
+                    cloudy_pixels += 1;
+                }
+                // Water vapour above the reflecting level: the cloud top where it is opaque, the ground otherwise
+                let opacity = 1.0 - (-tau_view).exp();
+                let water_vapour = opacity * water_vapour_above_cloud_cm + (1.0 - opacity) * water_vapour_cm;
+
+                for (b, band) in SENTINEL2_BANDS.iter().enumerate() {
+                    let rho_s = surface[b].as_ref().map_or(self.config.surface_reflectance.get(b).copied().unwrap_or(0.0), |s| s[p]);
+                    let omega = 1.0 - band.cloud_coalbedo_per_um * clouds.cloud_particle_size.max(0.0);
+                    let g = self.config.cloud_asymmetry;
+                    let seen = two_stream_layer(tau_view, omega, g, mu0);
+                    let lit = two_stream_layer(tau_sun, omega, g, mu0);
+                    let up = two_stream_layer(tau_view, omega, g, mu_v);
+                    let diffuse = two_stream_layer(tau_overhead, omega, g, DIFFUSE_MU);
+
+                    // Cloud over surface, for the direct beam and for diffuse light from above
+                    let trapping = 1.0 / (1.0 - diffuse.reflectance * rho_s);
+                    let lower_direct = seen.reflectance + lit.total_transmittance() * rho_s * up.total_transmittance() * trapping;
+                    let lower_diffuse = diffuse.reflectance + diffuse.total_transmittance().powi(2) * rho_s * trapping;
+
+                    let sky = &clear[b];
+                    let lower = sky.sun.direct_transmittance * lower_direct + sky.sun.diffuse_transmittance * lower_diffuse;
+                    let toa = sky.path_reflectance
+                        + lower * sky.view.total_transmittance() / (1.0 - sky.diffuse.reflectance * lower_diffuse);
+                    let gas = (-(band.ozone_absorption * self.config.ozone_column_atm_cm
+                        + band.water_vapour_absorption * water_vapour)
+                        * air_mass)
+                        .exp();
+                    data[p * bands + b] = toa * gas;
+                }
+            }
+        }
+
+        let cloud_fraction = cloudy_pixels as f64 / (n * n) as f64;
+        let image = ImageData {
+            width: n,
+            height: n,
+            channels: bands,
+            data,
+            metadata: ImageMetadata {
+                acquisition_time: timestamp_to_datetime(geometry.timestamp),
+                satellite_id: "SYNTHETIC".to_string(),
+                sensor_type: "MSI".to_string(),
+                spectral_bands: SENTINEL2_BANDS.iter().map(|b| b.name.to_string()).collect(),
+                spatial_resolution: pixel_m,
+                cloud_coverage: cloud_fraction,
+            },
+        };
+        SyntheticScene {
+            image,
+            bounds,
+            geometry: geometry.clone(),
+            cloud_fraction,
+            aerosol_optical_depth,
+            water_vapour_cm,
+            physical_consistency: physical_consistency(state).0,
+        }
+    }
+
+    /// Clear-sky layer of Rayleigh and aerosol scattering for one band
+    fn clear_sky(&self, state: &AtmosphericState, band: &SpectralBand, mu0: f64, mu_v: f64, cos_scattering: f64) -> ClearSky {
+        let scattering = &state.scattering_parameters;
+        let tau_r = rayleigh_optical_depth(band.centre_nm) * surface_pressure(state) / STANDARD_PRESSURE_PA;
+        let tau_a = scattering.aerosol_optical_depth.max(0.0)
+            * (band.centre_nm / 550.0).powf(-self.config.aerosol_angstrom_exponent);
+        let omega_a = scattering.single_scattering_albedo.clamp(0.0, 1.0);
+        let g_a = scattering.asymmetry_parameter.clamp(-0.99, 0.99);
+        let tau = tau_r + tau_a;
+        let scattered = tau_r + omega_a * tau_a;
+        let omega = scattered / tau;
+        let g = omega_a * tau_a * g_a / scattered.max(f64::EPSILON);
+
+        let sun = two_stream_layer(tau, omega, g, mu0);
+        let view = two_stream_layer(tau, omega, g, mu_v);
+        let diffuse = two_stream_layer(tau, omega, g, DIFFUSE_MU);
+
+        // Single scattering with the full phase functions, plus the multiple scattering the
+        // two-stream solution adds on top of its own (isotropic) single-scattering estimate
+        let rayleigh_phase = 0.75 * (1.0 + cos_scattering * cos_scattering);
+        let aerosol_phase = aerosol_phase_function(scattering, cos_scattering);
+        let attenuated = |mu: f64| (1.0 - (-tau * (1.0 / mu0 + 1.0 / mu)).exp()) / (mu0 + mu);
+        let single_scattering = (tau_r * rayleigh_phase + omega_a * tau_a * aerosol_phase) / tau * attenuated(mu_v) / 4.0;
+        let backscatter = (0.5 * tau_r + omega_a * tau_a * (1.0 - g_a) / 2.0) / tau;
+        let single_scattering_flux = backscatter * DIFFUSE_MU * attenuated(DIFFUSE_MU);
+        let path_reflectance = single_scattering + (sun.reflectance - single_scattering_flux).max(0.0);
+        ClearSky { sun, view, diffuse, path_reflectance }
+    }
+
+    /// Reference surface reflectance resampled onto the raster, per band where available
+    fn surface_bands(&self, n: usize) -> Vec<Option<Vec<f64>>> {
+        let Some(image) = self.surface_reference.as_ref().filter(|i| {
+            i.width > 0 && i.height > 0 && i.data.len() >= i.width * i.height * i.channels
+        }) else {
+            return vec![None; SENTINEL2_BANDS.len()];
+        };
+        SENTINEL2_BANDS
+            .iter()
+            .map(|band| {
+                let channel = image.metadata.spectral_bands.iter().position(|b| {
+                    b.eq_ignore_ascii_case(band.name) || band.common_name.is_some_and(|c| b.eq_ignore_ascii_case(c))
+                })?;
+                (channel < image.channels).then(|| {
+                    (0..n * n)
+                        .map(|p| {
+                            let row = (((p / n) as f64 + 0.5) * image.height as f64 / n as f64) as usize;
+                            let col = (((p % n) as f64 + 0.5) * image.width as f64 / n as f64) as usize;
+                            let index = row.min(image.height - 1) * image.width + col.min(image.width - 1);
+                            image.data[index * image.channels + channel].clamp(0.0, 1.0)
+                        })
+                        .collect()
+                })
+            })
+            .collect()
+    }
+
+    fn generated_image(
+        &self,
+        state: &AtmosphericState,
+        viewpoint: &SatelliteViewpoint,
+        geometry: &SceneGeometry,
+    ) -> GeneratedWeatherImage {
+        let started = Instant::now();
+        let scene = self.render_scene(state, geometry);
+        let (consistency, issues) = physical_consistency(state);
+        let confidence = consistency * sun_confidence(geometry);
+        let quality_metrics = HashMap::from([
+            ("cloud_fraction".to_string(), scene.cloud_fraction),
+            ("aerosol_optical_depth".to_string(), scene.aerosol_optical_depth),
+            ("water_vapour_cm".to_string(), scene.water_vapour_cm),
+            ("sun_zenith_deg".to_string(), geometry.sun_zenith_deg),
+            ("view_zenith_deg".to_string(), geometry.view_zenith_deg),
+            ("ground_sample_distance_m".to_string(), scene.image.metadata.spatial_resolution),
+        ]);
+        let memory_usage = scene.image.data.len() * std::mem::size_of::<f64>();
+        GeneratedWeatherImage {
+            timestamp: geometry.timestamp,
+            satellite_viewpoint: viewpoint.clone(),
+            atmospheric_state: state.clone(),
+            composite_image: scene.image,
+            // Every band is already a channel of the composite
+            spectral_bands: HashMap::new(),
+            generation_confidence: confidence,
+            physical_consistency_score: consistency,
+            generation_metadata: GenerationMetadata {
+                generation_algorithm: Self::GENERATOR_NAME.to_string(),
+                computation_time: started.elapsed(),
+                memory_usage,
+                quality_metrics,
+                validation_results: vec![ValidationResult {
+                    is_valid: issues.is_empty(),
+                    validation_score: consistency,
+                    issues_identified: issues,
+                    recommendations: Vec::new(),
+                }],
+            },
+        }
+    }
+}
+
+impl WeatherImageGenerator for ToaImageSynthesizer {
+    fn generate_weather_image(
+        &self,
+        atmospheric_state: &AtmosphericState,
+        satellite_viewpoint: &SatelliteViewpoint,
+        timestamp: f64,
+    ) -> GeneratedWeatherImage {
+        let geometry = self.scene_from_viewpoint(satellite_viewpoint, timestamp);
+        self.generated_image(atmospheric_state, satellite_viewpoint, &geometry)
+    }
+
+    /// Daylight frames only; clouds move with the wind between frames
+    fn generate_weather_sequence(
+        &self,
+        initial_state: &AtmosphericState,
+        satellite_trajectory: &SatelliteTrajectory,
+        time_sequence: &[f64],
+    ) -> Vec<GeneratedWeatherImage> {
+        time_sequence
+            .iter()
+            .filter_map(|&timestamp| {
+                let viewpoint = satellite_trajectory.get_viewpoint_at_time(timestamp);
+                let geometry = self.scene_from_viewpoint(&viewpoint, timestamp);
+                geometry.is_daylight().then(|| self.generated_image(initial_state, &viewpoint, &geometry))
+            })
+            .collect()
+    }
+}
+
+impl AtmosphericRenderer for ToaImageSynthesizer {
+    /// Layer optics at 550 nm
+    fn render_atmosphere(&self, state: &AtmosphericState, _viewpoint: &SatelliteViewpoint) -> AtmosphericRendering {
+        let scattering = &state.scattering_parameters;
+        let clouds = &state.cloud_parameters;
+        let layer = |layer_type, bottom: f64, top: f64, optical_depth: f64, albedo: f64, asymmetry: f64| {
+            let thickness = (top - bottom).max(1.0);
+            let extinction = optical_depth / thickness;
+            let temperature = profile_temperature(state, (bottom + top) / 2.0);
+            AtmosphericLayer {
+                layer_type,
+                altitude_range: (bottom, top),
+                optical_properties: OpticalProperties {
+                    extinction_coefficient: extinction,
+                    scattering_coefficient: extinction * albedo,
+                    absorption_coefficient: extinction * (1.0 - albedo),
+                    asymmetry_parameter: asymmetry,
+                    single_scattering_albedo: albedo,
+                },
+                thermal_properties: ThermalProperties {
+                    temperature,
+                    thermal_emission: 5.670_374e-8 * temperature.powi(4),
+                    heat_capacity: 1004.0,
+                    thermal_conductivity: 0.025,
+                },
+            }
+        };
+
+        let rayleigh = rayleigh_optical_depth(550.0) * surface_pressure(state) / STANDARD_PRESSURE_PA;
+        let aerosol = scattering.aerosol_optical_depth.max(0.0);
+        let cloud = clouds.cloud_fraction.clamp(0.0, 1.0) * clouds.cloud_optical_depth.max(0.0);
+        let mut rendered_layers = vec![
+            layer(AtmosphericLayerType::Troposphere, 0.0, TROPOPAUSE_M, rayleigh, 1.0, 0.0),
+            layer(
+                AtmosphericLayerType::AerosolLayer,
+                0.0,
+                AEROSOL_LAYER_TOP_M,
+                aerosol,
+                scattering.single_scattering_albedo.clamp(0.0, 1.0),
+                scattering.asymmetry_parameter,
+            ),
+        ];
+        if cloud > 0.0 {
+            rendered_layers.push(layer(
+                AtmosphericLayerType::CloudLayer,
+                clouds.cloud_base_height,
+                clouds.cloud_top_height,
+                clouds.cloud_optical_depth,
+                1.0,
+                self.config.cloud_asymmetry,
+            ));
+        }
+        let total_optical_depth = rayleigh + aerosol + cloud;
+        let effective_temperature = [(rayleigh, &rendered_layers[0]), (aerosol, &rendered_layers[1])]
+            .into_iter()
+            .chain(rendered_layers.get(2).map(|l| (cloud, l)))
+            .map(|(depth, l)| depth * l.thermal_properties.temperature)
+            .sum::<f64>()
+            / total_optical_depth;
+        AtmosphericRendering {
+            rendered_layers,
+            total_optical_depth,
+            effective_temperature,
+            rendering_quality: physical_consistency(state).0,
+        }
+    }
+
+    fn get_renderer_capabilities(&self) -> RendererCapabilities {
+        RendererCapabilities {
+            supported_wavelengths: SENTINEL2_BANDS.iter().map(|b| b.centre_nm).collect(),
+            spatial_resolution_range: (10.0, self.config.default_swath_m / 4.0),
+            temporal_resolution_range: (Duration::from_secs(1), Duration::from_secs(86_400)),
+            atmospheric_phenomena: [
+                "rayleigh_scattering",
+                "aerosol_scattering",
+                "cloud",
+                "cloud_shadow",
+                "precipitation",
+                "water_vapour_absorption",
+                "ozone_absorption",
+            ]
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
+        }
+    }
+}
+
+impl WeatherStripSynthesizer for ToaImageSynthesizer {
+    /// Strip for the forecast at its first time step, centred on the mean forecast location
+    fn synthesize_weather_strip(&self, weather_data: &WeatherForecast, viewing_geometry: &ViewingGeometry) -> SynthesizedStrip {
+        let points = &weather_data.forecast_points;
+        let start = points.iter().map(|p| p.timestamp).fold(f64::INFINITY, f64::min);
+        let current: Vec<_> = points.iter().filter(|p| p.timestamp == start).collect();
+        let parameter = |names: &[&str]| {
+            let values: Vec<f64> = current
+                .iter()
+                .filter_map(|p| names.iter().find_map(|n| p.weather_parameters.get(*n)))
+                .copied()
+                .filter(|v| v.is_finite())
+                .collect();
+            (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+        };
+        let inputs = [
+            parameter(&["cloud_cover"]),
+            parameter(&["precipitation"]),
+            parameter(&["humidity"]),
+            parameter(&["temperature"]),
+            parameter(&["pressure"]),
+            parameter(&["aerosol_optical_depth", "visibility"]),
+        ];
+        let state = state_from_forecast(&inputs, parameter(&["aerosol_optical_depth"]), parameter(&["visibility"]), parameter(&["cloud_optical_depth"]));
+
+        let count = current.len().max(1) as f64;
+        let centre = Geodetic {
+            latitude_deg: current.iter().map(|p| p.location.y).sum::<f64>() / count,
+            longitude_deg: current.iter().map(|p| p.location.x).sum::<f64>() / count,
+            height_km: 0.0,
+        };
+        let geometry = SceneGeometry {
+            centre,
+            timestamp: if start.is_finite() { start } else { 0.0 },
+            swath_m: self.config.default_swath_m,
+            sun_zenith_deg: 90.0 - viewing_geometry.sun_elevation,
+            sun_azimuth_deg: viewing_geometry.sun_azimuth,
+            view_zenith_deg: viewing_geometry.viewing_angle.abs(),
+            view_azimuth_deg: viewing_geometry.azimuth_angle,
+        };
+        let scene = self.render_scene(&state, &geometry);
+
+        let weather_fidelity = if current.is_empty() {
+            0.0
+        } else {
+            inputs.iter().filter(|v| v.is_some()).count() as f64 / inputs.len() as f64
+        };
+        let validation_metrics = SENTINEL2_BANDS
+            .iter()
+            .enumerate()
+            .map(|(b, band)| {
+                let channel = scene.image.data.iter().skip(b).step_by(scene.image.channels);
+                (format!("mean_reflectance_{}", band.name), channel.sum::<f64>() / (scene.image.width * scene.image.height) as f64)
+            })
+            .chain([("cloud_fraction".to_string(), scene.cloud_fraction)])
+            .collect();
+        SynthesizedStrip {
+            synthesis_confidence: scene.physical_consistency * weather_fidelity * sun_confidence(&geometry),
+            weather_fidelity,
+            temporal_consistency: weather_data.forecast_skill_metrics.skill_score.clamp(0.0, 1.0),
+            synthesis_metadata: SynthesisMetadata {
+                synthesis_method: Self::GENERATOR_NAME.to_string(),
+                input_data_quality: weather_fidelity,
+                processing_parameters: HashMap::from([
+                    ("aerosol_optical_depth".to_string(), scene.aerosol_optical_depth),
+                    ("water_vapour_cm".to_string(), scene.water_vapour_cm),
+                    ("cloud_fraction".to_string(), state.cloud_parameters.cloud_fraction),
+                    ("cloud_optical_depth".to_string(), state.cloud_parameters.cloud_optical_depth),
+                    ("sun_zenith_deg".to_string(), geometry.sun_zenith_deg),
+                    ("view_zenith_deg".to_string(), geometry.view_zenith_deg),
+                    ("ground_sample_distance_m".to_string(), scene.image.metadata.spatial_resolution),
+                ]),
+                validation_metrics,
+            },
+            synthesized_image: scene.image,
+        }
+    }
+
+    /// Synthesis confidence scaled by the share of pixels holding a plausible reflectance
+    fn validate_synthesis_quality(&self, strip: &SynthesizedStrip) -> f64 {
+        let image = &strip.synthesized_image;
+        if image.channels != SENTINEL2_BANDS.len() || image.data.is_empty() || image.data.len() != image.width * image.height * image.channels {
+            return 0.0;
+        }
+        let plausible = image.data.iter().filter(|v| v.is_finite() && (0.0..=1.6).contains(*v)).count();
+        strip.synthesis_confidence * plausible as f64 / image.data.len() as f64
+    }
+}
+
+struct ClearSky {
+    sun: LayerResponse,
+    view: LayerResponse,
+    diffuse: LayerResponse,
+    path_reflectance: f64,
+}
+
+/// Procedural cloud optical depth, thresholded to the state's cloud fraction over the scene
+struct CloudField {
+    seed: u64,
+    scale_m: f64,
+    drift: (f64, f64),
+    threshold: f64,
+    depth_per_excess: f64,
+    precipitation: Option<(GeoBounds, f64)>,
+    metres_per_degree_lon: f64,
+}
+
+impl CloudField {
+    fn new(
+        state: &AtmosphericState,
+        config: &ToaSynthesisConfig,
+        timestamp: f64,
+        metres_per_degree_lon: f64,
+        samples: impl Iterator<Item = (f64, f64)>,
+    ) -> Self {
+        let clouds = &state.cloud_parameters;
+        // Wind is (speed m/s, direction it blows from in degrees) on the profile levels
+        let level = ((clouds.cloud_base_height + clouds.cloud_top_height) / 2.0 / PROFILE_LEVEL_SPACING_M).round().max(0.0) as usize;
+        let (speed, from) = state.wind_profile.get(level.min(state.wind_profile.len().saturating_sub(1))).copied().unwrap_or((0.0, 0.0));
+        let from = from.to_radians();
+        let mut field = Self {
+            seed: config.seed,
+            scale_m: config.cloud_length_scale_m.max(1.0),
+            drift: (-speed * from.sin() * timestamp, -speed * from.cos() * timestamp),
+            threshold: f64::INFINITY,
+            depth_per_excess: 0.0,
+            precipitation: None,
+            metres_per_degree_lon,
+        };
+
+        let fraction = clouds.cloud_fraction.clamp(0.0, 1.0);
+        if fraction > 0.0 && clouds.cloud_optical_depth > 0.0 {
+            let mut density: Vec<f64> = samples.map(|(x, y)| field.density(x, y)).collect();
+            density.sort_by(f64::total_cmp);
+            if let Some(&lowest) = density.first() {
+                let cut = ((1.0 - fraction) * density.len() as f64) as usize;
+                field.threshold = density.get(cut).map_or(lowest, |&d| d) - 1e-9;
+                let excess: Vec<f64> = density.iter().map(|d| d - field.threshold).filter(|e| *e > 0.0).collect();
+                let mean_excess = excess.iter().sum::<f64>() / excess.len().max(1) as f64;
+                field.depth_per_excess = clouds.cloud_optical_depth / mean_excess.max(1e-9);
+            }
+        }
+        let rain = &state.precipitation_parameters;
+        if rain.precipitation_rate > 0.0 {
+            field.precipitation = Some((rain.precipitation_extent.clone(), PRECIPITATION_DEPTH_PER_SQRT_RATE * rain.precipitation_rate.sqrt()));
+        }
+        field
+    }
+
+    fn density(&self, x: f64, y: f64) -> f64 {
+        let (x, y) = ((x - self.drift.0) / self.scale_m, (y - self.drift.1) / self.scale_m);
+        let (mut total, mut norm) = (0.0, 0.0);
+        for octave in 0..NOISE_OCTAVES {
+            let frequency = (1 << octave) as f64;
+            let amplitude = 1.0 / frequency;
+            total += amplitude * value_noise(self.seed.wrapping_add(octave as u64), x * frequency, y * frequency);
+            norm += amplitude;
+        }
+        total / norm
+    }
+
+    fn optical_depth(&self, x: f64, y: f64) -> f64 {
+        let excess = self.density(x, y) - self.threshold;
+        if excess <= 0.0 {
+            return 0.0;
+        }
+        let rain = self.precipitation.as_ref().map_or(0.0, |(extent, depth)| {
+            let (lat, lon) = (y / METRES_PER_DEGREE_LATITUDE, x / self.metres_per_degree_lon);
+            let unbounded = extent.north <= extent.south;
+            let inside = lat <= extent.north && lat >= extent.south && lon <= extent.east && lon >= extent.west;
+            if unbounded || inside { *depth } else { 0.0 }
+        });
+        excess * self.depth_per_excess + rain
+    }
+}
+
+/// Smooth value noise in [0, 1) on the unit lattice
+fn value_noise(seed: u64, x: f64, y: f64) -> f64 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let smooth = |t: f64| t * t * (3.0 - 2.0 * t);
+    let (tx, ty) = (smooth(x - x0), smooth(y - y0));
+    let (i, j) = (x0 as i64, y0 as i64);
+    let corner = |di: i64, dj: i64| lattice_value(seed, i + di, j + dj);
+    let top = corner(0, 0) + tx * (corner(1, 0) - corner(0, 0));
+    let bottom = corner(0, 1) + tx * (corner(1, 1) - corner(0, 1));
+    top + ty * (bottom - top)
+}
+
+fn lattice_value(seed: u64, i: i64, j: i64) -> f64 {
+    // SplitMix64 finaliser over the seeded lattice coordinates
+    let mut z = seed ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (j as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Aerosol phase function at a scattering angle, normalised to a mean of one over the sphere
+///
+/// Uses the tabulated `phase_function` (evenly spaced from 0° to 180°) when one is given,
+/// otherwise a two-term Henyey-Greenstein function whose weak backward lobe keeps the
+/// backscatter of real aerosols that a single lobe misses.
+fn aerosol_phase_function(scattering: &ScatteringParameters, cos_scattering: f64) -> f64 {
+    let table = &scattering.phase_function;
+    if table.len() >= 2 {
+        let position = cos_scattering.clamp(-1.0, 1.0).acos() / std::f64::consts::PI * (table.len() - 1) as f64;
+        let lower = (position.floor() as usize).min(table.len() - 2);
+        let t = position - lower as f64;
+        return (table[lower] + t * (table[lower + 1] - table[lower])).max(0.0);
+    }
+    let henyey_greenstein = |g: f64| (1.0 - g * g) / (1.0 + g * g - 2.0 * g * cos_scattering).powf(1.5);
+    let forward = ((scattering.asymmetry_parameter - (1.0 - HG_FORWARD_WEIGHT) * HG_BACKWARD_ASYMMETRY) / HG_FORWARD_WEIGHT)
+        .clamp(-0.99, 0.99);
+    HG_FORWARD_WEIGHT * henyey_greenstein(forward) + (1.0 - HG_FORWARD_WEIGHT) * henyey_greenstein(HG_BACKWARD_ASYMMETRY)
+}
+
+fn surface_pressure(state: &AtmosphericState) -> f64 {
+    state
+        .pressure_profile
+        .first()
+        .copied()
+        .filter(|p| (30_000.0..=110_000.0).contains(p))
+        .unwrap_or(STANDARD_PRESSURE_PA)
+}
+
+/// Temperature at `altitude_m`, falling back to the standard lapse rate above the profile
+fn profile_temperature(state: &AtmosphericState, altitude_m: f64) -> f64 {
+    let level = (altitude_m / PROFILE_LEVEL_SPACING_M).round().max(0.0) as usize;
+    state
+        .temperature_profile
+        .get(level)
+        .copied()
+        .filter(|t| *t > 0.0)
+        .unwrap_or(288.15 - 0.0065 * altitude_m.min(11_000.0))
+}
+
+/// Precipitable water of the whole column and of the part above `cloud_top_m`, in cm
+fn water_vapour_columns(state: &AtmosphericState, cloud_top_m: f64) -> (f64, f64) {
+    let (mut total, mut above) = (0.0, 0.0);
+    for (level, (&temperature, &humidity)) in state.temperature_profile.iter().zip(&state.humidity_profile).enumerate() {
+        let altitude = level as f64 * PROFILE_LEVEL_SPACING_M;
+        if altitude > WATER_VAPOUR_TOP_M || temperature <= 0.0 {
+            break;
+        }
+        // Magnus saturation vapour pressure, Pa, and vapour density, kg m⁻³
+        let saturation = 611.2 * (17.67 * (temperature - 273.15) / (temperature - 29.65)).exp();
+        let density = humidity.clamp(0.0, 1.0) * saturation / (461.5 * temperature);
+        let column_g_cm2 = density * PROFILE_LEVEL_SPACING_M / 10.0;
+        total += column_g_cm2;
+        if altitude >= cloud_top_m {
+            above += column_g_cm2;
+        }
+    }
+    (total.min(MAX_WATER_VAPOUR_CM), above.min(MAX_WATER_VAPOUR_CM))
+}
+
+/// Share of plausibility checks passed, with an issue for each failure
+fn physical_consistency(state: &AtmosphericState) -> (f64, Vec<ValidationIssue>) {
+    let clouds = &state.cloud_parameters;
+    let scattering = &state.scattering_parameters;
+    let checks = [
+        (state.temperature_profile.iter().all(|t| (150.0..=350.0).contains(t)), "temperature_profile", "Temperatures outside 150-350 K"),
+        (state.humidity_profile.iter().all(|h| (0.0..=1.0).contains(h)), "humidity_profile", "Relative humidity outside 0-1"),
+        (state.pressure_profile.windows(2).all(|w| w[1] <= w[0]), "pressure_profile", "Pressure increases with height"),
+        ((0.0..=1.0).contains(&clouds.cloud_fraction), "cloud_parameters", "Cloud fraction outside 0-1"),
+        (clouds.cloud_base_height <= clouds.cloud_top_height, "cloud_parameters", "Cloud base above cloud top"),
+        (clouds.cloud_optical_depth >= 0.0, "cloud_parameters", "Negative cloud optical depth"),
+        ((0.0..=5.0).contains(&scattering.aerosol_optical_depth), "scattering_parameters", "Aerosol optical depth outside 0-5"),
+        ((0.0..=1.0).contains(&scattering.single_scattering_albedo), "scattering_parameters", "Single-scattering albedo outside 0-1"),
+        (state.precipitation_parameters.precipitation_rate >= 0.0, "precipitation_parameters", "Negative precipitation rate"),
+    ];
+    let issues: Vec<ValidationIssue> = checks
+        .iter()
+        .filter(|(passed, _, _)| !passed)
+        .map(|(_, component, description)| ValidationIssue {
+            issue_type: ValidationIssueType::PhysicalInconsistency,
+            severity: 1.0 / checks.len() as f64,
+            description: description.to_string(),
+            affected_components: vec![component.to_string()],
+        })
+        .collect();
+    (1.0 - issues.len() as f64 / checks.len() as f64, issues)
+}
+
+/// Low sun makes the plane-parallel solution unreliable
+fn sun_confidence(geometry: &SceneGeometry) -> f64 {
+    (geometry.sun_zenith_deg.to_radians().cos() / 0.2).clamp(0.0, 1.0)
+}
+
+/// Atmospheric state from forecast surface values: `[cloud_cover, precipitation, humidity,
+/// temperature, pressure, _]`, with percentages, °C and hPa recognised by magnitude;
+/// visibility is in metres, as everywhere else in the repository
+fn state_from_forecast(
+    inputs: &[Option<f64>; 6],
+    aerosol_optical_depth: Option<f64>,
+    visibility_m: Option<f64>,
+    cloud_optical_depth: Option<f64>,
+) -> AtmosphericState {
+    let defaults = AtmosphericState::default();
+    let fraction = |v: f64| if v > 1.0 { v / 100.0 } else { v };
+    let cloud_fraction = inputs[0].map(fraction).unwrap_or(defaults.cloud_parameters.cloud_fraction);
+    let humidity = inputs[2].map(fraction).unwrap_or(0.6).clamp(0.0, 1.0);
+    let temperature = inputs[3].map(|t| if t < 100.0 { t + 273.15 } else { t }).unwrap_or(288.15);
+    let pressure = inputs[4].map(|p| if p < 2_000.0 { p * 100.0 } else { p }).unwrap_or(STANDARD_PRESSURE_PA);
+    // Koschmieder extinction spread over a 1.5 km boundary layer
+    let aerosol = aerosol_optical_depth
+        .or_else(|| visibility_m.filter(|v| *v > 0.0).map(|v| 3.912 / (v / 1000.0) * 1.5))
+        .unwrap_or(defaults.scattering_parameters.aerosol_optical_depth);
+
+    let levels = 20;
+    AtmosphericState {
+        temperature_profile: (0..levels).map(|z| temperature - 6.5 * z.min(11) as f64).collect(),
+        // Moisture decays with a 2 km scale height in relative terms above the boundary layer
+        humidity_profile: (0..levels).map(|z| humidity * (-(z as f64 - 1.0).max(0.0) / 2.0).exp()).collect(),
+        pressure_profile: (0..levels).map(|z| pressure * (-(z as f64) * PROFILE_LEVEL_SPACING_M / 8_400.0).exp()).collect(),
+        wind_profile: vec![(0.0, 0.0); levels],
+        cloud_parameters: CloudParameters {
+            cloud_fraction: cloud_fraction.clamp(0.0, 1.0),
+            cloud_optical_depth: cloud_optical_depth.unwrap_or(defaults.cloud_parameters.cloud_optical_depth),
+            ..defaults.cloud_parameters
+        },
+        precipitation_parameters: PrecipitationParameters {
+            precipitation_rate: inputs[1].unwrap_or(0.0).max(0.0),
+            ..defaults.precipitation_parameters
+        },
+        scattering_parameters: ScatteringParameters { aerosol_optical_depth: aerosol.clamp(0.0, 5.0), ..defaults.scattering_parameters },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clear_state(aerosol_optical_depth: f64) -> AtmosphericState {
+        let defaults = AtmosphericState::default();
+        AtmosphericState {
+            temperature_profile: (0..20).map(|z| 293.0 - 6.5 * z.min(11) as f64).collect(),
+            humidity_profile: vec![0.5; 20],
+            pressure_profile: (0..20).map(|z| 101_325.0 * (-(z as f64) / 8.4).exp()).collect(),
+            cloud_parameters: CloudParameters { cloud_fraction: 0.0, ..defaults.cloud_parameters },
+            scattering_parameters: ScatteringParameters { aerosol_optical_depth, ..defaults.scattering_parameters },
+            ..defaults
+        }
+    }
+
+    fn geometry(sun_zenith_deg: f64) -> SceneGeometry {
+        SceneGeometry {
+            centre: Geodetic { latitude_deg: -19.3, longitude_deg: 31.4, height_km: 0.0 },
+            timestamp: 1_750_000_000.0,
+            swath_m: 100_000.0,
+            sun_zenith_deg,
+            sun_azimuth_deg: 45.0,
+            view_zenith_deg: 0.0,
+            view_azimuth_deg: 0.0,
+        }
+    }
+
+    fn band_mean(scene: &SyntheticScene, name: &str) -> f64 {
+        let b = scene.band_index(name).unwrap();
+        scene.image.data.iter().skip(b).step_by(scene.image.channels).sum::<f64>() / (scene.image.width * scene.image.height) as f64
+    }
+
+    #[test]
+    fn test_two_stream_conserves_energy_and_brightens_with_depth() {
+        for mu0 in [0.3, 0.7, 1.0] {
+            assert_eq!(two_stream_layer(0.0, 1.0, 0.85, mu0), LayerResponse::TRANSPARENT);
+            let mut previous = 0.0;
+            for tau in [0.1, 1.0, 5.0, 20.0, 100.0] {
+                let layer = two_stream_layer(tau, 1.0, 0.85, mu0);
+                assert!((layer.reflectance + layer.total_transmittance() - 1.0).abs() < 1e-3, "tau {} mu0 {}: {:?}", tau, mu0, layer);
+                assert!(layer.reflectance > previous);
+                previous = layer.reflectance;
+            }
+            assert!(previous > 0.8);
+            // Absorbing droplets reflect less and lose energy
+            let absorbing = two_stream_layer(20.0, 0.98, 0.85, mu0);
+            assert!(absorbing.reflectance < two_stream_layer(20.0, 1.0, 0.85, mu0).reflectance);
+            assert!(absorbing.reflectance + absorbing.total_transmittance() < 0.99);
+        }
+    }
+
+    #[test]
+    fn test_clear_scenes_show_rayleigh_path_radiance_and_vegetation() {
+        let synthesizer = ToaImageSynthesizer::with_config(ToaSynthesisConfig { raster_size: 8, ..ToaSynthesisConfig::default() });
+        let clean = synthesizer.render_scene(&clear_state(0.05), &geometry(30.0));
+        assert_eq!(clean.cloud_fraction, 0.0);
+        // Blue gains path radiance over the 0.05 surface, red keeps close to its 0.06
+        assert!(band_mean(&clean, "B01") > 0.1);
+        assert!((band_mean(&clean, "B04") - 0.06).abs() < 0.03);
+        assert!(band_mean(&clean, "B08") > 3.0 * band_mean(&clean, "B04"));
+        // Water vapour absorbs B09 and hides the ground entirely at 1375 nm
+        assert!(band_mean(&clean, "B09") < band_mean(&clean, "B8A"));
+        assert!(band_mean(&clean, "B10") < 0.01);
+
+        let hazy = synthesizer.render_scene(&clear_state(0.8), &geometry(30.0));
+        // Haze brightens the dark visible bands far more than the bright near infrared
+        let brightening = |name| band_mean(&hazy, name) - band_mean(&clean, name);
+        assert!(brightening("B02") > 0.03);
+        assert!(brightening("B02") > 3.0 * brightening("B08").abs());
+
+        let night = synthesizer.render_scene(&clear_state(0.05), &geometry(100.0));
+        assert!(night.image.data.iter().all(|v| *v == 0.0));
+    }
+
+    #[test]
+    fn test_clouds_follow_the_requested_fraction_and_move_with_the_wind() {
+        let synthesizer = ToaImageSynthesizer::with_config(ToaSynthesisConfig { raster_size: 32, ..ToaSynthesisConfig::default() });
+        let mut state = clear_state(0.1);
+        state.cloud_parameters.cloud_fraction = 0.4;
+        state.cloud_parameters.cloud_optical_depth = 20.0;
+        state.wind_profile = vec![(15.0, 270.0); 20];
+        let scene = synthesizer.render_scene(&state, &geometry(50.0));
+        assert!((scene.cloud_fraction - 0.4).abs() < 0.05, "{}", scene.cloud_fraction);
+
+        let pixels = scene.image.width * scene.image.height;
+        let red = scene.band_index("B04").unwrap();
+        let brightness: Vec<f64> = (0..pixels).map(|p| scene.image.data[p * scene.image.channels + red]).collect();
+        let bright = brightness.iter().filter(|r| **r > 0.3).count() as f64 / pixels as f64;
+        assert!(bright > 0.2 && bright < 0.45, "{}", bright);
+        // Some ground lies in cloud shadow, well below the same scene without clouds
+        let clear_sky = synthesizer.render_scene(&AtmosphericState { cloud_parameters: CloudParameters { cloud_fraction: 0.0, ..state.cloud_parameters.clone() }, ..state.clone() }, &geometry(50.0));
+        assert!(brightness.iter().any(|r| *r < 0.6 * band_mean(&clear_sky, "B04")));
+
+        let later = synthesizer.render_scene(&state, &SceneGeometry { timestamp: geometry(50.0).timestamp + 1_800.0, ..geometry(50.0) });
+        assert_ne!(later.image.data, scene.image.data);
+
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        scene.write_geotiff("B04", &mut buffer).unwrap();
+        buffer.set_position(0);
+        let mut decoder = tiff::decoder::Decoder::new(buffer).unwrap();
+        assert_eq!(decoder.dimensions().unwrap(), (32, 32));
+        let tiepoint = decoder.get_tag_f64_vec(Tag::ModelTiepointTag).unwrap();
+        assert_eq!((tiepoint[3], tiepoint[4]), (scene.bounds.west, scene.bounds.north));
+        match decoder.read_image().unwrap() {
+            tiff::decoder::DecodingResult::F32(samples) => assert_eq!(samples[5], brightness[5] as f32),
+            other => panic!("unexpected sample type {:?}", std::mem::discriminant(&other)),
+        }
+        let mut png = Vec::new();
+        scene.write_true_colour_png(&mut png).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+    }
+
+    #[test]
+    fn test_forecast_visibility_in_metres() {
+        // 20 km visibility: 0.196 per km of extinction through a 1.5 km boundary layer
+        let state = state_from_forecast(&[None; 6], None, Some(20_000.0), None);
+        assert!((state.scattering_parameters.aerosol_optical_depth - 0.2934).abs() < 1e-9);
+        let given = state_from_forecast(&[None; 6], Some(0.1), Some(20_000.0), None);
+        assert_eq!(given.scattering_parameters.aerosol_optical_depth, 0.1);
+    }
+}